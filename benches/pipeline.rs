@@ -0,0 +1,154 @@
+//! Throughput of the terminal pipeline, from PTY bytes to glyphs.
+//!
+//! - `parse`: bytes/sec through `feed_bytes` for a few kinds of output.
+//! - `render_cells`: rebuilding the cell snapshot for common grid sizes.
+//! - `glyph_atlas`: rasterizing and packing a fresh atlas.
+//!
+//! Run with `cargo bench --bench pipeline`; pass a filter such as
+//! `cargo bench --bench pipeline -- parse` to run one group.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use rabbitty::gui::render::populate_atlas;
+use rabbitty::terminal::{TerminalEngine, TerminalSize};
+use std::fmt::Write;
+use std::hint::black_box;
+
+/// Roughly how much output each capture holds.
+const CAPTURE_BYTES: usize = 4 * 1024 * 1024;
+/// PTY reads are at most this big, so captures are fed in chunks of it.
+const READ_CHUNK: usize = 64 * 1024;
+
+/// `ls -l`-style lines with no escapes at all.
+fn plain_capture() -> Vec<u8> {
+    let mut out = String::with_capacity(CAPTURE_BYTES);
+    let mut n = 0u32;
+    while out.len() < CAPTURE_BYTES {
+        let _ = write!(
+            out,
+            "-rw-r--r--  1 rabbit staff {:>8} Oct 16 05:12 file_{n:06}.rs\r\n",
+            n.wrapping_mul(7919) % 100_000
+        );
+        n += 1;
+    }
+    out.into_bytes()
+}
+
+/// Compiler-style output where most words change color.
+fn colored_capture() -> Vec<u8> {
+    let mut out = String::with_capacity(CAPTURE_BYTES);
+    let mut n = 0u32;
+    while out.len() < CAPTURE_BYTES {
+        let _ = write!(
+            out,
+            "\x1b[1;31merror\x1b[0m\x1b[1m[E{:04}]\x1b[0m: mismatched types \
+             \x1b[38;2;{};{};200mexpected\x1b[0m \x1b[38;5;{}m`u32`\x1b[0m\r\n \
+             \x1b[1;34m-->\x1b[0m src/lib.rs:{}:{}\r\n",
+            n % 1000,
+            n % 256,
+            (n * 3) % 256,
+            n % 256,
+            n % 4000,
+            n % 80
+        );
+        n += 1;
+    }
+    out.into_bytes()
+}
+
+/// Full-screen redraws: every row addressed and repainted, as `htop` does.
+fn fullscreen_capture() -> Vec<u8> {
+    let mut out = String::with_capacity(CAPTURE_BYTES);
+    let mut frame = 0u32;
+    while out.len() < CAPTURE_BYTES {
+        out.push_str("\x1b[?2026h\x1b[H");
+        for row in 1..=50 {
+            let _ = write!(
+                out,
+                "\x1b[{row};1H\x1b[48;5;{}m{:>5} rabbit  20   0 {:>8} {:>6} S {:>4}.{} \x1b[0m\x1b[K",
+                (row + frame) % 8 + 232,
+                row * 97 + frame,
+                frame * 13 % 999_999,
+                row * 31 % 99_999,
+                (row + frame) % 100,
+                frame % 10
+            );
+        }
+        out.push_str("\x1b[?2026l");
+        frame += 1;
+    }
+    out.into_bytes()
+}
+
+/// Wide CJK text and combining marks, which take the grapheme paths.
+fn unicode_capture() -> Vec<u8> {
+    let line =
+        "터미널 에뮬레이터 端末エミュレータ 终端模拟器 e\u{301}a\u{308}o\u{302} \u{1F407}\r\n";
+    line.repeat(CAPTURE_BYTES / line.len() + 1).into_bytes()
+}
+
+fn parse(c: &mut Criterion) {
+    let captures = [
+        ("plain", plain_capture()),
+        ("colored", colored_capture()),
+        ("fullscreen", fullscreen_capture()),
+        ("unicode", unicode_capture()),
+    ];
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    for (name, capture) in &captures {
+        group.throughput(Throughput::Bytes(capture.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), capture, |b, capture| {
+            b.iter_with_large_drop(|| {
+                let mut engine = TerminalEngine::headless(TerminalSize::new(200, 50));
+                for chunk in capture.chunks(READ_CHUNK) {
+                    engine.feed_bytes(black_box(chunk));
+                }
+                engine
+            });
+        });
+    }
+    group.finish();
+}
+
+fn render_cells(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render_cells");
+    for (columns, lines) in [(80, 24), (200, 50), (400, 120)] {
+        let mut engine = TerminalEngine::headless(TerminalSize::new(columns, lines));
+        engine.feed_bytes(&colored_capture()[..256 * 1024]);
+        group.throughput(Throughput::Elements((columns * lines) as u64));
+        group.bench_function(
+            BenchmarkId::from_parameter(format!("{columns}x{lines}")),
+            |b| {
+                b.iter(|| {
+                    // Scrolling by nothing marks the snapshot stale without
+                    // changing the grid, so every iteration rebuilds it.
+                    engine.scroll(0);
+                    black_box(engine.render_cells());
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn glyph_atlas(c: &mut Criterion) {
+    let ascii: String = (' '..='~').collect();
+    let mixed: String = ascii
+        .chars()
+        .chain('\u{2500}'..='\u{257F}')
+        .chain('\u{00C0}'..='\u{017F}')
+        .collect();
+    let mut group = c.benchmark_group("glyph_atlas");
+    for (name, text) in [("ascii", &ascii), ("mixed", &mixed)] {
+        for font_px in [14.0, 28.0] {
+            group.throughput(Throughput::Elements(text.chars().count() as u64));
+            group.bench_function(BenchmarkId::new(name, font_px), |b| {
+                b.iter(|| populate_atlas(black_box(text), font_px));
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse, render_cells, glyph_atlas);
+criterion_main!(benches);
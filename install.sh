@@ -81,7 +81,7 @@ Icon=io.github.rabbitty
 Terminal=false
 Categories=System;TerminalEmulator;
 StartupNotify=true
-StartupWMClass=rabbitty
+StartupWMClass=io.github.rabbitty
 Keywords=terminal;shell;ssh;
 EOF
     chmod 0644 "$DESKTOP_FILE"
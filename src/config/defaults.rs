@@ -18,6 +18,12 @@ pub const DEFAULT_SHORTCUT_CLOSE_TAB: &str = "Command+W";
 #[cfg(not(target_os = "macos"))]
 pub const DEFAULT_SHORTCUT_CLOSE_TAB: &str = "Ctrl+W";
 
+// Ctrl+Shift+Z already zooms the pane off macOS.
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_REOPEN_TAB: &str = "Command+Shift+Z";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_REOPEN_TAB: &str = "Ctrl+Alt+Shift+Z";
+
 #[cfg(target_os = "macos")]
 pub const DEFAULT_SHORTCUT_OPEN_SETTINGS: &str = "Command+Comma";
 #[cfg(not(target_os = "macos"))]
@@ -78,6 +84,11 @@ pub const DEFAULT_SHORTCUT_CLOSE_PANE: &str = "Command+Shift+W";
 #[cfg(not(target_os = "macos"))]
 pub const DEFAULT_SHORTCUT_CLOSE_PANE: &str = "Ctrl+Shift+W";
 
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_ZOOM_PANE: &str = "Command+Shift+Enter";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_ZOOM_PANE: &str = "Ctrl+Shift+Z";
+
 #[cfg(target_os = "macos")]
 pub const DEFAULT_SHORTCUT_FOCUS_LEFT: &str = "Command+Alt+ArrowLeft";
 #[cfg(not(target_os = "macos"))]
@@ -98,13 +109,157 @@ pub const DEFAULT_SHORTCUT_FOCUS_DOWN: &str = "Command+Alt+ArrowDown";
 #[cfg(not(target_os = "macos"))]
 pub const DEFAULT_SHORTCUT_FOCUS_DOWN: &str = "Ctrl+Alt+ArrowDown";
 
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_TOGGLE_MARK: &str = "Command+Shift+M";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_TOGGLE_MARK: &str = "Ctrl+Shift+M";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_PREV_MARK: &str = "Command+ArrowUp";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_PREV_MARK: &str = "Ctrl+Shift+ArrowUp";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_NEXT_MARK: &str = "Command+ArrowDown";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_NEXT_MARK: &str = "Ctrl+Shift+ArrowDown";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_COPY_LAST_OUTPUT: &str = "Command+Shift+O";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_COPY_LAST_OUTPUT: &str = "Ctrl+Shift+O";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_RERUN_COMMAND: &str = "Command+Shift+R";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_RERUN_COMMAND: &str = "Ctrl+Shift+R";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_SELECT_ALL: &str = "Command+A";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_SELECT_ALL: &str = "Ctrl+Shift+A";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_COPY_MODE: &str = "Command+Shift+Space";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_COPY_MODE: &str = "Ctrl+Shift+Space";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_LIST_MARKS: &str = "Command+Shift+L";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_LIST_MARKS: &str = "Ctrl+Shift+L";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_SAVE_WORKSPACE: &str = "Command+Shift+S";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_SAVE_WORKSPACE: &str = "Ctrl+Shift+S";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_OPEN_DIRECTORY: &str = "Command+Shift+G";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_OPEN_DIRECTORY: &str = "Ctrl+Shift+G";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_COPY_DIRECTORY: &str = "Command+Shift+Y";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_COPY_DIRECTORY: &str = "Ctrl+Shift+Y";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_DEBUG_OVERLAY: &str = "Command+Shift+F12";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_DEBUG_OVERLAY: &str = "Ctrl+Shift+F12";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_PRIVACY_MODE: &str = "Command+Shift+H";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_PRIVACY_MODE: &str = "Ctrl+Shift+H";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_SCREENSHOT: &str = "Command+Alt+S";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_SCREENSHOT: &str = "Ctrl+Alt+S";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_RECORD: &str = "Command+Alt+R";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_RECORD: &str = "Ctrl+Alt+R";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_CHEAT_SHEET: &str = "Command+Shift+/";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_CHEAT_SHEET: &str = "Ctrl+Shift+/";
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHORTCUT_READ_SCREEN: &str = "Command+Alt+Shift+S";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHORTCUT_READ_SCREEN: &str = "Ctrl+Alt+Shift+S";
+
+/// Animation frame rate while the window is in the background.
+pub const DEFAULT_UNFOCUSED_FPS: u32 = 30;
+pub const DEFAULT_PAUSE_WHEN_MINIMIZED: bool = true;
+
+/// Besides whitespace, these end a word for double-click selection.
+pub const DEFAULT_WORD_SEPARATORS: &str = ",│`\"'()[]{}<>";
+
+/// Regexes shift-double-click tries in order: URLs, paths, UUIDs, IPv4
+/// addresses, git hashes and quoted strings.
+pub const DEFAULT_SEMANTIC_RULES: [&str; 8] = [
+    r#"[a-z][a-z0-9+.-]*://[^ \t<>"'`]+"#,
+    r"~?[-A-Za-z0-9._+@%]*(/[-A-Za-z0-9._+@%~]+)+/?",
+    r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}",
+    r"[0-9]{1,3}(\.[0-9]{1,3}){3}(:[0-9]{1,5})?",
+    r"[0-9a-f]{7,40}",
+    r#""[^"]*""#,
+    r"'[^']*'",
+    r"`[^`]*`",
+];
+
+/// Masked in privacy mode: AWS access key IDs, bearer tokens, GitHub tokens
+/// and email addresses.
+pub const DEFAULT_REDACT_PATTERNS: [&str; 4] = [
+    r"(AKIA|ASIA)[0-9A-Z]{16}",
+    r"[Bb]earer [A-Za-z0-9._~+/=-]+",
+    r"gh[pousr]_[A-Za-z0-9]{36,}",
+    r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+];
+
 pub const DEFAULT_TERMINAL_FONT_SIZE: f32 = 14.0;
+pub const DEFAULT_TERMINAL_LINE_HEIGHT: f32 = 1.0;
+pub const DEFAULT_TERMINAL_LETTER_SPACING: f32 = 1.0;
 pub const DEFAULT_TERMINAL_PADDING_X: f32 = 4.0;
 pub const DEFAULT_TERMINAL_PADDING_Y: f32 = 4.0;
 pub const DEFAULT_TERMINAL_SCROLLBACK: usize = 10_000;
 pub const DEFAULT_BRACKETED_PASTE: bool = true;
 pub const DEFAULT_MULTILINE_PASTE_CONFIRM: bool = false;
+pub const DEFAULT_TERMINAL_AUTOCOMPLETE: bool = false;
+pub const DEFAULT_LARGE_PASTE_LINES: usize = 1_000;
+pub const DEFAULT_LARGE_PASTE_KIB: usize = 512;
 pub const DEFAULT_TERMINAL_SCROLL_MULTIPLIER: f32 = 1.0;
+pub const DEFAULT_SMOOTH_SCROLL: bool = false;
 pub const DEFAULT_CURSOR_BLINK: bool = true;
+pub const DEFAULT_CURSOR_TRAIL: bool = false;
+pub const DEFAULT_CURSOR_TRAIL_MS: u32 = 120;
 pub const DEFAULT_BOLD_IS_BRIGHT: bool = false;
+pub const DEFAULT_FONT_SYNTHESIZE: bool = true;
+pub const DEFAULT_HIGHLIGHT_CURSOR_ROW: bool = false;
+pub const DEFAULT_WRAP_INDICATOR: bool = false;
+pub const DEFAULT_INACTIVE_PANE_DIM: f32 = 0.0;
+pub const DEFAULT_DIM_UNFOCUSED_WINDOW: bool = false;
+pub const DEFAULT_LOCAL_ECHO: bool = false;
+pub const DEFAULT_TMUX_PASSTHROUGH: bool = true;
+pub const DEFAULT_PERSISTENT_SESSIONS: bool = false;
+pub const DEFAULT_NOTIFY_COMMAND_SECS: u64 = 10;
+pub const DEFAULT_BELL_ATTENTION: bool = true;
+pub const DEFAULT_IDLE_LOCK_MINUTES: u64 = 0;
+pub const DEFAULT_SECURE_KEYBOARD_ENTRY: bool = false;
+pub const DEFAULT_CONFIRM_LINKS: bool = true;
+pub const DEFAULT_LINK_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+pub const DEFAULT_SSH_KEEPALIVE_INTERVAL: u64 = 15;
 pub const DEFAULT_ANIMATIONS_ENABLED: bool = true;
+pub const DEFAULT_STATUS_BAR: bool = false;
+pub const DEFAULT_HIGH_CONTRAST: bool = false;
+pub const DEFAULT_LARGE_TEXT: bool = false;
+/// Wayland app id and X11 `WM_CLASS`; matches the installed desktop entry.
+pub const DEFAULT_APP_ID: &str = "io.github.rabbitty";
+/// How much larger the whole interface is drawn in large-text mode.
+pub const LARGE_TEXT_SCALE: f32 = 1.25;
@@ -4,7 +4,13 @@ use std::path::{Path, PathBuf};
 
 use super::AppConfig;
 use super::defaults::*;
-use super::types::{BellMode, CursorShape, RightClickAction, TabBarPosition};
+use super::sanitize::parse_hex_color;
+use super::snippet::Snippet;
+use super::types::{
+    AdapterPreference, BellMode, CursorShape, ExitBehavior, GpuBackend, PresentMode,
+    RightClickAction, StatusSegment, TabBarPosition, TextRendering, TimestampMode, Trigger,
+    TriggerAction,
+};
 use crate::gui::tab::Profile;
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -12,9 +18,13 @@ pub(super) struct FileConfig {
     pub(super) ui: Option<UiFileConfig>,
     pub(super) terminal: Option<TerminalFileConfig>,
     pub(super) theme: Option<ThemeFileConfig>,
+    pub(super) selection: Option<SelectionFileConfig>,
+    pub(super) renderer: Option<RendererFileConfig>,
     pub(super) shortcuts: Option<ShortcutsFileConfig>,
     #[serde(default)]
     pub(super) profiles: Option<Vec<Profile>>,
+    #[serde(default)]
+    pub(super) snippets: Option<Vec<Snippet>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -24,6 +34,11 @@ pub(super) struct UiFileConfig {
     pub(super) language: Option<String>,
     pub(super) animations_enabled: Option<bool>,
     pub(super) tab_bar_position: Option<TabBarPosition>,
+    pub(super) status_bar: Option<bool>,
+    pub(super) status_segments: Option<Vec<StatusSegment>>,
+    pub(super) high_contrast: Option<bool>,
+    pub(super) large_text: Option<bool>,
+    pub(super) app_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -34,17 +49,92 @@ pub(super) struct TerminalFileConfig {
     #[serde(alias = "font_path")]
     pub(super) legacy_font_path: Option<String>,
     pub(super) font_size: Option<f32>,
+    pub(super) text_rendering: Option<TextRendering>,
+    pub(super) font_synthesize: Option<bool>,
+    pub(super) line_height: Option<f32>,
+    pub(super) letter_spacing: Option<f32>,
     pub(super) padding_x: Option<f32>,
     pub(super) padding_y: Option<f32>,
     pub(super) scrollback_lines: Option<usize>,
     pub(super) bracketed_paste: Option<bool>,
     pub(super) multiline_paste_confirm: Option<bool>,
+    pub(super) autocomplete: Option<bool>,
+    pub(super) large_paste_lines: Option<usize>,
+    pub(super) large_paste_kib: Option<usize>,
     pub(super) scroll_multiplier: Option<f32>,
+    pub(super) smooth_scroll: Option<bool>,
     pub(super) cursor_shape: Option<CursorShape>,
     pub(super) cursor_blink: Option<bool>,
+    pub(super) cursor_trail: Option<bool>,
+    pub(super) cursor_trail_ms: Option<u32>,
+    pub(super) highlight_cursor_row: Option<bool>,
+    pub(super) wrap_indicator: Option<bool>,
+    pub(super) inactive_pane_dim: Option<f32>,
+    pub(super) dim_unfocused_window: Option<bool>,
+    pub(super) local_echo: Option<bool>,
+    pub(super) persistent_sessions: Option<bool>,
+    pub(super) notify_command_secs: Option<u64>,
+    pub(super) idle_lock_minutes: Option<u64>,
+    pub(super) secure_keyboard_entry: Option<bool>,
+    pub(super) column_rulers: Option<Vec<u16>>,
     pub(super) bold_is_bright: Option<bool>,
     pub(super) bell_mode: Option<BellMode>,
+    pub(super) bell_attention: Option<bool>,
+    pub(super) timestamps: Option<TimestampMode>,
     pub(super) right_click_action: Option<RightClickAction>,
+    pub(super) exit_behavior: Option<ExitBehavior>,
+    pub(super) default_profile: Option<String>,
+    pub(super) editor_command: Option<String>,
+    pub(super) assist_command: Option<String>,
+    pub(super) confirm_links: Option<bool>,
+    pub(super) link_schemes: Option<Vec<String>>,
+    pub(super) answerback: Option<String>,
+    pub(super) tmux_passthrough: Option<bool>,
+    pub(super) triggers: Option<Vec<TriggerFileConfig>>,
+    pub(super) redact_patterns: Option<Vec<String>>,
+    pub(super) container_shells: Option<std::collections::BTreeMap<String, String>>,
+    pub(super) env: Option<std::collections::BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct TriggerFileConfig {
+    pub(super) pattern: String,
+    pub(super) foreground: Option<String>,
+    pub(super) background: Option<String>,
+    #[serde(default)]
+    pub(super) underline: bool,
+    #[serde(default)]
+    pub(super) action: TriggerAction,
+}
+
+impl TriggerFileConfig {
+    /// Drops triggers without a pattern; unparseable colors leave that part of
+    /// the cell's style alone.
+    pub(super) fn to_trigger(&self) -> Option<Trigger> {
+        if self.pattern.trim().is_empty() {
+            return None;
+        }
+        Some(Trigger {
+            pattern: self.pattern.clone(),
+            foreground: self.foreground.as_deref().and_then(parse_hex_color),
+            background: self.background.as_deref().and_then(parse_hex_color),
+            underline: self.underline,
+            action: self.action,
+        })
+    }
+}
+
+impl From<&Trigger> for TriggerFileConfig {
+    fn from(trigger: &Trigger) -> Self {
+        let hex = |c: [u8; 3]| format!("#{:02x}{:02x}{:02x}", c[0], c[1], c[2]);
+        Self {
+            pattern: trigger.pattern.clone(),
+            foreground: trigger.foreground.map(hex),
+            background: trigger.background.map(hex),
+            underline: trigger.underline,
+            action: trigger.action,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -59,6 +149,21 @@ pub(super) struct ThemeFileConfig {
     pub(super) macos_blur_radius: Option<i32>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct SelectionFileConfig {
+    pub(super) word_separators: Option<String>,
+    pub(super) semantic_rules: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct RendererFileConfig {
+    pub(super) present_mode: Option<PresentMode>,
+    pub(super) unfocused_fps: Option<u32>,
+    pub(super) pause_when_minimized: Option<bool>,
+    pub(super) backend: Option<GpuBackend>,
+    pub(super) adapter: Option<AdapterPreference>,
+}
+
 pub(super) type ShortcutsFileConfig = std::collections::BTreeMap<String, String>;
 
 impl From<&AppConfig> for FileConfig {
@@ -70,6 +175,11 @@ impl From<&AppConfig> for FileConfig {
                 language: config.ui.language.clone(),
                 animations_enabled: Some(config.ui.animations_enabled),
                 tab_bar_position: Some(config.ui.tab_bar_position),
+                status_bar: Some(config.ui.status_bar),
+                status_segments: Some(config.ui.status_segments.clone()),
+                high_contrast: Some(config.ui.high_contrast),
+                large_text: Some(config.ui.large_text),
+                app_id: config.ui.app_id.clone(),
             }),
             terminal: Some(TerminalFileConfig {
                 cell_width: None,
@@ -77,17 +187,63 @@ impl From<&AppConfig> for FileConfig {
                 font_selection: config.terminal.font_selection.clone(),
                 legacy_font_path: None,
                 font_size: Some(config.terminal.font_size),
+                text_rendering: Some(config.terminal.text_rendering),
+                font_synthesize: Some(config.terminal.font_synthesize),
+                line_height: Some(config.terminal.line_height),
+                letter_spacing: Some(config.terminal.letter_spacing),
                 padding_x: Some(config.terminal.padding_x),
                 padding_y: Some(config.terminal.padding_y),
                 scrollback_lines: Some(config.terminal.scrollback_lines),
                 bracketed_paste: Some(config.terminal.bracketed_paste),
                 multiline_paste_confirm: Some(config.terminal.multiline_paste_confirm),
+                autocomplete: Some(config.terminal.autocomplete),
+                large_paste_lines: Some(config.terminal.large_paste_lines),
+                large_paste_kib: Some(config.terminal.large_paste_kib),
                 scroll_multiplier: Some(config.terminal.scroll_multiplier),
+                smooth_scroll: Some(config.terminal.smooth_scroll),
                 cursor_shape: Some(config.terminal.cursor_shape),
                 cursor_blink: Some(config.terminal.cursor_blink),
+                cursor_trail: Some(config.terminal.cursor_trail),
+                cursor_trail_ms: Some(config.terminal.cursor_trail_ms),
+                highlight_cursor_row: Some(config.terminal.highlight_cursor_row),
+                wrap_indicator: Some(config.terminal.wrap_indicator),
+                inactive_pane_dim: Some(config.terminal.inactive_pane_dim),
+                dim_unfocused_window: Some(config.terminal.dim_unfocused_window),
+                local_echo: Some(config.terminal.local_echo),
+                persistent_sessions: Some(config.terminal.persistent_sessions),
+                notify_command_secs: Some(config.terminal.notify_command_secs),
+                idle_lock_minutes: Some(config.terminal.idle_lock_minutes),
+                secure_keyboard_entry: Some(config.terminal.secure_keyboard_entry),
+                column_rulers: Some(config.terminal.column_rulers.clone()),
                 bold_is_bright: Some(config.terminal.bold_is_bright),
                 bell_mode: Some(config.terminal.bell_mode),
+                bell_attention: Some(config.terminal.bell_attention),
+                timestamps: Some(config.terminal.timestamps),
                 right_click_action: Some(config.terminal.right_click_action),
+                exit_behavior: Some(config.terminal.exit_behavior),
+                default_profile: config.terminal.default_profile.clone(),
+                editor_command: config.terminal.editor_command.clone(),
+                assist_command: config.terminal.assist_command.clone(),
+                confirm_links: Some(config.terminal.confirm_links),
+                link_schemes: Some(config.terminal.link_schemes.clone()),
+                answerback: Some(config.terminal.answerback.clone()),
+                tmux_passthrough: Some(config.terminal.tmux_passthrough),
+                triggers: if config.terminal.triggers.is_empty() {
+                    None
+                } else {
+                    Some(config.terminal.triggers.iter().map(Into::into).collect())
+                },
+                redact_patterns: Some(config.terminal.redact_patterns.clone()),
+                container_shells: if config.terminal.container_shells.is_empty() {
+                    None
+                } else {
+                    Some(config.terminal.container_shells.clone())
+                },
+                env: if config.terminal.env.is_empty() {
+                    None
+                } else {
+                    Some(config.terminal.env.clone())
+                },
             }),
             theme: Some(ThemeFileConfig {
                 color_scheme: if config.theme.color_scheme.is_empty() {
@@ -120,6 +276,17 @@ impl From<&AppConfig> for FileConfig {
                 blur_enabled: Some(config.theme.blur_enabled),
                 macos_blur_radius: Some(config.theme.macos_blur_radius),
             }),
+            selection: Some(SelectionFileConfig {
+                word_separators: Some(config.selection.word_separators.clone()),
+                semantic_rules: Some(config.selection.semantic_rules.clone()),
+            }),
+            renderer: Some(RendererFileConfig {
+                present_mode: Some(config.renderer.present_mode),
+                unfocused_fps: Some(config.renderer.unfocused_fps),
+                pause_when_minimized: Some(config.renderer.pause_when_minimized),
+                backend: Some(config.renderer.backend),
+                adapter: Some(config.renderer.adapter),
+            }),
             shortcuts: Some(
                 config
                     .shortcuts
@@ -139,6 +306,7 @@ impl From<&AppConfig> for FileConfig {
                         .collect(),
                 )
             },
+            snippets: (!config.snippets.is_empty()).then(|| config.snippets.clone()),
         }
     }
 }
@@ -208,6 +376,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn app_id_keeps_to_desktop_id_characters() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.app_id(), DEFAULT_APP_ID);
+
+        let file = toml::from_str::<FileConfig>("[ui]\napp_id = \" rabbitty-work \"")
+            .expect("file config should parse");
+        config.apply_file(file);
+        assert_eq!(config.app_id(), "rabbitty-work");
+
+        let file = toml::from_str::<FileConfig>("[ui]\napp_id = \"my terminal\"")
+            .expect("file config should parse");
+        config.apply_file(file);
+        assert_eq!(config.app_id(), DEFAULT_APP_ID);
+    }
+
     #[test]
     fn profiles_parse_ssh_and_skip_empty_host() {
         let mut config = AppConfig::default();
@@ -241,6 +425,28 @@ mod tests {
         assert!(sshs[0].password.is_none());
     }
 
+    #[test]
+    fn agent_profiles_parse_with_forwarding() {
+        let mut config = AppConfig::default();
+        let file = toml::from_str::<FileConfig>(
+            r#"
+            [[profiles]]
+            name = "Jump"
+            [profiles.kind]
+            type = "ssh"
+            host = "jump.example.com"
+            auth_method = "agent"
+            forward_agent = true
+            "#,
+        )
+        .expect("file config should parse");
+
+        config.apply_file(file);
+        let sshs = config.ssh_profiles();
+        assert_eq!(sshs[0].auth_method, SshAuthMethod::Agent);
+        assert!(sshs[0].forward_agent);
+    }
+
     #[test]
     fn ssh_profile_serialization_excludes_password() {
         let config = AppConfig {
@@ -253,6 +459,11 @@ mod tests {
                 identity_file: None,
                 password: Some("secret123".into()),
                 proxy_command: None,
+                keepalive_interval: 15,
+                auto_reconnect: false,
+                remote_attach: false,
+                forward_agent: false,
+                forwards: Vec::new(),
             })],
             ..Default::default()
         };
@@ -275,6 +486,11 @@ mod tests {
                 identity_file: None,
                 password: None,
                 proxy_command: None,
+                keepalive_interval: 15,
+                auto_reconnect: false,
+                remote_attach: false,
+                forward_agent: false,
+                forwards: Vec::new(),
             })],
             ..Default::default()
         };
@@ -323,4 +539,110 @@ mod tests {
             Some("JetBrains Mono".to_string())
         );
     }
+
+    #[test]
+    fn renderer_section_parses_and_rejects_bad_frame_rates() {
+        let mut config = AppConfig::default();
+        let file = toml::from_str::<FileConfig>(
+            r#"
+            [renderer]
+            present_mode = "mailbox"
+            unfocused_fps = 0
+            pause_when_minimized = false
+            backend = "gl"
+            adapter = "high_performance"
+            "#,
+        )
+        .expect("file config should parse");
+
+        config.apply_file(file);
+        assert_eq!(config.renderer.present_mode, PresentMode::Mailbox);
+        assert_eq!(config.renderer.unfocused_fps, DEFAULT_UNFOCUSED_FPS);
+        assert!(!config.renderer.pause_when_minimized);
+        assert_eq!(config.renderer.backend, GpuBackend::Gl);
+        assert_eq!(config.renderer.adapter, AdapterPreference::HighPerformance);
+    }
+
+    #[test]
+    fn word_separators_are_read_from_the_selection_section() {
+        let mut config = AppConfig::default();
+        let file = toml::from_str::<FileConfig>(
+            r#"
+            [selection]
+            word_separators = ",/:"
+            "#,
+        )
+        .expect("file config should parse");
+
+        config.apply_file(file);
+        assert_eq!(config.selection.word_separators, ",/:");
+    }
+
+    #[test]
+    fn triggers_parse_colors_and_skip_empty_patterns() {
+        let mut config = AppConfig::default();
+        let file = toml::from_str::<FileConfig>(
+            r##"
+            [[terminal.triggers]]
+            pattern = "ERROR"
+            foreground = "#ff5555"
+            underline = true
+            action = "notify"
+
+            [[terminal.triggers]]
+            pattern = "  "
+            "##,
+        )
+        .expect("file config should parse");
+
+        config.apply_file(file);
+        assert_eq!(
+            config.terminal.triggers,
+            vec![Trigger {
+                pattern: "ERROR".into(),
+                foreground: Some([0xff, 0x55, 0x55]),
+                background: None,
+                underline: true,
+                action: TriggerAction::Notify,
+            }]
+        );
+    }
+
+    #[test]
+    fn container_shells_drop_blank_entries() {
+        let mut config = AppConfig::default();
+        let file = toml::from_str::<FileConfig>(
+            r#"
+            [terminal.container_shells]
+            web = " /bin/bash "
+            "postgres:16" = "/bin/zsh"
+            empty = "  "
+            "#,
+        )
+        .expect("file config should parse");
+
+        config.apply_file(file);
+        let shells: Vec<_> = config
+            .terminal
+            .container_shells
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(shells, [("postgres:16", "/bin/zsh"), ("web", "/bin/bash")]);
+    }
+
+    #[test]
+    fn text_rendering_parses_and_applies() {
+        let mut config = AppConfig::default();
+        let file = toml::from_str::<FileConfig>(
+            r#"
+            [terminal]
+            text_rendering = "subpixel"
+            "#,
+        )
+        .expect("file config should parse");
+
+        config.apply_file(file);
+        assert_eq!(config.terminal.text_rendering, TextRendering::Subpixel);
+    }
 }
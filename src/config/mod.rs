@@ -3,23 +3,28 @@ mod file;
 mod metrics;
 mod sanitize;
 mod shortcuts;
+mod snippet;
 mod types;
 mod updates;
 
 pub use defaults::*;
 pub use metrics::cell_metrics_for_selection;
-pub use shortcuts::{ShortcutId, ShortcutsConfig};
+pub use shortcuts::{ShortcutCategory, ShortcutId, ShortcutPreset, ShortcutsConfig};
+pub use snippet::{Placeholder, Snippet};
 pub use types::{
-    BellMode, CursorShape, RightClickAction, SshAuthMethod, SshProfile, TabBarPosition,
+    AdapterPreference, BellMode, CursorShape, ExitBehavior, ForwardKind, GpuBackend,
+    NetworkProtocol, PortForward, PresentMode, RightClickAction, SshAuthMethod, SshProfile,
+    StatusSegment, TabBarPosition, TextRendering, TimestampMode, Trigger, TriggerAction,
 };
 pub use updates::AppConfigUpdates;
 
-pub(crate) use sanitize::parse_hex_color;
+pub(crate) use sanitize::{is_valid_env_name, parse_hex_color, sanitize_app_id};
 
 use crate::gui::tab::Profile;
-use file::{FileConfig, config_path, ensure_config_file};
+use file::{FileConfig, TriggerFileConfig, config_path, ensure_config_file};
 use metrics::default_cell_metrics;
 use sanitize::*;
+use std::collections::BTreeMap;
 use std::fs;
 
 #[derive(Debug, Clone)]
@@ -27,11 +32,19 @@ pub struct AppConfig {
     pub ui: UiConfig,
     pub terminal: TerminalConfig,
     pub theme: ThemeConfig,
+    pub selection: SelectionConfig,
+    pub renderer: RendererConfig,
     pub shortcuts: ShortcutsConfig,
     pub profiles: Vec<Profile>,
+    pub snippets: Vec<Snippet>,
 }
 
 impl AppConfig {
+    /// The window class this instance runs under.
+    pub fn app_id(&self) -> &str {
+        self.ui.app_id.as_deref().unwrap_or(DEFAULT_APP_ID)
+    }
+
     /// The SSH connections among the configured profiles.
     pub fn ssh_profiles(&self) -> Vec<SshProfile> {
         self.profiles
@@ -51,6 +64,19 @@ pub struct UiConfig {
     pub animations_enabled: bool,
     /// Where the tab bar / title bar is anchored.
     pub tab_bar_position: TabBarPosition,
+    /// Shows a bar with session info under the terminal.
+    pub status_bar: bool,
+    /// What the status bar shows, in order.
+    pub status_segments: Vec<StatusSegment>,
+    /// Draws interface text and borders at full strength against the
+    /// background.
+    pub high_contrast: bool,
+    /// Draws the whole window, terminal included, `LARGE_TEXT_SCALE` times
+    /// larger.
+    pub large_text: bool,
+    /// Window class the desktop groups windows by. `None` uses
+    /// `DEFAULT_APP_ID`; a separate id keeps another instance apart.
+    pub app_id: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -59,17 +85,144 @@ pub struct TerminalConfig {
     pub cell_height: f32,
     pub font_selection: Option<String>,
     pub font_size: f32,
+    pub text_rendering: TextRendering,
+    /// Draws bold and italic text by thickening and slanting the regular
+    /// face, which is the only one loaded.
+    pub font_synthesize: bool,
+    /// Multiplies the font's line height to get the cell height.
+    pub line_height: f32,
+    /// Multiplies the font's advance width to get the cell width.
+    pub letter_spacing: f32,
     pub padding_x: f32,
     pub padding_y: f32,
     pub scrollback_lines: usize,
     pub bracketed_paste: bool,
     pub multiline_paste_confirm: bool,
+    /// Suggest commands from the shell's history while typing at a prompt.
+    pub autocomplete: bool,
+    /// Pastes with more lines than this ask for confirmation. 0 disables.
+    pub large_paste_lines: usize,
+    /// Pastes larger than this many KiB ask for confirmation. 0 disables.
+    pub large_paste_kib: usize,
     pub scroll_multiplier: f32,
+    /// Eases the view between scrollback positions instead of jumping.
+    pub smooth_scroll: bool,
     pub cursor_shape: CursorShape,
     pub cursor_blink: bool,
+    /// Glides the cursor between cells with a fading trail instead of
+    /// jumping.
+    pub cursor_trail: bool,
+    /// How long the glide takes.
+    pub cursor_trail_ms: u32,
+    /// Tints the background of the row the cursor is on.
+    pub highlight_cursor_row: bool,
+    /// Marks rows the terminal wrapped onto the next one with an arrow in a
+    /// column kept free right of the grid.
+    pub wrap_indicator: bool,
+    /// How much darker unfocused panes of a split are drawn, 0 to 0.8.
+    pub inactive_pane_dim: f32,
+    /// Dims the whole terminal by `inactive_pane_dim` while the window is
+    /// in the background.
+    pub dim_unfocused_window: bool,
+    /// Shows typed characters in SSH sessions before the server echoes them.
+    pub local_echo: bool,
+    /// Runs local shells in the session daemon, so they survive the app
+    /// closing or crashing and are re-attached on the next launch.
+    pub persistent_sessions: bool,
+    /// A command that ran at least this many seconds notifies when it
+    /// finishes in a tab that is not in view; 0 never notifies.
+    pub notify_command_secs: u64,
+    /// Hides the window's contents after this many minutes without input,
+    /// until a key press or click; 0 never locks.
+    pub idle_lock_minutes: u64,
+    /// Asks macOS to keep other apps from reading keystrokes while the
+    /// window has focus.
+    pub secure_keyboard_entry: bool,
+    /// Columns after which a vertical guide line is drawn, ascending.
+    pub column_rulers: Vec<u16>,
     pub bold_is_bright: bool,
     pub bell_mode: BellMode,
+    /// Asks the window manager to flag the window when a bell or a
+    /// notification arrives while it is in the background.
+    pub bell_attention: bool,
+    /// Gutter with the time each line of output arrived.
+    pub timestamps: TimestampMode,
     pub right_click_action: RightClickAction,
+    pub exit_behavior: ExitBehavior,
+    /// Display name of the profile new windows open with, starred in the
+    /// shell picker. `None` uses the user's default shell.
+    pub default_profile: Option<String>,
+    /// Command used to open file paths clicked in terminal output, with
+    /// `{path}`, `{line}` and `{column}` placeholders. `None` uses the
+    /// system's default application.
+    pub editor_command: Option<String>,
+    /// Command or `http(s)://` endpoint that explains or fixes selected
+    /// text. `None` turns Explain and Fix off; nothing is sent by default.
+    pub assist_command: Option<String>,
+    /// Asks before opening a clicked link, showing its full target.
+    pub confirm_links: bool,
+    /// Lowercase URI schemes links may be opened with. Others are blocked.
+    pub link_schemes: Vec<String>,
+    /// Sent to programs that print ENQ. Empty sends nothing.
+    pub answerback: String,
+    /// Unwraps sequences programs inside tmux send through its passthrough
+    /// (`DCS tmux ; … ST`), so they are handled as if printed directly.
+    pub tmux_passthrough: bool,
+    /// Patterns highlighted in output as it arrives, in the order they are
+    /// applied.
+    pub triggers: Vec<Trigger>,
+    /// Regexes for secrets masked on screen while privacy mode is on.
+    pub redact_patterns: Vec<String>,
+    /// Shell to exec in a container, keyed by container name or image.
+    /// Others get `/bin/sh`.
+    pub container_shells: BTreeMap<String, String>,
+    /// Variables set for every local shell; a profile's own take precedence.
+    pub env: BTreeMap<String, String>,
+}
+
+impl TerminalConfig {
+    /// Recomputes the cell size from the font, its size and the spacing
+    /// multipliers.
+    pub(super) fn refresh_cell_metrics(&mut self) {
+        let (cw, ch) = cell_metrics_for_selection(self.font_selection.as_deref(), self.font_size);
+        self.cell_width = (cw * self.letter_spacing).max(1.0);
+        self.cell_height = (ch * self.line_height).max(1.0);
+    }
+
+    /// The cell size in logical pixels, rounded so a cell spans a whole
+    /// number of physical pixels at `scale`. Fractional cells put glyphs
+    /// between pixels and blur them.
+    pub fn cell_size_at(&self, scale: f32) -> [f32; 2] {
+        let scale = if scale.is_finite() && scale > 0.0 {
+            scale
+        } else {
+            1.0
+        };
+        [self.cell_width, self.cell_height]
+            .map(|logical| (logical * scale).round().max(1.0) / scale)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SelectionConfig {
+    /// Characters besides whitespace that end a double-clicked word.
+    pub word_separators: String,
+    /// Regexes for the units shift-double-click selects, tried in order.
+    pub semantic_rules: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RendererConfig {
+    /// Read when the window is created, so changes apply after a restart.
+    pub present_mode: PresentMode,
+    /// Frame rate animations are held to while the window is in the
+    /// background.
+    pub unfocused_fps: u32,
+    /// Stops animations and cursor blinking while the window is minimized.
+    pub pause_when_minimized: bool,
+    /// Read at startup, like `present_mode`.
+    pub backend: GpuBackend,
+    pub adapter: AdapterPreference,
 }
 
 #[derive(Debug, Clone)]
@@ -94,23 +247,62 @@ impl Default for AppConfig {
                 language: None,
                 animations_enabled: DEFAULT_ANIMATIONS_ENABLED,
                 tab_bar_position: TabBarPosition::default(),
+                status_bar: DEFAULT_STATUS_BAR,
+                status_segments: StatusSegment::ALL.to_vec(),
+                high_contrast: DEFAULT_HIGH_CONTRAST,
+                large_text: DEFAULT_LARGE_TEXT,
+                app_id: None,
             },
             terminal: TerminalConfig {
                 cell_width,
                 cell_height,
                 font_selection: None,
                 font_size: DEFAULT_TERMINAL_FONT_SIZE,
+                text_rendering: TextRendering::default(),
+                font_synthesize: DEFAULT_FONT_SYNTHESIZE,
+                line_height: DEFAULT_TERMINAL_LINE_HEIGHT,
+                letter_spacing: DEFAULT_TERMINAL_LETTER_SPACING,
                 padding_x: DEFAULT_TERMINAL_PADDING_X,
                 padding_y: DEFAULT_TERMINAL_PADDING_Y,
                 scrollback_lines: DEFAULT_TERMINAL_SCROLLBACK,
                 bracketed_paste: DEFAULT_BRACKETED_PASTE,
                 multiline_paste_confirm: DEFAULT_MULTILINE_PASTE_CONFIRM,
+                autocomplete: DEFAULT_TERMINAL_AUTOCOMPLETE,
+                large_paste_lines: DEFAULT_LARGE_PASTE_LINES,
+                large_paste_kib: DEFAULT_LARGE_PASTE_KIB,
                 scroll_multiplier: DEFAULT_TERMINAL_SCROLL_MULTIPLIER,
+                smooth_scroll: DEFAULT_SMOOTH_SCROLL,
                 cursor_shape: CursorShape::default(),
                 cursor_blink: DEFAULT_CURSOR_BLINK,
+                cursor_trail: DEFAULT_CURSOR_TRAIL,
+                cursor_trail_ms: DEFAULT_CURSOR_TRAIL_MS,
+                highlight_cursor_row: DEFAULT_HIGHLIGHT_CURSOR_ROW,
+                wrap_indicator: DEFAULT_WRAP_INDICATOR,
+                inactive_pane_dim: DEFAULT_INACTIVE_PANE_DIM,
+                dim_unfocused_window: DEFAULT_DIM_UNFOCUSED_WINDOW,
+                local_echo: DEFAULT_LOCAL_ECHO,
+                persistent_sessions: DEFAULT_PERSISTENT_SESSIONS,
+                notify_command_secs: DEFAULT_NOTIFY_COMMAND_SECS,
+                idle_lock_minutes: DEFAULT_IDLE_LOCK_MINUTES,
+                secure_keyboard_entry: DEFAULT_SECURE_KEYBOARD_ENTRY,
+                column_rulers: Vec::new(),
                 bold_is_bright: DEFAULT_BOLD_IS_BRIGHT,
                 bell_mode: BellMode::default(),
+                bell_attention: DEFAULT_BELL_ATTENTION,
+                timestamps: TimestampMode::default(),
                 right_click_action: RightClickAction::default(),
+                exit_behavior: ExitBehavior::default(),
+                default_profile: None,
+                editor_command: None,
+                assist_command: None,
+                confirm_links: DEFAULT_CONFIRM_LINKS,
+                link_schemes: DEFAULT_LINK_SCHEMES.map(String::from).to_vec(),
+                answerback: String::new(),
+                tmux_passthrough: DEFAULT_TMUX_PASSTHROUGH,
+                triggers: Vec::new(),
+                redact_patterns: DEFAULT_REDACT_PATTERNS.map(String::from).to_vec(),
+                container_shells: BTreeMap::new(),
+                env: BTreeMap::new(),
             },
             theme: ThemeConfig {
                 color_scheme: "Catppuccin Mocha".to_string(),
@@ -122,26 +314,50 @@ impl Default for AppConfig {
                 blur_enabled: DEFAULT_BLUR_ENABLED,
                 macos_blur_radius: DEFAULT_MACOS_BLUR_RADIUS,
             },
+            selection: SelectionConfig {
+                word_separators: DEFAULT_WORD_SEPARATORS.to_string(),
+                semantic_rules: DEFAULT_SEMANTIC_RULES.map(String::from).to_vec(),
+            },
+            renderer: RendererConfig {
+                present_mode: PresentMode::default(),
+                unfocused_fps: DEFAULT_UNFOCUSED_FPS,
+                pause_when_minimized: DEFAULT_PAUSE_WHEN_MINIMIZED,
+                backend: GpuBackend::default(),
+                adapter: AdapterPreference::default(),
+            },
             shortcuts: ShortcutsConfig::default(),
             profiles: vec![],
+            snippets: vec![],
         }
     }
 }
 
 impl AppConfig {
+    /// Whether no config file has been written yet, as on the first
+    /// launch. The onboarding wizard writes it once it is done.
+    pub fn is_first_run() -> bool {
+        config_path().is_some_and(|path| !path.exists())
+    }
+
     pub fn load() -> Self {
         let mut config = Self::default();
-        if let Some(path) = config_path() {
-            let _ = ensure_config_file(&path);
-            if let Ok(contents) = fs::read_to_string(&path)
-                && let Ok(file) = toml::from_str::<FileConfig>(&contents)
-            {
-                config.apply_file(file);
-            }
+        if let Some(path) = config_path()
+            && let Ok(contents) = fs::read_to_string(&path)
+            && let Ok(file) = toml::from_str::<FileConfig>(&contents)
+        {
+            config.apply_file(file);
         }
         config
     }
 
+    /// Writes the commented default config file, unless there is one.
+    pub fn write_default_file() -> std::io::Result<()> {
+        match config_path() {
+            Some(path) => ensure_config_file(&path),
+            None => Ok(()),
+        }
+    }
+
     pub fn save(&self) -> std::io::Result<()> {
         let Some(path) = config_path() else {
             return Ok(());
@@ -173,6 +389,21 @@ impl AppConfig {
             if let Some(position) = ui.tab_bar_position {
                 self.ui.tab_bar_position = position;
             }
+            if let Some(enabled) = ui.status_bar {
+                self.ui.status_bar = enabled;
+            }
+            if let Some(segments) = ui.status_segments.as_deref() {
+                self.ui.status_segments = sanitize_status_segments(segments);
+            }
+            if let Some(enabled) = ui.high_contrast {
+                self.ui.high_contrast = enabled;
+            }
+            if let Some(enabled) = ui.large_text {
+                self.ui.large_text = enabled;
+            }
+            if let Some(id) = ui.app_id.as_deref() {
+                self.ui.app_id = sanitize_app_id(id);
+            }
         }
 
         if let Some(term) = file.terminal {
@@ -186,14 +417,21 @@ impl AppConfig {
                     sanitize_terminal_font_size(size, self.terminal.font_size);
             }
 
-            // Cell dimensions derived from selected font + size
-            let (cw, ch) = cell_metrics_for_selection(
-                self.terminal.font_selection.as_deref(),
-                self.terminal.font_size,
-            );
-            self.terminal.cell_width = cw;
-            self.terminal.cell_height = ch;
+            if let Some(mult) = term.line_height {
+                self.terminal.line_height = sanitize_cell_spacing(mult, self.terminal.line_height);
+            }
+            if let Some(mult) = term.letter_spacing {
+                self.terminal.letter_spacing =
+                    sanitize_cell_spacing(mult, self.terminal.letter_spacing);
+            }
+            self.terminal.refresh_cell_metrics();
 
+            if let Some(rendering) = term.text_rendering {
+                self.terminal.text_rendering = rendering;
+            }
+            if let Some(enabled) = term.font_synthesize {
+                self.terminal.font_synthesize = enabled;
+            }
             if let Some(px) = term.padding_x {
                 self.terminal.padding_x = sanitize_padding(px);
             }
@@ -207,28 +445,137 @@ impl AppConfig {
             if let Some(enabled) = term.bracketed_paste {
                 self.terminal.bracketed_paste = enabled;
             }
+            if let Some(enabled) = term.autocomplete {
+                self.terminal.autocomplete = enabled;
+            }
             if let Some(enabled) = term.multiline_paste_confirm {
                 self.terminal.multiline_paste_confirm = enabled;
             }
+            if let Some(lines) = term.large_paste_lines {
+                self.terminal.large_paste_lines =
+                    sanitize_paste_threshold(lines, self.terminal.large_paste_lines);
+            }
+            if let Some(kib) = term.large_paste_kib {
+                self.terminal.large_paste_kib =
+                    sanitize_paste_threshold(kib, self.terminal.large_paste_kib);
+            }
             if let Some(mult) = term.scroll_multiplier {
                 self.terminal.scroll_multiplier =
                     sanitize_scroll_multiplier(mult, self.terminal.scroll_multiplier);
             }
+            if let Some(enabled) = term.smooth_scroll {
+                self.terminal.smooth_scroll = enabled;
+            }
             if let Some(shape) = term.cursor_shape {
                 self.terminal.cursor_shape = shape;
             }
             if let Some(enabled) = term.cursor_blink {
                 self.terminal.cursor_blink = enabled;
             }
+            if let Some(enabled) = term.cursor_trail {
+                self.terminal.cursor_trail = enabled;
+            }
+            if let Some(ms) = term.cursor_trail_ms {
+                self.terminal.cursor_trail_ms =
+                    sanitize_cursor_trail_ms(ms, self.terminal.cursor_trail_ms);
+            }
+            if let Some(enabled) = term.highlight_cursor_row {
+                self.terminal.highlight_cursor_row = enabled;
+            }
+            if let Some(enabled) = term.wrap_indicator {
+                self.terminal.wrap_indicator = enabled;
+            }
+            if let Some(dim) = term.inactive_pane_dim {
+                self.terminal.inactive_pane_dim =
+                    sanitize_dim(dim, self.terminal.inactive_pane_dim);
+            }
+            if let Some(enabled) = term.dim_unfocused_window {
+                self.terminal.dim_unfocused_window = enabled;
+            }
+            if let Some(enabled) = term.local_echo {
+                self.terminal.local_echo = enabled;
+            }
+            if let Some(enabled) = term.persistent_sessions {
+                self.terminal.persistent_sessions = enabled;
+            }
+            if let Some(secs) = term.notify_command_secs {
+                self.terminal.notify_command_secs = secs;
+            }
+            if let Some(minutes) = term.idle_lock_minutes {
+                self.terminal.idle_lock_minutes = minutes;
+            }
+            if let Some(enabled) = term.secure_keyboard_entry {
+                self.terminal.secure_keyboard_entry = enabled;
+            }
+            if let Some(columns) = term.column_rulers.as_deref() {
+                self.terminal.column_rulers = sanitize_column_rulers(columns);
+            }
             if let Some(enabled) = term.bold_is_bright {
                 self.terminal.bold_is_bright = enabled;
             }
             if let Some(mode) = term.bell_mode {
                 self.terminal.bell_mode = mode;
             }
+            if let Some(enabled) = term.bell_attention {
+                self.terminal.bell_attention = enabled;
+            }
+            if let Some(mode) = term.timestamps {
+                self.terminal.timestamps = mode;
+            }
             if let Some(action) = term.right_click_action {
                 self.terminal.right_click_action = action;
             }
+            if let Some(behavior) = term.exit_behavior {
+                self.terminal.exit_behavior = behavior;
+            }
+            self.terminal.default_profile = term
+                .default_profile
+                .as_deref()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string);
+            self.terminal.editor_command = term
+                .editor_command
+                .as_deref()
+                .and_then(sanitize_editor_command);
+            self.terminal.assist_command = term
+                .assist_command
+                .as_deref()
+                .and_then(sanitize_assist_command);
+            if let Some(enabled) = term.confirm_links {
+                self.terminal.confirm_links = enabled;
+            }
+            if let Some(schemes) = term.link_schemes.as_deref() {
+                self.terminal.link_schemes = sanitize_link_schemes(schemes);
+            }
+            if let Some(answerback) = term.answerback.as_deref() {
+                self.terminal.answerback = sanitize_answerback(answerback);
+            }
+            if let Some(enabled) = term.tmux_passthrough {
+                self.terminal.tmux_passthrough = enabled;
+            }
+            if let Some(triggers) = term.triggers.as_deref() {
+                self.terminal.triggers = triggers
+                    .iter()
+                    .filter_map(TriggerFileConfig::to_trigger)
+                    .collect();
+            }
+            if let Some(patterns) = term.redact_patterns {
+                self.terminal.redact_patterns = patterns
+                    .into_iter()
+                    .filter(|pattern| !pattern.trim().is_empty())
+                    .collect();
+            }
+            if let Some(shells) = term.container_shells {
+                self.terminal.container_shells = shells
+                    .into_iter()
+                    .map(|(key, shell)| (key.trim().to_string(), shell.trim().to_string()))
+                    .filter(|(key, shell)| !key.is_empty() && !shell.is_empty())
+                    .collect();
+            }
+            if let Some(env) = term.env.as_ref() {
+                self.terminal.env = sanitize_env(env);
+            }
         }
 
         if let Some(theme) = file.theme {
@@ -279,6 +626,36 @@ impl AppConfig {
             }
         }
 
+        if let Some(selection) = file.selection {
+            if let Some(separators) = selection.word_separators {
+                self.selection.word_separators = separators;
+            }
+            if let Some(rules) = selection.semantic_rules {
+                self.selection.semantic_rules = rules
+                    .into_iter()
+                    .filter(|rule| !rule.trim().is_empty())
+                    .collect();
+            }
+        }
+
+        if let Some(renderer) = file.renderer {
+            if let Some(mode) = renderer.present_mode {
+                self.renderer.present_mode = mode;
+            }
+            if let Some(fps) = renderer.unfocused_fps {
+                self.renderer.unfocused_fps = sanitize_fps(fps, self.renderer.unfocused_fps);
+            }
+            if let Some(enabled) = renderer.pause_when_minimized {
+                self.renderer.pause_when_minimized = enabled;
+            }
+            if let Some(backend) = renderer.backend {
+                self.renderer.backend = backend;
+            }
+            if let Some(adapter) = renderer.adapter {
+                self.renderer.adapter = adapter;
+            }
+        }
+
         if let Some(shortcuts) = file.shortcuts {
             for (key, value) in shortcuts {
                 if let Some(id) = ShortcutId::from_key(&key) {
@@ -297,6 +674,10 @@ impl AppConfig {
                 })
                 .collect();
         }
+
+        if let Some(snippets) = file.snippets.as_deref() {
+            self.snippets = sanitize_snippets(snippets);
+        }
     }
 }
 
@@ -309,5 +690,40 @@ mod tests {
         let config = AppConfig::default();
 
         assert_eq!(config.terminal.bell_mode, BellMode::Sound);
+        assert!(config.terminal.bell_attention);
+    }
+
+    #[test]
+    fn cell_size_lands_on_whole_physical_pixels() {
+        let mut terminal = AppConfig::default().terminal;
+        terminal.cell_width = 8.43;
+        terminal.cell_height = 17.2;
+
+        assert_eq!(terminal.cell_size_at(1.0), [8.0, 17.0]);
+        assert_eq!(terminal.cell_size_at(1.5), [26.0 / 3.0, 52.0 / 3.0]);
+        assert_eq!(terminal.cell_size_at(2.0), [8.5, 17.0]);
+        assert_eq!(terminal.cell_size_at(f32::NAN), [8.0, 17.0]);
+    }
+
+    #[test]
+    fn port_forward_specs_follow_openssh_syntax() {
+        let local = PortForward::parse(ForwardKind::Local, "8080:localhost:80").unwrap();
+        assert_eq!(local.bind_host(), "127.0.0.1");
+        assert_eq!((local.bind_port, local.target_port), (8080, 80));
+        assert_eq!(local.target_host, "localhost");
+
+        let remote = PortForward::parse(ForwardKind::Remote, "0.0.0.0:9000:[::1]:3000").unwrap();
+        assert_eq!(remote.bind_address, "0.0.0.0");
+        assert_eq!(remote.target_host, "::1");
+        assert_eq!(remote.spec(), "0.0.0.0:9000:[::1]:3000");
+
+        let socks = PortForward::parse(ForwardKind::Dynamic, "1080").unwrap();
+        assert_eq!(socks.spec(), "1080");
+
+        assert!(PortForward::parse(ForwardKind::Dynamic, "1080:host:80").is_none());
+        assert!(PortForward::parse(ForwardKind::Local, "8080").is_none());
+        assert!(PortForward::parse(ForwardKind::Local, "0:host:80").is_none());
+        assert!(PortForward::parse(ForwardKind::Local, "8080::80").is_none());
+        assert!(PortForward::parse(ForwardKind::Remote, "[::1:9000:host:80").is_none());
     }
 }
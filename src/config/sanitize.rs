@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 pub(super) fn sanitize_positive(value: f32, fallback: f32) -> f32 {
     if value.is_finite() && value > 0.0 {
         value
@@ -35,6 +37,16 @@ pub(super) fn sanitize_language(value: &str) -> Option<String> {
     }
 }
 
+/// Desktop ids are limited to letters, digits, `.`, `_` and `-`.
+pub(crate) fn sanitize_app_id(value: &str) -> Option<String> {
+    let id = value.trim();
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    valid.then(|| id.to_string())
+}
+
 pub(super) fn sanitize_terminal_font_selection(value: &str) -> Option<String> {
     let selection = value.trim();
     if selection.is_empty() {
@@ -44,6 +56,97 @@ pub(super) fn sanitize_terminal_font_selection(value: &str) -> Option<String> {
     }
 }
 
+/// Blank turns Explain and Fix off.
+pub(super) fn sanitize_assist_command(value: &str) -> Option<String> {
+    let command = value.trim();
+    (!command.is_empty()).then(|| command.to_string())
+}
+
+/// Blank means "use the system opener". A command without `{path}` gets the
+/// path appended so `code` or `subl` work as-is.
+pub(super) fn sanitize_editor_command(value: &str) -> Option<String> {
+    let command = value.trim();
+    if command.is_empty() {
+        None
+    } else if command.contains("{path}") {
+        Some(command.to_string())
+    } else {
+        Some(format!("{command} {{path}}"))
+    }
+}
+
+/// Whether `name` can be set as an environment variable: non-empty and free
+/// of `=` and NUL.
+pub(crate) fn is_valid_env_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['=', '\0'])
+}
+
+/// Trimmed names, without entries that could not be set.
+pub(super) fn sanitize_env(env: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    env.iter()
+        .map(|(name, value)| (name.trim().to_string(), value.clone()))
+        .filter(|(name, value)| is_valid_env_name(name) && !value.contains('\0'))
+        .collect()
+}
+
+/// Snippets that have both a name and a command, trimmed.
+pub(super) fn sanitize_snippets(snippets: &[super::Snippet]) -> Vec<super::Snippet> {
+    snippets
+        .iter()
+        .map(|snippet| super::Snippet {
+            name: snippet.name.trim().to_string(),
+            command: snippet.command.trim().to_string(),
+        })
+        .filter(|snippet| !snippet.name.is_empty() && !snippet.command.is_empty())
+        .collect()
+}
+
+/// Printable text only, so a program printing ENQ cannot make the terminal
+/// type escape sequences or a line break into the shell.
+pub(super) fn sanitize_answerback(value: &str) -> String {
+    value.chars().filter(|c| !c.is_control()).take(64).collect()
+}
+
+/// Lowercase and without duplicates; `https:` and `https://` are accepted
+/// for `https`.
+pub(super) fn sanitize_link_schemes(schemes: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for scheme in schemes {
+        let scheme = scheme.trim().trim_end_matches("//").trim_end_matches(':');
+        let scheme = scheme.to_ascii_lowercase();
+        if crate::terminal::url::is_valid_scheme(&scheme) && !out.contains(&scheme) {
+            out.push(scheme);
+        }
+    }
+    out
+}
+
+/// Sorted, without duplicates or column 0, and few enough to stay readable.
+pub(super) fn sanitize_column_rulers(columns: &[u16]) -> Vec<u16> {
+    let mut columns: Vec<u16> = columns
+        .iter()
+        .copied()
+        .filter(|column| (1..=1_000).contains(column))
+        .collect();
+    columns.sort_unstable();
+    columns.dedup();
+    columns.truncate(8);
+    columns
+}
+
+/// In the order given, each segment once.
+pub(super) fn sanitize_status_segments(
+    segments: &[super::StatusSegment],
+) -> Vec<super::StatusSegment> {
+    let mut out = Vec::new();
+    for &segment in segments {
+        if !out.contains(&segment) {
+            out.push(segment);
+        }
+    }
+    out
+}
+
 pub(super) fn sanitize_scrollback(value: usize, fallback: usize) -> usize {
     if (100..=1_000_000).contains(&value) {
         value
@@ -52,6 +155,11 @@ pub(super) fn sanitize_scrollback(value: usize, fallback: usize) -> usize {
     }
 }
 
+/// 0 is valid and turns the threshold off.
+pub(super) fn sanitize_paste_threshold(value: usize, fallback: usize) -> usize {
+    if value <= 1_000_000 { value } else { fallback }
+}
+
 pub(super) fn sanitize_scroll_multiplier(value: f32, fallback: f32) -> f32 {
     if value.is_finite() && (0.1..=10.0).contains(&value) {
         value
@@ -60,6 +168,40 @@ pub(super) fn sanitize_scroll_multiplier(value: f32, fallback: f32) -> f32 {
     }
 }
 
+pub(super) fn sanitize_cell_spacing(value: f32, fallback: f32) -> f32 {
+    if value.is_finite() && (0.5..=3.0).contains(&value) {
+        value
+    } else {
+        fallback
+    }
+}
+
+pub(super) fn sanitize_fps(value: u32, fallback: u32) -> u32 {
+    if (1..=240).contains(&value) {
+        value
+    } else {
+        fallback
+    }
+}
+
+/// Longer than a second and the cursor would trail behind typing.
+pub(super) fn sanitize_cursor_trail_ms(value: u32, fallback: u32) -> u32 {
+    if (20..=1_000).contains(&value) {
+        value
+    } else {
+        fallback
+    }
+}
+
+/// Dimming past 80% would make a pane unreadable.
+pub(super) fn sanitize_dim(value: f32, fallback: f32) -> f32 {
+    if value.is_finite() {
+        value.clamp(0.0, 0.8)
+    } else {
+        fallback
+    }
+}
+
 pub(super) fn sanitize_terminal_font_size(value: f32, fallback: f32) -> f32 {
     if value.is_finite() && (6.0..=72.0).contains(&value) {
         value
@@ -5,6 +5,7 @@ use std::collections::BTreeMap;
 pub enum ShortcutId {
     NewTab,
     CloseTab,
+    ReopenTab,
     OpenSettings,
     NextTab,
     PrevTab,
@@ -17,16 +18,65 @@ pub enum ShortcutId {
     SplitRight,
     SplitDown,
     ClosePane,
+    ZoomPane,
     FocusLeft,
     FocusRight,
     FocusUp,
     FocusDown,
+    ToggleMark,
+    PrevMark,
+    NextMark,
+    ListMarks,
+    CopyLastOutput,
+    RerunCommand,
+    SelectAll,
+    CopyMode,
+    SaveWorkspace,
+    OpenDirectory,
+    CopyDirectory,
+    DebugOverlay,
+    PrivacyMode,
+    Screenshot,
+    Record,
+    CheatSheet,
+    ReadScreen,
+}
+
+/// The groups the cheat sheet lists shortcuts under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutCategory {
+    Tabs,
+    Panes,
+    Terminal,
+    View,
+    Application,
+}
+
+impl ShortcutCategory {
+    pub const ALL: [Self; 5] = [
+        Self::Tabs,
+        Self::Panes,
+        Self::Terminal,
+        Self::View,
+        Self::Application,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Tabs => crate::t!("cheat_sheet.tabs"),
+            Self::Panes => crate::t!("cheat_sheet.panes"),
+            Self::Terminal => crate::t!("cheat_sheet.terminal"),
+            Self::View => crate::t!("cheat_sheet.view"),
+            Self::Application => crate::t!("cheat_sheet.application"),
+        }
+    }
 }
 
 impl ShortcutId {
-    pub const ALL: [Self; 18] = [
+    pub const ALL: [Self; 37] = [
         Self::NewTab,
         Self::CloseTab,
+        Self::ReopenTab,
         Self::DuplicateTab,
         Self::NextTab,
         Self::PrevTab,
@@ -34,14 +84,32 @@ impl ShortcutId {
         Self::SplitRight,
         Self::SplitDown,
         Self::ClosePane,
+        Self::ZoomPane,
         Self::FocusLeft,
         Self::FocusRight,
         Self::FocusUp,
         Self::FocusDown,
+        Self::ToggleMark,
+        Self::PrevMark,
+        Self::NextMark,
+        Self::ListMarks,
+        Self::CopyLastOutput,
+        Self::RerunCommand,
+        Self::SelectAll,
+        Self::CopyMode,
+        Self::SaveWorkspace,
+        Self::OpenDirectory,
+        Self::CopyDirectory,
         Self::FontSizeIncrease,
         Self::FontSizeDecrease,
         Self::FontSizeReset,
         Self::OpenSettings,
+        Self::CheatSheet,
+        Self::DebugOverlay,
+        Self::PrivacyMode,
+        Self::Screenshot,
+        Self::Record,
+        Self::ReadScreen,
         Self::Quit,
     ];
 
@@ -49,6 +117,7 @@ impl ShortcutId {
         match self {
             Self::NewTab => "new_tab",
             Self::CloseTab => "close_tab",
+            Self::ReopenTab => "reopen_tab",
             Self::OpenSettings => "open_settings",
             Self::NextTab => "next_tab",
             Self::PrevTab => "prev_tab",
@@ -61,10 +130,28 @@ impl ShortcutId {
             Self::SplitRight => "split_right",
             Self::SplitDown => "split_down",
             Self::ClosePane => "close_pane",
+            Self::ZoomPane => "zoom_pane",
             Self::FocusLeft => "focus_left",
             Self::FocusRight => "focus_right",
             Self::FocusUp => "focus_up",
             Self::FocusDown => "focus_down",
+            Self::ToggleMark => "toggle_mark",
+            Self::PrevMark => "prev_mark",
+            Self::NextMark => "next_mark",
+            Self::ListMarks => "list_marks",
+            Self::CopyLastOutput => "copy_last_output",
+            Self::RerunCommand => "rerun_command",
+            Self::SelectAll => "select_all",
+            Self::CopyMode => "copy_mode",
+            Self::SaveWorkspace => "save_workspace",
+            Self::OpenDirectory => "open_directory",
+            Self::CopyDirectory => "copy_directory",
+            Self::DebugOverlay => "debug_overlay",
+            Self::PrivacyMode => "privacy_mode",
+            Self::Screenshot => "screenshot",
+            Self::Record => "record",
+            Self::CheatSheet => "cheat_sheet",
+            Self::ReadScreen => "read_screen",
         }
     }
 
@@ -72,6 +159,7 @@ impl ShortcutId {
         match self {
             Self::NewTab => crate::t!("settings.shortcuts.new_tab"),
             Self::CloseTab => crate::t!("settings.shortcuts.close_tab"),
+            Self::ReopenTab => crate::t!("settings.shortcuts.reopen_tab"),
             Self::OpenSettings => crate::t!("settings.shortcuts.open_settings"),
             Self::NextTab => crate::t!("settings.shortcuts.next_tab"),
             Self::PrevTab => crate::t!("settings.shortcuts.prev_tab"),
@@ -84,10 +172,28 @@ impl ShortcutId {
             Self::SplitRight => crate::t!("settings.shortcuts.split_right"),
             Self::SplitDown => crate::t!("settings.shortcuts.split_down"),
             Self::ClosePane => crate::t!("settings.shortcuts.close_pane"),
+            Self::ZoomPane => crate::t!("settings.shortcuts.zoom_pane"),
             Self::FocusLeft => crate::t!("settings.shortcuts.focus_left"),
             Self::FocusRight => crate::t!("settings.shortcuts.focus_right"),
             Self::FocusUp => crate::t!("settings.shortcuts.focus_up"),
             Self::FocusDown => crate::t!("settings.shortcuts.focus_down"),
+            Self::ToggleMark => crate::t!("settings.shortcuts.toggle_mark"),
+            Self::PrevMark => crate::t!("settings.shortcuts.prev_mark"),
+            Self::NextMark => crate::t!("settings.shortcuts.next_mark"),
+            Self::ListMarks => crate::t!("settings.shortcuts.list_marks"),
+            Self::CopyLastOutput => crate::t!("settings.shortcuts.copy_last_output"),
+            Self::RerunCommand => crate::t!("settings.shortcuts.rerun_command"),
+            Self::SelectAll => crate::t!("settings.shortcuts.select_all"),
+            Self::CopyMode => crate::t!("settings.shortcuts.copy_mode"),
+            Self::SaveWorkspace => crate::t!("settings.shortcuts.save_workspace"),
+            Self::OpenDirectory => crate::t!("settings.shortcuts.open_directory"),
+            Self::CopyDirectory => crate::t!("settings.shortcuts.copy_directory"),
+            Self::DebugOverlay => crate::t!("settings.shortcuts.debug_overlay"),
+            Self::PrivacyMode => crate::t!("settings.shortcuts.privacy_mode"),
+            Self::Screenshot => crate::t!("settings.shortcuts.screenshot"),
+            Self::Record => crate::t!("settings.shortcuts.record"),
+            Self::CheatSheet => crate::t!("settings.shortcuts.cheat_sheet"),
+            Self::ReadScreen => crate::t!("settings.shortcuts.read_screen"),
         }
     }
 
@@ -95,6 +201,7 @@ impl ShortcutId {
         match self {
             Self::NewTab => DEFAULT_SHORTCUT_NEW_TAB,
             Self::CloseTab => DEFAULT_SHORTCUT_CLOSE_TAB,
+            Self::ReopenTab => DEFAULT_SHORTCUT_REOPEN_TAB,
             Self::OpenSettings => DEFAULT_SHORTCUT_OPEN_SETTINGS,
             Self::NextTab => DEFAULT_SHORTCUT_NEXT_TAB,
             Self::PrevTab => DEFAULT_SHORTCUT_PREV_TAB,
@@ -107,10 +214,68 @@ impl ShortcutId {
             Self::SplitRight => DEFAULT_SHORTCUT_SPLIT_RIGHT,
             Self::SplitDown => DEFAULT_SHORTCUT_SPLIT_DOWN,
             Self::ClosePane => DEFAULT_SHORTCUT_CLOSE_PANE,
+            Self::ZoomPane => DEFAULT_SHORTCUT_ZOOM_PANE,
             Self::FocusLeft => DEFAULT_SHORTCUT_FOCUS_LEFT,
             Self::FocusRight => DEFAULT_SHORTCUT_FOCUS_RIGHT,
             Self::FocusUp => DEFAULT_SHORTCUT_FOCUS_UP,
             Self::FocusDown => DEFAULT_SHORTCUT_FOCUS_DOWN,
+            Self::ToggleMark => DEFAULT_SHORTCUT_TOGGLE_MARK,
+            Self::PrevMark => DEFAULT_SHORTCUT_PREV_MARK,
+            Self::NextMark => DEFAULT_SHORTCUT_NEXT_MARK,
+            Self::ListMarks => DEFAULT_SHORTCUT_LIST_MARKS,
+            Self::CopyLastOutput => DEFAULT_SHORTCUT_COPY_LAST_OUTPUT,
+            Self::RerunCommand => DEFAULT_SHORTCUT_RERUN_COMMAND,
+            Self::SelectAll => DEFAULT_SHORTCUT_SELECT_ALL,
+            Self::CopyMode => DEFAULT_SHORTCUT_COPY_MODE,
+            Self::SaveWorkspace => DEFAULT_SHORTCUT_SAVE_WORKSPACE,
+            Self::OpenDirectory => DEFAULT_SHORTCUT_OPEN_DIRECTORY,
+            Self::CopyDirectory => DEFAULT_SHORTCUT_COPY_DIRECTORY,
+            Self::DebugOverlay => DEFAULT_SHORTCUT_DEBUG_OVERLAY,
+            Self::PrivacyMode => DEFAULT_SHORTCUT_PRIVACY_MODE,
+            Self::Screenshot => DEFAULT_SHORTCUT_SCREENSHOT,
+            Self::Record => DEFAULT_SHORTCUT_RECORD,
+            Self::CheatSheet => DEFAULT_SHORTCUT_CHEAT_SHEET,
+            Self::ReadScreen => DEFAULT_SHORTCUT_READ_SCREEN,
+        }
+    }
+
+    pub fn category(self) -> ShortcutCategory {
+        match self {
+            Self::NewTab
+            | Self::CloseTab
+            | Self::ReopenTab
+            | Self::DuplicateTab
+            | Self::NextTab
+            | Self::PrevTab
+            | Self::SaveWorkspace => ShortcutCategory::Tabs,
+            Self::SplitAuto
+            | Self::SplitRight
+            | Self::SplitDown
+            | Self::ClosePane
+            | Self::ZoomPane
+            | Self::FocusLeft
+            | Self::FocusRight
+            | Self::FocusUp
+            | Self::FocusDown => ShortcutCategory::Panes,
+            Self::ToggleMark
+            | Self::PrevMark
+            | Self::NextMark
+            | Self::ListMarks
+            | Self::CopyLastOutput
+            | Self::RerunCommand
+            | Self::SelectAll
+            | Self::CopyMode
+            | Self::OpenDirectory
+            | Self::CopyDirectory
+            | Self::Screenshot
+            | Self::Record
+            | Self::ReadScreen => ShortcutCategory::Terminal,
+            Self::FontSizeIncrease
+            | Self::FontSizeDecrease
+            | Self::FontSizeReset
+            | Self::DebugOverlay
+            | Self::PrivacyMode => ShortcutCategory::View,
+            Self::OpenSettings | Self::CheatSheet | Self::Quit => ShortcutCategory::Application,
         }
     }
 
@@ -119,6 +284,71 @@ impl ShortcutId {
     }
 }
 
+/// A set of bindings modeled on another terminal, offered on first launch.
+/// Shortcuts a preset leaves out keep their defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShortcutPreset {
+    #[default]
+    Default,
+    Iterm2,
+    WindowsTerminal,
+}
+
+#[cfg(target_os = "macos")]
+const ITERM2_BINDINGS: &[(ShortcutId, &str)] = &[
+    (ShortcutId::SplitRight, "Command+D"),
+    (ShortcutId::SplitDown, "Command+Shift+D"),
+    (ShortcutId::DuplicateTab, "Command+Alt+D"),
+    (ShortcutId::NextTab, "Command+Shift+]"),
+    (ShortcutId::PrevTab, "Command+Shift+["),
+    (ShortcutId::PrevMark, "Command+Shift+ArrowUp"),
+    (ShortcutId::NextMark, "Command+Shift+ArrowDown"),
+];
+#[cfg(not(target_os = "macos"))]
+const ITERM2_BINDINGS: &[(ShortcutId, &str)] = &[
+    (ShortcutId::SplitRight, "Ctrl+Shift+D"),
+    (ShortcutId::SplitDown, "Ctrl+Alt+Shift+D"),
+    (ShortcutId::DuplicateTab, "Ctrl+Alt+D"),
+    (ShortcutId::NextTab, "Ctrl+Shift+]"),
+    (ShortcutId::PrevTab, "Ctrl+Shift+["),
+];
+
+const WINDOWS_TERMINAL_BINDINGS: &[(ShortcutId, &str)] = &[
+    (ShortcutId::NewTab, "Ctrl+Shift+T"),
+    (ShortcutId::CloseTab, "Ctrl+F4"),
+    (ShortcutId::DuplicateTab, "Ctrl+Shift+D"),
+    (ShortcutId::NextTab, "Ctrl+Tab"),
+    (ShortcutId::PrevTab, "Ctrl+Shift+Tab"),
+    (ShortcutId::SplitAuto, "Alt+Shift+D"),
+    (ShortcutId::SplitRight, "Alt+Shift+="),
+    (ShortcutId::SplitDown, "Alt+Shift+-"),
+    (ShortcutId::ClosePane, "Ctrl+Shift+W"),
+    (ShortcutId::FocusLeft, "Alt+ArrowLeft"),
+    (ShortcutId::FocusRight, "Alt+ArrowRight"),
+    (ShortcutId::FocusUp, "Alt+ArrowUp"),
+    (ShortcutId::FocusDown, "Alt+ArrowDown"),
+];
+
+impl ShortcutPreset {
+    pub const ALL: [Self; 3] = [Self::Default, Self::Iterm2, Self::WindowsTerminal];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Default => crate::t!("onboarding.keys_default"),
+            Self::Iterm2 => "iTerm2",
+            Self::WindowsTerminal => "Windows Terminal",
+        }
+    }
+
+    fn bindings(self) -> &'static [(ShortcutId, &'static str)] {
+        match self {
+            Self::Default => &[],
+            Self::Iterm2 => ITERM2_BINDINGS,
+            Self::WindowsTerminal => WINDOWS_TERMINAL_BINDINGS,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ShortcutsConfig {
     bindings: BTreeMap<ShortcutId, String>,
@@ -136,6 +366,14 @@ impl Default for ShortcutsConfig {
 }
 
 impl ShortcutsConfig {
+    pub fn from_preset(preset: ShortcutPreset) -> Self {
+        let mut config = Self::default();
+        for &(id, binding) in preset.bindings() {
+            config.set(id, binding.to_string());
+        }
+        config
+    }
+
     pub fn get(&self, id: ShortcutId) -> &str {
         self.bindings
             .get(&id)
@@ -177,6 +415,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn presets_do_not_collide_and_are_already_normalized() {
+        for preset in ShortcutPreset::ALL {
+            let config = ShortcutsConfig::from_preset(preset);
+            let mut bindings: Vec<&str> = config.iter().map(|(_, binding)| binding).collect();
+            for binding in &bindings {
+                assert_eq!(
+                    super::super::sanitize::normalize_shortcut(binding).as_deref(),
+                    Some(*binding)
+                );
+            }
+            bindings.sort_unstable();
+            let before = bindings.len();
+            bindings.dedup();
+            assert_eq!(bindings.len(), before, "{preset:?}");
+        }
+    }
+
     #[test]
     fn keys_round_trip() {
         for id in ShortcutId::ALL {
@@ -0,0 +1,129 @@
+//! Snippets: saved command templates typed into the terminal on demand.
+//! `${name}` marks a value asked for when the snippet is used, and
+//! `${name:default}` pre-fills the answer.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub name: String,
+    pub command: String,
+}
+
+/// A value a snippet asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub name: String,
+    pub default: String,
+}
+
+enum Piece<'a> {
+    Text(&'a str),
+    Field { name: &'a str, default: &'a str },
+}
+
+impl Snippet {
+    /// The placeholders in the order they first appear. A name used more
+    /// than once is asked for once.
+    pub fn placeholders(&self) -> Vec<Placeholder> {
+        let mut out: Vec<Placeholder> = Vec::new();
+        for piece in pieces(&self.command) {
+            if let Piece::Field { name, default } = piece
+                && !out.iter().any(|known| known.name == name)
+            {
+                out.push(Placeholder {
+                    name: name.to_string(),
+                    default: default.to_string(),
+                });
+            }
+        }
+        out
+    }
+
+    /// The command with every placeholder replaced by its value, `values`
+    /// being in the order of [`Self::placeholders`].
+    pub fn expand(&self, values: &[String]) -> String {
+        let placeholders = self.placeholders();
+        pieces(&self.command)
+            .into_iter()
+            .map(|piece| match piece {
+                Piece::Text(text) => text,
+                Piece::Field { name, default } => placeholders
+                    .iter()
+                    .position(|known| known.name == name)
+                    .and_then(|index| values.get(index))
+                    .map_or(default, String::as_str),
+            })
+            .collect()
+    }
+}
+
+/// Splits a command into literal text and placeholders. A `${` without a
+/// closing brace or with an empty name stays literal.
+fn pieces(command: &str) -> Vec<Piece<'_>> {
+    let mut out = Vec::new();
+    let mut rest = command;
+    while let Some(open) = rest.find("${") {
+        let Some(close) = rest[open + 2..].find('}') else {
+            break;
+        };
+        let inner = &rest[open + 2..open + 2 + close];
+        let (name, default) = inner.split_once(':').unwrap_or((inner, ""));
+        let name = name.trim();
+        if name.is_empty() {
+            out.push(Piece::Text(&rest[..open + 2]));
+            rest = &rest[open + 2..];
+            continue;
+        }
+        out.push(Piece::Text(&rest[..open]));
+        out.push(Piece::Field { name, default });
+        rest = &rest[open + 3 + close..];
+    }
+    out.push(Piece::Text(rest));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(command: &str) -> Snippet {
+        Snippet {
+            name: "test".into(),
+            command: command.into(),
+        }
+    }
+
+    #[test]
+    fn placeholders_are_listed_once_in_order() {
+        let snippet = snippet("ssh ${user}@${host:localhost} -t 'echo ${user}'");
+        let names: Vec<_> = snippet
+            .placeholders()
+            .into_iter()
+            .map(|p| (p.name, p.default))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("user".to_string(), String::new()),
+                ("host".to_string(), "localhost".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn expansion_fills_every_use_of_a_name() {
+        let snippet = snippet("git checkout -b ${branch} && git push -u origin ${branch}");
+        assert_eq!(
+            snippet.expand(&["fix".into()]),
+            "git checkout -b fix && git push -u origin fix"
+        );
+    }
+
+    #[test]
+    fn missing_values_fall_back_to_defaults_and_odd_braces_stay_literal() {
+        let snippet = snippet("echo ${} ${port:8080} ${unclosed");
+        assert_eq!(snippet.placeholders().len(), 1);
+        assert_eq!(snippet.expand(&[]), "echo ${} 8080 ${unclosed");
+    }
+}
@@ -1,3 +1,4 @@
+use super::defaults::DEFAULT_SSH_KEEPALIVE_INTERVAL;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
@@ -6,6 +7,169 @@ pub enum SshAuthMethod {
     KeyFile,
     #[default]
     Password,
+    /// Keys held by the local SSH agent, or Pageant on Windows.
+    Agent,
+}
+
+/// How a network profile talks to its host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkProtocol {
+    /// Telnet, negotiating terminal type and window size.
+    #[default]
+    Telnet,
+    /// Bytes passed through unchanged.
+    Raw,
+}
+
+impl NetworkProtocol {
+    /// Short lowercase name, as shown next to the address.
+    pub fn scheme(self) -> &'static str {
+        match self {
+            Self::Telnet => "telnet",
+            Self::Raw => "tcp",
+        }
+    }
+}
+
+/// Direction of an SSH port forward, after OpenSSH's `-L`, `-R` and `-D`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ForwardKind {
+    /// Listen here; the server connects to the target.
+    #[default]
+    Local,
+    /// The server listens; we connect to the target.
+    Remote,
+    /// SOCKS5 proxy listening here; the server connects to each destination.
+    Dynamic,
+}
+
+impl ForwardKind {
+    pub const ALL: [Self; 3] = [Self::Local, Self::Remote, Self::Dynamic];
+
+    /// The matching OpenSSH command-line flag.
+    pub fn flag(self) -> &'static str {
+        match self {
+            Self::Local => "-L",
+            Self::Remote => "-R",
+            Self::Dynamic => "-D",
+        }
+    }
+}
+
+/// One port forward of an SSH profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortForward {
+    pub kind: ForwardKind,
+    /// Address to listen on; empty means loopback.
+    pub bind_address: String,
+    pub bind_port: u16,
+    /// Unused by dynamic forwards.
+    pub target_host: String,
+    pub target_port: u16,
+    /// Started when the session connects. Toggled from the tab at runtime.
+    pub enabled: bool,
+}
+
+impl Default for PortForward {
+    fn default() -> Self {
+        Self {
+            kind: ForwardKind::Local,
+            bind_address: String::new(),
+            bind_port: 0,
+            target_host: String::new(),
+            target_port: 0,
+            enabled: true,
+        }
+    }
+}
+
+impl PortForward {
+    /// Parses an OpenSSH-style spec: `[bind:]port:host:hostport`, or
+    /// `[bind:]port` for dynamic forwards. IPv6 addresses go in brackets.
+    pub fn parse(kind: ForwardKind, spec: &str) -> Option<Self> {
+        let fields = split_forward_spec(spec.trim())?;
+        fn port(s: &str) -> Option<u16> {
+            s.parse().ok().filter(|&p| p != 0)
+        }
+        let (bind_address, bind_port, target) = match (kind, fields.as_slice()) {
+            (ForwardKind::Dynamic, [bind_port]) => (String::new(), port(bind_port)?, None),
+            (ForwardKind::Dynamic, [bind, bind_port]) => (bind.clone(), port(bind_port)?, None),
+            (ForwardKind::Dynamic, _) => return None,
+            (_, [bind_port, host, target_port]) => {
+                (String::new(), port(bind_port)?, Some((host, target_port)))
+            }
+            (_, [bind, bind_port, host, target_port]) => {
+                (bind.clone(), port(bind_port)?, Some((host, target_port)))
+            }
+            _ => return None,
+        };
+        let (target_host, target_port) = match target {
+            Some((host, _)) if host.is_empty() => return None,
+            Some((host, target_port)) => (host.clone(), port(target_port)?),
+            None => (String::new(), 0),
+        };
+        Some(Self {
+            kind,
+            bind_address,
+            bind_port,
+            target_host,
+            target_port,
+            enabled: true,
+        })
+    }
+
+    /// The spec `parse` reads back, without the flag.
+    pub fn spec(&self) -> String {
+        let mut out = String::new();
+        if !self.bind_address.is_empty() {
+            out.push_str(&bracket_host(&self.bind_address));
+            out.push(':');
+        }
+        out.push_str(&self.bind_port.to_string());
+        if self.kind != ForwardKind::Dynamic {
+            out.push_str(&format!(
+                ":{}:{}",
+                bracket_host(&self.target_host),
+                self.target_port
+            ));
+        }
+        out
+    }
+
+    /// The listen address, loopback when none is set.
+    pub fn bind_host(&self) -> &str {
+        if self.bind_address.is_empty() {
+            "127.0.0.1"
+        } else {
+            &self.bind_address
+        }
+    }
+}
+
+/// Splits a forward spec on `:`, keeping bracketed IPv6 addresses whole.
+fn split_forward_spec(spec: &str) -> Option<Vec<String>> {
+    let mut fields = vec![String::new()];
+    let mut bracketed = false;
+    for ch in spec.chars() {
+        match ch {
+            '[' if !bracketed => bracketed = true,
+            ']' if bracketed => bracketed = false,
+            ':' if !bracketed => fields.push(String::new()),
+            _ => fields.last_mut()?.push(ch),
+        }
+    }
+    (!bracketed).then_some(fields)
+}
+
+fn bracket_host(host: &str) -> String {
+    if host.contains(':') {
+        format!("[{host}]")
+    } else {
+        host.to_string()
+    }
 }
 
 /// Visual shape of the terminal text cursor.
@@ -58,6 +222,134 @@ impl std::fmt::Display for BellMode {
     }
 }
 
+/// What the gutter left of each pane shows for the lines of output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampMode {
+    /// No gutter.
+    #[default]
+    Off,
+    /// How long ago each line arrived.
+    Relative,
+    /// The time of day each line arrived.
+    Absolute,
+}
+
+impl TimestampMode {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Relative, Self::Absolute];
+}
+
+impl std::fmt::Display for TimestampMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Self::Off => crate::t!("settings.terminal.timestamps.off"),
+            Self::Relative => crate::t!("settings.terminal.timestamps.relative"),
+            Self::Absolute => crate::t!("settings.terminal.timestamps.absolute"),
+        };
+        f.write_str(label)
+    }
+}
+
+/// How glyph coverage is blended onto the background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextRendering {
+    /// Coverage used as alpha as-is.
+    #[default]
+    Grayscale,
+    /// Coverage adjusted for the text's brightness, so dark text on a light
+    /// background is not thinned by linear blending.
+    Gamma,
+    /// Per-channel LCD coverage, gamma adjusted. Only on an opaque
+    /// background; otherwise the same as `Gamma`.
+    Subpixel,
+}
+
+impl TextRendering {
+    pub const ALL: [Self; 3] = [Self::Grayscale, Self::Gamma, Self::Subpixel];
+}
+
+/// How finished frames are handed to the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PresentMode {
+    /// Wait for the display's refresh; no tearing.
+    #[default]
+    Vsync,
+    /// Replace a queued frame with a newer one; no tearing, lower latency,
+    /// not available everywhere.
+    Mailbox,
+    /// Show frames as soon as they are ready, tearing included.
+    Immediate,
+}
+
+impl PresentMode {
+    pub const ALL: [Self; 3] = [Self::Vsync, Self::Mailbox, Self::Immediate];
+
+    /// The value iced's wgpu backend reads from `ICED_PRESENT_MODE`.
+    pub fn env_value(self) -> &'static str {
+        match self {
+            Self::Vsync => "vsync",
+            Self::Mailbox => "mailbox",
+            Self::Immediate => "immediate",
+        }
+    }
+}
+
+/// Graphics API wgpu draws with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuBackend {
+    /// Whatever the platform offers first.
+    #[default]
+    Auto,
+    Vulkan,
+    Dx12,
+    Metal,
+    /// OpenGL, which also covers software rasterizers such as llvmpipe.
+    Gl,
+}
+
+impl GpuBackend {
+    pub const ALL: [Self; 5] = [Self::Auto, Self::Vulkan, Self::Dx12, Self::Metal, Self::Gl];
+
+    /// The value wgpu reads from `WGPU_BACKEND`; `None` leaves it unset.
+    pub fn env_value(self) -> Option<&'static str> {
+        match self {
+            Self::Auto => None,
+            Self::Vulkan => Some("vulkan"),
+            Self::Dx12 => Some("dx12"),
+            Self::Metal => Some("metal"),
+            Self::Gl => Some("gl"),
+        }
+    }
+}
+
+/// Which GPU to ask for when there is more than one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AdapterPreference {
+    #[default]
+    Auto,
+    /// Usually the integrated GPU.
+    LowPower,
+    /// Usually the discrete GPU.
+    HighPerformance,
+}
+
+impl AdapterPreference {
+    pub const ALL: [Self; 3] = [Self::Auto, Self::LowPower, Self::HighPerformance];
+
+    /// The value wgpu reads from `WGPU_POWER_PREF`; `None` leaves it unset.
+    pub fn env_value(self) -> Option<&'static str> {
+        match self {
+            Self::Auto => None,
+            Self::LowPower => Some("low"),
+            Self::HighPerformance => Some("high"),
+        }
+    }
+}
+
 /// Where the tab bar (which doubles as the title bar) is anchored.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -71,6 +363,57 @@ impl TabBarPosition {
     pub const ALL: [Self; 2] = [Self::Top, Self::Bottom];
 }
 
+/// A piece of information the status bar can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusSegment {
+    Profile,
+    Cwd,
+    GridSize,
+    Scrollback,
+    Process,
+    /// Bells and output in tabs out of view.
+    Alerts,
+}
+
+impl StatusSegment {
+    pub const ALL: [Self; 6] = [
+        Self::Profile,
+        Self::Cwd,
+        Self::GridSize,
+        Self::Scrollback,
+        Self::Process,
+        Self::Alerts,
+    ];
+}
+
+/// What happens to a pane when its shell exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitBehavior {
+    /// Close the pane, and the tab with its last pane.
+    Close,
+    /// Close after a clean exit; keep the final screen otherwise.
+    #[default]
+    CloseOnSuccess,
+    /// Always keep the final screen until the pane is closed by hand.
+    Hold,
+}
+
+impl ExitBehavior {
+    pub const ALL: [Self; 3] = [Self::Close, Self::CloseOnSuccess, Self::Hold];
+
+    /// Whether a pane whose shell ended with `exit_code` closes by itself.
+    /// An unknown code counts as success.
+    pub fn closes(self, exit_code: Option<i32>) -> bool {
+        match self {
+            Self::Close => true,
+            Self::CloseOnSuccess => exit_code.is_none_or(|code| code == 0),
+            Self::Hold => false,
+        }
+    }
+}
+
 /// Action taken when the terminal area is right-clicked.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -96,6 +439,31 @@ impl std::fmt::Display for RightClickAction {
     }
 }
 
+/// What a trigger does, besides restyling its match, when its pattern appears
+/// in a line of output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerAction {
+    #[default]
+    None,
+    /// Posts a desktop notification with the matched text.
+    Notify,
+    /// Rings the bell as if the program had sent `\a`.
+    Bell,
+    /// Marks the matching line in scrollback.
+    Mark,
+}
+
+/// A regex matched against every line of output as it completes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trigger {
+    pub pattern: String,
+    pub foreground: Option<[u8; 3]>,
+    pub background: Option<[u8; 3]>,
+    pub underline: bool,
+    pub action: TriggerAction,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct SshProfile {
@@ -108,6 +476,18 @@ pub struct SshProfile {
     #[serde(skip)]
     pub password: Option<String>,
     pub proxy_command: Option<String>,
+    /// Seconds between keep-alive probes, like OpenSSH's
+    /// `ServerAliveInterval`. 0 disables them.
+    pub keepalive_interval: u64,
+    /// Reconnect with backoff when the connection drops, instead of waiting
+    /// for a key press.
+    pub auto_reconnect: bool,
+    /// Runs the session in the RabbiTTY daemon on the server through
+    /// `rabbitty attach`, so it survives dropped connections.
+    pub remote_attach: bool,
+    /// Lets the server use the local agent's keys, like `ssh -A`.
+    pub forward_agent: bool,
+    pub forwards: Vec<PortForward>,
 }
 
 impl Default for SshProfile {
@@ -121,6 +501,11 @@ impl Default for SshProfile {
             identity_file: None,
             password: None,
             proxy_command: None,
+            keepalive_interval: DEFAULT_SSH_KEEPALIVE_INTERVAL,
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         }
     }
 }
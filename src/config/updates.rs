@@ -1,8 +1,12 @@
 use super::AppConfig;
-use super::metrics::cell_metrics_for_selection;
 use super::sanitize::*;
 use super::shortcuts::ShortcutId;
-use super::types::{BellMode, CursorShape, RightClickAction, TabBarPosition};
+use super::snippet::Snippet;
+use super::types::{
+    AdapterPreference, BellMode, CursorShape, ExitBehavior, GpuBackend, PresentMode,
+    RightClickAction, StatusSegment, TabBarPosition, TextRendering, TimestampMode,
+};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Default, Clone)]
 pub struct AppConfigUpdates {
@@ -12,8 +16,16 @@ pub struct AppConfigUpdates {
     pub language: Option<String>,
     pub animations_enabled: Option<bool>,
     pub tab_bar_position: Option<TabBarPosition>,
+    pub status_bar: Option<bool>,
+    pub status_segments: Option<Vec<StatusSegment>>,
+    pub high_contrast: Option<bool>,
+    pub large_text: Option<bool>,
     pub terminal_font_selection: Option<String>,
     pub terminal_font_size: Option<f32>,
+    pub terminal_text_rendering: Option<TextRendering>,
+    pub terminal_font_synthesize: Option<bool>,
+    pub terminal_line_height: Option<f32>,
+    pub terminal_letter_spacing: Option<f32>,
     pub terminal_padding_x: Option<f32>,
     pub terminal_padding_y: Option<f32>,
     pub color_scheme: Option<String>,
@@ -28,12 +40,46 @@ pub struct AppConfigUpdates {
     pub terminal_scrollback: Option<usize>,
     pub terminal_bracketed_paste: Option<bool>,
     pub terminal_multiline_paste_confirm: Option<bool>,
+    pub terminal_autocomplete: Option<bool>,
+    pub terminal_large_paste_lines: Option<usize>,
+    pub terminal_large_paste_kib: Option<usize>,
     pub terminal_scroll_multiplier: Option<f32>,
+    pub terminal_smooth_scroll: Option<bool>,
     pub terminal_cursor_shape: Option<CursorShape>,
     pub terminal_cursor_blink: Option<bool>,
+    pub terminal_cursor_trail: Option<bool>,
+    pub terminal_cursor_trail_ms: Option<u32>,
+    pub terminal_highlight_cursor_row: Option<bool>,
+    pub terminal_wrap_indicator: Option<bool>,
+    pub terminal_inactive_pane_dim: Option<f32>,
+    pub terminal_dim_unfocused_window: Option<bool>,
+    pub terminal_local_echo: Option<bool>,
+    pub terminal_persistent_sessions: Option<bool>,
+    pub terminal_notify_command_secs: Option<u64>,
+    pub terminal_idle_lock_minutes: Option<u64>,
+    pub terminal_secure_keyboard_entry: Option<bool>,
+    pub terminal_column_rulers: Option<Vec<u16>>,
     pub terminal_bold_is_bright: Option<bool>,
     pub terminal_bell_mode: Option<BellMode>,
+    pub terminal_bell_attention: Option<bool>,
+    pub terminal_timestamps: Option<TimestampMode>,
     pub terminal_right_click_action: Option<RightClickAction>,
+    pub terminal_exit_behavior: Option<ExitBehavior>,
+    /// `None` = no change; `Some("")` = use the system opener.
+    pub terminal_editor_command: Option<String>,
+    /// `None` = no change; `Some("")` = turn Explain and Fix off.
+    pub terminal_assist_command: Option<String>,
+    pub terminal_confirm_links: Option<bool>,
+    pub terminal_link_schemes: Option<Vec<String>>,
+    pub terminal_answerback: Option<String>,
+    pub terminal_tmux_passthrough: Option<bool>,
+    pub terminal_env: Option<BTreeMap<String, String>>,
+    pub snippets: Option<Vec<Snippet>>,
+    pub renderer_present_mode: Option<PresentMode>,
+    pub renderer_unfocused_fps: Option<u32>,
+    pub renderer_pause_when_minimized: Option<bool>,
+    pub renderer_backend: Option<GpuBackend>,
+    pub renderer_adapter: Option<AdapterPreference>,
 }
 
 impl AppConfig {
@@ -53,6 +99,18 @@ impl AppConfig {
         if let Some(position) = updates.tab_bar_position {
             self.ui.tab_bar_position = position;
         }
+        if let Some(enabled) = updates.status_bar {
+            self.ui.status_bar = enabled;
+        }
+        if let Some(segments) = updates.status_segments.as_deref() {
+            self.ui.status_segments = sanitize_status_segments(segments);
+        }
+        if let Some(enabled) = updates.high_contrast {
+            self.ui.high_contrast = enabled;
+        }
+        if let Some(enabled) = updates.large_text {
+            self.ui.large_text = enabled;
+        }
         let old_font = self.terminal.font_selection.clone();
         if let Some(selection) = updates.terminal_font_selection {
             self.terminal.font_selection = sanitize_terminal_font_selection(&selection);
@@ -60,14 +118,20 @@ impl AppConfig {
         if let Some(size) = updates.terminal_font_size {
             self.terminal.font_size = sanitize_terminal_font_size(size, self.terminal.font_size);
         }
-        // Recalculate cell metrics if font or size changed
-        if self.terminal.font_selection != old_font || updates.terminal_font_size.is_some() {
-            let (cw, ch) = cell_metrics_for_selection(
-                self.terminal.font_selection.as_deref(),
-                self.terminal.font_size,
-            );
-            self.terminal.cell_width = cw;
-            self.terminal.cell_height = ch;
+        if let Some(mult) = updates.terminal_line_height {
+            self.terminal.line_height = sanitize_cell_spacing(mult, self.terminal.line_height);
+        }
+        if let Some(mult) = updates.terminal_letter_spacing {
+            self.terminal.letter_spacing =
+                sanitize_cell_spacing(mult, self.terminal.letter_spacing);
+        }
+        // Recalculate cell metrics if font, size or spacing changed
+        if self.terminal.font_selection != old_font
+            || updates.terminal_font_size.is_some()
+            || updates.terminal_line_height.is_some()
+            || updates.terminal_letter_spacing.is_some()
+        {
+            self.terminal.refresh_cell_metrics();
         }
         if let Some(px) = updates.terminal_padding_x {
             self.terminal.padding_x = sanitize_padding(px);
@@ -82,28 +146,118 @@ impl AppConfig {
         if let Some(enabled) = updates.terminal_bracketed_paste {
             self.terminal.bracketed_paste = enabled;
         }
+        if let Some(enabled) = updates.terminal_autocomplete {
+            self.terminal.autocomplete = enabled;
+        }
         if let Some(enabled) = updates.terminal_multiline_paste_confirm {
             self.terminal.multiline_paste_confirm = enabled;
         }
+        if let Some(lines) = updates.terminal_large_paste_lines {
+            self.terminal.large_paste_lines =
+                sanitize_paste_threshold(lines, self.terminal.large_paste_lines);
+        }
+        if let Some(kib) = updates.terminal_large_paste_kib {
+            self.terminal.large_paste_kib =
+                sanitize_paste_threshold(kib, self.terminal.large_paste_kib);
+        }
         if let Some(mult) = updates.terminal_scroll_multiplier {
             self.terminal.scroll_multiplier =
                 sanitize_scroll_multiplier(mult, self.terminal.scroll_multiplier);
         }
+        if let Some(enabled) = updates.terminal_smooth_scroll {
+            self.terminal.smooth_scroll = enabled;
+        }
+        if let Some(rendering) = updates.terminal_text_rendering {
+            self.terminal.text_rendering = rendering;
+        }
+        if let Some(enabled) = updates.terminal_font_synthesize {
+            self.terminal.font_synthesize = enabled;
+        }
         if let Some(shape) = updates.terminal_cursor_shape {
             self.terminal.cursor_shape = shape;
         }
         if let Some(enabled) = updates.terminal_cursor_blink {
             self.terminal.cursor_blink = enabled;
         }
+        if let Some(enabled) = updates.terminal_cursor_trail {
+            self.terminal.cursor_trail = enabled;
+        }
+        if let Some(ms) = updates.terminal_cursor_trail_ms {
+            self.terminal.cursor_trail_ms =
+                sanitize_cursor_trail_ms(ms, self.terminal.cursor_trail_ms);
+        }
+        if let Some(enabled) = updates.terminal_highlight_cursor_row {
+            self.terminal.highlight_cursor_row = enabled;
+        }
+        if let Some(enabled) = updates.terminal_wrap_indicator {
+            self.terminal.wrap_indicator = enabled;
+        }
+        if let Some(dim) = updates.terminal_inactive_pane_dim {
+            self.terminal.inactive_pane_dim = sanitize_dim(dim, self.terminal.inactive_pane_dim);
+        }
+        if let Some(enabled) = updates.terminal_dim_unfocused_window {
+            self.terminal.dim_unfocused_window = enabled;
+        }
+        if let Some(enabled) = updates.terminal_local_echo {
+            self.terminal.local_echo = enabled;
+        }
+        if let Some(enabled) = updates.terminal_persistent_sessions {
+            self.terminal.persistent_sessions = enabled;
+        }
+        if let Some(secs) = updates.terminal_notify_command_secs {
+            self.terminal.notify_command_secs = secs;
+        }
+        if let Some(minutes) = updates.terminal_idle_lock_minutes {
+            self.terminal.idle_lock_minutes = minutes;
+        }
+        if let Some(enabled) = updates.terminal_secure_keyboard_entry {
+            self.terminal.secure_keyboard_entry = enabled;
+        }
+        if let Some(columns) = updates.terminal_column_rulers.as_deref() {
+            self.terminal.column_rulers = sanitize_column_rulers(columns);
+        }
         if let Some(enabled) = updates.terminal_bold_is_bright {
             self.terminal.bold_is_bright = enabled;
         }
         if let Some(mode) = updates.terminal_bell_mode {
             self.terminal.bell_mode = mode;
         }
+        if let Some(enabled) = updates.terminal_bell_attention {
+            self.terminal.bell_attention = enabled;
+        }
+        if let Some(mode) = updates.terminal_timestamps {
+            self.terminal.timestamps = mode;
+        }
         if let Some(action) = updates.terminal_right_click_action {
             self.terminal.right_click_action = action;
         }
+        if let Some(behavior) = updates.terminal_exit_behavior {
+            self.terminal.exit_behavior = behavior;
+        }
+        if let Some(command) = updates.terminal_editor_command.as_deref() {
+            self.terminal.editor_command = sanitize_editor_command(command);
+        }
+        if let Some(command) = updates.terminal_assist_command.as_deref() {
+            self.terminal.assist_command = sanitize_assist_command(command);
+        }
+        if let Some(enabled) = updates.terminal_confirm_links {
+            self.terminal.confirm_links = enabled;
+        }
+        if let Some(schemes) = updates.terminal_link_schemes.as_deref() {
+            self.terminal.link_schemes = sanitize_link_schemes(schemes);
+        }
+        if let Some(answerback) = updates.terminal_answerback.as_deref() {
+            self.terminal.answerback = sanitize_answerback(answerback);
+        }
+        if let Some(enabled) = updates.terminal_tmux_passthrough {
+            self.terminal.tmux_passthrough = enabled;
+        }
+        if let Some(env) = updates.terminal_env.as_ref() {
+            self.terminal.env = sanitize_env(env);
+        }
+        if let Some(snippets) = updates.snippets.as_deref() {
+            self.snippets = sanitize_snippets(snippets);
+        }
         if let Some(scheme) = updates.color_scheme {
             self.theme.color_scheme = scheme;
         }
@@ -129,6 +283,21 @@ impl AppConfig {
         if let Some(radius) = updates.macos_blur_radius {
             self.theme.macos_blur_radius = radius.clamp(0, 100);
         }
+        if let Some(mode) = updates.renderer_present_mode {
+            self.renderer.present_mode = mode;
+        }
+        if let Some(fps) = updates.renderer_unfocused_fps {
+            self.renderer.unfocused_fps = sanitize_fps(fps, self.renderer.unfocused_fps);
+        }
+        if let Some(enabled) = updates.renderer_pause_when_minimized {
+            self.renderer.pause_when_minimized = enabled;
+        }
+        if let Some(backend) = updates.renderer_backend {
+            self.renderer.backend = backend;
+        }
+        if let Some(adapter) = updates.renderer_adapter {
+            self.renderer.adapter = adapter;
+        }
 
         for (id, binding) in updates.shortcuts {
             let sanitized = sanitize_shortcut(&binding, self.shortcuts.get(id));
@@ -142,6 +311,25 @@ mod tests {
     use super::*;
     use crate::config::DEFAULT_TERMINAL_SCROLLBACK;
 
+    #[test]
+    fn env_updates_drop_names_that_cannot_be_set() {
+        let mut config = AppConfig::default();
+        config.apply_updates(AppConfigUpdates {
+            terminal_env: Some(BTreeMap::from([
+                (" EDITOR ".to_string(), "vim".to_string()),
+                ("A=B".to_string(), "1".to_string()),
+                (String::new(), "orphan".to_string()),
+                ("NUL".to_string(), "a\0b".to_string()),
+            ])),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            config.terminal.env,
+            BTreeMap::from([("EDITOR".to_string(), "vim".to_string())])
+        );
+    }
+
     #[test]
     fn apply_updates_sanitizes_invalid_values() {
         let mut config = AppConfig::default();
@@ -210,6 +398,138 @@ mod tests {
         assert_eq!(config.terminal.font_size, default_size);
     }
 
+    #[test]
+    fn apply_updates_spacing_scales_cell_metrics() {
+        let mut config = AppConfig::default();
+        let (width, height) = (config.terminal.cell_width, config.terminal.cell_height);
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_line_height: Some(1.5),
+            terminal_letter_spacing: Some(1.25),
+            ..Default::default()
+        });
+        assert!((config.terminal.cell_height - height * 1.5).abs() < 0.01);
+        assert!((config.terminal.cell_width - width * 1.25).abs() < 0.01);
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_line_height: Some(10.0),
+            terminal_letter_spacing: Some(f32::NAN),
+            ..Default::default()
+        });
+        assert_eq!(config.terminal.line_height, 1.5);
+        assert_eq!(config.terminal.letter_spacing, 1.25);
+    }
+
+    #[test]
+    fn inactive_pane_dim_is_capped() {
+        let mut config = AppConfig::default();
+        config.apply_updates(AppConfigUpdates {
+            terminal_inactive_pane_dim: Some(0.95),
+            ..Default::default()
+        });
+        assert_eq!(config.terminal.inactive_pane_dim, 0.8);
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_inactive_pane_dim: Some(f32::NAN),
+            ..Default::default()
+        });
+        assert_eq!(config.terminal.inactive_pane_dim, 0.8);
+    }
+
+    #[test]
+    fn column_rulers_are_sorted_and_deduplicated() {
+        let mut config = AppConfig::default();
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_column_rulers: Some(vec![120, 0, 80, 120, 5_000]),
+            ..Default::default()
+        });
+        assert_eq!(config.terminal.column_rulers, vec![80, 120]);
+    }
+
+    #[test]
+    fn answerback_drops_control_characters_and_is_bounded() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.terminal.answerback, "");
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_answerback: Some("vt\x1b[2Jterm\r".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(config.terminal.answerback, "vt[2Jterm");
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_answerback: Some("x".repeat(500)),
+            ..Default::default()
+        });
+        assert_eq!(config.terminal.answerback.len(), 64);
+    }
+
+    #[test]
+    fn link_schemes_are_normalized_and_invalid_ones_dropped() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.terminal.link_schemes, ["http", "https", "mailto"]);
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_link_schemes: Some(
+                [" HTTPS:// ", "ssh:", "https", "", "1bad", "x-help"]
+                    .map(String::from)
+                    .to_vec(),
+            ),
+            ..Default::default()
+        });
+        assert_eq!(config.terminal.link_schemes, ["https", "ssh", "x-help"]);
+    }
+
+    #[test]
+    fn editor_command_is_trimmed_and_gets_a_path_placeholder() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.terminal.editor_command, None);
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_editor_command: Some(" code -g {path}:{line} ".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            config.terminal.editor_command.as_deref(),
+            Some("code -g {path}:{line}")
+        );
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_editor_command: Some("subl".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            config.terminal.editor_command.as_deref(),
+            Some("subl {path}")
+        );
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_editor_command: Some("   ".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(config.terminal.editor_command, None);
+    }
+
+    #[test]
+    fn large_paste_thresholds_accept_zero_and_reject_huge_values() {
+        let mut config = AppConfig::default();
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_large_paste_lines: Some(0),
+            terminal_large_paste_kib: Some(2_048),
+            ..Default::default()
+        });
+        assert_eq!(config.terminal.large_paste_lines, 0);
+        assert_eq!(config.terminal.large_paste_kib, 2_048);
+
+        config.apply_updates(AppConfigUpdates {
+            terminal_large_paste_kib: Some(usize::MAX),
+            ..Default::default()
+        });
+        assert_eq!(config.terminal.large_paste_kib, 2_048);
+    }
+
     #[test]
     fn scrollback_sanitize_clamps_to_valid_range() {
         let mut config = AppConfig::default();
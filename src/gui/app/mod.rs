@@ -1,17 +1,21 @@
 use crate::config::AppConfig;
 use crate::gui::settings::{
-    ProfileField, ProfileModalTab, SettingsCategory, SettingsDraft, SettingsField,
-    TerminalFontOption,
+    EnvEdit, ProfileField, ProfileModalTab, SettingsCategory, SettingsDraft, SettingsField,
+    SnippetEdit, TerminalFontOption,
 };
 use crate::gui::tab::{Profile, TerminalTab, discover_available_shells};
 use crate::session::OutputEvent;
+use crate::session::container::Container;
 use crate::session::history::SessionHistory;
+use crate::session::workspace::Workspaces;
+use crate::terminal::TerminalSize;
 use crate::terminal::font::discover_system_terminal_fonts;
 use iced::Animation;
 use iced::Size;
 use iced::futures::channel::mpsc;
 use iced::keyboard::{Key, Modifiers};
 use iced::widget::combo_box;
+use std::collections::HashMap;
 use std::sync::mpsc as std_mpsc;
 
 mod shortcuts;
@@ -28,26 +32,115 @@ pub enum Message {
     TabDragHover(usize),
     TabDragRelease,
     CloseTab(usize),
+    /// Brings back the most recently closed tab.
+    ReopenClosedTab,
+    /// Periodic check whether closed tabs' sessions should now be ended.
+    ExpireClosedTabs,
+    /// Close button on the banner of a pane whose session ended.
+    CloseExitedPane {
+        pane: u64,
+    },
+    /// Types startup commands whose shell never marked a prompt.
+    SendStartupCommands,
+    /// Periodic look at what each pane runs, for tabs titled by it.
+    RefreshCommandTitles,
     OpenShellPicker,
+    ContainersListed(Vec<Container>),
     CloseShellPicker,
     CreateTab(Profile),
+    /// Stars the profile with this display name as the one new windows
+    /// open with, or unstars it when it already is.
+    ToggleDefaultProfile(String),
     Settings(SettingsMessage),
     LaunchFromHistory(usize),
     DuplicateTab,
     Sftp(SftpMessage),
+    Onboarding(OnboardingMessage),
     SshPasswordPromptChanged(String),
     SshPasswordPromptToggleSave(bool),
     SshPasswordPromptSubmit,
     SshPasswordPromptCancel,
     ShowTabContextMenu(usize),
     CloseTabContextMenu,
-    TerminalRightClick(u64),
+    /// Lock or unlock input to the tab at this index.
+    ToggleTabLock(usize),
+    /// Step the tab at this index to its next preset color.
+    CycleTabColor(usize),
+    /// Step the tab at this index to its next icon.
+    CycleTabIcon(usize),
+    /// Show the working directory of this tab's focused pane in the file
+    /// manager.
+    OpenTabDirectory(usize),
+    /// Copy the working directory of this tab's focused pane.
+    CopyTabDirectory(usize),
+    /// Switch an SSH port forward of this pane on or off.
+    TogglePortForward {
+        pane: u64,
+        index: usize,
+    },
+    /// Open the prompt that names the current tabs as a workspace.
+    ShowWorkspacePrompt,
+    WorkspaceNameChanged(String),
+    SaveWorkspace,
+    CloseWorkspacePrompt,
+    OpenWorkspace(String),
+    /// Type the configured snippet at this index, asking for its
+    /// placeholders first.
+    UseSnippet(usize),
+    SnippetValueChanged(usize, String),
+    InsertSnippet,
+    CloseSnippetPrompt,
+    /// Send the selection, or else the last command's output, to the
+    /// configured Explain/Fix command.
+    Assist(crate::terminal::assist::AssistAction),
+    /// Run a plugin's command: the plugin's index and the command's id.
+    RunPluginCommand(usize, String),
+    /// A reply for the side panel to the request with this number.
+    SidePanelReply(u64, Result<String, String>),
+    CopySidePanel,
+    CloseSidePanel,
+    /// Right-click on a pane, with the link under the pointer if any.
+    TerminalRightClick {
+        pane: u64,
+        link: Option<crate::gui::render::LinkTarget>,
+    },
     CloseTerminalContextMenu,
     TerminalContextPaste,
     TerminalContextCopy,
+    TerminalContextCopyOutput,
+    /// Save the view, or the rows of the selection, as a PNG.
+    SaveScreenshot,
+    ScreenshotCaptured(crate::gui::render::CaptureResult),
+    TerminalContextRerun,
+    /// Run the focused pane's quick action at this index.
+    RunQuickAction(usize),
+    TerminalContextSelectAll,
+    TerminalContextClearBuffer,
+    TerminalContextSearchWeb,
+    TerminalContextOpenLink,
     OpenUrl(String),
+    ConfirmOpenLink,
+    CancelOpenLink,
+    CopyPendingLink,
+    /// Modifier-click on a file path in terminal output.
+    OpenPath {
+        pane: u64,
+        path: String,
+        line: Option<u32>,
+        column: Option<u32>,
+    },
+    /// The link under the cursor while the link modifier is held, if any.
+    LinkHovered(Option<String>),
+    /// Return the focused pane to the live view.
+    JumpToBottom,
+    /// Scroll the focused pane to a mark picked from the marks list.
+    ScrollToMark(u64),
+    CloseMarksList,
+    CloseCheatSheet,
     CursorMoved(iced::Point),
     PtySenderReady(mpsc::UnboundedSender<OutputEvent>),
+    /// Sessions the daemon kept alive, listed at startup.
+    DaemonSessionsListed(Vec<crate::session::daemon::SessionInfo>),
     PtyOutput(OutputEvent),
     PtyOutputBatch(Vec<OutputEvent>),
     KeyPressed {
@@ -59,10 +152,30 @@ pub enum Message {
 
     TabBarScroll(f32),
     TabBarScrolled(f32),
+    /// Opens or closes the list of all tabs shown when they overflow.
+    ToggleTabList,
+    TabListFilterChanged(String),
+    TabListSelect(usize),
+    /// Enter in the filter: goes to the best match.
+    TabListSubmit,
+    /// Periodic request to measure what the tabs' processes use.
+    SampleResources,
+    ResourcesSampled(Option<crate::session::resources::ProcessTable>),
+    /// Click on a tab's resource badge: lists the processes under it.
+    ShowProcessList(usize),
+    CloseProcessList,
     SelectionChanged {
         pane: u64,
         selection: Option<crate::terminal::Selection>,
     },
+    /// Shift-double-click: select the semantic unit at the cell, or `word`
+    /// when no rule matches there.
+    SemanticSelect {
+        pane: u64,
+        row: usize,
+        col: usize,
+        word: Option<crate::terminal::Selection>,
+    },
     TerminalMousePress {
         pane: u64,
         col: usize,
@@ -79,14 +192,25 @@ pub enum Message {
     TerminalSelectionAutoscroll {
         up: bool,
         col: usize,
+        /// How far past the edge the pointer is, in rows.
+        overshoot: f32,
     },
     TerminalSelectionAutoscrollStop,
     SelectionAutoscrollTick,
     PasteClipboard(String),
-    ConfirmMultilinePaste,
-    CancelMultilinePaste,
+    ConfirmPaste,
+    CancelPaste,
     ImeStateChanged(bool),
     ImeCommit(String),
+    /// A file from the OS file manager was dropped onto the window.
+    FileDropped(std::path::PathBuf),
+    /// Files are being dragged over the window.
+    FileHovered,
+    FilesHoveredLeft,
+    WindowFocused(bool),
+    DismissGpuFallback,
+    /// The window moved to a monitor with a different scale factor.
+    WindowRescaled(f32),
     ImePreedit(String, Option<std::ops::Range<usize>>),
     PaneScrollTo {
         pane: u64,
@@ -99,6 +223,10 @@ pub enum Message {
     ResizeDebounce,
     AnimationTick,
     CursorBlink,
+    /// Periodic check whether the idle lock is due.
+    IdleCheck,
+    /// Click on the lock screen.
+    Unlock,
     ApplyWindowStyle,
 
     #[cfg(target_os = "windows")]
@@ -114,21 +242,49 @@ pub enum Message {
 pub enum SettingsMessage {
     OpenTab,
     SelectCategory(SettingsCategory),
+    SearchChanged(String),
+    /// Opens a category scrolled to a search result, given as 0–1 of its height.
+    JumpToSetting(SettingsCategory, f32),
     InputChanged(SettingsField, String),
     InputCommitted(SettingsField, String),
     CommitDebounce,
     BlurToggled(bool),
     AnimationsToggled(bool),
     TabBarPositionSelected(crate::config::TabBarPosition),
+    StatusBarToggled(bool),
+    StatusSegmentToggled(crate::config::StatusSegment, bool),
+    HighContrastToggled(bool),
+    LargeTextToggled(bool),
     BracketedPasteToggled(bool),
+    AutocompleteToggled(bool),
     MultilinePasteConfirmToggled(bool),
+    SmoothScrollToggled(bool),
     CursorShapeSelected(crate::config::CursorShape),
     CursorBlinkToggled(bool),
+    CursorTrailToggled(bool),
+    HighlightCursorRowToggled(bool),
+    WrapIndicatorToggled(bool),
+    DimUnfocusedWindowToggled(bool),
+    PresentModeSelected(crate::config::PresentMode),
+    PauseWhenMinimizedToggled(bool),
+    GpuBackendSelected(crate::config::GpuBackend),
+    GpuAdapterSelected(crate::config::AdapterPreference),
+    LocalEchoToggled(bool),
+    TmuxPassthroughToggled(bool),
+    PersistentSessionsToggled(bool),
+    SecureKeyboardEntryToggled(bool),
+    ConfirmLinksToggled(bool),
     BoldIsBrightToggled(bool),
     BellModeSelected(crate::config::BellMode),
+    BellAttentionToggled(bool),
+    TimestampModeSelected(crate::config::TimestampMode),
     RightClickActionSelected(crate::config::RightClickAction),
+    ExitBehaviorSelected(crate::config::ExitBehavior),
     FontSelected(TerminalFontOption),
+    TextRenderingSelected(crate::config::TextRendering),
+    FontSynthesizeToggled(bool),
     ToggleShowAllFonts(bool),
+    ColorPicker(ColorPickerMessage),
 
     AddProfile,
     EditProfile(usize),
@@ -139,7 +295,14 @@ pub enum SettingsMessage {
     ProfileTemplateSelected(usize),
     ProfileModalFieldChanged(ProfileField, String),
     ProfileModalTabSelected(ProfileModalTab),
+    /// Edit the environment of the local profile being edited.
+    ProfileEnvEdited(EnvEdit),
+    /// Edit the environment set for every local shell.
+    GlobalEnvEdited(EnvEdit),
+    SnippetEdited(SnippetEdit),
     TestSshConnection,
+    CheckShellIntegration,
+    InstallShellIntegration(crate::session::integration::IntegrationShell),
     SshConnectionTestFinished(Result<(), String>),
     CloseProfileModal,
     SaveProfileModal,
@@ -192,6 +355,62 @@ pub enum SftpMessage {
     },
 }
 
+#[derive(Debug, Clone)]
+pub enum ColorPickerMessage {
+    Open(SettingsField),
+    /// The cursor moved over the hue/saturation area, relative to it.
+    AreaMoved(iced::Point),
+    AreaPressed,
+    AreaReleased,
+    ValueChanged(f32),
+    HexChanged(String),
+    Done,
+    Cancel,
+}
+
+#[derive(Debug, Clone)]
+pub enum OnboardingMessage {
+    /// The theme and font size apply right away, so they show behind the
+    /// wizard while it is open.
+    PickTheme(String),
+    FontSize(f32),
+    PickProfile(Option<String>),
+    PickShortcuts(crate::config::ShortcutPreset),
+    Back,
+    /// The next step, or writing the config after the last one.
+    Next,
+    /// Keeps the defaults and writes them out.
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnboardingStep {
+    #[default]
+    Theme,
+    Font,
+    Shell,
+    Shortcuts,
+}
+
+impl OnboardingStep {
+    pub const ALL: [Self; 4] = [Self::Theme, Self::Font, Self::Shell, Self::Shortcuts];
+
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|&step| step == self).unwrap_or(0)
+    }
+}
+
+/// The first-run wizard: the step on screen and the picks that are only
+/// applied once it is finished.
+#[derive(Debug, Clone, Default)]
+pub struct Onboarding {
+    pub step: OnboardingStep,
+    /// Display name of the profile new windows open with; `None` is the
+    /// user's default shell.
+    pub default_profile: Option<String>,
+    pub shortcuts: crate::config::ShortcutPreset,
+}
+
 pub struct App {
     pub(super) tabs: Vec<TerminalTab>,
     pub(super) active_tab: usize,
@@ -201,7 +420,9 @@ pub struct App {
     pub(super) terminal_area: Size,
     pub(super) settings_open: bool,
     pub(super) settings_category: SettingsCategory,
+    pub(super) settings_search: String,
     pub(super) settings_draft: SettingsDraft,
+    pub(super) color_picker: Option<crate::gui::settings::color_picker::ColorPicker>,
     pub(super) font_combo_state: combo_box::State<TerminalFontOption>,
     pub(super) show_all_fonts: bool,
     pub(super) all_font_options: Vec<TerminalFontOption>,
@@ -211,15 +432,16 @@ pub struct App {
     pub(super) initial_shell_opened: bool,
     pub(super) next_tab_id: u64,
     pub(super) tab_bar_scroll_x: f32,
+    /// Filter typed into the list of all tabs, while it is open.
+    pub(super) tab_list: Option<String>,
     pub(super) scroll_follow_bottom: bool,
     pub(super) wheel_last_event: Option<std::time::Instant>,
     pub(super) wheel_suppressed: bool,
     pub(super) dragging_tab: Option<usize>,
     pub(super) drag_target: Option<usize>,
     pub(super) scroll_accumulator: f32,
-    // Some(true) = autoscroll up, Some(false) = down, None = off.
-    pub(super) selection_autoscroll: Option<bool>,
-    pub(super) selection_autoscroll_col: usize,
+    /// Scrolling while a selection is dragged past the top or bottom edge.
+    pub(super) selection_autoscroll: Option<SelectionAutoscroll>,
     pub(super) resize_debounce_pending: bool,
     pub(super) resize_debounce_seq: u64,
     pub(super) resize_debounce_spawned_seq: u64,
@@ -232,11 +454,47 @@ pub struct App {
     pub(super) ime_active: bool,
     pub(super) ime_preedit: Option<(String, Option<std::ops::Range<usize>>)>,
     pub(super) session_history: SessionHistory,
+    pub(super) workspaces: Workspaces,
+    /// Name being typed for a workspace about to be saved.
+    pub(super) workspace_prompt: Option<String>,
+    pub(super) snippet_prompt: Option<SnippetPrompt>,
+    /// Side panel with the last Explain, Fix or plugin reply.
+    pub(super) side_panel: Option<SidePanel>,
+    pub(super) side_panel_seq: u64,
+    /// Grid size and unhandled sequence count over the focused pane.
+    pub(super) debug_overlay: bool,
+    /// Masks secrets matching `terminal.redact_patterns` in every pane.
+    pub(super) privacy_mode: bool,
+    /// Screenshot waiting for the renderer to read it back.
+    pub(super) capture: Option<crate::gui::render::CaptureRequest>,
+    pub(super) capture_seq: u64,
+    /// Last key press, click, scroll or pointer move, for the idle lock.
+    pub(super) last_input: std::time::Instant,
+    /// Whether the idle lock is hiding the window's contents.
+    pub(super) screen_locked: bool,
+    pub(super) plugins: crate::plugin::PluginHost,
+    /// The init script's engine, kept for the keys it bound.
+    pub(super) scripts: Option<crate::script::Scripts>,
+    /// What the init script queued, carried out once tabs can open.
+    pub(super) script_startup: Vec<crate::script::ScriptAction>,
+    /// Commands from the shell's history file, read when autocomplete is on.
+    pub(super) shell_history: Vec<String>,
     pub(super) window_style_applied: bool,
     pub(super) tab_context_menu: Option<usize>,
     /// Whether the terminal right-click context menu is currently shown.
     pub(super) terminal_context_menu: bool,
+    /// Link under the pointer at the last right-click, and its pane.
+    pub(super) terminal_context_link: Option<(u64, crate::gui::render::LinkTarget)>,
+    /// Whether the focused pane's marks are listed in a popup.
+    pub(super) marks_list: bool,
+    /// The overlay listing every shortcut as currently bound.
+    pub(super) cheat_sheet: bool,
+    /// Row of the open context menu or list picked with the arrow keys.
+    pub(super) menu_cursor: Option<usize>,
     pub(super) cursor_position: iced::Point,
+    /// Target of the link under the cursor, shown as a hint while the link
+    /// modifier is held.
+    pub(super) hovered_link: Option<String>,
     #[cfg(target_os = "macos")]
     pub(super) show_restart_confirm: bool,
     #[cfg(target_os = "macos")]
@@ -247,19 +505,109 @@ pub struct App {
     /// Profiles parsed from `~/.ssh/config`, merged into shell/SSH lists at
     /// runtime so users do not have to re-enter them in Settings.
     pub(super) ssh_config_profiles: Vec<crate::config::SshProfile>,
+    /// Running containers, refreshed each time the shell picker opens.
+    pub(super) containers: Vec<Container>,
     /// In-flight password prompt deferred from an SSH tab creation.
     pub(super) password_prompt: Option<PasswordPromptState>,
-    /// Text waiting for multiline-paste confirmation.
-    pub(super) pending_paste: Option<String>,
+    /// The last process table read, to measure CPU use against.
+    pub(super) process_table: Option<crate::session::resources::ProcessTable>,
+    /// What each tab's processes used over the last sample, by tab id.
+    pub(super) tab_resources: HashMap<u64, crate::session::resources::TreeUsage>,
+    /// Id of the tab whose processes are listed.
+    pub(super) process_list: Option<u64>,
+    /// Tabs the user closed, most recent last, for reopening.
+    pub(super) closed_tabs: Vec<ClosedTab>,
+    /// Clipboard text waiting for paste confirmation.
+    pub(super) pending_paste: Option<PendingPaste>,
+    /// A clicked link waiting for confirmation, or shown as blocked.
+    pub(super) pending_link: Option<String>,
     /// Current on/off phase of the blinking cursor.
     pub(super) cursor_blink_on: bool,
     /// Start time of an active visual bell flash, if any.
     pub(super) bell_flash_start: Option<std::time::Instant>,
+    /// Whether the window has keyboard focus, for dimming in the background.
+    pub(super) window_focused: bool,
+    /// Whether Secure Keyboard Entry is turned on right now; it is held
+    /// only while the window has focus.
+    pub(super) secure_input: bool,
+    /// Set while the window is minimized, which shows up as a resize to
+    /// nothing.
+    pub(super) window_minimized: bool,
+    /// The graphics error this process was relaunched on the fallback
+    /// backend for, until the notice is dismissed.
+    pub(super) gpu_fallback: Option<String>,
+    /// Physical pixels per logical pixel on the monitor the window is on.
+    pub(super) scale_factor: f32,
+    /// This process runs with administrator rights, so its local shells do.
+    pub(super) elevated: bool,
+    /// Opened as the first tab instead of the default shell.
+    pub(super) startup_profile: Option<Profile>,
+    /// Shown on the first launch, before there is a config file.
+    pub(super) onboarding: Option<Onboarding>,
 }
 
 /// Duration of the visual bell flash overlay.
 pub(super) const BELL_FLASH_DURATION: std::time::Duration = std::time::Duration::from_millis(150);
 
+/// How long a closed tab's sessions keep running so reopening it picks up
+/// where it left off.
+pub(super) const CLOSED_TAB_GRACE: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// How often the tabs' CPU and memory use is measured.
+pub(super) const RESOURCE_SAMPLE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// How often tabs whose shell sets no title look up the program it runs.
+pub(super) const COMMAND_TITLE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How many closed tabs can be reopened.
+pub(super) const CLOSED_TAB_HISTORY: usize = 10;
+
+/// Shortest time between two oversized-grid warnings, so resizing a huge
+/// window doesn't repeat it on every frame but a later one is still reported.
+const GRID_CLAMP_WARN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// When the oversized-grid warning was last printed.
+static GRID_CLAMP_WARNED_AT: std::sync::Mutex<Option<std::time::Instant>> =
+    std::sync::Mutex::new(None);
+
+/// Lines scrolled per autoscroll tick for each row the pointer is past the
+/// edge, so dragging further out scrolls faster.
+pub(super) const AUTOSCROLL_LINES_PER_ROW: f32 = 0.5;
+
+/// Fastest autoscroll, in lines per tick.
+pub(super) const AUTOSCROLL_MAX_LINES: f32 = 12.0;
+
+/// A selection drag held past the top or bottom of its pane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct SelectionAutoscroll {
+    pub up: bool,
+    pub col: usize,
+    /// Lines to scroll per tick, from how far past the edge the pointer is.
+    pub speed: f32,
+    /// The part of a line not yet scrolled, carried to the next tick.
+    pub carry: f32,
+}
+
+impl SelectionAutoscroll {
+    /// `overshoot` is how far past the edge the pointer is, in rows.
+    pub(super) fn speed_for(overshoot: f32) -> f32 {
+        (0.25 + overshoot.max(0.0) * AUTOSCROLL_LINES_PER_ROW).min(AUTOSCROLL_MAX_LINES)
+    }
+}
+
+/// A tab the user closed, kept so it can be reopened.
+pub(super) struct ClosedTab {
+    /// The tab itself, its sessions still running, until the grace period
+    /// ends or they exit.
+    pub tab: Option<TerminalTab>,
+    /// Position the tab had in the tab bar.
+    pub index: usize,
+    /// What the focused pane ran, and where, to start it afresh.
+    pub profile: Profile,
+    pub cwd: Option<std::path::PathBuf>,
+    pub closed_at: std::time::Instant,
+}
+
 #[derive(Debug, Clone)]
 pub struct PasswordPromptState {
     pub profile: crate::config::SshProfile,
@@ -268,6 +616,38 @@ pub struct PasswordPromptState {
     pub error: Option<String>,
 }
 
+/// A paste held back until the user confirms it.
+#[derive(Debug, Clone)]
+pub struct PendingPaste {
+    pub text: String,
+    pub size: crate::terminal::paste::PasteSize,
+    /// Set when the paste crossed a size threshold rather than only being
+    /// multi-line; the dialog then shows the first few lines.
+    pub preview: Option<String>,
+    /// The pane is at a password prompt, where line breaks in the paste
+    /// would submit part of it and type the rest as commands.
+    pub into_secret: bool,
+}
+
+/// A snippet waiting for its placeholder values.
+#[derive(Debug, Clone)]
+pub struct SnippetPrompt {
+    pub snippet: crate::config::Snippet,
+    pub placeholders: Vec<crate::config::Placeholder>,
+    /// One per placeholder, starting from its default.
+    pub values: Vec<String>,
+}
+
+/// A request whose reply is shown beside the terminal, and the reply once
+/// it is back.
+#[derive(Debug, Clone)]
+pub struct SidePanel {
+    pub title: String,
+    /// Tells this request's reply from the replies to earlier ones.
+    pub seq: u64,
+    pub reply: Option<Result<String, String>>,
+}
+
 fn spawn_config_save_worker() -> std_mpsc::Sender<AppConfig> {
     let (tx, rx) = std_mpsc::channel::<AppConfig>();
     std::thread::spawn(move || {
@@ -285,7 +665,7 @@ fn spawn_config_save_worker() -> std_mpsc::Sender<AppConfig> {
 
 impl App {
     pub fn new(config: AppConfig) -> Self {
-        let palette = crate::gui::theme::Palette::from_theme(&config.theme);
+        let palette = crate::gui::theme::Palette::from_config(&config);
         let all_font_options = build_all_font_options(config.terminal.font_selection.as_deref());
         let show_all_fonts = false;
         let font_combo_state = build_font_combo_state(
@@ -293,6 +673,15 @@ impl App {
             show_all_fonts,
             config.terminal.font_selection.as_deref(),
         );
+        let (scripts, script_startup) = match crate::script::Scripts::load() {
+            Some((scripts, actions)) => (Some(scripts), actions),
+            None => (None, Vec::new()),
+        };
+        let shell_history = if config.terminal.autocomplete {
+            crate::terminal::history::load()
+        } else {
+            Vec::new()
+        };
         Self {
             tabs: vec![],
             active_tab: 0,
@@ -302,7 +691,9 @@ impl App {
             window_size: Size::new(config.ui.window_width, config.ui.window_height),
             settings_open: false,
             settings_category: SettingsCategory::Appearance,
+            settings_search: String::new(),
             settings_draft: SettingsDraft::from_config(&config),
+            color_picker: None,
             font_combo_state,
             show_all_fonts,
             all_font_options,
@@ -312,6 +703,7 @@ impl App {
             initial_shell_opened: false,
             next_tab_id: 1,
             tab_bar_scroll_x: 0.0,
+            tab_list: None,
             scroll_follow_bottom: true,
             wheel_last_event: None,
             wheel_suppressed: false,
@@ -319,7 +711,6 @@ impl App {
             drag_target: None,
             scroll_accumulator: 0.0,
             selection_autoscroll: None,
-            selection_autoscroll_col: 0,
             resize_debounce_pending: false,
             resize_debounce_seq: 0,
             resize_debounce_spawned_seq: 0,
@@ -327,10 +718,29 @@ impl App {
             settings_debounce_seq: 0,
             settings_debounce_spawned_seq: 0,
             session_history: SessionHistory::load(),
+            workspaces: Workspaces::load(),
+            workspace_prompt: None,
+            snippet_prompt: None,
+            side_panel: None,
+            side_panel_seq: 0,
+            debug_overlay: false,
+            privacy_mode: false,
+            capture: None,
+            capture_seq: 0,
+            last_input: std::time::Instant::now(),
+            screen_locked: false,
+            plugins: crate::plugin::PluginHost::load(),
+            scripts,
+            script_startup,
             palette,
             tab_context_menu: None,
             terminal_context_menu: false,
+            terminal_context_link: None,
+            marks_list: false,
+            cheat_sheet: false,
+            menu_cursor: None,
             cursor_position: iced::Point::ORIGIN,
+            hovered_link: None,
             ime_active: false,
             ime_preedit: None,
             modal_anim: Animation::new(false)
@@ -346,21 +756,89 @@ impl App {
             pending_save_on_restart: false,
             config_save_tx: spawn_config_save_worker(),
             ssh_config_profiles: crate::ssh::user_config::load(),
+            containers: Vec::new(),
             password_prompt: None,
+            process_table: None,
+            tab_resources: HashMap::new(),
+            process_list: None,
+            closed_tabs: Vec::new(),
             pending_paste: None,
+            pending_link: None,
             cursor_blink_on: true,
             bell_flash_start: None,
+            window_focused: true,
+            secure_input: false,
+            window_minimized: false,
+            gpu_fallback: crate::gui::render::surface::fallback_reason(),
+            scale_factor: 1.0,
+            elevated: crate::platform::is_elevated(),
+            startup_profile: None,
+            onboarding: None,
+            shell_history,
+        }
+    }
+
+    /// Opens `profile` as the first tab instead of the default shell.
+    pub fn with_startup_profile(mut self, profile: Option<Profile>) -> Self {
+        self.startup_profile = profile;
+        self
+    }
+
+    /// Opens the onboarding wizard over the first window.
+    pub fn with_onboarding(mut self, first_run: bool) -> Self {
+        self.onboarding = first_run.then(Onboarding::default);
+        self
+    }
+
+    /// Extra scale applied to the whole window on top of the system's.
+    pub fn ui_scale(&self) -> f32 {
+        if self.config.ui.large_text {
+            crate::config::LARGE_TEXT_SCALE
+        } else {
+            1.0
+        }
+    }
+
+    pub(super) fn cell_size(&self) -> [f32; 2] {
+        self.config
+            .terminal
+            .cell_size_at(self.scale_factor * self.ui_scale())
+    }
+
+    /// Cells left of each pane's grid for the timestamp gutter.
+    pub(super) fn gutter_columns(&self) -> usize {
+        match self.config.terminal.timestamps {
+            crate::config::TimestampMode::Off => 0,
+            _ => crate::terminal::timestamps::GUTTER_COLUMNS,
         }
     }
 
     pub(super) fn grid_for_rect(&self, rect: iced::Rectangle) -> (usize, usize) {
         let pad_x = self.config.terminal.padding_x * 2.0;
         let pad_y = self.config.terminal.padding_y * 2.0;
-        let cell_width = self.config.terminal.cell_width.max(1.0);
-        let cell_height = self.config.terminal.cell_height.max(1.0);
-        let cols = ((rect.width - pad_x).max(1.0) / cell_width) as usize;
+        let [cell_width, cell_height] = self.cell_size();
+        // The wrap indicator takes a column right of the grid.
+        let reserved = self.gutter_columns() + usize::from(self.config.terminal.wrap_indicator);
+        let gutter = reserved as f32 * cell_width;
+        // `as` maps NaN to zero and infinity to `usize::MAX`; both are
+        // clamped below.
+        let cols = ((rect.width - pad_x - gutter).max(1.0) / cell_width) as usize;
         let rows = ((rect.height - pad_y).max(1.0) / cell_height) as usize;
-        (cols.max(10), rows.max(5))
+        let size = TerminalSize::new(cols.max(10), rows.max(5));
+        let clamped = size.clamped();
+        if clamped != size {
+            let mut warned_at = GRID_CLAMP_WARNED_AT
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if warned_at.is_none_or(|at| at.elapsed() >= GRID_CLAMP_WARN_INTERVAL) {
+                *warned_at = Some(std::time::Instant::now());
+                eprintln!(
+                    "Terminal grid of {}x{} cells is too large; using {}x{} instead",
+                    size.columns, size.lines, clamped.columns, clamped.lines
+                );
+            }
+        }
+        (clamped.columns, clamped.lines)
     }
 
     pub(super) fn terminal_area_rect(&self) -> iced::Rectangle {
@@ -638,6 +1116,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_huge_area_with_tiny_cells_gets_a_bounded_grid() {
+        let mut app = App::new(AppConfig::default());
+        app.config.terminal.cell_width = 1.0;
+        app.config.terminal.cell_height = 1.0;
+        let (cols, rows) = app.grid_for_rect(iced::Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: 20_000.0,
+            height: 20_000.0,
+        });
+        assert_eq!(
+            (cols, rows),
+            (TerminalSize::MAX_COLUMNS, TerminalSize::MAX_LINES)
+        );
+
+        let degenerate = iced::Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: f32::NAN,
+            height: 0.0,
+        };
+        assert_eq!(app.grid_for_rect(degenerate), (10, 5));
+    }
+
     fn app_with_pty() -> App {
         let mut app = App::new(AppConfig::default());
         let (tx, _rx) = mpsc::unbounded();
@@ -645,6 +1148,87 @@ mod tests {
         app
     }
 
+    #[test]
+    fn large_paste_waits_for_confirmation_with_a_preview() {
+        let mut app = App::new(AppConfig::default());
+        let text: String = (0..5_000).map(|i| format!("echo {i}\n")).collect();
+        let _ = app.update(Message::PasteClipboard(text.clone()));
+
+        let pending = app.pending_paste.as_ref().expect("paste was not held");
+        assert_eq!(pending.text, text);
+        assert_eq!(pending.size.lines, 5_000);
+        assert!(pending.preview.as_deref().unwrap().starts_with("echo 0"));
+
+        let _ = app.update(Message::CancelPaste);
+        assert!(app.pending_paste.is_none());
+    }
+
+    #[test]
+    fn short_paste_is_not_held() {
+        let mut app = App::new(AppConfig::default());
+        let _ = app.update(Message::PasteClipboard("echo hi\nls\n".into()));
+        assert!(app.pending_paste.is_none());
+
+        app.config.terminal.multiline_paste_confirm = true;
+        let _ = app.update(Message::PasteClipboard("echo hi\nls\n".into()));
+        let pending = app
+            .pending_paste
+            .as_ref()
+            .expect("multi-line paste was not held");
+        assert!(pending.preview.is_none());
+    }
+
+    #[test]
+    fn a_command_ending_in_a_newline_is_held_for_confirmation() {
+        let mut app = App::new(AppConfig::default());
+        app.config.terminal.multiline_paste_confirm = true;
+        let _ = app.update(Message::PasteClipboard("cmd\n".into()));
+        let pending = app
+            .pending_paste
+            .as_ref()
+            .expect("a paste that runs itself was not held");
+        assert_eq!(pending.text, "cmd\n");
+        assert_eq!(pending.size.lines, 1);
+
+        app.pending_paste = None;
+        let _ = app.update(Message::PasteClipboard("cmd".into()));
+        assert!(app.pending_paste.is_none());
+    }
+
+    #[test]
+    fn multi_line_paste_into_a_password_prompt_is_held() {
+        let mut app = app_with_pty();
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        app.tabs[0]
+            .focused_mut()
+            .feed_bytes(b"\r\x1b[2J[sudo] password for sam: ");
+        assert!(app.tabs[0].focused().secret_input());
+
+        let _ = app.update(Message::PasteClipboard("hunter2".into()));
+        assert!(app.pending_paste.is_none(), "one line is a normal paste");
+
+        let _ = app.update(Message::PasteClipboard("hunter2\nrm -rf build\n".into()));
+        let pending = app.pending_paste.as_ref().expect("paste was not held");
+        assert!(pending.into_secret);
+    }
+
+    #[test]
+    fn blocked_link_schemes_are_held_even_without_confirmation() {
+        let mut app = App::new(AppConfig::default());
+        app.config.terminal.confirm_links = false;
+
+        let _ = app.update(Message::OpenUrl("file:///etc/passwd".into()));
+        assert_eq!(app.pending_link.as_deref(), Some("file:///etc/passwd"));
+
+        let _ = app.update(Message::KeyPressed {
+            key: Key::Named(iced::keyboard::key::Named::Escape),
+            physical_key: iced::keyboard::key::Physical::Code(iced::keyboard::key::Code::Escape),
+            modifiers: Modifiers::empty(),
+            text: None,
+        });
+        assert!(app.pending_link.is_none());
+    }
+
     #[test]
     fn a_split_shortcut_actually_creates_a_pane() {
         let mut app = app_with_pty();
@@ -672,6 +1256,30 @@ mod tests {
         assert_eq!(app.tabs[0].layout.leaves().len(), 2);
     }
 
+    #[test]
+    fn a_closed_tab_comes_back_live_then_afresh_after_the_grace_period() {
+        let mut app = app_with_pty();
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        let first = app.tabs[0].id;
+
+        let _ = app.update(Message::CloseTab(0));
+        assert_eq!(app.tabs.len(), 1);
+        let _ = app.update(Message::ReopenClosedTab);
+        assert_eq!(app.tabs.len(), 2);
+        assert_eq!(app.tabs[0].id, first, "the same tab was not put back");
+        assert_eq!(app.active_tab, 0);
+
+        let _ = app.update(Message::CloseTab(0));
+        app.closed_tabs[0].closed_at -= CLOSED_TAB_GRACE;
+        let _ = app.update(Message::ExpireClosedTabs);
+        assert!(app.closed_tabs[0].tab.is_none());
+        let _ = app.update(Message::ReopenClosedTab);
+        assert_eq!(app.tabs.len(), 2);
+        assert_ne!(app.tabs[0].id, first, "an expired tab should start afresh");
+        assert!(app.closed_tabs.is_empty());
+    }
+
     #[test]
     fn split_shortcut_fires_under_korean_ime() {
         let mut app = app_with_pty();
@@ -714,13 +1322,166 @@ mod tests {
         });
         assert_eq!(app.tabs[0].focused, first, "click did not move focus");
 
-        let _ = app.update(Message::TerminalRightClick(second));
+        let _ = app.update(Message::TerminalRightClick {
+            pane: second,
+            link: None,
+        });
         assert_eq!(
             app.tabs[0].focused, second,
             "right click did not move focus"
         );
     }
 
+    #[test]
+    fn context_menu_selects_all_and_opens_the_link_under_the_pointer() {
+        let mut config = AppConfig::default();
+        config.terminal.right_click_action = crate::config::RightClickAction::Menu;
+        config.terminal.confirm_links = true;
+        let mut app = App::new(config);
+        let (tx, _rx) = mpsc::unbounded();
+        app.pty_sender = Some(tx);
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        let pane = app.tabs[0].focused;
+        app.tabs[0]
+            .focused_mut()
+            .feed_bytes(b"\r\x1b[2Jfirst\r\nsecond");
+
+        let url = "https://example.com".to_string();
+        let _ = app.update(Message::TerminalRightClick {
+            pane,
+            link: Some(crate::gui::render::LinkTarget::Url(url.clone())),
+        });
+        assert!(app.terminal_context_menu);
+
+        let _ = app.update(Message::TerminalContextSelectAll);
+        let selected = app.tabs[0].focused().selected_text().unwrap_or_default();
+        assert!(selected.contains("first\nsecond"), "{selected:?}");
+
+        let _ = app.update(Message::TerminalRightClick {
+            pane,
+            link: Some(crate::gui::render::LinkTarget::Url(url.clone())),
+        });
+        let _ = app.update(Message::TerminalContextOpenLink);
+        assert!(!app.terminal_context_menu);
+        assert_eq!(app.pending_link, Some(url), "link did not ask to open");
+    }
+
+    #[test]
+    fn context_menu_rows_are_picked_with_the_arrow_keys() {
+        let mut config = AppConfig::default();
+        config.terminal.right_click_action = crate::config::RightClickAction::Menu;
+        let mut app = App::new(config);
+        let (tx, _rx) = mpsc::unbounded();
+        app.pty_sender = Some(tx);
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        let pane = app.tabs[0].focused;
+        app.tabs[0]
+            .focused_mut()
+            .feed_bytes(b"\r\x1b[2Jfirst\r\nsecond");
+        let press = |app: &mut App, named: iced::keyboard::key::Named| {
+            let _ = app.update(Message::KeyPressed {
+                key: Key::Named(named),
+                physical_key: iced::keyboard::key::Physical::Unidentified(
+                    iced::keyboard::key::NativeCode::Unidentified,
+                ),
+                modifiers: Modifiers::default(),
+                text: None,
+            });
+        };
+
+        let _ = app.update(Message::TerminalRightClick { pane, link: None });
+        press(&mut app, iced::keyboard::key::Named::Escape);
+        assert!(!app.terminal_context_menu, "Escape did not close the menu");
+
+        let _ = app.update(Message::TerminalRightClick { pane, link: None });
+        let row = app
+            .terminal_context_menu_items()
+            .iter()
+            .position(|item| matches!(item.message, Message::TerminalContextSelectAll))
+            .unwrap();
+        for _ in 0..=row {
+            press(&mut app, iced::keyboard::key::Named::ArrowDown);
+        }
+        assert_eq!(app.menu_cursor, Some(row));
+        press(&mut app, iced::keyboard::key::Named::Enter);
+        assert!(!app.terminal_context_menu);
+        let selected = app.tabs[0].focused().selected_text().unwrap_or_default();
+        assert!(selected.contains("first\nsecond"), "{selected:?}");
+    }
+
+    #[test]
+    fn copy_mode_selects_lines_from_the_keyboard() {
+        let mut app = app_with_pty();
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        app.tabs[0]
+            .focused_mut()
+            .feed_bytes(b"\r\x1b[2Jfirst\r\nsecond");
+
+        let modifiers = if cfg!(target_os = "macos") {
+            Modifiers::LOGO | Modifiers::SHIFT
+        } else {
+            Modifiers::CTRL | Modifiers::SHIFT
+        };
+        let _ = app.update(Message::KeyPressed {
+            key: Key::Named(iced::keyboard::key::Named::Space),
+            physical_key: iced::keyboard::key::Physical::Code(iced::keyboard::key::Code::Space),
+            modifiers,
+            text: None,
+        });
+        assert!(app.tabs[0].focused().in_copy_mode());
+
+        use iced::keyboard::key::Code;
+        for (typed, code) in [("V", Code::KeyV), ("k", Code::KeyK)] {
+            let _ = app.update(Message::KeyPressed {
+                key: Key::Character(typed.to_lowercase().into()),
+                physical_key: iced::keyboard::key::Physical::Code(code),
+                modifiers: Modifiers::default(),
+                text: Some(typed.to_string()),
+            });
+        }
+        let selected = app.tabs[0].focused().selected_text().unwrap_or_default();
+        assert_eq!(selected, "first\nsecond");
+
+        let _ = app.update(Message::KeyPressed {
+            key: Key::Character("y".into()),
+            physical_key: iced::keyboard::key::Physical::Code(iced::keyboard::key::Code::KeyY),
+            modifiers: Modifiers::default(),
+            text: Some("y".to_string()),
+        });
+        let pane = app.tabs[0].focused();
+        assert!(!pane.in_copy_mode());
+        assert!(pane.selection.is_none());
+    }
+
+    #[test]
+    fn reopened_workspace_restores_splits_and_tab_colors() {
+        let mut app = app_with_pty();
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        let _ = app.split_focused(crate::gui::pane::Axis::Horizontal);
+        app.tabs[0].color = Some(crate::gui::tab::TAB_COLORS[2]);
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+
+        let workspace = app.capture_workspace("backend");
+        assert_eq!(workspace.tabs.len(), 2);
+        assert_eq!(workspace.active, 1);
+
+        app.open_workspace(&workspace);
+        assert_eq!(app.tabs.len(), 4);
+        assert_eq!(app.active_tab, 3);
+        let restored = &app.tabs[2];
+        assert_eq!(restored.layout.leaves().len(), 2);
+        assert_eq!(restored.panes.len(), 2);
+        assert_eq!(restored.color, Some(crate::gui::tab::TAB_COLORS[2]));
+        assert!(
+            restored
+                .layout
+                .leaves()
+                .iter()
+                .all(|id| !app.tabs[0].layout.leaves().contains(id)),
+            "restored panes reused ids"
+        );
+    }
+
     #[test]
     fn focus_shortcut_moves_between_panes() {
         let mut app = app_with_pty();
@@ -779,6 +1540,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn zoomed_pane_fills_the_tab_until_unzoomed() {
+        let mut app = app_with_pty();
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        app.terminal_area = Size::new(1000.0, 600.0);
+        let _ = app.split_focused(crate::gui::pane::Axis::Vertical);
+        let focused = app.tabs[0].focused;
+        let split_size = app.tabs[0].focused().size();
+
+        let (modifiers, code) = if cfg!(target_os = "macos") {
+            (
+                Modifiers::LOGO | Modifiers::SHIFT,
+                iced::keyboard::key::Code::Enter,
+            )
+        } else {
+            (
+                Modifiers::CTRL | Modifiers::SHIFT,
+                iced::keyboard::key::Code::KeyZ,
+            )
+        };
+        let zoom = |app: &mut App| {
+            let _ = app.update(Message::KeyPressed {
+                key: Key::Character("z".into()),
+                physical_key: iced::keyboard::key::Physical::Code(code),
+                modifiers,
+                text: None,
+            });
+        };
+
+        zoom(&mut app);
+        assert!(app.tabs[0].zoomed());
+        let regions = app.tabs[0].regions(app.terminal_area_rect());
+        assert_eq!(regions, [(focused, app.terminal_area_rect())]);
+        assert!(app.tabs[0].focused().size().columns > split_size.columns);
+        assert_eq!(app.tabs[0].layout.leaves().len(), 2);
+
+        zoom(&mut app);
+        assert!(!app.tabs[0].zoomed());
+        assert_eq!(app.tabs[0].focused().size(), split_size);
+    }
+
+    #[test]
+    fn dragging_further_past_the_edge_autoscrolls_faster() {
+        let scrolled = |overshoot: f32| {
+            let mut app = app_with_pty();
+            let _ = app.update(Message::CreateTab(Profile::default_shell()));
+            let pane = app.tabs[0].focused_mut();
+            for _ in 0..200 {
+                pane.feed_bytes(b"line\r\n");
+            }
+            let point = crate::terminal::SelectionPoint { row: 3, col: 0 };
+            pane.selection = Some(crate::terminal::Selection {
+                start: point,
+                end: point,
+                anchor_offset: 0,
+            });
+            let _ = app.update(Message::TerminalSelectionAutoscroll {
+                up: true,
+                col: 0,
+                overshoot,
+            });
+            for _ in 0..4 {
+                let _ = app.update(Message::SelectionAutoscrollTick);
+            }
+            app.tabs[0].focused().scroll_position().0
+        };
+
+        let near = scrolled(0.5);
+        assert!(near > 0);
+        assert!(scrolled(10.0) > near);
+    }
+
     #[test]
     fn each_pane_scrolls_on_its_own() {
         let mut app = app_with_pty();
@@ -827,6 +1660,7 @@ mod tests {
 
         let _ = app.update(Message::PtyOutput(crate::session::OutputEvent::Closed {
             tab_id: dying,
+            exit_code: Some(0),
         }));
 
         assert_eq!(app.tabs[0].panes.len(), 1);
@@ -838,6 +1672,141 @@ mod tests {
         assert_eq!(after.lines, full.lines);
     }
 
+    #[test]
+    fn a_duplicate_opens_beside_its_source_with_the_same_environment() {
+        let mut app = app_with_pty();
+        app.config.terminal.env =
+            std::collections::BTreeMap::from([("STAGE".to_string(), "dev".to_string())]);
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        app.config.terminal.env.clear();
+
+        app.active_tab = 0;
+        let _ = app.update(Message::DuplicateTab);
+        assert_eq!(app.tabs.len(), 3);
+        assert_eq!(app.active_tab, 1);
+        assert_eq!(
+            app.tabs[1]
+                .focused()
+                .launch_env()
+                .get("STAGE")
+                .map(String::as_str),
+            Some("dev"),
+            "the duplicate took the current settings instead of its source's"
+        );
+    }
+
+    #[test]
+    fn startup_commands_wait_for_the_first_prompt_mark() {
+        let mut app = app_with_pty();
+        let mut profile = Profile::default_shell();
+        profile.startup_commands = vec!["true".into()];
+        let _ = app.update(Message::CreateTab(profile));
+        assert!(app.tabs[0].focused().startup_pending());
+
+        let pane = app.tabs[0].focused_mut();
+        pane.feed_bytes(b"\r\x1b[2Jbanner\r\n");
+        assert!(pane.startup_pending(), "typed before the prompt");
+        pane.feed_bytes(b"\x1b]133;A\x07$ \x1b]133;B\x07");
+        assert!(!pane.startup_pending());
+    }
+
+    #[test]
+    fn history_suggestions_are_only_offered_at_the_end_of_the_line() {
+        let mut app = app_with_pty();
+        app.config.terminal.autocomplete = true;
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        let pane = app.tabs[0].focused_mut();
+        pane.feed_bytes(b"\r\x1b[2J\x1b]133;A\x07$ \x1b]133;B\x07git status\r\n\x1b]133;C\x07");
+        pane.feed_bytes(b"\x1b]133;D;0\x07\x1b]133;A\x07$ \x1b]133;B\x07git s");
+        assert_eq!(app.autocomplete(), Some(("git s".into(), "tatus".into())));
+
+        // Moved back over the "s", Tab and → go to the shell unchanged.
+        app.tabs[0].focused_mut().feed_bytes(b"\x1b[1D");
+        assert_eq!(app.autocomplete(), None);
+    }
+
+    #[test]
+    fn a_failed_session_stays_open_until_its_banner_is_closed() {
+        let mut app = app_with_pty();
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        let pane = app.tabs[0].focused().id;
+
+        let _ = app.update(Message::PtyOutput(crate::session::OutputEvent::Closed {
+            tab_id: pane,
+            exit_code: Some(2),
+        }));
+        assert_eq!(app.tabs.len(), 1, "a failed session closed its tab");
+        assert_eq!(
+            app.tabs[0].focused().exit,
+            Some(crate::gui::tab::SessionExit { code: Some(2) })
+        );
+
+        let _ = app.update(Message::CloseExitedPane { pane });
+        assert!(app.tabs.is_empty());
+    }
+
+    #[test]
+    fn moving_to_a_monitor_with_another_scale_regrids_the_panes() {
+        let mut app = app_with_pty();
+        app.config.terminal.cell_width = 8.43;
+        let _ = app.update(Message::CreateTab(Profile::default_shell()));
+        app.terminal_area = Size::new(1000.0, 600.0);
+        app.resize_panes();
+        let before = app.tabs[0].focused_mut().size().columns;
+
+        let _ = app.update(Message::WindowRescaled(2.0));
+
+        assert_eq!(app.cell_size()[0], 8.5);
+        let after = app.tabs[0].focused_mut().size().columns;
+        assert!(after < before, "{after} columns at 2x, {before} at 1x");
+    }
+
+    #[test]
+    fn background_and_minimized_windows_animate_less() {
+        use std::time::Duration;
+
+        let mut app = app_with_pty();
+        app.config.renderer.unfocused_fps = 10;
+        assert_eq!(app.frame_interval(), Some(Duration::from_millis(16)));
+
+        let _ = app.update(Message::WindowFocused(false));
+        assert_eq!(app.frame_interval(), Some(Duration::from_millis(100)));
+
+        app.window_size = Size::new(800.0, 600.0);
+        let _ = app.update(Message::WindowResized(Size::new(0.0, 0.0)));
+        assert_eq!(app.frame_interval(), None);
+        assert_eq!(app.window_size, Size::new(800.0, 600.0));
+
+        app.config.renderer.pause_when_minimized = false;
+        assert_eq!(app.frame_interval(), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn idle_lock_hides_the_window_until_a_key_press_or_click() {
+        let mut app = app_with_pty();
+        let _ = app.update(Message::IdleCheck);
+        assert!(!app.screen_locked, "off by default");
+
+        app.config.terminal.idle_lock_minutes = 5;
+        let _ = app.update(Message::IdleCheck);
+        assert!(!app.screen_locked);
+        app.last_input -= std::time::Duration::from_secs(5 * 60);
+        let _ = app.update(Message::IdleCheck);
+        assert!(app.screen_locked);
+
+        let _ = app.update(Message::TerminalWheelScroll(1.0));
+        assert!(app.screen_locked, "scrolling does not unlock");
+        let _ = app.update(Message::Unlock);
+        assert!(!app.screen_locked);
+        let _ = app.update(Message::IdleCheck);
+        assert!(!app.screen_locked, "unlocking counts as input");
+
+        app.config.terminal.idle_lock_minutes = u64::MAX;
+        let _ = app.update(Message::IdleCheck);
+        assert!(!app.screen_locked, "a huge timeout never runs out");
+    }
+
     #[test]
     fn an_unfocused_pane_exiting_leaves_focus_alone() {
         let mut app = app_with_pty();
@@ -856,6 +1825,7 @@ mod tests {
 
         let _ = app.update(Message::PtyOutput(crate::session::OutputEvent::Closed {
             tab_id: other,
+            exit_code: Some(0),
         }));
 
         assert_eq!(app.tabs[0].focused, focused, "focus jumped to another pane");
@@ -67,6 +67,7 @@ fn ascii_char_to_static(ch: char) -> Option<&'static str> {
 pub(super) enum ShortcutAction {
     NewTab,
     CloseTab,
+    ReopenClosedTab,
     OpenSettings,
     NextTab,
     PrevTab,
@@ -79,7 +80,24 @@ pub(super) enum ShortcutAction {
     SplitRight,
     SplitDown,
     ClosePane,
+    ZoomPane,
     FocusPane(crate::gui::pane::Direction),
+    ToggleMark,
+    JumpToMark { older: bool },
+    ListMarks,
+    CopyLastOutput,
+    RerunCommand,
+    SelectAll,
+    CopyMode,
+    SaveWorkspace,
+    OpenDirectory,
+    CopyDirectory,
+    DebugOverlay,
+    PrivacyMode,
+    Screenshot,
+    Record,
+    CheatSheet,
+    ReadScreen,
 }
 
 impl ShortcutAction {
@@ -88,6 +106,7 @@ impl ShortcutAction {
         match id {
             ShortcutId::NewTab => Self::NewTab,
             ShortcutId::CloseTab => Self::CloseTab,
+            ShortcutId::ReopenTab => Self::ReopenClosedTab,
             ShortcutId::OpenSettings => Self::OpenSettings,
             ShortcutId::NextTab => Self::NextTab,
             ShortcutId::PrevTab => Self::PrevTab,
@@ -100,10 +119,28 @@ impl ShortcutAction {
             ShortcutId::SplitRight => Self::SplitRight,
             ShortcutId::SplitDown => Self::SplitDown,
             ShortcutId::ClosePane => Self::ClosePane,
+            ShortcutId::ZoomPane => Self::ZoomPane,
             ShortcutId::FocusLeft => Self::FocusPane(Direction::Left),
             ShortcutId::FocusRight => Self::FocusPane(Direction::Right),
             ShortcutId::FocusUp => Self::FocusPane(Direction::Up),
             ShortcutId::FocusDown => Self::FocusPane(Direction::Down),
+            ShortcutId::ToggleMark => Self::ToggleMark,
+            ShortcutId::PrevMark => Self::JumpToMark { older: true },
+            ShortcutId::NextMark => Self::JumpToMark { older: false },
+            ShortcutId::ListMarks => Self::ListMarks,
+            ShortcutId::CopyLastOutput => Self::CopyLastOutput,
+            ShortcutId::RerunCommand => Self::RerunCommand,
+            ShortcutId::SelectAll => Self::SelectAll,
+            ShortcutId::CopyMode => Self::CopyMode,
+            ShortcutId::SaveWorkspace => Self::SaveWorkspace,
+            ShortcutId::OpenDirectory => Self::OpenDirectory,
+            ShortcutId::CopyDirectory => Self::CopyDirectory,
+            ShortcutId::DebugOverlay => Self::DebugOverlay,
+            ShortcutId::PrivacyMode => Self::PrivacyMode,
+            ShortcutId::Screenshot => Self::Screenshot,
+            ShortcutId::Record => Self::Record,
+            ShortcutId::CheatSheet => Self::CheatSheet,
+            ShortcutId::ReadScreen => Self::ReadScreen,
         }
     }
 
@@ -227,6 +264,14 @@ pub(super) fn physical_key_token(physical: &Physical) -> Option<Cow<'static, str
         Code::PageDown => "PageDown",
         Code::Comma => "Comma",
         Code::Period => "Period",
+        Code::BracketLeft => "[",
+        Code::BracketRight => "]",
+        Code::Slash => "/",
+        Code::Semicolon => ";",
+        Code::Quote => "'",
+        Code::Minus => "-",
+        Code::Equal => "=",
+        Code::Backquote => "`",
         Code::F1 => "F1",
         Code::F2 => "F2",
         Code::F3 => "F3",
@@ -371,4 +416,27 @@ mod tests {
             Some(ShortcutAction::SplitAuto)
         ));
     }
+
+    #[test]
+    fn punctuation_shortcuts_match_their_physical_keys() {
+        let shortcuts = crate::config::AppConfig::default().shortcuts;
+        let primary = if cfg!(target_os = "macos") {
+            Modifiers::LOGO
+        } else {
+            Modifiers::CTRL
+        };
+
+        assert!(matches!(
+            ShortcutAction::resolve(&Physical::Code(Code::Equal), primary, &shortcuts),
+            Some(ShortcutAction::FontSizeIncrease)
+        ));
+        assert!(matches!(
+            ShortcutAction::resolve(
+                &Physical::Code(Code::Slash),
+                primary | Modifiers::SHIFT,
+                &shortcuts
+            ),
+            Some(ShortcutAction::CheatSheet)
+        ));
+    }
 }
@@ -20,15 +20,20 @@ impl App {
                 .flat_map(|tab| tab.panes.iter())
                 .any(|pane| pane.sftp.anim.is_animating(now))
             || self.settings_category_transition.is_animating(now)
-            || bell_flashing;
+            || bell_flashing
+            // A screenshot is read back over the next frames.
+            || self.capture.is_some();
 
-        let animation_tick = if has_animation {
-            time::every(std::time::Duration::from_millis(16)).map(|_| Message::AnimationTick)
-        } else {
-            Subscription::none()
+        let frame_interval = self.frame_interval();
+        let animation_tick = match frame_interval {
+            Some(interval) if has_animation => {
+                time::every(interval).map(|_| Message::AnimationTick)
+            }
+            _ => Subscription::none(),
         };
 
         let cursor_blink = if self.config.terminal.cursor_blink
+            && frame_interval.is_some()
             && self.active_tab != super::SETTINGS_TAB_INDEX
             && self.tabs.get(self.active_tab).is_some()
         {
@@ -44,10 +49,49 @@ impl App {
             Subscription::none()
         };
 
+        let idle_check = if self.config.terminal.idle_lock_minutes > 0 && !self.screen_locked {
+            time::every(std::time::Duration::from_secs(15)).map(|_| Message::IdleCheck)
+        } else {
+            Subscription::none()
+        };
+
+        let closed_tab_expiry = if self.closed_tabs.iter().any(|closed| closed.tab.is_some()) {
+            time::every(std::time::Duration::from_secs(1)).map(|_| Message::ExpireClosedTabs)
+        } else {
+            Subscription::none()
+        };
+
+        let startup_commands = if self
+            .tabs
+            .iter()
+            .any(|tab| tab.panes.iter().any(|pane| pane.startup_pending()))
+        {
+            time::every(std::time::Duration::from_millis(250)).map(|_| Message::SendStartupCommands)
+        } else {
+            Subscription::none()
+        };
+
+        let resource_sampling = if !self.tabs.is_empty() && !self.window_minimized {
+            time::every(super::RESOURCE_SAMPLE_INTERVAL).map(|_| Message::SampleResources)
+        } else {
+            Subscription::none()
+        };
+
+        let command_titles = if !self.tabs.is_empty() && !self.window_minimized {
+            time::every(super::COMMAND_TITLE_INTERVAL).map(|_| Message::RefreshCommandTitles)
+        } else {
+            Subscription::none()
+        };
+
         Subscription::batch([
             animation_tick,
             cursor_blink,
             selection_autoscroll,
+            idle_check,
+            closed_tab_expiry,
+            startup_commands,
+            resource_sampling,
+            command_titles,
             Subscription::run(|| {
                 stream::channel(100, async |mut output| {
                     let (sender, mut receiver) = mpsc::unbounded();
@@ -75,6 +119,14 @@ impl App {
             event::listen_with(|event, status, _id| match event {
                 Event::Window(window::Event::CloseRequested) => Some(Message::Exit),
                 Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(size)),
+                Event::Window(window::Event::Rescaled(scale)) => {
+                    Some(Message::WindowRescaled(scale))
+                }
+                Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+                Event::Window(window::Event::FileHovered(_)) => Some(Message::FileHovered),
+                Event::Window(window::Event::FilesHoveredLeft) => Some(Message::FilesHoveredLeft),
+                Event::Window(window::Event::Focused) => Some(Message::WindowFocused(true)),
+                Event::Window(window::Event::Unfocused) => Some(Message::WindowFocused(false)),
                 Event::Keyboard(keyboard::Event::KeyPressed {
                     key,
                     physical_key,
@@ -124,4 +176,20 @@ impl App {
             }),
         ])
     }
+
+    /// Time between animation frames: 60 fps in the foreground, the
+    /// configured rate in the background, and `None` while minimized with
+    /// rendering paused.
+    pub(super) fn frame_interval(&self) -> Option<std::time::Duration> {
+        const FOREGROUND: std::time::Duration = std::time::Duration::from_millis(16);
+        let renderer = &self.config.renderer;
+        if self.window_minimized && renderer.pause_when_minimized {
+            None
+        } else if self.window_focused {
+            Some(FOREGROUND)
+        } else {
+            let background = std::time::Duration::from_secs(1) / renderer.unfocused_fps.max(1);
+            Some(background.max(FOREGROUND))
+        }
+    }
 }
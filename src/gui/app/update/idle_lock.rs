@@ -0,0 +1,47 @@
+use super::super::{App, Message};
+use std::time::Duration;
+
+impl App {
+    /// Notes user input for the idle lock and says whether `message` is
+    /// swallowed by it. While locked, a key press or a click on the lock
+    /// screen unlocks; that key and any other input go nowhere, so nothing
+    /// reaches the sessions hidden underneath.
+    pub(super) fn idle_lock_swallows(&mut self, message: &Message) -> bool {
+        let input = matches!(
+            message,
+            Message::KeyPressed { .. }
+                | Message::Unlock
+                | Message::CursorMoved(_)
+                | Message::TerminalWheelScroll(_)
+                | Message::TabBarScroll(_)
+                | Message::ImeCommit(_)
+                | Message::ImePreedit(..)
+                | Message::FileDropped(_)
+                | Message::FileHovered
+        );
+        if !input {
+            return false;
+        }
+        self.last_input = std::time::Instant::now();
+        if !self.screen_locked {
+            return false;
+        }
+        if matches!(message, Message::KeyPressed { .. } | Message::Unlock) {
+            self.screen_locked = false;
+        }
+        true
+    }
+
+    /// Locks once the configured time has passed without input.
+    pub(super) fn check_idle_lock(&mut self) {
+        let minutes = self.config.terminal.idle_lock_minutes;
+        if minutes > 0
+            && self.last_input.elapsed() >= Duration::from_secs(minutes.saturating_mul(60))
+        {
+            self.screen_locked = true;
+            self.ime_preedit = None;
+            self.tab_context_menu = None;
+            self.terminal_context_menu = false;
+        }
+    }
+}
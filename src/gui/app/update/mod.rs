@@ -1,11 +1,23 @@
+mod idle_lock;
+mod navigation;
+mod onboarding;
+mod resources;
+mod screenshot;
+mod script;
 mod settings;
 mod sftp;
+mod side_panel;
+mod snippet;
 pub(in crate::gui) mod tab;
+mod tab_list;
 mod terminal;
+mod workspace;
 
-use super::{App, Message, SETTINGS_TAB_INDEX};
+use super::{App, Message, PendingPaste, SETTINGS_TAB_INDEX, SelectionAutoscroll, SftpMessage};
 use crate::gui::settings::SettingsDraft;
 use crate::gui::tab::Profile;
+use crate::terminal::copy_mode::{CopyMotion, VisualMode};
+use crate::terminal::paste::{self, PasteSize};
 use iced::keyboard::{Key, key::Named};
 use iced::time::Instant;
 use iced::{Task, widget};
@@ -13,6 +25,14 @@ use std::sync::LazyLock;
 
 pub(in crate::gui) static TAB_BAR_SCROLLABLE_ID: LazyLock<widget::Id> =
     LazyLock::new(widget::Id::unique);
+pub(in crate::gui) static WORKSPACE_NAME_INPUT_ID: LazyLock<widget::Id> =
+    LazyLock::new(widget::Id::unique);
+pub(in crate::gui) static SNIPPET_INPUT_ID: LazyLock<widget::Id> =
+    LazyLock::new(widget::Id::unique);
+pub(in crate::gui) static TAB_LIST_INPUT_ID: LazyLock<widget::Id> =
+    LazyLock::new(widget::Id::unique);
+pub(in crate::gui) static SETTINGS_SCROLLABLE_ID: LazyLock<widget::Id> =
+    LazyLock::new(widget::Id::unique);
 
 const WHEEL_GESTURE_IDLE: std::time::Duration = std::time::Duration::from_millis(100);
 
@@ -23,7 +43,7 @@ fn is_gesture_tail(suppressed: bool, gap: Option<std::time::Duration>) -> bool {
 impl App {
     fn active_session_mut(&mut self) -> Option<&mut crate::gui::tab::Pane> {
         self.focused_pane_mut()
-            .filter(|pane| matches!(pane.session, crate::gui::tab::TerminalSession::Active(_)))
+            .filter(|pane| pane.input_session().is_some())
     }
 
     pub(super) fn dismiss_shell_picker(&mut self) {
@@ -48,7 +68,23 @@ impl App {
         }
     }
 
+    /// Holds Secure Keyboard Entry while it is wanted and the window has
+    /// focus, so other apps get their keystrokes back in the meantime.
+    pub(super) fn sync_secure_input(&mut self) {
+        let wanted = crate::platform::SECURE_INPUT_SUPPORTED
+            && self.config.terminal.secure_keyboard_entry
+            && self.window_focused;
+        if wanted != self.secure_input {
+            crate::platform::set_secure_input(wanted);
+            self.secure_input = wanted;
+        }
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        self.clear_tab_alerts();
+        if self.idle_lock_swallows(&message) {
+            return Task::none();
+        }
         match message {
             Message::Noop => {}
 
@@ -90,15 +126,47 @@ impl App {
                 self.tab_context_menu = None;
                 self.handle_close_tab(index);
             }
+            Message::ReopenClosedTab => return self.reopen_closed_tab(),
+            Message::ExpireClosedTabs => self.expire_closed_tabs(),
+            Message::CloseExitedPane { pane } => {
+                self.remove_pane(pane);
+            }
+            Message::SendStartupCommands => {
+                let now = std::time::Instant::now();
+                for pane in self.panes_mut() {
+                    pane.send_startup_commands(now);
+                }
+            }
+            Message::RefreshCommandTitles => {
+                for pane in self.panes_mut() {
+                    pane.refresh_command();
+                }
+            }
             Message::OpenShellPicker => {
                 self.show_shell_picker = true;
                 self.shell_picker_selected = 0;
                 self.modal_anim.go_mut(true, Instant::now());
+                return Task::perform(
+                    async { crate::session::container::list() },
+                    Message::ContainersListed,
+                );
+            }
+            Message::ContainersListed(containers) => {
+                self.containers = containers;
             }
             Message::CloseShellPicker => {
                 self.modal_anim.go_mut(false, Instant::now());
             }
             Message::CreateTab(profile) => return self.launch_profile(profile),
+            Message::ToggleDefaultProfile(name) => {
+                let current = &mut self.config.terminal.default_profile;
+                *current = if current.as_deref() == Some(name.as_str()) {
+                    None
+                } else {
+                    Some(name)
+                };
+                self.queue_config_save();
+            }
             Message::LaunchFromHistory(index) => {
                 if let Some(entry) = self.session_history.entries.get(index).cloned() {
                     return self.launch_profile(entry.profile);
@@ -107,12 +175,32 @@ impl App {
             Message::DuplicateTab => {
                 let index = self.tab_context_menu.unwrap_or(self.active_tab);
                 self.tab_context_menu = None;
-                if let Some(tab) = self.tabs.get(index) {
-                    let profile = tab.focused().profile.clone();
-                    return self.launch_profile(profile);
-                }
+                return self.duplicate_tab(index);
             }
             Message::Sftp(message) => return self.update_sftp(message),
+            Message::Onboarding(message) => return self.update_onboarding(message),
+            Message::ShowWorkspacePrompt
+            | Message::WorkspaceNameChanged(_)
+            | Message::SaveWorkspace
+            | Message::CloseWorkspacePrompt
+            | Message::OpenWorkspace(_) => return self.update_workspace(message),
+            Message::UseSnippet(_)
+            | Message::SnippetValueChanged(..)
+            | Message::InsertSnippet
+            | Message::CloseSnippetPrompt => return self.update_snippet(message),
+            Message::ToggleTabList
+            | Message::TabListFilterChanged(_)
+            | Message::TabListSelect(_)
+            | Message::TabListSubmit => return self.update_tab_list(message),
+            Message::SampleResources
+            | Message::ResourcesSampled(_)
+            | Message::ShowProcessList(_)
+            | Message::CloseProcessList => return self.update_resources(message),
+            Message::Assist(_)
+            | Message::RunPluginCommand(..)
+            | Message::SidePanelReply(..)
+            | Message::CopySidePanel
+            | Message::CloseSidePanel => return self.update_side_panel(message),
             Message::SshPasswordPromptChanged(value) => {
                 if let Some(prompt) = self.password_prompt.as_mut() {
                     prompt.draft = value;
@@ -139,12 +227,58 @@ impl App {
             }
             Message::ShowTabContextMenu(index) => {
                 self.tab_context_menu = Some(index);
+                self.menu_cursor = None;
             }
             Message::CloseTabContextMenu => {
                 self.tab_context_menu = None;
             }
-            Message::TerminalRightClick(pane) => {
+            Message::ToggleTabLock(index) => {
+                self.tab_context_menu = None;
+                if let Some(tab) = self.tabs.get_mut(index) {
+                    tab.set_locked(!tab.locked());
+                }
+            }
+            Message::CycleTabColor(index) => {
+                self.tab_context_menu = None;
+                if let Some(tab) = self.tabs.get_mut(index) {
+                    tab.cycle_color();
+                }
+            }
+            Message::CycleTabIcon(index) => {
+                self.tab_context_menu = None;
+                if let Some(tab) = self.tabs.get_mut(index) {
+                    tab.cycle_icon();
+                }
+            }
+            Message::OpenTabDirectory(index) => {
+                self.tab_context_menu = None;
+                if let Some(dir) = self
+                    .tabs
+                    .get(index)
+                    .and_then(|tab| tab.focused().working_directory())
+                {
+                    crate::platform::open_path(&dir);
+                }
+            }
+            Message::CopyTabDirectory(index) => {
+                self.tab_context_menu = None;
+                if let Some(dir) = self
+                    .tabs
+                    .get(index)
+                    .and_then(|tab| tab.focused().current_directory())
+                {
+                    return iced::clipboard::write(dir);
+                }
+            }
+            Message::TogglePortForward { pane, index } => {
+                self.tab_context_menu = None;
+                if let Some(pane) = self.pane_mut_by_id(pane) {
+                    pane.toggle_port_forward(index);
+                }
+            }
+            Message::TerminalRightClick { pane, link } => {
                 self.focus_pane(pane);
+                self.terminal_context_link = link.map(|link| (pane, link));
                 use crate::config::RightClickAction;
                 match self.config.terminal.right_click_action {
                     RightClickAction::Paste => {
@@ -153,6 +287,7 @@ impl App {
                     }
                     RightClickAction::Menu => {
                         self.terminal_context_menu = true;
+                        self.menu_cursor = None;
                     }
                     RightClickAction::None => {}
                 }
@@ -166,7 +301,57 @@ impl App {
                     .map(|content| Message::PasteClipboard(content.unwrap_or_default()));
             }
             Message::OpenUrl(url) => {
-                crate::platform::open_url(&url);
+                let schemes = &self.config.terminal.link_schemes;
+                if self.config.terminal.confirm_links
+                    || !crate::terminal::url::is_allowed(&url, schemes)
+                {
+                    self.pending_link = Some(url);
+                } else {
+                    crate::platform::open_url(&url, schemes);
+                }
+            }
+            Message::ConfirmOpenLink => {
+                if let Some(url) = self.pending_link.take() {
+                    crate::platform::open_url(&url, &self.config.terminal.link_schemes);
+                }
+            }
+            Message::CancelOpenLink => {
+                self.pending_link = None;
+            }
+            Message::CopyPendingLink => {
+                if let Some(url) = self.pending_link.take() {
+                    return iced::clipboard::write(url);
+                }
+            }
+            Message::OpenPath {
+                pane,
+                path,
+                line,
+                column,
+            } => self.open_path(pane, &path, line, column),
+            Message::LinkHovered(link) => {
+                self.hovered_link = link;
+            }
+            Message::JumpToBottom => {
+                if let Some(pane) = self.focused_pane_mut() {
+                    pane.scroll_to_bottom();
+                }
+                self.scroll_follow_bottom = true;
+            }
+            Message::ScrollToMark(line) => {
+                self.marks_list = false;
+                if let Some(pane) = self.focused_pane_mut()
+                    && pane.scroll_to_mark(line)
+                {
+                    let offset = pane.scroll_position().0;
+                    self.scroll_follow_bottom = offset == 0;
+                }
+            }
+            Message::CloseMarksList => {
+                self.marks_list = false;
+            }
+            Message::CloseCheatSheet => {
+                self.cheat_sheet = false;
             }
             Message::TerminalContextCopy => {
                 self.terminal_context_menu = false;
@@ -177,6 +362,52 @@ impl App {
                     return iced::clipboard::write(text);
                 }
             }
+            Message::TerminalContextCopyOutput => {
+                self.terminal_context_menu = false;
+                return self.copy_last_output();
+            }
+            Message::SaveScreenshot => {
+                self.terminal_context_menu = false;
+                return self.start_capture();
+            }
+            Message::ScreenshotCaptured(result) => return self.save_screenshot(result),
+            Message::RunQuickAction(index) => {
+                if let Some(pane) = self.focused_pane_mut() {
+                    pane.run_quick_action(index);
+                    pane.scroll_to_bottom();
+                }
+                self.scroll_follow_bottom = true;
+            }
+            Message::TerminalContextRerun => {
+                self.terminal_context_menu = false;
+                self.rerun_last_command();
+            }
+            Message::TerminalContextSelectAll => {
+                self.terminal_context_menu = false;
+                if let Some(pane) = self.focused_pane_mut() {
+                    pane.select_all();
+                }
+            }
+            Message::TerminalContextClearBuffer => {
+                self.terminal_context_menu = false;
+                if let Some(pane) = self.focused_pane_mut() {
+                    pane.clear_buffer();
+                }
+                self.scroll_follow_bottom = true;
+            }
+            Message::TerminalContextSearchWeb => {
+                self.terminal_context_menu = false;
+                if let Some(text) = self.focused_pane().and_then(|pane| pane.selected_text()) {
+                    let url = crate::terminal::url::web_search(&text);
+                    crate::platform::open_url(&url, &self.config.terminal.link_schemes);
+                }
+            }
+            Message::TerminalContextOpenLink => {
+                self.terminal_context_menu = false;
+                if let Some((pane, link)) = self.terminal_context_link.take() {
+                    return self.update(link.into_message(pane));
+                }
+            }
             Message::CursorMoved(point) => {
                 if self.tab_context_menu.is_none() && !self.terminal_context_menu {
                     self.cursor_position = point;
@@ -190,18 +421,34 @@ impl App {
             Message::PtySenderReady(sender) => {
                 self.pty_sender = Some(sender);
                 if self.take_initial_shell_request() {
-                    return self.create_tab(Profile::default_shell());
+                    if let Some(profile) = self.startup_profile.take() {
+                        return self.create_tab(profile);
+                    }
+                    if !self.script_startup.is_empty() {
+                        let actions = std::mem::take(&mut self.script_startup);
+                        return self.run_script_actions(actions);
+                    }
+                    if self.config.terminal.persistent_sessions {
+                        return Task::perform(
+                            async { crate::session::daemon::list() },
+                            Message::DaemonSessionsListed,
+                        );
+                    }
+                    return self.launch_profile(self.default_profile());
                 }
             }
+            Message::DaemonSessionsListed(sessions) => {
+                return self.handle_daemon_sessions_listed(sessions);
+            }
             Message::PtyOutput(event) => {
-                self.handle_pty_event(event);
-                return Task::none();
+                return self.handle_pty_event(event);
             }
             Message::PtyOutputBatch(events) => {
-                for event in events {
-                    self.handle_pty_event(event);
-                }
-                return Task::none();
+                let tasks: Vec<_> = events
+                    .into_iter()
+                    .map(|event| self.handle_pty_event(event))
+                    .collect();
+                return Task::batch(tasks);
             }
             Message::KeyPressed {
                 key,
@@ -228,9 +475,35 @@ impl App {
                     }
                 }
             }
-            Message::TerminalSelectionAutoscroll { up, col } => {
-                self.selection_autoscroll = Some(up);
-                self.selection_autoscroll_col = col;
+            Message::SemanticSelect {
+                pane,
+                row,
+                col,
+                word,
+            } => {
+                self.selection_autoscroll = None;
+                if self.active_tab != SETTINGS_TAB_INDEX
+                    && let Some(tab) = self.tabs.get_mut(self.active_tab)
+                {
+                    tab.focused = pane;
+                    if let Some(slot) = tab.pane_mut(pane)
+                        && !slot.select_semantic(row, col, &self.config.selection.semantic_rules)
+                    {
+                        slot.selection = word;
+                    }
+                }
+            }
+            Message::TerminalSelectionAutoscroll { up, col, overshoot } => {
+                let carry = self
+                    .selection_autoscroll
+                    .filter(|scroll| scroll.up == up)
+                    .map_or(0.0, |scroll| scroll.carry);
+                self.selection_autoscroll = Some(SelectionAutoscroll {
+                    up,
+                    col,
+                    speed: SelectionAutoscroll::speed_for(overshoot),
+                    carry,
+                });
             }
             Message::TerminalSelectionAutoscrollStop => {
                 self.selection_autoscroll = None;
@@ -256,21 +529,35 @@ impl App {
                 }
             }
             Message::PasteClipboard(text) => {
-                if !text.is_empty() {
-                    let is_multiline = text.contains('\n') || text.contains('\r');
-                    if self.config.terminal.multiline_paste_confirm && is_multiline {
-                        self.pending_paste = Some(text);
+                let locked = self.focused_pane().is_some_and(|pane| pane.input_locked);
+                if !text.is_empty() && !locked {
+                    let into_secret = self.focused_pane().is_some_and(|pane| pane.secret_input());
+                    let terminal = &self.config.terminal;
+                    let size = PasteSize::of(&text);
+                    let is_large = size.exceeds(
+                        terminal.large_paste_lines,
+                        terminal.large_paste_kib.saturating_mul(1024),
+                    );
+                    let confirm_multiline = terminal.multiline_paste_confirm || into_secret;
+                    if is_large || (confirm_multiline && size.is_multiline()) {
+                        let preview = is_large.then(|| paste::preview(&text));
+                        self.pending_paste = Some(PendingPaste {
+                            text,
+                            size,
+                            preview,
+                            into_secret: into_secret && size.is_multiline(),
+                        });
                     } else {
                         return self.perform_paste(text);
                     }
                 }
             }
-            Message::ConfirmMultilinePaste => {
-                if let Some(text) = self.pending_paste.take() {
-                    return self.perform_paste(text);
+            Message::ConfirmPaste => {
+                if let Some(pending) = self.pending_paste.take() {
+                    return self.perform_paste(pending.text);
                 }
             }
-            Message::CancelMultilinePaste => {
+            Message::CancelPaste => {
                 self.pending_paste = None;
             }
             Message::ImeStateChanged(active) => {
@@ -293,6 +580,60 @@ impl App {
                 self.wheel_suppressed = true;
                 return Task::none();
             }
+            Message::FileDropped(path) => {
+                // An open SFTP drawer takes drops as uploads.
+                if self.active_tab != SETTINGS_TAB_INDEX
+                    && let Some(pane) = self.focused_pane_mut()
+                    && pane.sftp.accepts_drop()
+                {
+                    pane.sftp.drop_hover = false;
+                    let tab_id = pane.id;
+                    return self.update_sftp(SftpMessage::UploadPicked {
+                        tab_id,
+                        files: vec![path],
+                    });
+                }
+                // Dropping several files delivers one event per file; the
+                // trailing space keeps consecutive paths separate words.
+                if let Some(pane) = self.active_session_mut()
+                    && let crate::gui::tab::TerminalSession::Active(session) = &pane.session
+                {
+                    let quoted = format!("{} ", paste::quote_path(&path));
+                    if let Err(err) = session.send_bytes(quoted.as_bytes()) {
+                        eprintln!("Failed to send dropped path: {err}");
+                    }
+                    pane.scroll_to_bottom();
+                    self.scroll_follow_bottom = true;
+                }
+            }
+            Message::FileHovered => {
+                if self.active_tab != SETTINGS_TAB_INDEX
+                    && let Some(pane) = self.focused_pane_mut()
+                {
+                    pane.sftp.drop_hover = pane.sftp.accepts_drop();
+                }
+            }
+            Message::FilesHoveredLeft => {
+                for pane in self.panes_mut() {
+                    pane.sftp.drop_hover = false;
+                }
+            }
+            Message::WindowFocused(focused) => {
+                self.window_focused = focused;
+                self.sync_secure_input();
+            }
+            Message::DismissGpuFallback => {
+                self.gpu_fallback = None;
+            }
+            Message::WindowRescaled(scale) => {
+                if scale.is_finite()
+                    && scale > 0.0
+                    && (scale - self.scale_factor).abs() > f32::EPSILON
+                {
+                    self.scale_factor = scale;
+                    self.resize_panes();
+                }
+            }
             Message::ImePreedit(text, cursor) => {
                 if text.is_empty() {
                     self.ime_preedit = None;
@@ -385,6 +726,9 @@ impl App {
             Message::CursorBlink => {
                 self.cursor_blink_on = !self.cursor_blink_on;
             }
+            Message::IdleCheck => self.check_idle_lock(),
+            // Handled by `idle_lock_swallows`.
+            Message::Unlock => {}
             Message::TerminalAreaResized(size) => {
                 if (self.terminal_area.width - size.width).abs() > 0.5
                     || (self.terminal_area.height - size.height).abs() > 0.5
@@ -410,9 +754,10 @@ impl App {
 
             // ── Window ──────────────────────────────────────────────
             Message::Exit => {
-                return iced::exit();
+                return self.quit();
             }
             Message::ApplyWindowStyle => {
+                self.sync_secure_input();
                 return self.handle_apply_window_style();
             }
             #[cfg(target_os = "windows")]
@@ -445,12 +790,23 @@ impl App {
         // shown, Enter confirms, Escape cancels, and all other keys are swallowed.
         if self.pending_paste.is_some() {
             match key {
-                Key::Named(Named::Enter) => return self.update(Message::ConfirmMultilinePaste),
-                Key::Named(Named::Escape) => return self.update(Message::CancelMultilinePaste),
+                Key::Named(Named::Enter) => return self.update(Message::ConfirmPaste),
+                Key::Named(Named::Escape) => return self.update(Message::CancelPaste),
                 _ => {}
             }
             return Task::none();
         }
+        if self.pending_link.is_some() {
+            match key {
+                Key::Named(Named::Enter) => return self.update(Message::ConfirmOpenLink),
+                Key::Named(Named::Escape) => return self.update(Message::CancelOpenLink),
+                _ => {}
+            }
+            return Task::none();
+        }
+        if let Some(task) = self.handle_navigation_key(&key, modifiers) {
+            return task;
+        }
 
         if self.show_shell_picker && self.modal_anim.value() {
             match key {
@@ -471,6 +827,44 @@ impl App {
             return Task::none();
         }
 
+        // The name field handles typing and Enter; keep keys off the terminal.
+        if self.workspace_prompt.is_some() {
+            if key == Key::Named(Named::Escape) {
+                return self.update(Message::CloseWorkspacePrompt);
+            }
+            return Task::none();
+        }
+        if self.snippet_prompt.is_some() {
+            if key == Key::Named(Named::Escape) {
+                return self.update(Message::CloseSnippetPrompt);
+            }
+            return Task::none();
+        }
+        if self.tab_list.is_some() {
+            if key == Key::Named(Named::Escape) {
+                return self.update(Message::ToggleTabList);
+            }
+            return Task::none();
+        }
+
+        if self.marks_list {
+            if key == Key::Named(Named::Escape) {
+                return self.update(Message::CloseMarksList);
+            }
+            return self
+                .handle_app_shortcut(&physical_key, modifiers)
+                .unwrap_or_else(Task::none);
+        }
+
+        if self.cheat_sheet {
+            if key == Key::Named(Named::Escape) {
+                return self.update(Message::CloseCheatSheet);
+            }
+            return self
+                .handle_app_shortcut(&physical_key, modifiers)
+                .unwrap_or_else(Task::none);
+        }
+
         // Cmd+1..9 (macOS) / Ctrl+1..9 (other) — switch to Nth tab
         if let Some(digit) = crate::gui::app::shortcuts::physical_key_token(&physical_key)
             .and_then(|token| token.parse::<u32>().ok())
@@ -497,6 +891,10 @@ impl App {
             return Task::none();
         }
 
+        if self.focused_pane().is_some_and(|pane| pane.in_copy_mode()) {
+            return self.handle_copy_mode_key(&key, &physical_key, modifiers, text.as_deref());
+        }
+
         // Copy: Cmd+C (macOS) / Ctrl+Shift+C (other)
         if is_copy_shortcut(&physical_key, modifiers)
             && let Some(pane) = self.focused_pane_mut()
@@ -532,10 +930,31 @@ impl App {
             return Task::none();
         }
 
+        // A locked tab is view-only: keys neither reach the session nor move
+        // the viewport.
+        if self.focused_pane().is_some_and(|pane| pane.input_locked) {
+            return Task::none();
+        }
+
+        // Tab or → takes the history suggestion shown at the prompt.
+        if modifiers.is_empty()
+            && matches!(key, Key::Named(Named::Tab | Named::ArrowRight))
+            && let Some((_, rest)) = self.autocomplete()
+            && let Some(pane) = self.focused_pane_mut()
+        {
+            pane.type_text(&rest);
+            return Task::none();
+        }
+
         // Clear selection on actual key input
         if let Some(pane) = self.focused_pane_mut() {
             pane.clear_selection();
-            pane.handle_key(&key, modifiers, text.as_deref());
+            pane.handle_key(
+                &key,
+                modifiers,
+                text.as_deref(),
+                self.config.terminal.local_echo,
+            );
             pane.scroll_to_bottom();
         }
         self.scroll_follow_bottom = true;
@@ -544,15 +963,21 @@ impl App {
     }
 
     fn advance_selection_autoscroll(&mut self) -> Task<Message> {
-        let Some(up) = self.selection_autoscroll else {
+        let Some(mut autoscroll) = self.selection_autoscroll else {
             return Task::none();
         };
         if self.active_tab == SETTINGS_TAB_INDEX {
             self.selection_autoscroll = None;
             return Task::none();
         }
-        let col = self.selection_autoscroll_col;
-        let step = (self.config.terminal.scroll_multiplier.round() as i32).max(1);
+        let SelectionAutoscroll { up, col, .. } = autoscroll;
+        autoscroll.carry += autoscroll.speed;
+        let step = autoscroll.carry.floor() as i32;
+        autoscroll.carry -= step as f32;
+        self.selection_autoscroll = Some(autoscroll);
+        if step == 0 {
+            return Task::none();
+        }
         let offset = {
             let Some(pane) = self.focused_pane_mut() else {
                 self.selection_autoscroll = None;
@@ -582,23 +1007,156 @@ impl App {
         Task::none()
     }
 
-    fn perform_paste(&mut self, text: String) -> Task<Message> {
+    /// Selects the focused pane's last command output and copies it.
+    /// Copy mode keeps keys off the session: arrows, Home/End, PageUp/Down
+    /// and hjkl/0/$/g/G move the cursor, v/V start a selection, y or Enter
+    /// copies it and Escape or q leaves.
+    fn handle_copy_mode_key(
+        &mut self,
+        key: &Key,
+        physical_key: &iced::keyboard::key::Physical,
+        modifiers: iced::keyboard::Modifiers,
+        text: Option<&str>,
+    ) -> Task<Message> {
+        let yank = is_copy_shortcut(physical_key, modifiers);
+        let typed = text.filter(|_| !modifiers.control() && !modifiers.logo());
+        let Some(pane) = self.focused_pane_mut() else {
+            return Task::none();
+        };
+
+        let motion = match (key, typed) {
+            (Key::Named(Named::ArrowLeft), _) | (_, Some("h")) => Some(CopyMotion::Left),
+            (Key::Named(Named::ArrowRight), _) | (_, Some("l")) => Some(CopyMotion::Right),
+            (Key::Named(Named::ArrowUp), _) | (_, Some("k")) => Some(CopyMotion::Up),
+            (Key::Named(Named::ArrowDown), _) | (_, Some("j")) => Some(CopyMotion::Down),
+            (Key::Named(Named::Home), _) | (_, Some("0")) => Some(CopyMotion::LineStart),
+            (Key::Named(Named::End), _) | (_, Some("$")) => Some(CopyMotion::LineEnd),
+            (Key::Named(Named::PageUp), _) => Some(CopyMotion::PageUp),
+            (Key::Named(Named::PageDown), _) => Some(CopyMotion::PageDown),
+            (_, Some("g")) => Some(CopyMotion::Top),
+            (_, Some("G")) => Some(CopyMotion::Bottom),
+            _ => None,
+        };
+        if let Some(motion) = motion {
+            pane.copy_mode_move(motion);
+            let offset = pane.scroll_position().0;
+            self.scroll_follow_bottom = offset == 0;
+            return Task::none();
+        }
+
+        if yank || *key == Key::Named(Named::Enter) || typed == Some("y") {
+            let text = pane.selected_text();
+            pane.exit_copy_mode();
+            return text.map_or_else(Task::none, iced::clipboard::write);
+        }
+        match (key, typed) {
+            (_, Some("v")) => pane.copy_mode_visual(VisualMode::Char),
+            (_, Some("V")) => pane.copy_mode_visual(VisualMode::Line),
+            (Key::Named(Named::Escape), _) | (_, Some("q")) => pane.exit_copy_mode(),
+            _ => {}
+        }
+        Task::none()
+    }
+
+    pub(super) fn copy_last_output(&mut self) -> Task<Message> {
+        let Some(pane) = self.focused_pane_mut() else {
+            return Task::none();
+        };
+        let Some(text) = pane.select_last_output() else {
+            return Task::none();
+        };
+        let offset = pane.scroll_position().0;
+        self.scroll_follow_bottom = offset == 0;
+        iced::clipboard::write(text)
+    }
+
+    /// Speaks the focused pane's visible text and puts it on the clipboard,
+    /// for screen readers that cannot see into the terminal grid.
+    pub(super) fn read_screen(&mut self) -> Task<Message> {
+        if self.active_tab == SETTINGS_TAB_INDEX {
+            return Task::none();
+        }
+        let Some(pane) = self.focused_pane() else {
+            return Task::none();
+        };
+        let text = pane.screen_text().trim_end().to_string();
+        if text.is_empty() {
+            return Task::none();
+        }
+        crate::platform::speak(text.clone());
+        iced::clipboard::write(text)
+    }
+
+    /// The history suggestion for the command line at the focused pane's
+    /// prompt: what is typed and the rest of the suggested command. Only
+    /// offered with the cursor at the end of the line, so Tab and → keep
+    /// their meaning while a command is edited in the middle.
+    pub(in crate::gui) fn autocomplete(&self) -> Option<(String, String)> {
+        if !self.config.terminal.autocomplete || self.active_tab == SETTINGS_TAB_INDEX {
+            return None;
+        }
+        let pane = self.focused_pane()?;
+        if pane.input_locked
+            || pane.in_copy_mode()
+            || pane.secret_input()
+            || pane.scroll_position().0 != 0
+            || pane.input_after_cursor()
+        {
+            return None;
+        }
+        let typed = pane.typed_command()?;
+        let suggestion =
+            crate::terminal::history::suggest(&typed, pane.command_history(), &self.shell_history)?;
+        let rest = suggestion[typed.len()..].to_string();
+        Some((typed, rest))
+    }
+
+    pub(super) fn rerun_last_command(&mut self) {
+        let Some(pane) = self.focused_pane_mut() else {
+            return;
+        };
+        pane.rerun_last_command();
+        pane.scroll_to_bottom();
+        self.scroll_follow_bottom = true;
+    }
+
+    fn open_path(&mut self, pane: u64, path: &str, line: Option<u32>, column: Option<u32>) {
+        let Some(pane) = self.pane_mut_by_id(pane) else {
+            return;
+        };
+        // Paths printed by a remote shell name files on the remote host.
+        if let crate::gui::tab::TerminalSession::Active(session) = &pane.session
+            && session.ssh_handle().is_some()
+        {
+            return;
+        }
+        let cwd = pane.working_directory();
+        let Some(resolved) = crate::terminal::path::resolve(path, cwd.as_deref()) else {
+            eprintln!("Not opening {path}: no such file");
+            return;
+        };
+        match self
+            .config
+            .terminal
+            .editor_command
+            .as_deref()
+            .and_then(|template| {
+                crate::terminal::path::editor_argv(template, &resolved, line, column)
+            }) {
+            Some(argv) => crate::platform::open_in_editor(&argv),
+            None => crate::platform::open_path(&resolved),
+        }
+    }
+
+    pub(super) fn perform_paste(&mut self, text: String) -> Task<Message> {
         let config_bracketed_paste = self.config.terminal.bracketed_paste;
         if let Some(pane) = self.active_session_mut()
             && let crate::gui::tab::TerminalSession::Active(session) = &pane.session
         {
-            // pasted contents cannot break out of bracketed-paste framing.
-            let sanitized = text
-                .replace("\r\n", "\r")
-                .replace('\n', "\r")
-                .replace("\x1b[200~", "")
-                .replace("\x1b[201~", "");
-            let payload = if config_bracketed_paste && pane.bracketed_paste() {
-                format!("\x1b[200~{sanitized}\x1b[201~").into_bytes()
-            } else {
-                sanitized.into_bytes()
-            };
-            let _ = session.send_bytes(&payload);
+            let payload = paste::encode(&text, config_bracketed_paste && pane.bracketed_paste());
+            if let Err(err) = session.send_bytes_chunked(payload) {
+                eprintln!("Failed to paste: {err}");
+            }
             pane.scroll_to_bottom();
         }
         self.scroll_follow_bottom = true;
@@ -613,7 +1171,11 @@ impl App {
         self.window_style_applied = true;
 
         let theme = self.config.theme.clone();
-        iced::window::latest()
+        // Rescaled only fires on a change, so ask for the starting scale.
+        let scale = iced::window::latest()
+            .and_then(iced::window::scale_factor)
+            .map(Message::WindowRescaled);
+        let style = iced::window::latest()
             .and_then(move |id| {
                 let theme = theme.clone();
                 iced::window::run(id, move |window| {
@@ -624,7 +1186,8 @@ impl App {
                     }
                 })
             })
-            .discard()
+            .discard();
+        Task::batch([scale, style])
     }
 }
 
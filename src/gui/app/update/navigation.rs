@@ -0,0 +1,174 @@
+use super::super::{
+    App, ColorPickerMessage, Message, OnboardingMessage, SETTINGS_TAB_INDEX, SettingsMessage,
+};
+use crate::gui::settings::SettingsCategory;
+use iced::Task;
+use iced::keyboard::{Key, Modifiers, key::Named};
+use iced::widget::operation::{focus_next, focus_previous};
+
+impl App {
+    /// Keys that work the open dialog, menu or list, or the settings page,
+    /// without a mouse: Tab moves between fields, the arrows between rows,
+    /// Enter picks and Escape dismisses. `None` leaves the key to the rest
+    /// of `handle_key_pressed`.
+    pub(super) fn handle_navigation_key(
+        &mut self,
+        key: &Key,
+        modifiers: Modifiers,
+    ) -> Option<Task<Message>> {
+        if self.onboarding.is_some() {
+            return Some(match key {
+                Key::Named(Named::Enter) => {
+                    self.update(Message::Onboarding(OnboardingMessage::Next))
+                }
+                Key::Named(Named::Escape) => {
+                    self.update(Message::Onboarding(OnboardingMessage::Skip))
+                }
+                _ => Task::none(),
+            });
+        }
+        let Key::Named(named) = key else {
+            return self.dialog_open().then(Task::none);
+        };
+
+        if self.gpu_fallback.is_some() {
+            return Some(match named {
+                Named::Enter | Named::Escape => self.update(Message::DismissGpuFallback),
+                _ => Task::none(),
+            });
+        }
+        #[cfg(target_os = "macos")]
+        if self.show_restart_confirm {
+            return Some(match named {
+                Named::Enter => {
+                    self.update(Message::Settings(SettingsMessage::ConfirmRestartForBlur))
+                }
+                Named::Escape => {
+                    self.update(Message::Settings(SettingsMessage::CancelRestartForBlur))
+                }
+                _ => Task::none(),
+            });
+        }
+
+        if let Some((messages, dismiss)) = self.menu_messages() {
+            let count = messages.len();
+            match named {
+                Named::ArrowDown if count > 0 => {
+                    self.menu_cursor = Some(self.menu_cursor.map_or(0, |row| (row + 1) % count));
+                }
+                Named::ArrowUp if count > 0 => {
+                    self.menu_cursor = Some(
+                        self.menu_cursor
+                            .map_or(count - 1, |row| (row + count - 1) % count),
+                    );
+                }
+                // The tab list's filter field submits Enter itself.
+                Named::Enter if self.tab_list.is_none() => {
+                    let picked = self.menu_cursor.and_then(|row| messages.get(row)).cloned();
+                    if let Some(message) = picked {
+                        self.menu_cursor = None;
+                        return Some(self.update(message));
+                    }
+                }
+                Named::Escape => return Some(self.update(dismiss)),
+                _ => return None,
+            }
+            return Some(Task::none());
+        }
+
+        let on_settings = self.active_tab == SETTINGS_TAB_INDEX;
+        if (on_settings || self.dialog_open()) && *named == Named::Tab {
+            return Some(if modifiers.shift() {
+                focus_previous()
+            } else {
+                focus_next()
+            });
+        }
+        if self.password_prompt.is_some() {
+            return Some(match named {
+                Named::Escape => self.update(Message::SshPasswordPromptCancel),
+                _ => Task::none(),
+            });
+        }
+        if on_settings {
+            if *named == Named::Escape && self.color_picker.is_some() {
+                return Some(self.update(Message::Settings(SettingsMessage::ColorPicker(
+                    ColorPickerMessage::Cancel,
+                ))));
+            }
+            if *named == Named::Escape && self.settings_draft.profile_modal_mode.is_some() {
+                return Some(self.update(Message::Settings(SettingsMessage::CloseProfileModal)));
+            }
+            let step = match named {
+                Named::ArrowUp if modifiers.alt() => -1,
+                Named::ArrowDown if modifiers.alt() => 1,
+                _ => return None,
+            };
+            let categories = SettingsCategory::ALL;
+            let current = categories
+                .iter()
+                .position(|&category| category == self.settings_category)
+                .unwrap_or(0);
+            let next = (current as isize + step).rem_euclid(categories.len() as isize);
+            return Some(
+                self.update(Message::Settings(SettingsMessage::SelectCategory(
+                    categories[next as usize],
+                ))),
+            );
+        }
+        None
+    }
+
+    /// Dialogs with text fields, which take typing instead of the terminal.
+    fn dialog_open(&self) -> bool {
+        self.password_prompt.is_some()
+            || self.workspace_prompt.is_some()
+            || self.snippet_prompt.is_some()
+    }
+
+    /// What each row of the open context menu or list sends, and what
+    /// closes it.
+    fn menu_messages(&self) -> Option<(Vec<Message>, Message)> {
+        if let Some(index) = self.tab_context_menu {
+            let items = self.tab_context_menu_items(index);
+            return Some((
+                items.into_iter().map(|item| item.message).collect(),
+                Message::CloseTabContextMenu,
+            ));
+        }
+        if self.terminal_context_menu {
+            let items = self.terminal_context_menu_items();
+            return Some((
+                items.into_iter().map(|item| item.message).collect(),
+                Message::CloseTerminalContextMenu,
+            ));
+        }
+        if self.marks_list {
+            let marks = self
+                .focused_pane()
+                .map(|pane| pane.marks())
+                .unwrap_or_default();
+            return Some((
+                marks
+                    .into_iter()
+                    .rev()
+                    .map(|(mark, _)| Message::ScrollToMark(mark.line))
+                    .collect(),
+                Message::CloseMarksList,
+            ));
+        }
+        if self.tab_list.is_some() {
+            return Some((
+                self.tab_list_matches()
+                    .into_iter()
+                    .map(Message::TabListSelect)
+                    .collect(),
+                Message::ToggleTabList,
+            ));
+        }
+        if self.process_list.is_some() {
+            return Some((Vec::new(), Message::CloseProcessList));
+        }
+        None
+    }
+}
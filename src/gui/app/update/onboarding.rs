@@ -0,0 +1,75 @@
+use super::super::{App, Message, OnboardingMessage, OnboardingStep};
+use crate::config::{AppConfig, AppConfigUpdates, ShortcutsConfig};
+use crate::gui::settings::SettingsDraft;
+use iced::Task;
+
+impl App {
+    pub(super) fn update_onboarding(&mut self, message: OnboardingMessage) -> Task<Message> {
+        let Some(onboarding) = self.onboarding.as_mut() else {
+            return Task::none();
+        };
+        match message {
+            OnboardingMessage::PickTheme(name) => {
+                return self.apply_updates_to_runtime(theme_updates(&name));
+            }
+            OnboardingMessage::FontSize(size) => {
+                return self.apply_updates_to_runtime(AppConfigUpdates {
+                    terminal_font_size: Some(size),
+                    ..Default::default()
+                });
+            }
+            OnboardingMessage::PickProfile(name) => onboarding.default_profile = name,
+            OnboardingMessage::PickShortcuts(preset) => onboarding.shortcuts = preset,
+            OnboardingMessage::Back => {
+                let index = onboarding.step.index().saturating_sub(1);
+                onboarding.step = OnboardingStep::ALL[index];
+            }
+            OnboardingMessage::Next => match OnboardingStep::ALL.get(onboarding.step.index() + 1) {
+                Some(&step) => onboarding.step = step,
+                None => self.finish_onboarding(),
+            },
+            OnboardingMessage::Skip => {
+                self.onboarding = None;
+                // Undo what the theme and font steps showed.
+                let defaults = AppConfig::default();
+                let mut updates = theme_updates(&defaults.theme.color_scheme);
+                updates.terminal_font_size = Some(defaults.terminal.font_size);
+                let task = self.apply_updates_to_runtime(updates);
+                if let Err(err) = AppConfig::write_default_file() {
+                    eprintln!("Failed to write config: {err}");
+                }
+                return task;
+            }
+        }
+        Task::none()
+    }
+
+    /// Applies the picks that wait for the end and writes the config file.
+    fn finish_onboarding(&mut self) {
+        let Some(onboarding) = self.onboarding.take() else {
+            return;
+        };
+        self.config.terminal.default_profile = onboarding.default_profile;
+        self.config.shortcuts = ShortcutsConfig::from_preset(onboarding.shortcuts);
+        self.settings_draft = SettingsDraft::from_config(&self.config);
+        if let Err(err) = self.config.save() {
+            eprintln!("Failed to save config: {err}");
+        }
+    }
+}
+
+/// Switches to the color scheme `name`, with a preset's colors when it is
+/// one.
+fn theme_updates(name: &str) -> AppConfigUpdates {
+    let mut updates = AppConfigUpdates {
+        color_scheme: Some(name.to_string()),
+        ..Default::default()
+    };
+    if let Some(preset) = crate::terminal::theme::find_preset(name) {
+        updates.foreground = Some(preset.fg);
+        updates.background = Some(preset.bg);
+        updates.cursor = Some(preset.cursor);
+        updates.ansi_colors = Some(preset.ansi);
+    }
+    updates
+}
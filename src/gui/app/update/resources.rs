@@ -0,0 +1,46 @@
+use super::super::{App, Message};
+use crate::session::resources;
+use iced::Task;
+
+impl App {
+    pub(super) fn update_resources(&mut self, message: Message) -> Task<Message> {
+        match message {
+            // Reading every process blocks on the file system, so it runs on
+            // a blocking thread rather than the executor or the UI thread.
+            Message::SampleResources => {
+                return Task::perform(
+                    async {
+                        tokio::task::spawn_blocking(resources::snapshot)
+                            .await
+                            .ok()
+                            .flatten()
+                    },
+                    Message::ResourcesSampled,
+                );
+            }
+            Message::ResourcesSampled(Some(table)) => {
+                let previous = self.process_table.take();
+                self.tab_resources = self
+                    .tabs
+                    .iter()
+                    .filter_map(|tab| {
+                        let roots: Vec<u32> = tab
+                            .panes
+                            .iter()
+                            .filter_map(|pane| pane.process_id())
+                            .collect();
+                        (!roots.is_empty())
+                            .then(|| (tab.id, table.usage(previous.as_ref(), &roots)))
+                    })
+                    .collect();
+                self.process_table = Some(table);
+            }
+            Message::ShowProcessList(index) => {
+                self.process_list = self.tabs.get(index).map(|tab| tab.id);
+            }
+            Message::CloseProcessList => self.process_list = None,
+            _ => {}
+        }
+        Task::none()
+    }
+}
@@ -0,0 +1,134 @@
+use super::super::{App, Message, SETTINGS_TAB_INDEX};
+use crate::gui::render::{CaptureRequest, CaptureResult, ClipRenderer, ClipStyle};
+use crate::terminal::TerminalTheme;
+use crate::terminal::recording::Recording;
+use iced::Task;
+
+/// Asks where to save `data`, offering `name`, and writes it there.
+fn save_as(data: Vec<u8>, name: String, filter: (&'static str, &'static str)) -> Task<Message> {
+    Task::perform(
+        async move {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .set_file_name(name)
+                .add_filter(filter.0, &[filter.1])
+                .save_file()
+                .await
+            else {
+                return Ok(());
+            };
+            file.write(&data).await
+        },
+        |result| {
+            if let Err(err) = result {
+                eprintln!("[export] {err}");
+            }
+            Message::Noop
+        },
+    )
+}
+
+/// Asks where to save `recording` and writes it there: as an asciicast when
+/// a `.cast` name is chosen, otherwise drawn into a GIF. Drawing replays the
+/// whole recording on a blocking thread, after the dialog has closed.
+fn save_recording(recording: Recording, style: ClipStyle, name: String) -> Task<Message> {
+    Task::perform(
+        async move {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .set_file_name(name)
+                .add_filter("GIF", &["gif"])
+                .add_filter("asciicast", &["cast"])
+                .save_file()
+                .await
+            else {
+                return Ok(());
+            };
+            let cast = file
+                .path()
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("cast"));
+            let data = if cast {
+                recording.to_asciicast(0.0..f64::INFINITY).into_bytes()
+            } else {
+                let renderer = ClipRenderer::new().await?;
+                tokio::task::spawn_blocking(move || renderer.gif(&recording, &style))
+                    .await
+                    .map_err(|err| err.to_string())??
+            };
+            file.write(&data).await.map_err(|err| err.to_string())
+        },
+        |result: Result<(), String>| {
+            if let Err(err) = result {
+                eprintln!("[export] {err}");
+            }
+            Message::Noop
+        },
+    )
+}
+
+impl App {
+    /// Asks the renderer for the active tab's view, or only the rows of the
+    /// focused pane's selection, and saves it once read back.
+    pub(super) fn start_capture(&mut self) -> Task<Message> {
+        if self.capture.is_some() || self.active_tab == SETTINGS_TAB_INDEX {
+            return Task::none();
+        }
+        let Some(pane) = self.focused_pane() else {
+            return Task::none();
+        };
+        self.capture_seq += 1;
+        let (request, receiver) = CaptureRequest::new(self.capture_seq, pane.selected_rows());
+        self.capture = Some(request);
+        Task::perform(
+            async move {
+                receiver
+                    .await
+                    .unwrap_or_else(|_| Err("the renderer dropped the request".to_string()))
+            },
+            Message::ScreenshotCaptured,
+        )
+    }
+
+    /// Offers to save the captured PNG.
+    pub(super) fn save_screenshot(&mut self, result: CaptureResult) -> Task<Message> {
+        self.capture = None;
+        let png = match result {
+            Ok(png) => png,
+            Err(err) => {
+                eprintln!("[screenshot] {err}");
+                return Task::none();
+            }
+        };
+        let name = chrono::Local::now()
+            .format("RabbiTTY %Y-%m-%d at %H.%M.%S.png")
+            .to_string();
+        save_as(png, name, ("PNG", "png"))
+    }
+
+    /// Starts recording the focused pane, or stops and offers to save the
+    /// recording as a GIF or an asciicast.
+    pub(in crate::gui::app) fn toggle_recording(&mut self) -> Task<Message> {
+        let Some(recording) = self
+            .focused_pane_mut()
+            .and_then(|pane| pane.toggle_recording())
+        else {
+            return Task::none();
+        };
+        let style = ClipStyle {
+            theme: TerminalTheme::from_config(&self.config),
+            cell_size: self.cell_size(),
+            padding: [
+                self.config.terminal.padding_x,
+                self.config.terminal.padding_y,
+            ],
+            font_selection: self.config.terminal.font_selection.clone(),
+            font_size: self.config.terminal.font_size,
+            cursor_shape: self.config.terminal.cursor_shape,
+            text_rendering: self.config.terminal.text_rendering,
+            font_synthesize: self.config.terminal.font_synthesize,
+        };
+        let name = chrono::Local::now()
+            .format("RabbiTTY %Y-%m-%d at %H.%M.%S.gif")
+            .to_string();
+        save_recording(recording, style, name)
+    }
+}
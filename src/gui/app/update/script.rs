@@ -0,0 +1,71 @@
+use super::super::{App, Message};
+use crate::gui::tab::Profile;
+use crate::script::ScriptAction;
+use iced::Task;
+
+impl App {
+    /// Carries out what a script queued, in order. Text and titles go to
+    /// the focused pane, which is the tab opened last.
+    pub(super) fn run_script_actions(&mut self, actions: Vec<ScriptAction>) -> Task<Message> {
+        let mut tasks = Vec::new();
+        for action in actions {
+            match action {
+                ScriptAction::OpenTab(name) => {
+                    let profile = match name {
+                        Some(name) => match self.profile_named(&name) {
+                            Some(profile) => profile,
+                            None => {
+                                eprintln!("Script: no profile named {name:?}");
+                                continue;
+                            }
+                        },
+                        None => self.default_profile(),
+                    };
+                    tasks.push(self.create_tab(profile));
+                }
+                ScriptAction::Send(text) => {
+                    if self.tabs.is_empty() {
+                        let profile = self.default_profile();
+                        tasks.push(self.create_tab(profile));
+                    }
+                    if let Some(pane) = self.focused_pane() {
+                        pane.type_text(&text);
+                    }
+                }
+                ScriptAction::SetTitle(title) => {
+                    if let Some(pane) = self.focused_pane_mut() {
+                        pane.set_title(title);
+                    }
+                }
+            }
+        }
+        Task::batch(tasks)
+    }
+
+    /// Runs the script function bound to a key.
+    pub(super) fn run_script_binding(&mut self, index: usize) -> Task<Message> {
+        let screen = self
+            .focused_pane()
+            .map(|pane| pane.screen_text())
+            .unwrap_or_default();
+        let Some(scripts) = self.scripts.as_ref() else {
+            return Task::none();
+        };
+        match scripts.run_binding(index, screen) {
+            Ok(actions) => self.run_script_actions(actions),
+            Err(err) => {
+                eprintln!("Script binding failed: {err}");
+                Task::none()
+            }
+        }
+    }
+
+    /// A saved, SSH or detected profile by its name, ignoring case.
+    pub(super) fn profile_named(&self, name: &str) -> Option<Profile> {
+        self.session_local_profiles()
+            .into_iter()
+            .chain(self.session_ssh_profiles().into_iter().map(Profile::ssh))
+            .chain(self.available_shells.iter().cloned())
+            .find(|profile| profile.display_name().eq_ignore_ascii_case(name))
+    }
+}
@@ -1,9 +1,13 @@
-use super::super::{App, Message, SETTINGS_TAB_INDEX, SettingsMessage};
-use crate::config::AppConfigUpdates;
+use super::super::{App, ColorPickerMessage, Message, SETTINGS_TAB_INDEX, SettingsMessage};
+use super::SETTINGS_SCROLLABLE_ID;
+use crate::config::{AppConfigUpdates, StatusSegment};
 use crate::gui::settings::SettingsField;
+use crate::gui::settings::color_picker::ColorPicker;
 use crate::gui::settings::{
-    ProfileDraft, ProfileDraftKind, ProfileTemplate, SettingsDraft, TemplateGroup,
+    EnvEdit, ProfileDraft, ProfileDraftKind, ProfileTemplate, SettingsDraft, SnippetEdit,
+    TemplateGroup, apply_env_edit, apply_snippet_edit, env_from_drafts, format_rgb,
 };
+use crate::session::integration::{IntegrationReport, IntegrationShell};
 use crate::terminal::TerminalTheme;
 use iced::time::Instant;
 use iced::{Size, Task, window};
@@ -37,6 +41,78 @@ impl App {
         resize_task
     }
 
+    /// Which shells have the integration snippet installed, and how many
+    /// open panes their shell is reporting prompts and directories from.
+    fn shell_integration_report(&self) -> IntegrationReport {
+        let reports: Vec<(bool, bool)> = self
+            .tabs
+            .iter()
+            .flat_map(|tab| &tab.panes)
+            .map(|pane| pane.integration_reports())
+            .collect();
+        IntegrationReport {
+            installed: IntegrationShell::ALL
+                .into_iter()
+                .map(|shell| (shell, shell.is_installed()))
+                .collect(),
+            panes: reports.len(),
+            prompts: reports.iter().filter(|(prompts, _)| *prompts).count(),
+            directories: reports.iter().filter(|(_, cwd)| *cwd).count(),
+        }
+    }
+
+    /// Applies and saves the draft once typing pauses for 500 ms.
+    fn debounce_settings_commit(&mut self) -> Task<Message> {
+        self.settings_debounce_seq = self.settings_debounce_seq.wrapping_add(1);
+        if self.settings_debounce_pending {
+            return Task::none();
+        }
+        self.settings_debounce_pending = true;
+        self.settings_debounce_spawned_seq = self.settings_debounce_seq;
+        Task::perform(
+            async {
+                std::thread::sleep(std::time::Duration::from_millis(500));
+            },
+            |()| Message::Settings(SettingsMessage::CommitDebounce),
+        )
+    }
+
+    /// Every color change shows in the terminal right away; the config is
+    /// saved when the picker is closed with Done.
+    fn update_color_picker(&mut self, message: ColorPickerMessage) -> Task<Message> {
+        match message {
+            ColorPickerMessage::Open(field) => {
+                let current = self.settings_draft.text_of(field).unwrap_or_default();
+                self.color_picker = Some(ColorPicker::open(field, current));
+                Task::none()
+            }
+            ColorPickerMessage::Done => {
+                if self.color_picker.take().is_none() {
+                    return Task::none();
+                }
+                self.apply_settings(true)
+            }
+            ColorPickerMessage::Cancel => {
+                let Some(picker) = self.color_picker.take() else {
+                    return Task::none();
+                };
+                self.settings_draft.update(picker.field, picker.original);
+                self.apply_settings(false)
+            }
+            message => {
+                let Some(picker) = self.color_picker.as_mut() else {
+                    return Task::none();
+                };
+                if !picker.update(&message) {
+                    return Task::none();
+                }
+                let (field, hex) = (picker.field, format_rgb(picker.rgb()));
+                self.settings_draft.update(field, hex);
+                self.apply_settings(false)
+            }
+        }
+    }
+
     pub(super) fn queue_config_save(&self) {
         let _ = self.config_save_tx.send(self.config.clone());
     }
@@ -52,22 +128,46 @@ impl App {
             || updates.background_opacity.is_some()
             || updates.blur_enabled.is_some()
             || updates.macos_blur_radius.is_some();
+        let affects_palette = affects_theme || updates.high_contrast.is_some();
         let affects_grid = updates.window_width.is_some()
             || updates.window_height.is_some()
             || updates.terminal_font_selection.is_some()
             || updates.terminal_font_size.is_some()
+            || updates.terminal_line_height.is_some()
+            || updates.terminal_letter_spacing.is_some()
             || updates.terminal_padding_x.is_some()
-            || updates.terminal_padding_y.is_some();
+            || updates.terminal_padding_y.is_some()
+            || updates.terminal_timestamps.is_some()
+            || updates.terminal_wrap_indicator.is_some()
+            || updates.large_text.is_some();
         let affects_window = updates.window_width.is_some() || updates.window_height.is_some();
+        let affects_passthrough = updates.terminal_tmux_passthrough.is_some();
 
         self.config.apply_updates(updates);
-        self.settings_draft = SettingsDraft::from_config(&self.config);
+        let previous = std::mem::replace(
+            &mut self.settings_draft,
+            SettingsDraft::from_config(&self.config),
+        );
+        // Invalid values stay on screen with their error instead of
+        // snapping back to what is applied.
+        self.settings_draft.keep_invalid_fields(&previous);
+        // Keep environment rows that are still being typed, or that will be
+        // skipped, unless the applied variables differ from them.
+        if env_from_drafts(&previous.env) == self.config.terminal.env {
+            self.settings_draft.env = previous.env;
+        }
 
         if affects_locale {
             crate::i18n::set_locale(self.config.ui.language.as_deref());
         }
-        if affects_theme {
-            self.palette = crate::gui::theme::Palette::from_theme(&self.config.theme);
+        if affects_passthrough {
+            let enabled = self.config.terminal.tmux_passthrough;
+            for pane in self.panes_mut() {
+                pane.set_tmux_passthrough(enabled);
+            }
+        }
+        if affects_palette {
+            self.palette = crate::gui::theme::Palette::from_config(&self.config);
         }
 
         let resize_task = if affects_window {
@@ -131,7 +231,7 @@ impl App {
         self.pending_save_on_restart = false;
 
         if restart_spawned {
-            return iced::exit();
+            return self.quit();
         }
 
         Task::none()
@@ -152,6 +252,10 @@ impl App {
                 group: TemplateGroup::Basic,
                 draft: ProfileDraft::default(),
             },
+            ProfileTemplate {
+                group: TemplateGroup::Basic,
+                draft: ProfileDraft::network(),
+            },
         ];
         out.extend(self.available_shells.iter().map(|shell| ProfileTemplate {
             group: TemplateGroup::Shell,
@@ -211,6 +315,25 @@ impl App {
             SettingsMessage::ProfileModalTabSelected(tab) => {
                 self.settings_draft.set_profile_modal_tab(tab);
             }
+            SettingsMessage::ProfileEnvEdited(edit) => {
+                self.settings_draft.edit_profile_modal_env(edit);
+            }
+            SettingsMessage::GlobalEnvEdited(edit) => {
+                let typing = matches!(edit, EnvEdit::Name(..) | EnvEdit::Value(..));
+                apply_env_edit(&mut self.settings_draft.env, edit);
+                if typing {
+                    return self.debounce_settings_commit();
+                }
+                return self.apply_settings(true);
+            }
+            SettingsMessage::SnippetEdited(edit) => {
+                let typing = matches!(edit, SnippetEdit::Name(..) | SnippetEdit::Command(..));
+                apply_snippet_edit(&mut self.settings_draft.snippets, edit);
+                if typing {
+                    return self.debounce_settings_commit();
+                }
+                return self.apply_settings(true);
+            }
             SettingsMessage::TestSshConnection => match self
                 .settings_draft
                 .begin_ssh_connection_test()
@@ -225,6 +348,14 @@ impl App {
                     eprintln!("Failed to start SSH connection test: {err}");
                 }
             },
+            SettingsMessage::CheckShellIntegration => {
+                self.settings_draft.shell_integration_error = None;
+                self.settings_draft.shell_integration = Some(self.shell_integration_report());
+            }
+            SettingsMessage::InstallShellIntegration(shell) => {
+                self.settings_draft.shell_integration_error = shell.install().err();
+                self.settings_draft.shell_integration = Some(self.shell_integration_report());
+            }
             SettingsMessage::SshConnectionTestFinished(result) => {
                 self.settings_draft.finish_ssh_connection_test(result);
             }
@@ -256,6 +387,7 @@ impl App {
                 self.settings_draft = SettingsDraft::from_config(&self.config);
             }
             SettingsMessage::SelectCategory(category) => {
+                self.settings_search.clear();
                 if !self.settings_open {
                     self.settings_open = true;
                     self.active_tab = SETTINGS_TAB_INDEX;
@@ -270,21 +402,25 @@ impl App {
                     self.settings_category = immediate;
                 }
             }
-            SettingsMessage::InputChanged(field, value) => {
-                self.settings_draft.update(field, value);
-                self.settings_debounce_seq = self.settings_debounce_seq.wrapping_add(1);
-                if self.settings_debounce_pending {
-                    return Task::none();
-                }
-                self.settings_debounce_pending = true;
-                self.settings_debounce_spawned_seq = self.settings_debounce_seq;
-                return Task::perform(
-                    async {
-                        std::thread::sleep(std::time::Duration::from_millis(500));
+            SettingsMessage::SearchChanged(query) => {
+                self.settings_search = query;
+            }
+            SettingsMessage::JumpToSetting(category, position) => {
+                self.settings_search.clear();
+                // Jump without the cross-fade so the scroll lands on the new page.
+                self.settings_category = category;
+                return iced::widget::operation::snap_to(
+                    SETTINGS_SCROLLABLE_ID.clone(),
+                    iced::widget::scrollable::RelativeOffset {
+                        x: 0.0,
+                        y: position,
                     },
-                    |()| Message::Settings(SettingsMessage::CommitDebounce),
                 );
             }
+            SettingsMessage::InputChanged(field, value) => {
+                self.settings_draft.update(field, value);
+                return self.debounce_settings_commit();
+            }
             SettingsMessage::InputCommitted(field, value) => {
                 self.settings_draft.update(field, value);
                 self.settings_debounce_spawned_seq = self.settings_debounce_seq;
@@ -315,10 +451,48 @@ impl App {
                 self.settings_draft.tab_bar_position = pos;
                 return self.apply_settings(true);
             }
+            SettingsMessage::StatusBarToggled(enabled) => {
+                self.settings_draft.status_bar = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::HighContrastToggled(enabled) => {
+                self.settings_draft.high_contrast = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::LargeTextToggled(enabled) => {
+                self.settings_draft.large_text = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::StatusSegmentToggled(segment, shown) => {
+                let segments = &mut self.settings_draft.status_segments;
+                segments.retain(|&s| s != segment);
+                if shown {
+                    // Goes back before the first segment that comes after it
+                    // by default, keeping any order set in the config file.
+                    let rank = |s: &StatusSegment| StatusSegment::ALL.iter().position(|a| a == s);
+                    let index = segments
+                        .iter()
+                        .position(|s| rank(s) > rank(&segment))
+                        .unwrap_or(segments.len());
+                    segments.insert(index, segment);
+                }
+                return self.apply_settings(true);
+            }
             SettingsMessage::BracketedPasteToggled(enabled) => {
                 self.settings_draft.bracketed_paste = enabled;
                 return self.apply_settings(true);
             }
+            SettingsMessage::AutocompleteToggled(enabled) => {
+                self.settings_draft.autocomplete = enabled;
+                if enabled && self.shell_history.is_empty() {
+                    self.shell_history = crate::terminal::history::load();
+                }
+                return self.apply_settings(true);
+            }
+            SettingsMessage::SmoothScrollToggled(enabled) => {
+                self.settings_draft.smooth_scroll = enabled;
+                return self.apply_settings(true);
+            }
             SettingsMessage::MultilinePasteConfirmToggled(enabled) => {
                 self.settings_draft.multiline_paste_confirm = enabled;
                 return self.apply_settings(true);
@@ -331,6 +505,60 @@ impl App {
                 self.settings_draft.cursor_blink = enabled;
                 return self.apply_settings(true);
             }
+            SettingsMessage::CursorTrailToggled(enabled) => {
+                self.settings_draft.cursor_trail = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::HighlightCursorRowToggled(enabled) => {
+                self.settings_draft.highlight_cursor_row = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::WrapIndicatorToggled(enabled) => {
+                self.settings_draft.wrap_indicator = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::DimUnfocusedWindowToggled(enabled) => {
+                self.settings_draft.dim_unfocused_window = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::PresentModeSelected(mode) => {
+                self.settings_draft.present_mode = mode;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::PauseWhenMinimizedToggled(enabled) => {
+                self.settings_draft.pause_when_minimized = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::GpuBackendSelected(backend) => {
+                self.settings_draft.gpu_backend = backend;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::GpuAdapterSelected(adapter) => {
+                self.settings_draft.gpu_adapter = adapter;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::LocalEchoToggled(enabled) => {
+                self.settings_draft.local_echo = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::TmuxPassthroughToggled(enabled) => {
+                self.settings_draft.tmux_passthrough = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::PersistentSessionsToggled(enabled) => {
+                self.settings_draft.persistent_sessions = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::SecureKeyboardEntryToggled(enabled) => {
+                self.settings_draft.secure_keyboard_entry = enabled;
+                let task = self.apply_settings(true);
+                self.sync_secure_input();
+                return task;
+            }
+            SettingsMessage::ConfirmLinksToggled(enabled) => {
+                self.settings_draft.confirm_links = enabled;
+                return self.apply_settings(true);
+            }
             SettingsMessage::BoldIsBrightToggled(enabled) => {
                 self.settings_draft.bold_is_bright = enabled;
                 return self.apply_settings(true);
@@ -339,10 +567,30 @@ impl App {
                 self.settings_draft.bell_mode = mode;
                 return self.apply_settings(true);
             }
+            SettingsMessage::BellAttentionToggled(enabled) => {
+                self.settings_draft.bell_attention = enabled;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::TimestampModeSelected(mode) => {
+                self.settings_draft.timestamps = mode;
+                return self.apply_settings(true);
+            }
             SettingsMessage::RightClickActionSelected(action) => {
                 self.settings_draft.right_click_action = action;
                 return self.apply_settings(true);
             }
+            SettingsMessage::ExitBehaviorSelected(behavior) => {
+                self.settings_draft.exit_behavior = behavior;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::TextRenderingSelected(rendering) => {
+                self.settings_draft.text_rendering = rendering;
+                return self.apply_settings(true);
+            }
+            SettingsMessage::FontSynthesizeToggled(enabled) => {
+                self.settings_draft.font_synthesize = enabled;
+                return self.apply_settings(true);
+            }
             SettingsMessage::FontSelected(option) => {
                 self.settings_draft
                     .update(SettingsField::TerminalFontSelection, option.value);
@@ -357,6 +605,7 @@ impl App {
                 );
                 return self.apply_settings(true);
             }
+            SettingsMessage::ColorPicker(message) => return self.update_color_picker(message),
             #[cfg(target_os = "macos")]
             SettingsMessage::ConfirmRestartForBlur => {
                 return self.handle_confirm_restart();
@@ -133,6 +133,11 @@ impl App {
                 {
                     let base = pane.sftp.current_path.clone();
                     for local in files {
+                        if local.is_dir() {
+                            pane.sftp.error =
+                                Some(format!("{}: folders can't be uploaded", local.display()));
+                            continue;
+                        }
                         let name = local
                             .file_name()
                             .and_then(|s| s.to_str())
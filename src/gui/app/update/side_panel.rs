@@ -0,0 +1,119 @@
+use super::super::{App, Message, SidePanel};
+use crate::plugin::{CommandContext, Effect};
+use crate::terminal::assist::{self, AssistAction};
+use iced::Task;
+use std::future::Future;
+
+impl App {
+    pub(super) fn update_side_panel(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Assist(action) => {
+                self.terminal_context_menu = false;
+                let Some(target) = self.config.terminal.assist_command.clone() else {
+                    return Task::none();
+                };
+                let Some(pane) = self.focused_pane_mut() else {
+                    return Task::none();
+                };
+                let Some(text) = pane.selected_text().or_else(|| pane.select_last_output()) else {
+                    return Task::none();
+                };
+                let title = match action {
+                    AssistAction::Explain => crate::t!("context_menu.explain"),
+                    AssistAction::Fix => crate::t!("context_menu.fix"),
+                };
+                return self.open_side_panel(title.to_string(), assist::run(target, action, text));
+            }
+            Message::RunPluginCommand(index, id) => {
+                self.dismiss_shell_picker();
+                let Some(pane) = self.focused_pane() else {
+                    return Task::none();
+                };
+                let context = CommandContext {
+                    pane: pane.id,
+                    selection: pane.selected_text(),
+                    cwd: pane.current_directory(),
+                };
+                let effects = self.plugins.run_command(index, &id, &context);
+                return self.apply_plugin_effects(context.pane, effects);
+            }
+            Message::SidePanelReply(seq, reply) => {
+                // A closed panel or a newer request drops the reply.
+                if let Some(panel) = self.side_panel.as_mut()
+                    && panel.seq == seq
+                {
+                    panel.reply = Some(reply);
+                }
+            }
+            Message::CopySidePanel => {
+                if let Some(Ok(reply)) = self
+                    .side_panel
+                    .as_ref()
+                    .and_then(|panel| panel.reply.as_ref())
+                {
+                    return iced::clipboard::write(reply.clone());
+                }
+            }
+            Message::CloseSidePanel => {
+                self.side_panel = None;
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+
+    /// Carries out what plugins asked for on behalf of pane `pane`.
+    pub(super) fn apply_plugin_effects(
+        &mut self,
+        pane: u64,
+        effects: Vec<(String, Effect)>,
+    ) -> Task<Message> {
+        let mut tasks = Vec::new();
+        for (plugin, effect) in effects {
+            match effect {
+                Effect::Write(bytes) => {
+                    if let Some(session) = self
+                        .pane_mut_by_id(pane)
+                        .and_then(|pane| pane.input_session())
+                    {
+                        let _ = session.send_bytes(&bytes);
+                    }
+                }
+                Effect::Notify(text) => {
+                    crate::platform::notify(&plugin, &text);
+                    tasks.push(self.request_attention());
+                }
+                Effect::Panel { title, body } => {
+                    self.side_panel_seq += 1;
+                    self.side_panel = Some(SidePanel {
+                        title,
+                        seq: self.side_panel_seq,
+                        reply: Some(Ok(body)),
+                    });
+                }
+                Effect::Run { argv, input, title } => {
+                    tasks.push(self.open_side_panel(title, async move {
+                        crate::platform::pipe_command(&argv, &[], input).await
+                    }));
+                }
+            }
+        }
+        Task::batch(tasks)
+    }
+
+    /// Opens the side panel waiting for `reply`, replacing what it showed.
+    fn open_side_panel(
+        &mut self,
+        title: String,
+        reply: impl Future<Output = Result<String, String>> + Send + 'static,
+    ) -> Task<Message> {
+        self.side_panel_seq += 1;
+        let seq = self.side_panel_seq;
+        self.side_panel = Some(SidePanel {
+            title,
+            seq,
+            reply: None,
+        });
+        Task::perform(reply, move |reply| Message::SidePanelReply(seq, reply))
+    }
+}
@@ -0,0 +1,46 @@
+use super::super::{App, Message, SnippetPrompt};
+use super::SNIPPET_INPUT_ID;
+use iced::Task;
+
+impl App {
+    pub(super) fn update_snippet(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::UseSnippet(index) => {
+                self.dismiss_shell_picker();
+                let Some(snippet) = self.config.snippets.get(index).cloned() else {
+                    return Task::none();
+                };
+                let placeholders = snippet.placeholders();
+                if placeholders.is_empty() {
+                    return self.perform_paste(snippet.command);
+                }
+                self.snippet_prompt = Some(SnippetPrompt {
+                    values: placeholders.iter().map(|p| p.default.clone()).collect(),
+                    placeholders,
+                    snippet,
+                });
+                return iced::widget::operation::focus(SNIPPET_INPUT_ID.clone());
+            }
+            Message::SnippetValueChanged(index, value) => {
+                if let Some(slot) = self
+                    .snippet_prompt
+                    .as_mut()
+                    .and_then(|prompt| prompt.values.get_mut(index))
+                {
+                    *slot = value;
+                }
+            }
+            Message::InsertSnippet => {
+                if let Some(prompt) = self.snippet_prompt.take() {
+                    // Typed, not run: the user reviews it and presses Enter.
+                    return self.perform_paste(prompt.snippet.expand(&prompt.values));
+                }
+            }
+            Message::CloseSnippetPrompt => {
+                self.snippet_prompt = None;
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+}
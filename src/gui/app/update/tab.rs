@@ -1,28 +1,48 @@
-use super::super::shortcuts::ShortcutAction;
-use super::super::{App, Message, SETTINGS_TAB_INDEX};
+use super::super::shortcuts::{ShortcutAction, shortcut_matches};
+use super::super::{
+    App, CLOSED_TAB_GRACE, CLOSED_TAB_HISTORY, ClosedTab, Message, SETTINGS_TAB_INDEX,
+};
 use crate::config::SshProfile;
 use crate::gui::pane::Axis;
 use crate::gui::settings::SettingsDraft;
-use crate::gui::tab::{Profile, ProfileKind};
+use crate::gui::tab::{
+    LocalHost, Pane, PaneSpawn, Profile, ProfileKind, TerminalSession, TerminalTab,
+};
+use crate::session::daemon::SessionInfo;
 use crate::terminal::TerminalTheme;
 use iced::Task;
 use iced::keyboard::Modifiers;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Most-used sessions listed above the rest of the picker.
+const RECENT_PICKER_ENTRIES: usize = 5;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(in crate::gui) enum PickerSection {
+    Recent,
     Ssh,
     Profiles,
     SshConfig,
+    Containers,
     Builtin,
+    Workspaces,
+    Snippets,
+    Plugins,
 }
 
 impl PickerSection {
     pub(in crate::gui) fn label(self) -> &'static str {
         match self {
+            Self::Recent => crate::t!("shell_picker.recent"),
             Self::Ssh => crate::t!("shell_picker.ssh"),
             Self::Profiles => crate::t!("shell_picker.profiles"),
             Self::SshConfig => crate::t!("shell_picker.ssh_config"),
+            Self::Containers => crate::t!("shell_picker.containers"),
             Self::Builtin => crate::t!("shell_picker.builtin"),
+            Self::Workspaces => crate::t!("shell_picker.workspaces"),
+            Self::Snippets => crate::t!("shell_picker.snippets"),
+            Self::Plugins => crate::t!("shell_picker.plugins"),
         }
     }
 }
@@ -31,7 +51,18 @@ pub(in crate::gui) struct PickerEntry {
     pub section: PickerSection,
     pub label: String,
     pub subtitle: Option<String>,
-    pub profile: Profile,
+    pub target: PickerTarget,
+}
+
+/// What picking an entry opens.
+pub(in crate::gui) enum PickerTarget {
+    Profile(Profile),
+    /// A saved workspace, by name.
+    Workspace(String),
+    /// A configured snippet, by index, typed into the active tab.
+    Snippet(usize),
+    /// A plugin's command: the plugin's index and the command's id.
+    PluginCommand(usize, String),
 }
 
 fn local_subtitle(profile: &Profile) -> String {
@@ -42,6 +73,11 @@ fn local_subtitle(profile: &Profile) -> String {
         } => path.clone(),
         ProfileKind::Local { program: None, .. } => crate::t!("shell_picker.default").to_string(),
         ProfileKind::Ssh(ssh) => format!("{}:{}", ssh.host, ssh.port),
+        ProfileKind::Network {
+            host,
+            port,
+            protocol,
+        } => format!("{} {host}:{port}", protocol.scheme()),
     }
 }
 
@@ -57,8 +93,7 @@ impl App {
 
     pub(in crate::gui) fn focused_pane_rect(&self) -> Option<iced::Rectangle> {
         let tab = self.tabs.get(self.active_tab)?;
-        tab.layout
-            .regions(self.terminal_area_rect())
+        tab.regions(self.terminal_area_rect())
             .into_iter()
             .find(|(id, _)| *id == tab.focused)
             .map(|(_, rect)| rect)
@@ -78,17 +113,18 @@ impl App {
             .tabs
             .iter()
             .map(|tab| {
-                tab.layout
-                    .regions(area)
+                tab.regions(area)
                     .into_iter()
                     .map(|(id, rect)| (id, self.grid_for_rect(rect)))
                     .collect()
             })
             .collect();
 
+        let cell_size = self.cell_size();
         for (tab, grids) in self.tabs.iter_mut().zip(grids) {
             for (id, (cols, rows)) in grids {
                 if let Some(pane) = tab.pane_mut(id) {
+                    pane.set_cell_pixel_size(cell_size);
                     let current = pane.size();
                     if current.columns != cols || current.lines != rows {
                         pane.resize(cols, rows);
@@ -105,28 +141,12 @@ impl App {
         let Some(profile) = self.focused_pane().map(|pane| pane.profile.clone()) else {
             return Task::none();
         };
-        let Some(sender) = self.pty_sender.clone() else {
-            return Task::none();
-        };
         let cwd = self
             .focused_pane()
             .and_then(|pane| pane.working_directory());
-
-        let (cols, rows) = self.grid_for_size(self.window_size);
-        let theme = TerminalTheme::from_config(&self.config);
-        let pane_id = self.next_tab_id;
-        self.next_tab_id = self.next_tab_id.wrapping_add(1);
-
-        let pane = crate::gui::tab::Pane::from_profile(crate::gui::tab::PaneSpawn {
-            profile,
-            columns: cols,
-            lines: rows,
-            theme,
-            id: pane_id,
-            output_tx: sender,
-            scrollback_lines: self.config.terminal.scrollback_lines,
-            cwd,
-        });
+        let Some(pane) = self.spawn_pane(profile, cwd) else {
+            return Task::none();
+        };
         if let Some(tab) = self.tabs.get_mut(self.active_tab) {
             tab.split(axis, pane);
         }
@@ -151,14 +171,14 @@ impl App {
 }
 
 impl App {
-    pub(in crate::gui) fn focused_pane(&self) -> Option<&crate::gui::tab::Pane> {
+    pub(in crate::gui) fn focused_pane(&self) -> Option<&Pane> {
         if self.active_tab == SETTINGS_TAB_INDEX {
             return None;
         }
         self.tabs.get(self.active_tab).map(|tab| tab.focused())
     }
 
-    pub(in crate::gui) fn focused_pane_mut(&mut self) -> Option<&mut crate::gui::tab::Pane> {
+    pub(in crate::gui) fn focused_pane_mut(&mut self) -> Option<&mut Pane> {
         if self.active_tab == SETTINGS_TAB_INDEX {
             return None;
         }
@@ -167,13 +187,33 @@ impl App {
             .map(|tab| tab.focused_mut())
     }
 
-    pub(in crate::gui) fn pane_mut_by_id(&mut self, id: u64) -> Option<&mut crate::gui::tab::Pane> {
+    pub(in crate::gui) fn pane_mut_by_id(&mut self, id: u64) -> Option<&mut Pane> {
         self.tabs.iter_mut().find_map(|tab| tab.pane_mut(id))
     }
 
-    pub(in crate::gui) fn panes_mut(&mut self) -> impl Iterator<Item = &mut crate::gui::tab::Pane> {
+    pub(in crate::gui) fn panes_mut(&mut self) -> impl Iterator<Item = &mut Pane> {
         self.tabs.iter_mut().flat_map(|tab| tab.panes.iter_mut())
     }
+
+    /// Patterns panes mask: the configured ones in privacy mode, else none.
+    fn redact_patterns(&self) -> &[String] {
+        if self.privacy_mode {
+            &self.config.terminal.redact_patterns
+        } else {
+            &[]
+        }
+    }
+
+    /// Exits the app, detaching daemon-hosted shells first so they keep
+    /// running for the next launch.
+    pub(in crate::gui) fn quit(&mut self) -> Task<Message> {
+        for pane in self.panes_mut() {
+            if let TerminalSession::Active(session) = &mut pane.session {
+                session.detach();
+            }
+        }
+        iced::exit()
+    }
 }
 
 impl App {
@@ -208,35 +248,145 @@ impl App {
         }
     }
 
-    pub(in crate::gui) fn create_tab(&mut self, profile: Profile) -> Task<Message> {
+    /// Starts a session for `profile` in a pane sized to the window, with a
+    /// fresh id. `None` until the PTY output channel is ready.
+    pub(in crate::gui) fn spawn_pane(
+        &mut self,
+        profile: Profile,
+        cwd: Option<PathBuf>,
+    ) -> Option<Pane> {
+        let host = if self.config.terminal.persistent_sessions {
+            LocalHost::Daemon
+        } else {
+            LocalHost::InProcess
+        };
+        let env = self.config.terminal.env.clone();
+        self.spawn_pane_on(profile, cwd, host, env)
+    }
+
+    fn spawn_pane_on(
+        &mut self,
+        profile: Profile,
+        cwd: Option<PathBuf>,
+        host: LocalHost,
+        env: BTreeMap<String, String>,
+    ) -> Option<Pane> {
         let Some(sender) = self.pty_sender.clone() else {
             eprintln!("PTY output channel not ready");
-            return Task::none();
+            return None;
         };
 
         let (cols, rows) = self.grid_for_size(self.window_size);
         let theme = TerminalTheme::from_config(&self.config);
-        let tab_id = self.next_tab_id;
+        let id = self.next_tab_id;
         self.next_tab_id = self.next_tab_id.wrapping_add(1);
-        let display_name = profile.display_name();
-        self.session_history.record(profile.clone(), display_name);
-        let pane = crate::gui::tab::Pane::from_profile(crate::gui::tab::PaneSpawn {
+        Some(Pane::from_profile(PaneSpawn {
             profile,
             columns: cols,
             lines: rows,
             theme,
-            id: tab_id,
+            id,
             output_tx: sender,
             scrollback_lines: self.config.terminal.scrollback_lines,
-            cwd: None,
-        });
-        self.tabs
-            .push(crate::gui::tab::TerminalTab::new(tab_id, pane));
+            triggers: self.config.terminal.triggers.clone(),
+            redact_patterns: self.redact_patterns().to_vec(),
+            answerback: self.config.terminal.answerback.clone(),
+            tmux_passthrough: self.config.terminal.tmux_passthrough,
+            cwd,
+            cell_size: self.cell_size(),
+            env,
+            host,
+        }))
+    }
+
+    /// Opens a tab for each session the daemon kept alive from an earlier
+    /// run, or the default shell when there are none.
+    pub(super) fn handle_daemon_sessions_listed(
+        &mut self,
+        sessions: Vec<SessionInfo>,
+    ) -> Task<Message> {
+        if sessions.is_empty() {
+            return self.launch_profile(self.default_profile());
+        }
+        for info in sessions {
+            let profile =
+                toml::from_str(&info.profile).unwrap_or_else(|_| Profile::default_shell());
+            let env = self.config.terminal.env.clone();
+            if let Some(pane) = self.spawn_pane_on(profile, None, LocalHost::Attach(info.id), env) {
+                self.tabs.push(TerminalTab::new(pane.id, pane));
+            }
+        }
+        self.clamp_active_tab();
+        Task::none()
+    }
+
+    pub(in crate::gui) fn create_tab(&mut self, profile: Profile) -> Task<Message> {
+        if self.pty_sender.is_none() {
+            eprintln!("PTY output channel not ready");
+            return Task::none();
+        }
+        #[cfg(target_os = "windows")]
+        if profile.wants_elevation() && !self.elevated {
+            return self.open_elevated(profile);
+        }
+        let display_name = profile.display_name();
+        self.session_history.record(profile.clone(), display_name);
+        let Some(pane) = self.spawn_pane(profile, None) else {
+            return Task::none();
+        };
+        self.tabs.push(TerminalTab::new(pane.id, pane));
         self.active_tab = self.tabs.len() - 1;
         self.dismiss_shell_picker();
         Task::none()
     }
 
+    /// Opens a tab next to `index` running its focused pane's profile again,
+    /// in the same working directory and with the environment that session
+    /// started with. Remote profiles just connect again.
+    pub(in crate::gui) fn duplicate_tab(&mut self, index: usize) -> Task<Message> {
+        let Some(source) = self.tabs.get(index).map(|tab| tab.focused()) else {
+            return Task::none();
+        };
+        let profile = source.profile.clone();
+        if !matches!(profile.kind, ProfileKind::Local { .. }) {
+            return self.launch_profile(profile);
+        }
+        #[cfg(target_os = "windows")]
+        if profile.wants_elevation() && !self.elevated {
+            return self.open_elevated(profile);
+        }
+        let cwd = source.working_directory();
+        let env = source.launch_env().clone();
+        let host = if self.config.terminal.persistent_sessions {
+            LocalHost::Daemon
+        } else {
+            LocalHost::InProcess
+        };
+        let Some(pane) = self.spawn_pane_on(profile, cwd, host, env) else {
+            return Task::none();
+        };
+        let at = (index + 1).min(self.tabs.len());
+        self.tabs.insert(at, TerminalTab::new(pane.id, pane));
+        self.active_tab = at;
+        Task::none()
+    }
+
+    /// Hands `profile` to a new instance started with administrator
+    /// rights; this window keeps its tabs.
+    #[cfg(target_os = "windows")]
+    fn open_elevated(&mut self, profile: Profile) -> Task<Message> {
+        self.dismiss_shell_picker();
+        let Some(arg) = profile.to_launch_arg() else {
+            return Task::none();
+        };
+        if let Err(err) =
+            crate::platform::relaunch_elevated(&[crate::gui::tab::LAUNCH_PROFILE_FLAG, &arg])
+        {
+            eprintln!("Failed to open {} elevated: {err}", profile.name);
+        }
+        Task::none()
+    }
+
     pub(super) fn handle_close_tab(&mut self, index: usize) {
         if index == SETTINGS_TAB_INDEX {
             self.settings_open = false;
@@ -244,13 +394,69 @@ impl App {
                 self.active_tab = self.tabs.len().saturating_sub(1);
             }
         } else if index < self.tabs.len() {
-            self.tabs.remove(index);
+            self.stash_closed_tab(index);
             if self.active_tab != SETTINGS_TAB_INDEX {
                 self.clamp_active_tab();
             }
         }
     }
 
+    /// Takes the tab at `index` out of the tab bar, leaving its sessions
+    /// running for [`CLOSED_TAB_GRACE`] in case it is reopened.
+    fn stash_closed_tab(&mut self, index: usize) {
+        let tab = self.tabs.remove(index);
+        let pane = tab.focused();
+        // Nothing is left to keep alive once every session has ended.
+        let live = tab.panes.iter().any(|p| p.exit.is_none());
+        let closed = ClosedTab {
+            index,
+            profile: pane.profile.clone(),
+            cwd: pane.working_directory(),
+            tab: live.then_some(tab),
+            closed_at: std::time::Instant::now(),
+        };
+        self.closed_tabs.push(closed);
+        if self.closed_tabs.len() > CLOSED_TAB_HISTORY {
+            self.closed_tabs.remove(0);
+        }
+    }
+
+    /// Puts the most recently closed tab back where it was: the same
+    /// sessions within the grace period, else its profile started afresh in
+    /// the directory it was in.
+    pub(in crate::gui) fn reopen_closed_tab(&mut self) -> Task<Message> {
+        let Some(closed) = self.closed_tabs.pop() else {
+            return Task::none();
+        };
+        if let Some(tab) = closed.tab {
+            let index = closed.index.min(self.tabs.len());
+            self.tabs.insert(index, tab);
+            self.active_tab = index;
+            self.resize_panes();
+            return Task::none();
+        }
+        if closed.profile.ssh_profile().is_some() {
+            return self.launch_profile(closed.profile);
+        }
+        let Some(pane) = self.spawn_pane(closed.profile, closed.cwd) else {
+            return Task::none();
+        };
+        let index = closed.index.min(self.tabs.len());
+        self.tabs.insert(index, TerminalTab::new(pane.id, pane));
+        self.active_tab = index;
+        Task::none()
+    }
+
+    /// Ends the sessions of closed tabs whose grace period is over; they
+    /// can still be reopened afresh.
+    pub(in crate::gui) fn expire_closed_tabs(&mut self) {
+        for closed in &mut self.closed_tabs {
+            if closed.closed_at.elapsed() >= CLOSED_TAB_GRACE {
+                closed.tab = None;
+            }
+        }
+    }
+
     fn clamp_active_tab(&mut self) {
         if self.tabs.is_empty() {
             self.active_tab = 0;
@@ -294,6 +500,16 @@ impl App {
 
     pub(in crate::gui) fn shell_picker_entries(&self) -> Vec<PickerEntry> {
         let mut entries = Vec::new();
+        for recent in self.session_history.most_used(RECENT_PICKER_ENTRIES) {
+            entries.push(PickerEntry {
+                section: PickerSection::Recent,
+                label: recent.display_name.clone(),
+                subtitle: Some(
+                    crate::t!("shell_picker.opened").replace("{count}", &recent.count.to_string()),
+                ),
+                target: PickerTarget::Profile(recent.profile.clone()),
+            });
+        }
         let push_ssh = |section, profiles: Vec<SshProfile>, entries: &mut Vec<PickerEntry>| {
             for ssh in profiles {
                 let label = if ssh.name.is_empty() {
@@ -310,7 +526,7 @@ impl App {
                     section,
                     label,
                     subtitle: Some(subtitle),
-                    profile: Profile::ssh(ssh),
+                    target: PickerTarget::Profile(Profile::ssh(ssh)),
                 });
             }
         };
@@ -326,7 +542,7 @@ impl App {
                 section: PickerSection::Profiles,
                 label: profile.display_name(),
                 subtitle: Some(local_subtitle(&profile)),
-                profile,
+                target: PickerTarget::Profile(profile),
             });
         }
 
@@ -336,18 +552,74 @@ impl App {
             &mut entries,
         );
 
+        for container in &self.containers {
+            let shell = container.shell(&self.config.terminal.container_shells);
+            entries.push(PickerEntry {
+                section: PickerSection::Containers,
+                label: container.name.clone(),
+                subtitle: Some(format!(
+                    "{} · {}",
+                    container.runtime.program(),
+                    container.image
+                )),
+                target: PickerTarget::Profile(container.profile(shell)),
+            });
+        }
+
         for shell in &self.available_shells {
             entries.push(PickerEntry {
                 section: PickerSection::Builtin,
                 label: shell.display_name(),
                 subtitle: Some(local_subtitle(shell)),
-                profile: shell.clone(),
+                target: PickerTarget::Profile(shell.clone()),
+            });
+        }
+
+        for workspace in &self.workspaces.entries {
+            entries.push(PickerEntry {
+                section: PickerSection::Workspaces,
+                label: crate::t!("shell_picker.open_workspace").replace("{name}", &workspace.name),
+                subtitle: Some(
+                    crate::t!("shell_picker.workspace_tabs")
+                        .replace("{count}", &workspace.tabs.len().to_string()),
+                ),
+                target: PickerTarget::Workspace(workspace.name.clone()),
             });
         }
 
+        if self.tabs.get(self.active_tab).is_some() {
+            for (index, snippet) in self.config.snippets.iter().enumerate() {
+                entries.push(PickerEntry {
+                    section: PickerSection::Snippets,
+                    label: snippet.name.clone(),
+                    subtitle: Some(snippet.command.clone()),
+                    target: PickerTarget::Snippet(index),
+                });
+            }
+            for (index, command) in self.plugins.commands() {
+                entries.push(PickerEntry {
+                    section: PickerSection::Plugins,
+                    label: command.title,
+                    subtitle: self.plugins.plugin_name(index).map(str::to_string),
+                    target: PickerTarget::PluginCommand(index, command.id),
+                });
+            }
+        }
+
         entries
     }
 
+    /// The starred profile, or the user's default shell when none is
+    /// starred or the starred one no longer exists.
+    pub(in crate::gui) fn default_profile(&self) -> Profile {
+        self.config
+            .terminal
+            .default_profile
+            .as_deref()
+            .and_then(|name| self.profile_named(name))
+            .unwrap_or_else(Profile::default_shell)
+    }
+
     pub(in crate::gui) fn session_local_profiles(&self) -> Vec<Profile> {
         let source = if self.settings_open {
             let draft: Vec<Profile> = self
@@ -400,7 +672,14 @@ impl App {
             return Task::none();
         };
         self.dismiss_shell_picker();
-        self.launch_profile(entry.profile)
+        match entry.target {
+            PickerTarget::Profile(profile) => self.launch_profile(profile),
+            PickerTarget::Workspace(name) => self.update(Message::OpenWorkspace(name)),
+            PickerTarget::Snippet(index) => self.update(Message::UseSnippet(index)),
+            PickerTarget::PluginCommand(index, id) => {
+                self.update(Message::RunPluginCommand(index, id))
+            }
+        }
     }
 
     pub(super) fn handle_app_shortcut(
@@ -408,6 +687,13 @@ impl App {
         physical: &iced::keyboard::key::Physical,
         modifiers: Modifiers,
     ) -> Option<Task<Message>> {
+        if let Some(index) = self
+            .scripts
+            .as_ref()
+            .and_then(|scripts| scripts.binding(|keys| shortcut_matches(keys, physical, modifiers)))
+        {
+            return Some(self.run_script_binding(index));
+        }
         let action = ShortcutAction::resolve(physical, modifiers, &self.config.shortcuts)?;
 
         match action {
@@ -424,10 +710,87 @@ impl App {
                 self.close_focused_pane();
                 Some(Task::none())
             }
+            ShortcutAction::ZoomPane => {
+                if self.active_tab != SETTINGS_TAB_INDEX
+                    && let Some(tab) = self.tabs.get_mut(self.active_tab)
+                {
+                    tab.toggle_zoom();
+                    self.resize_panes();
+                }
+                Some(Task::none())
+            }
             ShortcutAction::FocusPane(direction) => {
                 let area = self.terminal_area();
                 if let Some(tab) = self.tabs.get_mut(self.active_tab) {
+                    let was_zoomed = tab.zoomed();
                     tab.focus_direction(direction, area);
+                    if was_zoomed && !tab.zoomed() {
+                        self.resize_panes();
+                    }
+                }
+                Some(Task::none())
+            }
+            ShortcutAction::ToggleMark => {
+                if let Some(pane) = self.focused_pane_mut() {
+                    pane.toggle_mark();
+                }
+                Some(Task::none())
+            }
+            ShortcutAction::JumpToMark { older } => {
+                if let Some(pane) = self.focused_pane_mut()
+                    && pane.jump_to_mark(older)
+                {
+                    let offset = pane.scroll_position().0;
+                    self.scroll_follow_bottom = offset == 0;
+                }
+                Some(Task::none())
+            }
+            ShortcutAction::CopyLastOutput => Some(self.copy_last_output()),
+            ShortcutAction::RerunCommand => {
+                self.rerun_last_command();
+                Some(Task::none())
+            }
+            ShortcutAction::SelectAll => Some(self.update(Message::TerminalContextSelectAll)),
+            ShortcutAction::CopyMode => {
+                if self.active_tab != SETTINGS_TAB_INDEX
+                    && let Some(pane) = self.focused_pane_mut()
+                {
+                    if pane.in_copy_mode() {
+                        pane.exit_copy_mode();
+                    } else {
+                        pane.enter_copy_mode();
+                    }
+                }
+                Some(Task::none())
+            }
+            ShortcutAction::SaveWorkspace => Some(self.update(Message::ShowWorkspacePrompt)),
+            ShortcutAction::OpenDirectory => {
+                Some(self.update(Message::OpenTabDirectory(self.active_tab)))
+            }
+            ShortcutAction::CopyDirectory => {
+                Some(self.update(Message::CopyTabDirectory(self.active_tab)))
+            }
+            ShortcutAction::ListMarks => {
+                self.marks_list = !self.marks_list;
+                self.menu_cursor = None;
+                Some(Task::none())
+            }
+            ShortcutAction::DebugOverlay => {
+                self.debug_overlay = !self.debug_overlay;
+                Some(Task::none())
+            }
+            ShortcutAction::Screenshot => Some(self.update(Message::SaveScreenshot)),
+            ShortcutAction::Record => Some(self.toggle_recording()),
+            ShortcutAction::ReadScreen => Some(self.read_screen()),
+            ShortcutAction::CheatSheet => {
+                self.cheat_sheet = !self.cheat_sheet;
+                Some(Task::none())
+            }
+            ShortcutAction::PrivacyMode => {
+                self.privacy_mode = !self.privacy_mode;
+                let patterns = self.redact_patterns().to_vec();
+                for pane in self.panes_mut() {
+                    pane.set_redaction(&patterns);
                 }
                 Some(Task::none())
             }
@@ -436,6 +799,7 @@ impl App {
                 self.close_active_target();
                 Some(Task::none())
             }
+            ShortcutAction::ReopenClosedTab => Some(self.reopen_closed_tab()),
             ShortcutAction::OpenSettings => {
                 self.settings_open = true;
                 self.active_tab = SETTINGS_TAB_INDEX;
@@ -450,7 +814,7 @@ impl App {
                 self.select_relative_tab(-1);
                 Some(Task::none())
             }
-            ShortcutAction::Quit => Some(iced::exit()),
+            ShortcutAction::Quit => Some(self.quit()),
             ShortcutAction::FontSizeIncrease => Some(self.adjust_font_size(1.0)),
             ShortcutAction::FontSizeDecrease => Some(self.adjust_font_size(-1.0)),
             ShortcutAction::FontSizeReset => {
@@ -487,7 +851,7 @@ impl App {
         }
 
         let index = self.active_tab.min(self.tabs.len() - 1);
-        self.tabs.remove(index);
+        self.stash_closed_tab(index);
         self.clamp_active_tab();
     }
 
@@ -0,0 +1,131 @@
+use super::super::{App, Message, SETTINGS_TAB_INDEX};
+use super::{TAB_BAR_SCROLLABLE_ID, TAB_LIST_INPUT_ID};
+use crate::gui::components::tab_bar;
+use iced::Task;
+use iced::widget::operation::scroll_to;
+use iced::widget::scrollable;
+
+/// Width the bar keeps for its buttons and window controls.
+const TAB_BAR_TRAILING_WIDTH: f32 = 180.0;
+
+impl App {
+    pub(super) fn update_tab_list(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::ToggleTabList => {
+                self.menu_cursor = None;
+                if self.tab_list.take().is_none() {
+                    self.tab_list = Some(String::new());
+                    return iced::widget::operation::focus(TAB_LIST_INPUT_ID.clone());
+                }
+            }
+            Message::TabListFilterChanged(filter) => {
+                if let Some(current) = self.tab_list.as_mut() {
+                    *current = filter;
+                    self.menu_cursor = None;
+                }
+            }
+            Message::TabListSelect(index) => {
+                self.tab_list = None;
+                if index < self.tabs.len() {
+                    self.active_tab = index;
+                    return self.scroll_tab_into_view(index);
+                }
+            }
+            // Also sent by the Enter that picked a row, after it closed.
+            Message::TabListSubmit if self.tab_list.is_some() => {
+                let row = self.menu_cursor.unwrap_or(0);
+                if let Some(&index) = self.tab_list_matches().get(row) {
+                    return self.update_tab_list(Message::TabListSelect(index));
+                }
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+
+    /// Indices of the tabs whose titles match the list's filter, best first.
+    pub(in crate::gui::app) fn tab_list_matches(&self) -> Vec<usize> {
+        let filter = self.tab_list.as_deref().unwrap_or_default();
+        let mut scored: Vec<(i32, usize)> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, tab)| fuzzy_score(filter, tab.title()).map(|s| (s, index)))
+            .collect();
+        scored.sort_by_key(|&(score, index)| (std::cmp::Reverse(score), index));
+        scored.into_iter().map(|(_, index)| index).collect()
+    }
+
+    /// Title lengths of the tabs in the bar, how long their titles may be
+    /// to fit, and whether they overflow the bar even so.
+    pub(in crate::gui::app) fn tab_strip_fit(&self) -> (Vec<usize>, usize, bool) {
+        let mut title_chars: Vec<usize> = self
+            .tabs
+            .iter()
+            .map(|tab| tab.title().chars().count())
+            .collect();
+        if self.settings_open {
+            title_chars.push("Settings".len());
+        }
+        let available = (self.window_size.width - TAB_BAR_TRAILING_WIDTH).max(0.0);
+        let (max_chars, overflow) = tab_bar::fit_titles(&title_chars, available);
+        (title_chars, max_chars, overflow)
+    }
+
+    /// Scrolls the tab strip so the tab at `index` is in view.
+    fn scroll_tab_into_view(&mut self, index: usize) -> Task<Message> {
+        if index == SETTINGS_TAB_INDEX {
+            return Task::none();
+        }
+        let (title_chars, max_chars, _) = self.tab_strip_fit();
+        let offset = tab_bar::tab_offset(&title_chars, index, max_chars);
+        // Leave the tabs before it in sight too.
+        self.tab_bar_scroll_x = (offset - self.window_size.width / 4.0).max(0.0);
+        scroll_to(
+            TAB_BAR_SCROLLABLE_ID.clone(),
+            scrollable::AbsoluteOffset {
+                x: self.tab_bar_scroll_x,
+                y: 0.0,
+            },
+        )
+    }
+}
+
+/// Scores `text` against `query` when the query's characters appear in it
+/// in order, ignoring case; runs of adjacent characters and an early first
+/// match score higher. `None` when it doesn't match.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+    {
+        let found = next + text[next..].iter().position(|&c| c == wanted)?;
+        score += match previous {
+            Some(previous) if previous + 1 == found => 8,
+            Some(_) => 1,
+            None => 8 - found.min(8) as i32,
+        };
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn fuzzy_matches_prefer_adjacent_letters_near_the_start() {
+        assert!(fuzzy_score("srv", "ssh: server").is_some());
+        assert_eq!(fuzzy_score("xyz", "ssh: server"), None);
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert!(fuzzy_score("ser", "SSH: server") > fuzzy_score("ser", "s-e-r"));
+        assert!(fuzzy_score("vim", "vim notes") > fuzzy_score("vim", "nvim notes"));
+    }
+}
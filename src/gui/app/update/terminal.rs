@@ -1,40 +1,98 @@
 use super::super::{App, Message, SETTINGS_TAB_INDEX};
 use super::TAB_BAR_SCROLLABLE_ID;
 use crate::config::{AppConfigUpdates, BellMode};
+use crate::gui::tab::SessionExit;
 use crate::session::OutputEvent;
+use crate::terminal::timestamps;
 use iced::widget::operation::scroll_to;
 use iced::widget::scrollable;
-use iced::{Size, Task};
+use iced::{Size, Task, window};
+use std::time::Duration;
 
 impl App {
-    pub(super) fn handle_pty_event(&mut self, event: OutputEvent) {
+    pub(super) fn handle_pty_event(&mut self, event: OutputEvent) -> Task<Message> {
         match event {
             OutputEvent::Data { tab_id, bytes } => {
+                let (bytes, effects) = self.plugins.process_output(tab_id, &bytes);
+                let mut attention = Task::none();
                 if let Some(pane) = self.pane_mut_by_id(tab_id) {
                     let bell = pane.feed_bytes(&bytes);
+                    let title = pane.title.clone();
+                    let notifications = pane.take_notifications();
+                    for text in &notifications {
+                        crate::platform::notify(&title, text);
+                    }
+                    let finished = pane.take_finished_command();
                     if bell {
                         self.handle_bell(tab_id);
                     }
+                    self.mark_tab_alerts(tab_id, bell);
+                    let notified = finished.is_some_and(|elapsed| {
+                        self.handle_command_finished(tab_id, &title, elapsed)
+                    });
+                    if (bell && self.config.terminal.bell_mode != BellMode::Off)
+                        || !notifications.is_empty()
+                        || notified
+                    {
+                        attention = self.request_attention();
+                    }
+                } else if let Some(pane) = self
+                    .closed_tabs
+                    .iter_mut()
+                    .filter_map(|closed| closed.tab.as_mut())
+                    .find_map(|tab| tab.pane_mut(tab_id))
+                {
+                    // Keep a closed tab's screen current in case it is reopened.
+                    pane.feed_bytes(&bytes);
                 }
+                return Task::batch([attention, self.apply_plugin_effects(tab_id, effects)]);
             }
-            OutputEvent::Closed { tab_id } => {
-                if let Some(index) = self
-                    .tabs
-                    .iter()
-                    .position(|t| t.panes.iter().any(|p| p.id == tab_id))
+            OutputEvent::Closed { tab_id, exit_code } => {
+                self.plugins.forget_pane(tab_id);
+                if !self.config.terminal.exit_behavior.closes(exit_code)
+                    && let Some(pane) = self.pane_mut_by_id(tab_id)
                 {
-                    let closed_tab = !self.tabs[index].close_pane(tab_id);
-                    if closed_tab {
-                        self.tabs.remove(index);
-                        if self.active_tab >= self.tabs.len() && !self.tabs.is_empty() {
-                            self.active_tab = self.tabs.len() - 1;
+                    pane.exit = Some(SessionExit { code: exit_code });
+                    return Task::none();
+                }
+                if !self.remove_pane(tab_id) {
+                    // A closed tab lost a pane; with its last one gone it
+                    // can only be reopened afresh.
+                    for closed in &mut self.closed_tabs {
+                        let Some(tab) = &mut closed.tab else { continue };
+                        if tab.panes.iter().any(|p| p.id == tab_id) && !tab.close_pane(tab_id) {
+                            closed.tab = None;
                         }
-                    } else {
-                        self.resize_panes();
                     }
                 }
             }
+            // Only the tab's forward indicator changes; the redraw that
+            // follows every update picks it up.
+            OutputEvent::ForwardsChanged { .. } => {}
         }
+        Task::none()
+    }
+
+    /// Takes a pane out of its tab, and the tab out of the bar with its
+    /// last pane. `false` when no open tab has the pane.
+    pub(super) fn remove_pane(&mut self, pane_id: u64) -> bool {
+        let Some(index) = self
+            .tabs
+            .iter()
+            .position(|t| t.panes.iter().any(|p| p.id == pane_id))
+        else {
+            return false;
+        };
+        let closed_tab = !self.tabs[index].close_pane(pane_id);
+        if closed_tab {
+            self.tabs.remove(index);
+            if self.active_tab >= self.tabs.len() && !self.tabs.is_empty() {
+                self.active_tab = self.tabs.len() - 1;
+            }
+        } else {
+            self.resize_panes();
+        }
+        true
     }
 
     /// Reacts to a terminal bell from the tab identified by `tab_id`,
@@ -56,6 +114,76 @@ impl App {
         }
     }
 
+    /// Asks the window manager to flag the window, while it is in the
+    /// background and `terminal.bell_attention` is on: a taskbar flash on
+    /// Windows, a dock bounce on macOS, the urgency hint on X11 and Wayland.
+    /// Focusing the window clears it.
+    pub(super) fn request_attention(&self) -> Task<Message> {
+        if self.window_focused || !self.config.terminal.bell_attention {
+            return Task::none();
+        }
+        window::latest().and_then(|id| {
+            window::request_user_attention(id, Some(window::UserAttention::Informational))
+        })
+    }
+
+    /// Lets the user know a long command in pane `pane_id` finished, unless
+    /// they are looking at it: a notification, a badge on its tab and the
+    /// bell when the bell makes a sound. Returns whether it did.
+    fn handle_command_finished(&mut self, pane_id: u64, title: &str, elapsed: Duration) -> bool {
+        let threshold = self.config.terminal.notify_command_secs;
+        if threshold == 0 || elapsed < Duration::from_secs(threshold) {
+            return false;
+        }
+        let Some(index) = self
+            .tabs
+            .iter()
+            .position(|t| t.panes.iter().any(|p| p.id == pane_id))
+        else {
+            return false;
+        };
+        let in_view = self.active_tab == index;
+        if in_view && self.window_focused {
+            return false;
+        }
+
+        if !in_view {
+            self.tabs[index].command_done = true;
+        }
+        let body = crate::t!("terminal.command_finished")
+            .replace("{duration}", &timestamps::elapsed(elapsed));
+        crate::platform::notify(title, &body);
+        // On Windows the notification already is the bell.
+        if self.config.terminal.bell_mode == BellMode::Sound && !cfg!(target_os = "windows") {
+            crate::platform::ring_bell();
+        }
+        true
+    }
+
+    /// Notes output, and a bell, from a pane of a tab out of view, for the
+    /// status bar.
+    fn mark_tab_alerts(&mut self, pane_id: u64, bell: bool) {
+        let active = self.active_tab;
+        if let Some((_, tab)) = self
+            .tabs
+            .iter_mut()
+            .enumerate()
+            .find(|(index, tab)| *index != active && tab.panes.iter().any(|p| p.id == pane_id))
+        {
+            tab.activity = true;
+            tab.bell |= bell;
+        }
+    }
+
+    /// Drops the finished-command badge and the alerts of the tab in view.
+    pub(super) fn clear_tab_alerts(&mut self) {
+        if let Some(tab) = self.tabs.get_mut(self.active_tab) {
+            tab.command_done = false;
+            tab.bell = false;
+            tab.activity = false;
+        }
+    }
+
     pub(super) fn handle_tab_bar_scroll(&mut self, delta: f32) -> Task<Message> {
         let new_x = (self.tab_bar_scroll_x - delta).max(0.0);
         self.tab_bar_scroll_x = new_x;
@@ -66,6 +194,11 @@ impl App {
     }
 
     pub(super) fn handle_window_resized(&mut self, size: Size) -> Task<Message> {
+        // Minimizing reports a zero size; keep the grids for the restore.
+        self.window_minimized = size.width < 1.0 || size.height < 1.0;
+        if self.window_minimized {
+            return Task::none();
+        }
         self.window_size = size;
         self.resize_debounce_seq += 1;
 
@@ -0,0 +1,168 @@
+use super::super::{App, Message, SETTINGS_TAB_INDEX};
+use super::WORKSPACE_NAME_INPUT_ID;
+use crate::gui::pane::PaneNode;
+use crate::gui::tab::{Pane, TerminalTab};
+use crate::session::workspace::{Workspace, WorkspaceLayout, WorkspaceTab};
+use iced::Task;
+
+impl App {
+    pub(super) fn update_workspace(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::ShowWorkspacePrompt => {
+                self.tab_context_menu = None;
+                if !self.tabs.is_empty() {
+                    self.workspace_prompt = Some(String::new());
+                    return iced::widget::operation::focus(WORKSPACE_NAME_INPUT_ID.clone());
+                }
+            }
+            Message::WorkspaceNameChanged(name) => {
+                if let Some(prompt) = self.workspace_prompt.as_mut() {
+                    *prompt = name;
+                }
+            }
+            Message::SaveWorkspace => {
+                let Some(name) = self.workspace_prompt.take() else {
+                    return Task::none();
+                };
+                let name = name.trim();
+                if name.is_empty() {
+                    return Task::none();
+                }
+                let workspace = self.capture_workspace(name);
+                self.workspaces.insert(workspace);
+                self.workspaces.save();
+            }
+            Message::CloseWorkspacePrompt => {
+                self.workspace_prompt = None;
+            }
+            Message::OpenWorkspace(name) => {
+                self.dismiss_shell_picker();
+                if let Some(workspace) = self.workspaces.get(&name).cloned() {
+                    self.open_workspace(&workspace);
+                }
+            }
+            _ => {}
+        }
+        Task::none()
+    }
+
+    /// Snapshots every open tab: its splits, profiles, directories and titles.
+    pub(in crate::gui) fn capture_workspace(&self, name: &str) -> Workspace {
+        let tabs = self
+            .tabs
+            .iter()
+            .map(|tab| WorkspaceTab {
+                color: tab.color.map(|[r, g, b]| format!("#{r:02x}{g:02x}{b:02x}")),
+                icon: tab.icon.clone(),
+                layout: capture_layout(tab, &tab.layout),
+            })
+            .collect();
+        let active = if self.active_tab == SETTINGS_TAB_INDEX {
+            0
+        } else {
+            self.active_tab
+        };
+        Workspace {
+            name: name.to_string(),
+            active,
+            tabs,
+        }
+    }
+
+    /// Opens the workspace's tabs after the ones already open. SSH panes skip
+    /// the password prompt and fall back to the keychain.
+    pub(in crate::gui) fn open_workspace(&mut self, workspace: &Workspace) {
+        let first_new = self.tabs.len();
+        for saved in &workspace.tabs {
+            let mut panes = Vec::new();
+            let Some(layout) = self.restore_layout(&saved.layout, &mut panes) else {
+                continue;
+            };
+            let mut panes = panes.into_iter();
+            let Some(first) = panes.next() else {
+                continue;
+            };
+            let mut tab = TerminalTab::new(first.id, first);
+            tab.panes.extend(panes);
+            tab.layout = layout;
+            tab.color = saved
+                .color
+                .as_deref()
+                .and_then(crate::config::parse_hex_color);
+            tab.icon = saved.icon.clone();
+            self.tabs.push(tab);
+        }
+        if self.tabs.len() > first_new {
+            self.active_tab = (first_new + workspace.active).min(self.tabs.len() - 1);
+            self.resize_panes();
+        }
+    }
+
+    fn restore_layout(
+        &mut self,
+        layout: &WorkspaceLayout,
+        panes: &mut Vec<Pane>,
+    ) -> Option<PaneNode> {
+        match layout {
+            WorkspaceLayout::Pane {
+                profile,
+                cwd,
+                title,
+            } => {
+                let mut pane = self.spawn_pane(profile.clone(), cwd.clone())?;
+                if let Some(title) = title {
+                    pane.title = title.clone();
+                }
+                let id = pane.id;
+                panes.push(pane);
+                Some(PaneNode::Leaf(id))
+            }
+            WorkspaceLayout::Split {
+                axis,
+                ratio,
+                first,
+                second,
+            } => {
+                let first = self.restore_layout(first, panes);
+                let second = self.restore_layout(second, panes);
+                match (first, second) {
+                    (Some(first), Some(second)) => Some(PaneNode::Split {
+                        axis: *axis,
+                        ratio: *ratio,
+                        first: Box::new(first),
+                        second: Box::new(second),
+                    }),
+                    (first, second) => first.or(second),
+                }
+            }
+        }
+    }
+}
+
+fn capture_layout(tab: &TerminalTab, node: &PaneNode) -> WorkspaceLayout {
+    match node {
+        PaneNode::Leaf(id) => {
+            let pane = tab
+                .panes
+                .iter()
+                .find(|pane| pane.id == *id)
+                .unwrap_or(tab.focused());
+            WorkspaceLayout::Pane {
+                profile: pane.profile.clone(),
+                cwd: pane.working_directory(),
+                title: Some(pane.title.clone()),
+            }
+        }
+        PaneNode::Split {
+            axis,
+            ratio,
+            first,
+            second,
+        } => WorkspaceLayout::Split {
+            axis: *axis,
+            ratio: *ratio,
+            first: Box::new(capture_layout(tab, first)),
+            second: Box::new(capture_layout(tab, second)),
+        },
+    }
+}
@@ -0,0 +1,110 @@
+use super::super::{App, Message};
+use crate::config::ShortcutCategory;
+use crate::gui::theme::{RADIUS_NORMAL, RADIUS_SMALL, SPACING_NORMAL, SPACING_SMALL};
+use iced::widget::{center, column, container, mouse_area, row, scrollable, stack, text};
+use iced::{Alignment, Background, Border, Color, Element, Font, Length};
+
+const SHEET_WIDTH: f32 = 620.0;
+const SHEET_MAX_HEIGHT: f32 = 560.0;
+
+impl App {
+    /// Every shortcut as it is bound right now, grouped by what it acts on.
+    pub(in crate::gui) fn view_cheat_sheet<'a>(
+        &'a self,
+        base_layout: impl Into<Element<'a, Message>>,
+    ) -> Element<'a, Message> {
+        let palette = self.palette;
+
+        let groups = ShortcutCategory::ALL.map(|category| {
+            let mut rows: Vec<Element<Message>> = vec![
+                text(category.label())
+                    .size(11)
+                    .color(palette.text_secondary)
+                    .into(),
+            ];
+            for (id, binding) in self.config.shortcuts.iter() {
+                if id.category() != category {
+                    continue;
+                }
+                let key = container(text(binding.to_string()).size(12).font(Font::MONOSPACE))
+                    .padding([2, 6])
+                    .style(move |_theme: &iced::Theme| container::Style {
+                        background: Some(Background::Color(Color {
+                            a: 0.08,
+                            ..palette.text
+                        })),
+                        border: Border {
+                            radius: RADIUS_SMALL.into(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    });
+                rows.push(
+                    row![text(id.label()).size(13).width(Length::Fill), key]
+                        .spacing(SPACING_SMALL)
+                        .align_y(Alignment::Center)
+                        .into(),
+                );
+            }
+            column(rows).spacing(SPACING_SMALL).width(Length::Fill)
+        });
+
+        // Two columns of about the same height.
+        let [tabs, panes, terminal, view, application] = groups;
+        let sheet = row![
+            column![tabs, panes, application]
+                .spacing(SPACING_NORMAL * 2.0)
+                .width(Length::Fill),
+            column![terminal, view]
+                .spacing(SPACING_NORMAL * 2.0)
+                .width(Length::Fill),
+        ]
+        .spacing(SPACING_NORMAL * 2.0);
+
+        let card = container(scrollable(
+            column![text(t!("cheat_sheet.title")).size(16), sheet]
+                .spacing(SPACING_NORMAL)
+                .padding(20),
+        ))
+        .width(Length::Fixed(SHEET_WIDTH))
+        .max_height(SHEET_MAX_HEIGHT)
+        .style(move |_theme: &iced::Theme| container::Style {
+            background: Some(Background::Color(Color {
+                a: 0.97,
+                ..palette.surface
+            })),
+            border: Border {
+                radius: (RADIUS_NORMAL + 4.0).into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.15,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        });
+
+        let backdrop = mouse_area(
+            container(text(""))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(|_theme: &iced::Theme| container::Style {
+                    background: Some(Background::Color(Color {
+                        a: 0.5,
+                        ..Color::BLACK
+                    })),
+                    ..Default::default()
+                }),
+        )
+        .on_press(Message::CloseCheatSheet);
+
+        stack![
+            base_layout.into(),
+            backdrop,
+            center(card).width(Length::Fill).height(Length::Fill),
+        ]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
@@ -2,7 +2,10 @@ use super::super::Message;
 use crate::gui::components::{primary, secondary};
 use crate::gui::theme::{Palette, RADIUS_NORMAL, SPACING_NORMAL, SPACING_SMALL};
 use iced::widget::{center, column, container, mouse_area, row, stack, text};
-use iced::{Background, Border, Color, Element, Length};
+use iced::{Background, Border, Color, Element, Font, Length};
+
+const DIALOG_WIDTH: f32 = 300.0;
+const PREVIEW_DIALOG_WIDTH: f32 = 460.0;
 
 pub(in crate::gui) struct DialogButton {
     pub label: String,
@@ -19,6 +22,54 @@ pub(in crate::gui) fn confirm_dialog<'a>(
     on_dismiss: Message,
     palette: Palette,
     animations_enabled: bool,
+) -> Element<'a, Message> {
+    dialog(
+        base_layout,
+        title,
+        description,
+        None,
+        buttons,
+        on_dismiss,
+        palette,
+        animations_enabled,
+    )
+}
+
+/// A confirmation dialog that also shows a monospace excerpt of the content
+/// being confirmed, e.g. the first lines of a large paste.
+#[allow(clippy::too_many_arguments)]
+pub(in crate::gui) fn preview_dialog<'a>(
+    base_layout: impl Into<Element<'a, Message>>,
+    title: &str,
+    description: &str,
+    preview: &str,
+    buttons: Vec<DialogButton>,
+    on_dismiss: Message,
+    palette: Palette,
+    animations_enabled: bool,
+) -> Element<'a, Message> {
+    dialog(
+        base_layout,
+        title,
+        description,
+        Some(preview),
+        buttons,
+        on_dismiss,
+        palette,
+        animations_enabled,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dialog<'a>(
+    base_layout: impl Into<Element<'a, Message>>,
+    title: &str,
+    description: &str,
+    preview: Option<&str>,
+    buttons: Vec<DialogButton>,
+    on_dismiss: Message,
+    palette: Palette,
+    animations_enabled: bool,
 ) -> Element<'a, Message> {
     let backdrop = mouse_area(
         container(text(""))
@@ -47,15 +98,42 @@ pub(in crate::gui) fn confirm_dialog<'a>(
         })
         .collect();
 
+    let mut body: Vec<Element<Message>> = vec![
+        text(title.to_string()).size(16).into(),
+        text(description.to_string()).size(13).into(),
+    ];
+    let width = if let Some(preview) = preview {
+        body.push(
+            container(
+                text(preview.to_string())
+                    .size(12)
+                    .font(Font::MONOSPACE)
+                    .wrapping(text::Wrapping::WordOrGlyph)
+                    .color(palette.text_secondary),
+            )
+            .padding(SPACING_SMALL)
+            .width(Length::Fill)
+            .style(move |_theme: &iced::Theme| container::Style {
+                background: Some(Background::Color(palette.background)),
+                border: Border {
+                    radius: RADIUS_NORMAL.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .into(),
+        );
+        PREVIEW_DIALOG_WIDTH
+    } else {
+        DIALOG_WIDTH
+    };
+    body.push(row(button_row).spacing(SPACING_SMALL).into());
+
     let popup_card = container(
-        column(vec![
-            text(title.to_string()).size(16).into(),
-            text(description.to_string()).size(13).into(),
-            row(button_row).spacing(SPACING_SMALL).into(),
-        ])
-        .spacing(SPACING_NORMAL)
-        .padding(20)
-        .width(Length::Fixed(300.0)),
+        column(body)
+            .spacing(SPACING_NORMAL)
+            .padding(20)
+            .width(Length::Fixed(width)),
     )
     .style(move |_theme: &iced::Theme| container::Style {
         background: Some(Background::Color(palette.surface)),
@@ -0,0 +1,71 @@
+use super::super::{App, Message};
+use super::{DialogButton, preview_dialog};
+use crate::terminal::url;
+use iced::Element;
+
+impl App {
+    /// Shows the full, de-obfuscated target of a clicked link before it is
+    /// opened, or explains why it will not be.
+    pub(in crate::gui) fn view_link_dialog<'a>(
+        &'a self,
+        base_layout: impl Into<Element<'a, Message>>,
+        target: &str,
+    ) -> Element<'a, Message> {
+        let revealed = url::reveal(target);
+        let scheme = url::scheme(target).unwrap_or_default();
+        let copy = DialogButton {
+            label: t!("dialog.copy_link").into(),
+            message: Message::CopyPendingLink,
+            primary: false,
+        };
+
+        let (title, mut description, buttons) =
+            if url::is_allowed(target, &self.config.terminal.link_schemes) {
+                (
+                    t!("dialog.open_link_title"),
+                    t!("dialog.open_link_body").to_string(),
+                    vec![
+                        DialogButton {
+                            label: t!("dialog.cancel").into(),
+                            message: Message::CancelOpenLink,
+                            primary: false,
+                        },
+                        copy,
+                        DialogButton {
+                            label: t!("dialog.open").into(),
+                            message: Message::ConfirmOpenLink,
+                            primary: true,
+                        },
+                    ],
+                )
+            } else {
+                (
+                    t!("dialog.link_blocked_title"),
+                    t!("dialog.link_blocked_body").replace("{scheme}", scheme),
+                    vec![
+                        copy,
+                        DialogButton {
+                            label: t!("dialog.close").into(),
+                            message: Message::CancelOpenLink,
+                            primary: true,
+                        },
+                    ],
+                )
+            };
+        if revealed != target {
+            description.push_str("\n\n");
+            description.push_str(t!("dialog.link_disguised"));
+        }
+
+        preview_dialog(
+            base_layout,
+            title,
+            &description,
+            &revealed,
+            buttons,
+            Message::CancelOpenLink,
+            self.palette,
+            self.config.ui.animations_enabled,
+        )
+    }
+}
@@ -0,0 +1,91 @@
+use super::super::{App, Message};
+use crate::gui::components::menu_item_highlighted;
+use crate::gui::theme::{RADIUS_NORMAL, SPACING_SMALL};
+use crate::terminal::marks::MarkKind;
+use iced::widget::{column, container, mouse_area, scrollable, stack, text};
+use iced::{Background, Border, Color, Element, Length};
+
+const LIST_WIDTH: f32 = 380.0;
+const LIST_MAX_HEIGHT: f32 = 360.0;
+/// Marked lines are cut to this many characters in the list.
+const PREVIEW_CHARS: usize = 48;
+
+impl App {
+    /// The focused pane's marks, newest first; picking one scrolls to it.
+    pub(in crate::gui) fn view_marks_list<'a>(
+        &'a self,
+        base_layout: impl Into<Element<'a, Message>>,
+    ) -> Element<'a, Message> {
+        let palette = self.palette;
+        let marks = self
+            .focused_pane()
+            .map(|pane| pane.marks())
+            .unwrap_or_default();
+
+        let mut items: Vec<Element<Message>> = vec![
+            text(t!("terminal.marks"))
+                .size(15)
+                .color(palette.text)
+                .into(),
+        ];
+        if marks.is_empty() {
+            items.push(
+                text(t!("terminal.no_marks"))
+                    .size(12)
+                    .color(palette.text_secondary)
+                    .into(),
+            );
+        }
+        for (row, (mark, line)) in marks.into_iter().rev().enumerate() {
+            let kind = match mark.kind {
+                MarkKind::User => t!("terminal.mark_user"),
+                MarkKind::Prompt => t!("terminal.mark_prompt"),
+                MarkKind::Trigger => t!("terminal.mark_trigger"),
+            };
+            let mut preview: String = line.chars().take(PREVIEW_CHARS).collect();
+            if preview.len() < line.len() {
+                preview.push('…');
+            }
+            items.push(menu_item_highlighted(
+                format!("{kind} · {preview}"),
+                Message::ScrollToMark(mark.line),
+                self.menu_cursor == Some(row),
+                palette,
+                self.config.ui.animations_enabled,
+            ));
+        }
+
+        let list = scrollable(column(items).spacing(SPACING_SMALL).padding([12, 8]));
+        let card = container(list)
+            .width(Length::Fixed(LIST_WIDTH))
+            .max_height(LIST_MAX_HEIGHT)
+            .style(move |_theme: &iced::Theme| container::Style {
+                background: Some(Background::Color(Color {
+                    a: 0.97,
+                    ..palette.surface
+                })),
+                border: Border {
+                    radius: RADIUS_NORMAL.into(),
+                    width: 1.0,
+                    color: Color {
+                        a: 0.15,
+                        ..palette.text
+                    },
+                },
+                ..Default::default()
+            });
+
+        let backdrop = mouse_area(container(text("")).width(Length::Fill).height(Length::Fill))
+            .on_press(Message::CloseMarksList);
+        let centered = container(card)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        stack![base_layout.into(), backdrop, centered]
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -1,20 +1,34 @@
+mod cheat_sheet;
 mod dialog;
+mod link;
+mod marks;
+mod onboarding;
 mod password_prompt;
+mod process_list;
 mod settings;
 mod sftp;
 mod shell_picker;
+mod side_panel;
+mod snippet;
+mod status_bar;
+mod tab_list;
+mod workspace;
 
-pub(in crate::gui) use dialog::{DialogButton, confirm_dialog};
+pub(in crate::gui) use dialog::{DialogButton, confirm_dialog, preview_dialog};
 
 use super::{App, Message, SETTINGS_TAB_INDEX};
-use crate::config::TabBarPosition;
+use crate::config::{PortForward, TabBarPosition};
 use crate::gui::app::{SettingsMessage, SftpMessage};
 use crate::gui::components::context_menu::{ContextMenuItem, context_menu};
 use crate::gui::components::ime_wrapper::ImeEnabled;
-use crate::gui::components::{panel, secondary as button_secondary, tab_bar};
-use crate::gui::render::TerminalProgram;
+use crate::gui::components::tab_bar::TabItem;
+use crate::gui::components::{chip, panel, secondary as button_secondary, tab_bar};
+use crate::gui::render::{Guides, LinkTarget, TerminalProgram};
 use crate::gui::theme::{RADIUS_SMALL, SPACING_LARGE, SPACING_NORMAL, SPACING_SMALL};
-use iced::widget::{button, column, container, image, stack, text};
+use crate::ssh::forward::ForwardStatus;
+use crate::terminal::assist::AssistAction;
+use crate::terminal::{paste, timestamps};
+use iced::widget::{button, column, container, image, row, stack, svg, text};
 use iced::{Alignment, Background, Border, Color, Element, Length};
 use std::sync::LazyLock;
 
@@ -23,23 +37,63 @@ static LOGO_HANDLE: LazyLock<image::Handle> =
 
 impl App {
     pub fn view(&self) -> Element<'_, Message> {
+        if self.screen_locked {
+            return self.view_lock_screen();
+        }
         self.view_main()
     }
 
+    /// Stands in for the whole window while the idle lock is on, so no
+    /// terminal content or tab title shows.
+    fn view_lock_screen(&self) -> Element<'_, Message> {
+        let palette = self.palette;
+        let message = column![
+            text(t!("terminal.locked")).size(20).color(palette.text),
+            text(t!("terminal.locked_hint"))
+                .size(13)
+                .color(palette.text_secondary),
+        ]
+        .spacing(SPACING_SMALL)
+        .align_x(Alignment::Center);
+        let screen = container(message)
+            .center(Length::Fill)
+            .style(move |_| container::Style {
+                background: Some(Background::Color(palette.background)),
+                ..Default::default()
+            });
+        iced::widget::mouse_area(screen)
+            .on_press(Message::Unlock)
+            .into()
+    }
+
     fn view_main(&self) -> Element<'_, Message> {
         let palette = self.palette;
-        let tabs_iter = self
-            .tabs
-            .iter()
-            .enumerate()
-            .map(|(i, tab)| (tab.title(), i, i == self.active_tab));
+        let tabs_iter = self.tabs.iter().enumerate().map(|(i, tab)| TabItem {
+            title: tab.title(),
+            index: i,
+            active: i == self.active_tab,
+            locked: tab.locked(),
+            elevated: self.elevated && tab.runs_local_shell(),
+            color: tab.color,
+            icon: tab.icon.as_deref(),
+            forwards: tab.forward_summary(),
+            command_done: tab.command_done && i != self.active_tab,
+            resources: self.tab_resources.get(&tab.id),
+        });
         let settings_iter = self
             .settings_open
-            .then_some((
-                "Settings",
-                SETTINGS_TAB_INDEX,
-                self.active_tab == SETTINGS_TAB_INDEX,
-            ))
+            .then_some(TabItem {
+                title: "Settings",
+                index: SETTINGS_TAB_INDEX,
+                active: self.active_tab == SETTINGS_TAB_INDEX,
+                locked: false,
+                elevated: false,
+                color: None,
+                icon: None,
+                forwards: None,
+                command_done: false,
+                resources: None,
+            })
             .into_iter();
         let tabs_iter = tabs_iter.chain(settings_iter);
         let ui_alpha = self.config.theme.background_opacity;
@@ -56,11 +110,17 @@ impl App {
         } else {
             None
         };
+        let (_, max_title_chars, overflow) = self.tab_strip_fit();
+        let tab_list_toggle =
+            (overflow || self.tab_list.is_some()).then_some(Message::ToggleTabList);
         let tab_row = tab_bar(
             tabs_iter,
+            max_title_chars,
             Message::OpenShellPicker,
             Message::Settings(SettingsMessage::OpenTab),
+            tab_list_toggle,
             sftp_toggle,
+            self.secure_input_indicator(),
             bar_alpha,
             tab_alpha,
             self.dragging_tab,
@@ -121,36 +181,97 @@ impl App {
             );
         }
 
-        if let Some(text) = self.pending_paste.as_deref() {
-            let line_count = text.lines().count().max(1);
-            let description =
-                t!("dialog.paste_multiline_body").replace("{count}", &line_count.to_string());
+        if let Some(pending) = self.pending_paste.as_ref() {
+            let count = pending.size.lines.to_string();
+            let (title, description) = if pending.into_secret {
+                (
+                    t!("dialog.paste_secret_title"),
+                    t!("dialog.paste_secret_body").replace("{count}", &count),
+                )
+            } else if pending.preview.is_some() {
+                (
+                    t!("dialog.paste_large_title"),
+                    t!("dialog.paste_large_body")
+                        .replace("{count}", &count)
+                        .replace("{size}", &paste::format_size(pending.size.bytes)),
+                )
+            } else {
+                (
+                    t!("dialog.paste_multiline_title"),
+                    t!("dialog.paste_multiline_body").replace("{count}", &count),
+                )
+            };
+            let buttons = vec![
+                DialogButton {
+                    label: t!("dialog.cancel").into(),
+                    message: Message::CancelPaste,
+                    primary: false,
+                },
+                DialogButton {
+                    label: t!("dialog.paste").into(),
+                    message: Message::ConfirmPaste,
+                    primary: true,
+                },
+            ];
+            if let Some(preview) = pending.preview.as_deref() {
+                return preview_dialog(
+                    base_layout,
+                    title,
+                    &description,
+                    preview,
+                    buttons,
+                    Message::CancelPaste,
+                    palette,
+                    self.config.ui.animations_enabled,
+                );
+            }
             return confirm_dialog(
                 base_layout,
-                t!("dialog.paste_multiline_title"),
+                title,
                 &description,
-                vec![
-                    DialogButton {
-                        label: t!("dialog.cancel").into(),
-                        message: Message::CancelMultilinePaste,
-                        primary: false,
-                    },
-                    DialogButton {
-                        label: t!("dialog.paste").into(),
-                        message: Message::ConfirmMultilinePaste,
-                        primary: true,
-                    },
-                ],
-                Message::CancelMultilinePaste,
+                buttons,
+                Message::CancelPaste,
                 palette,
                 self.config.ui.animations_enabled,
             );
         }
 
+        if let Some(error) = self.gpu_fallback.as_deref() {
+            return confirm_dialog(
+                base_layout,
+                t!("dialog.gpu_fallback_title"),
+                &t!("dialog.gpu_fallback_body").replace("{error}", error),
+                vec![DialogButton {
+                    label: t!("dialog.close").into(),
+                    message: Message::DismissGpuFallback,
+                    primary: true,
+                }],
+                Message::DismissGpuFallback,
+                palette,
+                self.config.ui.animations_enabled,
+            );
+        }
+
+        if let Some(onboarding) = self.onboarding.as_ref() {
+            return self.view_onboarding(base_layout, onboarding);
+        }
+
+        if let Some(url) = self.pending_link.as_deref() {
+            return self.view_link_dialog(base_layout, url);
+        }
+
         if let Some(prompt) = self.password_prompt.as_ref() {
             return password_prompt::password_prompt(base_layout, prompt, palette);
         }
 
+        if let Some(name) = self.workspace_prompt.as_deref() {
+            return self.view_workspace_prompt(base_layout, name);
+        }
+
+        if let Some(prompt) = self.snippet_prompt.as_ref() {
+            return self.view_snippet_prompt(base_layout, prompt);
+        }
+
         if self.show_shell_picker {
             return self.view_shell_picker(base_layout);
         }
@@ -163,15 +284,387 @@ impl App {
             return self.view_terminal_context_menu(base_layout);
         }
 
+        if self.marks_list {
+            return self.view_marks_list(base_layout);
+        }
+
+        if self.cheat_sheet {
+            return self.view_cheat_sheet(base_layout);
+        }
+
+        if let Some(filter) = self.tab_list.as_deref() {
+            return self.view_tab_list(base_layout, filter);
+        }
+
+        if let Some(tab_id) = self.process_list {
+            return self.view_process_list(base_layout, tab_id);
+        }
+
         base_layout.into()
     }
 
+    /// Floats a "jump to bottom" button over the focused pane while it is
+    /// scrolled back, counting the lines that arrived in the meantime.
+    fn with_jump_to_bottom<'a>(
+        &'a self,
+        tab: &'a crate::gui::tab::TerminalTab,
+        terminal_view: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let pane = tab.focused();
+        if pane.scroll_position().0 == 0 || pane.alt_screen() {
+            return terminal_view;
+        }
+        let Some(rect) = self.focused_pane_rect(tab) else {
+            return terminal_view;
+        };
+
+        let label = match pane.unseen_lines() {
+            0 => t!("terminal.jump_to_bottom").to_string(),
+            count => t!("terminal.new_lines").replace("{count}", &count.to_string()),
+        };
+        let jump = button_secondary(
+            label,
+            Some(Message::JumpToBottom),
+            self.palette,
+            self.config.ui.animations_enabled,
+        );
+        let overlay = container(
+            container(jump)
+                .width(Length::Fixed(rect.width))
+                .height(Length::Fixed(rect.height))
+                .align_x(Alignment::End)
+                .align_y(Alignment::End)
+                .padding([SPACING_NORMAL, SPACING_LARGE]),
+        )
+        .padding(iced::Padding {
+            top: rect.y,
+            left: rect.x,
+            ..iced::Padding::ZERO
+        });
+        stack![terminal_view, overlay]
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Marks the focused pane with a lock while the program reads a
+    /// password, so it is clear why typing shows nothing.
+    fn with_secret_input_badge<'a>(
+        &'a self,
+        tab: &'a crate::gui::tab::TerminalTab,
+        terminal_view: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        if !tab.focused().secret_input() {
+            return terminal_view;
+        }
+        let Some(rect) = self.focused_pane_rect(tab) else {
+            return terminal_view;
+        };
+
+        let palette = self.palette;
+        let badge = container(
+            row![
+                svg(crate::gui::icons::lock())
+                    .width(Length::Fixed(11.0))
+                    .height(Length::Fixed(11.0))
+                    .style(move |_theme, _status| svg::Style {
+                        color: Some(palette.text_secondary),
+                    }),
+                text(t!("terminal.secret_input"))
+                    .size(11)
+                    .color(palette.text_secondary),
+            ]
+            .spacing(SPACING_SMALL)
+            .align_y(Alignment::Center),
+        )
+        .padding([2.0, SPACING_NORMAL])
+        .style(move |_| container::Style {
+            background: Some(Background::Color(palette.surface)),
+            border: Border {
+                radius: RADIUS_SMALL.into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.12,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        });
+        let overlay = container(
+            container(badge)
+                .width(Length::Fixed(rect.width))
+                .align_x(Alignment::End)
+                .padding([SPACING_SMALL, SPACING_NORMAL]),
+        )
+        .padding(iced::Padding {
+            top: rect.y,
+            left: rect.x,
+            ..iced::Padding::ZERO
+        });
+        stack![terminal_view, overlay]
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Shown in the focused pane's bottom-left corner while its output is
+    /// being recorded.
+    fn with_recording_badge<'a>(
+        &'a self,
+        tab: &'a crate::gui::tab::TerminalTab,
+        terminal_view: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        if !tab.focused().is_recording() {
+            return terminal_view;
+        }
+        let Some(rect) = self.focused_pane_rect(tab) else {
+            return terminal_view;
+        };
+
+        let palette = self.palette;
+        let badge = container(
+            text(t!("terminal.recording"))
+                .size(11)
+                .color(palette.text_secondary),
+        )
+        .padding([2.0, SPACING_NORMAL])
+        .style(move |_| container::Style {
+            background: Some(Background::Color(palette.surface)),
+            border: Border {
+                radius: RADIUS_SMALL.into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.12,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        });
+        let overlay = container(
+            container(badge)
+                .height(Length::Fixed(rect.height))
+                .align_y(Alignment::End)
+                .padding([SPACING_SMALL, SPACING_NORMAL]),
+        )
+        .padding(iced::Padding {
+            top: rect.y,
+            left: rect.x,
+            ..iced::Padding::ZERO
+        });
+        stack![terminal_view, overlay]
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// The tab bar's lock while Secure Keyboard Entry is on, lit while it
+    /// is held; pressing it turns the setting off.
+    fn secure_input_indicator(&self) -> Option<(Message, bool)> {
+        (crate::platform::SECURE_INPUT_SUPPORTED && self.config.terminal.secure_keyboard_entry)
+            .then(|| {
+                (
+                    Message::Settings(SettingsMessage::SecureKeyboardEntryToggled(false)),
+                    self.secure_input,
+                )
+            })
+    }
+
+    /// Across the bottom of every pane whose session ended and that was held
+    /// open on its final screen.
+    fn with_exit_banners<'a>(
+        &'a self,
+        tab: &'a crate::gui::tab::TerminalTab,
+        terminal_view: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let palette = self.palette;
+        let mut layers = vec![terminal_view];
+        for (id, rect) in tab.regions(iced::Rectangle::with_size(self.terminal_area)) {
+            let Some(exit) = tab.panes.iter().find(|p| p.id == id).and_then(|p| p.exit) else {
+                continue;
+            };
+            let label = match exit.code {
+                Some(code) => t!("terminal.exited_code", code = code),
+                None => t!("terminal.exited"),
+            };
+            let banner = container(
+                row![
+                    text(label).size(12).color(palette.text),
+                    iced::widget::Space::new().width(Length::Fill),
+                    button_secondary(
+                        t!("terminal.exited_close"),
+                        Some(Message::CloseExitedPane { pane: id }),
+                        palette,
+                        self.config.ui.animations_enabled,
+                    ),
+                ]
+                .spacing(SPACING_NORMAL)
+                .align_y(Alignment::Center),
+            )
+            .width(Length::Fill)
+            .padding([SPACING_SMALL, SPACING_NORMAL])
+            .style(move |_| container::Style {
+                background: Some(Background::Color(palette.surface)),
+                border: Border {
+                    radius: RADIUS_SMALL.into(),
+                    width: 1.0,
+                    color: Color {
+                        a: 0.12,
+                        ..palette.text
+                    },
+                },
+                ..Default::default()
+            });
+            let overlay = container(
+                container(banner)
+                    .width(Length::Fixed(rect.width))
+                    .height(Length::Fixed(rect.height))
+                    .align_y(Alignment::End)
+                    .padding([SPACING_SMALL, SPACING_NORMAL]),
+            )
+            .padding(iced::Padding {
+                top: rect.y,
+                left: rect.x,
+                ..iced::Padding::ZERO
+            });
+            layers.push(overlay.into());
+        }
+        if layers.len() == 1 {
+            return layers.remove(0);
+        }
+        iced::widget::Stack::with_children(layers)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Grid size and the count of sequences nothing handled, in the focused
+    /// pane's top-left corner.
+    fn with_debug_overlay<'a>(
+        &'a self,
+        tab: &'a crate::gui::tab::TerminalTab,
+        terminal_view: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        if !self.debug_overlay {
+            return terminal_view;
+        }
+        let Some(rect) = self.focused_pane_rect(tab) else {
+            return terminal_view;
+        };
+
+        let palette = self.palette;
+        let pane = tab.focused();
+        let size = pane.size();
+        let grid = t!("terminal.debug_grid")
+            .replace("{columns}", &size.columns.to_string())
+            .replace("{lines}", &size.lines.to_string());
+        let unhandled = t!("terminal.debug_unhandled")
+            .replace("{count}", &pane.unhandled_sequences().to_string());
+        let info = container(
+            column![
+                text(grid).size(11).color(palette.text_secondary),
+                text(unhandled).size(11).color(palette.text_secondary),
+            ]
+            .spacing(2),
+        )
+        .padding([2.0, SPACING_NORMAL])
+        .style(move |_| container::Style {
+            background: Some(Background::Color(palette.surface)),
+            border: Border {
+                radius: RADIUS_SMALL.into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.12,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        });
+        let overlay = container(info).padding(iced::Padding {
+            top: rect.y + SPACING_SMALL,
+            left: rect.x + SPACING_NORMAL,
+            ..iced::Padding::ZERO
+        });
+        stack![terminal_view, overlay]
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Shows the history suggestion under the cursor of the focused pane.
+    fn with_autocomplete<'a>(
+        &'a self,
+        tab: &'a crate::gui::tab::TerminalTab,
+        terminal_view: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        let Some((typed, rest)) = self.autocomplete() else {
+            return terminal_view;
+        };
+        let (Some(rect), Some((col, row))) =
+            (self.focused_pane_rect(tab), tab.focused().cursor_cell())
+        else {
+            return terminal_view;
+        };
+
+        let palette = self.palette;
+        let [cell_width, cell_height] = self.cell_size();
+        let popup = container(
+            row![
+                text(typed).size(12).color(palette.text_secondary),
+                text(rest).size(12).color(palette.text),
+                text(t!("terminal.autocomplete_accept"))
+                    .size(11)
+                    .color(palette.text_secondary),
+            ]
+            .spacing(2)
+            .align_y(Alignment::Center),
+        )
+        .padding([2.0, SPACING_SMALL])
+        .style(move |_| container::Style {
+            background: Some(Background::Color(palette.surface)),
+            border: Border {
+                radius: RADIUS_SMALL.into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.15,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        });
+        let left =
+            self.config.terminal.padding_x + (self.gutter_columns() + col) as f32 * cell_width;
+        // Below the cursor, or above it on the last row.
+        let below = if row + 1 < tab.focused().size().lines {
+            row + 1
+        } else {
+            row.saturating_sub(1)
+        };
+        let top = self.config.terminal.padding_y + below as f32 * cell_height;
+        let overlay = container(popup).padding(iced::Padding {
+            top: rect.y + top,
+            left: rect.x + left,
+            ..iced::Padding::ZERO
+        });
+        stack![terminal_view, overlay]
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn focused_pane_rect(&self, tab: &crate::gui::tab::TerminalTab) -> Option<iced::Rectangle> {
+        tab.regions(iced::Rectangle::with_size(self.terminal_area))
+            .into_iter()
+            .find_map(|(id, rect)| (id == tab.focused).then_some(rect))
+    }
+
     fn view_terminal<'a>(&'a self, tab: &'a crate::gui::tab::TerminalTab) -> Element<'a, Message> {
         let active_tab = tab.focused();
 
         // identical to other panes (e.g. Settings) and avoids double blending.
         let clear_color = [0.0, 0.0, 0.0, 0.0];
         let cursor_visible = !self.config.terminal.cursor_blink || self.cursor_blink_on;
+        let gutter_columns = self.gutter_columns();
+        let now = std::time::SystemTime::now();
         let terminal_widget = TerminalProgram {
             panes: tab
                 .panes
@@ -182,6 +675,7 @@ impl App {
                         id: pane.id,
                         scroll_history: pane_history,
                         cells: pane.render_cells(),
+                        images: pane.render_images(),
                         grid_size: pane.size(),
                         selection: pane.selection,
                         display_offset: pane_offset,
@@ -189,16 +683,29 @@ impl App {
                             .cursor_cell()
                             .map(|(col, row)| [col as u32, row as u32])
                             .filter(|_| pane.id == tab.focused),
-                        cursor_visible,
+                        cursor_visible: cursor_visible || pane.in_copy_mode(),
                         cursor_color: pane.cursor_color(),
                         mouse_mode: pane.mouse_mode(),
+                        gutter_columns,
+                        wrapped: if self.config.terminal.wrap_indicator {
+                            pane.wrapped_rows()
+                        } else {
+                            Vec::new()
+                        },
+                        gutter: if gutter_columns == 0 {
+                            Vec::new()
+                        } else {
+                            timestamps::labels(
+                                &pane.line_times(),
+                                self.config.terminal.timestamps,
+                                now,
+                            )
+                        },
+                        alt_screen: pane.alt_screen(),
                     }
                 })
                 .collect(),
-            cell_size: [
-                self.config.terminal.cell_width.max(1.0),
-                self.config.terminal.cell_height.max(1.0),
-            ],
+            cell_size: self.cell_size(),
             scrollbar_color: [
                 self.palette.text_secondary.r,
                 self.palette.text_secondary.g,
@@ -218,7 +725,8 @@ impl App {
                 self.palette.text.b,
                 0.07,
             ],
-            layout: tab.layout.clone(),
+            layout: tab.visible_layout(),
+            zoomed: tab.zoomed(),
             terminal_font_selection: self.config.terminal.font_selection.clone(),
             terminal_font_size: self.config.terminal.font_size,
             padding: [
@@ -228,12 +736,55 @@ impl App {
             clear_color,
             cursor_shape: self.config.terminal.cursor_shape,
             background_opacity: self.config.theme.background_opacity,
+            text_rendering: self.config.terminal.text_rendering,
+            font_synthesize: self.config.terminal.font_synthesize,
+            cursor_trail: (self.config.terminal.cursor_trail && self.config.ui.animations_enabled)
+                .then(|| {
+                    std::time::Duration::from_millis(self.config.terminal.cursor_trail_ms.into())
+                }),
+            smooth_scroll: self.config.terminal.smooth_scroll && self.config.ui.animations_enabled,
+            guides: Guides {
+                cursor_row: self.config.terminal.highlight_cursor_row,
+                rulers: self.config.terminal.column_rulers.clone(),
+                row_color: [
+                    self.palette.text.r,
+                    self.palette.text.g,
+                    self.palette.text.b,
+                    0.05,
+                ],
+                ruler_color: [
+                    self.palette.text.r,
+                    self.palette.text.g,
+                    self.palette.text.b,
+                    0.14,
+                ],
+                gutter_color: [
+                    self.palette.text_secondary.r,
+                    self.palette.text_secondary.g,
+                    self.palette.text_secondary.b,
+                    0.6,
+                ],
+            },
+            word_separators: self.config.selection.word_separators.clone(),
+            inactive_dim: self.config.terminal.inactive_pane_dim,
+            window_dim: if self.config.terminal.dim_unfocused_window && !self.window_focused {
+                self.config.terminal.inactive_pane_dim
+            } else {
+                0.0
+            },
+            capture: self.capture.clone(),
         }
         .widget()
         .width(Length::Fill)
         .height(Length::Fill);
 
         let terminal_view: Element<Message> = terminal_widget.into();
+        let terminal_view = self.with_jump_to_bottom(tab, terminal_view);
+        let terminal_view = self.with_secret_input_badge(tab, terminal_view);
+        let terminal_view = self.with_recording_badge(tab, terminal_view);
+        let terminal_view = self.with_exit_banners(tab, terminal_view);
+        let terminal_view = self.with_autocomplete(tab, terminal_view);
+        let terminal_view = self.with_debug_overlay(tab, terminal_view);
 
         let now = iced::time::Instant::now();
         let drawer_progress: f32 = active_tab
@@ -270,6 +821,36 @@ impl App {
             terminal_view
         };
 
+        // Where a modifier-click would go, like a browser's status bar.
+        let with_drawer: Element<Message> = if let Some(link) = self.hovered_link.as_deref() {
+            let palette = self.palette;
+            let hint = container(text(link.to_string()).size(12).color(palette.text))
+                .padding([4, 8])
+                .style(move |_theme: &iced::Theme| container::Style {
+                    background: Some(Background::Color(palette.surface)),
+                    border: Border {
+                        radius: RADIUS_SMALL.into(),
+                        width: 1.0,
+                        color: Color {
+                            a: 0.15,
+                            ..palette.text
+                        },
+                    },
+                    ..Default::default()
+                });
+            let overlay = container(hint)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_y(Alignment::End)
+                .padding(SPACING_SMALL);
+            stack![with_drawer, overlay]
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into()
+        } else {
+            with_drawer
+        };
+
         // Visual bell: a translucent flash that fades out over its duration.
         let with_flash: Element<Message> = if let Some(progress) = self
             .bell_flash_start
@@ -298,24 +879,63 @@ impl App {
         };
 
         let (cursor_col, cursor_row) = active_tab.cursor_position();
+        let [cell_width, cell_height] = self.cell_size();
         let cursor_cell = crate::gui::components::ime_wrapper::CursorCell {
             col: cursor_col,
             row: cursor_row,
-            cell_width: self.config.terminal.cell_width.max(1.0),
-            cell_height: self.config.terminal.cell_height.max(1.0),
+            cell_width,
+            cell_height,
             padding: [
-                self.config.terminal.padding_x,
+                self.config.terminal.padding_x + gutter_columns as f32 * cell_width,
                 self.config.terminal.padding_y,
             ],
-            layout: tab.layout.clone(),
+            layout: tab.visible_layout(),
             focused: tab.focused,
         };
 
-        ImeEnabled::new(with_flash)
+        let terminal: Element<Message> = ImeEnabled::new(with_flash)
             .preedit(self.ime_preedit.clone())
             .cursor_cell(Some(cursor_cell))
             .text_size(self.config.terminal.font_size)
-            .into()
+            .into();
+        let terminal = match self.quick_actions_bar(active_tab) {
+            Some(bar) => column![bar, terminal].into(),
+            None => terminal,
+        };
+        let terminal = match self.view_side_panel() {
+            Some(panel) => row![terminal, panel].into(),
+            None => terminal,
+        };
+        match self.view_status_bar(active_tab) {
+            Some(bar) => column![terminal, bar].into(),
+            None => terminal,
+        }
+    }
+
+    /// A row of buttons for the quick actions of the focused pane's
+    /// profile; `None` when it defines none.
+    fn quick_actions_bar<'a>(
+        &self,
+        pane: &'a crate::gui::tab::Pane,
+    ) -> Option<Element<'a, Message>> {
+        let actions = &pane.profile.actions;
+        if actions.is_empty() {
+            return None;
+        }
+        let buttons = actions.iter().enumerate().map(|(index, action)| {
+            chip(
+                &action.name,
+                Message::RunQuickAction(index),
+                self.palette,
+                self.config.ui.animations_enabled,
+            )
+        });
+        Some(
+            container(row(buttons).spacing(SPACING_SMALL))
+                .padding([SPACING_SMALL, SPACING_NORMAL])
+                .width(Length::Fill)
+                .into(),
+        )
     }
 
     fn view_lobby(&self, palette: crate::gui::theme::Palette) -> Element<'_, Message> {
@@ -366,6 +986,7 @@ impl App {
                     }
                     crate::gui::tab::ProfileKind::Local { .. } => t!("session_kind.shell"),
                     crate::gui::tab::ProfileKind::Ssh(_) => t!("session_kind.ssh"),
+                    crate::gui::tab::ProfileKind::Network { .. } => t!("session_kind.network"),
                 };
 
                 let label_col = column![
@@ -453,16 +1074,8 @@ impl App {
     ) -> Element<'a, Message> {
         context_menu(
             base_layout,
-            vec![
-                ContextMenuItem {
-                    label: t!("context_menu.duplicate"),
-                    message: Message::DuplicateTab,
-                },
-                ContextMenuItem {
-                    label: t!("context_menu.close"),
-                    message: Message::CloseTab(tab_index),
-                },
-            ],
+            self.tab_context_menu_items(tab_index),
+            self.menu_cursor,
             self.cursor_position,
             Message::CloseTabContextMenu,
             self.palette,
@@ -470,34 +1083,192 @@ impl App {
         )
     }
 
+    pub(in crate::gui::app) fn tab_context_menu_items(
+        &self,
+        tab_index: usize,
+    ) -> Vec<ContextMenuItem> {
+        let mut items = vec![
+            ContextMenuItem {
+                label: t!("context_menu.duplicate").into(),
+                message: Message::DuplicateTab,
+            },
+            ContextMenuItem {
+                label: if self.tabs.get(tab_index).is_some_and(|tab| tab.locked()) {
+                    t!("context_menu.unlock_input")
+                } else {
+                    t!("context_menu.lock_input")
+                }
+                .into(),
+                message: Message::ToggleTabLock(tab_index),
+            },
+            ContextMenuItem {
+                label: t!("context_menu.tab_color").into(),
+                message: Message::CycleTabColor(tab_index),
+            },
+            ContextMenuItem {
+                label: t!("context_menu.tab_icon").into(),
+                message: Message::CycleTabIcon(tab_index),
+            },
+            ContextMenuItem {
+                label: t!("context_menu.save_workspace").into(),
+                message: Message::ShowWorkspacePrompt,
+            },
+        ];
+        if let Some(pane) = self.tabs.get(tab_index).map(|tab| tab.focused()) {
+            if pane.working_directory().is_some() {
+                items.push(ContextMenuItem {
+                    label: t!("context_menu.open_directory").into(),
+                    message: Message::OpenTabDirectory(tab_index),
+                });
+            }
+            if pane.current_directory().is_some() {
+                items.push(ContextMenuItem {
+                    label: t!("context_menu.copy_directory").into(),
+                    message: Message::CopyTabDirectory(tab_index),
+                });
+            }
+            for (index, (forward, status)) in pane.port_forwards().into_iter().enumerate() {
+                items.push(ContextMenuItem {
+                    label: forward_menu_label(&forward, &status).into(),
+                    message: Message::TogglePortForward {
+                        pane: pane.id,
+                        index,
+                    },
+                });
+            }
+        }
+        if !self.closed_tabs.is_empty() {
+            items.push(ContextMenuItem {
+                label: t!("context_menu.reopen_tab").into(),
+                message: Message::ReopenClosedTab,
+            });
+        }
+        if crate::platform::SECURE_INPUT_SUPPORTED {
+            let enabled = self.config.terminal.secure_keyboard_entry;
+            items.push(ContextMenuItem {
+                label: if enabled {
+                    t!("context_menu.secure_input_off")
+                } else {
+                    t!("context_menu.secure_input_on")
+                }
+                .into(),
+                message: Message::Settings(SettingsMessage::SecureKeyboardEntryToggled(!enabled)),
+            });
+        }
+        items.push(ContextMenuItem {
+            label: t!("context_menu.close").into(),
+            message: Message::CloseTab(tab_index),
+        });
+        items
+    }
+
     fn view_terminal_context_menu<'a>(
         &'a self,
         base_layout: impl Into<Element<'a, Message>>,
     ) -> Element<'a, Message> {
+        context_menu(
+            base_layout,
+            self.terminal_context_menu_items(),
+            self.menu_cursor,
+            self.cursor_position,
+            Message::CloseTerminalContextMenu,
+            self.palette,
+            self.config.ui.animations_enabled,
+        )
+    }
+
+    pub(in crate::gui::app) fn terminal_context_menu_items(&self) -> Vec<ContextMenuItem> {
         let has_selection = self
             .focused_pane()
             .and_then(|pane| pane.selected_text())
             .is_some();
+        let has_last_output = self
+            .focused_pane()
+            .is_some_and(|pane| pane.has_last_output());
 
         let mut items = Vec::new();
+        if let Some((_, link)) = &self.terminal_context_link {
+            items.push(ContextMenuItem {
+                label: match link {
+                    LinkTarget::Url(_) => t!("context_menu.open_link"),
+                    LinkTarget::Path(_) => t!("context_menu.open_path"),
+                }
+                .into(),
+                message: Message::TerminalContextOpenLink,
+            });
+        }
         if has_selection {
             items.push(ContextMenuItem {
-                label: t!("context_menu.copy"),
+                label: t!("context_menu.copy").into(),
                 message: Message::TerminalContextCopy,
             });
         }
+        if has_last_output {
+            items.push(ContextMenuItem {
+                label: t!("context_menu.copy_last_output").into(),
+                message: Message::TerminalContextCopyOutput,
+            });
+        }
+        if self.config.terminal.assist_command.is_some() && (has_selection || has_last_output) {
+            items.push(ContextMenuItem {
+                label: t!("context_menu.explain").into(),
+                message: Message::Assist(AssistAction::Explain),
+            });
+            items.push(ContextMenuItem {
+                label: t!("context_menu.fix").into(),
+                message: Message::Assist(AssistAction::Fix),
+            });
+        }
         items.push(ContextMenuItem {
-            label: t!("context_menu.paste"),
+            label: t!("context_menu.rerun_command").into(),
+            message: Message::TerminalContextRerun,
+        });
+        items.push(ContextMenuItem {
+            label: t!("context_menu.paste").into(),
             message: Message::TerminalContextPaste,
         });
+        items.push(ContextMenuItem {
+            label: t!("context_menu.select_all").into(),
+            message: Message::TerminalContextSelectAll,
+        });
+        items.push(ContextMenuItem {
+            label: if has_selection {
+                t!("context_menu.save_selection_image")
+            } else {
+                t!("context_menu.save_image")
+            }
+            .into(),
+            message: Message::SaveScreenshot,
+        });
+        if has_selection {
+            items.push(ContextMenuItem {
+                label: t!("context_menu.search_web").into(),
+                message: Message::TerminalContextSearchWeb,
+            });
+        }
+        items.push(ContextMenuItem {
+            label: t!("context_menu.clear_buffer").into(),
+            message: Message::TerminalContextClearBuffer,
+        });
+        items
+    }
+}
 
-        context_menu(
-            base_layout,
-            items,
-            self.cursor_position,
-            Message::CloseTerminalContextMenu,
-            self.palette,
-            self.config.ui.animations_enabled,
-        )
+/// `● -L 8080:localhost:80`, with the error appended when it failed.
+fn forward_menu_label(forward: &PortForward, status: &ForwardStatus) -> String {
+    const MAX_ERROR_LEN: usize = 32;
+    let mark = match status {
+        ForwardStatus::Active => "\u{25CF}",
+        ForwardStatus::Stopped => "\u{25CB}",
+        ForwardStatus::Failed(_) => "\u{2715}",
+    };
+    let label = format!("{mark} {} {}", forward.kind.flag(), forward.spec());
+    match status {
+        ForwardStatus::Failed(err) if err.chars().count() > MAX_ERROR_LEN => {
+            let short: String = err.chars().take(MAX_ERROR_LEN - 1).collect();
+            format!("{label} ({short}\u{2026})")
+        }
+        ForwardStatus::Failed(err) => format!("{label} ({err})"),
+        _ => label,
     }
 }
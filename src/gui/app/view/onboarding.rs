@@ -0,0 +1,209 @@
+use super::super::{App, Message, Onboarding, OnboardingMessage, OnboardingStep};
+use crate::config::ShortcutPreset;
+use crate::gui::components::{menu_item_highlighted, primary, secondary};
+use crate::gui::tab::ProfileKind;
+use crate::gui::theme::{RADIUS_NORMAL, SPACING_NORMAL, SPACING_SMALL};
+use iced::widget::{Space, center, column, container, opaque, row, scrollable, stack, text};
+use iced::{Alignment, Background, Border, Color, Element, Length};
+
+const WIZARD_WIDTH: f32 = 420.0;
+const CHOICES_HEIGHT: f32 = 240.0;
+const FONT_SIZE_STEP: f32 = 1.0;
+const MIN_FONT_SIZE: f32 = 8.0;
+const MAX_FONT_SIZE: f32 = 32.0;
+
+impl App {
+    /// The first-run wizard: theme, font size, default shell and shortcut
+    /// preset, one step at a time.
+    pub(in crate::gui) fn view_onboarding<'a>(
+        &'a self,
+        base_layout: impl Into<Element<'a, Message>>,
+        onboarding: &'a Onboarding,
+    ) -> Element<'a, Message> {
+        let palette = self.palette;
+        let animations_enabled = self.config.ui.animations_enabled;
+
+        let (title, body, choices) = match onboarding.step {
+            OnboardingStep::Theme => (
+                t!("onboarding.theme_title"),
+                t!("onboarding.theme_body"),
+                self.onboarding_theme_choices(),
+            ),
+            OnboardingStep::Font => (
+                t!("onboarding.font_title"),
+                t!("onboarding.font_body"),
+                self.onboarding_font_size(),
+            ),
+            OnboardingStep::Shell => (
+                t!("onboarding.shell_title"),
+                t!("onboarding.shell_body"),
+                self.onboarding_shell_choices(onboarding),
+            ),
+            OnboardingStep::Shortcuts => (
+                t!("onboarding.keys_title"),
+                t!("onboarding.keys_body"),
+                column(ShortcutPreset::ALL.map(|preset| {
+                    menu_item_highlighted(
+                        preset.label(),
+                        Message::Onboarding(OnboardingMessage::PickShortcuts(preset)),
+                        onboarding.shortcuts == preset,
+                        palette,
+                        animations_enabled,
+                    )
+                }))
+                .spacing(2)
+                .into(),
+            ),
+        };
+
+        let index = onboarding.step.index();
+        let last = index + 1 == OnboardingStep::ALL.len();
+        let progress = t!("onboarding.step")
+            .replace("{step}", &(index + 1).to_string())
+            .replace("{count}", &OnboardingStep::ALL.len().to_string());
+        let buttons = row![
+            secondary(
+                t!("onboarding.skip"),
+                Some(Message::Onboarding(OnboardingMessage::Skip)),
+                palette,
+                animations_enabled,
+            ),
+            Space::new().width(Length::Fill),
+            secondary(
+                t!("onboarding.back"),
+                (index > 0).then(|| Message::Onboarding(OnboardingMessage::Back)),
+                palette,
+                animations_enabled,
+            ),
+            primary(
+                if last {
+                    t!("onboarding.finish")
+                } else {
+                    t!("onboarding.next")
+                },
+                Message::Onboarding(OnboardingMessage::Next),
+                palette,
+                animations_enabled,
+            ),
+        ]
+        .spacing(SPACING_SMALL)
+        .align_y(Alignment::Center);
+
+        let card = container(
+            column![
+                text(progress).size(11).color(palette.text_secondary),
+                text(title).size(16),
+                text(body).size(13).color(palette.text_secondary),
+                choices,
+                buttons,
+            ]
+            .spacing(SPACING_NORMAL)
+            .padding(20)
+            .width(Length::Fixed(WIZARD_WIDTH)),
+        )
+        .style(move |_theme: &iced::Theme| container::Style {
+            background: Some(Background::Color(palette.surface)),
+            border: Border {
+                radius: (RADIUS_NORMAL + 4.0).into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.15,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        });
+
+        let backdrop = container(text(""))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(|_theme: &iced::Theme| container::Style {
+                background: Some(Background::Color(Color {
+                    a: 0.5,
+                    ..Color::BLACK
+                })),
+                ..Default::default()
+            });
+
+        stack![
+            base_layout.into(),
+            opaque(backdrop),
+            center(card).width(Length::Fill).height(Length::Fill),
+        ]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    fn onboarding_theme_choices(&self) -> Element<'_, Message> {
+        let current = self.config.theme.color_scheme.as_str();
+        let rows = crate::terminal::theme::all_presets().iter().map(|preset| {
+            menu_item_highlighted(
+                &preset.name,
+                Message::Onboarding(OnboardingMessage::PickTheme(preset.name.clone())),
+                preset.name.eq_ignore_ascii_case(current),
+                self.palette,
+                self.config.ui.animations_enabled,
+            )
+        });
+        scrollable(column(rows).spacing(2))
+            .height(Length::Fixed(CHOICES_HEIGHT))
+            .into()
+    }
+
+    fn onboarding_font_size(&self) -> Element<'_, Message> {
+        let palette = self.palette;
+        let animations_enabled = self.config.ui.animations_enabled;
+        let size = self.config.terminal.font_size;
+        let step = |delta: f32| {
+            let next = (size + delta).clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
+            (next != size).then_some(Message::Onboarding(OnboardingMessage::FontSize(next)))
+        };
+        column![
+            row![
+                secondary("−", step(-FONT_SIZE_STEP), palette, animations_enabled),
+                text(format!("{size:.0} pt")).size(14),
+                secondary("+", step(FONT_SIZE_STEP), palette, animations_enabled),
+            ]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center),
+            text(t!("onboarding.font_sample"))
+                .size(size)
+                .font(iced::Font::MONOSPACE),
+        ]
+        .spacing(SPACING_NORMAL)
+        .into()
+    }
+
+    fn onboarding_shell_choices(&self, onboarding: &Onboarding) -> Element<'_, Message> {
+        let mut names = vec![None];
+        for profile in self
+            .session_local_profiles()
+            .into_iter()
+            .chain(self.available_shells.iter().cloned())
+        {
+            if matches!(profile.kind, ProfileKind::Local { program: None, .. }) {
+                continue;
+            }
+            let name = Some(profile.display_name());
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        let rows = names.into_iter().map(|name| {
+            let label = name
+                .clone()
+                .unwrap_or_else(|| t!("onboarding.system_shell").to_string());
+            menu_item_highlighted(
+                label,
+                Message::Onboarding(OnboardingMessage::PickProfile(name.clone())),
+                onboarding.default_profile == name,
+                self.palette,
+                self.config.ui.animations_enabled,
+            )
+        });
+        scrollable(column(rows).spacing(2))
+            .height(Length::Fixed(CHOICES_HEIGHT))
+            .into()
+    }
+}
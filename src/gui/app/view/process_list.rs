@@ -0,0 +1,152 @@
+use super::super::{App, Message};
+use crate::config::TabBarPosition;
+use crate::gui::theme::{RADIUS_NORMAL, SPACING_SMALL};
+use crate::session::resources::compact_bytes;
+use iced::widget::{column, container, mouse_area, row, scrollable, stack, text};
+use iced::{Alignment, Background, Border, Color, Element, Font, Length};
+
+const LIST_WIDTH: f32 = 360.0;
+const LIST_MAX_HEIGHT: f32 = 420.0;
+const NUMBER_WIDTH: f32 = 56.0;
+
+impl App {
+    /// The processes under a tab with what each used over the last sample,
+    /// opened from the tab's resource badge.
+    pub(in crate::gui) fn view_process_list<'a>(
+        &'a self,
+        base_layout: impl Into<Element<'a, Message>>,
+        tab_id: u64,
+    ) -> Element<'a, Message> {
+        let palette = self.palette;
+        let title = self
+            .tabs
+            .iter()
+            .find(|tab| tab.id == tab_id)
+            .map(|tab| tab.title())
+            .unwrap_or_default();
+
+        let cell = |value: String, width: Length, align: Alignment, color: Color| {
+            container(text(value).size(12).font(Font::MONOSPACE).color(color))
+                .width(width)
+                .align_x(align)
+        };
+        let header_color = palette.text_secondary;
+        let header = row![
+            cell(
+                t!("process_list.name").into(),
+                Length::Fill,
+                Alignment::Start,
+                header_color
+            ),
+            cell(
+                t!("process_list.pid").into(),
+                Length::Fixed(NUMBER_WIDTH),
+                Alignment::End,
+                header_color
+            ),
+            cell(
+                t!("process_list.cpu").into(),
+                Length::Fixed(NUMBER_WIDTH),
+                Alignment::End,
+                header_color
+            ),
+            cell(
+                t!("process_list.memory").into(),
+                Length::Fixed(NUMBER_WIDTH),
+                Alignment::End,
+                header_color
+            ),
+        ]
+        .spacing(SPACING_SMALL);
+
+        let mut rows: Vec<Element<Message>> = Vec::new();
+        match self.tab_resources.get(&tab_id) {
+            Some(usage) if !usage.processes.is_empty() => {
+                for process in &usage.processes {
+                    rows.push(
+                        row![
+                            cell(
+                                process.name.clone(),
+                                Length::Fill,
+                                Alignment::Start,
+                                palette.text
+                            ),
+                            cell(
+                                process.pid.to_string(),
+                                Length::Fixed(NUMBER_WIDTH),
+                                Alignment::End,
+                                palette.text_secondary,
+                            ),
+                            cell(
+                                format!("{:.1}", process.cpu_percent),
+                                Length::Fixed(NUMBER_WIDTH),
+                                Alignment::End,
+                                palette.text,
+                            ),
+                            cell(
+                                compact_bytes(process.rss_bytes),
+                                Length::Fixed(NUMBER_WIDTH),
+                                Alignment::End,
+                                palette.text,
+                            ),
+                        ]
+                        .spacing(SPACING_SMALL)
+                        .into(),
+                    );
+                }
+            }
+            _ => rows.push(
+                text(t!("process_list.empty"))
+                    .size(12)
+                    .color(palette.text_secondary)
+                    .into(),
+            ),
+        }
+
+        let card = container(
+            column![
+                text(title).size(13).color(palette.text),
+                header,
+                scrollable(column(rows).spacing(2)),
+            ]
+            .spacing(SPACING_SMALL)
+            .padding([10, 12]),
+        )
+        .width(Length::Fixed(LIST_WIDTH))
+        .max_height(LIST_MAX_HEIGHT)
+        .style(move |_theme: &iced::Theme| container::Style {
+            background: Some(Background::Color(Color {
+                a: 0.97,
+                ..palette.surface
+            })),
+            border: Border {
+                radius: RADIUS_NORMAL.into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.15,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        });
+
+        let backdrop = mouse_area(container(text("")).width(Length::Fill).height(Length::Fill))
+            .on_press(Message::CloseProcessList);
+        let align_y = match self.config.ui.tab_bar_position {
+            TabBarPosition::Top => Alignment::Start,
+            TabBarPosition::Bottom => Alignment::End,
+        };
+        // Just clear of the tab bar, where the badge was clicked.
+        let anchored = container(card)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Alignment::Start)
+            .align_y(align_y)
+            .padding([40, 8]);
+
+        stack![base_layout.into(), backdrop, anchored]
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
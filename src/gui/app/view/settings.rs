@@ -1,7 +1,8 @@
+use super::super::update::SETTINGS_SCROLLABLE_ID;
 use super::super::{App, Message, SettingsMessage};
 use crate::gui::settings::{self, SettingsCategory};
 use crate::gui::theme::{RADIUS_NORMAL, SPACING_LARGE, SPACING_NORMAL, SPACING_SMALL};
-use iced::widget::{button, column, container, row, scrollable, stack, text};
+use iced::widget::{button, column, container, row, scrollable, stack, text, text_input};
 use iced::{Background, Border, Color, Element, Length};
 
 const CONTENT_MAX_WIDTH: f32 = 820.0;
@@ -14,8 +15,18 @@ impl App {
         let sidebar_alpha = 0.22;
         let mut category_items: Vec<Element<Message>> = Vec::new();
 
+        category_items.push(
+            text_input(t!("settings.search.placeholder"), &self.settings_search)
+                .on_input(|query| Message::Settings(SettingsMessage::SearchChanged(query)))
+                .padding([6, 10])
+                .size(13)
+                .width(Length::Fill)
+                .into(),
+        );
+
         for category in SettingsCategory::ALL {
-            let is_active = category == self.settings_category;
+            let is_active =
+                self.settings_search.trim().is_empty() && category == self.settings_category;
             let icon = category.icon();
             let label = category.label();
             // The background is painted by `hover_fade` behind the button so
@@ -93,20 +104,27 @@ impl App {
             ..Default::default()
         });
 
-        let body_content = container(settings::view_category(
-            self.settings_category,
-            &self.config,
-            &self.settings_draft,
-            &self.font_combo_state,
-            self.show_all_fonts,
-            &self.all_font_options,
-            palette,
-        ))
-        .padding([SPACING_LARGE, 12.0])
-        .max_width(CONTENT_MAX_WIDTH)
-        .width(Length::Fill);
+        let category_view = if self.settings_search.trim().is_empty() {
+            settings::view_category(
+                self.settings_category,
+                &self.config,
+                &self.settings_draft,
+                &self.font_combo_state,
+                self.show_all_fonts,
+                &self.all_font_options,
+                palette,
+            )
+        } else {
+            settings::search::view_results(&self.settings_search, palette)
+        };
+
+        let body_content = container(category_view)
+            .padding([SPACING_LARGE, 12.0])
+            .max_width(CONTENT_MAX_WIDTH)
+            .width(Length::Fill);
 
         let body_scroll: Element<Message> = scrollable(body_content)
+            .id(SETTINGS_SCROLLABLE_ID.clone())
             .height(Length::Fill)
             .width(Length::Fill)
             .into();
@@ -146,7 +164,9 @@ impl App {
             .width(Length::Fill)
             .into();
 
-        if matches!(self.settings_category, SettingsCategory::Ssh) {
+        if let Some(picker) = &self.color_picker {
+            settings::color_picker::overlay(settings_layout, picker, palette, animations_enabled)
+        } else if matches!(self.settings_category, SettingsCategory::Ssh) {
             settings::ssh::modal_overlay(
                 settings_layout,
                 &self.settings_draft,
@@ -35,6 +35,7 @@ pub fn drawer<'a>(
         layers.push(transfer_strip(state, palette, animations_enabled));
     }
 
+    let drop_hover = state.drop_hover;
     container(column(layers).width(Length::Fill).height(Length::Fill))
         .padding([SPACING_SMALL, SPACING_NORMAL])
         .width(Length::Fill)
@@ -51,8 +52,12 @@ pub fn drawer<'a>(
                     bottom_right: 0.0,
                     bottom_left: 0.0,
                 },
-                width: 0.0,
-                color: Color::TRANSPARENT,
+                width: if drop_hover { 1.0 } else { 0.0 },
+                color: if drop_hover {
+                    palette.accent
+                } else {
+                    Color::TRANSPARENT
+                },
             },
             shadow: Shadow {
                 color: Color {
@@ -77,10 +82,16 @@ fn drawer_header<'a>(
     } else {
         state.current_path.as_str()
     };
-    let path_label = text(path).size(12).color(Color {
-        a: 0.8,
-        ..palette.text
-    });
+    let path_label = if state.drop_hover {
+        text(format!("Drop to upload to {path}"))
+            .size(12)
+            .color(palette.accent)
+    } else {
+        text(path).size(12).color(Color {
+            a: 0.8,
+            ..palette.text
+        })
+    };
 
     let status = if state.opening {
         Some("opening")
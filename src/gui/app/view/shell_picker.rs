@@ -1,5 +1,5 @@
 use super::super::{App, Message};
-use crate::gui::app::update::tab::PickerSection;
+use crate::gui::app::update::tab::{PickerSection, PickerTarget};
 use crate::gui::icons::{self, ShellIcon};
 use crate::gui::tab::{Profile, ProfileKind};
 use crate::gui::theme::{Palette, RADIUS_NORMAL, RADIUS_SMALL, SPACING_SMALL};
@@ -71,6 +71,7 @@ impl PickerStyle {
         subtitle: Option<String>,
         selected: bool,
         on_press: Message,
+        trailing: Option<Element<'static, Message>>,
     ) -> Element<'static, Message> {
         let alpha = self.alpha;
         let palette = self.palette;
@@ -108,6 +109,10 @@ impl PickerStyle {
             .padding([6, 10])
             .on_press(on_press)
             .width(Length::Fill);
+        let mut inner = row![inner].align_y(iced::Alignment::Center);
+        if let Some(trailing) = trailing {
+            inner = inner.push(trailing);
+        }
 
         let rest = crate::gui::components::HoverStyle {
             background: if selected {
@@ -136,17 +141,50 @@ impl PickerStyle {
 
         crate::gui::components::hover_fade(inner, rest, hover, self.animations_enabled).into()
     }
+
+    /// A star that makes the profile the one new windows open with.
+    fn default_star(&self, name: String, starred: bool) -> Element<'static, Message> {
+        let alpha = self.alpha;
+        let palette = self.palette;
+        button(text(if starred { "★" } else { "☆" }).size(14))
+            .style(move |_theme: &iced::Theme, status: button::Status| {
+                let color = if starred || matches!(status, button::Status::Hovered) {
+                    palette.accent
+                } else {
+                    palette.text_secondary
+                };
+                button::Style {
+                    background: None,
+                    text_color: Color {
+                        a: alpha * if starred { 1.0 } else { 0.6 },
+                        ..color
+                    },
+                    ..Default::default()
+                }
+            })
+            .padding([4, 8])
+            .on_press(Message::ToggleDefaultProfile(name))
+            .into()
+    }
 }
 
+/// The profile's icon, tinted with its accent color when it has one.
 fn icon_for_shell(shell: &Profile) -> ShellIcon {
-    if let Some(name) = shell.icon.as_deref().filter(|n| !n.trim().is_empty()) {
-        return icons::by_name(name);
-    }
-    match &shell.kind {
-        ProfileKind::Ssh(_) => icons::ssh(),
-        ProfileKind::Local { program: None, .. } => icons::by_name(&icons::default_shell_name()),
-        ProfileKind::Local { .. } => icons::by_name(&shell.name),
+    let mut icon = match shell.icon.as_deref().filter(|n| !n.trim().is_empty()) {
+        Some(name) => icons::by_name(name),
+        None => match &shell.kind {
+            ProfileKind::Ssh(_) => icons::ssh(),
+            ProfileKind::Network { .. } => icons::by_name("terminal"),
+            ProfileKind::Local { program: None, .. } => {
+                icons::by_name(&icons::default_shell_name())
+            }
+            ProfileKind::Local { .. } => icons::by_name(&shell.name),
+        },
+    };
+    if let Some([r, g, b]) = shell.color_rgb() {
+        icon.color = Color::from_rgb8(r, g, b);
     }
+    icon
 }
 
 const PICKER_WIDTH: f32 = 280.0;
@@ -190,6 +228,7 @@ impl App {
         items.push(style.text(t!("shell_picker.title"), 15.0));
         items.push(style.divider());
 
+        let default_profile = self.config.terminal.default_profile.as_deref();
         let mut previous: Option<PickerSection> = None;
         for (option_index, entry) in self.shell_picker_entries().into_iter().enumerate() {
             if previous != Some(entry.section) {
@@ -200,13 +239,33 @@ impl App {
                 previous = Some(entry.section);
             }
             let selected = self.shell_picker_selected == option_index;
-            let icon = style.icon(icon_for_shell(&entry.profile));
+            let mut star = None;
+            let (icon, message) = match &entry.target {
+                PickerTarget::Profile(profile) => {
+                    let name = profile.display_name();
+                    let starred = default_profile == Some(name.as_str());
+                    star = Some(style.default_star(name, starred));
+                    (icon_for_shell(profile), Message::CreateTab(profile.clone()))
+                }
+                PickerTarget::Workspace(name) => (
+                    icons::by_name("terminal"),
+                    Message::OpenWorkspace(name.clone()),
+                ),
+                PickerTarget::Snippet(index) => {
+                    (icons::by_name("terminal"), Message::UseSnippet(*index))
+                }
+                PickerTarget::PluginCommand(index, id) => (
+                    icons::by_name("terminal"),
+                    Message::RunPluginCommand(*index, id.clone()),
+                ),
+            };
             items.push(style.item_button(
-                icon,
+                style.icon(icon),
                 entry.label.clone(),
                 entry.subtitle.clone(),
                 selected,
-                Message::CreateTab(entry.profile.clone()),
+                message,
+                star,
             ));
         }
 
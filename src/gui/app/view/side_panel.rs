@@ -0,0 +1,80 @@
+use super::super::{App, Message};
+use crate::gui::components::button_icon;
+use iced::widget::{Space, column, container, row, scrollable, text};
+use iced::{Alignment, Background, Border, Color, Element, Font, Length};
+
+const PANEL_WIDTH: f32 = 360.0;
+
+impl App {
+    /// The Explain, Fix or plugin reply beside the terminal; `None` when no
+    /// request was made or the panel was closed.
+    pub(in crate::gui) fn view_side_panel(&self) -> Option<Element<'_, Message>> {
+        let panel = self.side_panel.as_ref()?;
+        let palette = self.palette;
+        let animations_enabled = self.config.ui.animations_enabled;
+
+        let mut header = row![
+            text(panel.title.as_str()).size(14).color(palette.text),
+            Space::new().width(Length::Fill),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center);
+        if matches!(panel.reply, Some(Ok(_))) {
+            header = header.push(button_icon(
+                t!("side_panel.copy"),
+                Message::CopySidePanel,
+                palette,
+                animations_enabled,
+            ));
+        }
+        header = header.push(button_icon(
+            "\u{2715}",
+            Message::CloseSidePanel,
+            palette,
+            animations_enabled,
+        ));
+
+        let body: Element<Message> = match &panel.reply {
+            None => text(t!("side_panel.waiting"))
+                .size(12)
+                .color(palette.text_secondary)
+                .into(),
+            Some(Ok(reply)) if reply.is_empty() => text(t!("side_panel.empty"))
+                .size(12)
+                .color(palette.text_secondary)
+                .into(),
+            Some(Ok(reply)) => text(reply.as_str())
+                .size(12)
+                .font(Font::MONOSPACE)
+                .color(palette.text)
+                .into(),
+            Some(Err(err)) => text(t!("side_panel.failed").replace("{error}", err))
+                .size(12)
+                .color(palette.error)
+                .into(),
+        };
+
+        Some(
+            container(
+                column![header, scrollable(body).height(Length::Fill)]
+                    .spacing(8)
+                    .padding([12, 12]),
+            )
+            .width(Length::Fixed(PANEL_WIDTH))
+            .height(Length::Fill)
+            .style(move |_theme: &iced::Theme| container::Style {
+                background: Some(Background::Color(palette.surface)),
+                border: Border {
+                    width: 1.0,
+                    color: Color {
+                        a: 0.1,
+                        ..palette.text
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .into(),
+        )
+    }
+}
@@ -0,0 +1,110 @@
+use super::super::update::SNIPPET_INPUT_ID;
+use super::super::{App, Message, SnippetPrompt};
+use crate::gui::components::{primary, secondary};
+use crate::gui::theme::{RADIUS_NORMAL, SPACING_NORMAL, SPACING_SMALL};
+use iced::widget::{center, column, container, mouse_area, row, stack, text, text_input};
+use iced::{Background, Border, Color, Element, Length};
+
+const PROMPT_WIDTH: f32 = 360.0;
+
+impl App {
+    /// Asks for a snippet's placeholder values before typing it.
+    pub(in crate::gui) fn view_snippet_prompt<'a>(
+        &'a self,
+        base_layout: impl Into<Element<'a, Message>>,
+        prompt: &'a SnippetPrompt,
+    ) -> Element<'a, Message> {
+        let palette = self.palette;
+        let animations_enabled = self.config.ui.animations_enabled;
+        let backdrop = mouse_area(
+            container(text(""))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(|_theme: &iced::Theme| container::Style {
+                    background: Some(Background::Color(Color {
+                        a: 0.5,
+                        ..Color::BLACK
+                    })),
+                    ..Default::default()
+                }),
+        )
+        .on_press(Message::CloseSnippetPrompt);
+
+        let mut fields: Vec<Element<Message>> = vec![
+            text(prompt.snippet.name.as_str()).size(16).into(),
+            text(prompt.snippet.command.as_str())
+                .size(12)
+                .color(palette.text_secondary)
+                .into(),
+        ];
+        for (index, (placeholder, value)) in
+            prompt.placeholders.iter().zip(&prompt.values).enumerate()
+        {
+            let mut input = text_input(&placeholder.name, value)
+                .on_input(move |value| Message::SnippetValueChanged(index, value))
+                .on_submit(Message::InsertSnippet)
+                .padding([6, 10])
+                .size(13)
+                .width(Length::Fill);
+            if index == 0 {
+                input = input.id(SNIPPET_INPUT_ID.clone());
+            }
+            fields.push(
+                column![
+                    text(placeholder.name.as_str())
+                        .size(12)
+                        .color(palette.text_secondary),
+                    input,
+                ]
+                .spacing(4)
+                .into(),
+            );
+        }
+        fields.push(
+            row![
+                secondary(
+                    t!("dialog.cancel"),
+                    Some(Message::CloseSnippetPrompt),
+                    palette,
+                    animations_enabled,
+                ),
+                primary(
+                    t!("dialog.insert"),
+                    Message::InsertSnippet,
+                    palette,
+                    animations_enabled,
+                ),
+            ]
+            .spacing(SPACING_SMALL)
+            .into(),
+        );
+
+        let card = container(
+            column(fields)
+                .spacing(SPACING_NORMAL)
+                .padding(20)
+                .width(Length::Fixed(PROMPT_WIDTH)),
+        )
+        .style(move |_theme: &iced::Theme| container::Style {
+            background: Some(Background::Color(palette.surface)),
+            border: Border {
+                radius: (RADIUS_NORMAL + 4.0).into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.15,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        });
+
+        stack![
+            base_layout.into(),
+            backdrop,
+            center(card).width(Length::Fill).height(Length::Fill),
+        ]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
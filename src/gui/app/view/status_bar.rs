@@ -0,0 +1,98 @@
+use super::super::{App, Message};
+use crate::config::StatusSegment;
+use crate::gui::tab::TerminalTab;
+use crate::gui::theme::{SPACING_NORMAL, SPACING_SMALL};
+use iced::widget::{container, row, text};
+use iced::{Alignment, Background, Border, Color, Element, Length};
+
+impl App {
+    /// The bar under the terminal with the configured segments for the
+    /// focused pane; `None` when it is turned off.
+    pub(in crate::gui) fn view_status_bar(
+        &self,
+        tab: &TerminalTab,
+    ) -> Option<Element<'_, Message>> {
+        if !self.config.ui.status_bar {
+            return None;
+        }
+        let pane = tab.focused();
+        let palette = self.palette;
+
+        let segments = self
+            .config
+            .ui
+            .status_segments
+            .iter()
+            .filter_map(|segment| match segment {
+                StatusSegment::Profile => Some(pane.profile.display_name()),
+                StatusSegment::Cwd => pane.current_directory(),
+                StatusSegment::GridSize => {
+                    let size = pane.size();
+                    Some(format!("{}×{}", size.columns, size.lines))
+                }
+                StatusSegment::Scrollback => {
+                    let (offset, history) = pane.scroll_position();
+                    Some(if offset == 0 {
+                        t!("status_bar.at_bottom").to_string()
+                    } else {
+                        t!("status_bar.scrolled")
+                            .replace("{offset}", &offset.to_string())
+                            .replace("{history}", &history.to_string())
+                    })
+                }
+                StatusSegment::Process => pane.foreground_process(),
+                StatusSegment::Alerts => self.alerts_summary(),
+            })
+            .filter(|segment| !segment.is_empty());
+
+        let mut items: Vec<Element<Message>> = Vec::new();
+        for segment in segments {
+            if !items.is_empty() {
+                items.push(
+                    text("│")
+                        .size(11)
+                        .color(Color {
+                            a: 0.3,
+                            ..palette.text_secondary
+                        })
+                        .into(),
+                );
+            }
+            items.push(text(segment).size(11).color(palette.text_secondary).into());
+        }
+
+        Some(
+            container(row(items).spacing(SPACING_SMALL).align_y(Alignment::Center))
+                .padding([2.0, SPACING_NORMAL])
+                .width(Length::Fill)
+                .style(move |_| container::Style {
+                    background: Some(Background::Color(palette.surface)),
+                    border: Border {
+                        width: 1.0,
+                        color: Color {
+                            a: 0.08,
+                            ..palette.text
+                        },
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .into(),
+        )
+    }
+
+    /// How many tabs out of view rang the bell or printed something since
+    /// they were last shown.
+    fn alerts_summary(&self) -> Option<String> {
+        let bells = self.tabs.iter().filter(|tab| tab.bell).count();
+        let active = self.tabs.iter().filter(|tab| tab.activity).count();
+        let mut parts = Vec::new();
+        if bells > 0 {
+            parts.push(t!("status_bar.bells").replace("{count}", &bells.to_string()));
+        }
+        if active > 0 {
+            parts.push(t!("status_bar.activity").replace("{count}", &active.to_string()));
+        }
+        (!parts.is_empty()).then(|| parts.join("  "))
+    }
+}
@@ -0,0 +1,92 @@
+use super::super::update::TAB_LIST_INPUT_ID;
+use super::super::{App, Message};
+use crate::config::TabBarPosition;
+use crate::gui::components::menu_item_highlighted;
+use crate::gui::theme::{RADIUS_NORMAL, SPACING_SMALL};
+use iced::widget::{column, container, mouse_area, scrollable, stack, text, text_input};
+use iced::{Alignment, Background, Border, Color, Element, Length};
+
+const LIST_WIDTH: f32 = 320.0;
+const LIST_MAX_HEIGHT: f32 = 420.0;
+
+impl App {
+    /// Every open tab in a dropdown under the tab bar's list button,
+    /// narrowed by a fuzzy filter; picking one switches to it.
+    pub(in crate::gui) fn view_tab_list<'a>(
+        &'a self,
+        base_layout: impl Into<Element<'a, Message>>,
+        filter: &'a str,
+    ) -> Element<'a, Message> {
+        let palette = self.palette;
+        let input = text_input(t!("tab_list.filter"), filter)
+            .id(TAB_LIST_INPUT_ID.clone())
+            .on_input(Message::TabListFilterChanged)
+            .on_submit(Message::TabListSubmit)
+            .padding([6, 10])
+            .size(13)
+            .width(Length::Fill);
+
+        let matches = self.tab_list_matches();
+        let mut items: Vec<Element<Message>> = Vec::new();
+        if matches.is_empty() {
+            items.push(
+                text(t!("tab_list.no_matches"))
+                    .size(12)
+                    .color(palette.text_secondary)
+                    .into(),
+            );
+        }
+        for (row, index) in matches.into_iter().enumerate() {
+            let marker = if index == self.active_tab { "●" } else { " " };
+            items.push(menu_item_highlighted(
+                format!("{marker} {}  {}", index + 1, self.tabs[index].title()),
+                Message::TabListSelect(index),
+                self.menu_cursor == Some(row),
+                palette,
+                self.config.ui.animations_enabled,
+            ));
+        }
+
+        let card = container(
+            column![input, scrollable(column(items).spacing(2))]
+                .spacing(SPACING_SMALL)
+                .padding([10, 8]),
+        )
+        .width(Length::Fixed(LIST_WIDTH))
+        .max_height(LIST_MAX_HEIGHT)
+        .style(move |_theme: &iced::Theme| container::Style {
+            background: Some(Background::Color(Color {
+                a: 0.97,
+                ..palette.surface
+            })),
+            border: Border {
+                radius: RADIUS_NORMAL.into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.15,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        });
+
+        let backdrop = mouse_area(container(text("")).width(Length::Fill).height(Length::Fill))
+            .on_press(Message::ToggleTabList);
+        let align_y = match self.config.ui.tab_bar_position {
+            TabBarPosition::Top => Alignment::Start,
+            TabBarPosition::Bottom => Alignment::End,
+        };
+        // Just clear of the bar, at the end where the list button sits.
+        let anchored = container(card)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Alignment::End)
+            .align_y(align_y)
+            .padding([40, 8]);
+
+        stack![base_layout.into(), backdrop, anchored]
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,97 @@
+use super::super::update::WORKSPACE_NAME_INPUT_ID;
+use super::super::{App, Message};
+use crate::gui::components::{primary, secondary};
+use crate::gui::theme::{RADIUS_NORMAL, SPACING_NORMAL, SPACING_SMALL};
+use iced::widget::{center, column, container, mouse_area, row, stack, text, text_input};
+use iced::{Background, Border, Color, Element, Length};
+
+const PROMPT_WIDTH: f32 = 320.0;
+
+impl App {
+    /// Asks for the name to save the open tabs under.
+    pub(in crate::gui) fn view_workspace_prompt<'a>(
+        &'a self,
+        base_layout: impl Into<Element<'a, Message>>,
+        name: &'a str,
+    ) -> Element<'a, Message> {
+        let palette = self.palette;
+        let animations_enabled = self.config.ui.animations_enabled;
+        let backdrop = mouse_area(
+            container(text(""))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(|_theme: &iced::Theme| container::Style {
+                    background: Some(Background::Color(Color {
+                        a: 0.5,
+                        ..Color::BLACK
+                    })),
+                    ..Default::default()
+                }),
+        )
+        .on_press(Message::CloseWorkspacePrompt);
+
+        let input = text_input(t!("dialog.workspace_name"), name)
+            .id(WORKSPACE_NAME_INPUT_ID.clone())
+            .on_input(Message::WorkspaceNameChanged)
+            .on_submit(Message::SaveWorkspace)
+            .padding([6, 10])
+            .size(13)
+            .width(Length::Fill);
+
+        let save = if name.trim().is_empty() {
+            secondary(t!("dialog.save"), None, palette, animations_enabled)
+        } else {
+            primary(
+                t!("dialog.save"),
+                Message::SaveWorkspace,
+                palette,
+                animations_enabled,
+            )
+        };
+        let buttons = row![
+            secondary(
+                t!("dialog.cancel"),
+                Some(Message::CloseWorkspacePrompt),
+                palette,
+                animations_enabled,
+            ),
+            save,
+        ]
+        .spacing(SPACING_SMALL);
+
+        let card = container(
+            column![
+                text(t!("dialog.save_workspace_title")).size(16),
+                text(t!("dialog.save_workspace_body"))
+                    .size(13)
+                    .color(palette.text_secondary),
+                input,
+                buttons,
+            ]
+            .spacing(SPACING_NORMAL)
+            .padding(20)
+            .width(Length::Fixed(PROMPT_WIDTH)),
+        )
+        .style(move |_theme: &iced::Theme| container::Style {
+            background: Some(Background::Color(palette.surface)),
+            border: Border {
+                radius: (RADIUS_NORMAL + 4.0).into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.15,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        });
+
+        stack![
+            base_layout.into(),
+            backdrop,
+            center(card).width(Length::Fill).height(Length::Fill),
+        ]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
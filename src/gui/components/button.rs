@@ -125,6 +125,50 @@ pub fn secondary<'a>(
     hover_fade(inner, rest, hover, animations_enabled).into()
 }
 
+/// Compact labelled button for toolbars.
+pub fn chip<'a>(
+    label: impl AsRef<str>,
+    on_press: Message,
+    palette: Palette,
+    animations_enabled: bool,
+) -> Element<'a, Message> {
+    let inner = button(text(label.as_ref().to_string()).size(12))
+        .padding([3, 10])
+        .on_press(on_press)
+        .style(
+            move |_theme: &Theme, _status: button::Status| button::Style {
+                background: Some(Background::Color(Color::TRANSPARENT)),
+                text_color: palette.text,
+                border: Border {
+                    radius: MENU_RADIUS.into(),
+                    width: 0.0,
+                    color: Color::TRANSPARENT,
+                },
+                shadow: Shadow::default(),
+                snap: true,
+            },
+        );
+
+    let rest = HoverStyle {
+        background: Color::TRANSPARENT,
+        border_color: Color {
+            a: 0.1,
+            ..palette.text
+        },
+        border_width: 1.0,
+        radius: MENU_RADIUS,
+    };
+    let hover = HoverStyle {
+        background: Color {
+            a: 0.12,
+            ..palette.text
+        },
+        ..rest
+    };
+
+    hover_fade(inner, rest, hover, animations_enabled).into()
+}
+
 /// Small glyph/icon button used for tab-bar controls, SSH row actions, etc.
 pub fn icon<'a>(
     glyph: impl AsRef<str>,
@@ -246,6 +290,18 @@ pub fn menu_item<'a>(
     on_press: Message,
     palette: Palette,
     animations_enabled: bool,
+) -> Element<'a, Message> {
+    menu_item_highlighted(label, on_press, false, palette, animations_enabled)
+}
+
+/// A [`menu_item`] drawn as hovered while `highlighted`, for the row picked
+/// with the arrow keys.
+pub fn menu_item_highlighted<'a>(
+    label: impl AsRef<str>,
+    on_press: Message,
+    highlighted: bool,
+    palette: Palette,
+    animations_enabled: bool,
 ) -> Element<'a, Message> {
     let inner = button(text(label.as_ref().to_string()).size(13))
         .padding([7, 14])
@@ -265,17 +321,22 @@ pub fn menu_item<'a>(
             },
         );
 
+    let highlight = Color {
+        a: 0.1,
+        ..palette.text
+    };
     let rest = HoverStyle {
-        background: Color::TRANSPARENT,
+        background: if highlighted {
+            highlight
+        } else {
+            Color::TRANSPARENT
+        },
         border_color: Color::TRANSPARENT,
         border_width: 0.0,
         radius: MENU_RADIUS,
     };
     let hover = HoverStyle {
-        background: Color {
-            a: 0.1,
-            ..palette.text
-        },
+        background: highlight,
         ..rest
     };
 
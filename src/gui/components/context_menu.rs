@@ -1,29 +1,46 @@
 use crate::gui::app::Message;
-use crate::gui::components::menu_item;
+use crate::gui::components::menu_item_highlighted;
 use crate::gui::theme::{Palette, RADIUS_SMALL};
 use iced::widget::{column, container, mouse_area, stack, text};
 use iced::{Background, Border, Color, Element, Length, Padding};
 
 pub struct ContextMenuItem {
-    pub label: &'static str,
+    pub label: std::borrow::Cow<'static, str>,
     pub message: Message,
 }
 
 pub fn context_menu<'a>(
     base: impl Into<Element<'a, Message>>,
     items: Vec<ContextMenuItem>,
+    highlighted: Option<usize>,
     position: iced::Point,
     on_dismiss: Message,
     palette: Palette,
     animations_enabled: bool,
 ) -> Element<'a, Message> {
+    // Wide enough for the longest label, e.g. a port forward spec.
+    let longest = items
+        .iter()
+        .map(|item| item.label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = (longest as f32 * 7.5 + 28.0).max(140.0);
     let menu_items: Vec<Element<Message>> = items
         .into_iter()
-        .map(|item| menu_item(item.label, item.message, palette, animations_enabled))
+        .enumerate()
+        .map(|(index, item)| {
+            menu_item_highlighted(
+                item.label,
+                item.message,
+                highlighted == Some(index),
+                palette,
+                animations_enabled,
+            )
+        })
         .collect();
 
     let menu = container(column(menu_items).padding([4, 4]))
-        .width(Length::Fixed(140.0))
+        .width(Length::Fixed(width))
         .style(move |_theme: &iced::Theme| container::Style {
             background: Some(Background::Color(Color {
                 a: 0.97,
@@ -7,12 +7,15 @@ pub mod ime_wrapper;
 pub mod tab_bar;
 pub mod widget_styles;
 
-pub use button::{icon as button_icon, icon_toggle_content, menu_item, primary, secondary};
+pub use button::{
+    chip, icon as button_icon, icon_toggle_content, menu_item, menu_item_highlighted, primary,
+    secondary,
+};
 pub use category_transition::CategoryTransition;
 pub use container::panel;
 pub use hover_fade::{HoverStyle, hover_fade};
 pub use tab_bar::tab_bar;
 pub use widget_styles::{
     accent_combo_box_input_style, accent_combo_box_menu_style, accent_pick_list_style,
-    accent_toggler_style,
+    accent_slider_style, accent_toggler_style,
 };
@@ -2,16 +2,85 @@ use crate::config::TabBarPosition;
 use crate::gui::app::Message;
 use crate::gui::components::{HoverStyle, button as button_factory, hover_fade};
 use crate::gui::theme::Palette;
+use crate::session::resources::{TreeUsage, compact_bytes};
 use iced::widget::mouse_area;
-use iced::widget::{button, container, row, scrollable, text};
+use iced::widget::{button, container, row, scrollable, svg, text};
 use iced::{Background, Border, Color, Element, Length, Theme};
 
+/// Longest title a tab shows before it is cut with an ellipsis.
+pub const MAX_TITLE_CHARS: usize = 24;
+/// Tabs shrink down to titles this short before the strip has to scroll.
+const MIN_TITLE_CHARS: usize = 6;
+/// Rough layout of a tab, for fitting tabs to the bar without measuring
+/// them: everything but the title, and one title character.
+const TAB_CHROME_WIDTH: f32 = 64.0;
+const TITLE_CHAR_WIDTH: f32 = 7.0;
+const TAB_SPACING: f32 = 2.0;
+
+fn estimated_width(title_chars: usize, max_chars: usize) -> f32 {
+    TAB_CHROME_WIDTH + title_chars.min(max_chars) as f32 * TITLE_CHAR_WIDTH + TAB_SPACING
+}
+
+/// The longest titles that let tabs with titles of `title_chars`
+/// characters fit in `available` pixels, and whether they overflow even at
+/// the shortest.
+pub fn fit_titles(title_chars: &[usize], available: f32) -> (usize, bool) {
+    let total = |max| {
+        title_chars
+            .iter()
+            .map(|&chars| estimated_width(chars, max))
+            .sum::<f32>()
+    };
+    match (MIN_TITLE_CHARS..=MAX_TITLE_CHARS)
+        .rev()
+        .find(|&max| total(max) <= available)
+    {
+        Some(max) => (max, false),
+        None => (MIN_TITLE_CHARS, true),
+    }
+}
+
+/// Roughly where the tab at `index` starts in the strip.
+pub fn tab_offset(title_chars: &[usize], index: usize, max_chars: usize) -> f32 {
+    title_chars[..index.min(title_chars.len())]
+        .iter()
+        .map(|&chars| estimated_width(chars, max_chars))
+        .sum()
+}
+
+/// One tab in the bar.
+pub struct TabItem<'a> {
+    pub title: &'a str,
+    pub index: usize,
+    pub active: bool,
+    /// Input to the tab is locked; drawn with a lock icon.
+    pub locked: bool,
+    /// The shell runs with administrator rights; drawn with a shield icon.
+    pub elevated: bool,
+    /// Accent stripe color under the tab.
+    pub color: Option<[u8; 3]>,
+    /// Icon name drawn before the title.
+    pub icon: Option<&'a str>,
+    /// SSH port forwards as (running, any failed); None when there are none.
+    pub forwards: Option<(usize, bool)>,
+    /// A long command finished since the tab was last shown.
+    pub command_done: bool,
+    /// CPU and memory of the tab's local processes; clicking lists them.
+    pub resources: Option<&'a TreeUsage>,
+}
+
+/// CPU use, in percent of a core, from which a tab's badge turns red.
+const HOT_CPU_PERCENT: f32 = 80.0;
+
 #[allow(clippy::too_many_arguments)]
 pub fn tab_bar<'a>(
-    tabs: impl Iterator<Item = (&'a str, usize, bool)>,
+    tabs: impl Iterator<Item = TabItem<'a>>,
+    max_title_chars: usize,
     on_add: Message,
     on_settings: Message,
+    on_list: Option<Message>,
     sftp_toggle: Option<(Message, bool)>,
+    secure_input: Option<(Message, bool)>,
     bar_alpha: f32,
     tab_alpha: f32,
     dragging_tab: Option<usize>,
@@ -24,7 +93,8 @@ pub fn tab_bar<'a>(
     let is_reordering =
         dragging_tab.is_some() && drag_target.is_some() && dragging_tab != drag_target;
 
-    for (title, index, is_active) in tabs {
+    for tab in tabs {
+        let index = tab.index;
         // Insert drop indicator before the target tab
         if is_reordering && drag_target == Some(index) {
             let gap = container(text("")).width(24).height(Length::Shrink).style(
@@ -45,14 +115,7 @@ pub fn tab_bar<'a>(
             tab_elements.push(gap.into());
         }
 
-        let tab_item = browser_tab(
-            title,
-            index,
-            is_active,
-            tab_alpha,
-            palette,
-            animations_enabled,
-        );
+        let tab_item = browser_tab(tab, max_title_chars, tab_alpha, palette, animations_enabled);
         let is_terminal_tab = index != crate::gui::app::SETTINGS_TAB_INDEX;
         let mut tab_item = mouse_area(tab_item)
             .on_press(Message::TabSelected(index))
@@ -96,9 +159,37 @@ pub fn tab_bar<'a>(
     let padding = iced::Padding::new(0.0).left(left_padding);
 
     let mut trailing: Vec<Element<Message>> = Vec::new();
+    if let Some((msg, held)) = secure_input {
+        let lock_color = if held {
+            palette.accent
+        } else {
+            palette.text_secondary
+        };
+        let lock = svg(crate::gui::icons::lock())
+            .width(Length::Fixed(13.0))
+            .height(Length::Fixed(13.0))
+            .style(move |_theme: &Theme, _status| svg::Style {
+                color: Some(lock_color),
+            });
+        trailing.push(button_factory::icon_toggle_content(
+            lock.into(),
+            msg,
+            held,
+            palette,
+            animations_enabled,
+        ));
+    }
     if let Some(btn) = sftp_btn {
         trailing.push(btn);
     }
+    if let Some(msg) = on_list {
+        trailing.push(button_factory::icon(
+            "\u{25BE}",
+            msg,
+            palette,
+            animations_enabled,
+        ));
+    }
     trailing.push(add_btn);
     trailing.push(settings_btn);
 
@@ -243,16 +334,26 @@ fn chrome_bar_style(palette: Palette, bar_alpha: f32) -> container::Style {
 }
 
 fn browser_tab<'a>(
-    title: &'a str,
-    index: usize,
-    is_active: bool,
+    tab: TabItem<'a>,
+    max_title_chars: usize,
     tab_alpha: f32,
     palette: Palette,
     animations_enabled: bool,
 ) -> Element<'a, Message> {
-    const MAX_TITLE_LEN: usize = 24;
-    let display_title: std::borrow::Cow<'a, str> = if title.chars().count() > MAX_TITLE_LEN {
-        let truncated: String = title.chars().take(MAX_TITLE_LEN - 1).collect();
+    let TabItem {
+        title,
+        index,
+        active: is_active,
+        locked,
+        elevated,
+        color,
+        icon,
+        forwards,
+        command_done,
+        resources,
+    } = tab;
+    let display_title: std::borrow::Cow<'a, str> = if title.chars().count() > max_title_chars {
+        let truncated: String = title.chars().take(max_title_chars - 1).collect();
         format!("{truncated}\u{2026}").into()
     } else {
         title.into()
@@ -306,9 +407,103 @@ fn browser_tab<'a>(
     };
     let close_btn = hover_fade(close_btn_inner, close_rest, close_hover, animations_enabled);
 
-    let tab_content = row![index_label, tab_text, close_btn]
+    let mut tab_content = row![index_label]
         .spacing(6)
         .align_y(iced::Alignment::Center);
+    if locked {
+        let lock_color = palette.text_secondary;
+        tab_content = tab_content.push(
+            svg(crate::gui::icons::lock())
+                .width(Length::Fixed(11.0))
+                .height(Length::Fixed(11.0))
+                .style(move |_theme: &Theme, _status| svg::Style {
+                    color: Some(lock_color),
+                }),
+        );
+    }
+    if elevated {
+        let shield_color = palette.error;
+        tab_content = tab_content.push(
+            svg(crate::gui::icons::shield())
+                .width(Length::Fixed(11.0))
+                .height(Length::Fixed(11.0))
+                .style(move |_theme: &Theme, _status| svg::Style {
+                    color: Some(shield_color),
+                }),
+        );
+    }
+    if let Some(name) = icon {
+        let opacity = if is_active { 1.0 } else { 0.7 };
+        tab_content = tab_content.push(crate::gui::icons::view(
+            crate::gui::icons::by_name(name),
+            12.0,
+            opacity,
+        ));
+    }
+    let tab_content = tab_content.push(tab_text);
+    let tab_content = match forwards {
+        Some((running, failed)) => {
+            let color = if failed {
+                palette.error
+            } else if running > 0 {
+                palette.accent
+            } else {
+                palette.text_secondary
+            };
+            tab_content.push(text(format!("\u{21C4}{running}")).size(10).color(color))
+        }
+        None => tab_content,
+    };
+    let tab_content = if command_done {
+        tab_content.push(text("\u{25CF}").size(8).color(palette.success))
+    } else {
+        tab_content
+    };
+    let tab_content = match resources {
+        Some(usage) => {
+            let color = if usage.cpu_percent >= HOT_CPU_PERCENT {
+                palette.error
+            } else {
+                Color {
+                    a: 0.6,
+                    ..palette.text_secondary
+                }
+            };
+            let label = format!(
+                "{:.0}% {}",
+                usage.cpu_percent,
+                compact_bytes(usage.rss_bytes)
+            );
+            tab_content.push(
+                button(text(label).size(10).color(color))
+                    .on_press(Message::ShowProcessList(index))
+                    .padding([1, 3])
+                    .style(
+                        move |_theme: &Theme, status: button::Status| button::Style {
+                            background: match status {
+                                button::Status::Hovered | button::Status::Pressed => {
+                                    Some(Background::Color(Color {
+                                        a: 0.12,
+                                        ..palette.text
+                                    }))
+                                }
+                                _ => None,
+                            },
+                            text_color: color,
+                            border: Border {
+                                radius: 4.0.into(),
+                                width: 0.0,
+                                color: Color::TRANSPARENT,
+                            },
+                            shadow: iced::Shadow::default(),
+                            snap: true,
+                        },
+                    ),
+            )
+        }
+        None => tab_content,
+    };
+    let tab_content = tab_content.push(close_btn);
 
     let inactive_alpha = tab_alpha.clamp(0.0, 1.0);
     // The tab background is painted by `hover_fade` so it can cross-fade on
@@ -372,16 +567,26 @@ fn browser_tab<'a>(
         animations_enabled,
     );
 
-    if is_active {
+    // A tab color replaces the active indicator and stays visible, dimmer,
+    // while the tab is in the background.
+    let indicator_color = match color {
+        Some([r, g, b]) => Some(Color {
+            a: if is_active { 1.0 } else { 0.55 },
+            ..Color::from_rgb8(r, g, b)
+        }),
+        None if is_active => Some(Color {
+            a: 0.6,
+            ..palette.text
+        }),
+        None => None,
+    };
+    if let Some(indicator_color) = indicator_color {
         let indicator =
             container(text(""))
                 .width(Length::Fill)
                 .height(2)
                 .style(move |_theme: &Theme| container::Style {
-                    background: Some(Background::Color(Color {
-                        a: 0.6,
-                        ..palette.text
-                    })),
+                    background: Some(Background::Color(indicator_color)),
                     ..Default::default()
                 });
 
@@ -392,3 +597,21 @@ fn browser_tab<'a>(
         tab_button.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_shrink_before_the_tabs_overflow() {
+        let titles = [30, 30, 30];
+        assert_eq!(fit_titles(&titles, 10_000.0), (MAX_TITLE_CHARS, false));
+
+        let (chars, overflow) = fit_titles(&titles, 3.0 * estimated_width(10, 10));
+        assert_eq!((chars, overflow), (10, false));
+
+        let (chars, overflow) = fit_titles(&[30; 40], 800.0);
+        assert_eq!((chars, overflow), (MIN_TITLE_CHARS, true));
+        assert_eq!(tab_offset(&titles, 2, 10), 2.0 * estimated_width(10, 10));
+    }
+}
@@ -4,7 +4,7 @@
 
 use crate::gui::theme::{Palette, RADIUS_SMALL};
 use iced::widget::overlay::menu;
-use iced::widget::{pick_list, text_input, toggler};
+use iced::widget::{pick_list, slider, text_input, toggler};
 use iced::{Background, Border, Color, Shadow, Theme};
 
 /// Style closure for `toggler` widgets driven by `palette.accent`.
@@ -80,6 +80,42 @@ pub fn accent_toggler_style(
     }
 }
 
+/// Style closure for `slider` widgets: the filled part of the rail and the
+/// handle take the accent, the rest of the rail a dim text tint.
+pub fn accent_slider_style(palette: Palette) -> impl Fn(&Theme, slider::Status) -> slider::Style {
+    move |_theme: &Theme, status: slider::Status| {
+        let handle_radius = match status {
+            slider::Status::Active => 7.0,
+            slider::Status::Hovered | slider::Status::Dragged => 8.0,
+        };
+        slider::Style {
+            rail: slider::Rail {
+                backgrounds: (
+                    Background::Color(palette.accent),
+                    Background::Color(Color {
+                        a: 0.18,
+                        ..palette.text
+                    }),
+                ),
+                width: 4.0,
+                border: Border {
+                    radius: 2.0.into(),
+                    width: 0.0,
+                    color: Color::TRANSPARENT,
+                },
+            },
+            handle: slider::Handle {
+                shape: slider::HandleShape::Circle {
+                    radius: handle_radius,
+                },
+                background: Background::Color(palette.accent),
+                border_width: 2.0,
+                border_color: palette.background,
+            },
+        }
+    }
+}
+
 /// Style closure for the text-input portion of a `combo_box`.
 /// Mirrors the project's `styled_text_input`: subtle background, accent
 /// border on focus, accent-tinted selection.
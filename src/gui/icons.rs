@@ -14,6 +14,10 @@ static ICON_TERMINAL: LazyLock<svg::Handle> =
     LazyLock::new(|| svg::Handle::from_memory(include_bytes!("../../assets/icons/terminal.svg")));
 static ICON_SSH: LazyLock<svg::Handle> =
     LazyLock::new(|| svg::Handle::from_memory(include_bytes!("../../assets/icons/ssh.svg")));
+static ICON_LOCK: LazyLock<svg::Handle> =
+    LazyLock::new(|| svg::Handle::from_memory(include_bytes!("../../assets/icons/lock.svg")));
+static ICON_SHIELD: LazyLock<svg::Handle> =
+    LazyLock::new(|| svg::Handle::from_memory(include_bytes!("../../assets/icons/shield.svg")));
 
 pub struct ShellIcon {
     pub handle: svg::Handle,
@@ -55,9 +59,19 @@ pub fn ssh() -> ShellIcon {
     }
 }
 
+/// Shown on tabs whose input is locked.
+pub fn lock() -> svg::Handle {
+    ICON_LOCK.clone()
+}
+
+/// Shown on tabs whose shell runs with administrator rights.
+pub fn shield() -> svg::Handle {
+    ICON_SHIELD.clone()
+}
+
 pub fn default_shell_name() -> String {
-    let shell = std::env::var("SHELL").unwrap_or_default();
-    std::path::Path::new(&shell)
+    let shell = crate::platform::shell::login_shell().unwrap_or_default();
+    std::path::Path::new(shell)
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("")
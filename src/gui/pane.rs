@@ -1,9 +1,11 @@
 use iced::Rectangle;
+use serde::{Deserialize, Serialize};
 
 const MIN_RATIO: f32 = 0.05;
 pub const DIVIDER: f32 = 1.0;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Axis {
     Horizontal,
     Vertical,
@@ -1,3 +1,5 @@
+use super::cursor_trail::{CursorTrail, GHOSTS};
+use super::ring::{InstanceRing, UniformRing};
 use crate::config::CursorShape;
 use crate::terminal::{CellVisual, Selection};
 use bytemuck::{Pod, Zeroable};
@@ -19,26 +21,49 @@ struct InstanceRaw {
     rect_size: [f32; 2],
     color: [f32; 4],
     origin: [f32; 2],
+    /// Corner radius, in cell heights; zero for a plain rectangle.
+    radius: f32,
+}
+
+/// How round the ends of a selection run are, in cell heights.
+const SELECTION_RADIUS: f32 = 0.2;
+
+/// Each row's selected cells as `(row, first column, last column)` runs, so
+/// a selection draws as bars rather than a tile per cell.
+fn selected_runs(
+    cells: &[CellVisual],
+    selection: &Selection,
+    display_offset: usize,
+) -> Vec<(usize, usize, usize)> {
+    let mut runs: Vec<(usize, usize, usize)> = Vec::new();
+    for cell in cells {
+        if !selection.contains_at(cell.row, cell.col, display_offset) {
+            continue;
+        }
+        match runs.last_mut() {
+            Some((row, _, end)) if *row == cell.row && *end + 1 == cell.col => *end = cell.col,
+            _ => runs.push((cell.row, cell.col, cell.col)),
+        }
+    }
+    runs
 }
 
 #[derive(Debug)]
 pub(super) struct BackgroundPipeline {
     pipeline: wgpu::RenderPipeline,
-    uniform_buffer: wgpu::Buffer,
-    uniform_bind_group: wgpu::BindGroup,
+    uniforms: UniformRing,
     quad_buffer: wgpu::Buffer,
-    instance_buffer: wgpu::Buffer,
-    instance_capacity: usize,
+    instance_buffers: InstanceRing,
     instances: Vec<InstanceRaw>,
 }
 
 impl BackgroundPipeline {
-    pub(super) fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
-        let shader_src = include_str!("shaders/terminal.wgsl");
-        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("terminal.wgsl"),
-            source: wgpu::ShaderSource::Wgsl(shader_src.into()),
-        });
+    pub(super) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        shaders: &super::Shaders,
+    ) -> Self {
+        let module = shaders.terminal();
 
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("terminal.uniform.layout"),
@@ -54,21 +79,21 @@ impl BackgroundPipeline {
             }],
         });
 
-        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("terminal.uniform.buffer"),
-            size: std::mem::size_of::<Uniforms>() as u64,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("terminal.uniform.bind_group"),
-            layout: &bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            }],
-        });
+        let uniforms = UniformRing::new(
+            device,
+            "terminal.uniform.buffer",
+            std::mem::size_of::<Uniforms>(),
+            |buffer| {
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("terminal.uniform.bind_group"),
+                    layout: &bind_group_layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: buffer.as_entire_binding(),
+                    }],
+                })
+            },
+        );
 
         let quad: [[f32; 2]; 6] = [
             [0.0, 0.0],
@@ -84,12 +109,12 @@ impl BackgroundPipeline {
             usage: wgpu::BufferUsages::VERTEX,
         });
 
-        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("terminal.instances"),
-            size: (64 * std::mem::size_of::<InstanceRaw>()) as u64,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let instance_buffers = InstanceRing::new(
+            device,
+            "terminal.instances",
+            std::mem::size_of::<InstanceRaw>(),
+            64,
+        );
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("terminal.pipeline.layout"),
@@ -101,7 +126,7 @@ impl BackgroundPipeline {
             label: Some("terminal.pipeline"),
             layout: Some(&pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &module,
+                module,
                 entry_point: Some("vs_main"),
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
                 buffers: &[
@@ -118,13 +143,14 @@ impl BackgroundPipeline {
                             2 => Float32x2,
                             3 => Float32x2,
                             4 => Float32x4,
-                            5 => Float32x2
+                            5 => Float32x2,
+                            6 => Float32
                         ],
                     },
                 ],
             },
             fragment: Some(wgpu::FragmentState {
-                module: &module,
+                module,
                 entry_point: Some("fs_main"),
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
                 targets: &[Some(wgpu::ColorTargetState {
@@ -150,17 +176,15 @@ impl BackgroundPipeline {
 
         Self {
             pipeline,
-            uniform_buffer,
-            uniform_bind_group,
+            uniforms,
             quad_buffer,
-            instance_buffer,
-            instance_capacity: 64,
+            instance_buffers,
             instances: Vec::new(),
         }
     }
 
     pub(super) fn update_uniforms(
-        &self,
+        &mut self,
         queue: &wgpu::Queue,
         cell_size: [f32; 2],
         viewport: [f32; 2],
@@ -171,7 +195,7 @@ impl BackgroundPipeline {
             viewport,
             offset,
         };
-        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+        self.uniforms.write(queue, bytemuck::bytes_of(&uniforms));
     }
 
     pub(super) fn begin(&mut self) {
@@ -185,6 +209,7 @@ impl BackgroundPipeline {
         selection: Option<&Selection>,
         display_offset: usize,
         cursor: Option<[u32; 2]>,
+        cursor_trail: Option<CursorTrail>,
         cursor_shape: CursorShape,
         cursor_color: [f32; 4],
         background_opacity: f32,
@@ -196,12 +221,7 @@ impl BackgroundPipeline {
             self.instances.reserve(needed);
         }
         self.instances.extend(cells.iter().map(|cell| {
-            let mut bg =
-                if selection.is_some_and(|s| s.contains_at(cell.row, cell.col, display_offset)) {
-                    super::SELECTION_BG
-                } else {
-                    cell.bg
-                };
+            let mut bg = cell.bg;
             // Keep colored backgrounds as translucent as the rest of the window.
             bg[3] *= background_opacity;
             InstanceRaw {
@@ -210,9 +230,25 @@ impl BackgroundPipeline {
                 rect_size: [1.0, 1.0],
                 color: bg,
                 origin,
+                radius: 0.0,
             }
         }));
 
+        if let Some(selection) = selection {
+            let mut color = super::SELECTION_BG;
+            color[3] *= background_opacity;
+            for (row, start, end) in selected_runs(cells, selection, display_offset) {
+                self.instances.push(InstanceRaw {
+                    pos: [start as u32, row as u32],
+                    rect_offset: [0.0, 0.0],
+                    rect_size: [(end + 1 - start) as f32, 1.0],
+                    color,
+                    origin,
+                    radius: SELECTION_RADIUS,
+                });
+            }
+        }
+
         self.instances.extend(
             cells
                 .iter()
@@ -223,6 +259,7 @@ impl BackgroundPipeline {
                     rect_size: [1.0, 0.06],
                     color: cell.fg,
                     origin,
+                    radius: 0.0,
                 }),
         );
 
@@ -240,6 +277,7 @@ impl BackgroundPipeline {
                     rect_size: [1.0, 0.06],
                     color,
                     origin,
+                    radius: 0.0,
                 });
             }
         }
@@ -250,12 +288,76 @@ impl BackgroundPipeline {
                 CursorShape::Bar => ([0.0, 0.0], [0.15, 1.0]),
                 CursorShape::Underline => ([0.0, 0.85], [1.0, 0.15]),
             };
+            let Some(trail) = cursor_trail else {
+                self.instances.push(InstanceRaw {
+                    pos,
+                    rect_offset,
+                    rect_size,
+                    color: cursor_color,
+                    origin,
+                    radius: 0.0,
+                });
+                return;
+            };
+            // Mid-glide the cursor is off the grid, so it is placed by offset
+            // alone, with fainter copies strung back toward the tail.
+            let at = |cell: [f32; 2], alpha: f32| {
+                let mut color = cursor_color;
+                color[3] *= alpha;
+                InstanceRaw {
+                    pos: [0, 0],
+                    rect_offset: [cell[0] + rect_offset[0], cell[1] + rect_offset[1]],
+                    rect_size,
+                    color,
+                    origin,
+                    radius: 0.0,
+                }
+            };
+            for ghost in 0..GHOSTS {
+                let t = ghost as f32 / GHOSTS as f32;
+                let cell =
+                    [0, 1].map(|axis| trail.tail[axis] + (trail.head[axis] - trail.tail[axis]) * t);
+                self.instances.push(at(cell, 0.15 + 0.5 * t));
+            }
+            self.instances.push(at(trail.head, 1.0));
+        }
+    }
+
+    /// Pushed before the cells so colored cell backgrounds cover the guides.
+    pub(super) fn push_guides(
+        &mut self,
+        cells: &[CellVisual],
+        cursor_row: Option<u32>,
+        guides: &super::Guides,
+        origin: [f32; 2],
+    ) {
+        let Some(last) = cells.last() else {
+            return;
+        };
+        let (columns, lines) = (last.col + 1, last.row + 1);
+        if guides.cursor_row
+            && let Some(row) = cursor_row
+        {
             self.instances.push(InstanceRaw {
-                pos,
-                rect_offset,
-                rect_size,
-                color: cursor_color,
+                pos: [0, row],
+                rect_offset: [0.0, 0.0],
+                rect_size: [columns as f32, 1.0],
+                color: guides.row_color,
                 origin,
+                radius: 0.0,
+            });
+        }
+        for &column in &guides.rulers {
+            if usize::from(column) >= columns {
+                break;
+            }
+            self.instances.push(InstanceRaw {
+                pos: [u32::from(column), 0],
+                rect_offset: [0.0, 0.0],
+                rect_size: [0.1, lines as f32],
+                color: guides.ruler_color,
+                origin,
+                radius: 0.0,
             });
         }
     }
@@ -275,39 +377,25 @@ impl BackgroundPipeline {
             rect_size: [size[0] / cw, size[1] / ch],
             color,
             origin,
+            radius: 0.0,
         });
     }
 
     pub(super) fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
-        let required = self.instances.len().max(1);
-
-        if required > self.instance_capacity {
-            let new_cap = (required.next_power_of_two()).max(64);
-            self.instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("terminal.instances"),
-                size: (new_cap * std::mem::size_of::<InstanceRaw>()) as wgpu::BufferAddress,
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
-            self.instance_capacity = new_cap;
-        }
-
         if !self.instances.is_empty() {
-            queue.write_buffer(
-                &self.instance_buffer,
-                0,
-                bytemuck::cast_slice(&self.instances),
-            );
+            self.instance_buffers
+                .write(device, queue, bytemuck::cast_slice(&self.instances));
         } else {
-            queue.write_buffer(
-                &self.instance_buffer,
-                0,
+            self.instance_buffers.write(
+                device,
+                queue,
                 bytemuck::cast_slice(&[InstanceRaw {
                     pos: [0, 0],
                     rect_offset: [0.0, 0.0],
                     rect_size: [1.0, 1.0],
                     color: [0.0, 0.0, 0.0, 0.0],
                     origin: [0.0, 0.0],
+                    radius: 0.0,
                 }]),
             );
         }
@@ -322,7 +410,7 @@ impl BackgroundPipeline {
     }
 
     pub(super) fn uniform_bind_group(&self) -> &wgpu::BindGroup {
-        &self.uniform_bind_group
+        self.uniforms.bind_group()
     }
 
     pub(super) fn quad_buffer(&self) -> &wgpu::Buffer {
@@ -330,6 +418,45 @@ impl BackgroundPipeline {
     }
 
     pub(super) fn instance_buffer(&self) -> &wgpu::Buffer {
-        &self.instance_buffer
+        self.instance_buffers.buffer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::SelectionPoint;
+
+    fn cell(row: usize, col: usize) -> CellVisual {
+        CellVisual {
+            ch: 'x',
+            grapheme: None,
+            col,
+            row,
+            fg: [1.0; 4],
+            bg: [0.0; 4],
+            underline: false,
+            bold: false,
+            italic: false,
+            wide: false,
+            hyperlink: None,
+            image: None,
+        }
+    }
+
+    #[test]
+    fn selected_cells_merge_into_one_run_per_row() {
+        let cells: Vec<CellVisual> = (0..3)
+            .flat_map(|row| (0..6).map(move |col| cell(row, col)))
+            .collect();
+        let selection = Selection {
+            start: SelectionPoint { row: 0, col: 4 },
+            end: SelectionPoint { row: 2, col: 1 },
+            anchor_offset: 0,
+        };
+        assert_eq!(
+            selected_runs(&cells, &selection, 0),
+            vec![(0, 4, 5), (1, 0, 5), (2, 0, 1)]
+        );
     }
 }
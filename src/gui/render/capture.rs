@@ -0,0 +1,172 @@
+//! Screenshots of the terminal view: the offscreen texture the panes are
+//! drawn into, read back from the GPU and encoded as a PNG.
+
+use iced::futures::channel::oneshot;
+use iced::wgpu;
+use std::sync::{Arc, Mutex};
+
+/// PNG bytes, or why there are none.
+pub type CaptureResult = Result<Vec<u8>, String>;
+
+/// A screenshot the app asked for. The view is rebuilt every frame, so `id`
+/// tells a new request from one already read back.
+#[derive(Debug, Clone)]
+pub struct CaptureRequest {
+    pub id: u64,
+    /// Viewport rows of the focused pane to keep; the whole view when `None`.
+    pub rows: Option<(usize, usize)>,
+    reply: Arc<Mutex<Option<oneshot::Sender<CaptureResult>>>>,
+}
+
+impl CaptureRequest {
+    pub fn new(id: u64, rows: Option<(usize, usize)>) -> (Self, oneshot::Receiver<CaptureResult>) {
+        let (sender, receiver) = oneshot::channel();
+        let request = Self {
+            id,
+            rows,
+            reply: Arc::new(Mutex::new(Some(sender))),
+        };
+        (request, receiver)
+    }
+
+    fn take_reply(&self) -> Option<oneshot::Sender<CaptureResult>> {
+        self.reply.lock().ok()?.take()
+    }
+
+    pub(super) fn fail(&self, reason: &str) {
+        if let Some(reply) = self.take_reply() {
+            let _ = reply.send(Err(reason.to_string()));
+        }
+    }
+}
+
+/// Copies `region` (x, y, width, height in physical pixels) of `texture` and
+/// replies with it as a PNG. The copy is mapped once the GPU gets to it, on
+/// a later frame, so the app keeps frames coming until the reply arrives.
+pub(super) fn read_back(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    format: wgpu::TextureFormat,
+    [x, y, width, height]: [u32; 4],
+    request: &CaptureRequest,
+) {
+    if width == 0 || height == 0 {
+        request.fail("nothing to capture");
+        return;
+    }
+    let Some(reply) = request.take_reply() else {
+        return;
+    };
+
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let row_bytes = (width * 4).div_ceil(align) * align;
+    let buffer = Arc::new(device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("terminal.capture"),
+        size: u64::from(row_bytes) * u64::from(height),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    }));
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("terminal.capture"),
+    });
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d { x, y, z: 0 },
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(row_bytes),
+                rows_per_image: Some(height),
+            },
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let bgra = matches!(
+        format,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+    );
+    let mapped = Arc::clone(&buffer);
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            let png = result.map_err(|err| err.to_string()).and_then(|()| {
+                let data = mapped.slice(..).get_mapped_range();
+                let png = encode_png(&data, width, height, row_bytes, bgra);
+                drop(data);
+                mapped.unmap();
+                png
+            });
+            let _ = reply.send(png);
+        });
+}
+
+/// Rows of `row_bytes` (padded past `width` pixels) as an RGBA image.
+pub(super) fn unpad_rows(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    row_bytes: u32,
+    bgra: bool,
+) -> Result<::image::RgbaImage, String> {
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for row in data.chunks(row_bytes as usize).take(height as usize) {
+        for pixel in row[..(width * 4) as usize].chunks_exact(4) {
+            if bgra {
+                pixels.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+            } else {
+                pixels.extend_from_slice(pixel);
+            }
+        }
+    }
+    ::image::RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| "captured fewer pixels than expected".to_string())
+}
+
+/// Encodes rows of `row_bytes` (padded past `width` pixels) as a PNG.
+fn encode_png(data: &[u8], width: u32, height: u32, row_bytes: u32, bgra: bool) -> CaptureResult {
+    let image = unpad_rows(data, width, height, row_bytes, bgra)?;
+    let mut png = Vec::new();
+    image
+        .write_to(
+            &mut std::io::Cursor::new(&mut png),
+            ::image::ImageFormat::Png,
+        )
+        .map_err(|err| err.to_string())?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padded_bgra_rows_become_a_png_of_the_visible_pixels() {
+        // Two rows of one pixel each, padded to eight bytes.
+        let data = [3, 2, 1, 255, 9, 9, 9, 9, 30, 20, 10, 128, 9, 9, 9, 9];
+        let png = encode_png(&data, 1, 2, 8, true).unwrap();
+        let image = ::image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (1, 2));
+        assert_eq!(image.get_pixel(0, 0).0, [1, 2, 3, 255]);
+        assert_eq!(image.get_pixel(0, 1).0, [10, 20, 30, 128]);
+    }
+
+    #[test]
+    fn a_request_replies_once() {
+        let (request, _receiver) = CaptureRequest::new(1, None);
+        let copy = request.clone();
+        assert!(request.take_reply().is_some());
+        assert!(copy.take_reply().is_none());
+    }
+}
@@ -0,0 +1,231 @@
+//! Animated GIFs of a recording. The recording is replayed into a headless
+//! engine and each frame drawn by the same pipelines as the terminal view,
+//! on a GPU device of our own rather than the one iced renders the window
+//! with, then read back and encoded.
+
+use super::{Guides, PanePrimitive, TerminalPipeline, TerminalPrimitive, capture};
+use crate::config::{CursorShape, TextRendering};
+use crate::terminal::recording::Recording;
+use crate::terminal::{TerminalEngine, TerminalTheme};
+use ::image::codecs::gif::{GifEncoder, Repeat};
+use ::image::{Delay, Frame};
+use iced::wgpu;
+use iced::widget::shader::{Pipeline, Primitive, Viewport};
+use iced::{Rectangle, Size};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// What frames are drawn into: sRGB like a window surface, and read back in
+/// the byte order the encoder takes.
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// NeuQuant sampling for each frame's palette: 1 is slowest and best, 30
+/// fastest. Terminal frames have few colors, so a coarse sample keeps them.
+const GIF_SPEED: i32 = 10;
+
+/// How a clip is drawn: what the terminal view would use for the pane.
+#[derive(Debug, Clone)]
+pub struct ClipStyle {
+    pub theme: TerminalTheme,
+    pub cell_size: [f32; 2],
+    pub padding: [f32; 2],
+    pub font_selection: Option<String>,
+    pub font_size: f32,
+    pub cursor_shape: CursorShape,
+    pub text_rendering: TextRendering,
+    pub font_synthesize: bool,
+}
+
+/// A GPU device for drawing clips, separate from the window's.
+pub struct ClipRenderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+}
+
+impl ClipRenderer {
+    pub async fn new() -> Result<Self, String> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::from_env_or_default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await
+            .map_err(|err| err.to_string())?;
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: Some("terminal.clip"),
+                ..Default::default()
+            })
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(Self { device, queue })
+    }
+
+    /// Replays all of `recording` and encodes it as a looping GIF, sized to
+    /// the largest the terminal was. Blocks until every frame is drawn.
+    pub fn gif(&self, recording: &Recording, style: &ClipStyle) -> Result<Vec<u8>, String> {
+        let size = recording.largest_size();
+        let max_side = self.device.limits().max_texture_dimension_2d;
+        let side = |cells: usize, cell: f32, padding: f32| {
+            ((cells as f32 * cell + padding * 2.0).ceil() as u32).clamp(1, max_side)
+        };
+        let width = side(size.columns, style.cell_size[0], style.padding[0]);
+        let height = side(size.lines, style.cell_size[1], style.padding[1]);
+
+        let mut pipeline = TerminalPipeline::new(&self.device, &self.queue, FORMAT);
+        let target = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("terminal.clip"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let viewport = Viewport::with_physical_size(Size::new(width, height), 1.0);
+        let bounds = Rectangle::with_size(Size::new(width as f32, height as f32));
+        let clip_bounds = Rectangle {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        };
+
+        let mut gif = Vec::new();
+        {
+            let mut encoder = GifEncoder::new_with_speed(&mut gif, GIF_SPEED);
+            encoder
+                .set_repeat(Repeat::Infinite)
+                .map_err(|err| err.to_string())?;
+            recording.replay(0.0..f64::INFINITY, style.theme.clone(), |engine, shown| {
+                let primitive = frame(engine, style, [width as f32, height as f32]);
+                primitive.prepare(&mut pipeline, &self.device, &self.queue, &bounds, &viewport);
+                let mut commands =
+                    self.device
+                        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                            label: Some("terminal.clip"),
+                        });
+                primitive.render(&pipeline, &mut commands, &view, &clip_bounds);
+                self.queue.submit(Some(commands.finish()));
+                let image = self.read_back(&target, width, height)?;
+                encoder
+                    .encode_frame(Frame::from_parts(
+                        image,
+                        0,
+                        0,
+                        Delay::from_saturating_duration(shown),
+                    ))
+                    .map_err(|err| err.to_string())
+            })?;
+        }
+        Ok(gif)
+    }
+
+    /// Copies `texture` out and waits for the GPU to get to it.
+    fn read_back(
+        &self,
+        texture: &wgpu::Texture,
+        width: u32,
+        height: u32,
+    ) -> Result<::image::RgbaImage, String> {
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let row_bytes = (width * 4).div_ceil(align) * align;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("terminal.clip"),
+            size: u64::from(row_bytes) * u64::from(height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("terminal.clip"),
+            });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(row_bytes),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let (sender, receiver) = mpsc::channel();
+        buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|err| err.to_string())?;
+        receiver
+            .recv_timeout(Duration::from_secs(5))
+            .map_err(|err| err.to_string())?
+            .map_err(|err| err.to_string())?;
+        let data = buffer.slice(..).get_mapped_range();
+        let image = capture::unpad_rows(&data, width, height, row_bytes, false);
+        drop(data);
+        buffer.unmap();
+        image
+    }
+}
+
+/// The view of one replayed screen: a single focused pane filling the clip,
+/// with the cursor shown steadily.
+fn frame(engine: &TerminalEngine, style: &ClipStyle, viewport: [f32; 2]) -> TerminalPrimitive {
+    let (display_offset, _) = engine.scroll_position();
+    TerminalPrimitive {
+        panes: vec![PanePrimitive {
+            cells: engine.render_cells(),
+            images: engine.render_images(),
+            origin: style.padding,
+            gutter: Vec::new(),
+            gutter_origin: style.padding,
+            wrapped: Vec::new(),
+            wrap_origin: style.padding,
+            rect: [0.0, 0.0, viewport[0], viewport[1]],
+            scrollbar: None,
+            focused: true,
+            selection: None,
+            display_offset,
+            cursor: engine
+                .cursor_cell()
+                .map(|(col, row)| [col as u32, row as u32]),
+            cursor_row: None,
+            cursor_color: engine.cursor_color(),
+            cursor_trail: None,
+            link_row: None,
+        }],
+        scrollbar_color: [0.0; 4],
+        focus_color: [0.0; 4],
+        divider_color: [0.0; 4],
+        zoomed: false,
+        cell_size: style.cell_size,
+        viewport,
+        clear_color: style.theme.background_color(),
+        terminal_font_selection: style.font_selection.clone(),
+        terminal_font_size: style.font_size,
+        cursor_shape: style.cursor_shape,
+        background_opacity: 1.0,
+        text_rendering: style.text_rendering,
+        font_synthesize: style.font_synthesize,
+        guides: Guides::default(),
+        inactive_dim: 0.0,
+        window_dim: 0.0,
+        capture: None,
+    }
+}
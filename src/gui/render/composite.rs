@@ -9,9 +9,16 @@ struct CompositeVertex {
     uv: [f32; 2],
 }
 
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+#[repr(C)]
+struct CompositeUniforms {
+    focus_rect: [f32; 4],
+    brightness: [f32; 4],
+}
+
 #[derive(Debug)]
 pub(super) struct OffscreenTarget {
-    _texture: wgpu::Texture,
+    texture: wgpu::Texture,
     pub(super) view: wgpu::TextureView,
     pub(super) size: [u32; 2],
 }
@@ -22,18 +29,19 @@ pub(super) struct CompositePipeline {
     bind_group_layout: wgpu::BindGroupLayout,
     sampler: wgpu::Sampler,
     quad_buffer: wgpu::Buffer,
+    uniform_buffer: wgpu::Buffer,
     bind_group: Option<wgpu::BindGroup>,
     offscreen: Option<OffscreenTarget>,
     format: wgpu::TextureFormat,
 }
 
 impl CompositePipeline {
-    pub(super) fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
-        let shader_src = include_str!("shaders/terminal.wgsl");
-        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("terminal.composite.wgsl"),
-            source: wgpu::ShaderSource::Wgsl(shader_src.into()),
-        });
+    pub(super) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        shaders: &super::Shaders,
+    ) -> Self {
+        let module = shaders.terminal();
 
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("terminal.composite.bind_group_layout"),
@@ -54,9 +62,30 @@ impl CompositePipeline {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(
+                            std::mem::size_of::<CompositeUniforms>() as u64,
+                        ),
+                    },
+                    count: None,
+                },
             ],
         });
 
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("terminal.composite.uniforms"),
+            contents: bytemuck::bytes_of(&CompositeUniforms {
+                focus_rect: [0.0; 4],
+                brightness: [1.0; 4],
+            }),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("terminal.composite.sampler"),
             address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -110,7 +139,7 @@ impl CompositePipeline {
             label: Some("terminal.composite.pipeline"),
             layout: Some(&pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &module,
+                module,
                 entry_point: Some("composite_vs_main"),
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
                 buffers: &[wgpu::VertexBufferLayout {
@@ -120,7 +149,7 @@ impl CompositePipeline {
                 }],
             },
             fragment: Some(wgpu::FragmentState {
-                module: &module,
+                module,
                 entry_point: Some("composite_fs_main"),
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
                 targets: &[Some(wgpu::ColorTargetState {
@@ -141,6 +170,7 @@ impl CompositePipeline {
             bind_group_layout,
             sampler,
             quad_buffer,
+            uniform_buffer,
             bind_group: None,
             offscreen: None,
             format,
@@ -170,7 +200,10 @@ impl CompositePipeline {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: self.format,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            // Copied from for screenshots.
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
             view_formats: &[],
         });
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
@@ -186,17 +219,42 @@ impl CompositePipeline {
                     binding: 1,
                     resource: wgpu::BindingResource::TextureView(&view),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.uniform_buffer.as_entire_binding(),
+                },
             ],
         });
 
         self.offscreen = Some(OffscreenTarget {
-            _texture: texture,
+            texture,
             view,
             size,
         });
         self.bind_group = Some(bind_group);
     }
 
+    /// Scales the color of everything outside `focus_rect` (texture
+    /// coordinates) by `outside`, and inside it by `inside`.
+    pub(super) fn update_dimming(
+        &self,
+        queue: &wgpu::Queue,
+        focus_rect: [f32; 4],
+        inside: f32,
+        outside: f32,
+    ) {
+        let uniforms = CompositeUniforms {
+            focus_rect,
+            brightness: [inside, outside, 0.0, 0.0],
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+    }
+
+    /// Whether a frame has been prepared; a degenerate area skips that.
+    pub(super) fn has_offscreen(&self) -> bool {
+        self.offscreen.is_some()
+    }
+
     pub(super) fn offscreen_view(&self) -> &wgpu::TextureView {
         &self
             .offscreen
@@ -212,6 +270,33 @@ impl CompositePipeline {
             .size
     }
 
+    /// Reads back `crop` (x, y, width, height in physical pixels) of what
+    /// was last drawn offscreen, for `request`.
+    pub(super) fn capture(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        crop: [f32; 4],
+        request: &super::capture::CaptureRequest,
+    ) {
+        let Some(target) = &self.offscreen else {
+            request.fail("nothing drawn yet");
+            return;
+        };
+        let [width, height] = target.size.map(|v| v as f32);
+        let x0 = crop[0].clamp(0.0, width);
+        let y0 = crop[1].clamp(0.0, height);
+        let x1 = (crop[0] + crop[2]).clamp(x0, width);
+        let y1 = (crop[1] + crop[3]).clamp(y0, height);
+        let region = [
+            x0.floor() as u32,
+            y0.floor() as u32,
+            (x1.ceil() - x0.floor()) as u32,
+            (y1.ceil() - y0.floor()) as u32,
+        ];
+        super::capture::read_back(device, queue, &target.texture, self.format, region, request);
+    }
+
     pub(super) fn bind_group(&self) -> &wgpu::BindGroup {
         self.bind_group
             .as_ref()
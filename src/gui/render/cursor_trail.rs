@@ -0,0 +1,102 @@
+//! The smooth cursor: a pane's cursor glides from the cell it left to the
+//! one it moved to, with fading copies trailing behind it.
+
+use std::time::{Duration, Instant};
+
+/// Fading copies drawn between the tail and the cursor.
+pub(super) const GHOSTS: usize = 3;
+
+/// Where the cursor is drawn partway through a glide, in cells.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct CursorTrail {
+    pub head: [f32; 2],
+    /// Lags the head: it moves at a steady pace while the head eases out.
+    pub tail: [f32; 2],
+}
+
+/// One pane's cursor and the glide it is in, if any.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct CursorMotion {
+    from: [f32; 2],
+    to: [u32; 2],
+    started: Instant,
+}
+
+impl CursorMotion {
+    /// A cursor resting on `cell`.
+    pub(super) fn at(cell: [u32; 2], now: Instant) -> Self {
+        Self {
+            from: cell.map(|v| v as f32),
+            to: cell,
+            started: now,
+        }
+    }
+
+    pub(super) fn target(&self) -> [u32; 2] {
+        self.to
+    }
+
+    /// Starts a glide toward `cell` from wherever the cursor is drawn now.
+    pub(super) fn retarget(&mut self, cell: [u32; 2], now: Instant, duration: Duration) {
+        if cell != self.to {
+            self.from = self.head(now, duration);
+            self.to = cell;
+            self.started = now;
+        }
+    }
+
+    /// How far through the glide, 0 to 1.
+    fn progress(&self, now: Instant, duration: Duration) -> f32 {
+        if duration.is_zero() {
+            return 1.0;
+        }
+        (now.saturating_duration_since(self.started).as_secs_f32() / duration.as_secs_f32())
+            .min(1.0)
+    }
+
+    fn lerp(&self, t: f32) -> [f32; 2] {
+        [0, 1].map(|axis| self.from[axis] + (self.to[axis] as f32 - self.from[axis]) * t)
+    }
+
+    fn head(&self, now: Instant, duration: Duration) -> [f32; 2] {
+        let t = self.progress(now, duration);
+        // Ease out: quick to leave, gentle to land.
+        self.lerp(1.0 - (1.0 - t).powi(3))
+    }
+
+    pub(super) fn is_moving(&self, now: Instant, duration: Duration) -> bool {
+        self.progress(now, duration) < 1.0 && self.from != self.to.map(|v| v as f32)
+    }
+
+    /// The head and tail mid-glide; `None` once the cursor has landed.
+    pub(super) fn trail(&self, now: Instant, duration: Duration) -> Option<CursorTrail> {
+        self.is_moving(now, duration).then(|| CursorTrail {
+            head: self.head(now, duration),
+            tail: self.lerp(self.progress(now, duration)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_cursor_glides_to_its_new_cell_and_lands() {
+        let start = Instant::now();
+        let duration = Duration::from_millis(100);
+        let mut motion = CursorMotion::at([0, 0], start);
+        assert!(motion.trail(start, duration).is_none());
+
+        motion.retarget([10, 0], start, duration);
+        let mid = motion
+            .trail(start + Duration::from_millis(50), duration)
+            .unwrap();
+        assert!(mid.head[0] > mid.tail[0], "the tail lags the head");
+        assert!(mid.head[0] > 0.0 && mid.head[0] < 10.0);
+
+        let later = start + Duration::from_millis(100);
+        assert!(motion.trail(later, duration).is_none());
+        assert!(!motion.is_moving(later, duration));
+    }
+}
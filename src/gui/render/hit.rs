@@ -0,0 +1,223 @@
+//! Hit-testing the terminal view: which pane, cell, word or link is under a
+//! point. Points are relative to the view's top-left corner, as
+//! `mouse::Cursor::position_in` gives them.
+
+use super::{LinkTarget, TerminalProgram};
+use crate::terminal::{CellVisual, GridPos};
+use iced::{Point, Rectangle, Size};
+
+/// The grid cell under a point in the terminal view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridPoint {
+    pub pane: u64,
+    /// Row among the rows on screen.
+    pub row: usize,
+    pub column: usize,
+    /// The same row counted from the oldest scrollback line, so it keeps
+    /// pointing at the same text while the view scrolls.
+    pub line: usize,
+}
+
+impl GridPoint {
+    fn grid(self) -> GridPos {
+        GridPos {
+            row: self.row,
+            col: self.column,
+        }
+    }
+}
+
+impl TerminalProgram {
+    /// The cell under `pos` in a view of `size`. Points in a pane's padding
+    /// snap to its nearest cell; `None` off every pane.
+    pub fn hit_test(&self, pos: Point, size: Size) -> Option<GridPoint> {
+        let (pane, rect) = self.pane_under(pos, Rectangle::with_size(size))?;
+        let grid = pane.pixel_to_grid(pos, rect, self.padding, self.cell_size);
+        Some(GridPoint {
+            pane: pane.id,
+            row: grid.row,
+            column: grid.col,
+            line: pane.scroll_history.saturating_sub(pane.display_offset) + grid.row,
+        })
+    }
+
+    /// The URL or file path under `point`, from an OSC 8 hyperlink or the
+    /// text itself.
+    pub fn link_at(&self, point: GridPoint) -> Option<LinkTarget> {
+        self.pane(point.pane)?.link_at(point.grid())
+    }
+}
+
+/// Queries for plugins and actions built on [`TerminalProgram::hit_test`].
+/// The app itself doesn't call these yet.
+#[allow(dead_code)]
+impl TerminalProgram {
+    /// The cell under `point`; `None` past the pane's last column.
+    pub fn cell_at(&self, point: GridPoint) -> Option<&CellVisual> {
+        let pane = self.pane(point.pane)?;
+        if point.column >= pane.grid_size.columns {
+            return None;
+        }
+        pane.cells
+            .get(point.row * pane.grid_size.columns + point.column)
+    }
+
+    /// The word under `point`, by the same rules as double-click selection.
+    pub fn word_at(&self, point: GridPoint) -> Option<String> {
+        let pane = self.pane(point.pane)?;
+        let (start, end) = pane.word_span(point.grid(), &self.word_separators)?;
+        Some(
+            (start..=end)
+                .map(|col| pane.cell_char(point.row, col))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::pane::PaneNode;
+    use crate::gui::render::{Guides, PaneView};
+    use crate::terminal::{TerminalEngine, TerminalSize};
+
+    const CELL: [f32; 2] = [10.0, 20.0];
+    const PADDING: [f32; 2] = [4.0, 4.0];
+
+    fn program(output: &[u8]) -> TerminalProgram {
+        let size = TerminalSize::new(40, 5);
+        let mut engine = TerminalEngine::headless(size);
+        engine.feed_bytes(output);
+        let (display_offset, scroll_history) = engine.scroll_position();
+        TerminalProgram {
+            panes: vec![PaneView {
+                id: 7,
+                scroll_history,
+                cells: engine.render_cells(),
+                images: Default::default(),
+                grid_size: size,
+                selection: None,
+                display_offset,
+                cursor: None,
+                cursor_visible: false,
+                cursor_color: [1.0; 4],
+                mouse_mode: false,
+                gutter_columns: 0,
+                gutter: Vec::new(),
+                wrapped: Vec::new(),
+                alt_screen: false,
+            }],
+            scrollbar_color: [0.0; 4],
+            focused: 7,
+            focus_color: [0.0; 4],
+            divider_color: [0.0; 4],
+            cell_size: CELL,
+            layout: PaneNode::Leaf(7),
+            zoomed: false,
+            terminal_font_selection: None,
+            terminal_font_size: 14.0,
+            padding: PADDING,
+            clear_color: [0.0; 4],
+            cursor_shape: Default::default(),
+            background_opacity: 1.0,
+            text_rendering: Default::default(),
+            font_synthesize: false,
+            cursor_trail: None,
+            smooth_scroll: false,
+            guides: Guides::default(),
+            word_separators: crate::config::DEFAULT_WORD_SEPARATORS.to_string(),
+            inactive_dim: 0.0,
+            window_dim: 0.0,
+            capture: None,
+        }
+    }
+
+    fn center_of(column: usize, row: usize) -> Point {
+        Point::new(
+            PADDING[0] + (column as f32 + 0.5) * CELL[0],
+            PADDING[1] + (row as f32 + 0.5) * CELL[1],
+        )
+    }
+
+    #[test]
+    fn points_map_to_cells_and_scrollback_lines() {
+        let program = program(b"1\r\n2\r\n3\r\n4\r\n5\r\n6\r\nsee here");
+        let point = program
+            .hit_test(center_of(4, 4), Size::new(400.0, 108.0))
+            .expect("point is over the pane");
+
+        assert_eq!((point.pane, point.row, point.column), (7, 4, 4));
+        assert_eq!(point.line, 6, "two lines scrolled off the top");
+        assert_eq!(program.cell_at(point).map(|cell| cell.ch), Some('h'));
+        assert_eq!(program.word_at(point).as_deref(), Some("here"));
+    }
+
+    #[test]
+    fn word_separators_decide_how_much_a_word_covers() {
+        let mut program = program(b"ls /usr/local/bin");
+        let point = program
+            .hit_test(center_of(9, 0), Size::new(400.0, 108.0))
+            .unwrap();
+        assert_eq!(program.word_at(point).as_deref(), Some("/usr/local/bin"));
+
+        program.word_separators.push('/');
+        assert_eq!(program.word_at(point).as_deref(), Some("local"));
+    }
+
+    #[test]
+    fn pointer_shape_follows_what_is_under_it() {
+        use crate::gui::render::{ShaderProgram, TerminalShaderState};
+        use iced::mouse::{Cursor, Interaction};
+
+        let mut program = program(b"open https://example.com/docs now");
+        let bounds = Rectangle::with_size(Size::new(400.0, 108.0));
+        let mut state = TerminalShaderState::default();
+        let over = |column| Cursor::Available(center_of(column, 0));
+
+        assert_eq!(
+            program.mouse_interaction(&state, bounds, over(10)),
+            Interaction::Text
+        );
+        state.modifiers = if cfg!(target_os = "macos") {
+            iced::keyboard::Modifiers::LOGO
+        } else {
+            iced::keyboard::Modifiers::CTRL
+        };
+        assert_eq!(
+            program.mouse_interaction(&state, bounds, over(10)),
+            Interaction::Pointer
+        );
+        assert_eq!(
+            program.mouse_interaction(&state, bounds, over(1)),
+            Interaction::Text
+        );
+
+        program.panes[0].mouse_mode = true;
+        assert_eq!(
+            program.mouse_interaction(&state, bounds, over(1)),
+            Interaction::Idle
+        );
+    }
+
+    #[test]
+    fn links_resolve_from_plain_text() {
+        let program = program(b"open https://example.com/docs now");
+        let point = program
+            .hit_test(center_of(10, 0), Size::new(400.0, 108.0))
+            .unwrap();
+
+        assert_eq!(
+            program.link_at(point),
+            Some(LinkTarget::Url("https://example.com/docs".to_string()))
+        );
+        assert!(
+            program
+                .link_at(
+                    program
+                        .hit_test(center_of(1, 0), Size::new(400.0, 108.0))
+                        .unwrap()
+                )
+                .is_none()
+        );
+    }
+}
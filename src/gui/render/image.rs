@@ -0,0 +1,371 @@
+use crate::terminal::CellVisual;
+use crate::terminal::graphics::{ImageData, ImagePlacement};
+use bytemuck::{Pod, Zeroable};
+use iced::wgpu;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::Arc;
+
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+#[repr(C)]
+struct Uniforms {
+    viewport: [f32; 2],
+    _pad: [f32; 2],
+}
+
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+#[repr(C)]
+struct InstanceRaw {
+    pos: [f32; 2],
+    size: [f32; 2],
+    uv_min: [f32; 2],
+    uv_max: [f32; 2],
+}
+
+#[derive(Debug)]
+struct ImageTexture {
+    _texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Draws inline images as one textured quad per covered cell, so whatever
+/// the grid scrolled away or overwrote is simply not drawn. Placements with a
+/// negative z-index go below the text, the rest above it.
+#[derive(Debug)]
+pub(super) struct ImagePipeline {
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    texture_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    /// Keyed by [`ImageData::id`]; textures of images that left the screen
+    /// are released on the next upload.
+    textures: HashMap<u64, ImageTexture>,
+    used: HashSet<u64>,
+    instance_buffer: wgpu::Buffer,
+    instance_capacity: usize,
+    instances: Vec<InstanceRaw>,
+    /// `(z-index, image id, instances)`, sorted by z-index on upload.
+    batches: Vec<(i32, u64, Range<u32>)>,
+}
+
+impl ImagePipeline {
+    pub(super) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        shaders: &super::Shaders,
+    ) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("image.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/image.wgsl").into()),
+        });
+
+        let uniform_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("terminal.image.uniform.layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: wgpu::BufferSize::new(std::mem::size_of::<Uniforms>() as u64),
+                },
+                count: None,
+            }],
+        });
+
+        let texture_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("terminal.image.texture.layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("terminal.image.uniform.buffer"),
+            size: std::mem::size_of::<Uniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("terminal.image.uniform.bind_group"),
+            layout: &uniform_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("terminal.image.sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("terminal.image.instances"),
+            size: (64 * std::mem::size_of::<InstanceRaw>()) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("terminal.image.pipeline.layout"),
+            bind_group_layouts: &[&uniform_layout, &texture_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("terminal.image.pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("image_vs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[
+                    wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<[f32; 2]>() as u64,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &wgpu::vertex_attr_array![0 => Float32x2],
+                    },
+                    wgpu::VertexBufferLayout {
+                        array_stride: std::mem::size_of::<InstanceRaw>() as u64,
+                        step_mode: wgpu::VertexStepMode::Instance,
+                        attributes: &wgpu::vertex_attr_array![
+                            1 => Float32x2,
+                            2 => Float32x2,
+                            3 => Float32x2,
+                            4 => Float32x2
+                        ],
+                    },
+                ],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("image_fs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            uniform_buffer,
+            uniform_bind_group,
+            texture_layout,
+            sampler,
+            textures: HashMap::new(),
+            used: HashSet::new(),
+            instance_buffer,
+            instance_capacity: 64,
+            instances: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+
+    pub(super) fn update_uniforms(&self, queue: &wgpu::Queue, viewport: [f32; 2]) {
+        let uniforms = Uniforms {
+            viewport,
+            _pad: [0.0; 2],
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+    }
+
+    pub(super) fn begin(&mut self) {
+        self.instances.clear();
+        self.batches.clear();
+        self.used.clear();
+    }
+
+    pub(super) fn push_pane(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        cells: &[CellVisual],
+        images: &[Arc<ImagePlacement>],
+        cell_size: [f32; 2],
+        origin: [f32; 2],
+    ) {
+        for placement in images {
+            self.ensure_texture(device, queue, &placement.image);
+
+            // The bitmap's extent in cells, measured from the top-left tile.
+            let extent = [
+                placement.columns as f32 * placement.fill[0],
+                placement.lines as f32 * placement.fill[1],
+            ];
+            let [u0, v0, u1, v1] = placement.source;
+            let uv = |x: f32, y: f32| {
+                [
+                    u0 + (u1 - u0) * x / extent[0],
+                    v0 + (v1 - v0) * y / extent[1],
+                ]
+            };
+            let start = self.instances.len() as u32;
+            for cell in cells {
+                let Some(tile) = cell.image.filter(|t| t.placement == placement.id) else {
+                    continue;
+                };
+                let x0 = f32::from(tile.col);
+                let y0 = f32::from(tile.row);
+                let x1 = (x0 + 1.0).min(extent[0]);
+                let y1 = (y0 + 1.0).min(extent[1]);
+                if x1 <= x0 || y1 <= y0 {
+                    continue;
+                }
+                self.instances.push(InstanceRaw {
+                    pos: [
+                        origin[0] + cell.col as f32 * cell_size[0],
+                        origin[1] + cell.row as f32 * cell_size[1],
+                    ],
+                    size: [(x1 - x0) * cell_size[0], (y1 - y0) * cell_size[1]],
+                    uv_min: uv(x0, y0),
+                    uv_max: uv(x1, y1),
+                });
+            }
+            let end = self.instances.len() as u32;
+            if end > start {
+                self.batches
+                    .push((placement.z_index, placement.image.id, start..end));
+            }
+        }
+    }
+
+    fn ensure_texture(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, image: &ImageData) {
+        self.used.insert(image.id);
+        if self.textures.contains_key(&image.id) {
+            return;
+        }
+
+        let size = wgpu::Extent3d {
+            width: image.width,
+            height: image.height,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("terminal.image.texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &image.rgba,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(image.width * 4),
+                rows_per_image: Some(image.height),
+            },
+            size,
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("terminal.image.bind_group"),
+            layout: &self.texture_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+            ],
+        });
+        self.textures.insert(
+            image.id,
+            ImageTexture {
+                _texture: texture,
+                bind_group,
+            },
+        );
+    }
+
+    pub(super) fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.textures.retain(|id, _| self.used.contains(id));
+        self.batches.sort_by_key(|(z, ..)| *z);
+
+        if self.instances.is_empty() {
+            return;
+        }
+        if self.instances.len() > self.instance_capacity {
+            let new_cap = self.instances.len().next_power_of_two().max(64);
+            self.instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("terminal.image.instances"),
+                size: (new_cap * std::mem::size_of::<InstanceRaw>()) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            self.instance_capacity = new_cap;
+        }
+        queue.write_buffer(
+            &self.instance_buffer,
+            0,
+            bytemuck::cast_slice(&self.instances),
+        );
+    }
+
+    /// Draws the placements below the text when `below_text` is set, the ones
+    /// above it otherwise.
+    pub(super) fn draw(
+        &self,
+        pass: &mut wgpu::RenderPass<'_>,
+        quad_buffer: &wgpu::Buffer,
+        below_text: bool,
+    ) {
+        let layer = self.batches.iter().filter(|(z, ..)| (*z < 0) == below_text);
+        if layer.clone().next().is_none() {
+            return;
+        }
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+        pass.set_vertex_buffer(0, quad_buffer.slice(..));
+        pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        for (_, image, range) in layer {
+            if let Some(texture) = self.textures.get(image) {
+                pass.set_bind_group(1, &texture.bind_group, &[]);
+                pass.draw(0..6, range.clone());
+            }
+        }
+    }
+}
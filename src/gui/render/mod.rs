@@ -1,4 +1,5 @@
 use crate::gui::pane::PaneNode;
+use crate::terminal::graphics::ImagePlacement;
 use crate::terminal::{CellVisual, GridPos, Selection, SelectionPoint, TerminalSize};
 use iced::advanced::mouse::{Click, click};
 use iced::mouse;
@@ -6,25 +7,90 @@ use iced::wgpu;
 use iced::widget::shader::Program as ShaderProgram;
 use iced::widget::shader::{Action, Pipeline, Primitive, Shader, Viewport};
 use iced::{Event, Length, Point, Rectangle};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 pub const SCROLLBAR_WIDTH: f32 = 8.0;
 
 mod bg;
+mod capture;
+mod clip;
 mod composite;
+mod cursor_trail;
+mod hit;
+mod image;
+mod ring;
+mod smooth_scroll;
+pub mod surface;
 mod text;
 use bg::BackgroundPipeline;
+pub use capture::{CaptureRequest, CaptureResult};
+pub use clip::{ClipRenderer, ClipStyle};
 use composite::CompositePipeline;
+use cursor_trail::{CursorMotion, CursorTrail};
+pub use hit::GridPoint;
+use image::ImagePipeline;
+use smooth_scroll::ScrollMotion;
 use text::TextPipelineData;
+pub use text::populate_atlas;
 
 const SELECTION_BG: [f32; 4] = [0.25, 0.38, 0.60, 1.0];
 
+/// What a modifier-click on the grid would open.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkTarget {
+    Url(String),
+    Path(crate::terminal::path::PathSpan),
+}
+
+impl LinkTarget {
+    /// How the target reads in the status hint and confirmation dialog.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Url(url) => crate::terminal::url::reveal(url),
+            Self::Path(span) => match (span.line, span.column) {
+                (Some(line), Some(column)) => format!("{}:{line}:{column}", span.path),
+                (Some(line), None) => format!("{}:{line}", span.path),
+                _ => span.path.clone(),
+            },
+        }
+    }
+
+    /// The message that opens the target, for a link in `pane`.
+    pub fn into_message(self, pane: u64) -> Message {
+        match self {
+            Self::Url(url) => Message::OpenUrl(url),
+            Self::Path(span) => Message::OpenPath {
+                pane,
+                path: span.path,
+                line: span.line,
+                column: span.column,
+            },
+        }
+    }
+}
+
+/// Optional guides drawn under the focused pane's text: a tint on its cursor
+/// row and vertical rulers after the configured columns. Other panes show
+/// none.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Guides {
+    pub cursor_row: bool,
+    pub rulers: Vec<u16>,
+    pub row_color: [f32; 4],
+    pub ruler_color: [f32; 4],
+    /// Text of the timestamp gutter and the wrap indicator.
+    pub gutter_color: [f32; 4],
+}
+
 /// Iced shader wrapper for terminal rendering.
 #[derive(Debug, Clone)]
 pub struct PaneView {
     pub id: u64,
     pub scroll_history: usize,
     pub cells: Arc<Vec<CellVisual>>,
+    pub images: Arc<Vec<Arc<ImagePlacement>>>,
     pub grid_size: TerminalSize,
     pub selection: Option<Selection>,
     pub display_offset: usize,
@@ -32,6 +98,14 @@ pub struct PaneView {
     pub cursor_visible: bool,
     pub cursor_color: [f32; 4],
     pub mouse_mode: bool,
+    /// Cells reserved left of the grid for the timestamp gutter.
+    pub gutter_columns: usize,
+    /// Gutter text for each row in view.
+    pub gutter: Vec<String>,
+    /// Rows in view the terminal wrapped; empty when the indicator is off.
+    pub wrapped: Vec<bool>,
+    /// Full-screen programs own the screen, so their panes never glide.
+    pub alt_screen: bool,
 }
 
 pub struct TerminalProgram {
@@ -42,20 +116,40 @@ pub struct TerminalProgram {
     pub divider_color: [f32; 4],
     pub cell_size: [f32; 2],
     pub layout: PaneNode,
+    /// The only pane shown is zoomed out of a split; its border says so.
+    pub zoomed: bool,
     pub terminal_font_selection: Option<String>,
     pub terminal_font_size: f32,
     pub padding: [f32; 2],
     pub clear_color: [f32; 4],
     pub cursor_shape: crate::config::CursorShape,
     pub background_opacity: f32,
+    pub text_rendering: crate::config::TextRendering,
+    /// Draw bold and italic cells with synthesized glyphs.
+    pub font_synthesize: bool,
+    /// How long the cursor takes to glide to a new cell; `None` to jump.
+    pub cursor_trail: Option<Duration>,
+    /// Ease scrollback moves instead of jumping.
+    pub smooth_scroll: bool,
+    pub guides: Guides,
+    /// Characters besides whitespace that end a double-clicked word.
+    pub word_separators: String,
+    /// How much darker unfocused panes are drawn, 0 to 1.
+    pub inactive_dim: f32,
+    /// How much darker the whole view is drawn, for an unfocused window.
+    pub window_dim: f32,
+    /// A screenshot waiting to be read back from the next frame.
+    pub capture: Option<CaptureRequest>,
 }
 
 impl PaneView {
-    fn inner(rect: Rectangle, padding: [f32; 2]) -> Rectangle {
+    /// The grid's area inside `rect`, after the padding and the gutter.
+    fn inner(&self, rect: Rectangle, padding: [f32; 2], cell: [f32; 2]) -> Rectangle {
+        let gutter = self.gutter_columns as f32 * cell[0];
         Rectangle {
-            x: rect.x + padding[0],
+            x: rect.x + padding[0] + gutter,
             y: rect.y + padding[1],
-            width: (rect.width - padding[0] * 2.0).max(1.0),
+            width: (rect.width - padding[0] * 2.0 - gutter).max(1.0),
             height: (rect.height - padding[1] * 2.0).max(1.0),
         }
     }
@@ -67,7 +161,7 @@ impl PaneView {
         padding: [f32; 2],
         cell: [f32; 2],
     ) -> GridPos {
-        let inner = Self::inner(rect, padding);
+        let inner = self.inner(rect, padding, cell);
         let cell_w = cell[0].max(1.0);
         let cell_h = cell[1].max(1.0);
         let x = (pos.x - inner.x).max(0.0);
@@ -86,15 +180,18 @@ impl PaneView {
         (0..cols).map(|col| self.cell_char(row, col)).collect()
     }
 
-    fn link_at(&self, grid: GridPos) -> Option<String> {
+    fn link_at(&self, grid: GridPos) -> Option<LinkTarget> {
         if let Some(uri) = self
             .cells
             .get(grid.row * self.grid_size.columns.max(1) + grid.col)
             .and_then(|cell| cell.hyperlink.clone())
         {
-            return crate::terminal::url::is_openable(&uri).then(|| uri.to_string());
+            return crate::terminal::url::is_link(&uri).then(|| LinkTarget::Url(uri.to_string()));
         }
-        crate::terminal::url::url_at(&self.row_chars(grid.row), grid.col).map(|span| span.url)
+        let row = self.row_chars(grid.row);
+        crate::terminal::url::url_at(&row, grid.col)
+            .map(|span| LinkTarget::Url(span.url))
+            .or_else(|| crate::terminal::path::path_at(&row, grid.col).map(LinkTarget::Path))
     }
 
     fn link_span_at(&self, grid: GridPos) -> Option<(usize, usize)> {
@@ -102,12 +199,16 @@ impl PaneView {
             .cells
             .get(grid.row * self.grid_size.columns.max(1) + grid.col)
             && let Some(uri) = cell.hyperlink.as_deref()
-            && crate::terminal::url::is_openable(uri)
+            && crate::terminal::url::is_link(uri)
         {
             return Some(self.hyperlink_run(grid, uri));
         }
-        crate::terminal::url::url_at(&self.row_chars(grid.row), grid.col)
+        let row = self.row_chars(grid.row);
+        crate::terminal::url::url_at(&row, grid.col)
             .map(|span| (span.start, span.end))
+            .or_else(|| {
+                crate::terminal::path::path_at(&row, grid.col).map(|span| (span.start, span.end))
+            })
     }
 
     fn hyperlink_run(&self, grid: GridPos, uri: &str) -> (usize, usize) {
@@ -140,19 +241,27 @@ impl PaneView {
             .unwrap_or(' ')
     }
 
-    fn word_selection(&self, grid: GridPos) -> Option<Selection> {
+    /// First and last column of the word under `grid`, where words end at
+    /// whitespace and any of `separators`.
+    fn word_span(&self, grid: GridPos, separators: &str) -> Option<(usize, usize)> {
         let cols = self.grid_size.columns;
-        if cols == 0 || !is_word_char(self.cell_char(grid.row, grid.col)) {
+        let is_word = |col| is_word_char(self.cell_char(grid.row, col), separators);
+        if cols == 0 || !is_word(grid.col) {
             return None;
         }
         let mut start = grid.col;
-        while start > 0 && is_word_char(self.cell_char(grid.row, start - 1)) {
+        while start > 0 && is_word(start - 1) {
             start -= 1;
         }
         let mut end = grid.col;
-        while end + 1 < cols && is_word_char(self.cell_char(grid.row, end + 1)) {
+        while end + 1 < cols && is_word(end + 1) {
             end += 1;
         }
+        Some((start, end))
+    }
+
+    fn word_selection(&self, grid: GridPos, separators: &str) -> Option<Selection> {
+        let (start, end) = self.word_span(grid, separators)?;
         Some(Selection {
             start: SelectionPoint {
                 row: grid.row as i64,
@@ -241,6 +350,65 @@ impl TerminalProgram {
         let rect = regions.iter().find(|(rid, _)| *rid == id)?.1;
         self.pane(id).map(|pane| (pane, rect))
     }
+
+    /// Reports the link under the cursor while the link modifier is held so
+    /// the app can show where a click would go. Only publishes on change.
+    fn update_hovered_link(
+        &self,
+        state: &mut TerminalShaderState,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<Action<Message>> {
+        let hovered = link_modifier(state.modifiers)
+            .then(|| cursor.position_in(bounds))
+            .flatten()
+            .and_then(|pos| self.link_at(self.hit_test(pos, bounds.size())?))
+            .map(|link| link.label());
+        if hovered == state.hovered_link {
+            return None;
+        }
+        state.hovered_link = hovered.clone();
+        Some(Action::publish(Message::LinkHovered(hovered)))
+    }
+
+    /// Points each pane's cursor and scroll glides at where the pane is
+    /// now, and keeps frames coming while any is still moving.
+    fn advance_motion(
+        &self,
+        state: &mut TerminalShaderState,
+        now: Instant,
+    ) -> Option<Action<Message>> {
+        state.cursor_motion.retain(|id, _| self.pane(*id).is_some());
+        state.scroll_motion.retain(|id, _| {
+            self.smooth_scroll && self.pane(*id).is_some_and(|pane| !pane.alt_screen)
+        });
+        let mut moving = false;
+        for pane in &self.panes {
+            if let Some(duration) = self.cursor_trail
+                && let Some(cell) = pane.cursor
+            {
+                let motion = state
+                    .cursor_motion
+                    .entry(pane.id)
+                    .or_insert_with(|| CursorMotion::at(cell, now));
+                motion.retarget(cell, now, duration);
+                moving |= motion.is_moving(now, duration);
+            }
+            if self.smooth_scroll && !pane.alt_screen {
+                let top = pane.scroll_history as i64 - pane.display_offset as i64;
+                let motion = state
+                    .scroll_motion
+                    .entry(pane.id)
+                    .or_insert_with(|| ScrollMotion::at(top, pane.display_offset, now));
+                motion.follow(top, pane.display_offset, pane.grid_size.lines, now);
+                moving |= motion.is_moving(now);
+            }
+        }
+        if self.cursor_trail.is_none() {
+            state.cursor_motion.clear();
+        }
+        moving.then(Action::request_redraw)
+    }
 }
 
 #[derive(Debug, Default)]
@@ -254,19 +422,19 @@ pub struct TerminalShaderState {
     scrollbar_drag: Option<u64>,
     last_bounds: Rectangle,
     modifiers: iced::keyboard::Modifiers,
+    /// Label of the link last reported through `Message::LinkHovered`.
+    hovered_link: Option<String>,
+    /// Each pane's cursor glide, while the smooth cursor is on.
+    cursor_motion: HashMap<u64, CursorMotion>,
+    /// Each pane's scroll glide, while smooth scrolling is on.
+    scroll_motion: HashMap<u64, ScrollMotion>,
 }
 
 /// Word delimiter check (alacritty-style). A "word" is a run of non-whitespace
-/// characters that are not common semantic-escape delimiters, so paths and URLs
-/// select as a single unit.
-fn is_word_char(c: char) -> bool {
-    if c == '\0' || c.is_whitespace() {
-        return false;
-    }
-    !matches!(
-        c,
-        ',' | '│' | '`' | '"' | '\'' | '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>'
-    )
+/// characters outside `separators`; the default set leaves out `/`, `:` and
+/// `.`, so paths and URLs select as a single unit.
+fn is_word_char(c: char, separators: &str) -> bool {
+    c != '\0' && !c.is_whitespace() && !separators.contains(c)
 }
 
 type Message = crate::gui::app::Message;
@@ -281,6 +449,29 @@ fn clamp_to_bounds(absolute: Point, bounds: Rectangle) -> Point {
     )
 }
 
+/// An arrow for each wrapped row, in a one-column grid of its own.
+fn wrap_indicator_cells(wrapped: &[bool], color: [f32; 4]) -> Vec<CellVisual> {
+    wrapped
+        .iter()
+        .enumerate()
+        .filter(|&(_, &wrapped)| wrapped)
+        .map(|(row, _)| CellVisual {
+            ch: '↩',
+            grapheme: None,
+            col: 0,
+            row,
+            fg: color,
+            bg: [0.0; 4],
+            underline: false,
+            bold: false,
+            italic: false,
+            wide: false,
+            hyperlink: None,
+            image: None,
+        })
+        .collect()
+}
+
 fn scrollbar_rel(pos_y: f32, rect_y: f32, rect_height: f32, thumb: f32) -> f32 {
     let travel = (rect_height - thumb).max(1.0);
     ((pos_y - rect_y - thumb / 2.0) / travel).clamp(0.0, 1.0)
@@ -318,8 +509,12 @@ impl ShaderProgram<Message> for TerminalProgram {
         }
 
         match event {
+            Event::Window(iced::window::Event::RedrawRequested(now)) => {
+                return self.advance_motion(state, *now);
+            }
             Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) => {
                 state.modifiers = *modifiers;
+                return self.update_hovered_link(state, bounds, cursor);
             }
             Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
                 let pos = cursor.position_in(bounds)?;
@@ -338,9 +533,9 @@ impl ShaderProgram<Message> for TerminalProgram {
                 let grid_pos = pane.pixel_to_grid(pos, rect, padding, self.cell_size);
 
                 if link_modifier(state.modifiers)
-                    && let Some(url) = pane.link_at(grid_pos)
+                    && let Some(link) = pane.link_at(grid_pos)
                 {
-                    return Some(Action::publish(Message::OpenUrl(url)).and_capture());
+                    return Some(Action::publish(link.into_message(pane.id)).and_capture());
                 }
                 if pane.mouse_mode {
                     state.dragging = true;
@@ -361,14 +556,21 @@ impl ShaderProgram<Message> for TerminalProgram {
                     click::Kind::Double => {
                         state.dragging = false;
                         state.drag_start = None;
-                        let sel = pane.word_selection(grid_pos);
-                        return Some(
-                            Action::publish(Message::SelectionChanged {
+                        let sel = pane.word_selection(grid_pos, &self.word_separators);
+                        let message = if state.modifiers.shift() {
+                            Message::SemanticSelect {
+                                pane: pane.id,
+                                row: grid_pos.row,
+                                col: grid_pos.col,
+                                word: sel,
+                            }
+                        } else {
+                            Message::SelectionChanged {
                                 pane: pane.id,
                                 selection: sel,
-                            })
-                            .and_capture(),
-                        );
+                            }
+                        };
+                        return Some(Action::publish(message).and_capture());
                     }
                     click::Kind::Triple => {
                         state.dragging = false;
@@ -399,10 +601,14 @@ impl ShaderProgram<Message> for TerminalProgram {
             }
             Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) => {
                 if let Some(pos) = cursor.position_in(bounds)
-                    && let Some((pane, _)) = self.pane_under(pos, bounds)
+                    && let Some(point) = self.hit_test(pos, bounds.size())
                 {
                     return Some(
-                        Action::publish(Message::TerminalRightClick(pane.id)).and_capture(),
+                        Action::publish(Message::TerminalRightClick {
+                            pane: point.pane,
+                            link: self.link_at(point),
+                        })
+                        .and_capture(),
                     );
                 }
             }
@@ -428,7 +634,9 @@ impl ShaderProgram<Message> for TerminalProgram {
                     );
                 }
 
-                let pos = pos_dragging?;
+                let Some(pos) = pos_dragging else {
+                    return self.update_hovered_link(state, bounds, cursor);
+                };
                 let regions = self.regions(bounds);
                 let (pane, rect) = state
                     .drag_pane
@@ -452,13 +660,17 @@ impl ShaderProgram<Message> for TerminalProgram {
                 }
                 if let Some(drag_start) = state.drag_start {
                     let raw_y = cursor.position().map(|p| p.y);
-                    let out_up = raw_y.is_some_and(|y| y < bounds.y + rect.y);
-                    let out_down = raw_y.is_some_and(|y| y > bounds.y + rect.y + rect.height);
+                    let top = bounds.y + rect.y;
+                    let bottom = top + rect.height;
+                    let out_up = raw_y.is_some_and(|y| y < top);
+                    let out_down = raw_y.is_some_and(|y| y > bottom);
                     if out_up || out_down {
+                        let past = raw_y.map_or(0.0, |y| (top - y).max(y - bottom));
                         return Some(
                             Action::publish(Message::TerminalSelectionAutoscroll {
                                 up: out_up,
                                 col: grid_pos.col,
+                                overshoot: past / self.cell_size[1].max(1.0),
                             })
                             .and_capture(),
                         );
@@ -536,12 +748,22 @@ impl ShaderProgram<Message> for TerminalProgram {
                     .map(|(pane, rect)| (pane, rect, pos))
             });
 
+        let now = Instant::now();
         let panes = self
             .regions(bounds)
             .into_iter()
             .filter_map(|(id, rect)| {
                 let pane = self.pane(id)?;
-                let inner = PaneView::inner(rect, self.padding);
+                let mut inner = pane.inner(rect, self.padding, self.cell_size);
+                // Mid-glide the grid is drawn off its rows, with whatever it
+                // showed before still moving out of view.
+                if let Some(motion) = state
+                    .scroll_motion
+                    .get(&id)
+                    .filter(|_| self.smooth_scroll && !pane.alt_screen)
+                {
+                    inner.y += motion.shift(now) * self.cell_size[1];
+                }
                 let link_row =
                     hovered
                         .as_ref()
@@ -561,24 +783,53 @@ impl ShaderProgram<Message> for TerminalProgram {
                 });
                 Some(PanePrimitive {
                     cells: Arc::clone(&pane.cells),
+                    images: Arc::clone(&pane.images),
                     origin: [inner.x, inner.y],
+                    gutter: pane.gutter.clone(),
+                    gutter_origin: [rect.x + self.padding[0], inner.y],
+                    wrapped: pane.wrapped.clone(),
+                    wrap_origin: [
+                        inner.x + pane.grid_size.columns as f32 * self.cell_size[0],
+                        inner.y,
+                    ],
                     rect: [rect.x, rect.y, rect.width, rect.height],
                     scrollbar,
                     focused: pane.id == self.focused,
                     selection: pane.selection,
                     display_offset: pane.display_offset,
                     cursor: pane.cursor.filter(|_| pane.cursor_visible),
+                    cursor_row: pane.cursor.map(|[_, row]| row),
                     cursor_color: pane.cursor_color,
+                    cursor_trail: self
+                        .cursor_trail
+                        .zip(state.cursor_motion.get(&id))
+                        .filter(|(_, motion)| pane.cursor == Some(motion.target()))
+                        .and_then(|(duration, motion)| motion.trail(now, duration)),
                     link_row,
                 })
             })
-            .collect();
+            .collect::<Vec<_>>();
+
+        let capture = self.capture.clone().map(|request| {
+            let crop = request
+                .rows
+                .and_then(|(first, last)| {
+                    let pane = panes.iter().find(|pane| pane.focused)?;
+                    let [x, _, width, _] = pane.rect;
+                    let top = pane.origin[1] + first as f32 * self.cell_size[1];
+                    let height = (last + 1 - first) as f32 * self.cell_size[1];
+                    Some([x, top, width, height])
+                })
+                .unwrap_or([0.0, 0.0, bounds.width, bounds.height]);
+            (request, crop)
+        });
 
         TerminalPrimitive {
             panes,
             scrollbar_color: self.scrollbar_color,
             focus_color: self.focus_color,
             divider_color: self.divider_color,
+            zoomed: self.zoomed,
             cell_size: self.cell_size,
             viewport: [bounds.width, bounds.height],
             clear_color: self.clear_color,
@@ -586,6 +837,12 @@ impl ShaderProgram<Message> for TerminalProgram {
             terminal_font_size: self.terminal_font_size,
             cursor_shape: self.cursor_shape,
             background_opacity: self.background_opacity,
+            text_rendering: self.text_rendering,
+            font_synthesize: self.font_synthesize,
+            guides: self.guides.clone(),
+            inactive_dim: self.inactive_dim,
+            window_dim: self.window_dim,
+            capture,
         }
     }
 
@@ -595,17 +852,34 @@ impl ShaderProgram<Message> for TerminalProgram {
         bounds: Rectangle,
         cursor: mouse::Cursor,
     ) -> mouse::Interaction {
+        // Drags keep their cursor when they leave the view.
+        if state.scrollbar_drag.is_some() {
+            return mouse::Interaction::Grabbing;
+        }
+        if state.dragging
+            && state
+                .drag_pane
+                .and_then(|id| self.pane(id))
+                .is_some_and(|pane| !pane.mouse_mode)
+        {
+            return mouse::Interaction::Text;
+        }
         let Some(pos) = cursor.position_in(bounds) else {
             return mouse::Interaction::default();
         };
-        if link_modifier(state.modifiers)
-            && let Some((pane, rect)) = self.pane_under(pos, bounds)
-            && pane
-                .link_at(pane.pixel_to_grid(pos, rect, self.padding, self.cell_size))
-                .is_some()
-        {
+        if self.scrollbar_at(pos, bounds).is_some() {
+            return mouse::Interaction::Idle;
+        }
+        let Some(point) = self.hit_test(pos, bounds.size()) else {
+            return mouse::Interaction::Idle;
+        };
+        if link_modifier(state.modifiers) && self.link_at(point).is_some() {
             return mouse::Interaction::Pointer;
         }
+        // Clicks go to the program, not to selection.
+        if self.pane(point.pane).is_some_and(|pane| pane.mouse_mode) {
+            return mouse::Interaction::Idle;
+        }
         mouse::Interaction::Text
     }
 }
@@ -614,6 +888,7 @@ impl ShaderProgram<Message> for TerminalProgram {
 pub struct TerminalPipeline {
     bg: BackgroundPipeline,
     text: TextPipelineData,
+    image: ImagePipeline,
     composite: CompositePipeline,
     last_panes: Vec<PaneSignature>,
     last_viewport: [f32; 2],
@@ -621,20 +896,54 @@ pub struct TerminalPipeline {
     last_font_size: f32,
     last_cursor_shape: crate::config::CursorShape,
     last_background_opacity: f32,
+    last_text_rendering: crate::config::TextRendering,
+    last_font_synthesize: bool,
+    last_guides: Guides,
+    /// Id of the last screenshot request read back.
+    last_capture: u64,
+}
+
+/// What the terminal's pipelines are built from, made once per device.
+///
+/// Compiled pipelines are not cached between runs: iced creates the device
+/// without `Features::PIPELINE_CACHE` and offers no way to ask for it.
+struct Shaders {
+    /// `terminal.wgsl`, holding the background, text and composite stages.
+    terminal: wgpu::ShaderModule,
+}
+
+impl Shaders {
+    fn new(device: &wgpu::Device) -> Self {
+        let terminal = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("terminal.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/terminal.wgsl").into()),
+        });
+        Self { terminal }
+    }
+
+    fn terminal(&self) -> &wgpu::ShaderModule {
+        &self.terminal
+    }
 }
 
 impl Pipeline for TerminalPipeline {
     fn new(device: &wgpu::Device, _queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        let shaders = Shaders::new(device);
         Self {
-            bg: BackgroundPipeline::new(device, format),
-            text: TextPipelineData::new(device, format),
-            composite: CompositePipeline::new(device, format),
+            bg: BackgroundPipeline::new(device, format, &shaders),
+            text: TextPipelineData::new(device, format, &shaders),
+            image: ImagePipeline::new(device, format, &shaders),
+            composite: CompositePipeline::new(device, format, &shaders),
             last_panes: Vec::new(),
             last_viewport: [0.0; 2],
             last_cell_size: [0.0; 2],
             last_font_size: 0.0,
             last_cursor_shape: crate::config::CursorShape::Block,
             last_background_opacity: 1.0,
+            last_text_rendering: crate::config::TextRendering::default(),
+            last_font_synthesize: crate::config::DEFAULT_FONT_SYNTHESIZE,
+            last_guides: Guides::default(),
+            last_capture: 0,
         }
     }
 }
@@ -643,28 +952,47 @@ impl Pipeline for TerminalPipeline {
 struct PaneSignature {
     cells_ptr: usize,
     cells_len: usize,
+    images_ptr: usize,
     origin: [f32; 2],
+    gutter: Vec<String>,
+    gutter_origin: [f32; 2],
+    wrapped: Vec<bool>,
+    wrap_origin: [f32; 2],
     rect: [f32; 4],
     scrollbar: Option<[u32; 2]>,
     focused: bool,
     selection: Option<Selection>,
     display_offset: usize,
     cursor: Option<[u32; 2]>,
+    cursor_row: Option<u32>,
     cursor_color: [f32; 4],
+    /// Head and tail in 64ths of a cell.
+    cursor_trail: Option<[i32; 4]>,
     link_row: Option<(usize, usize, usize)>,
 }
 
 #[derive(Debug)]
 pub struct PanePrimitive {
     cells: Arc<Vec<CellVisual>>,
+    images: Arc<Vec<Arc<ImagePlacement>>>,
     origin: [f32; 2],
+    /// Timestamp labels, one per row, drawn from `gutter_origin`.
+    gutter: Vec<String>,
+    gutter_origin: [f32; 2],
+    /// Rows marked with the wrap indicator, drawn from `wrap_origin`.
+    wrapped: Vec<bool>,
+    wrap_origin: [f32; 2],
     rect: [f32; 4],
     scrollbar: Option<[f32; 2]>,
     focused: bool,
     selection: Option<Selection>,
     display_offset: usize,
     cursor: Option<[u32; 2]>,
+    /// Unlike `cursor`, not hidden while the cursor blinks.
+    cursor_row: Option<u32>,
     cursor_color: [f32; 4],
+    /// Set while the smooth cursor is gliding to `cursor`.
+    cursor_trail: Option<CursorTrail>,
     link_row: Option<(usize, usize, usize)>,
 }
 
@@ -673,7 +1001,12 @@ impl PanePrimitive {
         PaneSignature {
             cells_ptr: Arc::as_ptr(&self.cells) as usize,
             cells_len: self.cells.len(),
+            images_ptr: Arc::as_ptr(&self.images) as usize,
             origin: [self.origin[0] * scale, self.origin[1] * scale],
+            gutter: self.gutter.clone(),
+            gutter_origin: [self.gutter_origin[0] * scale, self.gutter_origin[1] * scale],
+            wrapped: self.wrapped.clone(),
+            wrap_origin: [self.wrap_origin[0] * scale, self.wrap_origin[1] * scale],
             rect: [
                 self.rect[0] * scale,
                 self.rect[1] * scale,
@@ -687,7 +1020,13 @@ impl PanePrimitive {
             selection: self.selection,
             display_offset: self.display_offset,
             cursor: self.cursor,
+            cursor_row: self.cursor_row,
             cursor_color: self.cursor_color,
+            cursor_trail: self.cursor_trail.map(|trail| {
+                let [hx, hy] = trail.head.map(|v| (v * 64.0) as i32);
+                let [tx, ty] = trail.tail.map(|v| (v * 64.0) as i32);
+                [hx, hy, tx, ty]
+            }),
             link_row: self.link_row,
         }
     }
@@ -699,6 +1038,7 @@ pub struct TerminalPrimitive {
     scrollbar_color: [f32; 4],
     focus_color: [f32; 4],
     divider_color: [f32; 4],
+    zoomed: bool,
     cell_size: [f32; 2],
     viewport: [f32; 2],
     clear_color: [f32; 4],
@@ -706,6 +1046,34 @@ pub struct TerminalPrimitive {
     terminal_font_size: f32,
     cursor_shape: crate::config::CursorShape,
     background_opacity: f32,
+    text_rendering: crate::config::TextRendering,
+    font_synthesize: bool,
+    guides: Guides,
+    inactive_dim: f32,
+    window_dim: f32,
+    /// A screenshot request and the part of the view it keeps, in logical
+    /// pixels.
+    capture: Option<(CaptureRequest, [f32; 4])>,
+}
+
+impl TerminalPrimitive {
+    /// The focused pane's rect in texture coordinates and the brightness
+    /// inside and outside it. Only split layouts dim their other panes.
+    fn dimming(&self) -> ([f32; 4], f32, f32) {
+        let lit = 1.0 - self.window_dim.clamp(0.0, 1.0);
+        let focused = self
+            .panes
+            .iter()
+            .find(|pane| pane.focused)
+            .filter(|_| self.panes.len() > 1);
+        let Some(pane) = focused else {
+            return ([0.0; 4], lit, lit);
+        };
+        let [x, y, w, h] = pane.rect;
+        let [vw, vh] = self.viewport.map(|v| v.max(1.0));
+        let rect = [x / vw, y / vh, (x + w) / vw, (y + h) / vh];
+        (rect, lit, lit * (1.0 - self.inactive_dim.clamp(0.0, 1.0)))
+    }
 }
 
 impl Primitive for TerminalPrimitive {
@@ -721,13 +1089,33 @@ impl Primitive for TerminalPrimitive {
     ) {
         let scale = viewport.scale_factor().max(1.0);
         let view = [self.viewport[0] * scale, self.viewport[1] * scale];
+        // A collapsed or not-yet-laid-out area has nothing to draw.
+        if !view.iter().all(|side| side.is_finite() && *side >= 1.0) {
+            return;
+        }
         let font_size = self.terminal_font_size * scale;
+        // Larger than the GPU allows would fail texture creation outright.
+        let max_side = device.limits().max_texture_dimension_2d;
         let offscreen_size = [
-            view[0].ceil().max(1.0) as u32,
-            view[1].ceil().max(1.0) as u32,
+            (view[0].ceil() as u32).min(max_side),
+            (view[1].ceil() as u32).min(max_side),
         ];
 
+        // The offscreen texture still holds the frame on screen.
+        if let Some((request, crop)) = &self.capture
+            && request.id != pipeline.last_capture
+        {
+            pipeline.last_capture = request.id;
+            pipeline
+                .composite
+                .capture(device, queue, crop.map(|v| v * scale), request);
+        }
+
         pipeline.composite.ensure_offscreen(device, offscreen_size);
+        let (focus_rect, inside, outside) = self.dimming();
+        pipeline
+            .composite
+            .update_dimming(queue, focus_rect, inside, outside);
 
         let cell_size = [self.cell_size[0] * scale, self.cell_size[1] * scale];
         let signatures: Vec<PaneSignature> =
@@ -737,7 +1125,10 @@ impl Primitive for TerminalPrimitive {
             && cell_size == pipeline.last_cell_size
             && (font_size - pipeline.last_font_size).abs() < 0.01
             && self.cursor_shape == pipeline.last_cursor_shape
-            && self.background_opacity == pipeline.last_background_opacity;
+            && self.background_opacity == pipeline.last_background_opacity
+            && self.text_rendering == pipeline.last_text_rendering
+            && self.font_synthesize == pipeline.last_font_synthesize
+            && self.guides == pipeline.last_guides;
 
         if unchanged {
             return;
@@ -749,27 +1140,45 @@ impl Primitive for TerminalPrimitive {
         pipeline.last_font_size = font_size;
         pipeline.last_cursor_shape = self.cursor_shape;
         pipeline.last_background_opacity = self.background_opacity;
+        pipeline.last_text_rendering = self.text_rendering;
+        pipeline.last_font_synthesize = self.font_synthesize;
+        pipeline.last_guides = self.guides.clone();
 
         pipeline
             .text
-            .apply_terminal_font_selection(device, self.terminal_font_selection.as_deref());
+            .apply_terminal_font_selection(self.terminal_font_selection.as_deref());
         pipeline.text.set_requested_font_size(font_size);
-        pipeline.text.update_uniforms(queue, view, [0.0, 0.0]);
+        pipeline.text.set_synthesize(self.font_synthesize);
+        pipeline.text.update_uniforms(
+            queue,
+            view,
+            [0.0, 0.0],
+            self.text_rendering,
+            self.background_opacity >= 1.0,
+        );
         pipeline
             .bg
             .update_uniforms(queue, cell_size, view, [0.0, 0.0]);
+        pipeline.image.update_uniforms(queue, view);
 
         pipeline.bg.begin();
         pipeline.text.begin();
+        pipeline.image.begin();
 
         for pane in &self.panes {
             let origin = [pane.origin[0] * scale, pane.origin[1] * scale];
             let cells = pane.cells.as_slice();
+            if pane.focused {
+                pipeline
+                    .bg
+                    .push_guides(cells, pane.cursor_row, &self.guides, origin);
+            }
             pipeline.bg.push_pane(
                 cells,
                 pane.selection.as_ref(),
                 pane.display_offset,
                 pane.cursor,
+                pane.cursor_trail,
                 self.cursor_shape,
                 pane.cursor_color,
                 self.background_opacity,
@@ -783,11 +1192,46 @@ impl Primitive for TerminalPrimitive {
                 cell_size,
                 pane.selection.as_ref(),
                 pane.display_offset,
-                pane.cursor
-                    .filter(|_| self.cursor_shape == crate::config::CursorShape::Block),
+                // Text under a gliding block waits until it lands.
+                pane.cursor.filter(|_| {
+                    self.cursor_shape == crate::config::CursorShape::Block
+                        && pane.cursor_trail.is_none()
+                }),
                 pane.cursor_color,
                 origin,
             );
+            pipeline
+                .image
+                .push_pane(device, queue, cells, &pane.images, cell_size, origin);
+            if !pane.gutter.is_empty() {
+                let gutter =
+                    crate::terminal::timestamps::cells(&pane.gutter, self.guides.gutter_color);
+                pipeline.text.push_pane(
+                    device,
+                    queue,
+                    &gutter,
+                    cell_size,
+                    None,
+                    0,
+                    None,
+                    [0.0; 4],
+                    [pane.gutter_origin[0] * scale, pane.gutter_origin[1] * scale],
+                );
+            }
+            if pane.wrapped.contains(&true) {
+                let marks = wrap_indicator_cells(&pane.wrapped, self.guides.gutter_color);
+                pipeline.text.push_pane(
+                    device,
+                    queue,
+                    &marks,
+                    cell_size,
+                    None,
+                    0,
+                    None,
+                    [0.0; 4],
+                    [pane.wrap_origin[0] * scale, pane.wrap_origin[1] * scale],
+                );
+            }
         }
 
         for pane in &self.panes {
@@ -818,7 +1262,7 @@ impl Primitive for TerminalPrimitive {
             );
         }
 
-        if self.panes.len() > 1 {
+        if self.panes.len() > 1 || self.zoomed {
             for pane in &self.panes {
                 let [x, y, w, h] = pane.rect.map(|v| v * scale);
                 let color = if pane.focused {
@@ -826,7 +1270,9 @@ impl Primitive for TerminalPrimitive {
                 } else {
                     self.divider_color
                 };
-                let t = scale;
+                // A zoomed pane gets a thicker border, so hidden splits
+                // aren't forgotten.
+                let t = if self.zoomed { 2.0 * scale } else { scale };
                 for (origin, size) in [
                     ([x, y], [w, t]),
                     ([x, y + h - t], [w, t]),
@@ -840,6 +1286,7 @@ impl Primitive for TerminalPrimitive {
 
         pipeline.bg.upload(device, queue);
         pipeline.text.upload(device, queue);
+        pipeline.image.upload(device, queue);
     }
 
     fn render(
@@ -849,6 +1296,10 @@ impl Primitive for TerminalPrimitive {
         target: &wgpu::TextureView,
         clip_bounds: &Rectangle<u32>,
     ) {
+        if clip_bounds.width == 0 || clip_bounds.height == 0 || !pipeline.composite.has_offscreen()
+        {
+            return;
+        }
         let bg_pipeline = &pipeline.bg;
         let text_pipeline = &pipeline.text;
         let composite = &pipeline.composite;
@@ -896,6 +1347,10 @@ impl Primitive for TerminalPrimitive {
             let instance_count = bg_pipeline.instance_count().max(1) as u32;
             offscreen_pass.draw(0..6, 0..instance_count);
 
+            pipeline
+                .image
+                .draw(&mut offscreen_pass, bg_pipeline.quad_buffer(), true);
+
             if text_pipeline.instance_len() > 0 {
                 offscreen_pass.set_pipeline(text_pipeline.pipeline());
                 offscreen_pass.set_bind_group(0, text_pipeline.empty_bind_group(), &[]);
@@ -904,6 +1359,10 @@ impl Primitive for TerminalPrimitive {
                 offscreen_pass.set_vertex_buffer(1, text_pipeline.instance_buffer().slice(..));
                 offscreen_pass.draw(0..6, 0..text_pipeline.instance_len() as u32);
             }
+
+            pipeline
+                .image
+                .draw(&mut offscreen_pass, bg_pipeline.quad_buffer(), false);
         }
 
         let mut composite_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
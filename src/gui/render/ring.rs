@@ -0,0 +1,120 @@
+//! Buffers rewritten every frame, kept in rings of [`FRAMES`] so a frame
+//! being prepared never writes into a buffer an earlier frame the GPU may
+//! still be reading from is bound to.
+
+use iced::wgpu;
+
+/// Frames that can be in flight at once.
+pub(super) const FRAMES: usize = 3;
+
+/// Instance data for one draw per frame, each slot grown on its own.
+#[derive(Debug)]
+pub(super) struct InstanceRing {
+    label: &'static str,
+    stride: usize,
+    slots: Vec<(wgpu::Buffer, usize)>,
+    current: usize,
+}
+
+impl InstanceRing {
+    /// Room for `capacity` instances of `stride` bytes in each slot to start.
+    pub(super) fn new(
+        device: &wgpu::Device,
+        label: &'static str,
+        stride: usize,
+        capacity: usize,
+    ) -> Self {
+        let slots = (0..FRAMES)
+            .map(|_| (instance_buffer(device, label, stride, capacity), capacity))
+            .collect();
+        Self {
+            label,
+            stride,
+            slots,
+            current: 0,
+        }
+    }
+
+    /// Moves to the next slot and fills it with `bytes`.
+    pub(super) fn write(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, bytes: &[u8]) {
+        self.current = (self.current + 1) % FRAMES;
+        let required = bytes.len().div_ceil(self.stride);
+        let (buffer, capacity) = &mut self.slots[self.current];
+        if required > *capacity {
+            *capacity = required.next_power_of_two().max(64);
+            *buffer = instance_buffer(device, self.label, self.stride, *capacity);
+        }
+        if !bytes.is_empty() {
+            queue.write_buffer(buffer, 0, bytes);
+        }
+    }
+
+    /// The slot last written.
+    pub(super) fn buffer(&self) -> &wgpu::Buffer {
+        &self.slots[self.current].0
+    }
+}
+
+fn instance_buffer(
+    device: &wgpu::Device,
+    label: &'static str,
+    stride: usize,
+    capacity: usize,
+) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: (capacity * stride) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+/// A uniform block per frame, each with the bind group that reads it.
+#[derive(Debug)]
+pub(super) struct UniformRing {
+    slots: Vec<(wgpu::Buffer, wgpu::BindGroup)>,
+    current: usize,
+}
+
+impl UniformRing {
+    /// `bind` makes the bind group for one slot's buffer.
+    pub(super) fn new(
+        device: &wgpu::Device,
+        label: &'static str,
+        size: usize,
+        bind: impl Fn(&wgpu::Buffer) -> wgpu::BindGroup,
+    ) -> Self {
+        let slots = (0..FRAMES)
+            .map(|_| {
+                let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some(label),
+                    size: size as wgpu::BufferAddress,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                let bind_group = bind(&buffer);
+                (buffer, bind_group)
+            })
+            .collect();
+        Self { slots, current: 0 }
+    }
+
+    /// Moves to the next slot and fills it with `bytes`.
+    pub(super) fn write(&mut self, queue: &wgpu::Queue, bytes: &[u8]) {
+        self.current = (self.current + 1) % FRAMES;
+        queue.write_buffer(&self.slots[self.current].0, 0, bytes);
+    }
+
+    /// Remakes every slot's bind group, for when something else it binds
+    /// was replaced.
+    pub(super) fn rebind(&mut self, bind: impl Fn(&wgpu::Buffer) -> wgpu::BindGroup) {
+        for (buffer, bind_group) in &mut self.slots {
+            *bind_group = bind(buffer);
+        }
+    }
+
+    /// The bind group for the slot last written.
+    pub(super) fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.slots[self.current].1
+    }
+}
@@ -0,0 +1,44 @@
+struct ImageUniforms {
+    viewport : vec2<f32>,
+    _pad     : vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms : ImageUniforms;
+
+@group(1) @binding(0)
+var image_sampler : sampler;
+@group(1) @binding(1)
+var image_texture : texture_2d<f32>;
+
+struct ImageVertexIn {
+    @location(0) quad_pos : vec2<f32>,
+    @location(1) pos      : vec2<f32>,
+    @location(2) size     : vec2<f32>,
+    @location(3) uv_min   : vec2<f32>,
+    @location(4) uv_max   : vec2<f32>,
+};
+
+struct ImageVertexOut {
+    @builtin(position) position : vec4<f32>,
+    @location(0) uv : vec2<f32>,
+};
+
+@vertex
+fn image_vs_main(input : ImageVertexIn) -> ImageVertexOut {
+    let pixel = input.pos + input.quad_pos * input.size;
+    let ndc = vec2<f32>(
+        (pixel.x / uniforms.viewport.x) * 2.0 - 1.0,
+        1.0 - (pixel.y / uniforms.viewport.y) * 2.0
+    );
+
+    var out : ImageVertexOut;
+    out.position = vec4<f32>(ndc, 0.0, 1.0);
+    out.uv = input.uv_min + (input.uv_max - input.uv_min) * input.quad_pos;
+    return out;
+}
+
+@fragment
+fn image_fs_main(input : ImageVertexOut) -> @location(0) vec4<f32> {
+    return textureSample(image_texture, image_sampler, input.uv);
+}
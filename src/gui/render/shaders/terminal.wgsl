@@ -14,11 +14,17 @@ struct VertexIn {
     @location(3) rect_size   : vec2<f32>,
     @location(4) color       : vec4<f32>,
     @location(5) origin      : vec2<f32>,
+    // Corner radius in cell heights; 0 for a plain rectangle.
+    @location(6) radius      : f32,
 };
 
 struct VertexOut {
     @builtin(position) position : vec4<f32>,
     @location(0) color : vec4<f32>,
+    // Position within the rectangle, in pixels.
+    @location(1) local : vec2<f32>,
+    @location(2) @interpolate(flat) size : vec2<f32>,
+    @location(3) @interpolate(flat) radius : f32,
 };
 
 @vertex
@@ -36,17 +42,29 @@ fn vs_main(input : VertexIn) -> VertexOut {
     var out : VertexOut;
     out.position = vec4<f32>(ndc, 0.0, 1.0);
     out.color = input.color;
+    out.size = input.rect_size * uniforms.cell_size;
+    out.local = input.quad_pos * out.size;
+    out.radius = min(input.radius * uniforms.cell_size.y, min(out.size.x, out.size.y) * 0.5);
     return out;
 }
 
 @fragment
 fn fs_main(input : VertexOut) -> @location(0) vec4<f32> {
+    if (input.radius > 0.0) {
+        let half_size = input.size * 0.5;
+        let corner = abs(input.local - half_size) - (half_size - vec2<f32>(input.radius));
+        if (length(max(corner, vec2<f32>(0.0))) > input.radius) {
+            discard;
+        }
+    }
     return input.color;
 }
 
 struct TextUniforms {
     viewport : vec2<f32>,
     offset   : vec2<f32>,
+    // 0 = grayscale, 1 = gamma-correct grayscale, 2 = gamma-correct LCD.
+    mode     : u32,
 };
 
 @group(1) @binding(0)
@@ -64,6 +82,7 @@ struct TextVertexIn {
     @location(4) uv_max     : vec2<f32>,
     @location(5) color      : vec4<f32>,
     @location(6) bg_color   : vec4<f32>,
+    @location(7) colored    : f32,
 };
 
 struct TextVertexOut {
@@ -71,6 +90,7 @@ struct TextVertexOut {
     @location(0) uv : vec2<f32>,
     @location(1) color : vec4<f32>,
     @location(2) bg_color : vec4<f32>,
+    @location(3) @interpolate(flat) colored : f32,
 };
 
 @vertex
@@ -87,17 +107,56 @@ fn text_vs_main(input : TextVertexIn) -> TextVertexOut {
     out.uv = uv;
     out.color = input.color;
     out.bg_color = input.bg_color;
+    out.colored = input.colored;
     return out;
 }
 
-// Grayscale anti-aliased text rendering.
-// Subpixel coverage is averaged into a single alpha value, avoiding
-// color fringing on transparent backgrounds and non-RGB-stripe panels.
+fn srgb_to_linear(c : vec3<f32>) -> vec3<f32> {
+    let low = c / 12.92;
+    let high = pow((c + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, c <= vec3<f32>(0.04045));
+}
+
+// Blending happens in linear light, which thins dark strokes on light
+// backgrounds and thickens light ones on dark. Bending the coverage curve by
+// the text's luminance evens the two out.
+const TEXT_GAMMA : f32 = 1.8;
+
+fn gamma_coverage(cov : vec3<f32>, color : vec3<f32>) -> vec3<f32> {
+    let luma = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    let exponent = mix(1.0 / TEXT_GAMMA, 1.0, luma);
+    return pow(cov, vec3<f32>(exponent));
+}
+
+// Anti-aliased text. The atlas holds coverage for each of a pixel's three
+// subpixels. In the grayscale modes it is averaged into a single alpha value,
+// avoiding color fringing on transparent backgrounds and non-RGB-stripe
+// panels. The LCD mode keeps the three apart.
 @fragment
 fn text_fs_subpixel(input : TextVertexOut) -> @location(0) vec4<f32> {
     let cov = textureSample(text_atlas, text_sampler, input.uv);
-    let gray = (cov.r + cov.g + cov.b) / 3.0;
-    return vec4<f32>(input.color.rgb, gray * input.color.a);
+    // Color glyphs (emoji) store straight sRGB color rather than coverage.
+    if (input.colored > 0.5) {
+        return vec4<f32>(srgb_to_linear(cov.rgb), cov.a * input.color.a);
+    }
+    if (text_uniforms.mode == 0u) {
+        let gray = (cov.r + cov.g + cov.b) / 3.0;
+        return vec4<f32>(input.color.rgb, gray * input.color.a);
+    }
+    let adjusted = gamma_coverage(cov.rgb, input.color.rgb) * input.color.a;
+    if (text_uniforms.mode == 1u) {
+        let gray = (adjusted.r + adjusted.g + adjusted.b) / 3.0;
+        return vec4<f32>(input.color.rgb, gray);
+    }
+    // Per-channel blending with a single alpha: blend each channel against
+    // the cell background up to the strongest channel's coverage, and let
+    // alpha blending take the rest from what is already drawn.
+    let alpha = max(adjusted.r, max(adjusted.g, adjusted.b));
+    if (alpha <= 0.0) {
+        discard;
+    }
+    let rgb = (input.color.rgb * adjusted + input.bg_color.rgb * (alpha - adjusted)) / alpha;
+    return vec4<f32>(rgb, alpha);
 }
 
 struct CompositeVertexIn {
@@ -115,6 +174,16 @@ var composite_sampler : sampler;
 @group(0) @binding(1)
 var composite_texture : texture_2d<f32>;
 
+struct CompositeUniforms {
+    // Focused pane in texture coordinates: min.xy, max.xy.
+    focus_rect : vec4<f32>,
+    // x: brightness inside `focus_rect`, y: outside it.
+    brightness : vec4<f32>,
+};
+
+@group(0) @binding(2)
+var<uniform> composite_uniforms : CompositeUniforms;
+
 @vertex
 fn composite_vs_main(input : CompositeVertexIn) -> CompositeVertexOut {
     var out : CompositeVertexOut;
@@ -125,5 +194,9 @@ fn composite_vs_main(input : CompositeVertexIn) -> CompositeVertexOut {
 
 @fragment
 fn composite_fs_main(input : CompositeVertexOut) -> @location(0) vec4<f32> {
-    return textureSample(composite_texture, composite_sampler, input.uv);
+    let color = textureSample(composite_texture, composite_sampler, input.uv);
+    let rect = composite_uniforms.focus_rect;
+    let inside = all(input.uv >= rect.xy) && all(input.uv <= rect.zw);
+    let factor = select(composite_uniforms.brightness.y, composite_uniforms.brightness.x, inside);
+    return vec4<f32>(color.rgb * factor, color.a);
 }
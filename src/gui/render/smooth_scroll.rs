@@ -0,0 +1,88 @@
+//! Smooth scrolling: after a pane's view moves through its scrollback, the
+//! new rows are drawn offset by the distance moved and eased back into place.
+
+use std::time::{Duration, Instant};
+
+/// How long the view takes to settle after a scroll.
+pub(super) const GLIDE: Duration = Duration::from_millis(140);
+
+/// One pane's scroll position and the glide it is in, if any.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ScrollMotion {
+    /// The line at the top of the view, counted from the oldest in history.
+    top: i64,
+    display_offset: usize,
+    /// Rows the view was drawn off by when the glide started; negative is up.
+    rows: f32,
+    started: Instant,
+}
+
+impl ScrollMotion {
+    pub(super) fn at(top: i64, display_offset: usize, now: Instant) -> Self {
+        Self {
+            top,
+            display_offset,
+            rows: 0.0,
+            started: now,
+        }
+    }
+
+    /// Follows the pane to where it is scrolled now. Only moves through the
+    /// scrollback glide: output arriving at the bottom moves the top line
+    /// without touching `display_offset`, and output arriving while scrolled
+    /// back moves `display_offset` without moving the top line. Glides
+    /// start no more than a screen of `lines` away.
+    pub(super) fn follow(&mut self, top: i64, display_offset: usize, lines: usize, now: Instant) {
+        if display_offset != self.display_offset {
+            let rows = (top - self.top) as f32 + self.shift(now);
+            self.rows = rows.clamp(-(lines as f32), lines as f32);
+            self.started = now;
+        }
+        self.top = top;
+        self.display_offset = display_offset;
+    }
+
+    fn progress(&self, now: Instant) -> f32 {
+        (now.saturating_duration_since(self.started).as_secs_f32() / GLIDE.as_secs_f32()).min(1.0)
+    }
+
+    /// Rows to draw the view off by at `now`, easing out to zero.
+    pub(super) fn shift(&self, now: Instant) -> f32 {
+        self.rows * (1.0 - self.progress(now)).powi(3)
+    }
+
+    pub(super) fn is_moving(&self, now: Instant) -> bool {
+        self.rows != 0.0 && self.progress(now) < 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_scrolling_through_history_glides() {
+        let start = Instant::now();
+        let mut motion = ScrollMotion::at(100, 0, start);
+
+        // New output at the bottom.
+        motion.follow(101, 0, 24, start);
+        assert!(!motion.is_moving(start));
+
+        // Three lines back: the new rows start three rows up.
+        motion.follow(98, 3, 24, start);
+        assert_eq!(motion.shift(start), -3.0);
+        let mid = motion.shift(start + GLIDE / 2);
+        assert!(mid > -3.0 && mid < 0.0);
+        assert!(!motion.is_moving(start + GLIDE));
+
+        // Output while scrolled back keeps the same top line.
+        let later = start + GLIDE;
+        motion.follow(98, 4, 24, later);
+        assert!(!motion.is_moving(later));
+
+        // Jumping to the bottom starts a screen away at most.
+        motion.follow(10_000, 0, 24, later);
+        assert_eq!(motion.shift(later), 24.0);
+    }
+}
@@ -0,0 +1,67 @@
+//! Surface options iced's wgpu backend only reads from the environment when
+//! it creates the window, so they are exported before the app starts.
+//!
+//! When the GPU cannot be set up at all, the app relaunches itself once on
+//! the OpenGL backend, which includes software rasterizers, and tells the
+//! user why.
+
+use crate::config::RendererConfig;
+use std::process::Command;
+
+const PRESENT_MODE_VAR: &str = "ICED_PRESENT_MODE";
+const BACKEND_VAR: &str = "WGPU_BACKEND";
+const POWER_PREFERENCE_VAR: &str = "WGPU_POWER_PREF";
+/// Set on the relaunched process, holding the error that caused it.
+const FALLBACK_VAR: &str = "RABBITTY_GPU_FALLBACK";
+
+/// Exports the configured surface options. Variables already set in the
+/// environment win.
+pub fn configure(config: &RendererConfig) {
+    let options = [
+        (PRESENT_MODE_VAR, Some(config.present_mode.env_value())),
+        (BACKEND_VAR, config.backend.env_value()),
+        (POWER_PREFERENCE_VAR, config.adapter.env_value()),
+    ];
+    for (var, value) in options {
+        if let Some(value) = value
+            && std::env::var_os(var).is_none()
+        {
+            // SAFETY: called from `main` before any other thread is started.
+            unsafe { std::env::set_var(var, value) };
+        }
+    }
+}
+
+/// Why this process runs on the fallback backend, if it does.
+pub fn fallback_reason() -> Option<String> {
+    std::env::var(FALLBACK_VAR)
+        .ok()
+        .filter(|reason| !reason.is_empty())
+}
+
+/// Starts this program again on the OpenGL backend and exits with its
+/// status. A process that is itself the fallback gives up instead, so a
+/// machine with no working backend fails once rather than looping.
+pub fn relaunch_with_fallback(error: &str) -> ! {
+    if fallback_reason().is_some() {
+        eprintln!("Graphics setup failed on the fallback backend too: {error}");
+        std::process::exit(1);
+    }
+    eprintln!("Graphics setup failed, retrying with OpenGL: {error}");
+
+    let status = std::env::current_exe().and_then(|exe| {
+        Command::new(exe)
+            .args(std::env::args_os().skip(1))
+            .env(BACKEND_VAR, "gl")
+            .env(POWER_PREFERENCE_VAR, "low")
+            .env(FALLBACK_VAR, error)
+            .status()
+    });
+    match status {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(err) => {
+            eprintln!("Failed to relaunch: {err}");
+            std::process::exit(1);
+        }
+    }
+}
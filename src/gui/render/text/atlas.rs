@@ -1,4 +1,5 @@
 use iced::wgpu;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 pub(super) const ATLAS_INITIAL_SIZE: u32 = 2048;
 pub(super) const ATLAS_MAX_SIZE: u32 = 4096;
@@ -51,8 +52,13 @@ impl AtlasPacker {
     }
 }
 
+/// Source of [`GlyphAtlas::id`].
+static NEXT_ATLAS_ID: AtomicU64 = AtomicU64::new(1);
+
 #[derive(Debug)]
 pub(super) struct GlyphAtlas {
+    /// Tells pipelines their bind group points at a replaced atlas.
+    pub(super) id: u64,
     pub(super) texture: wgpu::Texture,
     pub(super) view: wgpu::TextureView,
     pub(super) size: u32,
@@ -78,6 +84,7 @@ impl GlyphAtlas {
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         Self {
+            id: NEXT_ATLAS_ID.fetch_add(1, Ordering::Relaxed),
             texture,
             view,
             size,
@@ -0,0 +1,274 @@
+//! Line-drawing characters drawn from the cell's own size instead of the
+//! font, so lines meet across cells whatever the line height or letter
+//! spacing. Covers what DEC special graphics (`ESC ( 0`) maps to, the other
+//! light, heavy and double lines, and the shades and half blocks.
+
+use super::rasterize::{COPY_BYTES_PER_ROW_ALIGNMENT, RasterGlyph, align_to};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Weight {
+    None,
+    Light,
+    Heavy,
+    Double,
+}
+
+use Weight::{Double as D, Heavy as H, Light as L, None as N};
+
+/// Weights of the arms toward the top, right, bottom and left edges.
+fn arms(ch: char) -> Option<[Weight; 4]> {
+    Some(match ch {
+        '─' => [N, L, N, L],
+        '━' => [N, H, N, H],
+        '│' => [L, N, L, N],
+        '┃' => [H, N, H, N],
+        '┌' => [N, L, L, N],
+        '┏' => [N, H, H, N],
+        '┐' => [N, N, L, L],
+        '┓' => [N, N, H, H],
+        '└' => [L, L, N, N],
+        '┗' => [H, H, N, N],
+        '┘' => [L, N, N, L],
+        '┛' => [H, N, N, H],
+        '├' => [L, L, L, N],
+        '┣' => [H, H, H, N],
+        '┤' => [L, N, L, L],
+        '┫' => [H, N, H, H],
+        '┬' => [N, L, L, L],
+        '┳' => [N, H, H, H],
+        '┴' => [L, L, N, L],
+        '┻' => [H, H, N, H],
+        '┼' => [L, L, L, L],
+        '╋' => [H, H, H, H],
+        '═' => [N, D, N, D],
+        '║' => [D, N, D, N],
+        '╔' => [N, D, D, N],
+        '╗' => [N, N, D, D],
+        '╚' => [D, D, N, N],
+        '╝' => [D, N, N, D],
+        '╠' => [D, D, D, N],
+        '╣' => [D, N, D, D],
+        '╦' => [N, D, D, D],
+        '╩' => [D, D, N, D],
+        '╬' => [D, D, D, D],
+        '╴' => [N, N, N, L],
+        '╵' => [L, N, N, N],
+        '╶' => [N, L, N, N],
+        '╷' => [N, N, L, N],
+        '╸' => [N, N, N, H],
+        '╹' => [H, N, N, N],
+        '╺' => [N, H, N, N],
+        '╻' => [N, N, H, N],
+        _ => return None,
+    })
+}
+
+/// Whether `ch` is drawn here rather than taken from the font.
+pub(super) fn covers(ch: char) -> bool {
+    arms(ch).is_some() || shape(ch).is_some()
+}
+
+/// Filled rectangles, as fractions of the cell, and their coverage.
+fn shape(ch: char) -> Option<(&'static [[f32; 4]], u8)> {
+    const FULL: &[[f32; 4]] = &[[0.0, 0.0, 1.0, 1.0]];
+    Some(match ch {
+        '█' => (FULL, 255),
+        '▓' => (FULL, 192),
+        '▒' => (FULL, 128),
+        '░' => (FULL, 64),
+        '▀' => (&[[0.0, 0.0, 1.0, 0.5]], 255),
+        '▄' => (&[[0.0, 0.5, 1.0, 1.0]], 255),
+        '▌' => (&[[0.0, 0.0, 0.5, 1.0]], 255),
+        '▐' => (&[[0.5, 0.0, 1.0, 1.0]], 255),
+        // Scan lines 1, 3, 7 and 9 of DEC special graphics.
+        '⎺' => (&[[0.0, 0.0, 1.0, 0.1]], 255),
+        '⎻' => (&[[0.0, 0.25, 1.0, 0.35]], 255),
+        '⎼' => (&[[0.0, 0.65, 1.0, 0.75]], 255),
+        '⎽' => (&[[0.0, 0.9, 1.0, 1.0]], 255),
+        _ => return None,
+    })
+}
+
+/// Coverage of `ch` over a `width × height` cell, one byte per pixel.
+fn coverage(ch: char, width: u32, height: u32) -> Option<Vec<u8>> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let mut canvas = Canvas {
+        width,
+        height,
+        pixels: vec![0; (width * height) as usize],
+    };
+    if let Some((rects, alpha)) = shape(ch) {
+        for [x0, y0, x1, y1] in rects {
+            let fx = |f: f32| (f * width as f32).round() as u32;
+            let fy = |f: f32| (f * height as f32).round() as u32;
+            // Thin scan lines still get a pixel.
+            let (top, bottom) = (fy(*y0), fy(*y1).max(fy(*y0) + 1));
+            canvas.fill(fx(*x0), top, fx(*x1), bottom, alpha);
+        }
+        return Some(canvas.pixels);
+    }
+
+    let arms = arms(ch)?;
+    let light = (height as f32 / 16.0).round().max(1.0) as u32;
+    let thickness = |weight| match weight {
+        Weight::Heavy => light * 2,
+        _ => light,
+    };
+    // Where a light line starts across each axis, so it is centered.
+    let cx = width.saturating_sub(light) / 2;
+    let cy = height.saturating_sub(light) / 2;
+    let widest = arms.iter().map(|&w| thickness(w)).max().unwrap_or(light);
+    let [up, right, down, left] = arms;
+
+    for (index, weight) in arms.into_iter().enumerate() {
+        let vertical = index % 2 == 0;
+        let toward_end = index == 1 || index == 2;
+        let (c, length) = if vertical { (cx, height) } else { (cy, width) };
+        let along_c = if vertical { cy } else { cx };
+        match weight {
+            Weight::None => {}
+            Weight::Light | Weight::Heavy => {
+                let t = thickness(weight);
+                let across = (c + light / 2).saturating_sub(t / 2);
+                // Reach across the band the widest line takes up in the
+                // middle, so corners and crossings close.
+                let band = (along_c + light / 2).saturating_sub(widest / 2);
+                let (start, end) = if toward_end {
+                    (band, length)
+                } else {
+                    (0, (band + widest).min(length))
+                };
+                canvas.bar(vertical, across, across + t, start, end);
+            }
+            Weight::Double => {
+                // The arms across this one, on the side before and after.
+                let (before, after) = if vertical { (left, right) } else { (up, down) };
+                for (side, offset) in [(before, -1i32), (after, 1)] {
+                    let across = (c as i32 + offset * light as i32).max(0) as u32;
+                    // A line on the side of a crossing arm stops at its inner
+                    // line; the other one runs on to its outer line.
+                    let k = if side != Weight::None { 1 } else { -1 };
+                    let (start, end) = if toward_end {
+                        ((along_c as i32 + k * light as i32).max(0) as u32, length)
+                    } else {
+                        (0, (along_c as i32 + light as i32 - k * light as i32) as u32)
+                    };
+                    canvas.bar(vertical, across, across + light, start, end.min(length));
+                }
+            }
+        }
+    }
+    Some(canvas.pixels)
+}
+
+/// Rasterizes `ch` to fill a cell of `cell_size` pixels, in the atlas's
+/// format. The bearing is zero: the glyph sits at the cell's corner.
+pub(super) fn rasterize(ch: char, cell_size: [f32; 2]) -> Option<RasterGlyph> {
+    let width = cell_size[0].round() as u32;
+    let height = cell_size[1].round() as u32;
+    let pixels = coverage(ch, width, height)?;
+    let row_bytes = align_to(width * 4, COPY_BYTES_PER_ROW_ALIGNMENT);
+    let mut rgba = vec![0u8; (row_bytes * height) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            let value = pixels[(y * width + x) as usize];
+            let index = (y * row_bytes + x * 4) as usize;
+            rgba[index..index + 4].fill(value);
+        }
+    }
+    Some(RasterGlyph {
+        rgba,
+        width,
+        height,
+        bearing: [0.0, 0.0],
+    })
+}
+
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn fill(&mut self, x0: u32, y0: u32, x1: u32, y1: u32, alpha: u8) {
+        for y in y0..y1.min(self.height) {
+            for x in x0..x1.min(self.width) {
+                let pixel = &mut self.pixels[(y * self.width + x) as usize];
+                *pixel = (*pixel).max(alpha);
+            }
+        }
+    }
+
+    /// A line `across..across_end` wide running `start..end` along the
+    /// vertical or horizontal axis.
+    fn bar(&mut self, vertical: bool, across: u32, across_end: u32, start: u32, end: u32) {
+        if vertical {
+            self.fill(across, start, across_end, end, 255);
+        } else {
+            self.fill(start, across, end, across_end, 255);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The cell as rows of `#` and `.`.
+    fn picture(ch: char, width: u32, height: u32) -> Vec<String> {
+        let pixels = coverage(ch, width, height).unwrap();
+        pixels
+            .chunks(width as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|&p| if p == 255 { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lines_reach_the_cell_edges_so_neighbours_join() {
+        assert_eq!(
+            picture('┌', 5, 5),
+            [".....", ".....", "..###", "..#..", "..#.."]
+        );
+        assert_eq!(
+            picture('┼', 5, 5),
+            ["..#..", "..#..", "#####", "..#..", "..#.."]
+        );
+        // A tall cell still has its horizontal line in the middle and its
+        // vertical line from top to bottom.
+        let tall = picture('┤', 5, 9);
+        assert!(tall.iter().all(|row| row.as_bytes()[2] == b'#'));
+        assert_eq!(tall[4], "###..");
+    }
+
+    #[test]
+    fn double_corners_have_inner_and_outer_lines() {
+        assert_eq!(
+            picture('╔', 7, 7),
+            [
+                ".......", ".......", "..#####", "..#....", "..#.###", "..#.#..", "..#.#..",
+            ]
+        );
+        assert_eq!(
+            picture('╬', 7, 7),
+            [
+                "..#.#..", "..#.#..", "###.###", ".......", "###.###", "..#.#..", "..#.#..",
+            ]
+        );
+    }
+
+    #[test]
+    fn dec_special_graphics_output_is_covered() {
+        // `ESC ( 0` maps `jklmnqtuvwx` and `a`, `o`, `p`, `r`, `s` to these.
+        for ch in "┘┐┌└┼─├┤┴┬│▒⎺⎻⎼⎽".chars() {
+            assert!(covers(ch), "{ch}");
+        }
+        assert!(!covers('a'));
+    }
+}
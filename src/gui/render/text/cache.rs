@@ -0,0 +1,472 @@
+//! Glyph atlases shared by every text pipeline on a device. Tabs and windows
+//! showing the same font at the same size and cell size rasterize and upload
+//! each glyph once; an atlas is freed when the last pipeline using it moves
+//! to another size or goes away.
+
+use super::atlas::{ATLAS_INITIAL_SIZE, ATLAS_MAX_SIZE, ATLAS_PADDING, GlyphAtlas};
+use super::boxdraw;
+use super::color::rasterize_color_glyph;
+use super::rasterize::{
+    COPY_BYTES_PER_ROW_ALIGNMENT, RasterGlyph, Synthesis, align_to, load_cjk_fallback,
+    load_emoji_fallback, rasterize_outlines,
+};
+use super::shape::{ShapedGlyph, face_index, shape_cluster};
+use super::{EMOJI_PRESENTATION, EMPTY_GLYPH, GlyphInfo, GlyphKey, GlyphStyle};
+use crate::terminal::font::ColorFont;
+use ab_glyph::{Font, FontArc, GlyphId, OutlinedGlyph, PxScale, ScaleFont, point};
+use iced::wgpu;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError, Weak};
+
+/// Fonts for characters the configured one lacks, loaded once per process.
+struct Fallbacks {
+    cjk: Option<FontArc>,
+    emoji: Option<ColorFont>,
+}
+
+static FALLBACKS: LazyLock<Fallbacks> = LazyLock::new(|| Fallbacks {
+    cjk: load_cjk_fallback(),
+    emoji: load_emoji_fallback(),
+});
+
+/// Horizontal shift per pixel of height for synthesized italics, about 12°.
+const SYNTHETIC_SLANT: f32 = 0.21;
+
+static CACHES: LazyLock<Mutex<HashMap<CacheKey, Weak<Mutex<GlyphCache>>>>> =
+    LazyLock::new(Default::default);
+
+/// What a cached glyph depends on besides the character itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct CacheKey {
+    device: wgpu::Device,
+    /// The font selection as configured; `None` for the built-in font.
+    font: Option<String>,
+    /// Font size in device pixels, so the display scale is part of it, in
+    /// tenths: sizes closer than that draw the same.
+    px_tenths: u32,
+    /// Cell size in whole pixels, which box-drawing glyphs fill.
+    cell: [u32; 2],
+}
+
+impl CacheKey {
+    pub(super) fn new(
+        device: &wgpu::Device,
+        font: Option<&str>,
+        font_px: f32,
+        cell: [u32; 2],
+    ) -> Self {
+        Self {
+            device: device.clone(),
+            font: font.map(str::to_string),
+            px_tenths: (font_px * 10.0).round().max(10.0) as u32,
+            cell,
+        }
+    }
+
+    fn font_px(&self) -> f32 {
+        self.px_tenths as f32 / 10.0
+    }
+}
+
+/// The atlas for `key`, shared with every other pipeline using the same one.
+/// `font` is only read when no pipeline holds it yet.
+pub(super) fn shared(key: CacheKey, font: &FontArc) -> Arc<Mutex<GlyphCache>> {
+    let mut caches = CACHES.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(cache) = caches.get(&key).and_then(Weak::upgrade) {
+        return cache;
+    }
+    caches.retain(|_, cache| cache.strong_count() > 0);
+    let cache = Arc::new(Mutex::new(GlyphCache::new(&key, font.clone())));
+    caches.insert(key, Arc::downgrade(&cache));
+    cache
+}
+
+pub(super) fn lock(cache: &Mutex<GlyphCache>) -> MutexGuard<'_, GlyphCache> {
+    cache.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// How a font lays out in a cell at one size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Metrics {
+    pub(super) line_height: f32,
+    /// Top of the tallest printable ASCII glyph, relative to the ascent line.
+    pub(super) line_min_y: f32,
+    /// Widest printable ASCII advance.
+    pub(super) cell_advance: f32,
+}
+
+impl Metrics {
+    pub(super) fn measure(font: &FontArc, font_px: f32) -> Self {
+        let scale = PxScale::from(font_px);
+        let scaled = font.as_scaled(scale);
+        let ascent = scaled.ascent();
+        let mut bounds: Option<(f32, f32)> = None;
+        for code in 32u8..=126u8 {
+            let glyph = font
+                .glyph_id(code as char)
+                .with_scale_and_position(scale, point(0.0, ascent));
+            if let Some(outlined) = font.outline_glyph(glyph) {
+                let px = outlined.px_bounds();
+                bounds = Some(match bounds {
+                    Some((min_y, max_y)) => (min_y.min(px.min.y), max_y.max(px.max.y)),
+                    None => (px.min.y, px.max.y),
+                });
+            }
+        }
+        let (line_min_y, line_height) = match bounds {
+            Some((min_y, max_y)) => (min_y, (max_y - min_y).max(1.0)),
+            None => (0.0, scaled.height()),
+        };
+        let advance = (32u8..=126u8)
+            .map(|code| scaled.h_advance(font.glyph_id(code as char)))
+            .fold(0.0, f32::max);
+        Self {
+            line_height,
+            line_min_y,
+            cell_advance: if advance > 0.0 {
+                advance
+            } else {
+                (line_height * 0.6).max(1.0)
+            },
+        }
+    }
+}
+
+/// One font at one size: its atlas and where each glyph sits in it.
+#[derive(Debug)]
+pub(super) struct GlyphCache {
+    pub(super) atlas: GlyphAtlas,
+    pub(super) metrics: Metrics,
+    font: FontArc,
+    /// Which face of the font's data it is, for the shaper.
+    face_index: u32,
+    scale: PxScale,
+    glyphs: HashMap<GlyphKey, GlyphInfo>,
+    raster_buf: Vec<u8>,
+    filter_buf: Vec<u8>,
+}
+
+impl GlyphCache {
+    fn new(key: &CacheKey, font: FontArc) -> Self {
+        let font_px = key.font_px();
+        Self {
+            atlas: GlyphAtlas::new(&key.device, ATLAS_INITIAL_SIZE),
+            metrics: Metrics::measure(&font, font_px),
+            face_index: face_index(&font),
+            font,
+            scale: PxScale::from(font_px),
+            glyphs: HashMap::new(),
+            raster_buf: Vec::new(),
+            filter_buf: Vec::new(),
+        }
+    }
+
+    fn allocate_in_atlas(
+        &mut self,
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+    ) -> Option<(u32, u32)> {
+        let padded_width = width.saturating_add(ATLAS_PADDING * 2);
+        let padded_height = height.saturating_add(ATLAS_PADDING * 2);
+
+        if let Some(pos) = self.atlas.packer.allocate(padded_width, padded_height) {
+            return Some(pos);
+        }
+
+        // Pipelines still bound to the old atlas keep it alive until they
+        // next draw, so what they already placed stays valid.
+        if self.atlas.size < ATLAS_MAX_SIZE {
+            let new_size = (self.atlas.size * 2).min(ATLAS_MAX_SIZE);
+            self.atlas = GlyphAtlas::new(device, new_size);
+            self.glyphs.clear();
+            return self.atlas.packer.allocate(padded_width, padded_height);
+        }
+
+        None
+    }
+
+    pub(super) fn get_or_insert_glyph(
+        &mut self,
+        ch: char,
+        wide: bool,
+        style: GlyphStyle,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Option<GlyphInfo> {
+        let key = GlyphKey::Char(ch, style);
+        if let Some(info) = self.glyphs.get(&key) {
+            return Some(*info);
+        }
+        if self.font.glyph_id(ch).0 == 0
+            && let Some(info) = self.insert_color_glyph(key.clone(), ch, wide, device, queue)
+        {
+            return Some(info);
+        }
+        let outlines: Vec<_> = self.outline_char(ch, 0.0).into_iter().collect();
+        self.insert_glyph(key, &outlines, style, device, queue)
+    }
+
+    pub(super) fn get_or_insert_box(
+        &mut self,
+        ch: char,
+        cell_size: [f32; 2],
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Option<GlyphInfo> {
+        let key = GlyphKey::Box(ch);
+        if let Some(info) = self.glyphs.get(&key) {
+            return Some(*info);
+        }
+        let raster = boxdraw::rasterize(ch, cell_size)?;
+        self.upload_glyph(key, raster, false, device, queue)
+    }
+
+    /// Shapes a multi-codepoint cluster and draws it into one glyph.
+    ///
+    /// Emoji clusters are shaped with the emoji font, which turns a modifier
+    /// or ZWJ sequence it knows into a single color glyph; one it doesn't
+    /// know shows as its first emoji. Anything else is shaped with the
+    /// configured font, so marks sit on the font's anchors and ligatures and
+    /// reordering apply, and the shaped glyphs are outlined at their offsets.
+    pub(super) fn get_or_insert_cluster(
+        &mut self,
+        cluster: &Arc<str>,
+        wide: bool,
+        style: GlyphStyle,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Option<GlyphInfo> {
+        let key = GlyphKey::Cluster(Arc::clone(cluster), style);
+        if let Some(info) = self.glyphs.get(&key) {
+            return Some(*info);
+        }
+        let base = cluster.chars().next()?;
+        // An emoji presentation selector asks for the color glyph even when
+        // the configured font has a text one.
+        let wants_color = cluster.contains(EMOJI_PRESENTATION) || self.font.glyph_id(base).0 == 0;
+        if wants_color
+            && let Some(info) = self.insert_color_cluster(key.clone(), cluster, wide, device, queue)
+        {
+            return Some(info);
+        }
+        let outlines = match shape_cluster(self.font.font_data(), self.face_index, cluster) {
+            Some(shaped) => self.outline_shaped(&shaped),
+            None => self.outline_stacked(cluster),
+        };
+        self.insert_glyph(key, &outlines, style, device, queue)
+    }
+
+    /// Outlines a cluster shaped with the configured font, each glyph at its
+    /// shaped offset from the cell origin.
+    fn outline_shaped(&self, shaped: &[ShapedGlyph]) -> Vec<OutlinedGlyph> {
+        let scale = self.subpixel_scale();
+        let scaled = self.font.as_scaled(scale);
+        let ascent = self.font.as_scaled(self.scale).ascent();
+        shaped
+            .iter()
+            .filter_map(|glyph| {
+                let position = point(
+                    glyph.offset[0] * scaled.h_scale_factor(),
+                    ascent + glyph.offset[1] * scaled.v_scale_factor(),
+                );
+                self.font
+                    .outline_glyph(glyph.id.with_scale_and_position(scale, position))
+            })
+            .collect()
+    }
+
+    /// For clusters the configured font can't shape because it lacks some
+    /// of the characters: each comes from whichever font has it, a
+    /// zero-advance mark hung back over the base from the pen position after
+    /// it and any other mark drawn over the base from the cell origin.
+    fn outline_stacked(&self, cluster: &str) -> Vec<OutlinedGlyph> {
+        let mut chars = cluster.chars();
+        let Some(base) = chars.next() else {
+            return Vec::new();
+        };
+        let pen = self.subpixel_advance(base);
+        let mut outlines: Vec<_> = self.outline_char(base, 0.0).into_iter().collect();
+        for mark in chars {
+            let x = if self.subpixel_advance(mark) == 0.0 {
+                pen
+            } else {
+                0.0
+            };
+            outlines.extend(self.outline_char(mark, x));
+        }
+        outlines
+    }
+
+    /// The font that has a glyph for `ch`, the configured one first.
+    fn resolve_glyph(&self, ch: char) -> Option<(&FontArc, GlyphId)> {
+        let glyph_id = self.font.glyph_id(ch);
+        let (font, glyph_id) = match &FALLBACKS.cjk {
+            Some(fallback) if glyph_id.0 == 0 => (fallback, fallback.glyph_id(ch)),
+            _ => (&self.font, glyph_id),
+        };
+        (glyph_id.0 != 0).then_some((font, glyph_id))
+    }
+
+    fn subpixel_scale(&self) -> PxScale {
+        PxScale {
+            x: self.scale.x * 3.0,
+            y: self.scale.y,
+        }
+    }
+
+    fn subpixel_advance(&self, ch: char) -> f32 {
+        self.resolve_glyph(ch).map_or(0.0, |(font, glyph_id)| {
+            font.as_scaled(self.subpixel_scale()).h_advance(glyph_id)
+        })
+    }
+
+    /// Outlines `ch` at horizontal pen position `x`, in subpixel units.
+    fn outline_char(&self, ch: char, x: f32) -> Option<OutlinedGlyph> {
+        let (font, glyph_id) = self.resolve_glyph(ch)?;
+        let ascent = font.as_scaled(self.scale).ascent();
+        let glyph = glyph_id.with_scale_and_position(self.subpixel_scale(), point(x, ascent));
+        font.outline_glyph(glyph)
+    }
+
+    /// Rasterizes `ch` from the emoji font, fitted to the cell (or both
+    /// cells of a wide one) and centered in the line.
+    fn insert_color_glyph(
+        &mut self,
+        key: GlyphKey,
+        ch: char,
+        wide: bool,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Option<GlyphInfo> {
+        let emoji = FALLBACKS.emoji.as_ref()?;
+        let raster = rasterize_color_glyph(emoji, emoji.font.glyph_id(ch), self.color_fit(wide))?;
+        self.upload_color_glyph(key, raster, device, queue)
+    }
+
+    /// Shapes `cluster` with the emoji font and rasterizes the first glyph
+    /// of the run that has color, the same way as [`Self::insert_color_glyph`].
+    fn insert_color_cluster(
+        &mut self,
+        key: GlyphKey,
+        cluster: &str,
+        wide: bool,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Option<GlyphInfo> {
+        let emoji = FALLBACKS.emoji.as_ref()?;
+        let shaped = shape_cluster(emoji.font.as_slice(), emoji.index, cluster)?;
+        let fit = self.color_fit(wide);
+        let raster = shaped
+            .iter()
+            .find_map(|glyph| rasterize_color_glyph(emoji, glyph.id, fit))?;
+        self.upload_color_glyph(key, raster, device, queue)
+    }
+
+    fn color_fit(&self, wide: bool) -> [f32; 2] {
+        let span = if wide { 2.0 } else { 1.0 };
+        [self.metrics.cell_advance * span, self.metrics.line_height]
+    }
+
+    fn upload_color_glyph(
+        &mut self,
+        key: GlyphKey,
+        mut raster: RasterGlyph,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Option<GlyphInfo> {
+        raster.bearing[1] += self.metrics.line_min_y;
+        self.upload_glyph(key, raster, true, device, queue)
+    }
+
+    /// Faux bold smears strokes about a pixel per 14 of font size; faux
+    /// italic shears about the baseline the outlines were placed on.
+    fn synthesis(&self, style: GlyphStyle) -> Synthesis {
+        Synthesis {
+            embolden: if style.bold {
+                (self.scale.y * 3.0 / 14.0).round().max(2.0) as u32
+            } else {
+                0
+            },
+            slant: if style.italic { SYNTHETIC_SLANT } else { 0.0 },
+            baseline: self.font.as_scaled(self.scale).ascent(),
+        }
+    }
+
+    fn insert_glyph(
+        &mut self,
+        key: GlyphKey,
+        outlines: &[OutlinedGlyph],
+        style: GlyphStyle,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Option<GlyphInfo> {
+        let synthesis = self.synthesis(style);
+        let Some(raster) = rasterize_outlines(
+            outlines,
+            synthesis,
+            &mut self.raster_buf,
+            &mut self.filter_buf,
+        ) else {
+            self.glyphs.insert(key, EMPTY_GLYPH);
+            return Some(EMPTY_GLYPH);
+        };
+        self.upload_glyph(key, raster, false, device, queue)
+    }
+
+    fn upload_glyph(
+        &mut self,
+        key: GlyphKey,
+        raster: RasterGlyph,
+        colored: bool,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Option<GlyphInfo> {
+        let pos = self.allocate_in_atlas(device, raster.width, raster.height)?;
+        let origin_x = pos.0 + ATLAS_PADDING;
+        let origin_y = pos.1 + ATLAS_PADDING;
+        let padded_bytes_per_row = align_to(raster.width * 4, COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.atlas.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: origin_x,
+                    y: origin_y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &raster.rgba,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(raster.height),
+            },
+            wgpu::Extent3d {
+                width: raster.width,
+                height: raster.height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        let atlas_size = self.atlas.size as f32;
+        let uv_min = [origin_x as f32 / atlas_size, origin_y as f32 / atlas_size];
+        let uv_max = [
+            (origin_x + raster.width) as f32 / atlas_size,
+            (origin_y + raster.height) as f32 / atlas_size,
+        ];
+
+        let info = GlyphInfo {
+            uv_min,
+            uv_max,
+            size: [raster.width as f32, raster.height as f32],
+            bearing: raster.bearing,
+            colored,
+        };
+
+        self.glyphs.insert(key, info);
+        Some(info)
+    }
+}
@@ -0,0 +1,238 @@
+//! Color glyphs from emoji fonts: embedded bitmaps (CBDT, sbix) and layered
+//! COLR glyphs. They go into the same RGBA atlas as text, but hold straight
+//! sRGB color instead of subpixel coverage.
+
+use super::rasterize::{COPY_BYTES_PER_ROW_ALIGNMENT, RasterGlyph, align_to};
+use crate::terminal::font::ColorFont;
+use ab_glyph::{Font, GlyphId, GlyphImageFormat, OutlinedGlyph, Rect, point};
+use image::RgbaImage;
+use image::imageops::{self, FilterType};
+use ttf_parser::colr::{ClipBox, CompositeMode, Paint, Painter};
+use ttf_parser::{RgbaColor, Transform};
+
+/// What COLR layers drawn in "the text color" get. The glyph is cached once
+/// for every text color, so this is a fixed light gray that reads on the
+/// default dark theme.
+const FOREGROUND: RgbaColor = RgbaColor {
+    red: 0xd0,
+    green: 0xd0,
+    blue: 0xd0,
+    alpha: 0xff,
+};
+
+/// Rasterizes glyph `glyph_id` of `font` in color, scaled to fit a box of
+/// `fit` pixels and centered in it. The bearing is relative to the box's
+/// top-left corner. `None` when it isn't a color glyph.
+pub(super) fn rasterize_color_glyph(
+    font: &ColorFont,
+    glyph_id: GlyphId,
+    fit: [f32; 2],
+) -> Option<RasterGlyph> {
+    if glyph_id.0 == 0 || fit[0] < 1.0 || fit[1] < 1.0 {
+        return None;
+    }
+    let image = layered_glyph(font, glyph_id, fit).or_else(|| bitmap_glyph(font, glyph_id, fit))?;
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let row_bytes = (width * 4) as usize;
+    let padded_bytes_per_row = align_to(width * 4, COPY_BYTES_PER_ROW_ALIGNMENT) as usize;
+    let mut rgba = vec![0u8; padded_bytes_per_row * height as usize];
+    for (row, pixels) in image.as_raw().chunks_exact(row_bytes).enumerate() {
+        let start = row * padded_bytes_per_row;
+        rgba[start..start + row_bytes].copy_from_slice(pixels);
+    }
+
+    Some(RasterGlyph {
+        rgba,
+        width,
+        height,
+        bearing: [
+            ((fit[0] - width as f32) * 0.5).floor(),
+            ((fit[1] - height as f32) * 0.5).floor(),
+        ],
+    })
+}
+
+/// The strike closest to the box height, decoded and scaled to fit it.
+fn bitmap_glyph(font: &ColorFont, glyph_id: GlyphId, fit: [f32; 2]) -> Option<RgbaImage> {
+    let strike = font
+        .font
+        .glyph_raster_image2(glyph_id, fit[1].ceil() as u16)?;
+    let image = match strike.format {
+        GlyphImageFormat::Png => {
+            image::load_from_memory_with_format(strike.data, image::ImageFormat::Png)
+                .ok()?
+                .into_rgba8()
+        }
+        GlyphImageFormat::BitmapPremulBgra32 => {
+            let mut pixels = Vec::with_capacity(strike.data.len());
+            for bgra in strike.data.chunks_exact(4) {
+                let alpha = bgra[3];
+                let unpremultiply = |c: u8| match alpha {
+                    0 => 0,
+                    _ => ((c as u32 * 255 + alpha as u32 / 2) / alpha as u32).min(255) as u8,
+                };
+                pixels.extend([
+                    unpremultiply(bgra[2]),
+                    unpremultiply(bgra[1]),
+                    unpremultiply(bgra[0]),
+                    alpha,
+                ]);
+            }
+            RgbaImage::from_raw(strike.width.into(), strike.height.into(), pixels)?
+        }
+        _ => return None,
+    };
+
+    let scale = (fit[0] / image.width() as f32).min(fit[1] / image.height() as f32);
+    let width = ((image.width() as f32 * scale).round() as u32).max(1);
+    let height = ((image.height() as f32 * scale).round() as u32).max(1);
+    Some(imageops::resize(
+        &image,
+        width,
+        height,
+        FilterType::Triangle,
+    ))
+}
+
+/// Draws a COLR glyph's layers, each a glyph outline filled with one color,
+/// at the size that fits the box. Transforms and blend modes are not applied,
+/// and gradients are filled with the average of their stops, which keeps
+/// COLRv1 emoji recognizable if not exact.
+fn layered_glyph(font: &ColorFont, glyph_id: GlyphId, fit: [f32; 2]) -> Option<RgbaImage> {
+    let face = ttf_parser::Face::parse(font.font.as_slice(), font.index).ok()?;
+    let ttf_id = ttf_parser::GlyphId(glyph_id.0);
+    if !face.is_color_glyph(ttf_id) {
+        return None;
+    }
+    let mut layers = Layers::default();
+    face.paint_color_glyph(ttf_id, 0, FOREGROUND, &mut layers)?;
+
+    let outline_at = |px: f32| -> Vec<(OutlinedGlyph, RgbaColor)> {
+        layers
+            .layers
+            .iter()
+            .filter_map(|&(id, color)| {
+                let glyph = GlyphId(id).with_scale_and_position(px, point(0.0, 0.0));
+                Some((font.font.outline_glyph(glyph)?, color))
+            })
+            .collect()
+    };
+
+    // Measure at the box height first, then outline again at the scale
+    // that makes the whole glyph fit.
+    let probe = union_bounds(&outline_at(fit[1]))?;
+    let scale = (fit[0] / probe.width()).min(fit[1] / probe.height());
+    let outlines = outline_at(fit[1] * scale);
+    let bounds = union_bounds(&outlines)?;
+    let width = bounds.width().ceil().max(1.0) as u32;
+    let height = bounds.height().ceil().max(1.0) as u32;
+
+    let mut canvas = RgbaImage::new(width, height);
+    for (outlined, color) in &outlines {
+        let own = outlined.px_bounds();
+        let dx = (own.min.x - bounds.min.x).round() as u32;
+        let dy = (own.min.y - bounds.min.y).round() as u32;
+        outlined.draw(|x, y, coverage| {
+            if let Some(pixel) = canvas.get_pixel_mut_checked(x + dx, y + dy) {
+                blend_over(&mut pixel.0, *color, coverage);
+            }
+        });
+    }
+    Some(canvas)
+}
+
+fn union_bounds(outlines: &[(OutlinedGlyph, RgbaColor)]) -> Option<Rect> {
+    let mut iter = outlines.iter().map(|(outlined, _)| outlined.px_bounds());
+    let mut bounds = iter.next()?;
+    for other in iter {
+        bounds.min.x = bounds.min.x.min(other.min.x);
+        bounds.min.y = bounds.min.y.min(other.min.y);
+        bounds.max.x = bounds.max.x.max(other.max.x);
+        bounds.max.y = bounds.max.y.max(other.max.y);
+    }
+    (bounds.width() > 0.0 && bounds.height() > 0.0).then_some(bounds)
+}
+
+/// Source-over compositing of `color` at `coverage` onto a straight-alpha
+/// pixel.
+fn blend_over(dst: &mut [u8; 4], color: RgbaColor, coverage: f32) {
+    let src_a = coverage.clamp(0.0, 1.0) * color.alpha as f32 / 255.0;
+    if src_a <= 0.0 {
+        return;
+    }
+    let dst_a = dst[3] as f32 / 255.0;
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    let mix = |src: u8, dst: u8| {
+        let value = (src as f32 * src_a + dst as f32 * dst_a * (1.0 - src_a)) / out_a;
+        value.round().clamp(0.0, 255.0) as u8
+    };
+    *dst = [
+        mix(color.red, dst[0]),
+        mix(color.green, dst[1]),
+        mix(color.blue, dst[2]),
+        (out_a * 255.0).round() as u8,
+    ];
+}
+
+/// Collects a COLR glyph as flat layers: the last outlined glyph and the
+/// paint it was filled with.
+#[derive(Default)]
+struct Layers {
+    outline: Option<u16>,
+    layers: Vec<(u16, RgbaColor)>,
+}
+
+impl<'a> Painter<'a> for Layers {
+    fn outline_glyph(&mut self, glyph_id: ttf_parser::GlyphId) {
+        self.outline = Some(glyph_id.0);
+    }
+
+    fn paint(&mut self, paint: Paint<'a>) {
+        let color = match paint {
+            Paint::Solid(color) => color,
+            Paint::LinearGradient(gradient) => average(gradient.stops(0, &[]).map(|s| s.color)),
+            Paint::RadialGradient(gradient) => average(gradient.stops(0, &[]).map(|s| s.color)),
+            Paint::SweepGradient(gradient) => average(gradient.stops(0, &[]).map(|s| s.color)),
+        };
+        if let Some(glyph) = self.outline {
+            self.layers.push((glyph, color));
+        }
+    }
+
+    fn push_clip(&mut self) {}
+
+    fn push_clip_box(&mut self, _clipbox: ClipBox) {}
+
+    fn pop_clip(&mut self) {}
+
+    fn push_layer(&mut self, _mode: CompositeMode) {}
+
+    fn pop_layer(&mut self) {}
+
+    fn push_transform(&mut self, _transform: Transform) {}
+
+    fn pop_transform(&mut self) {}
+}
+
+fn average(colors: impl Iterator<Item = RgbaColor>) -> RgbaColor {
+    let mut sum = [0u32; 4];
+    let mut count = 0;
+    for color in colors {
+        sum[0] += color.red as u32;
+        sum[1] += color.green as u32;
+        sum[2] += color.blue as u32;
+        sum[3] += color.alpha as u32;
+        count += 1;
+    }
+    let channel = |total: u32| total.checked_div(count).unwrap_or(0) as u8;
+    RgbaColor::new(
+        channel(sum[0]),
+        channel(sum[1]),
+        channel(sum[2]),
+        channel(sum[3]),
+    )
+}
@@ -1,23 +1,30 @@
 mod atlas;
+mod boxdraw;
+mod cache;
+mod color;
 mod rasterize;
+mod shape;
 
-use crate::config::DEFAULT_TERMINAL_FONT_SIZE;
+use super::ring::{InstanceRing, UniformRing};
+use crate::config::{DEFAULT_TERMINAL_FONT_SIZE, TextRendering};
 use crate::terminal::CellVisual;
 use ab_glyph::{Font, FontArc, PxScale, ScaleFont, point};
-use atlas::{ATLAS_INITIAL_SIZE, ATLAS_MAX_SIZE, ATLAS_PADDING, GlyphAtlas};
+use atlas::{ATLAS_INITIAL_SIZE, ATLAS_PADDING, AtlasPacker};
 use bytemuck::{Pod, Zeroable};
+use cache::{CacheKey, GlyphCache, Metrics};
 use iced::wgpu;
-use rasterize::{
-    COPY_BYTES_PER_ROW_ALIGNMENT, align_to, apply_lcd_filter, default_terminal_font,
-    load_cjk_fallback, load_font_from_selection, pack_subpixel_rgba,
-};
-use std::collections::HashMap;
+use rasterize::{Synthesis, default_terminal_font, load_font_from_selection, rasterize_outlines};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
 #[repr(C)]
 struct TextUniforms {
     viewport: [f32; 2],
     offset: [f32; 2],
+    /// How the fragment shader blends coverage; see `blend_mode`.
+    mode: u32,
+    _padding: [u32; 3],
 }
 
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
@@ -29,6 +36,8 @@ struct GlyphInstance {
     uv_max: [f32; 2],
     color: [f32; 4],
     bg_color: [f32; 4],
+    /// 1.0 when the atlas holds the glyph's own colors rather than coverage.
+    colored: f32,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -37,44 +46,82 @@ struct GlyphInfo {
     uv_max: [f32; 2],
     size: [f32; 2],
     bearing: [f32; 2],
+    colored: bool,
+}
+
+const EMPTY_GLYPH: GlyphInfo = GlyphInfo {
+    uv_min: [0.0, 0.0],
+    uv_max: [0.0, 0.0],
+    size: [0.0, 0.0],
+    bearing: [0.0, 0.0],
+    colored: false,
+};
+
+/// Atlas entries are single characters or, for cells with combining marks,
+/// the whole cluster shaped and composed into one bitmap. Box-drawing
+/// characters are drawn to fill the cell rather than taken from the font.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum GlyphKey {
+    Char(char, GlyphStyle),
+    Cluster(Arc<str>, GlyphStyle),
+    Box(char),
+}
+
+/// The styles drawn by synthesizing them from the one loaded face.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+struct GlyphStyle {
+    bold: bool,
+    italic: bool,
 }
 
+/// The shader's `mode` for `rendering`. Per-channel coverage is blended
+/// against the cell's own background, which is only what ends up under the
+/// glyph when the background is opaque.
+fn blend_mode(rendering: TextRendering, opaque: bool) -> u32 {
+    match rendering {
+        TextRendering::Grayscale => 0,
+        TextRendering::Gamma => 1,
+        TextRendering::Subpixel if opaque => 2,
+        TextRendering::Subpixel => 1,
+    }
+}
+
+/// U+FE0F, which asks for the emoji (color) form of the character before it.
+const EMOJI_PRESENTATION: char = '\u{FE0F}';
+
 #[derive(Debug)]
 pub(super) struct TextPipelineData {
     pipeline: wgpu::RenderPipeline,
     bind_group_layout: wgpu::BindGroupLayout,
-    uniform_buffer: wgpu::Buffer,
-    uniform_bind_group: wgpu::BindGroup,
+    uniforms: UniformRing,
     empty_bind_group: wgpu::BindGroup,
     sampler: wgpu::Sampler,
-    atlas: GlyphAtlas,
     font: FontArc,
-    scale: PxScale,
-    font_px: f32,
+    /// The atlas for the current font, size and cell size, shared with other
+    /// pipelines drawing the same.
+    glyphs: Arc<Mutex<GlyphCache>>,
+    glyphs_key: CacheKey,
+    /// Id of the atlas the `uniforms` bind groups sample.
+    bound_atlas: u64,
+    /// Requested size and cell size the font was last fitted to, and the
+    /// size that fit.
+    fitted: Option<(f32, [f32; 2], f32)>,
     requested_font_size: f32,
-    ascent: f32,
-    descent: f32,
-    line_height: f32,
-    line_min_y: f32,
-    cell_advance: f32,
-    fallback_font: Option<FontArc>,
-    glyphs: HashMap<char, GlyphInfo>,
-    raster_buf: Vec<u8>,
-    filter_buf: Vec<u8>,
+    /// Whether bold and italic cells get synthesized glyphs.
+    synthesize: bool,
     glyph_instances: Vec<GlyphInstance>,
-    instance_buffer: wgpu::Buffer,
-    instance_capacity: usize,
+    instance_buffers: InstanceRing,
     instance_len: usize,
     requested_font_selection: Option<String>,
 }
 
 impl TextPipelineData {
-    pub(super) fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
-        let shader_src = include_str!("../shaders/terminal.wgsl");
-        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("terminal.text.wgsl"),
-            source: wgpu::ShaderSource::Wgsl(shader_src.into()),
-        });
+    pub(super) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        shaders: &super::Shaders,
+    ) -> Self {
+        let module = shaders.terminal();
 
         let empty_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -92,7 +139,7 @@ impl TextPipelineData {
             entries: &[
                 wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
@@ -121,13 +168,6 @@ impl TextPipelineData {
             ],
         });
 
-        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("terminal.text.uniform_buffer"),
-            size: std::mem::size_of::<TextUniforms>() as u64,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("terminal.text.sampler"),
             address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -139,25 +179,27 @@ impl TextPipelineData {
             ..Default::default()
         });
 
-        let atlas = GlyphAtlas::new(device, ATLAS_INITIAL_SIZE);
-        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("terminal.text.bind_group"),
-            layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: uniform_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: wgpu::BindingResource::TextureView(&atlas.view),
+        let font = default_terminal_font();
+        let glyphs_key = CacheKey::new(device, None, DEFAULT_TERMINAL_FONT_SIZE, [0, 0]);
+        let glyphs = cache::shared(glyphs_key.clone(), &font);
+        let (uniforms, bound_atlas) = {
+            let cache = cache::lock(&glyphs);
+            let uniforms = UniformRing::new(
+                device,
+                "terminal.text.uniform_buffer",
+                std::mem::size_of::<TextUniforms>(),
+                |buffer| {
+                    create_bind_group(
+                        device,
+                        &bind_group_layout,
+                        buffer,
+                        &sampler,
+                        &cache.atlas.view,
+                    )
                 },
-            ],
-        });
+            );
+            (uniforms, cache.atlas.id)
+        };
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("terminal.text.pipeline_layout"),
@@ -169,7 +211,7 @@ impl TextPipelineData {
             label: Some("terminal.text.pipeline"),
             layout: Some(&pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &module,
+                module,
                 entry_point: Some("text_vs_main"),
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
                 buffers: &[
@@ -187,13 +229,14 @@ impl TextPipelineData {
                             3 => Float32x2,
                             4 => Float32x2,
                             5 => Float32x4,
-                            6 => Float32x4
+                            6 => Float32x4,
+                            7 => Float32
                         ],
                     },
                 ],
             },
             fragment: Some(wgpu::FragmentState {
-                module: &module,
+                module,
                 entry_point: Some("text_fs_subpixel"),
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
                 targets: &[Some(wgpu::ColorTargetState {
@@ -217,51 +260,34 @@ impl TextPipelineData {
             cache: None,
         });
 
-        let font = default_terminal_font();
-        let fallback_font = load_cjk_fallback();
-        let scale = PxScale::from(1.0);
-
-        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("terminal.text.instances"),
-            size: (64 * std::mem::size_of::<GlyphInstance>()) as u64,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let instance_buffers = InstanceRing::new(
+            device,
+            "terminal.text.instances",
+            std::mem::size_of::<GlyphInstance>(),
+            64,
+        );
 
         Self {
             pipeline,
             bind_group_layout,
-            uniform_buffer,
-            uniform_bind_group,
+            uniforms,
             empty_bind_group,
             sampler,
-            atlas,
             font,
-            fallback_font,
-            scale,
-            font_px: 0.0,
+            glyphs,
+            glyphs_key,
+            bound_atlas,
+            fitted: None,
             requested_font_size: DEFAULT_TERMINAL_FONT_SIZE,
-            ascent: 0.0,
-            descent: 0.0,
-            line_height: 0.0,
-            line_min_y: 0.0,
-            cell_advance: 0.0,
-            glyphs: HashMap::new(),
-            raster_buf: Vec::new(),
-            filter_buf: Vec::new(),
+            synthesize: crate::config::DEFAULT_FONT_SYNTHESIZE,
             glyph_instances: Vec::new(),
-            instance_buffer,
-            instance_capacity: 64,
+            instance_buffers,
             instance_len: 0,
             requested_font_selection: None,
         }
     }
 
-    pub(super) fn apply_terminal_font_selection(
-        &mut self,
-        device: &wgpu::Device,
-        font_selection: Option<&str>,
-    ) {
+    pub(super) fn apply_terminal_font_selection(&mut self, font_selection: Option<&str>) {
         let requested_font_selection = font_selection
             .map(str::trim)
             .filter(|path| !path.is_empty())
@@ -271,11 +297,11 @@ impl TextPipelineData {
         }
         self.requested_font_selection = requested_font_selection.clone();
 
-        let next_font = requested_font_selection
+        self.font = requested_font_selection
             .as_deref()
             .and_then(load_font_from_selection)
             .unwrap_or_else(default_terminal_font);
-        self.set_font(device, next_font);
+        self.fitted = None;
     }
 
     pub(super) fn set_requested_font_size(&mut self, font_size: f32) {
@@ -284,14 +310,25 @@ impl TextPipelineData {
         }
     }
 
+    pub(super) fn set_synthesize(&mut self, synthesize: bool) {
+        self.synthesize = synthesize;
+    }
+
     pub(super) fn update_uniforms(
-        &self,
+        &mut self,
         queue: &wgpu::Queue,
         viewport: [f32; 2],
         offset: [f32; 2],
+        rendering: TextRendering,
+        opaque: bool,
     ) {
-        let uniforms = TextUniforms { viewport, offset };
-        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+        let uniforms = TextUniforms {
+            viewport,
+            offset,
+            mode: blend_mode(rendering, opaque),
+            _padding: [0; 3],
+        };
+        self.uniforms.write(queue, bytemuck::bytes_of(&uniforms));
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -326,31 +363,37 @@ impl TextPipelineData {
         let cell_width = cell_size[0];
         let cell_height = cell_size[1];
 
-        let mut font_px = self.requested_font_size.max(1.0);
-        self.ensure_font_size(font_px);
-        if cell_width > 0.0 && self.cell_advance > cell_width {
-            let scale = cell_width / self.cell_advance;
-            font_px = (font_px * scale).max(1.0);
-            self.ensure_font_size(font_px);
-        }
-        if cell_height > 0.0 && self.line_height > cell_height {
-            let scale = cell_height / self.line_height;
-            font_px = (font_px * scale).max(1.0);
-            self.ensure_font_size(font_px);
-        }
-
-        let top_margin = (cell_height - self.line_height).max(0.0) * 0.5;
+        self.use_glyphs(device, cell_size);
+        let glyphs = Arc::clone(&self.glyphs);
+        let mut glyphs = cache::lock(&glyphs);
+        let metrics = glyphs.metrics;
+        let top_margin = (cell_height - metrics.line_height).max(0.0) * 0.5;
 
         let needed = cells.len().saturating_sub(self.glyph_instances.capacity());
         if needed > 0 {
             self.glyph_instances.reserve(needed);
         }
         for cell in cells {
-            if cell.ch == ' ' {
-                continue;
-            }
-
-            let Some(info) = self.get_or_insert_glyph(cell.ch, device, queue) else {
+            let boxed = cell.grapheme.is_none() && !cell.wide && boxdraw::covers(cell.ch);
+            let style = if self.synthesize {
+                GlyphStyle {
+                    bold: cell.bold,
+                    italic: cell.italic,
+                }
+            } else {
+                GlyphStyle::default()
+            };
+            let info = match &cell.grapheme {
+                _ if boxed => glyphs.get_or_insert_box(cell.ch, cell_size, device, queue),
+                Some(cluster) => {
+                    glyphs.get_or_insert_cluster(cluster, cell.wide, style, device, queue)
+                }
+                // A tab leaves `\t` in the cell it started from, so copied
+                // text keeps it; it draws as a blank.
+                None if cell.ch == ' ' || cell.ch == '\t' => continue,
+                None => glyphs.get_or_insert_glyph(cell.ch, cell.wide, style, device, queue),
+            };
+            let Some(info) = info else {
                 continue;
             };
 
@@ -361,21 +404,32 @@ impl TextPipelineData {
             let span = if cell.wide { 2.0 } else { 1.0 };
             let cell_x = cell.col as f32 * cell_width;
             let cell_y = cell.row as f32 * cell_height;
-            let wide_offset_x = (cell_width * span - self.cell_advance * span).max(0.0) * 0.5;
-            let origin_x = cell_x + wide_offset_x;
-            let origin_y = cell_y + top_margin - self.line_min_y;
+            let wide_offset_x = (cell_width * span - metrics.cell_advance * span).max(0.0) * 0.5;
+            let (origin_x, origin_y) = if boxed {
+                (cell_x, cell_y)
+            } else {
+                (
+                    cell_x + wide_offset_x,
+                    cell_y + top_margin - metrics.line_min_y,
+                )
+            };
             let pos = [
                 origin[0] + origin_x + info.bearing[0],
                 origin[1] + origin_y + info.bearing[1],
             ];
 
-            let bg_color =
-                if selection.is_some_and(|s| s.contains_at(cell.row, cell.col, display_offset)) {
-                    super::SELECTION_BG
-                } else {
-                    cell.bg
-                };
-            let color = if cursor == Some([cell.col as u32, cell.row as u32]) {
+            let under_cursor = cursor == Some([cell.col as u32, cell.row as u32]);
+            let bg_color = if under_cursor {
+                cursor_color
+            } else if selection.is_some_and(|s| s.contains_at(cell.row, cell.col, display_offset)) {
+                super::SELECTION_BG
+            } else {
+                cell.bg
+            };
+            let color = if info.colored {
+                // Color glyphs keep their own colors; only the alpha applies.
+                [1.0, 1.0, 1.0, cell.fg[3]]
+            } else if under_cursor {
                 cursor_glyph_color
             } else {
                 cell.fg
@@ -387,6 +441,7 @@ impl TextPipelineData {
                 uv_max: info.uv_max,
                 color,
                 bg_color,
+                colored: if info.colored { 1.0 } else { 0.0 },
             });
         }
     }
@@ -394,24 +449,20 @@ impl TextPipelineData {
     pub(super) fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
         self.instance_len = self.glyph_instances.len();
 
-        if self.instance_len > self.instance_capacity {
-            let new_cap = self.instance_len.next_power_of_two().max(64);
-            self.instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("terminal.text.instances"),
-                size: (new_cap * std::mem::size_of::<GlyphInstance>()) as wgpu::BufferAddress,
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
+        // The atlas grew, or this pipeline moved to another one, since the
+        // bind group was made.
+        let cache = cache::lock(&self.glyphs);
+        if cache.atlas.id != self.bound_atlas {
+            self.bound_atlas = cache.atlas.id;
+            let (layout, sampler) = (&self.bind_group_layout, &self.sampler);
+            self.uniforms.rebind(|buffer| {
+                create_bind_group(device, layout, buffer, sampler, &cache.atlas.view)
             });
-            self.instance_capacity = new_cap;
         }
+        drop(cache);
 
-        if !self.glyph_instances.is_empty() {
-            queue.write_buffer(
-                &self.instance_buffer,
-                0,
-                bytemuck::cast_slice(&self.glyph_instances),
-            );
-        }
+        self.instance_buffers
+            .write(device, queue, bytemuck::cast_slice(&self.glyph_instances));
     }
 
     pub(super) fn pipeline(&self) -> &wgpu::RenderPipeline {
@@ -419,7 +470,7 @@ impl TextPipelineData {
     }
 
     pub(super) fn uniform_bind_group(&self) -> &wgpu::BindGroup {
-        &self.uniform_bind_group
+        self.uniforms.bind_group()
     }
 
     pub(super) fn empty_bind_group(&self) -> &wgpu::BindGroup {
@@ -427,262 +478,167 @@ impl TextPipelineData {
     }
 
     pub(super) fn instance_buffer(&self) -> &wgpu::Buffer {
-        &self.instance_buffer
+        self.instance_buffers.buffer()
     }
 
     pub(super) fn instance_len(&self) -> usize {
         self.instance_len
     }
 
-    fn ensure_font_size(&mut self, font_px: f32) {
-        if (self.font_px - font_px).abs() < 0.1 {
-            return;
-        }
-
-        self.font_px = font_px;
-        self.scale = PxScale::from(font_px);
-        let scaled = self.font.as_scaled(self.scale);
-        self.ascent = scaled.ascent();
-        self.descent = scaled.descent();
-        let mut min_y = 0.0;
-        let mut max_y = 0.0;
-        let mut has_bounds = false;
-        for code in 32u8..=126u8 {
-            let ch = code as char;
-            let glyph_id = self.font.glyph_id(ch);
-            let glyph = glyph_id.with_scale_and_position(self.scale, point(0.0, self.ascent));
-            if let Some(outlined) = self.font.outline_glyph(glyph) {
-                let bounds = outlined.px_bounds();
-                if !has_bounds {
-                    min_y = bounds.min.y;
-                    max_y = bounds.max.y;
-                    has_bounds = true;
-                } else {
-                    min_y = min_y.min(bounds.min.y);
-                    max_y = max_y.max(bounds.max.y);
-                }
+    /// Fits the font to `cell_size` and switches to the shared atlas for the
+    /// size that fits.
+    fn use_glyphs(&mut self, device: &wgpu::Device, cell_size: [f32; 2]) {
+        let font_px = match self.fitted {
+            Some((requested, fitted_cell, font_px))
+                if requested == self.requested_font_size && fitted_cell == cell_size =>
+            {
+                font_px
             }
-        }
-        if has_bounds {
-            self.line_min_y = min_y;
-            self.line_height = (max_y - min_y).max(1.0);
-        } else {
-            self.line_min_y = 0.0;
-            self.line_height = scaled.height();
-        }
-        let mut advance: f32 = 0.0;
-        for code in 32u8..=126u8 {
-            let candidate = scaled.h_advance(self.font.glyph_id(code as char));
-            if candidate > advance {
-                advance = candidate;
+            _ => {
+                let font_px = fit_font_px(&self.font, self.requested_font_size, cell_size);
+                self.fitted = Some((self.requested_font_size, cell_size, font_px));
+                font_px
             }
+        };
+        let key = CacheKey::new(
+            device,
+            self.requested_font_selection.as_deref(),
+            font_px,
+            [cell_size[0].round() as u32, cell_size[1].round() as u32],
+        );
+        if key != self.glyphs_key {
+            self.glyphs = cache::shared(key.clone(), &self.font);
+            self.glyphs_key = key;
         }
-        if advance <= 0.0 {
-            advance = (self.line_height * 0.6).max(1.0);
-        }
-        self.cell_advance = advance;
-        self.glyphs.clear();
-        self.atlas.packer.reset(self.atlas.size);
     }
+}
 
-    fn set_font(&mut self, device: &wgpu::Device, font: FontArc) {
-        self.font = font;
-        self.font_px = 0.0;
-        self.scale = PxScale::from(1.0);
-        self.ascent = 0.0;
-        self.descent = 0.0;
-        self.line_height = 0.0;
-        self.line_min_y = 0.0;
-        self.cell_advance = 0.0;
-        self.glyphs.clear();
-        self.rebuild_atlas(device, self.atlas.size);
+/// The largest size up to `requested` at which `font` fits in `cell_size`.
+fn fit_font_px(font: &FontArc, requested: f32, cell_size: [f32; 2]) -> f32 {
+    let [cell_width, cell_height] = cell_size;
+    let mut font_px = requested.max(1.0);
+    let mut metrics = Metrics::measure(font, font_px);
+    if cell_width > 0.0 && metrics.cell_advance > cell_width {
+        font_px = (font_px * cell_width / metrics.cell_advance).max(1.0);
+        metrics = Metrics::measure(font, font_px);
     }
-
-    fn rebuild_atlas(&mut self, device: &wgpu::Device, size: u32) {
-        let atlas = GlyphAtlas::new(device, size);
-        self.atlas = atlas;
-        self.glyphs.clear();
-        self.atlas.packer.reset(size);
-        self.uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("terminal.text.bind_group"),
-            layout: &self.bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: self.uniform_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&self.sampler),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: wgpu::BindingResource::TextureView(&self.atlas.view),
-                },
-            ],
-        });
+    if cell_height > 0.0 && metrics.line_height > cell_height {
+        font_px = (font_px * cell_height / metrics.line_height).max(1.0);
     }
+    font_px
+}
 
-    fn allocate_in_atlas(
-        &mut self,
-        device: &wgpu::Device,
-        width: u32,
-        height: u32,
-    ) -> Option<(u32, u32)> {
-        let padded_width = width.saturating_add(ATLAS_PADDING * 2);
-        let padded_height = height.saturating_add(ATLAS_PADDING * 2);
-
-        if let Some(pos) = self.atlas.packer.allocate(padded_width, padded_height) {
-            return Some(pos);
-        }
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    uniform_buffer: &wgpu::Buffer,
+    sampler: &wgpu::Sampler,
+    atlas: &wgpu::TextureView,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("terminal.text.bind_group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::TextureView(atlas),
+            },
+        ],
+    })
+}
 
-        if self.atlas.size < ATLAS_MAX_SIZE {
-            let new_size = (self.atlas.size * 2).min(ATLAS_MAX_SIZE);
-            self.rebuild_atlas(device, new_size);
-            return self.atlas.packer.allocate(padded_width, padded_height);
+/// Rasterizes the distinct characters of `text` at `font_px` with the
+/// built-in font and packs them the way the pipeline fills its atlas, without
+/// a GPU to upload to. Returns how many glyphs were packed. For benchmarks.
+#[allow(dead_code)]
+pub fn populate_atlas(text: &str, font_px: f32) -> usize {
+    let font = default_terminal_font();
+    let ascent = font.as_scaled(PxScale::from(font_px)).ascent();
+    let scale = PxScale {
+        x: font_px * 3.0,
+        y: font_px,
+    };
+    let mut packer = AtlasPacker::new(ATLAS_INITIAL_SIZE);
+    let mut seen = HashSet::new();
+    let (mut raster_buf, mut filter_buf) = (Vec::new(), Vec::new());
+    let mut packed = 0;
+    for ch in text.chars().filter(|&ch| seen.insert(ch)) {
+        let glyph = font
+            .glyph_id(ch)
+            .with_scale_and_position(scale, point(0.0, ascent));
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let Some(raster) = rasterize_outlines(
+            &[outlined],
+            Synthesis::default(),
+            &mut raster_buf,
+            &mut filter_buf,
+        ) else {
+            continue;
+        };
+        let padded = (
+            raster.width + ATLAS_PADDING * 2,
+            raster.height + ATLAS_PADDING * 2,
+        );
+        if packer.allocate(padded.0, padded.1).is_some() {
+            packed += 1;
         }
-
-        None
     }
+    packed
+}
 
-    fn get_or_insert_glyph(
-        &mut self,
-        ch: char,
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
-    ) -> Option<GlyphInfo> {
-        if let Some(info) = self.glyphs.get(&ch) {
-            return Some(*info);
-        }
-
-        let glyph_id = self.font.glyph_id(ch);
-
-        let use_fallback = glyph_id.0 == 0 && self.fallback_font.is_some();
-        let active_font: &FontArc = if use_fallback {
-            self.fallback_font.as_ref().unwrap()
-        } else {
-            &self.font
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let resolved_glyph_id = if use_fallback {
-            active_font.glyph_id(ch)
-        } else {
-            glyph_id
-        };
+    #[test]
+    fn the_font_shrinks_until_it_fits_the_cell() {
+        let font = default_terminal_font();
+        let roomy = fit_font_px(&font, 14.0, [40.0, 40.0]);
+        assert_eq!(roomy, 14.0);
 
-        if resolved_glyph_id.0 == 0 {
-            let info = GlyphInfo {
-                uv_min: [0.0, 0.0],
-                uv_max: [0.0, 0.0],
-                size: [0.0, 0.0],
-                bearing: [0.0, 0.0],
-            };
-            self.glyphs.insert(ch, info);
-            return Some(info);
-        }
+        let cramped = fit_font_px(&font, 14.0, [5.0, 40.0]);
+        assert!(cramped < 14.0);
+        assert!(Metrics::measure(&font, cramped).cell_advance <= 5.0 + 0.01);
+    }
 
-        let subpixel_scale = PxScale {
-            x: self.scale.x * 3.0,
-            y: self.scale.y,
+    #[test]
+    fn synthesized_styles_widen_the_glyph() {
+        let font = default_terminal_font();
+        let ascent = font.as_scaled(PxScale::from(14.0)).ascent();
+        let outline = || {
+            let glyph = font
+                .glyph_id('l')
+                .with_scale_and_position(PxScale { x: 42.0, y: 14.0 }, point(0.0, ascent));
+            font.outline_glyph(glyph).unwrap()
         };
-        let active_ascent = active_font.as_scaled(self.scale).ascent();
-        let glyph =
-            resolved_glyph_id.with_scale_and_position(subpixel_scale, point(0.0, active_ascent));
-
-        let outlined = match active_font.outline_glyph(glyph) {
-            Some(outlined) => outlined,
-            None => {
-                let info = GlyphInfo {
-                    uv_min: [0.0, 0.0],
-                    uv_max: [0.0, 0.0],
-                    size: [0.0, 0.0],
-                    bearing: [0.0, 0.0],
-                };
-                self.glyphs.insert(ch, info);
-                return Some(info);
-            }
+        let width = |synthesis| {
+            let (mut raster_buf, mut filter_buf) = (Vec::new(), Vec::new());
+            rasterize_outlines(&[outline()], synthesis, &mut raster_buf, &mut filter_buf)
+                .unwrap()
+                .width
         };
 
-        let bounds = outlined.px_bounds();
-        let raster_width = (bounds.max.x - bounds.min.x).ceil().max(0.0) as u32;
-        let raster_height = (bounds.max.y - bounds.min.y).ceil().max(0.0) as u32;
-        let display_width = raster_width.div_ceil(3);
-
-        if display_width == 0 || raster_height == 0 {
-            let info = GlyphInfo {
-                uv_min: [0.0, 0.0],
-                uv_max: [0.0, 0.0],
-                size: [0.0, 0.0],
-                bearing: [0.0, 0.0],
-            };
-            self.glyphs.insert(ch, info);
-            return Some(info);
-        }
-
-        let pos = self.allocate_in_atlas(device, display_width, raster_height)?;
-        let origin_x = pos.0 + ATLAS_PADDING;
-        let origin_y = pos.1 + ATLAS_PADDING;
-
-        let raster_len = (raster_width * raster_height) as usize;
-        self.raster_buf.clear();
-        self.raster_buf.resize(raster_len, 0);
-        outlined.draw(|x, y, v| {
-            let idx = (y * raster_width + x) as usize;
-            if let Some(slot) = self.raster_buf.get_mut(idx) {
-                *slot = (v * 255.0) as u8;
-            }
-        });
-
-        apply_lcd_filter(
-            &self.raster_buf,
-            &mut self.filter_buf,
-            raster_width,
-            raster_height,
-        );
-
-        let padded =
-            pack_subpixel_rgba(&self.filter_buf, raster_width, raster_height, display_width);
-        let padded_bytes_per_row = align_to(display_width * 4, COPY_BYTES_PER_ROW_ALIGNMENT);
-
-        queue.write_texture(
-            wgpu::TexelCopyTextureInfo {
-                texture: &self.atlas.texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d {
-                    x: origin_x,
-                    y: origin_y,
-                    z: 0,
-                },
-                aspect: wgpu::TextureAspect::All,
-            },
-            &padded,
-            wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                bytes_per_row: Some(padded_bytes_per_row),
-                rows_per_image: Some(raster_height),
-            },
-            wgpu::Extent3d {
-                width: display_width,
-                height: raster_height,
-                depth_or_array_layers: 1,
-            },
-        );
-
-        let atlas_size = self.atlas.size as f32;
-        let uv_min = [origin_x as f32 / atlas_size, origin_y as f32 / atlas_size];
-        let uv_max = [
-            (origin_x + display_width) as f32 / atlas_size,
-            (origin_y + raster_height) as f32 / atlas_size,
-        ];
-
-        let info = GlyphInfo {
-            uv_min,
-            uv_max,
-            size: [display_width as f32, raster_height as f32],
-            bearing: [bounds.min.x / 3.0, bounds.min.y],
+        let regular = width(Synthesis::default());
+        let bold = Synthesis {
+            embolden: 3,
+            ..Synthesis::default()
         };
-
-        self.glyphs.insert(ch, info);
-        Some(info)
+        let italic = Synthesis {
+            slant: 0.21,
+            baseline: ascent,
+            ..Synthesis::default()
+        };
+        assert!(width(bold) > regular);
+        assert!(width(italic) > regular);
     }
 }
@@ -1,7 +1,9 @@
-use ab_glyph::FontArc;
+use ab_glyph::{FontArc, OutlinedGlyph};
 use std::fs;
 
-use crate::terminal::font::{load_cjk_fallback_font, load_system_font_by_family};
+use crate::terminal::font::{
+    ColorFont, load_cjk_fallback_font, load_emoji_fallback_font, load_system_font_by_family,
+};
 
 const DEJAVU_SANS_MONO: &[u8] = include_bytes!("../../../../fonts/DejaVuSansMono.ttf");
 pub(super) const COPY_BYTES_PER_ROW_ALIGNMENT: u32 = 256;
@@ -80,6 +82,95 @@ pub(super) fn pack_subpixel_rgba(
     padded
 }
 
+/// A glyph's LCD-filtered coverage, packed as aligned RGBA rows ready to
+/// upload into the atlas.
+pub(super) struct RasterGlyph {
+    pub(super) rgba: Vec<u8>,
+    pub(super) width: u32,
+    pub(super) height: u32,
+    pub(super) bearing: [f32; 2],
+}
+
+/// Faux bold and italic, drawn from the regular face for fonts loaded
+/// without those styles. The default draws the outlines as they are.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Synthesis {
+    /// How many subpixel columns each stroke is smeared to the right.
+    pub(super) embolden: u32,
+    /// Horizontal shift per pixel above the baseline.
+    pub(super) slant: f32,
+    /// The outlines' baseline, in pixels from the top of the line.
+    pub(super) baseline: f32,
+}
+
+/// Draws `outlines` (in subpixel units) into one bitmap. `None` when they
+/// cover no pixels, as for a space.
+pub(super) fn rasterize_outlines(
+    outlines: &[OutlinedGlyph],
+    synthesis: Synthesis,
+    raster_buf: &mut Vec<u8>,
+    filter_buf: &mut Vec<u8>,
+) -> Option<RasterGlyph> {
+    let first = outlines.first()?;
+    let mut bounds = first.px_bounds();
+    for outlined in &outlines[1..] {
+        let other = outlined.px_bounds();
+        bounds.min.x = bounds.min.x.min(other.min.x);
+        bounds.min.y = bounds.min.y.min(other.min.y);
+        bounds.max.x = bounds.max.x.max(other.max.x);
+        bounds.max.y = bounds.max.y.max(other.max.y);
+    }
+    let outline_width = (bounds.max.x - bounds.min.x).ceil().max(0.0) as u32;
+    let raster_height = (bounds.max.y - bounds.min.y).ceil().max(0.0) as u32;
+    if outline_width == 0 {
+        return None;
+    }
+
+    // Each row shifts by its height above the baseline, in subpixel columns;
+    // the bottom row shifts least.
+    let shear =
+        |row: u32| (synthesis.baseline - (bounds.min.y + row as f32 + 0.5)) * synthesis.slant * 3.0;
+    let least_shear = shear(raster_height.saturating_sub(1));
+    let row_shift = |row: u32| (shear(row) - least_shear).round().max(0.0) as u32;
+    let raster_width = outline_width + row_shift(0) + synthesis.embolden;
+    let display_width = raster_width.div_ceil(3);
+
+    if display_width == 0 || raster_height == 0 {
+        return None;
+    }
+
+    let raster_len = (raster_width * raster_height) as usize;
+    raster_buf.clear();
+    raster_buf.resize(raster_len, 0);
+    for outlined in outlines {
+        let own = outlined.px_bounds();
+        let dx = (own.min.x - bounds.min.x).round() as u32;
+        let dy = (own.min.y - bounds.min.y).round() as u32;
+        outlined.draw(|x, y, v| {
+            let row = y + dy;
+            let start = x + dx + row_shift(row);
+            for column in start..=start + synthesis.embolden {
+                if column >= raster_width {
+                    return;
+                }
+                let idx = (row * raster_width + column) as usize;
+                if let Some(slot) = raster_buf.get_mut(idx) {
+                    *slot = (*slot).max((v * 255.0) as u8);
+                }
+            }
+        });
+    }
+
+    apply_lcd_filter(raster_buf, filter_buf, raster_width, raster_height);
+
+    Some(RasterGlyph {
+        rgba: pack_subpixel_rgba(filter_buf, raster_width, raster_height, display_width),
+        width: display_width,
+        height: raster_height,
+        bearing: [(bounds.min.x + least_shear) / 3.0, bounds.min.y],
+    })
+}
+
 pub(super) fn default_terminal_font() -> FontArc {
     FontArc::try_from_slice(DEJAVU_SANS_MONO).expect("font load failed")
 }
@@ -88,6 +179,10 @@ pub(super) fn load_cjk_fallback() -> Option<FontArc> {
     load_cjk_fallback_font()
 }
 
+pub(super) fn load_emoji_fallback() -> Option<ColorFont> {
+    load_emoji_fallback_font()
+}
+
 fn load_font_from_path(path: &str) -> Option<FontArc> {
     let bytes = fs::read(path).ok()?;
     FontArc::try_from_vec(bytes).ok()
@@ -0,0 +1,89 @@
+//! Shaping for cells holding more than one code point: combining marks,
+//! emoji modifiers and ZWJ sequences. The font's own substitution and
+//! positioning tables decide which glyphs a cluster becomes and where each
+//! one sits, rather than drawing it a character at a time.
+
+use ab_glyph::{Font, GlyphId};
+use rustybuzz::{BufferFlags, UnicodeBuffer};
+
+/// A glyph of a shaped cluster and where it goes relative to the cluster's
+/// origin, in font units with y pointing down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct ShapedGlyph {
+    pub(super) id: GlyphId,
+    pub(super) offset: [f32; 2],
+}
+
+/// Shapes `cluster` with face `index` of the font in `data`. `None` when the
+/// face can't be read or lacks a glyph for any of the cluster's characters,
+/// so the caller can try another font.
+pub(super) fn shape_cluster(data: &[u8], index: u32, cluster: &str) -> Option<Vec<ShapedGlyph>> {
+    let face = rustybuzz::Face::from_slice(data, index)?;
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(cluster);
+    buffer.guess_segment_properties();
+    // Joiners and variation selectors left over once the font has used them
+    // would otherwise come back as its space glyph.
+    buffer.set_flags(BufferFlags::REMOVE_DEFAULT_IGNORABLES);
+    let shaped = rustybuzz::shape(&face, &[], buffer);
+
+    let mut pen = 0;
+    let mut glyphs = Vec::with_capacity(shaped.len());
+    for (info, position) in shaped.glyph_infos().iter().zip(shaped.glyph_positions()) {
+        let id = u16::try_from(info.glyph_id).ok().filter(|&id| id != 0)?;
+        glyphs.push(ShapedGlyph {
+            id: GlyphId(id),
+            offset: [(pen + position.x_offset) as f32, -position.y_offset as f32],
+        });
+        pen += position.x_advance;
+    }
+    (!glyphs.is_empty()).then_some(glyphs)
+}
+
+/// Which face of `font`'s data ab_glyph loaded. A collection holds several
+/// and ab_glyph doesn't keep the index, so the faces are matched by glyph
+/// count and a few character mappings.
+pub(super) fn face_index(font: &impl Font) -> u32 {
+    const PROBE: &str = "Ag0 ";
+    let data = font.font_data();
+    let faces = ttf_parser::fonts_in_collection(data).unwrap_or(1);
+    (0..faces)
+        .find(|&index| {
+            ttf_parser::Face::parse(data, index).is_ok_and(|face| {
+                usize::from(face.number_of_glyphs()) == font.glyph_count()
+                    && PROBE
+                        .chars()
+                        .all(|ch| face.glyph_index(ch).map_or(0, |id| id.0) == font.glyph_id(ch).0)
+            })
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rasterize::default_terminal_font;
+    use super::*;
+
+    #[test]
+    fn a_base_and_its_mark_shape_to_the_precomposed_glyph() {
+        let font = default_terminal_font();
+        let shaped = shape_cluster(font.font_data(), face_index(&font), "e\u{301}").unwrap();
+        assert_eq!(shaped.len(), 1);
+        assert_eq!(shaped[0].id, font.glyph_id('é'));
+    }
+
+    #[test]
+    fn joiners_are_dropped_from_the_shaped_run() {
+        let font = default_terminal_font();
+        let shaped = shape_cluster(font.font_data(), 0, "a\u{200D}b").unwrap();
+        let ids: Vec<_> = shaped.iter().map(|glyph| glyph.id).collect();
+        assert_eq!(ids, [font.glyph_id('a'), font.glyph_id('b')]);
+        assert!(shaped[1].offset[0] > shaped[0].offset[0]);
+    }
+
+    #[test]
+    fn a_cluster_the_font_cannot_draw_is_left_to_another_font() {
+        let font = default_terminal_font();
+        assert_eq!(shape_cluster(font.font_data(), 0, "\u{D55C}\u{302}"), None);
+    }
+}
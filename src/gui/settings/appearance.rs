@@ -1,4 +1,7 @@
-use crate::config::{AppConfig, TabBarPosition};
+use crate::config::{
+    AdapterPreference, AppConfig, GpuBackend, PresentMode, StatusSegment, TabBarPosition,
+    TextRendering,
+};
 use crate::gui::app::{Message, SettingsMessage};
 use crate::gui::components::{
     accent_combo_box_input_style, accent_combo_box_menu_style, accent_pick_list_style,
@@ -6,7 +9,7 @@ use crate::gui::components::{
 };
 use crate::gui::settings::{
     ROW_SPACING, SECTION_SPACING, SettingsDraft, SettingsField, TerminalFontOption, hint_text,
-    input_row_with_suffix, section, segmented_control, setting_row,
+    section, segmented_control, setting_row, slider_row, stepper_row,
 };
 use crate::gui::theme::Palette;
 use crate::i18n::AVAILABLE_LOCALES;
@@ -30,12 +33,13 @@ pub fn view<'a>(
     let font_section = section(
         crate::t!("settings.terminal.font_section"),
         column(vec![
-            input_row_with_suffix(
+            stepper_row(
                 crate::t!("settings.terminal.size"),
-                &draft.terminal_font_size,
+                draft,
                 SettingsField::TerminalFontSize,
                 "pt",
                 palette,
+                config.ui.animations_enabled,
             ),
             setting_row(
                 crate::t!("settings.terminal.font_family"),
@@ -50,6 +54,46 @@ pub fn view<'a>(
                 .menu_style(accent_combo_box_menu_style(palette)),
                 palette,
             ),
+            segmented_control(
+                crate::t!("settings.terminal.text_rendering"),
+                TextRendering::ALL
+                    .iter()
+                    .map(|&rendering| {
+                        (
+                            text_rendering_label(rendering),
+                            Message::Settings(SettingsMessage::TextRenderingSelected(rendering)),
+                            draft.text_rendering == rendering,
+                        )
+                    })
+                    .collect(),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            hint_text(crate::t!("settings.terminal.text_rendering_hint"), palette),
+            setting_row(
+                crate::t!("settings.terminal.font_synthesize"),
+                toggler(draft.font_synthesize)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::FontSynthesizeToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            stepper_row(
+                crate::t!("settings.terminal.line_height"),
+                draft,
+                SettingsField::TerminalLineHeight,
+                "x",
+                palette,
+                config.ui.animations_enabled,
+            ),
+            stepper_row(
+                crate::t!("settings.terminal.letter_spacing"),
+                draft,
+                SettingsField::TerminalLetterSpacing,
+                "x",
+                palette,
+                config.ui.animations_enabled,
+            ),
             row![
                 checkbox(show_all_fonts)
                     .label(crate::t!("settings.terminal.show_all_fonts"))
@@ -76,19 +120,124 @@ pub fn view<'a>(
     let padding_section = section(
         crate::t!("settings.terminal.padding_section"),
         column(vec![
-            input_row_with_suffix(
+            stepper_row(
                 crate::t!("settings.terminal.horizontal"),
-                &draft.terminal_padding_x,
+                draft,
                 SettingsField::TerminalPaddingX,
                 "px",
                 palette,
+                config.ui.animations_enabled,
             ),
-            input_row_with_suffix(
+            stepper_row(
                 crate::t!("settings.terminal.vertical"),
-                &draft.terminal_padding_y,
+                draft,
                 SettingsField::TerminalPaddingY,
                 "px",
                 palette,
+                config.ui.animations_enabled,
+            ),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    let dimming_section = section(
+        crate::t!("settings.appearance.dimming_section"),
+        column(vec![
+            slider_row(
+                crate::t!("settings.appearance.inactive_pane_dim"),
+                draft,
+                SettingsField::TerminalInactivePaneDim,
+                "%",
+                palette,
+            ),
+            setting_row(
+                crate::t!("settings.appearance.dim_unfocused_window"),
+                toggler(draft.dim_unfocused_window)
+                    .on_toggle(|a0| {
+                        Message::Settings(SettingsMessage::DimUnfocusedWindowToggled(a0))
+                    })
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            hint_text(crate::t!("settings.appearance.dimming_hint"), palette),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    let rendering_section = section(
+        crate::t!("settings.appearance.rendering_section"),
+        column(vec![
+            segmented_control(
+                crate::t!("settings.appearance.present_mode"),
+                PresentMode::ALL
+                    .iter()
+                    .map(|&mode| {
+                        (
+                            present_mode_label(mode),
+                            Message::Settings(SettingsMessage::PresentModeSelected(mode)),
+                            draft.present_mode == mode,
+                        )
+                    })
+                    .collect(),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            hint_text(crate::t!("settings.appearance.present_mode_hint"), palette),
+            segmented_control(
+                crate::t!("settings.appearance.gpu_backend"),
+                GpuBackend::ALL
+                    .iter()
+                    .map(|&backend| {
+                        (
+                            gpu_backend_label(backend),
+                            Message::Settings(SettingsMessage::GpuBackendSelected(backend)),
+                            draft.gpu_backend == backend,
+                        )
+                    })
+                    .collect(),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            segmented_control(
+                crate::t!("settings.appearance.gpu_adapter"),
+                AdapterPreference::ALL
+                    .iter()
+                    .map(|&adapter| {
+                        (
+                            gpu_adapter_label(adapter),
+                            Message::Settings(SettingsMessage::GpuAdapterSelected(adapter)),
+                            draft.gpu_adapter == adapter,
+                        )
+                    })
+                    .collect(),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            hint_text(crate::t!("settings.appearance.gpu_hint"), palette),
+            stepper_row(
+                crate::t!("settings.appearance.unfocused_fps"),
+                draft,
+                SettingsField::RendererUnfocusedFps,
+                "fps",
+                palette,
+                config.ui.animations_enabled,
+            ),
+            setting_row(
+                crate::t!("settings.appearance.pause_when_minimized"),
+                toggler(draft.pause_when_minimized)
+                    .on_toggle(|a0| {
+                        Message::Settings(SettingsMessage::PauseWhenMinimizedToggled(a0))
+                    })
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
             ),
         ])
         .spacing(ROW_SPACING)
@@ -110,8 +259,34 @@ pub fn view<'a>(
         palette,
     );
 
-    let tabs_section = section(
-        crate::t!("settings.appearance.tabs_section"),
+    let accessibility_section = section(
+        crate::t!("settings.appearance.accessibility_section"),
+        column(vec![
+            setting_row(
+                crate::t!("settings.appearance.high_contrast"),
+                toggler(draft.high_contrast)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::HighContrastToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            setting_row(
+                crate::t!("settings.appearance.large_text"),
+                toggler(draft.large_text)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::LargeTextToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            hint_text(crate::t!("settings.appearance.accessibility_hint"), palette),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    let mut tabs_rows = vec![
         segmented_control(
             crate::t!("settings.appearance.position"),
             TabBarPosition::ALL
@@ -127,21 +302,90 @@ pub fn view<'a>(
             palette,
             config.ui.animations_enabled,
         ),
+        setting_row(
+            crate::t!("settings.appearance.status_bar"),
+            toggler(draft.status_bar)
+                .on_toggle(|a0| Message::Settings(SettingsMessage::StatusBarToggled(a0)))
+                .size(18)
+                .style(accent_toggler_style(palette)),
+            palette,
+        ),
+    ];
+    if draft.status_bar {
+        let checkboxes = StatusSegment::ALL.iter().map(|&segment| {
+            checkbox(draft.status_segments.contains(&segment))
+                .label(status_segment_label(segment))
+                .on_toggle(move |shown| {
+                    Message::Settings(SettingsMessage::StatusSegmentToggled(segment, shown))
+                })
+                .size(14)
+                .text_size(13)
+                .into()
+        });
+        tabs_rows.push(row(checkboxes).spacing(ROW_SPACING).into());
+    }
+    let tabs_section = section(
+        crate::t!("settings.appearance.tabs_section"),
+        column(tabs_rows)
+            .spacing(ROW_SPACING)
+            .width(Length::Fill)
+            .into(),
         palette,
     );
 
     column(vec![
         language_section,
+        accessibility_section,
         animations_section,
         tabs_section,
+        dimming_section,
         font_section,
         padding_section,
+        rendering_section,
     ])
     .spacing(SECTION_SPACING)
     .width(Length::Fill)
     .into()
 }
 
+fn text_rendering_label(rendering: TextRendering) -> &'static str {
+    match rendering {
+        TextRendering::Grayscale => crate::t!("settings.terminal.text_rendering_mode.grayscale"),
+        TextRendering::Gamma => crate::t!("settings.terminal.text_rendering_mode.gamma"),
+        TextRendering::Subpixel => crate::t!("settings.terminal.text_rendering_mode.subpixel"),
+    }
+}
+
+fn present_mode_label(mode: PresentMode) -> &'static str {
+    match mode {
+        PresentMode::Vsync => crate::t!("settings.appearance.present_mode_option.vsync"),
+        PresentMode::Mailbox => crate::t!("settings.appearance.present_mode_option.mailbox"),
+        PresentMode::Immediate => crate::t!("settings.appearance.present_mode_option.immediate"),
+    }
+}
+
+fn gpu_backend_label(backend: GpuBackend) -> &'static str {
+    match backend {
+        GpuBackend::Auto => crate::t!("settings.appearance.gpu_backend_option.auto"),
+        GpuBackend::Vulkan => "Vulkan",
+        GpuBackend::Dx12 => "DirectX 12",
+        GpuBackend::Metal => "Metal",
+        GpuBackend::Gl => "OpenGL",
+    }
+}
+
+fn gpu_adapter_label(adapter: AdapterPreference) -> &'static str {
+    match adapter {
+        AdapterPreference::Auto => crate::t!("settings.appearance.gpu_adapter_option.auto"),
+        AdapterPreference::LowPower => {
+            crate::t!("settings.appearance.gpu_adapter_option.low_power")
+        }
+        AdapterPreference::HighPerformance => {
+            crate::t!("settings.appearance.gpu_adapter_option.high_performance")
+        }
+    }
+}
+
 fn tab_bar_position_label(position: TabBarPosition) -> &'static str {
     match position {
         TabBarPosition::Top => crate::t!("settings.appearance.tab_position.top"),
@@ -149,6 +393,17 @@ fn tab_bar_position_label(position: TabBarPosition) -> &'static str {
     }
 }
 
+fn status_segment_label(segment: StatusSegment) -> &'static str {
+    match segment {
+        StatusSegment::Profile => crate::t!("settings.appearance.status_segment.profile"),
+        StatusSegment::Cwd => crate::t!("settings.appearance.status_segment.cwd"),
+        StatusSegment::GridSize => crate::t!("settings.appearance.status_segment.grid_size"),
+        StatusSegment::Scrollback => crate::t!("settings.appearance.status_segment.scrollback"),
+        StatusSegment::Process => crate::t!("settings.appearance.status_segment.process"),
+        StatusSegment::Alerts => crate::t!("settings.appearance.status_segment.alerts"),
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 struct LanguageOption {
     tag: &'static str,
@@ -0,0 +1,392 @@
+use crate::config::parse_hex_color;
+use crate::gui::app::{ColorPickerMessage, Message, SettingsMessage};
+use crate::gui::components::{accent_slider_style, primary, secondary};
+use crate::gui::settings::{SettingsField, format_rgb, styled_text_input_small};
+use crate::gui::theme::{Palette, RADIUS_NORMAL, RADIUS_SMALL, SPACING_NORMAL, SPACING_SMALL};
+use iced::gradient::Linear;
+use iced::widget::{
+    Space, center, column, container, mouse_area, opaque, row, slider, stack, text,
+};
+use iced::{Alignment, Background, Border, Color, Degrees, Element, Length, Point};
+
+pub const AREA_WIDTH: f32 = 260.0;
+pub const AREA_HEIGHT: f32 = 160.0;
+const MARKER_SIZE: f32 = 12.0;
+const SWATCH_SIZE: f32 = 28.0;
+
+/// The open color picker. The color is kept as hue, saturation and value so
+/// dragging through gray or black does not lose the hue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorPicker {
+    pub field: SettingsField,
+    /// Degrees, 0–360.
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+    /// What the hex field shows; may be half typed.
+    pub hex: String,
+    /// The field's text when the picker opened, restored on cancel.
+    pub original: String,
+    /// The cursor over the hue/saturation area, 0–1 on both axes.
+    pointer: Option<(f32, f32)>,
+    dragging: bool,
+}
+
+impl ColorPicker {
+    pub fn open(field: SettingsField, current: &str) -> Self {
+        let rgb = parse_hex_color(current).unwrap_or([0xff, 0xff, 0xff]);
+        let (hue, saturation, value) = rgb_to_hsv(rgb);
+        Self {
+            field,
+            hue,
+            saturation,
+            value,
+            hex: format_rgb(rgb),
+            original: current.to_string(),
+            pointer: None,
+            dragging: false,
+        }
+    }
+
+    pub fn rgb(&self) -> [u8; 3] {
+        hsv_to_rgb(self.hue, self.saturation, self.value)
+    }
+
+    /// Applies a message and returns whether the color changed.
+    pub fn update(&mut self, message: &ColorPickerMessage) -> bool {
+        match *message {
+            ColorPickerMessage::AreaMoved(point) => {
+                let x = (point.x / AREA_WIDTH).clamp(0.0, 1.0);
+                let y = (point.y / AREA_HEIGHT).clamp(0.0, 1.0);
+                self.pointer = Some((x, y));
+                self.dragging && self.pick(x, y)
+            }
+            ColorPickerMessage::AreaPressed => {
+                self.dragging = true;
+                self.pointer.is_some_and(|(x, y)| self.pick(x, y))
+            }
+            ColorPickerMessage::AreaReleased => {
+                self.dragging = false;
+                false
+            }
+            ColorPickerMessage::ValueChanged(value) => {
+                self.value = value.clamp(0.0, 1.0);
+                self.hex = format_rgb(self.rgb());
+                true
+            }
+            ColorPickerMessage::HexChanged(ref hex) => {
+                self.hex = hex.clone();
+                match parse_hex_color(hex) {
+                    Some(rgb) => {
+                        (self.hue, self.saturation, self.value) = rgb_to_hsv(rgb);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Hue runs left to right, saturation from full at the top to none at
+    /// the bottom.
+    fn pick(&mut self, x: f32, y: f32) -> bool {
+        self.hue = x * 360.0;
+        self.saturation = 1.0 - y;
+        self.hex = format_rgb(self.rgb());
+        true
+    }
+}
+
+/// `rgb` as hue in degrees, saturation and value.
+pub fn rgb_to_hsv(rgb: [u8; 3]) -> (f32, f32, f32) {
+    let [r, g, b] = rgb.map(|c| f32::from(c) / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    (hue, saturation, max)
+}
+
+pub fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> [u8; 3] {
+    let hue = hue.rem_euclid(360.0) / 60.0;
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    [r, g, b].map(|c| ((c + m) * 255.0).round().clamp(0.0, 255.0) as u8)
+}
+
+/// What the picker is editing, for its title.
+pub fn title(field: SettingsField) -> String {
+    match field {
+        SettingsField::ThemeForeground => crate::t!("settings.theme.foreground").to_string(),
+        SettingsField::ThemeBackground => crate::t!("settings.theme.background").to_string(),
+        SettingsField::ThemeCursor => crate::t!("settings.theme.cursor").to_string(),
+        SettingsField::ThemeAnsi(index) => ansi_name(index),
+        _ => String::new(),
+    }
+}
+
+/// "Red", "Bright red", … for the 16 ANSI palette entries.
+pub fn ansi_name(index: usize) -> String {
+    let base = match index % 8 {
+        0 => crate::t!("settings.theme.ansi.black"),
+        1 => crate::t!("settings.theme.ansi.red"),
+        2 => crate::t!("settings.theme.ansi.green"),
+        3 => crate::t!("settings.theme.ansi.yellow"),
+        4 => crate::t!("settings.theme.ansi.blue"),
+        5 => crate::t!("settings.theme.ansi.magenta"),
+        6 => crate::t!("settings.theme.ansi.cyan"),
+        _ => crate::t!("settings.theme.ansi.white"),
+    };
+    if index < 8 {
+        base.to_string()
+    } else {
+        crate::t!("settings.theme.ansi.bright").replace("{color}", base)
+    }
+}
+
+fn color_of(rgb: [u8; 3]) -> Color {
+    Color::from_rgb8(rgb[0], rgb[1], rgb[2])
+}
+
+fn picker_message(message: ColorPickerMessage) -> Message {
+    Message::Settings(SettingsMessage::ColorPicker(message))
+}
+
+/// The picker as a dialog over the settings page.
+pub fn overlay<'a>(
+    base: Element<'a, Message>,
+    picker: &'a ColorPicker,
+    palette: Palette,
+    animations_enabled: bool,
+) -> Element<'a, Message> {
+    let card = container(
+        column![
+            text(title(picker.field)).size(16).color(palette.text),
+            hue_saturation_area(picker),
+            slider(0.0..=1.0, picker.value, |value| {
+                picker_message(ColorPickerMessage::ValueChanged(value))
+            })
+            .step(0.01)
+            .width(Length::Fixed(AREA_WIDTH))
+            .style(accent_slider_style(palette)),
+            row![
+                swatch(parse_hex_color(&picker.original), palette),
+                swatch(Some(picker.rgb()), palette),
+                Space::new().width(Length::Fill),
+                styled_text_input_small(
+                    &picker.hex,
+                    |hex| picker_message(ColorPickerMessage::HexChanged(hex)),
+                    palette,
+                )
+                .on_submit(picker_message(ColorPickerMessage::Done)),
+            ]
+            .spacing(SPACING_SMALL)
+            .align_y(Alignment::Center)
+            .width(Length::Fixed(AREA_WIDTH)),
+            row![
+                Space::new().width(Length::Fill),
+                secondary(
+                    crate::t!("settings.color_picker.cancel"),
+                    Some(picker_message(ColorPickerMessage::Cancel)),
+                    palette,
+                    animations_enabled,
+                ),
+                primary(
+                    crate::t!("settings.color_picker.done"),
+                    picker_message(ColorPickerMessage::Done),
+                    palette,
+                    animations_enabled,
+                ),
+            ]
+            .spacing(SPACING_SMALL)
+            .width(Length::Fixed(AREA_WIDTH)),
+        ]
+        .spacing(SPACING_NORMAL),
+    )
+    .padding(20)
+    .style(move |_theme: &iced::Theme| container::Style {
+        background: Some(Background::Color(palette.surface)),
+        border: Border {
+            radius: (RADIUS_NORMAL + 4.0).into(),
+            width: 1.0,
+            color: Color {
+                a: 0.15,
+                ..palette.text
+            },
+        },
+        ..Default::default()
+    });
+
+    let backdrop = mouse_area(
+        container(text(""))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(move |_theme: &iced::Theme| container::Style {
+                background: Some(Background::Color(Color {
+                    a: 0.5,
+                    ..palette.background
+                })),
+                ..Default::default()
+            }),
+    )
+    .on_press(picker_message(ColorPickerMessage::Cancel));
+
+    stack![base, opaque(backdrop), center(card)]
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+/// Hue across, saturation down, darkened to the current value, with a
+/// ring where the color sits.
+fn hue_saturation_area<'a>(picker: &ColorPicker) -> Element<'a, Message> {
+    let hues = (0..=6).fold(Linear::new(Degrees(90.0)), |gradient, step| {
+        let offset = step as f32 / 6.0;
+        gradient.add_stop(offset, color_of(hsv_to_rgb(offset * 360.0, 1.0, 1.0)))
+    });
+    let fade_to_white = Linear::new(Degrees(180.0))
+        .add_stop(0.0, Color::TRANSPARENT)
+        .add_stop(1.0, Color::WHITE);
+    let darken = Color {
+        a: 1.0 - picker.value,
+        ..Color::BLACK
+    };
+
+    let layer = |background: Background| {
+        container(text(""))
+            .width(Length::Fixed(AREA_WIDTH))
+            .height(Length::Fixed(AREA_HEIGHT))
+            .style(move |_theme: &iced::Theme| container::Style {
+                background: Some(background),
+                border: Border {
+                    radius: RADIUS_SMALL.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+    };
+
+    let marker_x = picker.hue / 360.0 * AREA_WIDTH - MARKER_SIZE / 2.0;
+    let marker_y = (1.0 - picker.saturation) * AREA_HEIGHT - MARKER_SIZE / 2.0;
+    let marker_color = if picker.value > 0.5 {
+        Color::BLACK
+    } else {
+        Color::WHITE
+    };
+    let marker = container(
+        container(text(""))
+            .width(Length::Fixed(MARKER_SIZE))
+            .height(Length::Fixed(MARKER_SIZE))
+            .style(move |_theme: &iced::Theme| container::Style {
+                border: Border {
+                    radius: (MARKER_SIZE / 2.0).into(),
+                    width: 2.0,
+                    color: marker_color,
+                },
+                ..Default::default()
+            }),
+    )
+    .padding(iced::Padding {
+        top: marker_y.max(0.0),
+        left: marker_x.max(0.0),
+        ..iced::Padding::ZERO
+    });
+
+    mouse_area(
+        stack![
+            layer(Background::Gradient(hues.into())),
+            layer(Background::Gradient(fade_to_white.into())),
+            layer(Background::Color(darken)),
+            marker,
+        ]
+        .width(Length::Fixed(AREA_WIDTH))
+        .height(Length::Fixed(AREA_HEIGHT)),
+    )
+    .on_move(|point: Point| picker_message(ColorPickerMessage::AreaMoved(point)))
+    .on_press(picker_message(ColorPickerMessage::AreaPressed))
+    .on_release(picker_message(ColorPickerMessage::AreaReleased))
+    .into()
+}
+
+fn swatch<'a>(rgb: Option<[u8; 3]>, palette: Palette) -> Element<'a, Message> {
+    let fill = rgb.map(color_of).unwrap_or(Color::TRANSPARENT);
+    container(text(""))
+        .width(Length::Fixed(SWATCH_SIZE))
+        .height(Length::Fixed(SWATCH_SIZE))
+        .style(move |_theme: &iced::Theme| container::Style {
+            background: Some(Background::Color(fill)),
+            border: Border {
+                radius: RADIUS_SMALL.into(),
+                width: 1.0,
+                color: Color {
+                    a: 0.25,
+                    ..palette.text
+                },
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hsv_round_trips_rgb() {
+        for rgb in [
+            [0x1e, 0x1e, 0x2e],
+            [0xf3, 0x8b, 0xa8],
+            [0x00, 0x00, 0x00],
+            [0xff, 0xff, 0xff],
+            [0x4d, 0x9e, 0xf7],
+        ] {
+            let (h, s, v) = rgb_to_hsv(rgb);
+            assert_eq!(hsv_to_rgb(h, s, v), rgb);
+        }
+    }
+
+    #[test]
+    fn dragging_picks_hue_and_saturation_from_the_pointer() {
+        let mut picker = ColorPicker::open(SettingsField::ThemeCursor, "#ff0000");
+        assert!(!picker.update(&ColorPickerMessage::AreaMoved(Point::new(
+            AREA_WIDTH / 3.0,
+            0.0
+        ))));
+        assert!(picker.update(&ColorPickerMessage::AreaPressed));
+        assert_eq!(picker.hex, "#00ff00");
+        assert!(picker.update(&ColorPickerMessage::AreaMoved(Point::new(
+            AREA_WIDTH / 3.0,
+            AREA_HEIGHT
+        ))));
+        assert_eq!(picker.hex, "#ffffff");
+        picker.update(&ColorPickerMessage::AreaReleased);
+        assert!(!picker.update(&ColorPickerMessage::AreaMoved(Point::ORIGIN)));
+    }
+
+    #[test]
+    fn half_typed_hex_keeps_the_color() {
+        let mut picker = ColorPicker::open(SettingsField::ThemeCursor, "#4d9ef7");
+        assert!(!picker.update(&ColorPickerMessage::HexChanged("#4d9".to_string())));
+        assert_eq!(picker.rgb(), [0x4d, 0x9e, 0xf7]);
+    }
+}
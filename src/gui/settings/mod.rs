@@ -1,16 +1,24 @@
 use crate::config::{
-    AppConfig, AppConfigUpdates, BellMode, CursorShape, RightClickAction, SshAuthMethod,
-    SshProfile, TabBarPosition, parse_hex_color,
+    AdapterPreference, AppConfig, AppConfigUpdates, BellMode, CursorShape, ExitBehavior,
+    ForwardKind, GpuBackend, NetworkProtocol, PortForward, PresentMode, RightClickAction, Snippet,
+    SshAuthMethod, SshProfile, StatusSegment, TabBarPosition, TextRendering, TimestampMode,
+    is_valid_env_name, parse_hex_color,
 };
 use crate::gui::app::{Message, SettingsMessage};
-use crate::gui::components::accent_toggler_style;
-use crate::gui::tab::{Profile, ProfileKind};
+use crate::gui::components::{accent_slider_style, accent_toggler_style, button_icon, secondary};
+use crate::gui::tab::{Profile, ProfileKind, QuickAction};
 use crate::gui::theme::{Palette, RADIUS_SMALL, SPACING_LARGE, SPACING_NORMAL, SPACING_SMALL};
-use iced::widget::{Space, button, column, container, row, rule, text, text_input, toggler};
+use crate::session::integration::IntegrationReport;
+use iced::widget::{
+    Space, button, column, container, row, rule, slider, text, text_input, toggler,
+};
 use iced::{Alignment, Background, Border, Color, Element, Length};
+use std::collections::BTreeMap;
 use std::fmt;
 
 pub mod appearance;
+pub mod color_picker;
+pub mod search;
 pub mod shortcuts;
 pub mod ssh;
 pub mod terminal;
@@ -34,25 +42,139 @@ pub enum SettingsField {
     AppearanceLanguage,
     TerminalFontSelection,
     TerminalFontSize,
+    TerminalLineHeight,
+    TerminalLetterSpacing,
     TerminalPaddingX,
     TerminalPaddingY,
+    TerminalInactivePaneDim,
+    RendererUnfocusedFps,
     TerminalScrollback,
     TerminalScrollSpeed,
+    TerminalLargePasteLines,
+    TerminalLargePasteKib,
+    TerminalNotifyCommandSecs,
+    TerminalIdleLockMinutes,
+    TerminalCursorTrailMs,
+    TerminalEditorCommand,
+    TerminalAssistCommand,
+    TerminalLinkSchemes,
+    TerminalAnswerback,
+    TerminalColumnRulers,
     ThemeColorScheme,
     ThemeForeground,
     ThemeBackground,
     ThemeCursor,
+    /// One of the 16 ANSI palette entries.
+    ThemeAnsi(usize),
     ThemeBackgroundOpacity,
-    #[allow(dead_code)]
     ThemeMacosBlurRadius,
     Shortcut(crate::config::ShortcutId),
 }
 
+/// The values a numeric field accepts, as the config sanitizers do, and how
+/// far one stepper click moves it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericBounds {
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    /// Digits after the point; 0 for whole numbers.
+    pub decimals: usize,
+}
+
+impl NumericBounds {
+    const fn new(min: f32, max: f32, step: f32, decimals: usize) -> Self {
+        Self {
+            min,
+            max,
+            step,
+            decimals,
+        }
+    }
+
+    /// `value` written the way the field shows it.
+    pub fn format(self, value: f32) -> String {
+        format!("{value:.0$}", self.decimals)
+    }
+
+    /// The field's text moved `steps` clicks, kept inside the bounds.
+    /// Unparsable text steps from the nearest bound.
+    pub fn stepped(self, value: &str, steps: f32) -> String {
+        let current = value.trim().parse::<f32>().ok().filter(|v| v.is_finite());
+        let next = match current {
+            Some(current) => current + self.step * steps,
+            None if steps < 0.0 => self.min,
+            None => self.max,
+        };
+        // Land on the step grid so 12.3 + 1 gives 13, not 13.3.
+        let snapped = ((next - self.min) / self.step).round() * self.step + self.min;
+        self.format(snapped.clamp(self.min, self.max))
+    }
+}
+
+/// Every field `SettingsDraft::field_error` checks.
+const VALIDATED_FIELDS: [SettingsField; 20] = [
+    SettingsField::TerminalFontSize,
+    SettingsField::TerminalLineHeight,
+    SettingsField::TerminalLetterSpacing,
+    SettingsField::TerminalPaddingX,
+    SettingsField::TerminalPaddingY,
+    SettingsField::TerminalInactivePaneDim,
+    SettingsField::RendererUnfocusedFps,
+    SettingsField::TerminalScrollback,
+    SettingsField::TerminalScrollSpeed,
+    SettingsField::TerminalLargePasteLines,
+    SettingsField::TerminalLargePasteKib,
+    SettingsField::TerminalNotifyCommandSecs,
+    SettingsField::TerminalIdleLockMinutes,
+    SettingsField::TerminalCursorTrailMs,
+    SettingsField::TerminalColumnRulers,
+    SettingsField::ThemeForeground,
+    SettingsField::ThemeBackground,
+    SettingsField::ThemeCursor,
+    SettingsField::ThemeBackgroundOpacity,
+    SettingsField::ThemeMacosBlurRadius,
+];
+
+impl SettingsField {
+    pub fn bounds(self) -> Option<NumericBounds> {
+        let bounds = match self {
+            Self::TerminalFontSize => NumericBounds::new(6.0, 72.0, 1.0, 1),
+            Self::TerminalLineHeight | Self::TerminalLetterSpacing => {
+                NumericBounds::new(0.5, 3.0, 0.05, 2)
+            }
+            Self::TerminalPaddingX | Self::TerminalPaddingY => {
+                NumericBounds::new(0.0, 100.0, 1.0, 1)
+            }
+            // Percent.
+            Self::TerminalInactivePaneDim => NumericBounds::new(0.0, 80.0, 5.0, 0),
+            Self::RendererUnfocusedFps => NumericBounds::new(1.0, 240.0, 5.0, 0),
+            Self::TerminalScrollback => NumericBounds::new(100.0, 1_000_000.0, 1_000.0, 0),
+            Self::TerminalScrollSpeed => NumericBounds::new(0.1, 10.0, 0.1, 1),
+            Self::TerminalLargePasteLines | Self::TerminalLargePasteKib => {
+                NumericBounds::new(0.0, 1_000_000.0, 10.0, 0)
+            }
+            Self::TerminalNotifyCommandSecs => NumericBounds::new(0.0, 86_400.0, 5.0, 0),
+            Self::TerminalIdleLockMinutes => NumericBounds::new(0.0, 10_080.0, 5.0, 0),
+            Self::TerminalCursorTrailMs => NumericBounds::new(20.0, 1_000.0, 10.0, 0),
+            Self::ThemeBackgroundOpacity => NumericBounds::new(0.0, 1.0, 0.05, 2),
+            Self::ThemeMacosBlurRadius => NumericBounds::new(0.0, 100.0, 1.0, 0),
+            _ => return None,
+        };
+        Some(bounds)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProfileField {
     Name,
     Icon,
+    Color,
     Program,
+    Login,
+    Args,
+    InitialCommand,
+    Elevated,
     Host,
     Port,
     User,
@@ -61,12 +183,23 @@ pub enum ProfileField {
     Password,
     ProxyCommandEnabled,
     ProxyCommand,
+    KeepaliveInterval,
+    AutoReconnect,
+    RemoteAttach,
+    ForwardAgent,
+    Protocol,
+    ForwardKind(usize),
+    ForwardSpec(usize),
+    ForwardEnabled(usize),
+    AddForward,
+    RemoveForward(usize),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProfileDraftKind {
     Local,
     Ssh,
+    Network,
 }
 
 impl fmt::Display for ProfileDraftKind {
@@ -74,6 +207,7 @@ impl fmt::Display for ProfileDraftKind {
         f.write_str(match self {
             Self::Ssh => crate::t!("settings.ssh.type_ssh"),
             Self::Local => crate::t!("settings.ssh.type_local"),
+            Self::Network => crate::t!("settings.ssh.type_network"),
         })
     }
 }
@@ -83,15 +217,17 @@ pub enum ProfileModalTab {
     #[default]
     Connection,
     Advanced,
+    Forwards,
 }
 
 impl ProfileModalTab {
-    pub const ALL: [Self; 2] = [Self::Connection, Self::Advanced];
+    pub const ALL: [Self; 3] = [Self::Connection, Self::Advanced, Self::Forwards];
 
     pub fn label(self) -> &'static str {
         match self {
             Self::Connection => crate::t!("settings.ssh.tab_connection"),
             Self::Advanced => crate::t!("settings.ssh.tab_advanced"),
+            Self::Forwards => crate::t!("settings.ssh.tab_forwards"),
         }
     }
 }
@@ -140,7 +276,17 @@ pub struct ProfileDraft {
     pub kind: ProfileDraftKind,
     pub name: String,
     pub icon: String,
+    /// Hex accent color, empty for none.
+    pub color: String,
     pub program: String,
+    /// Local profiles only: start as a login shell.
+    pub login: bool,
+    /// Local profiles only: extra arguments, quoted like a shell would.
+    pub args: String,
+    /// Local profiles only: run before the interactive shell takes over.
+    pub initial_command: String,
+    /// Local profiles only: open with administrator rights (Windows).
+    pub elevated: bool,
     pub host: String,
     pub port: String,
     pub user: String,
@@ -149,6 +295,190 @@ pub struct ProfileDraft {
     pub password: String,
     pub proxy_command_enabled: bool,
     pub proxy_command: String,
+    pub keepalive_interval: String,
+    pub auto_reconnect: bool,
+    pub remote_attach: bool,
+    pub forward_agent: bool,
+    pub forwards: Vec<ForwardDraft>,
+    pub network_protocol: NetworkProtocol,
+    /// Environment of local profiles.
+    pub env: Vec<EnvDraft>,
+    /// Edited in the config file only; carried through unchanged.
+    pub actions: Vec<QuickAction>,
+    /// Edited in the config file only; carried through unchanged.
+    pub startup_commands: Vec<String>,
+}
+
+/// One `NAME=value` row of an environment editor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvDraft {
+    pub name: String,
+    pub value: String,
+}
+
+/// An edit to a list of [`EnvDraft`] rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvEdit {
+    Name(usize, String),
+    Value(usize, String),
+    Add,
+    Remove(usize),
+}
+
+fn env_drafts(env: &BTreeMap<String, String>) -> Vec<EnvDraft> {
+    env.iter()
+        .map(|(name, value)| EnvDraft {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect()
+}
+
+/// The rows that can be set; a later row with the same name wins.
+pub fn env_from_drafts(rows: &[EnvDraft]) -> BTreeMap<String, String> {
+    rows.iter()
+        .filter(|row| is_valid_env_name(row.name.trim()))
+        .map(|row| (row.name.trim().to_string(), row.value.clone()))
+        .collect()
+}
+
+/// Why the row at `index` will not be set as typed, if it will not. Blank
+/// rows are simply ignored.
+fn env_row_error(rows: &[EnvDraft], index: usize) -> Option<&'static str> {
+    let row = rows.get(index)?;
+    let name = row.name.trim();
+    if name.is_empty() {
+        return (!row.value.is_empty()).then(|| crate::t!("settings.env.name_required"));
+    }
+    if !is_valid_env_name(name) {
+        return Some(crate::t!("settings.env.invalid_name"));
+    }
+    rows[index + 1..]
+        .iter()
+        .any(|later| later.name.trim() == name)
+        .then(|| crate::t!("settings.env.duplicate_name"))
+}
+
+pub fn apply_env_edit(rows: &mut Vec<EnvDraft>, edit: EnvEdit) {
+    match edit {
+        EnvEdit::Name(index, name) => {
+            if let Some(row) = rows.get_mut(index) {
+                row.name = name;
+            }
+        }
+        EnvEdit::Value(index, value) => {
+            if let Some(row) = rows.get_mut(index) {
+                row.value = value;
+            }
+        }
+        EnvEdit::Add => rows.push(EnvDraft::default()),
+        EnvEdit::Remove(index) => {
+            if index < rows.len() {
+                rows.remove(index);
+            }
+        }
+    }
+}
+
+/// An edit to the snippet list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnippetEdit {
+    Name(usize, String),
+    Command(usize, String),
+    Add,
+    Remove(usize),
+}
+
+pub fn apply_snippet_edit(rows: &mut Vec<Snippet>, edit: SnippetEdit) {
+    match edit {
+        SnippetEdit::Name(index, name) => {
+            if let Some(row) = rows.get_mut(index) {
+                row.name = name;
+            }
+        }
+        SnippetEdit::Command(index, command) => {
+            if let Some(row) = rows.get_mut(index) {
+                row.command = command;
+            }
+        }
+        SnippetEdit::Add => rows.push(Snippet::default()),
+        SnippetEdit::Remove(index) => {
+            if index < rows.len() {
+                rows.remove(index);
+            }
+        }
+    }
+}
+
+/// Splits an argument string on whitespace. Single or double quotes keep
+/// spaces together; inside double quotes `\"` and `\\` are escapes, so
+/// Windows paths need no doubling elsewhere.
+fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote = None;
+    let mut chars = input.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (None, c) if c.is_whitespace() => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            }
+            (None, '"' | '\'') => {
+                quote = Some(ch);
+                current.get_or_insert_default();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') if matches!(chars.peek(), Some('"' | '\\')) => {
+                current.get_or_insert_default().extend(chars.next());
+            }
+            (_, c) => current.get_or_insert_default().push(c),
+        }
+    }
+    args.extend(current);
+    args
+}
+
+/// The inverse of [`split_args`].
+fn join_args(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if !arg.is_empty()
+                && !arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+            {
+                arg.clone()
+            } else {
+                format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A port forward as edited in the profile modal: the spec stays free text
+/// until the profile is saved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForwardDraft {
+    pub kind: ForwardKind,
+    pub spec: String,
+    pub enabled: bool,
+}
+
+impl ForwardDraft {
+    fn from_forward(forward: &PortForward) -> Self {
+        Self {
+            kind: forward.kind,
+            spec: forward.spec(),
+            enabled: forward.enabled,
+        }
+    }
+
+    pub fn to_forward(&self) -> Option<PortForward> {
+        let mut forward = PortForward::parse(self.kind, &self.spec)?;
+        forward.enabled = self.enabled;
+        Some(forward)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -203,7 +533,12 @@ impl ProfileDraft {
             kind: ProfileDraftKind::Ssh,
             name: profile.name.clone(),
             icon: String::new(),
+            color: String::new(),
             program: String::new(),
+            login: true,
+            args: String::new(),
+            initial_command: String::new(),
+            elevated: false,
             host: profile.host.clone(),
             port: profile.port.to_string(),
             user: profile.user.clone(),
@@ -215,24 +550,86 @@ impl ProfileDraft {
                 .as_deref()
                 .is_some_and(|value| !value.trim().is_empty()),
             proxy_command: profile.proxy_command.clone().unwrap_or_default(),
+            keepalive_interval: profile.keepalive_interval.to_string(),
+            auto_reconnect: profile.auto_reconnect,
+            remote_attach: profile.remote_attach,
+            forward_agent: profile.forward_agent,
+            forwards: profile
+                .forwards
+                .iter()
+                .map(ForwardDraft::from_forward)
+                .collect(),
+            network_protocol: NetworkProtocol::default(),
+            env: Vec::new(),
+            actions: Vec::new(),
+            startup_commands: Vec::new(),
+        }
+    }
+
+    /// A blank telnet connection on the standard port.
+    pub fn network() -> Self {
+        Self {
+            kind: ProfileDraftKind::Network,
+            port: DEFAULT_TELNET_PORT.to_string(),
+            ..Self::default()
         }
     }
 
     pub fn from_profile(profile: &Profile) -> Self {
         let icon = profile.icon.clone().unwrap_or_default();
+        let color = profile.color.clone().unwrap_or_default();
         match &profile.kind {
-            ProfileKind::Local { program, .. } => Self {
-                kind: ProfileDraftKind::Local,
-                name: profile.name.clone(),
-                icon,
-                program: program.clone().unwrap_or_default(),
-                ..Self::default()
-            },
+            ProfileKind::Local {
+                program,
+                args,
+                login,
+                command,
+                env,
+                elevated,
+            } => {
+                // Older profiles spelled the login flag as a leading `-l`.
+                let legacy_login = *login != Some(false) && args.first().is_some_and(|a| a == "-l");
+                let args = if legacy_login { &args[1..] } else { &args[..] };
+                Self {
+                    kind: ProfileDraftKind::Local,
+                    name: profile.name.clone(),
+                    icon,
+                    color,
+                    program: program.clone().unwrap_or_default(),
+                    login: login.unwrap_or(program.is_none() || legacy_login),
+                    args: join_args(args),
+                    initial_command: command.clone().unwrap_or_default(),
+                    elevated: *elevated,
+                    env: env_drafts(env),
+                    actions: profile.actions.clone(),
+                    startup_commands: profile.startup_commands.clone(),
+                    ..Self::default()
+                }
+            }
             ProfileKind::Ssh(ssh) => {
                 let mut draft = Self::from_ssh_fields(ssh);
                 draft.icon = icon;
+                draft.color = color;
+                draft.actions = profile.actions.clone();
+                draft.startup_commands = profile.startup_commands.clone();
                 draft
             }
+            ProfileKind::Network {
+                host,
+                port,
+                protocol,
+            } => Self {
+                kind: ProfileDraftKind::Network,
+                name: profile.name.clone(),
+                icon,
+                color,
+                host: host.clone(),
+                port: port.to_string(),
+                network_protocol: *protocol,
+                actions: profile.actions.clone(),
+                startup_commands: profile.startup_commands.clone(),
+                ..Self::default()
+            },
         }
     }
 
@@ -245,6 +642,11 @@ impl ProfileDraft {
         }
     }
 
+    fn color_option(&self) -> Option<String> {
+        crate::config::parse_hex_color(self.color.trim())
+            .map(|[r, g, b]| format!("#{r:02x}{g:02x}{b:02x}"))
+    }
+
     pub fn to_ssh_profile(&self) -> Option<SshProfile> {
         if !matches!(self.kind, ProfileDraftKind::Ssh) {
             return None;
@@ -289,6 +691,19 @@ impl ProfileDraft {
             } else {
                 None
             },
+            keepalive_interval: self
+                .keepalive_interval
+                .trim()
+                .parse()
+                .unwrap_or(crate::config::DEFAULT_SSH_KEEPALIVE_INTERVAL),
+            auto_reconnect: self.auto_reconnect,
+            remote_attach: self.remote_attach,
+            forward_agent: self.forward_agent,
+            forwards: self
+                .forwards
+                .iter()
+                .filter_map(ForwardDraft::to_forward)
+                .collect(),
         })
     }
 
@@ -296,23 +711,50 @@ impl ProfileDraft {
         match self.kind {
             ProfileDraftKind::Local => {
                 let program = self.program.trim();
-                let (program, args) = if program.is_empty() {
-                    (None, Vec::new())
-                } else {
-                    (Some(program.to_string()), vec!["-l".to_string()])
-                };
+                let command = self.initial_command.trim();
                 Some(Profile {
                     name: self.name.trim().to_string(),
                     icon: self.icon_option(),
-                    kind: ProfileKind::Local { program, args },
+                    color: self.color_option(),
+                    kind: ProfileKind::Local {
+                        program: (!program.is_empty()).then(|| program.to_string()),
+                        args: split_args(&self.args),
+                        login: Some(self.login),
+                        command: (!command.is_empty()).then(|| command.to_string()),
+                        env: env_from_drafts(&self.env),
+                        elevated: self.elevated,
+                    },
+                    actions: self.actions.clone(),
+                    startup_commands: self.startup_commands.clone(),
                 })
             }
             ProfileDraftKind::Ssh => {
                 let ssh = self.to_ssh_profile()?;
                 let mut profile = Profile::ssh(ssh);
                 profile.icon = self.icon_option();
+                profile.color = self.color_option();
+                profile.actions = self.actions.clone();
+                profile.startup_commands = self.startup_commands.clone();
                 Some(profile)
             }
+            ProfileDraftKind::Network => {
+                let host = self.host.trim();
+                if host.is_empty() {
+                    return None;
+                }
+                Some(Profile {
+                    name: self.name.trim().to_string(),
+                    icon: self.icon_option(),
+                    color: self.color_option(),
+                    kind: ProfileKind::Network {
+                        host: host.to_string(),
+                        port: self.port.trim().parse().unwrap_or(DEFAULT_TELNET_PORT),
+                        protocol: self.network_protocol,
+                    },
+                    actions: self.actions.clone(),
+                    startup_commands: self.startup_commands.clone(),
+                })
+            }
         }
     }
 
@@ -321,7 +763,11 @@ impl ProfileDraft {
             ProfileDraftKind::Local => {
                 self.name.trim().is_empty()
                     && self.program.trim().is_empty()
+                    && self.args.trim().is_empty()
+                    && self.initial_command.trim().is_empty()
                     && self.icon.trim().is_empty()
+                    && self.color.trim().is_empty()
+                    && env_from_drafts(&self.env).is_empty()
             }
             ProfileDraftKind::Ssh => {
                 self.name.trim().is_empty()
@@ -332,6 +778,12 @@ impl ProfileDraft {
                     && (!self.proxy_command_enabled || self.proxy_command.trim().is_empty())
                     && self.port.trim().parse::<u16>().unwrap_or(22) == 22
             }
+            ProfileDraftKind::Network => {
+                self.name.trim().is_empty()
+                    && self.host.trim().is_empty()
+                    && self.icon.trim().is_empty()
+                    && self.color.trim().is_empty()
+            }
         }
     }
 }
@@ -341,25 +793,69 @@ pub struct SettingsDraft {
     pub language: String,
     pub terminal_font_selection: String,
     pub terminal_font_size: String,
+    pub text_rendering: TextRendering,
+    pub font_synthesize: bool,
+    pub terminal_line_height: String,
+    pub terminal_letter_spacing: String,
     pub terminal_padding_x: String,
     pub terminal_padding_y: String,
     pub terminal_scrollback: String,
     pub terminal_scroll_speed: String,
+    pub smooth_scroll: bool,
     pub bracketed_paste: bool,
     pub multiline_paste_confirm: bool,
+    pub autocomplete: bool,
+    pub large_paste_lines: String,
+    pub large_paste_kib: String,
     pub cursor_shape: CursorShape,
     pub cursor_blink: bool,
+    pub cursor_trail: bool,
+    pub cursor_trail_ms: String,
+    pub highlight_cursor_row: bool,
+    pub wrap_indicator: bool,
+    /// Percent, 0–80.
+    pub inactive_pane_dim: String,
+    pub dim_unfocused_window: bool,
+    pub local_echo: bool,
+    pub persistent_sessions: bool,
+    pub notify_command_secs: String,
+    pub idle_lock_minutes: String,
+    pub secure_keyboard_entry: bool,
+    pub column_rulers: String,
     pub bold_is_bright: bool,
     pub bell_mode: BellMode,
+    pub bell_attention: bool,
+    pub timestamps: TimestampMode,
     pub right_click_action: RightClickAction,
+    pub exit_behavior: ExitBehavior,
+    pub editor_command: String,
+    pub assist_command: String,
+    pub confirm_links: bool,
+    /// Comma-separated, e.g. `http, https, mailto`.
+    pub link_schemes: String,
+    pub answerback: String,
+    pub tmux_passthrough: bool,
+    /// The global `terminal.env`.
+    pub env: Vec<EnvDraft>,
+    pub snippets: Vec<Snippet>,
     pub color_scheme: String,
     pub foreground: String,
     pub background: String,
     pub cursor: String,
+    pub ansi_colors: [String; 16],
     pub background_opacity: String,
     pub blur_enabled: bool,
     pub animations_enabled: bool,
     pub tab_bar_position: TabBarPosition,
+    pub status_bar: bool,
+    pub status_segments: Vec<StatusSegment>,
+    pub high_contrast: bool,
+    pub large_text: bool,
+    pub present_mode: PresentMode,
+    pub unfocused_fps: String,
+    pub pause_when_minimized: bool,
+    pub gpu_backend: GpuBackend,
+    pub gpu_adapter: AdapterPreference,
     pub macos_blur_radius: String,
     pub shortcuts: std::collections::BTreeMap<crate::config::ShortcutId, String>,
     pub profiles: Vec<ProfileDraft>,
@@ -369,6 +865,10 @@ pub struct SettingsDraft {
     pub profile_delete_pending: Option<usize>,
     pub profile_modal_tab: ProfileModalTab,
     pub ssh_connection_test_status: SshConnectionTestStatus,
+    /// The last shell integration check, once one has run.
+    pub shell_integration: Option<IntegrationReport>,
+    /// Why the last shell integration install failed.
+    pub shell_integration_error: Option<String>,
 }
 
 impl SettingsDraft {
@@ -381,25 +881,80 @@ impl SettingsDraft {
                 .unwrap_or_else(|| "auto".to_string()),
             terminal_font_selection: config.terminal.font_selection.clone().unwrap_or_default(),
             terminal_font_size: format!("{:.1}", config.terminal.font_size),
+            text_rendering: config.terminal.text_rendering,
+            font_synthesize: config.terminal.font_synthesize,
+            terminal_line_height: format!("{:.2}", config.terminal.line_height),
+            terminal_letter_spacing: format!("{:.2}", config.terminal.letter_spacing),
             terminal_padding_x: format!("{:.1}", config.terminal.padding_x),
             terminal_padding_y: format!("{:.1}", config.terminal.padding_y),
             terminal_scrollback: config.terminal.scrollback_lines.to_string(),
             terminal_scroll_speed: format!("{:.1}", config.terminal.scroll_multiplier),
+            smooth_scroll: config.terminal.smooth_scroll,
             bracketed_paste: config.terminal.bracketed_paste,
             multiline_paste_confirm: config.terminal.multiline_paste_confirm,
+            autocomplete: config.terminal.autocomplete,
+            large_paste_lines: config.terminal.large_paste_lines.to_string(),
+            large_paste_kib: config.terminal.large_paste_kib.to_string(),
             cursor_shape: config.terminal.cursor_shape,
             cursor_blink: config.terminal.cursor_blink,
+            cursor_trail: config.terminal.cursor_trail,
+            cursor_trail_ms: config.terminal.cursor_trail_ms.to_string(),
+            highlight_cursor_row: config.terminal.highlight_cursor_row,
+            wrap_indicator: config.terminal.wrap_indicator,
+            inactive_pane_dim: format!("{:.0}", config.terminal.inactive_pane_dim * 100.0),
+            dim_unfocused_window: config.terminal.dim_unfocused_window,
+            local_echo: config.terminal.local_echo,
+            persistent_sessions: config.terminal.persistent_sessions,
+            notify_command_secs: config.terminal.notify_command_secs.to_string(),
+            idle_lock_minutes: config.terminal.idle_lock_minutes.to_string(),
+            secure_keyboard_entry: config.terminal.secure_keyboard_entry,
+            column_rulers: config
+                .terminal
+                .column_rulers
+                .iter()
+                .map(u16::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
             bold_is_bright: config.terminal.bold_is_bright,
             bell_mode: config.terminal.bell_mode,
+            bell_attention: config.terminal.bell_attention,
+            timestamps: config.terminal.timestamps,
             right_click_action: config.terminal.right_click_action,
+            exit_behavior: config.terminal.exit_behavior,
+            editor_command: config.terminal.editor_command.clone().unwrap_or_default(),
+            assist_command: config.terminal.assist_command.clone().unwrap_or_default(),
+            confirm_links: config.terminal.confirm_links,
+            link_schemes: config.terminal.link_schemes.join(", "),
+            answerback: config.terminal.answerback.clone(),
+            tmux_passthrough: config.terminal.tmux_passthrough,
+            env: env_drafts(&config.terminal.env),
+            snippets: config.snippets.clone(),
             color_scheme: config.theme.color_scheme.clone(),
             foreground: format_rgb(config.theme.foreground),
             background: format_rgb(config.theme.background),
             cursor: format_rgb(config.theme.cursor),
+            ansi_colors: config
+                .theme
+                .ansi_colors
+                .or_else(|| {
+                    crate::terminal::theme::find_preset(&config.theme.color_scheme)
+                        .map(|preset| preset.ansi)
+                })
+                .unwrap_or(crate::terminal::theme::all_presets()[0].ansi)
+                .map(format_rgb),
             background_opacity: format!("{:.2}", config.theme.background_opacity),
             blur_enabled: config.theme.blur_enabled,
             animations_enabled: config.ui.animations_enabled,
             tab_bar_position: config.ui.tab_bar_position,
+            status_bar: config.ui.status_bar,
+            status_segments: config.ui.status_segments.clone(),
+            high_contrast: config.ui.high_contrast,
+            large_text: config.ui.large_text,
+            present_mode: config.renderer.present_mode,
+            unfocused_fps: config.renderer.unfocused_fps.to_string(),
+            pause_when_minimized: config.renderer.pause_when_minimized,
+            gpu_backend: config.renderer.backend,
+            gpu_adapter: config.renderer.adapter,
             macos_blur_radius: format!("{}", config.theme.macos_blur_radius),
             shortcuts: crate::config::ShortcutId::ALL
                 .into_iter()
@@ -416,6 +971,8 @@ impl SettingsDraft {
             profile_delete_pending: None,
             profile_modal_tab: ProfileModalTab::default(),
             ssh_connection_test_status: SshConnectionTestStatus::Idle,
+            shell_integration: None,
+            shell_integration_error: None,
         }
     }
 
@@ -500,6 +1057,11 @@ impl SettingsDraft {
         update_profile_draft(&mut self.profile_modal_draft, field, value);
     }
 
+    pub fn edit_profile_modal_env(&mut self, edit: EnvEdit) {
+        self.profiles_error = None;
+        apply_env_edit(&mut self.profile_modal_draft.env, edit);
+    }
+
     pub fn set_profile_modal_tab(&mut self, tab: ProfileModalTab) {
         self.profile_modal_tab = tab;
     }
@@ -572,21 +1134,41 @@ impl SettingsDraft {
             SettingsField::AppearanceLanguage => self.language = value,
             SettingsField::TerminalFontSelection => self.terminal_font_selection = value,
             SettingsField::TerminalFontSize => self.terminal_font_size = value,
+            SettingsField::TerminalLineHeight => self.terminal_line_height = value,
+            SettingsField::TerminalLetterSpacing => self.terminal_letter_spacing = value,
             SettingsField::TerminalPaddingX => self.terminal_padding_x = value,
             SettingsField::TerminalPaddingY => self.terminal_padding_y = value,
+            SettingsField::TerminalInactivePaneDim => self.inactive_pane_dim = value,
+            SettingsField::RendererUnfocusedFps => self.unfocused_fps = value,
             SettingsField::TerminalScrollback => self.terminal_scrollback = value,
             SettingsField::TerminalScrollSpeed => self.terminal_scroll_speed = value,
+            SettingsField::TerminalLargePasteLines => self.large_paste_lines = value,
+            SettingsField::TerminalLargePasteKib => self.large_paste_kib = value,
+            SettingsField::TerminalNotifyCommandSecs => self.notify_command_secs = value,
+            SettingsField::TerminalIdleLockMinutes => self.idle_lock_minutes = value,
+            SettingsField::TerminalCursorTrailMs => self.cursor_trail_ms = value,
+            SettingsField::TerminalEditorCommand => self.editor_command = value,
+            SettingsField::TerminalAssistCommand => self.assist_command = value,
+            SettingsField::TerminalLinkSchemes => self.link_schemes = value,
+            SettingsField::TerminalAnswerback => self.answerback = value,
+            SettingsField::TerminalColumnRulers => self.column_rulers = value,
             SettingsField::ThemeColorScheme => {
                 self.color_scheme = value.clone();
                 if let Some(preset) = crate::terminal::theme::find_preset(&value) {
                     self.foreground = format_rgb(preset.fg);
                     self.background = format_rgb(preset.bg);
                     self.cursor = format_rgb(preset.cursor);
+                    self.ansi_colors = preset.ansi.map(format_rgb);
                 }
             }
             SettingsField::ThemeForeground => self.foreground = value,
             SettingsField::ThemeBackground => self.background = value,
             SettingsField::ThemeCursor => self.cursor = value,
+            SettingsField::ThemeAnsi(index) => {
+                if let Some(color) = self.ansi_colors.get_mut(index) {
+                    *color = value;
+                }
+            }
             SettingsField::ThemeBackgroundOpacity => self.background_opacity = value,
             SettingsField::Shortcut(id) => {
                 self.shortcuts.insert(id, value);
@@ -595,35 +1177,181 @@ impl SettingsDraft {
         }
     }
 
-    #[allow(dead_code)]
+    /// The text being edited for a numeric, color or ruler field.
+    pub fn text_of(&self, field: SettingsField) -> Option<&str> {
+        let value = match field {
+            SettingsField::TerminalFontSize => &self.terminal_font_size,
+            SettingsField::TerminalLineHeight => &self.terminal_line_height,
+            SettingsField::TerminalLetterSpacing => &self.terminal_letter_spacing,
+            SettingsField::TerminalPaddingX => &self.terminal_padding_x,
+            SettingsField::TerminalPaddingY => &self.terminal_padding_y,
+            SettingsField::TerminalInactivePaneDim => &self.inactive_pane_dim,
+            SettingsField::RendererUnfocusedFps => &self.unfocused_fps,
+            SettingsField::TerminalScrollback => &self.terminal_scrollback,
+            SettingsField::TerminalScrollSpeed => &self.terminal_scroll_speed,
+            SettingsField::TerminalLargePasteLines => &self.large_paste_lines,
+            SettingsField::TerminalLargePasteKib => &self.large_paste_kib,
+            SettingsField::TerminalNotifyCommandSecs => &self.notify_command_secs,
+            SettingsField::TerminalIdleLockMinutes => &self.idle_lock_minutes,
+            SettingsField::TerminalCursorTrailMs => &self.cursor_trail_ms,
+            SettingsField::TerminalColumnRulers => &self.column_rulers,
+            SettingsField::ThemeForeground => &self.foreground,
+            SettingsField::ThemeBackground => &self.background,
+            SettingsField::ThemeCursor => &self.cursor,
+            SettingsField::ThemeAnsi(index) => self.ansi_colors.get(index)?,
+            SettingsField::ThemeBackgroundOpacity => &self.background_opacity,
+            SettingsField::ThemeMacosBlurRadius => &self.macos_blur_radius,
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Copies over the fields `previous` could not apply, so their text and
+    /// error stay until the user fixes them.
+    pub fn keep_invalid_fields(&mut self, previous: &SettingsDraft) {
+        for field in VALIDATED_FIELDS {
+            if previous.field_error(field).is_some()
+                && let Some(text) = previous.text_of(field)
+            {
+                self.update(field, text.to_string());
+            }
+        }
+    }
+
+    /// The field's value when it parses and is in bounds.
+    pub fn number(&self, field: SettingsField) -> Option<f32> {
+        let bounds = field.bounds()?;
+        let value = self.text_of(field)?.trim();
+        let number = if bounds.decimals == 0 {
+            value.parse::<i64>().ok()? as f32
+        } else {
+            value.parse::<f32>().ok().filter(|v| v.is_finite())?
+        };
+        (bounds.min..=bounds.max)
+            .contains(&number)
+            .then_some(number)
+    }
+
+    /// Why the field's text will not be applied, shown under it.
+    pub fn field_error(&self, field: SettingsField) -> Option<String> {
+        let value = self.text_of(field)?.trim();
+        if let Some(bounds) = field.bounds() {
+            if self.number(field).is_some() {
+                return None;
+            }
+            let parses = if bounds.decimals == 0 {
+                value.parse::<i64>().is_ok()
+            } else {
+                value.parse::<f32>().is_ok_and(f32::is_finite)
+            };
+            let message = if !parses && bounds.decimals == 0 {
+                crate::t!("settings.validation.whole_number").to_string()
+            } else if !parses {
+                crate::t!("settings.validation.number").to_string()
+            } else {
+                crate::t!("settings.validation.range")
+                    .replace("{min}", &bounds.format(bounds.min))
+                    .replace("{max}", &bounds.format(bounds.max))
+            };
+            return Some(message);
+        }
+        match field {
+            SettingsField::ThemeForeground
+            | SettingsField::ThemeBackground
+            | SettingsField::ThemeCursor
+            | SettingsField::ThemeAnsi(_) => parse_hex_color(value)
+                .is_none()
+                .then(|| crate::t!("settings.validation.color").to_string()),
+            SettingsField::TerminalColumnRulers => parse_column_rulers(value)
+                .is_none()
+                .then(|| crate::t!("settings.validation.rulers").to_string()),
+            _ => None,
+        }
+    }
+
     pub fn to_updates(&self) -> AppConfigUpdates {
-        let ansi_colors = crate::terminal::theme::find_preset(&self.color_scheme).map(|p| p.ansi);
+        let ansi_colors = self
+            .ansi_colors
+            .iter()
+            .map(|hex| parse_hex_color(hex))
+            .collect::<Option<Vec<_>>>()
+            .and_then(|colors| <[[u8; 3]; 16]>::try_from(colors).ok())
+            .or_else(|| crate::terminal::theme::find_preset(&self.color_scheme).map(|p| p.ansi));
 
         let mut updates = AppConfigUpdates {
             language: Some(self.language.clone()),
             animations_enabled: Some(self.animations_enabled),
             tab_bar_position: Some(self.tab_bar_position),
+            status_bar: Some(self.status_bar),
+            status_segments: Some(self.status_segments.clone()),
+            high_contrast: Some(self.high_contrast),
+            large_text: Some(self.large_text),
             terminal_font_selection: Some(self.terminal_font_selection.clone()),
-            terminal_font_size: parse_f32(&self.terminal_font_size),
-            terminal_padding_x: parse_f32(&self.terminal_padding_x),
-            terminal_padding_y: parse_f32(&self.terminal_padding_y),
-            terminal_scrollback: self.terminal_scrollback.trim().parse::<usize>().ok(),
-            terminal_scroll_multiplier: parse_f32(&self.terminal_scroll_speed),
+            terminal_font_size: self.number(SettingsField::TerminalFontSize),
+            terminal_text_rendering: Some(self.text_rendering),
+            terminal_font_synthesize: Some(self.font_synthesize),
+            terminal_line_height: self.number(SettingsField::TerminalLineHeight),
+            terminal_letter_spacing: self.number(SettingsField::TerminalLetterSpacing),
+            terminal_padding_x: self.number(SettingsField::TerminalPaddingX),
+            terminal_padding_y: self.number(SettingsField::TerminalPaddingY),
+            terminal_scrollback: self.whole_number(SettingsField::TerminalScrollback),
+            terminal_scroll_multiplier: self.number(SettingsField::TerminalScrollSpeed),
+            terminal_smooth_scroll: Some(self.smooth_scroll),
             terminal_bracketed_paste: Some(self.bracketed_paste),
             terminal_multiline_paste_confirm: Some(self.multiline_paste_confirm),
+            terminal_autocomplete: Some(self.autocomplete),
+            terminal_large_paste_lines: self.whole_number(SettingsField::TerminalLargePasteLines),
+            terminal_large_paste_kib: self.whole_number(SettingsField::TerminalLargePasteKib),
             terminal_cursor_shape: Some(self.cursor_shape),
             terminal_cursor_blink: Some(self.cursor_blink),
+            terminal_cursor_trail: Some(self.cursor_trail),
+            terminal_cursor_trail_ms: self.whole_number(SettingsField::TerminalCursorTrailMs),
+            terminal_highlight_cursor_row: Some(self.highlight_cursor_row),
+            terminal_wrap_indicator: Some(self.wrap_indicator),
+            terminal_inactive_pane_dim: self
+                .number(SettingsField::TerminalInactivePaneDim)
+                .map(|pct| pct / 100.0),
+            terminal_dim_unfocused_window: Some(self.dim_unfocused_window),
+            terminal_local_echo: Some(self.local_echo),
+            terminal_persistent_sessions: Some(self.persistent_sessions),
+            terminal_notify_command_secs: self
+                .whole_number(SettingsField::TerminalNotifyCommandSecs),
+            terminal_idle_lock_minutes: self.whole_number(SettingsField::TerminalIdleLockMinutes),
+            terminal_secure_keyboard_entry: Some(self.secure_keyboard_entry),
+            terminal_column_rulers: parse_column_rulers(&self.column_rulers),
             terminal_bold_is_bright: Some(self.bold_is_bright),
             terminal_bell_mode: Some(self.bell_mode),
+            terminal_bell_attention: Some(self.bell_attention),
+            terminal_timestamps: Some(self.timestamps),
             terminal_right_click_action: Some(self.right_click_action),
+            terminal_exit_behavior: Some(self.exit_behavior),
+            terminal_editor_command: Some(self.editor_command.clone()),
+            terminal_assist_command: Some(self.assist_command.clone()),
+            terminal_confirm_links: Some(self.confirm_links),
+            terminal_answerback: Some(self.answerback.clone()),
+            terminal_tmux_passthrough: Some(self.tmux_passthrough),
+            terminal_link_schemes: Some(
+                self.link_schemes
+                    .split([',', ' '])
+                    .filter(|part| !part.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ),
+            terminal_env: Some(env_from_drafts(&self.env)),
+            snippets: Some(self.snippets.clone()),
             color_scheme: Some(self.color_scheme.clone()),
             foreground: parse_hex_color(&self.foreground),
             background: parse_hex_color(&self.background),
             cursor: parse_hex_color(&self.cursor),
             ansi_colors,
-            background_opacity: parse_f32(&self.background_opacity),
+            background_opacity: self.number(SettingsField::ThemeBackgroundOpacity),
             blur_enabled: Some(self.blur_enabled),
-            macos_blur_radius: self.macos_blur_radius.trim().parse::<i32>().ok(),
+            macos_blur_radius: self.whole_number(SettingsField::ThemeMacosBlurRadius),
+            renderer_present_mode: Some(self.present_mode),
+            renderer_unfocused_fps: self.whole_number(SettingsField::RendererUnfocusedFps),
+            renderer_pause_when_minimized: Some(self.pause_when_minimized),
+            renderer_backend: Some(self.gpu_backend),
+            renderer_adapter: Some(self.gpu_adapter),
             ..Default::default()
         };
 
@@ -635,18 +1363,34 @@ impl SettingsDraft {
             .collect();
         updates
     }
+
+    /// `number` for fields the config stores as integers.
+    fn whole_number<T: TryFrom<i64>>(&self, field: SettingsField) -> Option<T> {
+        self.number(field)
+            .and_then(|value| T::try_from(value as i64).ok())
+    }
 }
 
-#[allow(dead_code)]
-fn parse_f32(value: &str) -> Option<f32> {
-    value.trim().parse::<f32>().ok()
+/// `"80, 120"` → `[80, 120]`; blank clears the rulers, anything unparsable
+/// leaves them unchanged.
+fn parse_column_rulers(value: &str) -> Option<Vec<u16>> {
+    value
+        .split([',', ' '])
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<u16>().ok())
+        .collect()
 }
 
 fn update_profile_draft(draft: &mut ProfileDraft, field: ProfileField, value: String) {
     match field {
         ProfileField::Name => draft.name = value,
         ProfileField::Icon => draft.icon = value,
+        ProfileField::Color => draft.color = value,
         ProfileField::Program => draft.program = value,
+        ProfileField::Login => draft.login = value == "true",
+        ProfileField::Elevated => draft.elevated = value == "true",
+        ProfileField::Args => draft.args = value,
+        ProfileField::InitialCommand => draft.initial_command = value,
         ProfileField::Host => draft.host = value,
         ProfileField::Port => draft.port = value,
         ProfileField::User => draft.user = value,
@@ -654,6 +1398,7 @@ fn update_profile_draft(draft: &mut ProfileDraft, field: ProfileField, value: St
             draft.auth_method = match value.as_str() {
                 "key_file" => SshAuthMethod::KeyFile,
                 "password" => SshAuthMethod::Password,
+                "agent" => SshAuthMethod::Agent,
                 _ => draft.auth_method,
             };
         }
@@ -663,6 +1408,47 @@ fn update_profile_draft(draft: &mut ProfileDraft, field: ProfileField, value: St
             draft.proxy_command_enabled = value == "true";
         }
         ProfileField::ProxyCommand => draft.proxy_command = value,
+        ProfileField::KeepaliveInterval => draft.keepalive_interval = value,
+        ProfileField::AutoReconnect => draft.auto_reconnect = value == "true",
+        ProfileField::RemoteAttach => draft.remote_attach = value == "true",
+        ProfileField::ForwardAgent => draft.forward_agent = value == "true",
+        ProfileField::Protocol => {
+            draft.network_protocol = match value.as_str() {
+                "telnet" => NetworkProtocol::Telnet,
+                "raw" => NetworkProtocol::Raw,
+                _ => draft.network_protocol,
+            };
+        }
+        ProfileField::ForwardKind(index) => {
+            if let Some(forward) = draft.forwards.get_mut(index) {
+                forward.kind = match value.as_str() {
+                    "local" => ForwardKind::Local,
+                    "remote" => ForwardKind::Remote,
+                    "dynamic" => ForwardKind::Dynamic,
+                    _ => forward.kind,
+                };
+            }
+        }
+        ProfileField::ForwardSpec(index) => {
+            if let Some(forward) = draft.forwards.get_mut(index) {
+                forward.spec = value;
+            }
+        }
+        ProfileField::ForwardEnabled(index) => {
+            if let Some(forward) = draft.forwards.get_mut(index) {
+                forward.enabled = value == "true";
+            }
+        }
+        ProfileField::AddForward => draft.forwards.push(ForwardDraft {
+            kind: ForwardKind::Local,
+            spec: String::new(),
+            enabled: true,
+        }),
+        ProfileField::RemoveForward(index) => {
+            if index < draft.forwards.len() {
+                draft.forwards.remove(index);
+            }
+        }
     }
 }
 
@@ -698,7 +1484,10 @@ pub fn view_category<'a>(
     }
 }
 
+const DEFAULT_TELNET_PORT: u16 = 23;
 const NUMERIC_INPUT_WIDTH: f32 = 110.0;
+const SLIDER_WIDTH: f32 = 200.0;
+const SUFFIX_WIDTH: f32 = 20.0;
 pub const SECTION_SPACING: f32 = 40.0;
 pub const ROW_SPACING: f32 = 22.0;
 const TEXT_INPUT_WIDTH: f32 = 260.0;
@@ -723,33 +1512,96 @@ pub fn input_row<'a>(
     )
 }
 
-pub fn input_row_with_suffix<'a>(
+/// A number with −/+ buttons that move it one step within the field's
+/// bounds, and the text still editable for exact values.
+pub fn stepper_row<'a>(
     label: &'a str,
-    value: &'a str,
+    draft: &'a SettingsDraft,
     field: SettingsField,
     suffix: &'a str,
     palette: Palette,
+    animations_enabled: bool,
 ) -> Element<'a, Message> {
+    let value = draft.text_of(field).unwrap_or_default();
+    let bounds = field
+        .bounds()
+        .expect("stepper_row is only used for numeric fields");
+    let step = move |steps: f32| {
+        Message::Settings(SettingsMessage::InputCommitted(
+            field,
+            bounds.stepped(value, steps),
+        ))
+    };
     let commit_msg = Message::Settings(SettingsMessage::InputCommitted(field, value.to_owned()));
-    setting_row(
-        label,
-        row![
-            styled_text_input(
-                value,
-                move |next| Message::Settings(SettingsMessage::InputChanged(field, next)),
-                palette,
-            )
-            .width(Length::Fixed(NUMERIC_INPUT_WIDTH))
-            .on_submit(commit_msg),
-            text(suffix)
-                .size(12)
-                .color(palette.text_secondary)
-                .width(Length::Fixed(20.0)),
+    let control = row![
+        button_icon("−", step(-1.0), palette, animations_enabled),
+        styled_text_input(
+            value,
+            move |next| Message::Settings(SettingsMessage::InputChanged(field, next)),
+            palette,
+        )
+        .width(Length::Fixed(NUMERIC_INPUT_WIDTH))
+        .on_submit(commit_msg),
+        button_icon("+", step(1.0), palette, animations_enabled),
+        text(suffix)
+            .size(12)
+            .color(palette.text_secondary)
+            .width(Length::Fixed(SUFFIX_WIDTH)),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(SPACING_SMALL);
+    with_field_error(setting_row(label, control, palette), draft, field, palette)
+}
+
+/// A number picked by dragging across the field's bounds, with its value
+/// shown beside the slider.
+pub fn slider_row<'a>(
+    label: &'a str,
+    draft: &'a SettingsDraft,
+    field: SettingsField,
+    suffix: &'a str,
+    palette: Palette,
+) -> Element<'a, Message> {
+    let bounds = field
+        .bounds()
+        .expect("slider_row is only used for numeric fields");
+    let value = draft.number(field).unwrap_or(bounds.min);
+    let control = row![
+        slider(bounds.min..=bounds.max, value, move |next| {
+            Message::Settings(SettingsMessage::InputChanged(field, bounds.format(next)))
+        })
+        .step(bounds.step)
+        .width(Length::Fixed(SLIDER_WIDTH))
+        .style(accent_slider_style(palette)),
+        text(draft.text_of(field).unwrap_or_default())
+            .size(13)
+            .width(Length::Fixed(40.0)),
+        text(suffix)
+            .size(12)
+            .color(palette.text_secondary)
+            .width(Length::Fixed(SUFFIX_WIDTH)),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(SPACING_NORMAL);
+    with_field_error(setting_row(label, control, palette), draft, field, palette)
+}
+
+/// `row` with the reason its value is not applied underneath, if any.
+pub fn with_field_error<'a>(
+    row: Element<'a, Message>,
+    draft: &SettingsDraft,
+    field: SettingsField,
+    palette: Palette,
+) -> Element<'a, Message> {
+    match draft.field_error(field) {
+        Some(error) => column![
+            row,
+            container(text(error).size(11).color(palette.error)).align_right(Length::Fill),
         ]
-        .align_y(Alignment::Center)
-        .spacing(SPACING_SMALL),
-        palette,
-    )
+        .spacing(SPACING_SMALL)
+        .into(),
+        None => row,
+    }
 }
 
 #[allow(dead_code)]
@@ -897,6 +1749,61 @@ fn segment_button<'a>(
     crate::gui::components::hover_fade(inner, rest, hover, animations_enabled).into()
 }
 
+/// `NAME = value` rows with remove buttons, a note under rows that will be
+/// skipped, and an add button.
+pub fn env_editor<'a>(
+    rows: &'a [EnvDraft],
+    on_edit: fn(EnvEdit) -> Message,
+    palette: Palette,
+    animations_enabled: bool,
+) -> Element<'a, Message> {
+    let mut items: Vec<Element<'a, Message>> = Vec::new();
+    for (index, env_row) in rows.iter().enumerate() {
+        items.push(
+            row![
+                placeholder_text_input(
+                    crate::t!("settings.env.name"),
+                    &env_row.name,
+                    move |next| on_edit(EnvEdit::Name(index, next)),
+                    palette,
+                )
+                .width(Length::FillPortion(2)),
+                text("=").size(13).color(palette.text_secondary),
+                placeholder_text_input(
+                    crate::t!("settings.env.value"),
+                    &env_row.value,
+                    move |next| on_edit(EnvEdit::Value(index, next)),
+                    palette,
+                )
+                .width(Length::FillPortion(3)),
+                button_icon(
+                    "x",
+                    on_edit(EnvEdit::Remove(index)),
+                    palette,
+                    animations_enabled
+                ),
+            ]
+            .spacing(SPACING_SMALL)
+            .align_y(Alignment::Center)
+            .width(Length::Fill)
+            .into(),
+        );
+        if let Some(problem) = env_row_error(rows, index) {
+            items.push(text(problem).size(11).color(palette.error).into());
+        }
+    }
+    items.push(secondary(
+        crate::t!("settings.env.add"),
+        Some(on_edit(EnvEdit::Add)),
+        palette,
+        animations_enabled,
+    ));
+    column(items)
+        .spacing(SPACING_SMALL)
+        .width(Length::Fill)
+        .into()
+}
+
 pub fn hint_text<'a>(msg: &'a str, palette: Palette) -> Element<'a, Message> {
     text(msg).size(11).color(palette.text_secondary).into()
 }
@@ -958,7 +1865,19 @@ fn styled_text_input<'a, F>(
 where
     F: 'a + Fn(String) -> Message,
 {
-    text_input("", value)
+    placeholder_text_input("", value, on_input, palette)
+}
+
+fn placeholder_text_input<'a, F>(
+    placeholder: &'a str,
+    value: &'a str,
+    on_input: F,
+    palette: Palette,
+) -> text_input::TextInput<'a, Message>
+where
+    F: 'a + Fn(String) -> Message,
+{
+    text_input(placeholder, value)
         .on_input(on_input)
         .padding([9, 12])
         .width(Length::Fill)
@@ -1041,6 +1960,126 @@ mod tests {
         ProfileDraft::from_profile(&Profile::ssh(profile.clone()))
     }
 
+    #[test]
+    fn steppers_stay_on_the_step_grid_and_inside_bounds() {
+        let bounds = SettingsField::TerminalFontSize.bounds().unwrap();
+        assert_eq!(bounds.stepped("12.3", 1.0), "13.0");
+        assert_eq!(bounds.stepped("72.0", 1.0), "72.0");
+        assert_eq!(bounds.stepped("abc", -1.0), "6.0");
+        let opacity = SettingsField::ThemeBackgroundOpacity.bounds().unwrap();
+        assert_eq!(opacity.stepped("0.95", 1.0), "1.00");
+    }
+
+    #[test]
+    fn invalid_values_are_reported_and_left_out_of_updates() {
+        let mut draft = SettingsDraft::from_config(&crate::config::AppConfig::default());
+        assert_eq!(draft.field_error(SettingsField::TerminalFontSize), None);
+
+        draft.update(SettingsField::TerminalFontSize, "200".to_string());
+        draft.update(SettingsField::TerminalScrollback, "1.5".to_string());
+        draft.update(SettingsField::ThemeForeground, "#12".to_string());
+        assert!(draft.field_error(SettingsField::TerminalFontSize).is_some());
+        assert!(
+            draft
+                .field_error(SettingsField::TerminalScrollback)
+                .is_some()
+        );
+        assert!(draft.field_error(SettingsField::ThemeForeground).is_some());
+
+        let updates = draft.to_updates();
+        assert_eq!(updates.terminal_font_size, None);
+        assert_eq!(updates.terminal_scrollback, None);
+        assert_eq!(updates.foreground, None);
+    }
+
+    #[test]
+    fn ansi_edits_reach_the_updates_and_a_scheme_resets_them() {
+        let mut draft = SettingsDraft::from_config(&crate::config::AppConfig::default());
+        draft.update(SettingsField::ThemeAnsi(1), "#123456".to_string());
+        let ansi = draft.to_updates().ansi_colors.unwrap();
+        assert_eq!(ansi[1], [0x12, 0x34, 0x56]);
+
+        let preset = &crate::terminal::theme::all_presets()[1];
+        draft.update(SettingsField::ThemeColorScheme, preset.name.clone());
+        assert_eq!(draft.to_updates().ansi_colors, Some(preset.ansi));
+    }
+
+    #[test]
+    fn invalid_text_survives_a_refresh_from_config() {
+        let config = crate::config::AppConfig::default();
+        let mut previous = SettingsDraft::from_config(&config);
+        previous.update(SettingsField::TerminalLineHeight, "9".to_string());
+        let mut draft = SettingsDraft::from_config(&config);
+        draft.keep_invalid_fields(&previous);
+        assert_eq!(draft.terminal_line_height, "9");
+        assert_eq!(draft.terminal_font_size, previous.terminal_font_size);
+    }
+
+    #[test]
+    fn column_rulers_parse_commas_and_spaces() {
+        assert_eq!(parse_column_rulers("80, 120"), Some(vec![80, 120]));
+        assert_eq!(parse_column_rulers("100 72"), Some(vec![100, 72]));
+        assert_eq!(parse_column_rulers(""), Some(Vec::new()));
+        assert_eq!(parse_column_rulers("80, x"), None);
+    }
+
+    #[test]
+    fn ssh_draft_roundtrips_keepalive_and_auto_reconnect() {
+        let profile = SshProfile {
+            host: "flaky.example.com".into(),
+            keepalive_interval: 30,
+            auto_reconnect: true,
+            ..SshProfile::default()
+        };
+
+        let mut draft = ssh_draft(&profile);
+        assert_eq!(draft.keepalive_interval, "30");
+        assert!(draft.auto_reconnect);
+
+        update_profile_draft(&mut draft, ProfileField::KeepaliveInterval, "0".into());
+        update_profile_draft(&mut draft, ProfileField::AutoReconnect, "false".into());
+        let back = draft.to_ssh_profile().unwrap();
+        assert_eq!(back.keepalive_interval, 0);
+        assert!(!back.auto_reconnect);
+
+        update_profile_draft(&mut draft, ProfileField::KeepaliveInterval, "soon".into());
+        let fallback = draft.to_ssh_profile().unwrap();
+        assert_eq!(fallback.keepalive_interval, 15);
+    }
+
+    #[test]
+    fn ssh_draft_keeps_valid_forwards_on_save() {
+        let profile = SshProfile {
+            host: "bastion".into(),
+            forwards: vec![PortForward::parse(ForwardKind::Local, "8080:localhost:80").unwrap()],
+            ..SshProfile::default()
+        };
+
+        let mut draft = ssh_draft(&profile);
+        assert_eq!(draft.forwards[0].spec, "8080:localhost:80");
+
+        update_profile_draft(&mut draft, ProfileField::AddForward, String::new());
+        update_profile_draft(&mut draft, ProfileField::ForwardKind(1), "dynamic".into());
+        update_profile_draft(&mut draft, ProfileField::ForwardSpec(1), "1080".into());
+        update_profile_draft(&mut draft, ProfileField::ForwardEnabled(1), "false".into());
+        update_profile_draft(&mut draft, ProfileField::AddForward, String::new());
+        update_profile_draft(
+            &mut draft,
+            ProfileField::ForwardSpec(2),
+            "not a spec".into(),
+        );
+
+        let back = draft.to_ssh_profile().unwrap();
+        assert_eq!(back.forwards.len(), 2);
+        assert_eq!(back.forwards[1].kind, ForwardKind::Dynamic);
+        assert_eq!(back.forwards[1].bind_port, 1080);
+        assert!(!back.forwards[1].enabled);
+
+        update_profile_draft(&mut draft, ProfileField::RemoveForward(0), String::new());
+        assert_eq!(draft.forwards.len(), 2);
+        assert_eq!(draft.forwards[0].spec, "1080");
+    }
+
     #[test]
     fn ssh_draft_roundtrip_with_password() {
         let profile = SshProfile {
@@ -1052,6 +2091,11 @@ mod tests {
             identity_file: Some("~/.ssh/id_rsa".into()),
             password: Some("s3cret".into()),
             proxy_command: None,
+            keepalive_interval: 15,
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         };
 
         let draft = ssh_draft(&profile);
@@ -1077,7 +2121,11 @@ mod tests {
             kind: ProfileDraftKind::Ssh,
             name: "test".into(),
             icon: String::new(),
+            color: String::new(),
             program: String::new(),
+            login: true,
+            args: String::new(),
+            initial_command: String::new(),
             host: "host".into(),
             port: "22".into(),
             user: "me".into(),
@@ -1086,6 +2134,16 @@ mod tests {
             password: "saved-password".into(),
             proxy_command_enabled: true,
             proxy_command: "  cloudflared access ssh --hostname %h  ".into(),
+            network_protocol: NetworkProtocol::default(),
+            env: Vec::new(),
+            elevated: false,
+            actions: Vec::new(),
+            startup_commands: Vec::new(),
+            keepalive_interval: "15".into(),
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         };
 
         let profile = draft.to_ssh_profile().unwrap();
@@ -1105,7 +2163,11 @@ mod tests {
             kind: ProfileDraftKind::Ssh,
             name: "test".into(),
             icon: String::new(),
+            color: String::new(),
             program: String::new(),
+            login: true,
+            args: String::new(),
+            initial_command: String::new(),
             host: "host".into(),
             port: "22".into(),
             user: "me".into(),
@@ -1114,6 +2176,16 @@ mod tests {
             password: "secret".into(),
             proxy_command_enabled: false,
             proxy_command: "cloudflared access ssh --hostname %h".into(),
+            network_protocol: NetworkProtocol::default(),
+            env: Vec::new(),
+            elevated: false,
+            actions: Vec::new(),
+            startup_commands: Vec::new(),
+            keepalive_interval: "15".into(),
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         };
 
         let disabled = draft.to_ssh_profile().unwrap();
@@ -1138,6 +2210,11 @@ mod tests {
             identity_file: Some("~/.ssh/id_ed25519".into()),
             password: None,
             proxy_command: Some("cloudflared access ssh --hostname %h".into()),
+            keepalive_interval: 15,
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         };
 
         let draft = ssh_draft(&profile);
@@ -1152,7 +2229,11 @@ mod tests {
             kind: ProfileDraftKind::Ssh,
             name: "test".into(),
             icon: String::new(),
+            color: String::new(),
             program: String::new(),
+            login: true,
+            args: String::new(),
+            initial_command: String::new(),
             host: "host".into(),
             port: "22".into(),
             user: "".into(),
@@ -1161,6 +2242,16 @@ mod tests {
             password: "  ".into(),
             proxy_command_enabled: false,
             proxy_command: "".into(),
+            network_protocol: NetworkProtocol::default(),
+            env: Vec::new(),
+            elevated: false,
+            actions: Vec::new(),
+            startup_commands: Vec::new(),
+            keepalive_interval: "15".into(),
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         };
         let profile = draft.to_ssh_profile().unwrap();
         assert!(profile.password.is_none());
@@ -1173,7 +2264,11 @@ mod tests {
             kind: ProfileDraftKind::Ssh,
             name: "test".into(),
             icon: String::new(),
+            color: String::new(),
             program: String::new(),
+            login: true,
+            args: String::new(),
+            initial_command: String::new(),
             host: "  ".into(),
             port: "22".into(),
             user: "".into(),
@@ -1182,46 +2277,174 @@ mod tests {
             password: "pass".into(),
             proxy_command_enabled: false,
             proxy_command: "".into(),
+            network_protocol: NetworkProtocol::default(),
+            env: Vec::new(),
+            elevated: false,
+            actions: Vec::new(),
+            startup_commands: Vec::new(),
+            keepalive_interval: "15".into(),
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         };
         assert!(draft.to_ssh_profile().is_none());
         assert!(draft.to_profile().is_none());
     }
 
     #[test]
-    fn local_draft_roundtrips_program_and_icon() {
+    fn local_draft_roundtrips_program_icon_and_color() {
         let profile = Profile {
             name: "My fish".into(),
             icon: Some("fish".into()),
+            color: Some("#E5534B".into()),
             kind: ProfileKind::Local {
                 program: Some("/opt/bin/fish".into()),
                 args: vec!["-l".into()],
+                login: None,
+                command: None,
+                env: BTreeMap::new(),
+                elevated: false,
             },
+            actions: vec![QuickAction {
+                name: "build".into(),
+                command: "cargo build".into(),
+            }],
+            startup_commands: vec!["source .venv/bin/activate".into()],
         };
         let draft = ProfileDraft::from_profile(&profile);
         assert_eq!(draft.kind, ProfileDraftKind::Local);
         assert_eq!(draft.name, "My fish");
         assert_eq!(draft.icon, "fish");
         assert_eq!(draft.program, "/opt/bin/fish");
+        assert!(draft.login);
+        assert_eq!(draft.args, "");
 
         let back = draft.to_profile().unwrap();
         assert_eq!(back.name, "My fish");
         assert_eq!(back.icon.as_deref(), Some("fish"));
+        assert_eq!(back.color.as_deref(), Some("#e5534b"));
+        assert_eq!(back.actions, profile.actions);
+        assert_eq!(back.startup_commands, profile.startup_commands);
         assert!(matches!(
             back.kind,
-            ProfileKind::Local { program: Some(p), args } if p == "/opt/bin/fish" && args == vec!["-l".to_string()]
+            ProfileKind::Local { program: Some(p), args, login: Some(true), .. }
+                if p == "/opt/bin/fish" && args.is_empty()
         ));
     }
 
+    #[test]
+    fn local_draft_splits_quoted_args_and_keeps_initial_command() {
+        let mut draft = ProfileDraft::default();
+        update_profile_draft(&mut draft, ProfileField::Program, "/bin/bash".into());
+        update_profile_draft(&mut draft, ProfileField::Login, "false".into());
+        update_profile_draft(
+            &mut draft,
+            ProfileField::Args,
+            r#"--rcfile "/my dir/rc" 'it"s' C:\tools "" "say \"hi\"""#.into(),
+        );
+        update_profile_draft(&mut draft, ProfileField::InitialCommand, " htop ".into());
+
+        let profile = draft.to_profile().unwrap();
+        let ProfileKind::Local {
+            args,
+            login,
+            command,
+            ..
+        } = &profile.kind
+        else {
+            panic!("expected a local profile");
+        };
+        assert_eq!(
+            args,
+            &[
+                "--rcfile",
+                "/my dir/rc",
+                "it\"s",
+                r"C:\tools",
+                "",
+                r#"say "hi""#
+            ]
+        );
+        assert_eq!(*login, Some(false));
+        assert_eq!(command.as_deref(), Some("htop"));
+
+        let again = ProfileDraft::from_profile(&profile);
+        assert!(!again.login);
+        assert_eq!(split_args(&again.args), *args);
+        assert_eq!(again.initial_command, "htop");
+    }
+
     #[test]
     fn local_draft_without_program_has_empty_args() {
         let draft = ProfileDraft::default();
         let profile = draft.to_profile().unwrap();
         assert!(matches!(
             profile.kind,
-            ProfileKind::Local { program: None, args } if args.is_empty()
+            ProfileKind::Local { program: None, args, login: Some(true), .. } if args.is_empty()
         ));
     }
 
+    #[test]
+    fn local_draft_env_skips_invalid_rows_and_keeps_last_duplicate() {
+        let mut draft = ProfileDraft::default();
+        for (name, value) in [
+            ("EDITOR", "vi"),
+            ("", "orphan"),
+            ("BAD=NAME", "x"),
+            (" EDITOR ", "nvim"),
+            ("", ""),
+        ] {
+            apply_env_edit(&mut draft.env, EnvEdit::Add);
+            let index = draft.env.len() - 1;
+            apply_env_edit(&mut draft.env, EnvEdit::Name(index, name.into()));
+            apply_env_edit(&mut draft.env, EnvEdit::Value(index, value.into()));
+        }
+
+        assert_eq!(
+            env_row_error(&draft.env, 0),
+            Some(crate::t!("settings.env.duplicate_name"))
+        );
+        assert_eq!(
+            env_row_error(&draft.env, 1),
+            Some(crate::t!("settings.env.name_required"))
+        );
+        assert_eq!(
+            env_row_error(&draft.env, 2),
+            Some(crate::t!("settings.env.invalid_name"))
+        );
+        assert_eq!(env_row_error(&draft.env, 3), None);
+        assert_eq!(env_row_error(&draft.env, 4), None);
+
+        let profile = draft.to_profile().unwrap();
+        let ProfileKind::Local { env, .. } = &profile.kind else {
+            panic!("expected a local profile");
+        };
+        assert_eq!(env, &BTreeMap::from([("EDITOR".into(), "nvim".into())]));
+        assert_eq!(ProfileDraft::from_profile(&profile).env.len(), 1);
+    }
+
+    #[test]
+    fn network_draft_roundtrips_protocol_and_requires_host() {
+        let mut draft = ProfileDraft::network();
+        assert!(draft.to_profile().is_none());
+
+        update_profile_draft(&mut draft, ProfileField::Host, " mud.example.org ".into());
+        update_profile_draft(&mut draft, ProfileField::Protocol, "raw".into());
+        let profile = draft.to_profile().unwrap();
+        assert!(matches!(
+            &profile.kind,
+            ProfileKind::Network { host, port: 23, protocol: NetworkProtocol::Raw }
+                if host == "mud.example.org"
+        ));
+        assert_eq!(profile.display_name(), "mud.example.org:23");
+
+        let back = ProfileDraft::from_profile(&profile);
+        assert_eq!(back.kind, ProfileDraftKind::Network);
+        assert_eq!(back.port, "23");
+        assert_eq!(back.network_protocol, NetworkProtocol::Raw);
+    }
+
     #[test]
     fn update_ssh_profile_password_field() {
         let config = crate::config::AppConfig {
@@ -1234,6 +2457,11 @@ mod tests {
                 identity_file: None,
                 password: None,
                 proxy_command: None,
+                keepalive_interval: 15,
+                auto_reconnect: false,
+                remote_attach: false,
+                forward_agent: false,
+                forwards: Vec::new(),
             }]
             .into_iter()
             .map(crate::gui::tab::Profile::ssh)
@@ -1273,6 +2501,11 @@ mod tests {
             identity_file: Some("~/.ssh/id_ed25519".into()),
             password: None,
             proxy_command: None,
+            keepalive_interval: 15,
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         }
     }
 
@@ -1355,6 +2588,11 @@ mod tests {
                 identity_file: Some("~/.ssh/id_ed25519".into()),
                 password: None,
                 proxy_command: None,
+                keepalive_interval: 15,
+                auto_reconnect: false,
+                remote_attach: false,
+                forward_agent: false,
+                forwards: Vec::new(),
             }]
             .into_iter()
             .map(crate::gui::tab::Profile::ssh)
@@ -1385,6 +2623,11 @@ mod tests {
                 identity_file: None,
                 password: Some("secret".into()),
                 proxy_command: None,
+                keepalive_interval: 15,
+                auto_reconnect: false,
+                remote_attach: false,
+                forward_agent: false,
+                forwards: Vec::new(),
             }]
             .into_iter()
             .map(crate::gui::tab::Profile::ssh)
@@ -1426,6 +2669,11 @@ mod tests {
                     identity_file: None,
                     password: Some("secret".into()),
                     proxy_command: None,
+                    keepalive_interval: 15,
+                    auto_reconnect: false,
+                    remote_attach: false,
+                    forward_agent: false,
+                    forwards: Vec::new(),
                 },
                 SshProfile {
                     name: "stage".into(),
@@ -1436,6 +2684,11 @@ mod tests {
                     identity_file: Some("~/.ssh/id_ed25519".into()),
                     password: None,
                     proxy_command: None,
+                    keepalive_interval: 15,
+                    auto_reconnect: false,
+                    remote_attach: false,
+                    forward_agent: false,
+                    forwards: Vec::new(),
                 },
             ]
             .into_iter()
@@ -1470,6 +2723,11 @@ mod tests {
                 identity_file: None,
                 password: Some("secret".into()),
                 proxy_command: None,
+                keepalive_interval: 15,
+                auto_reconnect: false,
+                remote_attach: false,
+                forward_agent: false,
+                forwards: Vec::new(),
             }]
             .into_iter()
             .map(crate::gui::tab::Profile::ssh)
@@ -1540,6 +2798,11 @@ mod tests {
                 identity_file: None,
                 password: None,
                 proxy_command: None,
+                keepalive_interval: 15,
+                auto_reconnect: false,
+                remote_attach: false,
+                forward_agent: false,
+                forwards: Vec::new(),
             }]
             .into_iter()
             .map(crate::gui::tab::Profile::ssh)
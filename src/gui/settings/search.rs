@@ -0,0 +1,487 @@
+use crate::config::ShortcutId;
+use crate::gui::app::{Message, SettingsMessage};
+use crate::gui::settings::SettingsCategory;
+use crate::gui::settings::SettingsCategory::{Appearance, Terminal, Theme};
+use crate::gui::theme::{Palette, RADIUS_NORMAL, SPACING_SMALL};
+use iced::widget::{Row, button, column, container, row, text};
+use iced::{Alignment, Background, Border, Color, Element, Font, Length};
+use std::ops::Range;
+
+/// A searchable row, in the order the rows appear on their page.
+struct Entry {
+    category: SettingsCategory,
+    /// Locale key of the row's label.
+    label: &'static str,
+    /// Where the value lives in the config file.
+    key: &'static str,
+}
+
+const fn entry(category: SettingsCategory, label: &'static str, key: &'static str) -> Entry {
+    Entry {
+        category,
+        label,
+        key,
+    }
+}
+
+const ENTRIES: &[Entry] = &[
+    entry(Appearance, "settings.language.section_title", "ui.language"),
+    entry(
+        Appearance,
+        "settings.appearance.high_contrast",
+        "ui.high_contrast",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.large_text",
+        "ui.large_text",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.animations",
+        "ui.animations_enabled",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.position",
+        "ui.tab_bar_position",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.status_bar",
+        "ui.status_bar",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.inactive_pane_dim",
+        "terminal.inactive_pane_dim",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.dim_unfocused_window",
+        "terminal.dim_unfocused_window",
+    ),
+    entry(Appearance, "settings.terminal.size", "terminal.font_size"),
+    entry(
+        Appearance,
+        "settings.terminal.font_family",
+        "terminal.font_selection",
+    ),
+    entry(
+        Appearance,
+        "settings.terminal.text_rendering",
+        "terminal.text_rendering",
+    ),
+    entry(
+        Appearance,
+        "settings.terminal.font_synthesize",
+        "terminal.font_synthesize",
+    ),
+    entry(
+        Appearance,
+        "settings.terminal.line_height",
+        "terminal.line_height",
+    ),
+    entry(
+        Appearance,
+        "settings.terminal.letter_spacing",
+        "terminal.letter_spacing",
+    ),
+    entry(
+        Appearance,
+        "settings.terminal.horizontal",
+        "terminal.padding_x",
+    ),
+    entry(
+        Appearance,
+        "settings.terminal.vertical",
+        "terminal.padding_y",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.present_mode",
+        "renderer.present_mode",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.gpu_backend",
+        "renderer.backend",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.gpu_adapter",
+        "renderer.adapter",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.unfocused_fps",
+        "renderer.unfocused_fps",
+    ),
+    entry(
+        Appearance,
+        "settings.appearance.pause_when_minimized",
+        "renderer.pause_when_minimized",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.scrollback",
+        "terminal.scrollback_lines",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.scroll_speed",
+        "terminal.scroll_multiplier",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.smooth_scroll",
+        "terminal.smooth_scroll",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.timestamps_label",
+        "terminal.timestamps",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.bracketed_paste",
+        "terminal.bracketed_paste",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.confirm_multiline_paste",
+        "terminal.multiline_paste_confirm",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.large_paste_lines",
+        "terminal.large_paste_lines",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.large_paste_size",
+        "terminal.large_paste_kib",
+    ),
+    entry(Terminal, "settings.terminal.shape", "terminal.cursor_shape"),
+    entry(Terminal, "settings.terminal.blink", "terminal.cursor_blink"),
+    entry(
+        Terminal,
+        "settings.terminal.cursor_trail",
+        "terminal.cursor_trail",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.highlight_cursor_row",
+        "terminal.highlight_cursor_row",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.wrap_indicator",
+        "terminal.wrap_indicator",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.local_echo",
+        "terminal.local_echo",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.column_rulers",
+        "terminal.column_rulers",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.bold_is_bright",
+        "terminal.bold_is_bright",
+    ),
+    entry(Terminal, "settings.terminal.behavior", "terminal.bell_mode"),
+    entry(
+        Terminal,
+        "settings.terminal.bell_attention",
+        "terminal.bell_attention",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.notify_command",
+        "terminal.notify_command_secs",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.answerback",
+        "terminal.answerback",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.tmux_passthrough",
+        "terminal.tmux_passthrough",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.right_click",
+        "terminal.right_click_action",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.editor_command",
+        "terminal.editor_command",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.confirm_links",
+        "terminal.confirm_links",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.link_schemes",
+        "terminal.link_schemes",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.persistent_sessions",
+        "terminal.persistent_sessions",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.exit_behavior",
+        "terminal.exit_behavior",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.idle_lock",
+        "terminal.idle_lock_minutes",
+    ),
+    #[cfg(target_os = "macos")]
+    entry(
+        Terminal,
+        "settings.terminal.secure_keyboard_entry",
+        "terminal.secure_keyboard_entry",
+    ),
+    entry(Terminal, "settings.env.title", "terminal.env"),
+    entry(
+        Terminal,
+        "settings.terminal.autocomplete",
+        "terminal.autocomplete",
+    ),
+    entry(
+        Terminal,
+        "settings.terminal.assist_command",
+        "terminal.assist_command",
+    ),
+    entry(Terminal, "settings.snippets.title", "snippets"),
+    entry(Theme, "settings.theme.preview_section", "theme"),
+    entry(
+        Theme,
+        "settings.theme.color_scheme_section",
+        "theme.color_scheme",
+    ),
+    entry(Theme, "settings.theme.foreground", "theme.foreground"),
+    entry(Theme, "settings.theme.background", "theme.background"),
+    entry(Theme, "settings.theme.cursor", "theme.cursor"),
+    entry(Theme, "settings.theme.ansi_section", "theme.ansi_colors"),
+    entry(
+        Theme,
+        "settings.theme.background_opacity",
+        "theme.background_opacity",
+    ),
+    entry(Theme, "settings.theme.enable_blur", "theme.blur_enabled"),
+    #[cfg(target_os = "macos")]
+    entry(
+        Theme,
+        "settings.theme.blur_radius",
+        "theme.macos_blur_radius",
+    ),
+];
+
+/// A row that matches the search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch {
+    pub category: SettingsCategory,
+    pub label: &'static str,
+    pub key: String,
+    /// How far down its page the row is, 0–1.
+    pub position: f32,
+}
+
+/// Every row whose label or config key contains all the words of `query`,
+/// ignoring case, in page order.
+pub fn search(query: &str) -> Vec<SearchMatch> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let mut rows: Vec<SearchMatch> = Vec::new();
+    for category in SettingsCategory::ALL {
+        let on_page: Vec<(&'static str, String)> = if category == SettingsCategory::Shortcuts {
+            ShortcutId::ALL
+                .iter()
+                .map(|id| (id.label(), format!("shortcuts.{}", id.key())))
+                .collect()
+        } else {
+            ENTRIES
+                .iter()
+                .filter(|entry| entry.category == category)
+                .map(|entry| (crate::t!(entry.label), entry.key.to_string()))
+                .collect()
+        };
+        let last = on_page.len().saturating_sub(1).max(1) as f32;
+        for (index, (label, key)) in on_page.into_iter().enumerate() {
+            let haystack = format!("{} {}", label.to_lowercase(), key);
+            if terms.iter().all(|term| haystack.contains(term.as_str())) {
+                rows.push(SearchMatch {
+                    category,
+                    label,
+                    key,
+                    position: index as f32 / last,
+                });
+            }
+        }
+    }
+    rows
+}
+
+/// Where the first word of `query` found in `text` sits, for highlighting.
+fn highlight_range(text: &str, query: &str) -> Option<Range<usize>> {
+    let lower = text.to_lowercase();
+    // Lowercasing can change byte lengths outside ASCII and Hangul; skip
+    // the highlight rather than split a character.
+    if lower.len() != text.len() {
+        return None;
+    }
+    query.split_whitespace().find_map(|term| {
+        let start = lower.find(&term.to_lowercase())?;
+        Some(start..start + term.len())
+    })
+}
+
+/// `text` with the matched part of `query` in the accent color.
+fn highlighted<'a>(
+    text_value: &str,
+    query: &str,
+    size: f32,
+    font: Font,
+    color: Color,
+    palette: Palette,
+) -> Element<'a, Message> {
+    let piece =
+        |value: &str, color: Color| text(value.to_string()).size(size).font(font).color(color);
+    match highlight_range(text_value, query) {
+        Some(range) => Row::new()
+            .push(piece(&text_value[..range.start], color))
+            .push(piece(&text_value[range.clone()], palette.accent))
+            .push(piece(&text_value[range.end..], color))
+            .into(),
+        None => piece(text_value, color).into(),
+    }
+}
+
+/// The matches for `query`, each jumping to its row when clicked.
+pub fn view_results<'a>(query: &str, palette: Palette) -> Element<'a, Message> {
+    let matches = search(query);
+    if matches.is_empty() {
+        return text(crate::t!("settings.search.no_results"))
+            .size(13)
+            .color(palette.text_secondary)
+            .into();
+    }
+
+    let rows = matches.into_iter().map(|found| {
+        let content = row![
+            text(found.category.icon())
+                .size(14)
+                .color(palette.text_secondary),
+            column![
+                highlighted(
+                    found.label,
+                    query,
+                    14.0,
+                    Font::DEFAULT,
+                    palette.text,
+                    palette
+                ),
+                row![
+                    text(found.category.label())
+                        .size(11)
+                        .color(palette.text_secondary),
+                    highlighted(
+                        &found.key,
+                        query,
+                        11.0,
+                        Font::MONOSPACE,
+                        palette.text_secondary,
+                        palette,
+                    ),
+                ]
+                .spacing(SPACING_SMALL * 2.0),
+            ]
+            .spacing(2),
+        ]
+        .spacing(SPACING_SMALL * 3.0)
+        .align_y(Alignment::Center);
+
+        button(content)
+            .padding([8, 12])
+            .width(Length::Fill)
+            .on_press(Message::Settings(SettingsMessage::JumpToSetting(
+                found.category,
+                found.position,
+            )))
+            .style(
+                move |_theme: &iced::Theme, status: button::Status| button::Style {
+                    background: Some(Background::Color(match status {
+                        button::Status::Hovered | button::Status::Pressed => Color {
+                            a: 0.08,
+                            ..palette.text
+                        },
+                        _ => Color::TRANSPARENT,
+                    })),
+                    text_color: palette.text,
+                    border: Border {
+                        radius: RADIUS_NORMAL.into(),
+                        ..Default::default()
+                    },
+                    shadow: Default::default(),
+                    snap: true,
+                },
+            )
+            .into()
+    });
+
+    container(column(rows).spacing(2))
+        .width(Length::Fill)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_word_must_match_the_label_or_key() {
+        let found = search("terminal.font_size");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].category, SettingsCategory::Appearance);
+
+        assert!(
+            search("SCROLLBACK lines")
+                .iter()
+                .any(|m| m.key == "terminal.scrollback_lines")
+        );
+        assert!(search("   ").is_empty());
+        assert!(search("no such setting").is_empty());
+    }
+
+    #[test]
+    fn shortcuts_are_found_by_key() {
+        let found = search("shortcuts.cheat_sheet");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].category, SettingsCategory::Shortcuts);
+        assert!((0.0..=1.0).contains(&found[0].position));
+    }
+
+    #[test]
+    fn highlight_covers_the_first_matching_word() {
+        assert_eq!(highlight_range("Font size", "xyz SIZE"), Some(5..9));
+        assert_eq!(highlight_range("Font size", "xyz"), None);
+    }
+}
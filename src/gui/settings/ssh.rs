@@ -1,4 +1,4 @@
-use crate::config::SshAuthMethod;
+use crate::config::{ForwardKind, NetworkProtocol, SshAuthMethod};
 use crate::gui::app::{Message, SettingsMessage};
 use crate::gui::components::{
     HoverStyle, button_icon, hover_fade, icon_toggle_content, primary, secondary,
@@ -6,8 +6,9 @@ use crate::gui::components::{
 use crate::gui::icons;
 use crate::gui::settings::{
     ProfileDraft, ProfileDraftKind, ProfileField, ProfileModalMode, ProfileModalTab,
-    ProfileTemplate, SettingsDraft, SshConnectionTestStatus,
+    ProfileTemplate, SettingsDraft, SshConnectionTestStatus, env_editor,
 };
+use crate::gui::tab::TAB_COLORS;
 use crate::gui::theme::{
     Palette, RADIUS_NORMAL, RADIUS_SMALL, SPACING_LARGE, SPACING_NORMAL, SPACING_SMALL,
 };
@@ -255,6 +256,7 @@ fn template_title(template: &ProfileTemplate) -> String {
     match template.draft.kind {
         ProfileDraftKind::Ssh => crate::t!("settings.ssh.template_blank_ssh").to_string(),
         ProfileDraftKind::Local => crate::t!("settings.ssh.template_default_shell").to_string(),
+        ProfileDraftKind::Network => crate::t!("settings.ssh.template_network").to_string(),
     }
 }
 
@@ -332,6 +334,7 @@ fn profile_icon<'a>(profile: &ProfileDraft) -> Element<'a, Message> {
         "" => match profile.kind {
             ProfileDraftKind::Local => icons::by_name(&icons::default_shell_name()),
             ProfileDraftKind::Ssh => icons::ssh(),
+            ProfileDraftKind::Network => icons::by_name("terminal"),
         },
         name => icons::by_name(name),
     };
@@ -428,6 +431,14 @@ fn profile_title(profile: &ProfileDraft) -> String {
                 crate::t!("settings.ssh.new_profile").to_string()
             }
         }
+        ProfileDraftKind::Network => {
+            let host = profile.host.trim();
+            if host.is_empty() {
+                crate::t!("settings.ssh.new_profile").to_string()
+            } else {
+                host.to_string()
+            }
+        }
     }
 }
 
@@ -459,6 +470,7 @@ fn profile_subtitle(profile: &ProfileDraft) -> String {
             let auth = match profile.auth_method {
                 SshAuthMethod::KeyFile => crate::t!("settings.ssh.key_file"),
                 SshAuthMethod::Password => crate::t!("settings.ssh.password"),
+                SshAuthMethod::Agent => crate::t!("settings.ssh.agent"),
             };
             let proxy = if profile.proxy_command.trim().is_empty() {
                 ""
@@ -467,6 +479,12 @@ fn profile_subtitle(profile: &ProfileDraft) -> String {
             };
             format!("{endpoint} · {auth}{proxy}")
         }
+        ProfileDraftKind::Network => format!(
+            "{} {}:{}",
+            profile.network_protocol.scheme(),
+            empty_label(&profile.host),
+            empty_label(&profile.port)
+        ),
     }
 }
 
@@ -709,6 +727,50 @@ fn icon_picker<'a>(
         .into()
 }
 
+fn color_picker<'a>(
+    selected: &'a str,
+    palette: Palette,
+    animations_enabled: bool,
+) -> Element<'a, Message> {
+    let current = crate::config::parse_hex_color(selected.trim());
+    let buttons = TAB_COLORS.iter().map(|&rgb| {
+        let active = current == Some(rgb);
+        let [r, g, b] = rgb;
+        let next = if active {
+            String::new()
+        } else {
+            format!("#{r:02x}{g:02x}{b:02x}")
+        };
+        let swatch = container(text(""))
+            .width(Length::Fixed(14.0))
+            .height(Length::Fixed(14.0))
+            .style(move |_theme: &iced::Theme| container::Style {
+                background: Some(Background::Color(Color::from_rgb8(r, g, b))),
+                border: Border {
+                    radius: 7.0.into(),
+                    width: 0.0,
+                    color: Color::TRANSPARENT,
+                },
+                ..Default::default()
+            });
+        icon_toggle_content(
+            swatch.into(),
+            Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                ProfileField::Color,
+                next,
+            )),
+            active,
+            palette,
+            animations_enabled,
+        )
+    });
+
+    row(buttons.collect::<Vec<_>>())
+        .spacing(2)
+        .width(Length::Fill)
+        .into()
+}
+
 fn identity_column<'a>(
     profile: &'a ProfileDraft,
     palette: Palette,
@@ -729,6 +791,8 @@ fn identity_column<'a>(
         ),
         field_label(crate::t!("settings.ssh.icon"), palette),
         icon_picker(&profile.icon, palette, animations_enabled),
+        field_label(crate::t!("settings.ssh.color"), palette),
+        color_picker(&profile.color, palette, animations_enabled),
     ]
     .spacing(4)
     .width(Length::Fill)
@@ -744,7 +808,10 @@ fn detail_column<'a>(
     let mut items: Vec<Element<'a, Message>> = Vec::new();
 
     match profile.kind {
-        ProfileDraftKind::Local => local_fields(&mut items, profile, palette),
+        ProfileDraftKind::Local => local_fields(&mut items, profile, palette, animations_enabled),
+        ProfileDraftKind::Network => {
+            network_fields(&mut items, profile, palette, animations_enabled)
+        }
         ProfileDraftKind::Ssh => {
             items.push(
                 row(ProfileModalTab::ALL
@@ -762,6 +829,9 @@ fn detail_column<'a>(
                     ssh_connection_fields(&mut items, profile, palette, animations_enabled)
                 }
                 ProfileModalTab::Advanced => ssh_advanced_fields(&mut items, profile, palette),
+                ProfileModalTab::Forwards => {
+                    ssh_forward_fields(&mut items, profile, palette, animations_enabled)
+                }
             }
         }
     }
@@ -773,6 +843,7 @@ fn local_fields<'a>(
     items: &mut Vec<Element<'a, Message>>,
     profile: &'a ProfileDraft,
     palette: Palette,
+    animations_enabled: bool,
 ) {
     items.push(field_label(crate::t!("settings.ssh.shell_path"), palette));
     items.push(
@@ -790,6 +861,154 @@ fn local_fields<'a>(
         .into(),
     );
     items.push(hint(crate::t!("settings.ssh.shell_path_hint"), palette));
+    items.push(
+        checkbox(profile.login)
+            .label(crate::t!("settings.ssh.login_shell"))
+            .on_toggle(|enabled| {
+                Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                    ProfileField::Login,
+                    enabled.to_string(),
+                ))
+            })
+            .size(14)
+            .text_size(13)
+            .into(),
+    );
+    items.push(hint(crate::t!("settings.ssh.login_shell_hint"), palette));
+
+    items.push(field_label(crate::t!("settings.ssh.shell_args"), palette));
+    items.push(
+        modal_input(
+            crate::t!("settings.ssh.shell_args_placeholder"),
+            &profile.args,
+            |next| {
+                Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                    ProfileField::Args,
+                    next,
+                ))
+            },
+            palette,
+        )
+        .into(),
+    );
+    items.push(hint(crate::t!("settings.ssh.shell_args_hint"), palette));
+
+    items.push(field_label(
+        crate::t!("settings.ssh.initial_command"),
+        palette,
+    ));
+    items.push(
+        modal_input(
+            crate::t!("settings.ssh.initial_command_placeholder"),
+            &profile.initial_command,
+            |next| {
+                Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                    ProfileField::InitialCommand,
+                    next,
+                ))
+            },
+            palette,
+        )
+        .into(),
+    );
+    items.push(hint(
+        crate::t!("settings.ssh.initial_command_hint"),
+        palette,
+    ));
+
+    if cfg!(target_os = "windows") {
+        items.push(
+            checkbox(profile.elevated)
+                .label(crate::t!("settings.ssh.open_elevated"))
+                .on_toggle(|enabled| {
+                    Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                        ProfileField::Elevated,
+                        enabled.to_string(),
+                    ))
+                })
+                .size(14)
+                .text_size(13)
+                .into(),
+        );
+        items.push(hint(crate::t!("settings.ssh.open_elevated_hint"), palette));
+    }
+
+    items.push(field_label(crate::t!("settings.env.title"), palette));
+    items.push(env_editor(
+        &profile.env,
+        |edit| Message::Settings(SettingsMessage::ProfileEnvEdited(edit)),
+        palette,
+        animations_enabled,
+    ));
+    items.push(hint(crate::t!("settings.env.profile_hint"), palette));
+}
+
+fn network_fields<'a>(
+    items: &mut Vec<Element<'a, Message>>,
+    profile: &'a ProfileDraft,
+    palette: Palette,
+    animations_enabled: bool,
+) {
+    items.push(field_label(crate::t!("settings.ssh.endpoint"), palette));
+    items.push(
+        row![
+            modal_input(
+                crate::t!("settings.ssh.host"),
+                &profile.host,
+                |next| {
+                    Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                        ProfileField::Host,
+                        next,
+                    ))
+                },
+                palette
+            )
+            .width(Length::Fill),
+            text(":").size(13).color(palette.text_secondary),
+            modal_input(
+                crate::t!("settings.ssh.port"),
+                &profile.port,
+                |next| {
+                    Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                        ProfileField::Port,
+                        next,
+                    ))
+                },
+                palette
+            )
+            .width(Length::Fixed(64.0)),
+        ]
+        .spacing(4)
+        .align_y(Alignment::Center)
+        .width(Length::Fill)
+        .into(),
+    );
+
+    items.push(field_label(crate::t!("settings.ssh.protocol"), palette));
+    items.push(
+        row![
+            choice_button(
+                ProfileField::Protocol,
+                crate::t!("settings.ssh.telnet"),
+                matches!(profile.network_protocol, NetworkProtocol::Telnet),
+                "telnet",
+                palette,
+                animations_enabled,
+            ),
+            choice_button(
+                ProfileField::Protocol,
+                crate::t!("settings.ssh.raw_tcp"),
+                matches!(profile.network_protocol, NetworkProtocol::Raw),
+                "raw",
+                palette,
+                animations_enabled,
+            ),
+        ]
+        .spacing(SPACING_SMALL)
+        .width(Length::Fill)
+        .into(),
+    );
+    items.push(hint(crate::t!("settings.ssh.protocol_hint"), palette));
 }
 
 fn ssh_connection_fields<'a>(
@@ -852,20 +1071,30 @@ fn ssh_connection_fields<'a>(
     ));
     items.push(
         row![
-            auth_method_button(
+            choice_button(
+                ProfileField::AuthMethod,
                 crate::t!("settings.ssh.key_file_label"),
                 matches!(profile.auth_method, SshAuthMethod::KeyFile),
                 "key_file",
                 palette,
                 animations_enabled,
             ),
-            auth_method_button(
+            choice_button(
+                ProfileField::AuthMethod,
                 crate::t!("settings.ssh.password"),
                 matches!(profile.auth_method, SshAuthMethod::Password),
                 "password",
                 palette,
                 animations_enabled,
             ),
+            choice_button(
+                ProfileField::AuthMethod,
+                crate::t!("settings.ssh.agent"),
+                matches!(profile.auth_method, SshAuthMethod::Agent),
+                "agent",
+                palette,
+                animations_enabled,
+            ),
         ]
         .spacing(SPACING_SMALL)
         .width(Length::Fill)
@@ -888,6 +1117,8 @@ fn ssh_connection_fields<'a>(
             .into(),
         );
         items.push(hint(crate::t!("settings.ssh.auth_hint_key_file"), palette));
+    } else if matches!(profile.auth_method, SshAuthMethod::Agent) {
+        items.push(hint(crate::t!("settings.ssh.auth_hint_agent"), palette));
     } else {
         items.push(
             modal_password(
@@ -936,9 +1167,166 @@ fn ssh_advanced_fields<'a>(
         );
     }
     items.push(hint(crate::t!("settings.ssh.proxy_command_hint"), palette));
+
+    items.push(field_label(
+        crate::t!("settings.ssh.keepalive_interval"),
+        palette,
+    ));
+    items.push(
+        modal_input(
+            crate::t!("settings.ssh.keepalive_interval"),
+            &profile.keepalive_interval,
+            |next| {
+                Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                    ProfileField::KeepaliveInterval,
+                    next,
+                ))
+            },
+            palette,
+        )
+        .width(Length::Fixed(96.0))
+        .into(),
+    );
+    items.push(hint(
+        crate::t!("settings.ssh.keepalive_interval_hint"),
+        palette,
+    ));
+    items.push(
+        checkbox(profile.auto_reconnect)
+            .label(crate::t!("settings.ssh.auto_reconnect"))
+            .on_toggle(|enabled| {
+                Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                    ProfileField::AutoReconnect,
+                    enabled.to_string(),
+                ))
+            })
+            .size(14)
+            .text_size(13)
+            .into(),
+    );
+    items.push(hint(crate::t!("settings.ssh.auto_reconnect_hint"), palette));
+    items.push(
+        checkbox(profile.remote_attach)
+            .label(crate::t!("settings.ssh.remote_attach"))
+            .on_toggle(|enabled| {
+                Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                    ProfileField::RemoteAttach,
+                    enabled.to_string(),
+                ))
+            })
+            .size(14)
+            .text_size(13)
+            .into(),
+    );
+    items.push(hint(crate::t!("settings.ssh.remote_attach_hint"), palette));
+    items.push(
+        checkbox(profile.forward_agent)
+            .label(crate::t!("settings.ssh.forward_agent"))
+            .on_toggle(|enabled| {
+                Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                    ProfileField::ForwardAgent,
+                    enabled.to_string(),
+                ))
+            })
+            .size(14)
+            .text_size(13)
+            .into(),
+    );
+    items.push(hint(crate::t!("settings.ssh.forward_agent_hint"), palette));
+}
+
+fn ssh_forward_fields<'a>(
+    items: &mut Vec<Element<'a, Message>>,
+    profile: &'a ProfileDraft,
+    palette: Palette,
+    animations_enabled: bool,
+) {
+    if profile.forwards.is_empty() {
+        items.push(hint(crate::t!("settings.ssh.forwards_empty"), palette));
+    }
+    for (index, forward) in profile.forwards.iter().enumerate() {
+        let kinds = ForwardKind::ALL.into_iter().map(|kind| {
+            let (label, value) = match kind {
+                ForwardKind::Local => (crate::t!("settings.ssh.forward_local"), "local"),
+                ForwardKind::Remote => (crate::t!("settings.ssh.forward_remote"), "remote"),
+                ForwardKind::Dynamic => (crate::t!("settings.ssh.forward_dynamic"), "dynamic"),
+            };
+            choice_button(
+                ProfileField::ForwardKind(index),
+                label,
+                forward.kind == kind,
+                value,
+                palette,
+                animations_enabled,
+            )
+        });
+        let placeholder = if forward.kind == ForwardKind::Dynamic {
+            crate::t!("settings.ssh.forward_dynamic_placeholder")
+        } else {
+            crate::t!("settings.ssh.forward_placeholder")
+        };
+        items.push(
+            row(kinds)
+                .push(
+                    modal_input(
+                        placeholder,
+                        &forward.spec,
+                        move |next| {
+                            Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                                ProfileField::ForwardSpec(index),
+                                next,
+                            ))
+                        },
+                        palette,
+                    )
+                    .width(Length::Fill),
+                )
+                .push(
+                    checkbox(forward.enabled)
+                        .on_toggle(move |enabled| {
+                            Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                                ProfileField::ForwardEnabled(index),
+                                enabled.to_string(),
+                            ))
+                        })
+                        .size(14),
+                )
+                .push(button_icon(
+                    "x",
+                    Message::Settings(SettingsMessage::ProfileModalFieldChanged(
+                        ProfileField::RemoveForward(index),
+                        String::new(),
+                    )),
+                    palette,
+                    animations_enabled,
+                ))
+                .spacing(4)
+                .align_y(Alignment::Center)
+                .width(Length::Fill)
+                .into(),
+        );
+        if !forward.spec.trim().is_empty() && forward.to_forward().is_none() {
+            items.push(
+                text(crate::t!("settings.ssh.forward_invalid"))
+                    .size(10)
+                    .color(palette.error)
+                    .into(),
+            );
+        }
+    }
+    items.push(secondary(
+        crate::t!("settings.ssh.add_forward"),
+        Some(Message::Settings(
+            SettingsMessage::ProfileModalFieldChanged(ProfileField::AddForward, String::new()),
+        )),
+        palette,
+        animations_enabled,
+    ));
+    items.push(hint(crate::t!("settings.ssh.forwards_hint"), palette));
 }
 
-fn auth_method_button<'a>(
+fn choice_button<'a>(
+    field: ProfileField,
     label: &'a str,
     selected: bool,
     value: &'static str,
@@ -946,7 +1334,7 @@ fn auth_method_button<'a>(
     animations_enabled: bool,
 ) -> Element<'a, Message> {
     let message = Message::Settings(SettingsMessage::ProfileModalFieldChanged(
-        ProfileField::AuthMethod,
+        field,
         value.into(),
     ));
     if selected {
@@ -1,13 +1,20 @@
-use crate::config::{AppConfig, BellMode, CursorShape, RightClickAction};
+use crate::config::Snippet;
+use crate::config::{
+    AppConfig, BellMode, CursorShape, ExitBehavior, RightClickAction, TimestampMode,
+};
 use crate::gui::app::{Message, SettingsMessage};
 use crate::gui::components::accent_toggler_style;
+use crate::gui::components::{button_icon, secondary};
 use crate::gui::settings::{ROW_SPACING, SECTION_SPACING};
 use crate::gui::settings::{
-    SettingsDraft, SettingsField, input_row_with_suffix, section, segmented_control, setting_row,
+    SettingsDraft, SettingsField, SnippetEdit, env_editor, hint_text, input_row, section,
+    segmented_control, setting_row, stepper_row, with_field_error,
 };
 use crate::gui::theme::Palette;
-use iced::widget::{column, toggler};
-use iced::{Element, Length};
+use crate::gui::theme::SPACING_SMALL;
+use crate::session::integration::IntegrationReport;
+use iced::widget::{column, row, text, toggler};
+use iced::{Alignment, Element, Length};
 
 pub fn view<'a>(
     config: &'a AppConfig,
@@ -17,20 +24,47 @@ pub fn view<'a>(
     let scrollback_section = section(
         crate::t!("settings.terminal.scrolling_section"),
         column(vec![
-            input_row_with_suffix(
+            stepper_row(
                 crate::t!("settings.terminal.scrollback"),
-                &draft.terminal_scrollback,
+                draft,
                 SettingsField::TerminalScrollback,
                 crate::t!("settings.terminal.scrollback_suffix"),
                 palette,
+                config.ui.animations_enabled,
             ),
-            input_row_with_suffix(
+            stepper_row(
                 crate::t!("settings.terminal.scroll_speed"),
-                &draft.terminal_scroll_speed,
+                draft,
                 SettingsField::TerminalScrollSpeed,
                 "x",
                 palette,
+                config.ui.animations_enabled,
+            ),
+            setting_row(
+                crate::t!("settings.terminal.smooth_scroll"),
+                toggler(draft.smooth_scroll)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::SmoothScrollToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            hint_text(crate::t!("settings.terminal.smooth_scroll_hint"), palette),
+            segmented_control(
+                crate::t!("settings.terminal.timestamps_label"),
+                TimestampMode::ALL
+                    .iter()
+                    .map(|&mode| {
+                        (
+                            timestamp_mode_label(mode),
+                            Message::Settings(SettingsMessage::TimestampModeSelected(mode)),
+                            draft.timestamps == mode,
+                        )
+                    })
+                    .collect(),
+                palette,
+                config.ui.animations_enabled,
             ),
+            hint_text(crate::t!("settings.terminal.timestamps_hint"), palette),
         ])
         .spacing(ROW_SPACING)
         .width(Length::Fill)
@@ -59,6 +93,22 @@ pub fn view<'a>(
                     .style(accent_toggler_style(palette)),
                 palette,
             ),
+            stepper_row(
+                crate::t!("settings.terminal.large_paste_lines"),
+                draft,
+                SettingsField::TerminalLargePasteLines,
+                crate::t!("settings.terminal.scrollback_suffix"),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            stepper_row(
+                crate::t!("settings.terminal.large_paste_size"),
+                draft,
+                SettingsField::TerminalLargePasteKib,
+                "KiB",
+                palette,
+                config.ui.animations_enabled,
+            ),
         ])
         .spacing(ROW_SPACING)
         .width(Length::Fill)
@@ -92,6 +142,63 @@ pub fn view<'a>(
                     .style(accent_toggler_style(palette)),
                 palette,
             ),
+            setting_row(
+                crate::t!("settings.terminal.cursor_trail"),
+                toggler(draft.cursor_trail)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::CursorTrailToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            stepper_row(
+                crate::t!("settings.terminal.cursor_trail_duration"),
+                draft,
+                SettingsField::TerminalCursorTrailMs,
+                crate::t!("settings.terminal.milliseconds_suffix"),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            hint_text(crate::t!("settings.terminal.cursor_trail_hint"), palette),
+            setting_row(
+                crate::t!("settings.terminal.highlight_cursor_row"),
+                toggler(draft.highlight_cursor_row)
+                    .on_toggle(|a0| {
+                        Message::Settings(SettingsMessage::HighlightCursorRowToggled(a0))
+                    })
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            setting_row(
+                crate::t!("settings.terminal.wrap_indicator"),
+                toggler(draft.wrap_indicator)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::WrapIndicatorToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            hint_text(crate::t!("settings.terminal.wrap_indicator_hint"), palette),
+            setting_row(
+                crate::t!("settings.terminal.local_echo"),
+                toggler(draft.local_echo)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::LocalEchoToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            hint_text(crate::t!("settings.terminal.local_echo_hint"), palette),
+            with_field_error(
+                input_row(
+                    crate::t!("settings.terminal.column_rulers"),
+                    &draft.column_rulers,
+                    SettingsField::TerminalColumnRulers,
+                    palette,
+                ),
+                draft,
+                SettingsField::TerminalColumnRulers,
+                palette,
+            ),
+            hint_text(crate::t!("settings.terminal.column_rulers_hint"), palette),
         ])
         .spacing(ROW_SPACING)
         .width(Length::Fill)
@@ -114,55 +221,412 @@ pub fn view<'a>(
 
     let bell_section = section(
         crate::t!("settings.terminal.bell_section"),
-        segmented_control(
-            crate::t!("settings.terminal.behavior"),
-            BellMode::ALL
-                .iter()
-                .map(|&mode| {
-                    (
-                        bell_mode_label(mode),
-                        Message::Settings(SettingsMessage::BellModeSelected(mode)),
-                        draft.bell_mode == mode,
-                    )
-                })
-                .collect(),
-            palette,
-            config.ui.animations_enabled,
-        ),
+        column(vec![
+            segmented_control(
+                crate::t!("settings.terminal.behavior"),
+                BellMode::ALL
+                    .iter()
+                    .map(|&mode| {
+                        (
+                            bell_mode_label(mode),
+                            Message::Settings(SettingsMessage::BellModeSelected(mode)),
+                            draft.bell_mode == mode,
+                        )
+                    })
+                    .collect(),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            setting_row(
+                crate::t!("settings.terminal.bell_attention"),
+                toggler(draft.bell_attention)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::BellAttentionToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            hint_text(crate::t!("settings.terminal.bell_attention_hint"), palette),
+            stepper_row(
+                crate::t!("settings.terminal.notify_command"),
+                draft,
+                SettingsField::TerminalNotifyCommandSecs,
+                crate::t!("settings.terminal.seconds_suffix"),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            hint_text(crate::t!("settings.terminal.notify_command_hint"), palette),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    let reports_section = section(
+        crate::t!("settings.terminal.reports_section"),
+        column(vec![
+            input_row(
+                crate::t!("settings.terminal.answerback"),
+                &draft.answerback,
+                SettingsField::TerminalAnswerback,
+                palette,
+            ),
+            hint_text(crate::t!("settings.terminal.answerback_hint"), palette),
+            setting_row(
+                crate::t!("settings.terminal.tmux_passthrough"),
+                toggler(draft.tmux_passthrough)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::TmuxPassthroughToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            hint_text(
+                crate::t!("settings.terminal.tmux_passthrough_hint"),
+                palette,
+            ),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
         palette,
     );
 
     let mouse_section = section(
         crate::t!("settings.terminal.mouse_section"),
-        segmented_control(
-            crate::t!("settings.terminal.right_click"),
-            RightClickAction::ALL
-                .iter()
-                .map(|&action| {
-                    (
-                        right_click_action_label(action),
-                        Message::Settings(SettingsMessage::RightClickActionSelected(action)),
-                        draft.right_click_action == action,
-                    )
-                })
-                .collect(),
+        column(vec![
+            segmented_control(
+                crate::t!("settings.terminal.right_click"),
+                RightClickAction::ALL
+                    .iter()
+                    .map(|&action| {
+                        (
+                            right_click_action_label(action),
+                            Message::Settings(SettingsMessage::RightClickActionSelected(action)),
+                            draft.right_click_action == action,
+                        )
+                    })
+                    .collect(),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            input_row(
+                crate::t!("settings.terminal.editor_command"),
+                &draft.editor_command,
+                SettingsField::TerminalEditorCommand,
+                palette,
+            ),
+            hint_text(crate::t!("settings.terminal.editor_command_hint"), palette),
+            setting_row(
+                crate::t!("settings.terminal.confirm_links"),
+                toggler(draft.confirm_links)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::ConfirmLinksToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            input_row(
+                crate::t!("settings.terminal.link_schemes"),
+                &draft.link_schemes,
+                SettingsField::TerminalLinkSchemes,
+                palette,
+            ),
+            hint_text(crate::t!("settings.terminal.link_schemes_hint"), palette),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    let integration_section = section(
+        crate::t!("settings.terminal.integration_section"),
+        integration_check(
+            draft.shell_integration.as_ref(),
+            draft.shell_integration_error.as_deref(),
             palette,
             config.ui.animations_enabled,
         ),
         palette,
     );
 
-    column(vec![
+    let sessions_section = section(
+        crate::t!("settings.terminal.sessions_section"),
+        column(vec![
+            setting_row(
+                crate::t!("settings.terminal.persistent_sessions"),
+                toggler(draft.persistent_sessions)
+                    .on_toggle(|a0| {
+                        Message::Settings(SettingsMessage::PersistentSessionsToggled(a0))
+                    })
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            hint_text(
+                crate::t!("settings.terminal.persistent_sessions_hint"),
+                palette,
+            ),
+            segmented_control(
+                crate::t!("settings.terminal.exit_behavior"),
+                ExitBehavior::ALL
+                    .iter()
+                    .map(|&behavior| {
+                        (
+                            exit_behavior_label(behavior),
+                            Message::Settings(SettingsMessage::ExitBehaviorSelected(behavior)),
+                            draft.exit_behavior == behavior,
+                        )
+                    })
+                    .collect(),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            hint_text(crate::t!("settings.terminal.exit_behavior_hint"), palette),
+            stepper_row(
+                crate::t!("settings.terminal.idle_lock"),
+                draft,
+                SettingsField::TerminalIdleLockMinutes,
+                crate::t!("settings.terminal.minutes_suffix"),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            hint_text(crate::t!("settings.terminal.idle_lock_hint"), palette),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    #[cfg(target_os = "macos")]
+    let secure_input_section = section(
+        crate::t!("settings.terminal.secure_input_section"),
+        column(vec![
+            setting_row(
+                crate::t!("settings.terminal.secure_keyboard_entry"),
+                toggler(draft.secure_keyboard_entry)
+                    .on_toggle(|a0| {
+                        Message::Settings(SettingsMessage::SecureKeyboardEntryToggled(a0))
+                    })
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            hint_text(
+                crate::t!("settings.terminal.secure_keyboard_entry_hint"),
+                palette,
+            ),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    let env_section = section(
+        crate::t!("settings.env.title"),
+        column(vec![
+            env_editor(
+                &draft.env,
+                |edit| Message::Settings(SettingsMessage::GlobalEnvEdited(edit)),
+                palette,
+                config.ui.animations_enabled,
+            ),
+            hint_text(crate::t!("settings.env.global_hint"), palette),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    let autocomplete_section = section(
+        crate::t!("settings.terminal.autocomplete_section"),
+        column(vec![
+            setting_row(
+                crate::t!("settings.terminal.autocomplete"),
+                toggler(draft.autocomplete)
+                    .on_toggle(|a0| Message::Settings(SettingsMessage::AutocompleteToggled(a0)))
+                    .size(18)
+                    .style(accent_toggler_style(palette)),
+                palette,
+            ),
+            hint_text(crate::t!("settings.terminal.autocomplete_hint"), palette),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    let assist_section = section(
+        crate::t!("settings.terminal.assist_section"),
+        column(vec![
+            input_row(
+                crate::t!("settings.terminal.assist_command"),
+                &draft.assist_command,
+                SettingsField::TerminalAssistCommand,
+                palette,
+            ),
+            hint_text(crate::t!("settings.terminal.assist_command_hint"), palette),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    let snippets_section = section(
+        crate::t!("settings.snippets.title"),
+        column(vec![
+            snippet_editor(&draft.snippets, palette, config.ui.animations_enabled),
+            hint_text(crate::t!("settings.snippets.hint"), palette),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
+    let mut sections = vec![
         scrollback_section,
         paste_section,
         cursor_section,
         colors_section,
         bell_section,
+        reports_section,
         mouse_section,
-    ])
-    .spacing(SECTION_SPACING)
-    .width(Length::Fill)
-    .into()
+        integration_section,
+        sessions_section,
+    ];
+    #[cfg(target_os = "macos")]
+    sections.push(secure_input_section);
+    sections.extend([
+        env_section,
+        autocomplete_section,
+        assist_section,
+        snippets_section,
+    ]);
+
+    column(sections)
+        .spacing(SECTION_SPACING)
+        .width(Length::Fill)
+        .into()
+}
+
+/// One row per snippet: its name, its command template and a remove button.
+fn snippet_editor<'a>(
+    rows: &'a [Snippet],
+    palette: Palette,
+    animations_enabled: bool,
+) -> Element<'a, Message> {
+    let edit = |edit| Message::Settings(SettingsMessage::SnippetEdited(edit));
+    let mut items: Vec<Element<'a, Message>> = rows
+        .iter()
+        .enumerate()
+        .map(|(index, snippet)| {
+            row![
+                super::placeholder_text_input(
+                    crate::t!("settings.snippets.name"),
+                    &snippet.name,
+                    move |next| edit(SnippetEdit::Name(index, next)),
+                    palette,
+                )
+                .width(Length::FillPortion(2)),
+                super::placeholder_text_input(
+                    crate::t!("settings.snippets.command"),
+                    &snippet.command,
+                    move |next| edit(SnippetEdit::Command(index, next)),
+                    palette,
+                )
+                .width(Length::FillPortion(5)),
+                button_icon(
+                    "x",
+                    edit(SnippetEdit::Remove(index)),
+                    palette,
+                    animations_enabled
+                ),
+            ]
+            .spacing(SPACING_SMALL)
+            .align_y(Alignment::Center)
+            .width(Length::Fill)
+            .into()
+        })
+        .collect();
+    items.push(secondary(
+        crate::t!("settings.snippets.add"),
+        Some(edit(SnippetEdit::Add)),
+        palette,
+        animations_enabled,
+    ));
+    column(items)
+        .spacing(SPACING_SMALL)
+        .width(Length::Fill)
+        .into()
+}
+
+/// What the last check found, with an install button per shell, and the
+/// button that runs the check.
+fn integration_check<'a>(
+    report: Option<&IntegrationReport>,
+    error: Option<&'a str>,
+    palette: Palette,
+    animations_enabled: bool,
+) -> Element<'a, Message> {
+    let mut items = vec![hint_text(
+        crate::t!("settings.terminal.integration_hint"),
+        palette,
+    )];
+    if let Some(report) = report {
+        for &(shell, installed) in &report.installed {
+            let (status, action) = if installed {
+                (
+                    crate::t!("settings.terminal.integration_installed"),
+                    crate::t!("settings.terminal.integration_reinstall"),
+                )
+            } else {
+                (
+                    crate::t!("settings.terminal.integration_missing"),
+                    crate::t!("settings.terminal.integration_install"),
+                )
+            };
+            items.push(setting_row(
+                shell.name(),
+                row![
+                    text(status).size(13).color(palette.text_secondary),
+                    secondary(
+                        action,
+                        Some(Message::Settings(SettingsMessage::InstallShellIntegration(
+                            shell
+                        ))),
+                        palette,
+                        animations_enabled,
+                    ),
+                ]
+                .spacing(SPACING_SMALL)
+                .align_y(Alignment::Center),
+                palette,
+            ));
+        }
+        let reporting = crate::t!("settings.terminal.integration_reporting")
+            .replace("{prompts}", &report.prompts.to_string())
+            .replace("{directories}", &report.directories.to_string())
+            .replace("{panes}", &report.panes.to_string());
+        items.push(text(reporting).size(13).color(palette.text).into());
+    }
+    if let Some(error) = error {
+        items.push(text(error).size(13).color(palette.error).into());
+    }
+    items.push(secondary(
+        crate::t!("settings.terminal.integration_check"),
+        Some(Message::Settings(SettingsMessage::CheckShellIntegration)),
+        palette,
+        animations_enabled,
+    ));
+    column(items)
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into()
 }
 
 /// Glyphs that visually represent each cursor shape in the segmented control.
@@ -182,6 +646,24 @@ fn bell_mode_label(mode: BellMode) -> &'static str {
     }
 }
 
+fn timestamp_mode_label(mode: TimestampMode) -> &'static str {
+    match mode {
+        TimestampMode::Off => crate::t!("settings.terminal.timestamps.off"),
+        TimestampMode::Relative => crate::t!("settings.terminal.timestamps.relative"),
+        TimestampMode::Absolute => crate::t!("settings.terminal.timestamps.absolute"),
+    }
+}
+
+fn exit_behavior_label(behavior: ExitBehavior) -> &'static str {
+    match behavior {
+        ExitBehavior::Close => crate::t!("settings.terminal.exit_behavior_option.close"),
+        ExitBehavior::CloseOnSuccess => {
+            crate::t!("settings.terminal.exit_behavior_option.close_on_success")
+        }
+        ExitBehavior::Hold => crate::t!("settings.terminal.exit_behavior_option.hold"),
+    }
+}
+
 fn right_click_action_label(action: RightClickAction) -> &'static str {
     match action {
         RightClickAction::Paste => crate::t!("settings.terminal.right_click_action.paste"),
@@ -1,19 +1,26 @@
 use crate::config::AppConfig;
-use crate::gui::app::{Message, SettingsMessage};
+use crate::gui::app::{ColorPickerMessage, Message, SettingsMessage};
+use crate::gui::settings::color_picker::ansi_name;
 use crate::gui::settings::{
-    ROW_SPACING, SECTION_SPACING, SettingsDraft, SettingsField, format_rgb, hint_text,
-    input_row_with_suffix, section, toggle_row,
+    ROW_SPACING, SECTION_SPACING, SettingsDraft, SettingsField, format_rgb, hint_text, section,
+    slider_row, toggle_row, with_field_error,
 };
 use crate::gui::theme::{Palette, RADIUS_SMALL, SPACING_NORMAL};
 use crate::terminal::theme::{ColorPreset, all_presets};
-use iced::widget::{Column, Row, Space, button, column, container, row, text};
+use iced::widget::{Column, Row, Space, button, column, container, row, text, tooltip};
 use iced::{Background, Border, Color, Element, Length};
 
 pub fn view<'a>(
-    _config: &'a AppConfig,
+    config: &'a AppConfig,
     draft: &'a SettingsDraft,
     palette: Palette,
 ) -> Element<'a, Message> {
+    let preview_section = section(
+        crate::t!("settings.theme.preview_section"),
+        preview(config, draft),
+        palette,
+    );
+
     // -- Preset picker: 2-column grid of visual cards --
     let grid_rows = build_preset_grid(draft, &palette);
 
@@ -28,29 +35,45 @@ pub fn view<'a>(
 
     // -- Color palette pickers for fg/bg/cursor --
     let current_preset = crate::terminal::theme::find_preset(&draft.color_scheme);
+    let swatches = build_palette_options(current_preset, config);
     let colors_section = section(
         crate::t!("settings.theme.colors_section"),
         column(vec![
-            color_palette_row(
-                crate::t!("settings.theme.foreground"),
-                &draft.foreground,
+            with_field_error(
+                color_palette_row(
+                    crate::t!("settings.theme.foreground"),
+                    &draft.foreground,
+                    SettingsField::ThemeForeground,
+                    &swatches,
+                    &palette,
+                ),
+                draft,
                 SettingsField::ThemeForeground,
-                current_preset,
-                &palette,
+                palette,
             ),
-            color_palette_row(
-                crate::t!("settings.theme.background"),
-                &draft.background,
+            with_field_error(
+                color_palette_row(
+                    crate::t!("settings.theme.background"),
+                    &draft.background,
+                    SettingsField::ThemeBackground,
+                    &swatches,
+                    &palette,
+                ),
+                draft,
                 SettingsField::ThemeBackground,
-                current_preset,
-                &palette,
+                palette,
             ),
-            color_palette_row(
-                crate::t!("settings.theme.cursor"),
-                &draft.cursor,
+            with_field_error(
+                color_palette_row(
+                    crate::t!("settings.theme.cursor"),
+                    &draft.cursor,
+                    SettingsField::ThemeCursor,
+                    &swatches,
+                    &palette,
+                ),
+                draft,
                 SettingsField::ThemeCursor,
-                current_preset,
-                &palette,
+                palette,
             ),
             hint_text(crate::t!("settings.theme.colors_hint"), palette),
         ])
@@ -60,13 +83,25 @@ pub fn view<'a>(
         palette,
     );
 
+    let ansi_section = section(
+        crate::t!("settings.theme.ansi_section"),
+        column(vec![
+            ansi_grid(draft, &palette),
+            hint_text(crate::t!("settings.theme.ansi_hint"), palette),
+        ])
+        .spacing(ROW_SPACING)
+        .width(Length::Fill)
+        .into(),
+        palette,
+    );
+
     let opacity_section = section(
         crate::t!("settings.theme.opacity_section"),
-        column(vec![input_row_with_suffix(
+        column(vec![slider_row(
             crate::t!("settings.theme.background_opacity"),
-            &draft.background_opacity,
+            draft,
             SettingsField::ThemeBackgroundOpacity,
-            "0.0 ~ 1.0",
+            "",
             palette,
         )])
         .spacing(ROW_SPACING)
@@ -92,11 +127,11 @@ pub fn view<'a>(
     let macos_blur_section = section(
         crate::t!("settings.theme.macos_blur_section"),
         column(vec![
-            input_row_with_suffix(
+            slider_row(
                 crate::t!("settings.theme.blur_radius"),
-                &draft.macos_blur_radius,
+                draft,
                 SettingsField::ThemeMacosBlurRadius,
-                "0 ~ 100",
+                "",
                 palette,
             ),
             hint_text(crate::t!("settings.theme.blur_radius_hint"), palette),
@@ -109,8 +144,10 @@ pub fn view<'a>(
 
     #[cfg(target_os = "macos")]
     let sections = vec![
+        preview_section,
         presets_section,
         colors_section,
+        ansi_section,
         opacity_section,
         blur_section,
         macos_blur_section,
@@ -118,8 +155,10 @@ pub fn view<'a>(
 
     #[cfg(not(target_os = "macos"))]
     let sections = vec![
+        preview_section,
         presets_section,
         colors_section,
+        ansi_section,
         opacity_section,
         blur_section,
     ];
@@ -130,6 +169,111 @@ pub fn view<'a>(
         .into()
 }
 
+/// Sample output drawn with the draft's colors, so a change can be judged
+/// before it is typed out in full. Colors that do not parse yet fall back
+/// to the applied ones.
+fn preview<'a>(config: &AppConfig, draft: &SettingsDraft) -> Element<'a, Message> {
+    let color = |hex: &str, fallback: [u8; 3]| {
+        let rgb = crate::config::parse_hex_color(hex).unwrap_or(fallback);
+        Color::from_rgb8(rgb[0], rgb[1], rgb[2])
+    };
+    let fg = color(&draft.foreground, config.theme.foreground);
+    let bg = color(&draft.background, config.theme.background);
+    let cursor = color(&draft.cursor, config.theme.cursor);
+    let applied = config
+        .theme
+        .ansi_colors
+        .or_else(|| crate::terminal::theme::find_preset(&config.theme.color_scheme).map(|p| p.ansi))
+        .unwrap_or(all_presets()[0].ansi);
+    let ansi: [Color; 16] =
+        std::array::from_fn(|index| color(&draft.ansi_colors[index], applied[index]));
+    let opacity = draft
+        .number(SettingsField::ThemeBackgroundOpacity)
+        .unwrap_or(config.theme.background_opacity);
+
+    let (red, green, yellow, blue, magenta, cyan) =
+        (ansi[1], ansi[2], ansi[3], ansi[4], ansi[5], ansi[6]);
+    let prompt = |command: &'static str| vec![("$ ", green), (command, fg)];
+    let lines: Vec<Vec<(&'static str, Color)>> = vec![
+        prompt("ls"),
+        vec![
+            ("src/  ", blue),
+            ("build.sh  ", green),
+            ("notes.txt  ", fg),
+            ("backup.tar.gz  ", red),
+            ("latest -> src/", cyan),
+        ],
+        prompt("git diff"),
+        vec![("@@ -1,2 +1,2 @@", cyan)],
+        vec![("-let color = \"old\";", red)],
+        vec![("+let color = \"new\";", green)],
+        prompt("htop"),
+        vec![
+            ("  CPU [", fg),
+            ("||||||", green),
+            ("|||", red),
+            ("        42%]  ", fg),
+            ("Mem [", fg),
+            ("|||||", yellow),
+            ("  1.2G/8G]", fg),
+        ],
+        vec![
+            ("  PID ", ansi[8]),
+            ("USER    ", magenta),
+            ("%CPU ", yellow),
+            ("COMMAND", fg),
+        ],
+        vec![("  812 rabbit  12.0 ", fg), ("cargo build", ansi[12])],
+        vec![
+            ("  ", fg),
+            ("warning: ", yellow),
+            ("unused variable", ansi[7]),
+        ],
+    ];
+
+    let mut rows: Vec<Element<'a, Message>> = lines
+        .into_iter()
+        .map(|spans| {
+            Row::with_children(
+                spans
+                    .into_iter()
+                    .map(|(segment, color)| preview_text(segment).color(color).into()),
+            )
+            .into()
+        })
+        .collect();
+    rows.push(
+        row![
+            preview_text("$ ").color(green),
+            container(preview_text(" ").color(bg)).style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(Background::Color(cursor)),
+                    ..Default::default()
+                }
+            }),
+        ]
+        .into(),
+    );
+
+    container(Column::with_children(rows).spacing(2))
+        .padding([12, 14])
+        .width(Length::Fill)
+        .style(move |_theme: &iced::Theme| container::Style {
+            background: Some(Background::Color(Color { a: opacity, ..bg })),
+            border: Border {
+                radius: RADIUS_SMALL.into(),
+                width: 1.0,
+                color: Color { a: 0.15, ..fg },
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+fn preview_text<'a>(segment: &'static str) -> iced::widget::Text<'a> {
+    text(segment).size(13).font(iced::Font::MONOSPACE)
+}
+
 /// Build preset cards in a 2-column grid.
 fn build_preset_grid<'a>(draft: &'a SettingsDraft, palette: &Palette) -> Vec<Element<'a, Message>> {
     let cards: Vec<Element<'a, Message>> = all_presets()
@@ -238,7 +382,7 @@ fn color_palette_row<'a>(
     label: &'a str,
     current_hex: &'a str,
     field: SettingsField,
-    preset: Option<&'static ColorPreset>,
+    options: &[[u8; 3]],
     palette: &Palette,
 ) -> Element<'a, Message> {
     let parsed = crate::config::parse_hex_color(current_hex);
@@ -246,10 +390,7 @@ fn color_palette_row<'a>(
         .map(|rgb| Color::from_rgb8(rgb[0], rgb[1], rgb[2]))
         .unwrap_or(palette.error);
 
-    // Build palette of clickable colors from the preset
-    let palette_colors = build_palette_options(preset);
-
-    let swatches: Vec<Element<'a, Message>> = palette_colors
+    let swatches: Vec<Element<'a, Message>> = options
         .iter()
         .map(|&c| {
             let is_current = parsed.is_some_and(|p| p == c);
@@ -312,21 +453,7 @@ fn color_palette_row<'a>(
         row![
             text(label).size(13),
             Space::new().width(Length::Fill),
-            container("")
-                .width(Length::Fixed(20.0))
-                .height(Length::Fixed(20.0))
-                .style(move |_theme: &iced::Theme| container::Style {
-                    background: Some(Background::Color(current_color)),
-                    border: Border {
-                        radius: 4.0.into(),
-                        width: 1.0,
-                        color: Color {
-                            a: 0.25,
-                            ..Color::WHITE
-                        },
-                    },
-                    ..Default::default()
-                }),
+            picker_swatch(current_color, field, 20.0),
             hex_input,
         ]
         .align_y(iced::Alignment::Center)
@@ -341,19 +468,93 @@ fn color_palette_row<'a>(
     .into()
 }
 
-/// Build a unique set of palette colors from the current preset.
-fn build_palette_options(preset: Option<&'static ColorPreset>) -> Vec<[u8; 3]> {
-    let Some(preset) = preset else {
-        return vec![];
+/// A swatch that opens the color picker for `field`.
+fn picker_swatch<'a>(color: Color, field: SettingsField, size: f32) -> Element<'a, Message> {
+    button(
+        container("")
+            .width(Length::Fixed(size))
+            .height(Length::Fixed(size))
+            .style(move |_theme: &iced::Theme| container::Style {
+                background: Some(Background::Color(color)),
+                border: Border {
+                    radius: 4.0.into(),
+                    width: 1.0,
+                    color: Color {
+                        a: 0.25,
+                        ..Color::WHITE
+                    },
+                },
+                ..Default::default()
+            }),
+    )
+    .on_press(Message::Settings(SettingsMessage::ColorPicker(
+        ColorPickerMessage::Open(field),
+    )))
+    .padding(0)
+    .style(|_theme: &iced::Theme, _status| button::Style {
+        background: None,
+        text_color: Color::TRANSPARENT,
+        border: Border::default(),
+        shadow: Default::default(),
+        snap: true,
+    })
+    .into()
+}
+
+/// The 16 ANSI colors as normal and bright rows, each opening the picker.
+fn ansi_grid<'a>(draft: &'a SettingsDraft, palette: &Palette) -> Element<'a, Message> {
+    let swatch_row = |bright: bool| {
+        let offset = if bright { 8 } else { 0 };
+        let swatches = (offset..offset + 8).map(|index| {
+            let color = crate::config::parse_hex_color(&draft.ansi_colors[index])
+                .map(|rgb| Color::from_rgb8(rgb[0], rgb[1], rgb[2]))
+                .unwrap_or(palette.error);
+            tooltip(
+                picker_swatch(color, SettingsField::ThemeAnsi(index), 26.0),
+                text(ansi_name(index)).size(12),
+                tooltip::Position::Top,
+            )
+            .into()
+        });
+        let label = if bright {
+            crate::t!("settings.theme.ansi_bright_row")
+        } else {
+            crate::t!("settings.theme.ansi_normal_row")
+        };
+        row![
+            text(label).size(13),
+            Space::new().width(Length::Fill),
+            Row::with_children(swatches).spacing(6),
+        ]
+        .align_y(iced::Alignment::Center)
+        .width(Length::Fill)
+    };
+    column![swatch_row(false), swatch_row(true)]
+        .spacing(SPACING_NORMAL)
+        .width(Length::Fill)
+        .into()
+}
+
+/// A unique set of palette colors from the current preset, or from the
+/// applied theme when the scheme is a custom one.
+fn build_palette_options(preset: Option<&'static ColorPreset>, config: &AppConfig) -> Vec<[u8; 3]> {
+    let (fg, bg, cursor, ansi) = match preset {
+        Some(preset) => (preset.fg, preset.bg, preset.cursor, Some(preset.ansi)),
+        None => (
+            config.theme.foreground,
+            config.theme.background,
+            config.theme.cursor,
+            config.theme.ansi_colors,
+        ),
     };
 
     let mut colors: Vec<[u8; 3]> = Vec::with_capacity(20);
-    colors.push(preset.fg);
-    colors.push(preset.bg);
-    colors.push(preset.cursor);
-    for c in &preset.ansi {
-        if !colors.contains(c) {
-            colors.push(*c);
+    for c in [fg, bg, cursor]
+        .into_iter()
+        .chain(ansi.into_iter().flatten())
+    {
+        if !colors.contains(&c) {
+            colors.push(c);
         }
     }
     colors
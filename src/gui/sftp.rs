@@ -27,6 +27,8 @@ pub struct SftpDrawerState {
     pub command_tx: Option<mpsc::UnboundedSender<sftp::Command>>,
     pub height_ratio: f32,
     pub anim: Animation<bool>,
+    /// Files from the OS file manager are being dragged over the window.
+    pub drop_hover: bool,
 }
 
 impl Default for SftpDrawerState {
@@ -76,9 +78,16 @@ impl SftpDrawerState {
             anim: Animation::new(false)
                 .duration(std::time::Duration::from_millis(220))
                 .easing(iced::animation::Easing::EaseOutQuint),
+            drop_hover: false,
         }
     }
 
+    /// Dropped files upload into the current directory while the drawer is
+    /// open and connected.
+    pub fn accepts_drop(&self) -> bool {
+        self.open && self.command_tx.is_some()
+    }
+
     pub fn reset(&mut self) {
         self.open = false;
         self.opening = false;
@@ -87,6 +96,7 @@ impl SftpDrawerState {
         self.entries.clear();
         self.transfers.clear();
         self.command_tx = None;
+        self.drop_hover = false;
     }
 }
 
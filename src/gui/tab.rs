@@ -1,16 +1,26 @@
-use crate::config::SshProfile;
+use crate::config::{NetworkProtocol, PortForward, SshProfile, Trigger};
 use crate::gui::pane::{Axis, Direction, PaneNode, neighbour};
 use crate::gui::sftp::SftpDrawerState;
 use crate::session::{LaunchSpec, OutputEvent, Session, SessionError};
+use crate::ssh::forward::ForwardStatus;
+use crate::terminal::copy_mode::{CopyMode, CopyMotion, VisualMode};
+use crate::terminal::graphics::ImagePlacement;
+use crate::terminal::marks::Mark;
+use crate::terminal::prediction::Predictor;
+use crate::terminal::recording::Recording;
 use crate::terminal::{CellVisual, Selection, TerminalEngine, TerminalSize, TerminalTheme};
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use iced::futures::channel::mpsc;
 use iced::keyboard::{Key, Modifiers, key::Named};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 pub struct Pane {
     pub id: u64,
@@ -19,7 +29,45 @@ pub struct Pane {
     pub session: TerminalSession,
     pub selection: Option<Selection>,
     pub sftp: SftpDrawerState,
+    /// Drops keyboard, paste and mouse input instead of writing it to the
+    /// session. Set for every pane of a locked tab.
+    pub input_locked: bool,
+    /// Keyboard selection cursor, while copy mode is on.
+    copy_mode: Option<CopyMode>,
     engine: TerminalEngine,
+    /// Local echo shown ahead of the server's, for SSH panes.
+    predictor: Predictor,
+    /// Output kept for a GIF or asciicast export, while recording.
+    recording: Option<Recording>,
+    /// Set once the session ended while the pane stays open on its final
+    /// screen.
+    pub exit: Option<SessionExit>,
+    /// The profile's startup commands, until they are typed.
+    startup: Option<PendingStartup>,
+    /// The global `terminal.env` the session started with, so a duplicate
+    /// gets the same environment after the setting changes.
+    launch_env: BTreeMap<String, String>,
+    /// Set once the shell or a script gives the pane a title; until then
+    /// the tab shows the program running in the foreground.
+    titled: bool,
+    /// The foreground program with its arguments, when it isn't the shell.
+    command: Option<String>,
+}
+
+/// How long startup commands wait for a shell that never marks its prompt.
+const STARTUP_COMMAND_FALLBACK: std::time::Duration = std::time::Duration::from_secs(2);
+
+struct PendingStartup {
+    commands: Vec<String>,
+    /// Typed at this point even without a prompt mark.
+    deadline: Instant,
+}
+
+/// How a pane's session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionExit {
+    /// See [`OutputEvent::Closed`](crate::session::OutputEvent::Closed).
+    pub code: Option<i32>,
 }
 
 pub struct TerminalTab {
@@ -27,14 +75,54 @@ pub struct TerminalTab {
     pub layout: PaneNode,
     pub focused: u64,
     pub panes: Vec<Pane>,
+    /// View-only: nothing typed or pasted reaches the tab's sessions.
+    locked: bool,
+    /// The focused pane fills the tab; `layout` is kept to restore.
+    zoomed: bool,
+    /// Accent stripe drawn under the tab; starts from the profile's color.
+    pub color: Option<[u8; 3]>,
+    /// Icon name shown before the title; starts from the profile's icon.
+    pub icon: Option<String>,
+    /// A long command finished while the tab was out of view; drawn as a
+    /// badge until the tab is next shown.
+    pub command_done: bool,
+    /// A bell rang, or output arrived, while the tab was out of view; kept
+    /// until the tab is next shown.
+    pub bell: bool,
+    pub activity: bool,
 }
 
+/// Command-line flag that opens the profile after it, packed by
+/// [`Profile::to_launch_arg`], as the first tab.
+pub const LAUNCH_PROFILE_FLAG: &str = "--profile";
+
+/// Preset colors the tab context menu and profile editor cycle through.
+pub const TAB_COLORS: [[u8; 3]; 6] = [
+    [0xE5, 0x53, 0x4B],
+    [0xEE, 0x9A, 0x3A],
+    [0xE5, 0xC0, 0x7B],
+    [0x4F, 0xB2, 0x6A],
+    [0x4C, 0x8D, 0xF6],
+    [0xA3, 0x71, 0xF7],
+];
+
 pub enum TerminalSession {
     Active(Session),
     #[allow(dead_code)]
     Failed(String),
 }
 
+/// Where a local shell runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalHost {
+    /// In this process; the shell ends with the app.
+    InProcess,
+    /// In the session daemon, so it outlives the app.
+    Daemon,
+    /// The daemon session with this id, started by an earlier run.
+    Attach(u64),
+}
+
 pub struct PaneSpawn {
     pub profile: Profile,
     pub columns: usize,
@@ -43,7 +131,18 @@ pub struct PaneSpawn {
     pub id: u64,
     pub output_tx: mpsc::UnboundedSender<OutputEvent>,
     pub scrollback_lines: usize,
+    pub triggers: Vec<Trigger>,
+    /// Secret patterns to mask; empty outside privacy mode.
+    pub redact_patterns: Vec<String>,
+    /// Reply to ENQ.
+    pub answerback: String,
+    pub tmux_passthrough: bool,
     pub cwd: Option<PathBuf>,
+    /// Cell size in logical pixels, for laying out inline images.
+    pub cell_size: [f32; 2],
+    /// The global `terminal.env`, for local shells.
+    pub env: BTreeMap<String, String>,
+    pub host: LocalHost,
 }
 
 impl Pane {
@@ -56,21 +155,59 @@ impl Pane {
             id,
             output_tx,
             scrollback_lines,
+            triggers,
+            redact_patterns,
+            answerback,
+            tmux_passthrough,
             cwd,
+            cell_size,
+            env,
+            host,
         } = spec;
 
         let size = TerminalSize::new(columns, lines);
 
         let title = profile.display_name();
+        // A reattached session ran its startup commands the first time.
+        let runs_startup =
+            !profile.startup_commands.is_empty() && !matches!(host, LocalHost::Attach(_));
 
         let (session, writer) = if let Some(ssh) = profile.ssh_profile() {
             let s = Session::spawn_ssh(ssh.clone(), id, lines as u16, columns as u16, output_tx);
 
+            let w = s.writer();
+            (TerminalSession::Active(s), w)
+        } else if let ProfileKind::Network {
+            host,
+            port,
+            protocol,
+        } = &profile.kind
+        {
+            let s = Session::spawn_network(
+                host.clone(),
+                *port,
+                *protocol,
+                id,
+                lines as u16,
+                columns as u16,
+                output_tx,
+            );
             let w = s.writer();
             (TerminalSession::Active(s), w)
         } else {
-            let spec = profile.launch_spec(size, cwd);
-            match Session::spawn(spec, id, output_tx) {
+            let started = match host {
+                LocalHost::InProcess => {
+                    Session::spawn(profile.launch_spec(size, cwd, &env), id, output_tx)
+                }
+                LocalHost::Daemon => Session::spawn_persistent(
+                    profile.launch_spec(size, cwd, &env),
+                    toml::to_string(&profile).unwrap_or_default(),
+                    id,
+                    output_tx,
+                ),
+                LocalHost::Attach(daemon_id) => Session::attach(daemon_id, id, output_tx),
+            };
+            match started {
                 Ok(s) => {
                     let w = s.writer();
                     (TerminalSession::Active(s), w)
@@ -84,9 +221,15 @@ impl Pane {
             }
         };
 
-        // scrollback_lines is read from config at tab creation time;
-        // changing the setting later applies only to newly created tabs.
-        let engine = TerminalEngine::new(size, scrollback_lines, writer, theme);
+        // scrollback_lines, triggers and the answerback are read from config
+        // at tab creation time; changing them later applies only to newly
+        // created tabs.
+        let mut engine = TerminalEngine::new(size, scrollback_lines, writer, theme);
+        engine.set_cell_pixel_size(cell_size);
+        engine.set_triggers(&triggers);
+        engine.set_redaction(&redact_patterns);
+        engine.set_answerback(&answerback);
+        engine.set_tmux_passthrough(tmux_passthrough);
 
         Self {
             id,
@@ -95,27 +238,161 @@ impl Pane {
             session,
             selection: None,
             sftp: SftpDrawerState::new(),
+            input_locked: false,
+            copy_mode: None,
             engine,
+            predictor: Predictor::default(),
+            recording: None,
+            exit: None,
+            startup: runs_startup.then(|| PendingStartup {
+                commands: profile.startup_commands.clone(),
+                deadline: Instant::now() + STARTUP_COMMAND_FALLBACK,
+            }),
+            launch_env: env,
+            titled: false,
+            command: None,
+        }
+    }
+
+    pub fn launch_env(&self) -> &BTreeMap<String, String> {
+        &self.launch_env
+    }
+
+    /// Whether the profile's startup commands are still to be typed.
+    pub fn startup_pending(&self) -> bool {
+        self.startup.is_some()
+    }
+
+    /// Types the profile's startup commands once the shell marks its first
+    /// prompt (OSC 133), or once `now` is past the fallback delay. Waits
+    /// while input is locked.
+    pub fn send_startup_commands(&mut self, now: Instant) {
+        let Some(startup) = &self.startup else {
+            return;
+        };
+        if !matches!(self.session, TerminalSession::Active(_)) {
+            self.startup = None;
+            return;
+        }
+        if self.engine.typed_command().is_none() && now < startup.deadline {
+            return;
+        }
+        let Some(session) = self.input_session() else {
+            return;
+        };
+        for command in &startup.commands {
+            let _ = session.send_bytes(format!("{command}\r").as_bytes());
         }
+        self.startup = None;
     }
 
     /// Feeds PTY bytes to the terminal engine. Returns `true` if a bell rang.
     pub fn feed_bytes(&mut self, bytes: &[u8]) -> bool {
         self.engine.feed_bytes(bytes);
+        if let Some(recording) = &mut self.recording {
+            recording.output(bytes);
+        }
+        if !self.predictor.is_empty() {
+            let cells = self.engine.render_cells();
+            let columns = self.engine.size().columns;
+            self.predictor.reconcile(
+                self.engine.cursor_cell(),
+                |col, row| cells.get(row * columns + col).map(|cell| cell.ch),
+                Instant::now(),
+            );
+        }
         if let Some(new_title) = self.engine.take_title() {
-            self.title = new_title;
+            self.set_title(new_title);
+        }
+        if self.startup.is_some() {
+            self.send_startup_commands(Instant::now());
         }
         self.engine.take_bell()
     }
 
+    /// Replaces the title for good: the running command no longer shows.
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+        self.titled = true;
+        self.command = None;
+    }
+
+    /// The title the tab shows: the program running in the foreground while
+    /// nothing has set one.
+    pub fn shown_title(&self) -> &str {
+        match &self.command {
+            Some(command) if !self.titled => command,
+            _ => &self.title,
+        }
+    }
+
+    /// Looks up the foreground program again, for panes whose shell doesn't
+    /// set titles.
+    pub fn refresh_command(&mut self) {
+        if self.titled {
+            return;
+        }
+        self.command = match &self.session {
+            TerminalSession::Active(session) => session.foreground_command(),
+            _ => None,
+        };
+    }
+
+    /// Matched text of `notify` triggers since the last call.
+    pub fn take_notifications(&mut self) -> Vec<String> {
+        self.engine.take_notifications()
+    }
+
+    /// When output first reached each row in view.
+    pub fn line_times(&self) -> Vec<Option<std::time::SystemTime>> {
+        self.engine.line_times()
+    }
+
+    /// The longest run time of the commands that finished since the last
+    /// call.
+    pub fn take_finished_command(&mut self) -> Option<std::time::Duration> {
+        self.engine.take_finished_command()
+    }
+
     pub fn render_cells(&self) -> std::sync::Arc<Vec<CellVisual>> {
-        self.engine.render_cells()
+        let cells = self.engine.render_cells();
+        let mut predictions = self.predictor.visible(Instant::now()).peekable();
+        if predictions.peek().is_none() {
+            return cells;
+        }
+        let columns = self.engine.size().columns;
+        let mut cells = (*cells).clone();
+        for prediction in predictions {
+            if let Some(cell) = cells.get_mut(prediction.row * columns + prediction.col) {
+                cell.ch = prediction.ch;
+                cell.grapheme = None;
+                cell.wide = false;
+                cell.underline = true;
+            }
+        }
+        std::sync::Arc::new(cells)
+    }
+
+    pub fn render_images(&self) -> std::sync::Arc<Vec<std::sync::Arc<ImagePlacement>>> {
+        self.engine.render_images()
+    }
+
+    pub fn set_cell_pixel_size(&mut self, size: [f32; 2]) {
+        self.engine.set_cell_pixel_size(size);
     }
 
     pub fn set_theme(&mut self, theme: TerminalTheme) {
         self.engine.set_theme(theme);
     }
 
+    pub fn set_tmux_passthrough(&mut self, enabled: bool) {
+        self.engine.set_tmux_passthrough(enabled);
+    }
+
+    pub fn set_redaction(&mut self, patterns: &[String]) {
+        self.engine.set_redaction(patterns);
+    }
+
     pub fn size(&self) -> TerminalSize {
         self.engine.size()
     }
@@ -134,60 +411,131 @@ impl Pane {
     }
 
     pub fn cursor_cell(&self) -> Option<(usize, usize)> {
-        self.engine.cursor_cell()
+        if let Some(copy) = &self.copy_mode {
+            let (offset, _) = self.engine.scroll_position();
+            return copy.cursor_cell(offset, self.engine.size().lines);
+        }
+        let cursor = self.engine.cursor_cell()?;
+        let columns = self.engine.size().columns;
+        Some(
+            self.predictor
+                .visible(Instant::now())
+                .last()
+                .map_or(cursor, |p| ((p.col + 1).min(columns - 1), p.row)),
+        )
     }
 
     pub fn cursor_color(&self) -> [f32; 4] {
         self.engine.cursor_color()
     }
 
+    /// The selected text, including rows scrolled out of view.
     pub fn selected_text(&self) -> Option<String> {
         let sel = self.selection.as_ref().filter(|s| !s.is_empty())?;
-        let cells = self.engine.render_cells();
-        let size = self.engine.size();
-        let (current_offset, _) = self.engine.scroll_position();
-        let delta = sel.delta(current_offset);
-        let (start, end) = sel.ordered();
-        let mut result = String::new();
-        for row in start.row..=end.row {
-            // Selection rows live in the anchor frame; translate to the
-            // current viewport before reading cells.
-            let viewport_row = row + delta;
-            let row_in_view = (0..size.lines as i64).contains(&viewport_row);
-            if row_in_view {
-                let viewport_row = viewport_row as usize;
-                let col_start = if row == start.row { start.col } else { 0 };
-                let col_end = if row == end.row {
-                    end.col
-                } else {
-                    size.columns.saturating_sub(1)
-                };
-                for col in col_start..=col_end {
-                    let idx = viewport_row * size.columns + col;
-                    if let Some(cell) = cells.get(idx) {
-                        result.push(cell.ch);
-                    }
-                }
-                let trimmed_len = result.trim_end_matches(' ').len();
-                result.truncate(trimmed_len);
-            }
-            if row != end.row {
-                result.push('\n');
+        let text = self.engine.selection_text(sel);
+        (!text.is_empty()).then_some(text)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Starts recording output, or stops and returns what was recorded.
+    pub fn toggle_recording(&mut self) -> Option<Recording> {
+        match self.recording.take() {
+            Some(recording) => Some(recording),
+            None => {
+                self.recording = Some(Recording::start(self.engine.size()));
+                None
             }
         }
-        let trimmed_len = result.trim_end_matches(' ').len();
-        result.truncate(trimmed_len);
-        if result.is_empty() {
-            None
-        } else {
-            Some(result)
-        }
+    }
+
+    /// First and last viewport rows the selection covers, clipped to the view.
+    pub fn selected_rows(&self) -> Option<(usize, usize)> {
+        let sel = self.selection.as_ref().filter(|s| !s.is_empty())?;
+        let (offset, _) = self.scroll_position();
+        let (start, end) = sel.ordered();
+        let first = (start.row + sel.delta(offset)).max(0);
+        let last = (end.row + sel.delta(offset)).min(self.size().lines as i64 - 1);
+        (first <= last).then_some((first as usize, last as usize))
+    }
+
+    /// Selects the first of `rules` that matches at viewport cell (`row`,
+    /// `col`). Returns whether one did.
+    pub fn select_semantic(&mut self, row: usize, col: usize, rules: &[String]) -> bool {
+        let Some(selection) = self.engine.semantic_selection(row, col, rules) else {
+            return false;
+        };
+        self.selection = Some(selection);
+        true
+    }
+
+    pub fn select_all(&mut self) {
+        self.selection = Some(self.engine.select_all());
+    }
+
+    /// Clears the scrollback and the screen above the prompt.
+    pub fn clear_buffer(&mut self) {
+        self.selection = None;
+        self.engine.clear_buffer();
     }
 
     pub fn clear_selection(&mut self) {
         self.selection = None;
     }
 
+    pub fn in_copy_mode(&self) -> bool {
+        self.copy_mode.is_some()
+    }
+
+    /// Starts copy mode at the terminal cursor, or at the top of the view
+    /// when scrolled back.
+    pub fn enter_copy_mode(&mut self) {
+        let (offset, _) = self.engine.scroll_position();
+        let (column, line) = match self.engine.cursor_cell() {
+            Some((col, row)) => (col, row as i64),
+            None => (0, -(offset as i64)),
+        };
+        self.selection = None;
+        self.copy_mode = Some(CopyMode::new(line, column));
+    }
+
+    pub fn exit_copy_mode(&mut self) {
+        self.copy_mode = None;
+        self.selection = None;
+    }
+
+    /// Moves the copy-mode cursor, scrolling to keep it in view.
+    pub fn copy_mode_move(&mut self, motion: CopyMotion) {
+        let Some(copy) = self.copy_mode.as_mut() else {
+            return;
+        };
+        let (_, history) = self.engine.scroll_position();
+        let size = self.engine.size();
+        copy.apply(motion, history, size);
+        let (offset, _) = self.engine.scroll_position();
+        let delta = copy.scroll_to_cursor(offset, size.lines);
+        if delta != 0 {
+            self.engine.scroll(delta);
+        }
+        self.sync_copy_selection();
+    }
+
+    pub fn copy_mode_visual(&mut self, mode: VisualMode) {
+        if let Some(copy) = self.copy_mode.as_mut() {
+            copy.toggle_visual(mode);
+            self.sync_copy_selection();
+        }
+    }
+
+    fn sync_copy_selection(&mut self) {
+        if let Some(copy) = &self.copy_mode {
+            let (offset, _) = self.engine.scroll_position();
+            self.selection = copy.selection(offset, self.engine.size().columns);
+        }
+    }
+
     pub fn scroll_to_relative(&mut self, rel: f32) {
         self.engine.scroll_to_relative(rel);
     }
@@ -196,6 +544,116 @@ impl Pane {
         self.engine.scroll_to_bottom();
     }
 
+    /// Selects the last command's output and returns its full text, which
+    /// may extend past the viewport.
+    pub fn select_last_output(&mut self) -> Option<String> {
+        let (selection, text) = self.engine.select_last_output()?;
+        self.selection = Some(selection);
+        Some(text)
+    }
+
+    pub fn has_last_output(&self) -> bool {
+        self.engine.has_last_output()
+    }
+
+    pub fn wrapped_rows(&self) -> Vec<bool> {
+        self.engine.wrapped_rows()
+    }
+
+    pub fn unhandled_sequences(&self) -> u64 {
+        self.engine.unhandled_sequences()
+    }
+
+    /// What has been typed at the shell prompt so far, when the shell
+    /// reports where its command line begins.
+    pub fn typed_command(&self) -> Option<String> {
+        self.engine.typed_command()
+    }
+
+    /// Whether the command line has text after the cursor.
+    pub fn input_after_cursor(&self) -> bool {
+        self.engine.input_after_cursor()
+    }
+
+    pub fn command_history(&self) -> &[String] {
+        self.engine.command_history()
+    }
+
+    /// The rows in view as text, trailing blanks trimmed.
+    pub fn screen_text(&self) -> String {
+        self.engine.screen_text().join("\n")
+    }
+
+    /// Sends `text` as if typed.
+    pub fn type_text(&self, text: &str) {
+        if let Some(session) = self.input_session() {
+            let _ = session.send_bytes(text.as_bytes());
+        }
+    }
+
+    /// Types a quick action's command and runs it.
+    pub fn run_quick_action(&self, index: usize) {
+        let Some(session) = self.input_session() else {
+            return;
+        };
+        if let Some(action) = self.profile.actions.get(index) {
+            let _ = session.send_bytes(format!("{}\r", action.command).as_bytes());
+        }
+    }
+
+    /// Types the last command again and runs it. Does nothing while a
+    /// command is running or input is locked.
+    pub fn rerun_last_command(&self) {
+        let Some(session) = self.input_session() else {
+            return;
+        };
+        if let Some(input) = self.engine.rerun_input() {
+            let _ = session.send_bytes(&input);
+        }
+    }
+
+    /// Port forwards of this pane's SSH session with their live status.
+    pub fn port_forwards(&self) -> Vec<(PortForward, ForwardStatus)> {
+        match &self.session {
+            TerminalSession::Active(session) => session
+                .ssh_handle()
+                .map(|ssh| ssh.forwards())
+                .unwrap_or_default(),
+            TerminalSession::Failed(_) => Vec::new(),
+        }
+    }
+
+    pub fn toggle_port_forward(&self, index: usize) {
+        if let TerminalSession::Active(session) = &self.session
+            && let Some(ssh) = session.ssh_handle()
+        {
+            ssh.toggle_forward(index);
+        }
+    }
+
+    pub fn toggle_mark(&mut self) {
+        self.engine.toggle_mark();
+    }
+
+    /// Scrolls to the previous (`older`) or next mark. Returns false when
+    /// there is none in that direction.
+    pub fn jump_to_mark(&mut self, older: bool) -> bool {
+        self.engine.jump_to_mark(older)
+    }
+
+    pub fn scroll_to_mark(&mut self, line: u64) -> bool {
+        self.engine.scroll_to_mark(line)
+    }
+
+    pub fn marks(&self) -> Vec<(Mark, String)> {
+        self.engine.marks()
+    }
+
+    /// Lines of output that arrived while scrolled back into history.
+    pub fn unseen_lines(&self) -> usize {
+        self.engine.unseen_lines()
+    }
+
     /// Returns true when the terminal program has enabled mouse reporting.
     pub fn mouse_mode(&self) -> bool {
         self.engine.mouse_mode()
@@ -206,14 +664,31 @@ impl Pane {
         self.engine.alt_screen()
     }
 
+    /// Whether the program is reading a password or other hidden input:
+    /// echo is off in line mode or, where that cannot be queried, the cursor
+    /// sits after a password prompt.
+    pub fn secret_input(&self) -> bool {
+        let echo_off =
+            matches!(&self.session, TerminalSession::Active(session) if session.echo_disabled());
+        echo_off || (!self.alt_screen() && self.engine.prompts_for_secret())
+    }
+
     /// Returns true when the running program has enabled bracketed paste.
     pub fn bracketed_paste(&self) -> bool {
         self.engine.bracketed_paste()
     }
 
+    /// The session to write user input to, unless input is locked.
+    pub fn input_session(&self) -> Option<&Session> {
+        match &self.session {
+            TerminalSession::Active(session) if !self.input_locked => Some(session),
+            _ => None,
+        }
+    }
+
     /// Send scroll as arrow key sequences (for alt screen without mouse mode).
     pub fn send_scroll_as_arrows(&self, lines: i32) {
-        let TerminalSession::Active(session) = &self.session else {
+        let Some(session) = self.input_session() else {
             return;
         };
         let arrow = if lines > 0 { b'A' } else { b'B' }; // Up / Down
@@ -225,7 +700,7 @@ impl Pane {
 
     /// Send a mouse event to the PTY using SGR or legacy encoding.
     pub fn send_mouse_event(&self, button: u8, col: usize, row: usize, pressed: bool) {
-        let TerminalSession::Active(session) = &self.session else {
+        let Some(session) = self.input_session() else {
             return;
         };
         // SGR encoding: \x1b[<btn;col;row;M/m  (M=press, m=release)
@@ -246,106 +721,209 @@ impl Pane {
         }
     }
 
+    /// The pane's local working directory: the one the shell last reported
+    /// through OSC 7, or else the shell process's own. Remote panes have none.
     pub fn working_directory(&self) -> Option<PathBuf> {
+        let TerminalSession::Active(session) = &self.session else {
+            return None;
+        };
+        if session.ssh_handle().is_some() {
+            return None;
+        }
+        self.engine
+            .reported_cwd()
+            .map(PathBuf::from)
+            // A shell in a container reports paths inside it.
+            .filter(|path| path.is_dir())
+            .or_else(|| session.working_directory())
+    }
+
+    /// Whether the shell has marked a prompt (OSC 133) and reported its
+    /// working directory (OSC 7), as shell integration makes it do.
+    pub fn integration_reports(&self) -> (bool, bool) {
+        (
+            self.engine.reports_prompts(),
+            self.engine.reported_cwd().is_some(),
+        )
+    }
+
+    /// The shell's process id, for local shells.
+    pub fn process_id(&self) -> Option<u32> {
+        match &self.session {
+            TerminalSession::Active(session) if session.ssh_handle().is_none() => {
+                session.process_id()
+            }
+            _ => None,
+        }
+    }
+
+    /// Name of the program in the foreground, for local shells.
+    pub fn foreground_process(&self) -> Option<String> {
         match &self.session {
-            TerminalSession::Active(session) => session.working_directory(),
+            TerminalSession::Active(session) => session.foreground_process(),
             _ => None,
         }
     }
 
+    /// The shell's working directory as text, including one reported by a
+    /// remote shell.
+    pub fn current_directory(&self) -> Option<String> {
+        self.engine.reported_cwd().map(str::to_string).or_else(|| {
+            self.working_directory()
+                .map(|path| path.to_string_lossy().into_owned())
+        })
+    }
+
     pub fn resize(&mut self, columns: usize, lines: usize) {
-        let new_size = TerminalSize::new(columns, lines);
-        self.engine.resize(new_size);
+        let new_size = TerminalSize::new(columns, lines).clamped();
+        let TerminalSize { columns, lines } = new_size;
+        if let Some(recording) = &mut self.recording
+            && self.engine.size() != new_size
+        {
+            recording.resize(new_size);
+        }
+        self.selection = self.engine.resize_with_selection(new_size, self.selection);
 
         if let TerminalSession::Active(session) = &mut self.session {
             let _ = session.resize(lines as u16, columns as u16);
         }
     }
 
-    pub fn handle_key(&mut self, key: &Key, modifiers: Modifiers, text: Option<&str>) {
-        if let TerminalSession::Active(session) = &self.session
-            && let Some(bytes) = self.key_to_bytes(key, modifiers, text)
-            && let Err(err) = session.send_bytes(&bytes)
-        {
-            eprintln!("Failed to send key to session: {err}")
+    /// Sends a key press to the session. With `predict_echo`, SSH panes show
+    /// typed characters before the server echoes them.
+    pub fn handle_key(
+        &mut self,
+        key: &Key,
+        modifiers: Modifiers,
+        text: Option<&str>,
+        predict_echo: bool,
+    ) {
+        let Some(session) = self.input_session() else {
+            return;
+        };
+        let Some(bytes) = key_to_bytes(key, modifiers, text) else {
+            return;
+        };
+        if let Err(err) = session.send_bytes(&bytes) {
+            eprintln!("Failed to send key to session: {err}");
+            return;
+        }
+
+        if predict_echo && self.profile.ssh_profile().is_some() && !self.engine.alt_screen() {
+            self.predictor.input(
+                &bytes,
+                self.engine.cursor_cell(),
+                self.engine.size().columns,
+                Instant::now(),
+            );
+        } else {
+            self.predictor.reset();
         }
     }
+}
 
-    fn key_to_bytes<'a>(
-        &self,
-        key: &Key,
-        modifiers: Modifiers,
-        text: Option<&'a str>,
-    ) -> Option<Cow<'a, [u8]>> {
-        match key {
-            Key::Named(named) => match named {
-                Named::Enter => Some(Cow::Borrowed(b"\r")),
-                Named::Backspace => {
-                    if modifiers.alt() || modifiers.control() {
-                        // Alt/Ctrl+Backspace: delete the previous word.
-                        Some(Cow::Borrowed(b"\x1b\x7f"))
-                    } else if modifiers.logo() {
-                        // Cmd+Backspace: delete to the start of the line.
-                        Some(Cow::Borrowed(b"\x15"))
-                    } else {
-                        Some(Cow::Borrowed(b"\x7f"))
-                    }
+/// Encodes a key press for the PTY.
+///
+/// Named keys with a terminal meaning (arrows, Enter, function keys...) map to
+/// their escape sequences. Everything else is driven by the text the platform
+/// committed for the press, not by the logical key: dead-key and compose
+/// sequences (`´` + `e` → `é`) report a bare accent or no key at all, while the
+/// committed text carries the finished character.
+fn key_to_bytes<'a>(
+    key: &Key,
+    modifiers: Modifiers,
+    text: Option<&'a str>,
+) -> Option<Cow<'a, [u8]>> {
+    match key {
+        Key::Named(named) => match named {
+            Named::Enter => Some(Cow::Borrowed(b"\r")),
+            Named::Backspace => {
+                if modifiers.alt() || modifiers.control() {
+                    // Alt/Ctrl+Backspace: delete the previous word.
+                    Some(Cow::Borrowed(b"\x1b\x7f"))
+                } else if modifiers.logo() {
+                    // Cmd+Backspace: delete to the start of the line.
+                    Some(Cow::Borrowed(b"\x15"))
+                } else {
+                    Some(Cow::Borrowed(b"\x7f"))
                 }
-                Named::Tab => {
-                    if modifiers.shift() {
-                        Some(Cow::Borrowed(b"\x1b[Z"))
-                    } else {
-                        Some(Cow::Borrowed(b"\t"))
-                    }
+            }
+            Named::Tab => {
+                if modifiers.shift() {
+                    Some(Cow::Borrowed(b"\x1b[Z"))
+                } else {
+                    Some(Cow::Borrowed(b"\t"))
                 }
-                Named::Escape => Some(Cow::Borrowed(b"\x1b")),
-                Named::ArrowUp => Some(cursor_seq(b'A', modifiers)),
-                Named::ArrowDown => Some(cursor_seq(b'B', modifiers)),
-                Named::ArrowRight => Some(cursor_seq(b'C', modifiers)),
-                Named::ArrowLeft => Some(cursor_seq(b'D', modifiers)),
-                Named::Home => Some(cursor_seq(b'H', modifiers)),
-                Named::End => Some(cursor_seq(b'F', modifiers)),
-                Named::Delete => Some(tilde_seq(b"3", modifiers)),
-                Named::PageUp => Some(tilde_seq(b"5", modifiers)),
-                Named::PageDown => Some(tilde_seq(b"6", modifiers)),
-                Named::Insert => Some(tilde_seq(b"2", modifiers)),
-                Named::F1 => Some(Cow::Borrowed(b"\x1bOP")),
-                Named::F2 => Some(Cow::Borrowed(b"\x1bOQ")),
-                Named::F3 => Some(Cow::Borrowed(b"\x1bOR")),
-                Named::F4 => Some(Cow::Borrowed(b"\x1bOS")),
-                Named::F5 => Some(Cow::Borrowed(b"\x1b[15~")),
-                Named::F6 => Some(Cow::Borrowed(b"\x1b[17~")),
-                Named::F7 => Some(Cow::Borrowed(b"\x1b[18~")),
-                Named::F8 => Some(Cow::Borrowed(b"\x1b[19~")),
-                Named::F9 => Some(Cow::Borrowed(b"\x1b[20~")),
-                Named::F10 => Some(Cow::Borrowed(b"\x1b[21~")),
-                Named::F11 => Some(Cow::Borrowed(b"\x1b[23~")),
-                Named::F12 => Some(Cow::Borrowed(b"\x1b[24~")),
-                Named::Space => {
-                    if modifiers.control() {
-                        Some(Cow::Borrowed(b"\0"))
-                    } else {
-                        Some(Cow::Borrowed(b" "))
-                    }
+            }
+            Named::Escape => Some(Cow::Borrowed(b"\x1b")),
+            Named::ArrowUp => Some(cursor_seq(b'A', modifiers)),
+            Named::ArrowDown => Some(cursor_seq(b'B', modifiers)),
+            Named::ArrowRight => Some(cursor_seq(b'C', modifiers)),
+            Named::ArrowLeft => Some(cursor_seq(b'D', modifiers)),
+            Named::Home => Some(cursor_seq(b'H', modifiers)),
+            Named::End => Some(cursor_seq(b'F', modifiers)),
+            Named::Delete => Some(tilde_seq(b"3", modifiers)),
+            Named::PageUp => Some(tilde_seq(b"5", modifiers)),
+            Named::PageDown => Some(tilde_seq(b"6", modifiers)),
+            Named::Insert => Some(tilde_seq(b"2", modifiers)),
+            Named::F1 => Some(Cow::Borrowed(b"\x1bOP")),
+            Named::F2 => Some(Cow::Borrowed(b"\x1bOQ")),
+            Named::F3 => Some(Cow::Borrowed(b"\x1bOR")),
+            Named::F4 => Some(Cow::Borrowed(b"\x1bOS")),
+            Named::F5 => Some(Cow::Borrowed(b"\x1b[15~")),
+            Named::F6 => Some(Cow::Borrowed(b"\x1b[17~")),
+            Named::F7 => Some(Cow::Borrowed(b"\x1b[18~")),
+            Named::F8 => Some(Cow::Borrowed(b"\x1b[19~")),
+            Named::F9 => Some(Cow::Borrowed(b"\x1b[20~")),
+            Named::F10 => Some(Cow::Borrowed(b"\x1b[21~")),
+            Named::F11 => Some(Cow::Borrowed(b"\x1b[23~")),
+            Named::F12 => Some(Cow::Borrowed(b"\x1b[24~")),
+            Named::Space => {
+                if modifiers.control() {
+                    Some(Cow::Borrowed(b"\0"))
+                } else {
+                    // A dead key followed by Space commits the bare accent.
+                    Some(committed_text(text).unwrap_or(Cow::Borrowed(b" ")))
                 }
-                _ => None,
-            },
+            }
+            _ => None,
+        },
 
-            Key::Character(c) if modifiers.control() => c.chars().next().and_then(|ch| {
+        // AltGr arrives as Ctrl+Alt on Windows; the character it produced
+        // (`@`, `€`, `{` on European layouts) is the input, not a control
+        // code. A real Ctrl+Alt sends the control code behind an ESC.
+        Key::Character(c) if modifiers.control() && !is_alt_gr(modifiers, text) => {
+            c.chars().next().and_then(|ch| {
                 let upper = ch.to_ascii_uppercase();
-                if upper.is_ascii_alphabetic() {
-                    Some(Cow::Owned(vec![(upper as u8) - b'A' + 1]))
-                } else {
-                    None
+                if !upper.is_ascii_alphabetic() {
+                    return None;
                 }
-            }),
-
-            Key::Character(_) => text.map(|t| Cow::Borrowed(t.as_bytes())),
-            _ => None,
+                let code = (upper as u8) - b'A' + 1;
+                Some(Cow::Owned(if modifiers.alt() {
+                    vec![0x1b, code]
+                } else {
+                    vec![code]
+                }))
+            })
         }
+
+        Key::Character(_) | Key::Unidentified => committed_text(text),
     }
 }
 
+/// The printable text a key press committed, if any. Control characters are
+/// left to the named-key encodings above.
+fn committed_text(text: Option<&str>) -> Option<Cow<'_, [u8]>> {
+    text.filter(|t| !t.is_empty() && !t.chars().any(char::is_control))
+        .map(|t| Cow::Borrowed(t.as_bytes()))
+}
+
+/// Whether a Ctrl+Alt press is Windows' AltGr: only when it committed a
+/// character of its own.
+fn is_alt_gr(m: Modifiers, text: Option<&str>) -> bool {
+    cfg!(target_os = "windows") && m.control() && m.alt() && committed_text(text).is_some()
+}
+
 fn csi_modifier(m: Modifiers) -> u8 {
     1 + m.shift() as u8 + (m.alt() as u8) * 2 + (m.control() as u8) * 4
 }
@@ -378,14 +956,43 @@ fn tilde_seq<'a>(num: &[u8], m: Modifiers) -> Cow<'a, [u8]> {
     Cow::Owned(seq)
 }
 
+/// The preset after `current`: the first one when nothing is set, and none
+/// after the last preset or a value that is not a preset.
+fn cycle_preset<'a, T: PartialEq>(presets: &'a [T], current: Option<&T>) -> Option<&'a T> {
+    match current {
+        None => presets.first(),
+        Some(current) => presets
+            .iter()
+            .position(|preset| preset == current)
+            .and_then(|index| presets.get(index + 1)),
+    }
+}
+
 /// A launchable session descriptor: a local shell (default or a specific
-/// program) or an SSH connection. The unifying type behind every tab.
+/// program), an SSH connection or a telnet/raw TCP connection. The unifying type behind every tab.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
     pub name: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub icon: Option<String>,
+    /// Hex accent color for tabs opened from this profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
     pub kind: ProfileKind,
+    /// Buttons in a toolbar above the terminal, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<QuickAction>,
+    /// Typed into the shell, one line each, once it first shows a prompt.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub startup_commands: Vec<String>,
+}
+
+/// A named command a profile shows as a button above its terminal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuickAction {
+    pub name: String,
+    /// Typed into the shell and run when the button is clicked.
+    pub command: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -396,8 +1003,29 @@ pub enum ProfileKind {
         program: Option<String>,
         #[serde(default)]
         args: Vec<String>,
+        /// Start as a login shell. `None` keeps the old behavior: the
+        /// default shell logs in, an explicit program runs `args` as given.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        login: Option<bool>,
+        /// Run before the interactive shell takes over.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        command: Option<String>,
+        /// Set on top of the global `terminal.env`.
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        env: BTreeMap<String, String>,
+        /// Windows only: open in a separate instance started through UAC,
+        /// so the shell runs with administrator rights.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        elevated: bool,
     },
     Ssh(SshProfile),
+    /// A plain TCP socket, for network devices and MUD-style services.
+    Network {
+        host: String,
+        port: u16,
+        #[serde(default)]
+        protocol: NetworkProtocol,
+    },
 }
 
 impl Profile {
@@ -405,10 +1033,17 @@ impl Profile {
         Self {
             name: default_shell_display_name(),
             icon: None,
+            color: None,
             kind: ProfileKind::Local {
                 program: None,
                 args: Vec::new(),
+                login: None,
+                command: None,
+                env: BTreeMap::new(),
+                elevated: false,
             },
+            actions: Vec::new(),
+            startup_commands: Vec::new(),
         }
     }
 
@@ -416,10 +1051,42 @@ impl Profile {
         Self {
             name,
             icon: None,
+            color: None,
             kind: ProfileKind::Local {
                 program: Some(path),
-                args: vec!["-l".to_string()],
+                args: Vec::new(),
+                login: Some(true),
+                command: None,
+                env: BTreeMap::new(),
+                elevated: false,
             },
+            actions: Vec::new(),
+            startup_commands: Vec::new(),
+        }
+    }
+
+    /// Git for Windows' bash, started as the Git Bash shortcut does: a
+    /// login shell in the MinGW environment that stays in the directory it
+    /// was opened in.
+    #[cfg(target_family = "windows")]
+    fn git_bash(path: String) -> Self {
+        Self {
+            name: "Git Bash".to_string(),
+            icon: Some("bash".to_string()),
+            color: None,
+            kind: ProfileKind::Local {
+                program: Some(path),
+                args: vec!["--login".to_string(), "-i".to_string()],
+                login: None,
+                command: None,
+                env: BTreeMap::from([
+                    ("CHERE_INVOKING".to_string(), "1".to_string()),
+                    ("MSYSTEM".to_string(), "MINGW64".to_string()),
+                ]),
+                elevated: false,
+            },
+            actions: Vec::new(),
+            startup_commands: Vec::new(),
         }
     }
 
@@ -427,29 +1094,85 @@ impl Profile {
         Self {
             name: profile.tab_title(),
             icon: None,
+            color: None,
             kind: ProfileKind::Ssh(profile),
+            actions: Vec::new(),
+            startup_commands: Vec::new(),
         }
     }
 
+    /// Whether the profile asks for a shell with administrator rights.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub fn wants_elevation(&self) -> bool {
+        matches!(self.kind, ProfileKind::Local { elevated: true, .. })
+    }
+
+    /// The profile packed into one command-line argument, for the
+    /// instance started to run it elevated.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub fn to_launch_arg(&self) -> Option<String> {
+        let toml = toml::to_string(self).ok()?;
+        Some(URL_SAFE_NO_PAD.encode(toml))
+    }
+
+    pub fn from_launch_arg(arg: &str) -> Option<Self> {
+        let toml = URL_SAFE_NO_PAD.decode(arg).ok()?;
+        toml::from_str(std::str::from_utf8(&toml).ok()?).ok()
+    }
+
     /// The SSH connection this profile launches, if any.
     pub fn ssh_profile(&self) -> Option<&SshProfile> {
         match &self.kind {
             ProfileKind::Ssh(p) => Some(p),
-            ProfileKind::Local { .. } => None,
+            ProfileKind::Local { .. } | ProfileKind::Network { .. } => None,
         }
     }
 
-    fn launch_spec(&self, size: TerminalSize, cwd: Option<PathBuf>) -> LaunchSpec {
-        let (program, args) = match &self.kind {
-            ProfileKind::Ssh(_) => unreachable!("SSH uses native russh, not launch_spec"),
-            ProfileKind::Local { program: None, .. } => resolve_default_shell(),
-            ProfileKind::Local {
-                program: Some(path),
-                args,
-            } => (path.clone(), args.clone()),
+    fn launch_spec(
+        &self,
+        size: TerminalSize,
+        cwd: Option<PathBuf>,
+        global_env: &BTreeMap<String, String>,
+    ) -> LaunchSpec {
+        let ProfileKind::Local {
+            program,
+            args: extra_args,
+            login,
+            command,
+            env: profile_env,
+            ..
+        } = &self.kind
+        else {
+            unreachable!("SSH and network sessions do not spawn a local program");
         };
+        let (program, mut args) = match program {
+            Some(path) => (path.clone(), Vec::new()),
+            None => resolve_default_shell(),
+        };
+        match login {
+            Some(false) => args.retain(|arg| arg != "-l"),
+            Some(true)
+                if cfg!(unix) && !args.iter().chain(extra_args.first()).any(|arg| arg == "-l") =>
+            {
+                args.push("-l".to_string());
+            }
+            _ => {}
+        }
+        args.extend(extra_args.iter().cloned());
+        if let Some(command) = command.as_deref().map(str::trim)
+            && !command.is_empty()
+        {
+            args = initial_command_args(&program, args, command);
+        }
 
-        let env = title_env_for_shell(&program);
+        // Later entries win, so the user's variables override the defaults.
+        let mut env = title_env_for_shell(&program);
+        env.extend(
+            global_env
+                .iter()
+                .chain(profile_env)
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
 
         LaunchSpec {
             program,
@@ -461,9 +1184,19 @@ impl Profile {
         }
     }
 
+    /// The accent color as RGB, ignoring values that are not hex colors.
+    pub fn color_rgb(&self) -> Option<[u8; 3]> {
+        self.color
+            .as_deref()
+            .and_then(crate::config::parse_hex_color)
+    }
+
     pub fn display_name(&self) -> String {
         match &self.kind {
             ProfileKind::Ssh(profile) => format!("SSH: {}", profile.tab_title()),
+            ProfileKind::Network { host, port, .. } if self.name.trim().is_empty() => {
+                format!("{host}:{port}")
+            }
             _ => self.name.clone(),
         }
     }
@@ -513,14 +1246,48 @@ fn title_env_for_shell(program: &str) -> Vec<(String, String)> {
     }
 }
 
+/// Arguments that run `command` first and then leave the user in the same
+/// shell, started with `args`.
+fn initial_command_args(program: &str, mut args: Vec<String>, command: &str) -> Vec<String> {
+    let stem = Path::new(program)
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+
+    match stem.as_str() {
+        "powershell" | "pwsh" => {
+            args.extend(["-NoExit", "-Command", command].map(String::from));
+            args
+        }
+        "cmd" => {
+            args.extend(["/K", command].map(String::from));
+            args
+        }
+        "nu" => {
+            args.extend(["-e", command].map(String::from));
+            args
+        }
+        // POSIX-style shells have no "run this, then stay" flag, so the
+        // command hands over to a fresh copy of the shell when it is done.
+        _ => {
+            let shell = std::iter::once(program)
+                .chain(args.iter().map(String::as_str))
+                .map(crate::ssh::shell_single_quote)
+                .collect::<Vec<_>>()
+                .join(" ");
+            let script = format!("{command}; exec {shell}");
+            args.extend(["-c".to_string(), script]);
+            args
+        }
+    }
+}
+
 fn resolve_default_shell() -> (String, Vec<String>) {
     #[cfg(target_family = "unix")]
     {
-        if let Ok(shell) = std::env::var("SHELL") {
-            let shell = shell.trim();
-            if !shell.is_empty() {
-                return (shell.to_string(), vec!["-l".to_string()]);
-            }
+        if let Some(shell) = crate::platform::shell::login_shell() {
+            return (shell.to_string(), vec!["-l".to_string()]);
         }
 
         const FALLBACKS: &[&str] = &["zsh", "bash", "fish", "sh"];
@@ -534,7 +1301,9 @@ fn resolve_default_shell() -> (String, Vec<String>) {
     #[cfg(target_family = "windows")]
     {
         (
-            "powershell".to_string(),
+            crate::platform::shell::login_shell()
+                .unwrap_or("powershell")
+                .to_string(),
             vec![
                 "-NoLogo".to_string(),
                 "-ExecutionPolicy".to_string(),
@@ -553,14 +1322,22 @@ fn command_exists(program: &str) -> bool {
         .is_ok_and(|status| status.success())
 }
 
-/// Discover available shells from `/etc/shells` (Unix) or known Windows shells.
+#[cfg(target_family = "unix")]
+fn find_on_path(program: &str) -> Option<PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| path.is_file())
+}
+
+/// Discover available shells from `/etc/shells` and `PATH` (Unix) or known
+/// Windows shells.
 pub fn discover_available_shells() -> Vec<Profile> {
     let mut shells = vec![Profile::default_shell()];
 
     #[cfg(target_family = "unix")]
     {
-        let default_path = std::env::var("SHELL").unwrap_or_default();
-        let default_path = default_path.trim();
+        let (default_path, _) = resolve_default_shell();
+        let default_path = default_path.as_str();
 
         let etc_shells = std::fs::read_to_string("/etc/shells")
             .or_else(|_| std::fs::read_to_string("/usr/share/defaults/etc/shells"))
@@ -595,11 +1372,41 @@ pub fn discover_available_shells() -> Vec<Profile> {
             }
             shells.push(Profile::shell(name.to_string(), line.to_string()));
         }
+
+        // Shells from a package manager are often missing from /etc/shells.
+        for name in ["bash", "zsh", "fish"] {
+            if seen_names.contains(name) {
+                continue;
+            }
+            if let Some(path) = find_on_path(name) {
+                seen_names.insert(name.to_string());
+                shells.push(Profile::shell(
+                    name.to_string(),
+                    path.to_string_lossy().into_owned(),
+                ));
+            }
+        }
     }
 
     #[cfg(target_family = "windows")]
     {
+        // PowerShell 7 leads when installed; Windows PowerShell stays on offer.
+        if resolve_default_shell().0 == "pwsh" {
+            shells.push(Profile::shell(
+                "powershell".to_string(),
+                "powershell".to_string(),
+            ));
+        }
         shells.push(Profile::shell("cmd".to_string(), "cmd".to_string()));
+        if let Some(path) = crate::platform::shell::git_bash() {
+            shells.push(Profile::git_bash(path.to_string_lossy().into_owned()));
+        }
+        if let Some(path) = crate::platform::shell::nushell() {
+            shells.push(Profile::shell(
+                "Nushell".to_string(),
+                path.to_string_lossy().into_owned(),
+            ));
+        }
     }
 
     shells
@@ -626,11 +1433,103 @@ impl Display for SessionError {
 impl TerminalTab {
     pub fn new(id: u64, pane: Pane) -> Self {
         let focused = pane.id;
+        let color = pane.profile.color_rgb();
+        let icon = pane.profile.icon.clone();
         Self {
             id,
             layout: PaneNode::Leaf(focused),
             focused,
             panes: vec![pane],
+            locked: false,
+            zoomed: false,
+            color,
+            icon,
+            command_done: false,
+            bell: false,
+            activity: false,
+        }
+    }
+
+    /// Steps to the next preset color, then back to no color.
+    pub fn cycle_color(&mut self) {
+        self.color = cycle_preset(&TAB_COLORS, self.color.as_ref()).copied();
+    }
+
+    /// Steps to the next picker icon, then back to no icon.
+    pub fn cycle_icon(&mut self) {
+        let current = self.icon.as_deref().map(str::to_lowercase);
+        self.icon = cycle_preset(
+            &crate::gui::icons::PROFILE_ICON_NAMES,
+            current.as_deref().as_ref(),
+        )
+        .map(|name| name.to_string());
+    }
+
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Whether any pane runs a shell on this machine.
+    pub fn runs_local_shell(&self) -> bool {
+        self.panes
+            .iter()
+            .any(|pane| matches!(pane.profile.kind, ProfileKind::Local { .. }))
+    }
+
+    /// Running forward count across the panes and whether any failed; None
+    /// when no pane has forwards.
+    pub fn forward_summary(&self) -> Option<(usize, bool)> {
+        let statuses: Vec<ForwardStatus> = self
+            .panes
+            .iter()
+            .flat_map(|pane| pane.port_forwards())
+            .map(|(_, status)| status)
+            .collect();
+        if statuses.is_empty() {
+            return None;
+        }
+        let running = statuses
+            .iter()
+            .filter(|status| **status == ForwardStatus::Active)
+            .count();
+        let failed = statuses
+            .iter()
+            .any(|status| matches!(status, ForwardStatus::Failed(_)));
+        Some((running, failed))
+    }
+
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+        for pane in &mut self.panes {
+            pane.input_locked = locked;
+        }
+    }
+
+    pub fn zoomed(&self) -> bool {
+        self.zoomed
+    }
+
+    /// Zooms the focused pane to fill the tab, or restores the split
+    /// layout. A tab with a single pane never zooms.
+    pub fn toggle_zoom(&mut self) {
+        self.zoomed = !self.zoomed && self.panes.len() > 1;
+    }
+
+    /// The layout as shown: only the focused pane while zoomed.
+    pub fn visible_layout(&self) -> PaneNode {
+        if self.zoomed {
+            PaneNode::Leaf(self.focused)
+        } else {
+            self.layout.clone()
+        }
+    }
+
+    /// Where each shown pane sits within `area`.
+    pub fn regions(&self, area: iced::Rectangle) -> Vec<(u64, iced::Rectangle)> {
+        if self.zoomed {
+            vec![(self.focused, area)]
+        } else {
+            self.layout.regions(area)
         }
     }
 
@@ -655,14 +1554,16 @@ impl TerminalTab {
     }
 
     pub fn title(&self) -> &str {
-        &self.focused().title
+        self.focused().shown_title()
     }
 
-    pub fn split(&mut self, axis: Axis, pane: Pane) {
+    pub fn split(&mut self, axis: Axis, mut pane: Pane) {
         let new_id = pane.id;
+        pane.input_locked = self.locked;
         if self.layout.split(self.focused, axis, new_id) {
             self.panes.push(pane);
             self.focused = new_id;
+            self.zoomed = false;
         }
     }
 
@@ -675,6 +1576,7 @@ impl TerminalTab {
             return false;
         }
         self.panes.retain(|p| p.id != target);
+        self.zoomed = false;
         if self.focused == target {
             self.focused = self.layout.leaves().first().copied().unwrap_or(target);
         }
@@ -685,6 +1587,7 @@ impl TerminalTab {
         let regions = self.layout.regions(area);
         if let Some(next) = neighbour(&regions, self.focused, direction) {
             self.focused = next;
+            self.zoomed = false;
         }
     }
 }
@@ -718,6 +1621,94 @@ mod tests {
         assert_eq!(&*tilde_seq(b"3", Modifiers::SHIFT), b"\x1b[3;2~");
     }
 
+    fn typed(key: Key, modifiers: Modifiers, text: Option<&str>) -> Option<Vec<u8>> {
+        key_to_bytes(&key, modifiers, text).map(|bytes| bytes.into_owned())
+    }
+
+    #[test]
+    fn dead_key_composition_sends_the_committed_character() {
+        // French / Spanish / German: `´` then `e` commits `é`.
+        assert_eq!(
+            typed(Key::Character("é".into()), Modifiers::empty(), Some("é")),
+            Some("é".as_bytes().to_vec())
+        );
+        // Some platforms report the composed press with no logical key.
+        assert_eq!(
+            typed(Key::Unidentified, Modifiers::empty(), Some("ñ")),
+            Some("ñ".as_bytes().to_vec())
+        );
+    }
+
+    #[test]
+    fn the_dead_key_itself_sends_nothing() {
+        assert_eq!(typed(Key::Unidentified, Modifiers::empty(), None), None);
+        assert_eq!(
+            typed(Key::Character("´".into()), Modifiers::empty(), None),
+            None
+        );
+    }
+
+    #[test]
+    fn dead_key_then_space_sends_the_bare_accent() {
+        assert_eq!(
+            typed(Key::Named(Named::Space), Modifiers::empty(), Some("^")),
+            Some(b"^".to_vec())
+        );
+        assert_eq!(
+            typed(Key::Named(Named::Space), Modifiers::empty(), Some(" ")),
+            Some(b" ".to_vec())
+        );
+    }
+
+    #[test]
+    fn shifted_text_wins_over_the_logical_key() {
+        // German QWERTZ: Shift+2 commits `"`.
+        assert_eq!(
+            typed(Key::Character("2".into()), Modifiers::SHIFT, Some("\"")),
+            Some(b"\"".to_vec())
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn alt_gr_characters_are_not_treated_as_control_codes() {
+        // German AltGr+Q and Polish AltGr+E arrive as Ctrl+Alt.
+        let alt_gr = Modifiers::CTRL | Modifiers::ALT;
+        assert_eq!(
+            typed(Key::Character("@".into()), alt_gr, Some("@")),
+            Some(b"@".to_vec())
+        );
+        assert_eq!(
+            typed(Key::Character("ę".into()), alt_gr, Some("ę")),
+            Some("ę".as_bytes().to_vec())
+        );
+    }
+
+    #[test]
+    fn ctrl_letters_still_send_control_codes() {
+        assert_eq!(
+            typed(Key::Character("c".into()), Modifiers::CTRL, Some("\u{3}")),
+            Some(vec![0x03])
+        );
+        assert_eq!(
+            typed(Key::Named(Named::Space), Modifiers::CTRL, Some(" ")),
+            Some(vec![0x00])
+        );
+    }
+
+    #[test]
+    fn ctrl_alt_letters_send_escaped_control_codes() {
+        let ctrl_alt = Modifiers::CTRL | Modifiers::ALT;
+        assert_eq!(
+            typed(Key::Character("a".into()), ctrl_alt, Some("\u{1}")),
+            Some(vec![0x1b, 0x01])
+        );
+        assert_eq!(
+            typed(Key::Character("a".into()), ctrl_alt, None),
+            Some(vec![0x1b, 0x01])
+        );
+    }
+
     #[test]
     fn ssh_profile_tab_title() {
         let with_name = SshProfile {
@@ -729,6 +1720,11 @@ mod tests {
             identity_file: None,
             password: None,
             proxy_command: None,
+            keepalive_interval: 15,
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         };
         assert_eq!(with_name.tab_title(), "Production");
 
@@ -741,6 +1737,11 @@ mod tests {
             identity_file: None,
             password: None,
             proxy_command: None,
+            keepalive_interval: 15,
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         };
         assert_eq!(no_name.tab_title(), "user@dev.example.com");
 
@@ -753,6 +1754,11 @@ mod tests {
             identity_file: None,
             password: None,
             proxy_command: None,
+            keepalive_interval: 15,
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         };
         assert_eq!(no_name_no_user.tab_title(), "bare.host");
     }
@@ -768,6 +1774,11 @@ mod tests {
             identity_file: None,
             password: Some("hunter2".into()),
             proxy_command: None,
+            keepalive_interval: 15,
+            auto_reconnect: false,
+            remote_attach: false,
+            forward_agent: false,
+            forwards: Vec::new(),
         });
 
         let toml = toml::to_string(&profile).expect("serialize");
@@ -780,6 +1791,80 @@ mod tests {
         assert!(restored.ssh_profile().unwrap().password.is_none());
     }
 
+    #[test]
+    fn profile_env_overrides_global_env_in_launch_spec() {
+        let mut profile = Profile::shell("fish".into(), "/opt/bin/fish".into());
+        if let ProfileKind::Local { env, .. } = &mut profile.kind {
+            env.insert("EDITOR".into(), "hx".into());
+        }
+        let global = BTreeMap::from([
+            ("EDITOR".to_string(), "vim".to_string()),
+            ("PAGER".to_string(), "less".to_string()),
+        ]);
+
+        let spec = profile.launch_spec(TerminalSize::new(80, 24), None, &global);
+        let effective: BTreeMap<String, String> = spec.env.into_iter().collect();
+
+        assert_eq!(effective["EDITOR"], "hx");
+        assert_eq!(effective["PAGER"], "less");
+    }
+
+    #[test]
+    fn login_flag_and_custom_args_build_the_command_line() {
+        let mut profile = Profile::shell("fish".into(), "/opt/bin/fish".into());
+        if let ProfileKind::Local { args, .. } = &mut profile.kind {
+            *args = vec!["--private".into()];
+        }
+        let spec = profile.launch_spec(TerminalSize::new(80, 24), None, &BTreeMap::new());
+        let expected: &[&str] = if cfg!(unix) {
+            &["-l", "--private"]
+        } else {
+            &["--private"]
+        };
+        assert_eq!(spec.args, expected);
+
+        // Profiles saved before the flag kept `-l` in their args.
+        let legacy: Profile = toml::from_str(
+            r#"
+            name = "fish"
+            [kind]
+            type = "local"
+            program = "/opt/bin/fish"
+            args = ["-l"]
+            "#,
+        )
+        .expect("deserialize");
+        let spec = legacy.launch_spec(TerminalSize::new(80, 24), None, &BTreeMap::new());
+        assert_eq!(spec.args, ["-l"]);
+
+        if let ProfileKind::Local { login, args, .. } = &mut profile.kind {
+            *login = Some(false);
+            args.clear();
+        }
+        let spec = profile.launch_spec(TerminalSize::new(80, 24), None, &BTreeMap::new());
+        assert!(spec.args.is_empty());
+    }
+
+    #[test]
+    fn initial_command_hands_over_to_the_shell() {
+        assert_eq!(
+            initial_command_args("/bin/bash", vec!["-l".into()], "make watch"),
+            ["-l", "-c", "make watch; exec '/bin/bash' '-l'"]
+        );
+        assert_eq!(
+            initial_command_args("pwsh.exe", Vec::new(), "Get-Date"),
+            ["-NoExit", "-Command", "Get-Date"]
+        );
+        assert_eq!(
+            initial_command_args("C:\\Windows\\System32\\cmd.exe", Vec::new(), "ver"),
+            ["/K", "ver"]
+        );
+        assert_eq!(
+            initial_command_args("nu.exe", Vec::new(), "ls"),
+            ["-e", "ls"]
+        );
+    }
+
     #[test]
     fn local_profile_round_trips() {
         let default = Profile::default_shell();
@@ -798,4 +1883,46 @@ mod tests {
             ProfileKind::Local { program: Some(p), .. } if p == "/opt/bin/fish"
         ));
     }
+
+    #[test]
+    fn presets_cycle_back_to_none() {
+        let presets = ["a", "b"];
+        assert_eq!(cycle_preset(&presets, None), Some(&"a"));
+        assert_eq!(cycle_preset(&presets, Some(&"a")), Some(&"b"));
+        assert_eq!(cycle_preset(&presets, Some(&"b")), None);
+        assert_eq!(cycle_preset(&presets, Some(&"custom")), None);
+    }
+
+    #[test]
+    fn profile_color_round_trips_and_ignores_invalid_hex() {
+        let mut profile = Profile::default_shell();
+        profile.color = Some("#4c8df6".into());
+        let restored: Profile =
+            toml::from_str(&toml::to_string(&profile).expect("serialize")).expect("deserialize");
+        assert_eq!(restored.color_rgb(), Some([0x4C, 0x8D, 0xF6]));
+
+        profile.color = Some("blue".into());
+        assert_eq!(profile.color_rgb(), None);
+        assert!(
+            !toml::to_string(&Profile::default_shell())
+                .expect("serialize")
+                .contains("color")
+        );
+    }
+
+    #[test]
+    fn elevated_profiles_survive_the_launch_argument() {
+        let mut profile = Profile::shell("PowerShell".into(), "pwsh.exe".into());
+        if let ProfileKind::Local { elevated, .. } = &mut profile.kind {
+            *elevated = true;
+        }
+        let arg = profile.to_launch_arg().expect("encode");
+        assert!(!arg.contains(' '), "passed unquoted: {arg}");
+
+        let restored = Profile::from_launch_arg(&arg).expect("decode");
+        assert_eq!(restored.name, "PowerShell");
+        assert!(restored.wants_elevation());
+        assert!(!Profile::default_shell().wants_elevation());
+        assert!(Profile::from_launch_arg("not a profile").is_none());
+    }
 }
@@ -56,6 +56,47 @@ impl Palette {
             error: color!(0xf3, 0x8b, 0xa8),
         }
     }
+
+    /// The theme's palette, pushed to full contrast when the user asked for
+    /// it.
+    pub fn from_config(config: &crate::config::AppConfig) -> Self {
+        let palette = Self::from_theme(&config.theme);
+        if config.ui.high_contrast {
+            palette.high_contrast()
+        } else {
+            palette
+        }
+    }
+
+    /// Pure black or white text, secondary text nearly as strong, and
+    /// surfaces that stand clearly apart from the background.
+    pub fn high_contrast(self) -> Self {
+        let bg = self.background;
+        let dark = bg.r + bg.g + bg.b < 1.5;
+        let (text, toward) = if dark {
+            (Color::WHITE, Color::BLACK)
+        } else {
+            (Color::BLACK, Color::WHITE)
+        };
+        let mix = |from: Color, to: Color, t: f32| Color {
+            r: from.r + (to.r - from.r) * t,
+            g: from.g + (to.g - from.g) * t,
+            b: from.b + (to.b - from.b) * t,
+            a: from.a,
+        };
+        Self {
+            background: mix(bg, toward, 0.6),
+            surface: mix(self.surface, text, 0.12),
+            text,
+            text_secondary: mix(text, toward, 0.15),
+            accent: if dark {
+                color!(0x7c, 0xbc, 0xff)
+            } else {
+                color!(0x00, 0x4c, 0xb3)
+            },
+            ..self
+        }
+    }
 }
 
 fn blend_u8(from: u8, to: u8, t: f32) -> u8 {
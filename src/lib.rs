@@ -4,6 +4,8 @@ pub mod i18n;
 pub mod gui;
 pub mod keychain;
 pub mod platform;
+pub mod plugin;
+pub mod script;
 pub mod session;
 pub mod ssh;
 pub mod terminal;
@@ -17,15 +17,47 @@ use crate::gui::App;
 // Embed DejaVu Sans font for better Unicode support (Box Drawing characters)
 const DEJAVU_SANS: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
 const APP_ICON_PNG: &[u8] = include_bytes!("../assets/logo.png");
+/// Runs this instance under another window class than the configured one.
+const APP_ID_FLAG: &str = "--class";
 
 fn main() -> iced::Result {
+    match std::env::args().nth(1).as_deref() {
+        Some(session::daemon::DAEMON_FLAG) => {
+            session::daemon::run();
+            return Ok(());
+        }
+        Some(session::daemon::ATTACH_COMMAND) => {
+            if let Err(err) = session::daemon::bridge_stdio() {
+                eprintln!("rabbitty attach: {err}");
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+    match run() {
+        Err(iced::Error::GraphicsCreationFailed(err)) => {
+            gui::render::surface::relaunch_with_fallback(&err.to_string())
+        }
+        result => result,
+    }
+}
+
+fn run() -> iced::Result {
+    let startup_profile = startup_profile();
+    let first_run = AppConfig::is_first_run();
     let app_config = AppConfig::load();
     i18n::set_locale(app_config.ui.language.as_deref());
+    gui::render::surface::configure(&app_config.renderer);
     let boot_config = app_config.clone();
+    let app_id = app_id_arg().unwrap_or_else(|| app_config.app_id().to_string());
+    platform::set_app_id(&app_id);
 
     iced::application(
         move || {
-            let app = App::new(boot_config.clone());
+            let app = App::new(boot_config.clone())
+                .with_startup_profile(startup_profile.clone())
+                .with_onboarding(first_run);
 
             let init_task = iced::Task::perform(
                 async {
@@ -42,6 +74,7 @@ fn main() -> iced::Result {
     .title("Rabbitty")
     .theme(iced::Theme::Dark)
     .style(|state, _| state.window_style())
+    .scale_factor(App::ui_scale)
     .subscription(App::subscription)
     .font(DEJAVU_SANS)
     .default_font(iced::Font {
@@ -60,8 +93,27 @@ fn main() -> iced::Result {
             titlebar_transparent: true,
             fullsize_content_view: true,
         },
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        platform_specific: iced::window::settings::PlatformSpecific {
+            application_id: app_id,
+            ..Default::default()
+        },
 
         ..Default::default()
     })
     .run()
 }
+
+/// The window class passed with `--class`, if it is a valid one.
+fn app_id_arg() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    args.find(|arg| arg == APP_ID_FLAG)?;
+    config::sanitize_app_id(&args.next()?)
+}
+
+/// The profile passed with `--profile`, as an elevated instance is started.
+fn startup_profile() -> Option<gui::tab::Profile> {
+    let mut args = std::env::args().skip(1);
+    args.find(|arg| arg == gui::tab::LAUNCH_PROFILE_FLAG)?;
+    gui::tab::Profile::from_launch_arg(&args.next()?)
+}
@@ -38,6 +38,19 @@ pub fn apply_style(window: WindowHandle<'_>, display: DisplayHandle<'_>, theme:
 /// No system bell API without extra dependencies; stay silent.
 pub fn ring_bell() {}
 
+/// The app id goes out with the window settings on Wayland and X11.
+pub fn set_app_id(_id: &str) {}
+
+/// Secure Keyboard Entry exists on macOS only.
+pub const SECURE_INPUT_SUPPORTED: bool = false;
+
+pub fn set_secure_input(_enabled: bool) {}
+
+/// Elevated sessions are a Windows feature.
+pub fn is_elevated() -> bool {
+    false
+}
+
 // ── X11 (KWin / picom): _KDE_NET_WM_BLUR_BEHIND_REGION ───────────────
 fn apply_x11_blur(window: u32, enabled: bool) {
     use x11rb::connection::Connection as _;
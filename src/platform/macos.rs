@@ -15,6 +15,12 @@ unsafe extern "C" {
     fn CGSDefaultConnectionForThread() -> *mut std::ffi::c_void;
 }
 
+#[link(name = "Carbon", kind = "framework")]
+unsafe extern "C" {
+    fn EnableSecureEventInput() -> i32;
+    fn DisableSecureEventInput() -> i32;
+}
+
 pub fn apply_style(handle: WindowHandle<'_>, _display: DisplayHandle<'_>, theme: &ThemeConfig) {
     set_app_icon_once();
     apply_style_inner(handle, theme);
@@ -25,6 +31,34 @@ pub fn ring_bell() {
     objc2_app_kit::NSBeep();
 }
 
+/// Secure Keyboard Entry exists on macOS only.
+pub const SECURE_INPUT_SUPPORTED: bool = true;
+
+/// Turns Secure Keyboard Entry on or off, so that other processes can't
+/// read keystrokes. The system counts calls, so each enable needs exactly
+/// one matching disable.
+pub fn set_secure_input(enabled: bool) {
+    // SAFETY: both calls take no arguments and only update a system counter.
+    let status = unsafe {
+        if enabled {
+            EnableSecureEventInput()
+        } else {
+            DisableSecureEventInput()
+        }
+    };
+    if status != 0 {
+        eprintln!("Secure Keyboard Entry could not be changed: OSStatus {status}");
+    }
+}
+
+/// The Dock groups by bundle identifier, which is fixed at build time.
+pub fn set_app_id(_id: &str) {}
+
+/// Elevated sessions are a Windows feature.
+pub fn is_elevated() -> bool {
+    false
+}
+
 const APP_ICON_PNG: &[u8] = include_bytes!("../../assets/logo.png");
 
 pub fn set_app_icon_once() {
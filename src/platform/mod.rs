@@ -18,32 +18,215 @@ pub mod linux;
 #[cfg(not(any(target_os = "windows", target_os = "macos")))]
 pub use linux::*;
 
-pub fn open_url(url: &str) {
-    if !crate::terminal::url::is_openable(url) {
-        eprintln!("Refusing to open non-http(s) URL: {url}");
+pub mod shell;
+
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// How long a piped command may take before it is killed.
+const PIPE_COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Runs `argv` with `input` on its standard input and returns what it
+/// printed, or its error output when it fails. The input is written while
+/// the output is read, so a command that answers as it reads never stalls
+/// on a full pipe; one that takes longer than a minute is killed.
+pub async fn pipe_command(
+    argv: &[String],
+    env: &[(&str, String)],
+    input: String,
+) -> Result<String, String> {
+    use tokio::io::AsyncWriteExt;
+
+    let Some((program, args)) = argv.split_first() else {
+        return Err("No command is set".to_string());
+    };
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("{program}: {err}"))?;
+    let stdin = child.stdin.take();
+    let write_input = async move {
+        if let Some(mut stdin) = stdin {
+            // A command that does not read its input is not an error.
+            let _ = stdin.write_all(input.as_bytes()).await;
+        }
+        // Dropping stdin here closes it, so the command sees the end.
+    };
+    // Timing out drops the child, which kills it.
+    let (_, output) = tokio::time::timeout(PIPE_COMMAND_TIMEOUT, async {
+        tokio::join!(write_input, child.wait_with_output())
+    })
+    .await
+    .map_err(|_| {
+        format!(
+            "{program} did not finish within {} seconds",
+            PIPE_COMMAND_TIMEOUT.as_secs()
+        )
+    })?;
+    let output = output.map_err(|err| format!("{program}: {err}"))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err([stderr, stdout]
+            .into_iter()
+            .find(|message| !message.is_empty())
+            .unwrap_or_else(|| format!("{program} exited with {}", output.status)))
+    }
+}
+
+/// Opens `url` with the system handler for its scheme, if that scheme is
+/// one of `allowed_schemes`.
+pub fn open_url(url: &str, allowed_schemes: &[String]) {
+    if !crate::terminal::url::is_allowed(url, allowed_schemes) {
+        eprintln!("Refusing to open URL with a blocked scheme: {url}");
+        return;
+    }
+
+    if let Err(err) = system_opener(OsStr::new(url)).spawn() {
+        eprintln!("Failed to open {url}: {err}");
+    }
+}
+
+/// Opens a local file with the desktop's default application.
+pub fn open_path(path: &Path) {
+    if let Err(err) = system_opener(path.as_os_str()).spawn() {
+        eprintln!("Failed to open {}: {err}", path.display());
+    }
+}
+
+/// Runs the user's editor command, already split into arguments.
+pub fn open_in_editor(argv: &[String]) {
+    let Some((program, args)) = argv.split_first() else {
         return;
+    };
+    if let Err(err) = Command::new(program).args(args).spawn() {
+        eprintln!("Failed to run editor {program}: {err}");
+    }
+}
+
+/// Posts a desktop notification. Windows has no toast tool to shell out to,
+/// so there the bell stands in for it.
+pub fn notify(title: &str, body: &str) {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = (title, body);
+        ring_bell();
     }
+    #[cfg(not(target_os = "windows"))]
+    {
+        #[cfg(target_os = "macos")]
+        let spawned = {
+            let quote = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+            let script = format!(
+                "display notification \"{}\" with title \"{}\"",
+                quote(body),
+                quote(title)
+            );
+            Command::new("osascript").args(["-e", &script]).spawn()
+        };
+        #[cfg(not(target_os = "macos"))]
+        let spawned = Command::new("notify-send")
+            .args(["--app-name=RabbiTTY", "--", title, body])
+            .spawn();
+
+        if let Err(err) = spawned {
+            eprintln!("Failed to post notification: {err}");
+        }
+    }
+}
+
+/// Reads `text` aloud with the system's speech tool, cutting off whatever
+/// an earlier call is still saying.
+pub fn speak(text: String) {
+    use std::io::Write;
+    use std::sync::Mutex;
+
+    static SPEAKING: Mutex<Option<std::process::Child>> = Mutex::new(None);
 
     #[cfg(target_os = "macos")]
     let mut command = {
-        let mut c = std::process::Command::new("open");
-        c.arg(url);
+        let mut c = Command::new("say");
+        c.args(["-f", "-"]);
         c
     };
     #[cfg(target_os = "windows")]
     let mut command = {
-        let mut c = std::process::Command::new("cmd");
-        c.args(["/c", "start", "", url]);
+        let mut c = Command::new("powershell");
+        c.args([
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+        ]);
         c
     };
     #[cfg(not(any(target_os = "macos", target_os = "windows")))]
     let mut command = {
-        let mut c = std::process::Command::new("xdg-open");
-        c.arg(url);
+        // spd-say reads standard input with --pipe-mode.
+        let mut c = Command::new("spd-say");
+        c.args(["--pipe-mode", "--wait"]);
         c
     };
 
-    if let Err(err) = command.spawn() {
-        eprintln!("Failed to open {url}: {err}");
+    let mut speaking = SPEAKING.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some(mut previous) = speaking.take() {
+        let _ = previous.kill();
+        let _ = previous.wait();
+    }
+    let mut child = match command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => {
+            eprintln!("Failed to start speech: {err}");
+            return;
+        }
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(text.as_bytes());
+        });
     }
+    *speaking = Some(child);
+}
+
+fn system_opener(target: &OsStr) -> Command {
+    #[cfg(target_os = "macos")]
+    let command = {
+        let mut c = Command::new("open");
+        c.arg(target);
+        c
+    };
+    #[cfg(target_os = "windows")]
+    let command = {
+        let mut c = Command::new("cmd");
+        c.args([
+            OsStr::new("/c"),
+            OsStr::new("start"),
+            OsStr::new(""),
+            target,
+        ]);
+        c
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let command = {
+        let mut c = Command::new("xdg-open");
+        c.arg(target);
+        c
+    };
+    command
 }
@@ -0,0 +1,153 @@
+//! The shell the user logs in with, which local tabs start by default.
+
+use std::sync::OnceLock;
+
+/// The user's own shell: `$SHELL` when it names a program that exists, then
+/// their `/etc/passwd` entry. On Windows, PowerShell 7 when it is installed
+/// and Windows PowerShell otherwise. Looked up once.
+pub fn login_shell() -> Option<&'static str> {
+    static SHELL: OnceLock<Option<String>> = OnceLock::new();
+    SHELL.get_or_init(detect).as_deref()
+}
+
+#[cfg(unix)]
+fn detect() -> Option<String> {
+    if let Ok(shell) = std::env::var("SHELL") {
+        let shell = shell.trim();
+        if !shell.is_empty() && std::path::Path::new(shell).is_file() {
+            return Some(shell.to_string());
+        }
+    }
+    // SAFETY: getuid cannot fail.
+    let uid = unsafe { libc::getuid() };
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    passwd_shell(&passwd, uid).map(str::to_string)
+}
+
+#[cfg(windows)]
+fn detect() -> Option<String> {
+    let on_path = |exe: &str| {
+        std::env::var_os("PATH")
+            .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(exe).is_file()))
+    };
+    let shell = if on_path("pwsh.exe") {
+        "pwsh"
+    } else {
+        "powershell"
+    };
+    Some(shell.to_string())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn detect() -> Option<String> {
+    None
+}
+
+/// `bash.exe` from Git for Windows: where its installer recorded it in the
+/// registry, then the per-machine and per-user default locations.
+#[cfg(windows)]
+pub fn git_bash() -> Option<std::path::PathBuf> {
+    use std::path::PathBuf;
+    use windows::Win32::System::Registry::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    let registered = [HKEY_LOCAL_MACHINE, HKEY_CURRENT_USER]
+        .into_iter()
+        .filter_map(|hive| registry_string(hive, r"SOFTWARE\GitForWindows", "InstallPath"))
+        .map(PathBuf::from);
+    let defaults = [("ProgramFiles", "Git"), ("LOCALAPPDATA", r"Programs\Git")]
+        .into_iter()
+        .filter_map(|(var, dir)| Some(PathBuf::from(std::env::var_os(var)?).join(dir)));
+    registered
+        .chain(defaults)
+        .map(|dir| dir.join(r"bin\bash.exe"))
+        .find(|path| path.is_file())
+}
+
+/// `nu.exe` on `PATH`, or where the Nushell installer and `cargo install`
+/// put it.
+#[cfg(windows)]
+pub fn nushell() -> Option<std::path::PathBuf> {
+    use std::path::PathBuf;
+
+    let on_path = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let defaults = [
+        ("ProgramFiles", r"nu\bin"),
+        ("LOCALAPPDATA", r"Programs\nu\bin"),
+        ("USERPROFILE", r".cargo\bin"),
+    ]
+    .into_iter()
+    .filter_map(|(var, dir)| Some(PathBuf::from(std::env::var_os(var)?).join(dir)));
+    on_path
+        .into_iter()
+        .chain(defaults)
+        .map(|dir| dir.join("nu.exe"))
+        .find(|path| path.is_file())
+}
+
+/// A `REG_SZ` value, or `None` when the key or value is missing.
+#[cfg(windows)]
+fn registry_string(
+    hive: windows::Win32::System::Registry::HKEY,
+    key: &str,
+    value: &str,
+) -> Option<String> {
+    use windows::Win32::System::Registry::{RRF_RT_REG_SZ, RegGetValueW};
+    use windows::core::HSTRING;
+
+    let mut buffer = [0u16; 1024];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+    // SAFETY: `size` is the buffer's length in bytes, as the call expects.
+    let status = unsafe {
+        RegGetValueW(
+            hive,
+            &HSTRING::from(key),
+            &HSTRING::from(value),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(&mut size),
+        )
+    };
+    if status.is_err() {
+        return None;
+    }
+    // The size counts the terminating NUL.
+    let len = (size as usize / 2).saturating_sub(1);
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+/// The shell field of the `/etc/passwd` line for `uid`, unless it is one
+/// that refuses logins.
+#[cfg(any(unix, test))]
+fn passwd_shell(passwd: &str, uid: u32) -> Option<&str> {
+    passwd
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.split(':').collect::<Vec<_>>())
+        .find(|fields| fields.len() == 7 && fields[2].parse() == Ok(uid))
+        .map(|fields| fields[6].trim())
+        .filter(|shell| {
+            !shell.is_empty() && !shell.ends_with("nologin") && !shell.ends_with("/false")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_passwd_entry_for_the_uid_names_the_shell() {
+        let passwd = "root:x:0:0:root:/root:/bin/bash\n\
+                      # comment\n\
+                      nobody:x:65534:65534::/nonexistent:/usr/sbin/nologin\n\
+                      ada:x:1000:1000:Ada,,,:/home/ada:/usr/bin/fish\n\
+                      blank:x:1001:1001::/home/blank:\n";
+        assert_eq!(passwd_shell(passwd, 1000), Some("/usr/bin/fish"));
+        assert_eq!(passwd_shell(passwd, 0), Some("/bin/bash"));
+        assert_eq!(passwd_shell(passwd, 1001), None);
+        assert_eq!(passwd_shell(passwd, 65534), None);
+        assert_eq!(passwd_shell(passwd, 42), None);
+    }
+}
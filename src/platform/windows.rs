@@ -150,3 +150,55 @@ pub fn ring_bell() {
         let _ = MessageBeep(MB_OK);
     }
 }
+
+/// Groups this process's windows on the taskbar under `id`, so instances
+/// with different ids get separate buttons.
+pub fn set_app_id(id: &str) {
+    use windows::Win32::UI::Shell::SetCurrentProcessExplicitAppUserModelID;
+    use windows::core::HSTRING;
+
+    if let Err(err) = unsafe { SetCurrentProcessExplicitAppUserModelID(&HSTRING::from(id)) } {
+        eprintln!("Failed to set the app user model id: {err}");
+    }
+}
+
+/// Secure Keyboard Entry exists on macOS only.
+pub const SECURE_INPUT_SUPPORTED: bool = false;
+
+pub fn set_secure_input(_enabled: bool) {}
+
+/// Whether this process runs with an elevated (administrator) token.
+pub fn is_elevated() -> bool {
+    use windows::Win32::UI::Shell::IsUserAnAdmin;
+
+    unsafe { IsUserAnAdmin().as_bool() }
+}
+
+/// Starts another instance of this program through the UAC prompt, so it
+/// runs with administrator rights. `args` are passed as given and must
+/// not need quoting. Fails when the user declines the prompt.
+pub fn relaunch_elevated(args: &[&str]) -> Result<(), String> {
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+    use windows::core::{HSTRING, PCWSTR, w};
+
+    let exe = std::env::current_exe().map_err(|err| err.to_string())?;
+    let exe = HSTRING::from(exe.as_path());
+    let params = HSTRING::from(args.join(" "));
+    let instance = unsafe {
+        ShellExecuteW(
+            None,
+            w!("runas"),
+            &exe,
+            &params,
+            PCWSTR::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    // Values up to 32 are error codes.
+    let code = instance.0 as isize;
+    if code <= 32 {
+        return Err(format!("ShellExecute failed with code {code}"));
+    }
+    Ok(())
+}
@@ -0,0 +1,191 @@
+//! Plugins installed as programs: a `plugin.toml` in its own directory
+//! under `<config dir>/rabbitty/plugins/`.
+//!
+//! ```toml
+//! name = "Tickets"
+//! api = 1
+//! # Relative to the plugin's directory, or looked up on the PATH.
+//! command = "tickets"
+//! osc = [7777]
+//!
+//! [[commands]]
+//! id = "open"
+//! title = "Open the ticket under the selection"
+//! ```
+//!
+//! A palette command runs `<command> command <id>` with the selection on
+//! standard input; an OSC sequence runs `<command> osc <number>` with its
+//! payload. What the program prints opens in a side panel.
+
+use super::{API_VERSION, CommandContext, Effect, Plugin, PluginCommand};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "plugin.toml";
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    name: String,
+    api: u32,
+    command: String,
+    #[serde(default)]
+    osc: Vec<u16>,
+    #[serde(default)]
+    commands: Vec<PluginCommand>,
+}
+
+#[derive(Debug)]
+pub(super) struct ExternalPlugin {
+    name: String,
+    program: String,
+    osc: Vec<u16>,
+    commands: Vec<PluginCommand>,
+}
+
+impl ExternalPlugin {
+    fn from_manifest(manifest: Manifest, dir: &Path) -> Result<Self, String> {
+        if manifest.api != API_VERSION {
+            return Err(format!(
+                "needs plugin API {}, this build has {API_VERSION}",
+                manifest.api
+            ));
+        }
+        let local = dir.join(&manifest.command);
+        let program = if manifest.command.contains(['/', '\\']) || local.is_file() {
+            local.to_string_lossy().into_owned()
+        } else {
+            manifest.command
+        };
+        Ok(Self {
+            name: manifest.name,
+            program,
+            osc: manifest.osc,
+            commands: manifest.commands,
+        })
+    }
+
+    fn run(&self, args: [String; 2], input: String, title: String) -> Vec<Effect> {
+        let mut argv = vec![self.program.clone()];
+        argv.extend(args);
+        vec![Effect::Run { argv, input, title }]
+    }
+}
+
+impl Plugin for ExternalPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn osc_numbers(&self) -> &[u16] {
+        &self.osc
+    }
+
+    fn on_osc(&mut self, _pane: u64, number: u16, payload: &str) -> Vec<Effect> {
+        self.run(
+            ["osc".into(), number.to_string()],
+            payload.to_string(),
+            self.name.clone(),
+        )
+    }
+
+    fn commands(&self) -> Vec<PluginCommand> {
+        self.commands.clone()
+    }
+
+    fn run_command(&mut self, id: &str, context: &CommandContext) -> Vec<Effect> {
+        let title = self
+            .commands
+            .iter()
+            .find(|command| command.id == id)
+            .map_or_else(|| self.name.clone(), |command| command.title.clone());
+        self.run(
+            ["command".into(), id.to_string()],
+            context.selection.clone().unwrap_or_default(),
+            title,
+        )
+    }
+}
+
+/// Every plugin directory with a valid manifest. Broken ones are reported
+/// and skipped.
+pub(super) fn discover() -> Vec<ExternalPlugin> {
+    let Some(dir) = plugins_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(MANIFEST).is_file())
+        .collect();
+    dirs.sort();
+    dirs.into_iter()
+        .filter_map(|dir| match load(&dir) {
+            Ok(plugin) => Some(plugin),
+            Err(err) => {
+                eprintln!("Warning: skipping plugin {}: {err}", dir.display());
+                None
+            }
+        })
+        .collect()
+}
+
+fn load(dir: &Path) -> Result<ExternalPlugin, String> {
+    let text = std::fs::read_to_string(dir.join(MANIFEST)).map_err(|err| err.to_string())?;
+    let manifest: Manifest = toml::from_str(&text).map_err(|err| err.to_string())?;
+    ExternalPlugin::from_manifest(manifest, dir)
+}
+
+fn plugins_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("rabbitty").join("plugins"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin(toml: &str) -> Result<ExternalPlugin, String> {
+        let manifest: Manifest = toml::from_str(toml).map_err(|err| err.to_string())?;
+        ExternalPlugin::from_manifest(manifest, Path::new("/plugins/tickets"))
+    }
+
+    #[test]
+    fn manifests_declare_commands_and_osc_numbers() {
+        let mut plugin = plugin(
+            r#"
+            name = "Tickets"
+            api = 1
+            command = "./tickets"
+            osc = [7777]
+
+            [[commands]]
+            id = "open"
+            title = "Open ticket"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(plugin.osc_numbers(), [7777]);
+        assert_eq!(plugin.commands()[0].title, "Open ticket");
+
+        let context = CommandContext {
+            pane: 1,
+            selection: Some("ABC-12".into()),
+            cwd: None,
+        };
+        let Effect::Run { argv, input, title } = &plugin.run_command("open", &context)[0] else {
+            panic!("expected a run effect");
+        };
+        assert!(argv[0].ends_with("tickets") && argv[0] != "tickets");
+        assert_eq!(&argv[1..], ["command", "open"]);
+        assert_eq!(input, "ABC-12");
+        assert_eq!(title, "Open ticket");
+    }
+
+    #[test]
+    fn other_api_versions_are_refused() {
+        let err = plugin("name = \"Old\"\napi = 0\ncommand = \"old\"").unwrap_err();
+        assert!(err.contains("plugin API 0"));
+    }
+}
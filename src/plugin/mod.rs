@@ -0,0 +1,243 @@
+//! Plugins: extensions that see a pane's output before the terminal does,
+//! handle OSC sequences of their own, add commands to the new session menu
+//! and show text in a side panel.
+//!
+//! Compiled-in plugins implement [`Plugin`] directly and are handed to
+//! [`PluginHost::with`]. Plugins installed as programs are discovered from
+//! the config directory (see [`external`]) and go through the same trait.
+//! Hooks only return [`Effect`]s; the app decides when and how to carry them
+//! out, so a plugin never blocks the window or reaches into its state.
+
+mod external;
+mod osc;
+
+pub use osc::OscSequence;
+
+use osc::OscSplitter;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Bumped when [`Plugin`] or the program protocol changes incompatibly.
+/// Installed plugins state the version they were written for.
+pub const API_VERSION: u32 = 1;
+
+/// An extension. Every hook has a default that does nothing, so a plugin
+/// implements only the ones it needs.
+pub trait Plugin {
+    fn name(&self) -> &str;
+
+    /// OSC numbers this plugin handles. Their sequences are taken out of
+    /// the output and passed to [`Self::on_osc`] instead of the terminal.
+    fn osc_numbers(&self) -> &[u16] {
+        &[]
+    }
+
+    fn on_osc(&mut self, _pane: u64, _number: u16, _payload: &str) -> Vec<Effect> {
+        Vec::new()
+    }
+
+    /// Rewrites a pane's output before the terminal parses it. Output comes
+    /// in reads of any size, so an escape sequence may be cut in two.
+    fn filter_output(&mut self, _pane: u64, _bytes: &mut Vec<u8>) {}
+
+    /// Entries for the new session menu, shown while a terminal is open.
+    fn commands(&self) -> Vec<PluginCommand> {
+        Vec::new()
+    }
+
+    fn run_command(&mut self, _id: &str, _context: &CommandContext) -> Vec<Effect> {
+        Vec::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PluginCommand {
+    pub id: String,
+    pub title: String,
+}
+
+/// What a command runs against: the focused pane.
+#[derive(Debug, Clone, Default)]
+pub struct CommandContext {
+    pub pane: u64,
+    pub selection: Option<String>,
+    pub cwd: Option<String>,
+}
+
+/// Something a plugin asks the app to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Effect {
+    /// Send bytes to the pane's program, as if typed.
+    Write(Vec<u8>),
+    /// A desktop notification.
+    Notify(String),
+    /// Show text in the plugin side panel.
+    Panel { title: String, body: String },
+    /// Run a program with `input` on its standard input and show what it
+    /// prints in the side panel.
+    Run {
+        argv: Vec<String>,
+        input: String,
+        title: String,
+    },
+}
+
+/// The loaded plugins and the per-pane state of the output they see.
+#[derive(Default)]
+pub struct PluginHost {
+    plugins: Vec<Box<dyn Plugin>>,
+    splitters: HashMap<u64, OscSplitter>,
+}
+
+impl PluginHost {
+    /// The plugins installed in the config directory.
+    pub fn load() -> Self {
+        let plugins = external::discover()
+            .into_iter()
+            .map(|plugin| Box::new(plugin) as Box<dyn Plugin>)
+            .collect();
+        Self::with(plugins)
+    }
+
+    pub fn with(plugins: Vec<Box<dyn Plugin>>) -> Self {
+        Self {
+            plugins,
+            splitters: HashMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Runs a pane's output through the plugins: claimed OSC sequences are
+    /// taken out and handled, then each plugin's filter runs in turn.
+    /// Returns the output for the terminal and the plugins' effects.
+    pub fn process_output<'a>(
+        &mut self,
+        pane: u64,
+        bytes: &'a [u8],
+    ) -> (Cow<'a, [u8]>, Vec<(String, Effect)>) {
+        if self.plugins.is_empty() {
+            return (Cow::Borrowed(bytes), Vec::new());
+        }
+        let claimed: Vec<u16> = self
+            .plugins
+            .iter()
+            .flat_map(|plugin| plugin.osc_numbers().iter().copied())
+            .collect();
+        let mut effects = Vec::new();
+        let mut output = if claimed.is_empty() {
+            bytes.to_vec()
+        } else {
+            let (output, sequences) = self
+                .splitters
+                .entry(pane)
+                .or_default()
+                .split(bytes, &claimed);
+            for sequence in sequences {
+                for plugin in &mut self.plugins {
+                    if plugin.osc_numbers().contains(&sequence.number) {
+                        let name = plugin.name().to_string();
+                        effects.extend(
+                            plugin
+                                .on_osc(pane, sequence.number, &sequence.payload)
+                                .into_iter()
+                                .map(|effect| (name.clone(), effect)),
+                        );
+                    }
+                }
+            }
+            output
+        };
+        for plugin in &mut self.plugins {
+            plugin.filter_output(pane, &mut output);
+        }
+        (Cow::Owned(output), effects)
+    }
+
+    /// Every plugin's commands, with the index of the plugin.
+    pub fn commands(&self) -> Vec<(usize, PluginCommand)> {
+        self.plugins
+            .iter()
+            .enumerate()
+            .flat_map(|(index, plugin)| {
+                plugin
+                    .commands()
+                    .into_iter()
+                    .map(move |command| (index, command))
+            })
+            .collect()
+    }
+
+    pub fn plugin_name(&self, index: usize) -> Option<&str> {
+        self.plugins.get(index).map(|plugin| plugin.name())
+    }
+
+    pub fn run_command(
+        &mut self,
+        index: usize,
+        id: &str,
+        context: &CommandContext,
+    ) -> Vec<(String, Effect)> {
+        let Some(plugin) = self.plugins.get_mut(index) else {
+            return Vec::new();
+        };
+        let name = plugin.name().to_string();
+        plugin
+            .run_command(id, context)
+            .into_iter()
+            .map(|effect| (name.clone(), effect))
+            .collect()
+    }
+
+    /// Drops the output state of a closed pane.
+    pub fn forget_pane(&mut self, pane: u64) {
+        self.splitters.remove(&pane);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Shouty;
+
+    impl Plugin for Shouty {
+        fn name(&self) -> &str {
+            "shouty"
+        }
+
+        fn osc_numbers(&self) -> &[u16] {
+            &[9001]
+        }
+
+        fn on_osc(&mut self, _pane: u64, _number: u16, payload: &str) -> Vec<Effect> {
+            vec![Effect::Notify(payload.to_string())]
+        }
+
+        fn filter_output(&mut self, _pane: u64, bytes: &mut Vec<u8>) {
+            bytes.make_ascii_uppercase();
+        }
+    }
+
+    #[test]
+    fn output_goes_through_osc_handlers_then_filters() {
+        let mut host = PluginHost::with(vec![Box::new(Shouty)]);
+        let (output, effects) = host.process_output(1, b"hi\x1b]9001;done\x07there");
+        assert_eq!(&*output, b"HITHERE");
+        assert_eq!(
+            effects,
+            [("shouty".to_string(), Effect::Notify("done".into()))]
+        );
+    }
+
+    #[test]
+    fn without_plugins_output_is_borrowed_as_is() {
+        let mut host = PluginHost::default();
+        let (output, effects) = host.process_output(1, b"\x1b]9001;x\x07");
+        assert!(matches!(output, Cow::Borrowed(_)));
+        assert!(effects.is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+//! Takes the OSC sequences plugins registered for out of the output before
+//! the terminal parses it. Other sequences pass through untouched.
+
+/// A sequence still unterminated past this many bytes is given up on and
+/// passed on as ordinary output.
+const MAX_PAYLOAD: usize = 1024 * 1024;
+
+/// One `OSC <number> ; <payload>` sequence meant for a plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OscSequence {
+    pub number: u16,
+    pub payload: String,
+}
+
+/// Per-pane scanner. A sequence split across reads is held back until its
+/// terminator arrives.
+#[derive(Debug, Default)]
+pub(super) struct OscSplitter {
+    held: Vec<u8>,
+}
+
+impl OscSplitter {
+    /// Returns the output with the claimed sequences removed, and those
+    /// sequences in order.
+    pub(super) fn split(&mut self, input: &[u8], claimed: &[u16]) -> (Vec<u8>, Vec<OscSequence>) {
+        let mut bytes = std::mem::take(&mut self.held);
+        bytes.extend_from_slice(input);
+
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut sequences = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let Some(offset) = bytes[i..].windows(2).position(|w| w == b"\x1b]") else {
+                // A trailing ESC may begin a sequence the next read ends.
+                if bytes.last() == Some(&0x1b) {
+                    out.extend_from_slice(&bytes[i..bytes.len() - 1]);
+                    self.held.push(0x1b);
+                } else {
+                    out.extend_from_slice(&bytes[i..]);
+                }
+                break;
+            };
+            let start = i + offset;
+            out.extend_from_slice(&bytes[i..start]);
+            let body = start + 2;
+            let digits = bytes[body..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+            let Some(&after) = bytes.get(body + digits) else {
+                // The number is not finished yet.
+                self.hold(&bytes[start..], &mut out);
+                break;
+            };
+            let number = std::str::from_utf8(&bytes[body..body + digits])
+                .ok()
+                .and_then(|digits| digits.parse::<u16>().ok());
+            let Some(number) = number.filter(|n| after == b';' && claimed.contains(n)) else {
+                out.extend_from_slice(&bytes[start..body]);
+                i = body;
+                continue;
+            };
+            let payload_start = body + digits + 1;
+            let Some((payload_end, next)) = terminator(&bytes[payload_start..]) else {
+                self.hold(&bytes[start..], &mut out);
+                break;
+            };
+            sequences.push(OscSequence {
+                number,
+                payload: String::from_utf8_lossy(
+                    &bytes[payload_start..payload_start + payload_end],
+                )
+                .into_owned(),
+            });
+            i = payload_start + next;
+        }
+        (out, sequences)
+    }
+
+    /// Keeps `rest` for the next read, or passes it through to `out` once it
+    /// is too long to be a sequence still on its way: a stray `ESC ]` must
+    /// not swallow the output after it.
+    fn hold(&mut self, rest: &[u8], out: &mut Vec<u8>) {
+        if rest.len() <= MAX_PAYLOAD {
+            self.held = rest.to_vec();
+        } else {
+            out.extend_from_slice(rest);
+        }
+    }
+}
+
+/// Where the payload ends and where the text after the terminator starts:
+/// BEL or ST (`ESC \`).
+fn terminator(payload: &[u8]) -> Option<(usize, usize)> {
+    let mut i = 0;
+    while i < payload.len() {
+        match payload[i] {
+            0x07 => return Some((i, i + 1)),
+            0x1b if payload.get(i + 1) == Some(&b'\\') => return Some((i, i + 2)),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claimed_sequences_are_taken_out_and_others_pass() {
+        let mut splitter = OscSplitter::default();
+        let (out, found) = splitter.split(
+            b"a\x1b]7777;hello\x07b\x1b]0;title\x07c\x1b]7777;x\x1b\\d",
+            &[7777],
+        );
+        assert_eq!(out, b"ab\x1b]0;title\x07cd");
+        assert_eq!(
+            found,
+            [
+                OscSequence {
+                    number: 7777,
+                    payload: "hello".into()
+                },
+                OscSequence {
+                    number: 7777,
+                    payload: "x".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn a_sequence_split_across_reads_is_held_back() {
+        let mut splitter = OscSplitter::default();
+        let (out, found) = splitter.split(b"one\x1b]77", &[7777]);
+        assert_eq!(out, b"one");
+        assert!(found.is_empty());
+        let (out, found) = splitter.split(b"77;pay", &[7777]);
+        assert!(out.is_empty() && found.is_empty());
+        let (out, found) = splitter.split(b"load\x07two\x1b", &[7777]);
+        assert_eq!(out, b"two");
+        assert_eq!(found[0].payload, "payload");
+        let (out, _) = splitter.split(b"[1m", &[7777]);
+        assert_eq!(out, b"\x1b[1m");
+    }
+
+    #[test]
+    fn an_unterminated_sequence_gives_way_to_the_output_after_it() {
+        let mut splitter = OscSplitter::default();
+        let (out, _) = splitter.split(b"a\x1b]7777;", &[7777]);
+        assert_eq!(out, b"a");
+        let text = vec![b'x'; MAX_PAYLOAD];
+        let (out, found) = splitter.split(&text, &[7777]);
+        assert!(found.is_empty());
+        assert_eq!(out.len(), 7 + MAX_PAYLOAD);
+        assert!(out.starts_with(b"\x1b]7777;x"));
+        assert!(splitter.held.is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+//! Automation scripts. `init.rhai` in the config directory runs at startup
+//! and drives the app through a handful of functions:
+//!
+//! ```rhai
+//! for host in ["web1", "web2", "db1"] {
+//!     open_tab();                 // or open_tab("Profile name")
+//!     set_title(host);
+//!     send("ssh " + host + "\r");
+//!     send("tail -f /var/log/syslog\r");
+//! }
+//!
+//! bind("Ctrl+Shift+G", || {
+//!     if screen().contains("$ ") { send("git status\r"); }
+//! });
+//! ```
+//!
+//! Functions queue actions that the app carries out once the script
+//! returns, in order; `screen()` is the focused pane's screen as it was when
+//! the script started. Key bindings use the shortcut syntax of the settings
+//! and are checked before the built-in shortcuts.
+
+use rhai::{AST, Dynamic, Engine, FnPtr};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+const INIT_SCRIPT: &str = "init.rhai";
+/// Bounds a runaway script, which would otherwise freeze the window.
+const MAX_OPERATIONS: u64 = 5_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptAction {
+    /// Open a tab with the profile of this name, or the default shell.
+    OpenTab(Option<String>),
+    /// Type text into the focused pane.
+    Send(String),
+    /// Rename the focused pane.
+    SetTitle(String),
+}
+
+#[derive(Default)]
+struct Shared {
+    actions: Vec<ScriptAction>,
+    bindings: Vec<(String, FnPtr)>,
+    screen: String,
+}
+
+pub struct Scripts {
+    engine: Engine,
+    ast: AST,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Scripts {
+    /// Runs the init script, if there is one, and returns what it queued.
+    /// A script that fails is reported and skipped.
+    pub fn load() -> Option<(Self, Vec<ScriptAction>)> {
+        let path = init_script_path()?;
+        let source = std::fs::read_to_string(&path).ok()?;
+        match Self::run(&source) {
+            Ok(loaded) => Some(loaded),
+            Err(err) => {
+                eprintln!("Warning: init script {} failed: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    fn run(source: &str) -> Result<(Self, Vec<ScriptAction>), String> {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        register(&mut engine, &shared);
+
+        let ast = engine.compile(source).map_err(|err| err.to_string())?;
+        engine.run_ast(&ast).map_err(|err| err.to_string())?;
+        let scripts = Self {
+            engine,
+            ast,
+            shared,
+        };
+        let actions = scripts.take_actions();
+        Ok((scripts, actions))
+    }
+
+    /// The index of the first binding `matches` accepts.
+    pub fn binding(&self, matches: impl Fn(&str) -> bool) -> Option<usize> {
+        let shared = self.shared.lock().ok()?;
+        shared.bindings.iter().position(|(keys, _)| matches(keys))
+    }
+
+    /// Runs a bound function with `screen` as what `screen()` returns.
+    pub fn run_binding(&self, index: usize, screen: String) -> Result<Vec<ScriptAction>, String> {
+        let function = {
+            let mut shared = self.shared.lock().map_err(|err| err.to_string())?;
+            shared.screen = screen;
+            let Some((_, function)) = shared.bindings.get(index) else {
+                return Ok(Vec::new());
+            };
+            function.clone()
+        };
+        let result = function.call::<Dynamic>(&self.engine, &self.ast, ());
+        let actions = self.take_actions();
+        result.map(|_| actions).map_err(|err| err.to_string())
+    }
+
+    fn take_actions(&self) -> Vec<ScriptAction> {
+        self.shared
+            .lock()
+            .map(|mut shared| std::mem::take(&mut shared.actions))
+            .unwrap_or_default()
+    }
+}
+
+fn register(engine: &mut Engine, shared: &Arc<Mutex<Shared>>) {
+    let queue = |shared: &Arc<Mutex<Shared>>| {
+        let shared = Arc::clone(shared);
+        move |action: ScriptAction| {
+            if let Ok(mut shared) = shared.lock() {
+                shared.actions.push(action);
+            }
+        }
+    };
+
+    let push = queue(shared);
+    engine.register_fn("open_tab", move || push(ScriptAction::OpenTab(None)));
+    let push = queue(shared);
+    engine.register_fn("open_tab", move |profile: &str| {
+        push(ScriptAction::OpenTab(Some(profile.to_string())))
+    });
+    let push = queue(shared);
+    engine.register_fn("send", move |text: &str| {
+        push(ScriptAction::Send(text.to_string()))
+    });
+    let push = queue(shared);
+    engine.register_fn("set_title", move |title: &str| {
+        push(ScriptAction::SetTitle(title.to_string()))
+    });
+
+    let state = Arc::clone(shared);
+    engine.register_fn("screen", move || {
+        state
+            .lock()
+            .map(|shared| shared.screen.clone())
+            .unwrap_or_default()
+    });
+    let state = Arc::clone(shared);
+    engine.register_fn("bind", move |keys: &str, function: FnPtr| {
+        if let Ok(mut shared) = state.lock() {
+            shared.bindings.push((keys.to_string(), function));
+        }
+    });
+}
+
+fn init_script_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("rabbitty").join(INIT_SCRIPT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_init_script_queues_actions_in_order() {
+        let (_, actions) = Scripts::run(
+            r#"
+            for host in ["web1", "db1"] {
+                open_tab();
+                send("ssh " + host + "\r");
+            }
+            open_tab("Logs");
+            set_title("logs");
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            actions,
+            [
+                ScriptAction::OpenTab(None),
+                ScriptAction::Send("ssh web1\r".into()),
+                ScriptAction::OpenTab(None),
+                ScriptAction::Send("ssh db1\r".into()),
+                ScriptAction::OpenTab(Some("Logs".into())),
+                ScriptAction::SetTitle("logs".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn bindings_run_later_and_read_the_screen() {
+        let (scripts, actions) = Scripts::run(
+            r#"
+            let command = "git status";
+            bind("Ctrl+Shift+G", || {
+                if screen().contains("$ ") { send(command + "\r"); }
+            });
+            "#,
+        )
+        .unwrap();
+        assert!(actions.is_empty());
+        let index = scripts.binding(|keys| keys == "Ctrl+Shift+G").unwrap();
+        assert_eq!(
+            scripts.run_binding(index, "~ $ ".into()).unwrap(),
+            [ScriptAction::Send("git status\r".into())]
+        );
+        assert!(scripts.run_binding(index, "vim".into()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn runaway_scripts_are_stopped() {
+        assert!(Scripts::run("loop {}").is_err());
+        assert!(Scripts::run("open_tab(").is_err());
+    }
+}
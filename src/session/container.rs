@@ -0,0 +1,176 @@
+//! Running Docker and Podman containers, listed in the shell picker and
+//! opened as `<runtime> exec -it` tabs.
+
+use crate::gui::tab::{Profile, ProfileKind};
+use std::collections::BTreeMap;
+use std::process::Command;
+
+/// Shell started in a container without an override.
+pub const DEFAULT_CONTAINER_SHELL: &str = "/bin/sh";
+
+/// `ps` output, one container per line. Both runtimes accept Go templates,
+/// and tabs cannot appear in ids, names or image references.
+const PS_FORMAT: &str = "{{.ID}}\t{{.Names}}\t{{.Image}}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Runtime {
+    Docker,
+    Podman,
+}
+
+impl Runtime {
+    pub const ALL: [Self; 2] = [Self::Docker, Self::Podman];
+
+    pub fn program(self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Container {
+    pub runtime: Runtime,
+    pub id: String,
+    pub name: String,
+    pub image: String,
+}
+
+impl Container {
+    /// The shell configured for this container by name, then by image.
+    pub fn shell<'a>(&self, overrides: &'a BTreeMap<String, String>) -> &'a str {
+        overrides
+            .get(&self.name)
+            .or_else(|| overrides.get(&self.image))
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_CONTAINER_SHELL)
+    }
+
+    /// A local profile that execs `shell` in the container with a TERM that
+    /// matches what the terminal emulates.
+    pub fn profile(&self, shell: &str) -> Profile {
+        let args = [
+            "exec",
+            "-it",
+            "-e",
+            "TERM=xterm-256color",
+            "-e",
+            "COLORTERM=truecolor",
+            &self.id,
+            shell,
+        ];
+        Profile {
+            name: self.name.clone(),
+            icon: None,
+            color: None,
+            kind: ProfileKind::Local {
+                program: Some(self.runtime.program().to_string()),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                login: None,
+                command: None,
+                env: BTreeMap::new(),
+                elevated: false,
+            },
+            actions: Vec::new(),
+            startup_commands: Vec::new(),
+        }
+    }
+}
+
+/// Running containers of every runtime that is installed and reachable.
+/// Runtimes that fail, e.g. because their daemon is down, list nothing.
+pub fn list() -> Vec<Container> {
+    Runtime::ALL
+        .into_iter()
+        .flat_map(|runtime| {
+            let output = Command::new(runtime.program())
+                .args(["ps", "--format", PS_FORMAT])
+                .output();
+            match output {
+                Ok(output) if output.status.success() => {
+                    parse_ps(runtime, &String::from_utf8_lossy(&output.stdout))
+                }
+                _ => Vec::new(),
+            }
+        })
+        .collect()
+}
+
+fn parse_ps(runtime: Runtime, output: &str) -> Vec<Container> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let id = fields.next()?.trim();
+            let names = fields.next()?.trim();
+            let image = fields.next().unwrap_or("").trim();
+            if id.is_empty() {
+                return None;
+            }
+            // Docker joins several names with commas.
+            let name = names.split(',').next().unwrap_or("").trim();
+            Some(Container {
+                runtime,
+                id: id.to_string(),
+                name: if name.is_empty() { id } else { name }.to_string(),
+                image: image.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ps_lines_and_skips_blank_ones() {
+        let output = "3f2a\tweb,web-alias\tnginx:1.27\n\n9c1b\t\tpostgres:16\n";
+        let containers = parse_ps(Runtime::Docker, output);
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[0].name, "web");
+        assert_eq!(containers[0].image, "nginx:1.27");
+        assert_eq!(containers[1].name, "9c1b", "unnamed containers use the id");
+    }
+
+    #[test]
+    fn shell_override_matches_name_before_image() {
+        let container = Container {
+            runtime: Runtime::Podman,
+            id: "9c1b".into(),
+            name: "db".into(),
+            image: "postgres:16".into(),
+        };
+        let mut overrides = BTreeMap::new();
+        assert_eq!(container.shell(&overrides), DEFAULT_CONTAINER_SHELL);
+        overrides.insert("postgres:16".to_string(), "/bin/bash".to_string());
+        assert_eq!(container.shell(&overrides), "/bin/bash");
+        overrides.insert("db".to_string(), "/usr/bin/fish".to_string());
+        assert_eq!(container.shell(&overrides), "/usr/bin/fish");
+
+        let profile = container.profile(container.shell(&overrides));
+        let ProfileKind::Local {
+            program: Some(program),
+            args,
+            ..
+        } = profile.kind
+        else {
+            panic!("expected a local profile");
+        };
+        assert_eq!(program, "podman");
+        assert_eq!(
+            args,
+            [
+                "exec",
+                "-it",
+                "-e",
+                "TERM=xterm-256color",
+                "-e",
+                "COLORTERM=truecolor",
+                "9c1b",
+                "/usr/bin/fish"
+            ]
+        );
+    }
+}
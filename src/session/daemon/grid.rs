@@ -0,0 +1,83 @@
+//! Grid updates for clients attached over a slow link: rather than every
+//! byte the program wrote, the client is sent the rows that changed since
+//! its last update, so output that scrolled past while the link stalled
+//! costs nothing to catch up on.
+
+use crate::terminal::TerminalEngine;
+use std::io::Write;
+
+/// What one client was last sent.
+#[derive(Default)]
+pub(super) struct GridStream {
+    rows: Vec<Vec<u8>>,
+    modes: String,
+    cursor: Option<(usize, usize)>,
+}
+
+impl GridStream {
+    /// Escape sequences that bring the client from its last update to what
+    /// `screen` shows now; `None` when nothing changed. The first update
+    /// clears the client's screen and draws every row.
+    pub(super) fn update(&mut self, screen: &TerminalEngine) -> Option<Vec<u8>> {
+        let rows = screen.repaint_rows();
+        let modes = screen.input_mode_sequences();
+        let cursor = screen.cursor_cell();
+
+        let mut out = Vec::new();
+        if rows.len() != self.rows.len() {
+            out.extend_from_slice(b"\x1b[H\x1b[2J");
+            self.rows.clear();
+        }
+        for (index, row) in rows.iter().enumerate() {
+            if self.rows.get(index) != Some(row) {
+                out.extend_from_slice(row);
+            }
+        }
+        if modes != self.modes {
+            out.extend_from_slice(modes.as_bytes());
+        }
+        if out.is_empty() && cursor == self.cursor {
+            return None;
+        }
+        match cursor {
+            Some((col, row)) => {
+                let _ = write!(out, "\x1b[{};{}H\x1b[?25h", row + 1, col + 1);
+            }
+            None => out.extend_from_slice(b"\x1b[?25l"),
+        }
+
+        self.rows = rows;
+        self.modes = modes;
+        self.cursor = cursor;
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::TerminalSize;
+
+    #[test]
+    fn only_changed_rows_are_sent_after_the_first_update() {
+        let size = TerminalSize::new(20, 4);
+        let mut screen = TerminalEngine::headless(size);
+        screen.feed_bytes(b"top line\r\nprompt$ ");
+        let mut grid = GridStream::default();
+
+        let first = grid.update(&screen).expect("the first update draws");
+        let mut client = TerminalEngine::headless(size);
+        client.feed_bytes(&first);
+        assert_eq!(client.screen_text(), screen.screen_text());
+        assert!(grid.update(&screen).is_none(), "nothing changed");
+
+        screen.feed_bytes(b"ls");
+        let next = grid.update(&screen).expect("a row changed");
+        let text = String::from_utf8_lossy(&next);
+        assert!(text.contains("prompt$ ls"));
+        assert!(!text.contains("top line"), "unchanged rows are not resent");
+        client.feed_bytes(&next);
+        assert_eq!(client.screen_text(), screen.screen_text());
+        assert_eq!(client.cursor_cell(), screen.cursor_cell());
+    }
+}
@@ -0,0 +1,146 @@
+//! Session keep-alive: local shells can run inside a background daemon
+//! instead of the GUI process, so they outlive a crash or a closed window
+//! and are re-attached on the next launch.
+//!
+//! The daemon is this same binary started with `--daemon`. It listens on a
+//! Unix socket in the runtime directory; each GUI connection drives one
+//! session. Windows has no daemon and always runs shells in-process.
+//!
+//! `rabbitty attach` connects its standard input and output to the daemon,
+//! starting one if needed. A GUI on another machine runs it over SSH and
+//! speaks the same protocol through the channel.
+
+#[cfg(unix)]
+mod grid;
+mod protocol;
+#[cfg(unix)]
+mod server;
+
+pub use protocol::SessionInfo;
+pub(crate) use protocol::{Reply, Request};
+
+/// Command-line flag that runs the daemon instead of the GUI.
+pub const DAEMON_FLAG: &str = "--daemon";
+/// Subcommand that bridges standard input and output to the daemon.
+pub const ATTACH_COMMAND: &str = "attach";
+
+#[cfg(unix)]
+pub use unix::*;
+
+#[cfg(not(unix))]
+pub fn run() {}
+
+#[cfg(not(unix))]
+pub fn bridge_stdio() -> std::io::Result<()> {
+    Err(std::io::Error::other("no session daemon on this platform"))
+}
+
+/// Sessions a running daemon is keeping alive. Always empty on Windows.
+#[cfg(not(unix))]
+pub fn list() -> Vec<SessionInfo> {
+    Vec::new()
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::{DAEMON_FLAG, Reply, Request, SessionInfo};
+    use std::io::{self, Read, Write};
+    use std::net::Shutdown;
+    use std::os::unix::net::UnixStream;
+    use std::os::unix::process::CommandExt;
+    use std::path::PathBuf;
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, Instant};
+
+    /// How long a GUI waits for a daemon it started to start listening.
+    const START_TIMEOUT: Duration = Duration::from_secs(3);
+
+    /// Runs the daemon in this process until its last session ends.
+    pub fn run() {
+        let Some(socket) = socket_path() else {
+            eprintln!("No directory for the session daemon socket");
+            return;
+        };
+        if let Err(err) = super::server::serve(&socket) {
+            eprintln!("Session daemon failed: {err}");
+        }
+    }
+
+    /// Relays frames between standard input and output and the daemon
+    /// until either side closes. Closing standard input detaches.
+    pub fn bridge_stdio() -> io::Result<()> {
+        let mut downstream = connect()?;
+        let mut upstream = downstream.try_clone()?;
+        std::thread::spawn(move || {
+            let _ = io::copy(&mut io::stdin().lock(), &mut upstream);
+            let _ = upstream.shutdown(Shutdown::Write);
+        });
+
+        let mut stdout = io::stdout().lock();
+        let mut buf = [0u8; 16 * 1024];
+        loop {
+            let n = downstream.read(&mut buf)?;
+            if n == 0 {
+                return Ok(());
+            }
+            stdout.write_all(&buf[..n])?;
+            stdout.flush()?;
+        }
+    }
+
+    /// Sessions a running daemon is keeping alive; empty when none runs.
+    pub fn list() -> Vec<SessionInfo> {
+        let Some(mut stream) = socket_path().and_then(|path| UnixStream::connect(path).ok()) else {
+            return Vec::new();
+        };
+        if Request::List.write_to(&mut stream).is_err() {
+            return Vec::new();
+        }
+        match Reply::read_from(&mut stream) {
+            Ok(Some(Reply::Sessions(sessions))) => sessions,
+            _ => Vec::new(),
+        }
+    }
+
+    /// Connects to the daemon, starting one first if none is listening.
+    pub(crate) fn connect() -> io::Result<UnixStream> {
+        let socket =
+            socket_path().ok_or_else(|| io::Error::other("no directory for the daemon socket"))?;
+        if let Ok(stream) = UnixStream::connect(&socket) {
+            return Ok(stream);
+        }
+
+        let mut child = Command::new(std::env::current_exe()?)
+            .arg(DAEMON_FLAG)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn()?;
+        // Reap the daemon if it exits while this GUI is still running.
+        std::thread::spawn(move || child.wait());
+
+        let deadline = Instant::now() + START_TIMEOUT;
+        loop {
+            match UnixStream::connect(&socket) {
+                Ok(stream) => return Ok(stream),
+                Err(err) if Instant::now() >= deadline => return Err(err),
+                Err(_) => std::thread::sleep(Duration::from_millis(20)),
+            }
+        }
+    }
+
+    /// `rabbitty-<user>/daemon.sock` in the runtime directory, created
+    /// private to the user.
+    fn socket_path() -> Option<PathBuf> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let user = std::env::var("USER").unwrap_or_default();
+        let dir = dirs::runtime_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join(format!("rabbitty-{user}"));
+        std::fs::create_dir_all(&dir).ok()?;
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700)).ok()?;
+        Some(dir.join("daemon.sock"))
+    }
+}
@@ -0,0 +1,431 @@
+//! Frames exchanged with the session daemon over its socket, or over an SSH
+//! channel running `rabbitty attach`: a big-endian `u32` length, a tag byte
+//! and the fields, strings and byte strings each prefixed with their `u32`
+//! length.
+
+use std::io::{self, Read, Write};
+
+/// Frames larger than this are refused rather than allocated.
+const MAX_FRAME: usize = 16 * 1024 * 1024;
+
+/// What the GUI asks of the daemon. The first request on a connection picks
+/// the session it is about; the rest act on that session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Request {
+    /// An empty `program` starts the user's login shell.
+    Spawn {
+        program: String,
+        args: Vec<String>,
+        env: Vec<(String, String)>,
+        rows: u16,
+        cols: u16,
+        cwd: Option<String>,
+        /// Opaque to the daemon; handed back by `List` so a relaunched GUI
+        /// knows what the session was started from.
+        profile: String,
+        grid: bool,
+    },
+    /// With `grid`, output arrives as redraws of the changed rows at a
+    /// bounded rate instead of the raw bytes, so a slow or dropped link
+    /// costs at most one screen to catch up.
+    Attach {
+        id: u64,
+        grid: bool,
+    },
+    List,
+    Input(Vec<u8>),
+    Resize {
+        rows: u16,
+        cols: u16,
+    },
+    Kill,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Reply {
+    Spawned { id: u64, pid: u32 },
+    Output(Vec<u8>),
+    Exited,
+    Sessions(Vec<SessionInfo>),
+    Error(String),
+}
+
+/// A session the daemon is keeping alive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionInfo {
+    pub id: u64,
+    pub pid: u32,
+    pub profile: String,
+}
+
+impl Request {
+    pub(crate) fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut frame = Frame::default();
+        match self {
+            Self::Spawn {
+                program,
+                args,
+                env,
+                rows,
+                cols,
+                cwd,
+                profile,
+                grid,
+            } => {
+                frame.tag(1).str(program).u32(args.len() as u32);
+                for arg in args {
+                    frame.str(arg);
+                }
+                frame.u32(env.len() as u32);
+                for (name, value) in env {
+                    frame.str(name).str(value);
+                }
+                frame
+                    .u16(*rows)
+                    .u16(*cols)
+                    .str(cwd.as_deref().unwrap_or(""))
+                    .str(profile)
+                    .flag(*grid);
+            }
+            Self::Attach { id, grid } => {
+                frame.tag(2).u64(*id).flag(*grid);
+            }
+            Self::List => {
+                frame.tag(3);
+            }
+            Self::Input(bytes) => {
+                frame.tag(4).bytes(bytes);
+            }
+            Self::Resize { rows, cols } => {
+                frame.tag(5).u16(*rows).u16(*cols);
+            }
+            Self::Kill => {
+                frame.tag(6);
+            }
+        }
+        frame.send(out)
+    }
+
+    /// The frame for this request, for transports that are not `Write`.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let _ = self.write_to(&mut bytes);
+        bytes
+    }
+
+    /// The next request, or `None` once the peer has closed the connection.
+    pub(crate) fn read_from(input: &mut impl Read) -> io::Result<Option<Self>> {
+        let Some((tag, mut fields)) = read_frame(input)? else {
+            return Ok(None);
+        };
+        let request = match tag {
+            1 => {
+                let program = fields.str()?;
+                let args = (0..fields.u32()?)
+                    .map(|_| fields.str())
+                    .collect::<io::Result<_>>()?;
+                let env = (0..fields.u32()?)
+                    .map(|_| Ok((fields.str()?, fields.str()?)))
+                    .collect::<io::Result<_>>()?;
+                let rows = fields.u16()?;
+                let cols = fields.u16()?;
+                let cwd = Some(fields.str()?).filter(|cwd| !cwd.is_empty());
+                Self::Spawn {
+                    program,
+                    args,
+                    env,
+                    rows,
+                    cols,
+                    cwd,
+                    profile: fields.str()?,
+                    grid: fields.flag()?,
+                }
+            }
+            2 => Self::Attach {
+                id: fields.u64()?,
+                grid: fields.flag()?,
+            },
+            3 => Self::List,
+            4 => Self::Input(fields.bytes()?),
+            5 => Self::Resize {
+                rows: fields.u16()?,
+                cols: fields.u16()?,
+            },
+            6 => Self::Kill,
+            other => return Err(invalid(format!("unknown request {other}"))),
+        };
+        Ok(Some(request))
+    }
+}
+
+impl Reply {
+    pub(crate) fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut frame = Frame::default();
+        match self {
+            Self::Spawned { id, pid } => {
+                frame.tag(1).u64(*id).u32(*pid);
+            }
+            Self::Output(bytes) => {
+                frame.tag(2).bytes(bytes);
+            }
+            Self::Exited => {
+                frame.tag(3);
+            }
+            Self::Sessions(sessions) => {
+                frame.tag(4).u32(sessions.len() as u32);
+                for session in sessions {
+                    frame.u64(session.id).u32(session.pid).str(&session.profile);
+                }
+            }
+            Self::Error(message) => {
+                frame.tag(5).str(message);
+            }
+        }
+        frame.send(out)
+    }
+
+    pub(crate) fn read_from(input: &mut impl Read) -> io::Result<Option<Self>> {
+        let Some((tag, mut fields)) = read_frame(input)? else {
+            return Ok(None);
+        };
+        let reply = match tag {
+            1 => Self::Spawned {
+                id: fields.u64()?,
+                pid: fields.u32()?,
+            },
+            2 => Self::Output(fields.bytes()?),
+            3 => Self::Exited,
+            4 => Self::Sessions(
+                (0..fields.u32()?)
+                    .map(|_| {
+                        Ok(SessionInfo {
+                            id: fields.u64()?,
+                            pid: fields.u32()?,
+                            profile: fields.str()?,
+                        })
+                    })
+                    .collect::<io::Result<_>>()?,
+            ),
+            5 => Self::Error(fields.str()?),
+            other => return Err(invalid(format!("unknown reply {other}"))),
+        };
+        Ok(Some(reply))
+    }
+
+    /// Takes the first complete reply off the front of `buf`, for readers
+    /// that receive the stream in arbitrary chunks. `None` until one has
+    /// fully arrived.
+    pub(crate) fn take_from(buf: &mut Vec<u8>) -> io::Result<Option<Self>> {
+        let Some(len) = buf.get(..4) else {
+            return Ok(None);
+        };
+        let end = 4 + u32::from_be_bytes(len.try_into().unwrap()) as usize;
+        if end > 4 + MAX_FRAME {
+            return Err(invalid(format!("bad frame length {}", end - 4)));
+        }
+        if buf.len() < end {
+            return Ok(None);
+        }
+        let reply = Self::read_from(&mut &buf[..end])?;
+        buf.drain(..end);
+        Ok(reply)
+    }
+}
+
+#[derive(Default)]
+struct Frame {
+    body: Vec<u8>,
+}
+
+impl Frame {
+    fn tag(&mut self, tag: u8) -> &mut Self {
+        self.body.push(tag);
+        self
+    }
+
+    fn u16(&mut self, value: u16) -> &mut Self {
+        self.body.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    fn u32(&mut self, value: u32) -> &mut Self {
+        self.body.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    fn u64(&mut self, value: u64) -> &mut Self {
+        self.body.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.u32(bytes.len() as u32);
+        self.body.extend_from_slice(bytes);
+        self
+    }
+
+    fn str(&mut self, value: &str) -> &mut Self {
+        self.bytes(value.as_bytes())
+    }
+
+    fn flag(&mut self, value: bool) -> &mut Self {
+        self.body.push(value as u8);
+        self
+    }
+
+    /// Writes the frame in one call, so frames from threads sharing a
+    /// locked stream never interleave.
+    fn send(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut packet = Vec::with_capacity(4 + self.body.len());
+        packet.extend_from_slice(&(self.body.len() as u32).to_be_bytes());
+        packet.extend_from_slice(&self.body);
+        out.write_all(&packet)?;
+        out.flush()
+    }
+}
+
+struct Fields {
+    body: Vec<u8>,
+    pos: usize,
+}
+
+impl Fields {
+    fn take(&mut self, len: usize) -> io::Result<&[u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.body.len())
+            .ok_or_else(|| invalid("frame ends early".to_string()))?;
+        let slice = &self.body[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn bytes(&mut self) -> io::Result<Vec<u8>> {
+        let len = self.u32()? as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn str(&mut self) -> io::Result<String> {
+        String::from_utf8(self.bytes()?).map_err(|err| invalid(err.to_string()))
+    }
+
+    fn flag(&mut self) -> io::Result<bool> {
+        Ok(self.take(1)?[0] != 0)
+    }
+}
+
+/// The tag and fields of the next frame; `None` at a clean end of stream.
+fn read_frame(input: &mut impl Read) -> io::Result<Option<(u8, Fields)>> {
+    let mut len = [0u8; 4];
+    match input.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let len = u32::from_be_bytes(len) as usize;
+    if len == 0 || len > MAX_FRAME {
+        return Err(invalid(format!("bad frame length {len}")));
+    }
+    let mut body = vec![0u8; len];
+    input.read_exact(&mut body)?;
+    let tag = body[0];
+    Ok(Some((tag, Fields { body, pos: 1 })))
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_and_replies_survive_a_round_trip() {
+        let requests = [
+            Request::Spawn {
+                program: "/bin/zsh".to_string(),
+                args: vec!["-l".to_string()],
+                env: vec![("TERM".to_string(), "xterm-256color".to_string())],
+                rows: 24,
+                cols: 80,
+                cwd: Some("/tmp".to_string()),
+                profile: "name = \"zsh\"".to_string(),
+                grid: false,
+            },
+            Request::Attach { id: 7, grid: true },
+            Request::Input(b"ls\r".to_vec()),
+            Request::Resize {
+                rows: 50,
+                cols: 200,
+            },
+            Request::Kill,
+        ];
+        let mut wire = Vec::new();
+        for request in &requests {
+            request.write_to(&mut wire).unwrap();
+        }
+        let mut input = wire.as_slice();
+        for request in &requests {
+            assert_eq!(
+                Request::read_from(&mut input).unwrap().as_ref(),
+                Some(request)
+            );
+        }
+        assert_eq!(Request::read_from(&mut input).unwrap(), None);
+
+        let reply = Reply::Sessions(vec![SessionInfo {
+            id: 3,
+            pid: 4242,
+            profile: String::new(),
+        }]);
+        let mut wire = Vec::new();
+        reply.write_to(&mut wire).unwrap();
+        assert_eq!(Reply::read_from(&mut wire.as_slice()).unwrap(), Some(reply));
+    }
+
+    #[test]
+    fn truncated_and_oversized_frames_are_errors() {
+        let mut wire = Vec::new();
+        Request::Attach { id: 1, grid: false }
+            .write_to(&mut wire)
+            .unwrap();
+        wire.truncate(wire.len() - 1);
+        assert!(Request::read_from(&mut wire.as_slice()).is_err());
+
+        let oversized = (MAX_FRAME as u32 + 1).to_be_bytes();
+        assert!(Reply::read_from(&mut oversized.as_slice()).is_err());
+    }
+
+    #[test]
+    fn replies_are_taken_once_they_have_fully_arrived() {
+        let mut wire = Vec::new();
+        Reply::Output(b"hello".to_vec())
+            .write_to(&mut wire)
+            .unwrap();
+        Reply::Exited.write_to(&mut wire).unwrap();
+
+        let mut buf = wire[..6].to_vec();
+        assert_eq!(Reply::take_from(&mut buf).unwrap(), None);
+        buf.extend_from_slice(&wire[6..]);
+        assert_eq!(
+            Reply::take_from(&mut buf).unwrap(),
+            Some(Reply::Output(b"hello".to_vec()))
+        );
+        assert_eq!(Reply::take_from(&mut buf).unwrap(), Some(Reply::Exited));
+        assert!(buf.is_empty());
+    }
+}
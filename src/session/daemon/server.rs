@@ -0,0 +1,490 @@
+//! The daemon side: owns the PTYs, keeps the recent output of each so a
+//! GUI attaching later can redraw the screen, and exits once the last
+//! session ends.
+
+use super::grid::GridStream;
+use super::protocol::{Reply, Request, SessionInfo};
+use crate::session::PtyInput;
+use crate::terminal::{TerminalEngine, TerminalSize, TerminalTheme};
+use alacritty_terminal::event::{OnResize, WindowSize};
+use alacritty_terminal::tty::{self, Options, Shell};
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+/// Output kept per session for replay on attach.
+const REPLAY_BYTES: usize = 512 * 1024;
+/// How long a daemon nobody has started a session in waits before exiting.
+const IDLE_EXIT: Duration = Duration::from_secs(60);
+/// A GUI that stops reading for this long is detached rather than allowed
+/// to stall its session.
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// How often a grid client is sent the rows that changed.
+const GRID_INTERVAL: Duration = Duration::from_millis(50);
+/// Scrollback of the daemon's own copy of each screen; grid clients are
+/// only ever sent the rows on screen.
+const SCREEN_SCROLLBACK: usize = 100;
+
+/// One hosted session. Each has locks of its own, so a session waiting on a
+/// slow client or a full PTY never holds up the others.
+struct Hosted {
+    pid: u32,
+    profile: String,
+    /// The PTY's input, locked apart from `state` so typing never waits on
+    /// output.
+    input: Mutex<PtyInput>,
+    state: Mutex<HostedState>,
+}
+
+struct HostedState {
+    pty: tty::Pty,
+    replay: VecDeque<u8>,
+    /// The session's screen, kept for clients that take grid updates.
+    screen: TerminalEngine,
+    /// Lets `screen` answer the program's queries while a grid client is
+    /// attached, since that client never sees them.
+    answers: Arc<AtomicBool>,
+    client: Option<Client>,
+}
+
+/// The attached GUI connection.
+struct Client {
+    serial: u64,
+    /// Locked for each frame on its own, so frames are written without
+    /// holding the session's state.
+    stream: Arc<Mutex<UnixStream>>,
+    /// Set for clients that take grid updates instead of raw output.
+    grid: Option<GridStream>,
+}
+
+impl HostedState {
+    fn attach(&mut self, client: Client) {
+        self.answers.store(client.grid.is_some(), Ordering::Release);
+        self.client = Some(client);
+    }
+
+    /// Detaches the client of connection `serial`, unless another GUI has
+    /// attached since.
+    fn detach(&mut self, serial: u64) {
+        if self
+            .client
+            .as_ref()
+            .is_some_and(|client| client.serial == serial)
+        {
+            self.answers.store(false, Ordering::Release);
+            self.client = None;
+        }
+    }
+}
+
+/// The screen's replies to queries, passed to the program only while
+/// `enabled`; otherwise the attached GUI's own terminal answers.
+struct QueryReplies {
+    pty: PtyInput,
+    enabled: Arc<AtomicBool>,
+}
+
+impl Write for QueryReplies {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.enabled.load(Ordering::Acquire) {
+            self.pty.write_all(buf)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.pty.flush()
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    sessions: Mutex<HashMap<u64, Arc<Hosted>>>,
+    next_id: AtomicU64,
+    next_connection: AtomicU64,
+}
+
+impl Registry {
+    /// The session `id`. Held only briefly: the last handle going away is
+    /// what hangs up a killed session's shell.
+    fn session(&self, id: u64) -> Option<Arc<Hosted>> {
+        lock(&self.sessions).get(&id).cloned()
+    }
+}
+
+/// Locks `mutex`, carrying on past a thread that panicked while holding it
+/// rather than taking the whole daemon down.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Writes one frame to the client of connection `serial`, detaching it if
+/// it has stopped reading.
+fn send(hosted: &Hosted, serial: u64, stream: &Mutex<UnixStream>, reply: Reply) -> bool {
+    if reply.write_to(&mut *lock(stream)).is_err() {
+        lock(&hosted.state).detach(serial);
+        return false;
+    }
+    true
+}
+
+/// Serves on `socket` until the last session ends. Returns early if
+/// another daemon already answers there.
+pub(super) fn serve(socket: &Path) -> io::Result<()> {
+    if UnixStream::connect(socket).is_ok() {
+        return Ok(());
+    }
+    let _ = std::fs::remove_file(socket);
+    let listener = UnixListener::bind(socket)?;
+    tty::setup_env();
+
+    let registry = Arc::new(Registry {
+        next_id: AtomicU64::new(1),
+        ..Default::default()
+    });
+
+    let idle = Arc::clone(&registry);
+    let idle_socket = socket.to_path_buf();
+    thread::spawn(move || {
+        thread::sleep(IDLE_EXIT);
+        if idle.next_id.load(Ordering::Acquire) == 1 {
+            shut_down(&idle_socket);
+        }
+    });
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let registry = Arc::clone(&registry);
+        let socket = socket.to_path_buf();
+        thread::spawn(move || {
+            if let Err(err) = handle_connection(stream, &registry, &socket) {
+                eprintln!("daemon connection failed: {err}");
+            }
+        });
+    }
+    Ok(())
+}
+
+fn shut_down(socket: &Path) -> ! {
+    let _ = std::fs::remove_file(socket);
+    std::process::exit(0);
+}
+
+fn handle_connection(
+    mut stream: UnixStream,
+    registry: &Arc<Registry>,
+    socket: &Path,
+) -> io::Result<()> {
+    let Some(first) = Request::read_from(&mut stream)? else {
+        return Ok(());
+    };
+    let connection = registry.next_connection.fetch_add(1, Ordering::Relaxed);
+    let id = match first {
+        Request::List => {
+            let mut list: Vec<SessionInfo> = lock(&registry.sessions)
+                .iter()
+                .map(|(&id, hosted)| SessionInfo {
+                    id,
+                    pid: hosted.pid,
+                    profile: hosted.profile.clone(),
+                })
+                .collect();
+            list.sort_by_key(|info| info.id);
+            return Reply::Sessions(list).write_to(&mut stream);
+        }
+        Request::Spawn {
+            program,
+            args,
+            env,
+            rows,
+            cols,
+            cwd,
+            profile,
+            grid,
+        } => {
+            let options = Options {
+                shell: (!program.is_empty()).then(|| Shell::new(program, args)),
+                env: env.into_iter().collect(),
+                working_directory: cwd.map(PathBuf::from),
+                ..Default::default()
+            };
+            let id = registry.next_id.fetch_add(1, Ordering::AcqRel);
+            match spawn_pty(&options, rows, cols, id) {
+                Ok((pty, reader, writer)) => {
+                    let pid = pty.child().id();
+                    let mut client = stream.try_clone()?;
+                    client.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT))?;
+                    // Reply before the session is visible to its reader
+                    // thread, so `Spawned` is the first frame the GUI sees.
+                    Reply::Spawned { id, pid }.write_to(&mut client)?;
+                    let answers = Arc::new(AtomicBool::new(false));
+                    let replies = QueryReplies {
+                        pty: PtyInput(writer.try_clone()?),
+                        enabled: Arc::clone(&answers),
+                    };
+                    let mut state = HostedState {
+                        pty,
+                        replay: VecDeque::new(),
+                        screen: TerminalEngine::new(
+                            TerminalSize::new(cols as usize, rows as usize),
+                            SCREEN_SCROLLBACK,
+                            Arc::new(Mutex::new(Box::new(replies))),
+                            TerminalTheme::default(),
+                        ),
+                        answers,
+                        client: None,
+                    };
+                    state.attach(Client {
+                        serial: connection,
+                        stream: Arc::new(Mutex::new(client)),
+                        grid: grid.then(GridStream::default),
+                    });
+                    let hosted = Hosted {
+                        pid,
+                        profile,
+                        input: Mutex::new(PtyInput(writer)),
+                        state: Mutex::new(state),
+                    };
+                    lock(&registry.sessions).insert(id, Arc::new(hosted));
+                    let registry = Arc::clone(registry);
+                    let socket = socket.to_path_buf();
+                    thread::spawn(move || pump_output(id, reader, &registry, &socket));
+                    id
+                }
+                Err(err) => return Reply::Error(err.to_string()).write_to(&mut stream),
+            }
+        }
+        Request::Attach { id, grid } => {
+            let Some(hosted) = registry.session(id) else {
+                return Reply::Error(format!("no session {id}")).write_to(&mut stream);
+            };
+            let client = stream.try_clone()?;
+            client.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT))?;
+            let client = Arc::new(Mutex::new(client));
+            // Hold the new client's stream until the replay is written, so
+            // output the session produces meanwhile follows it.
+            let mut writing = lock(&client);
+            let replay = {
+                let mut state = lock(&hosted.state);
+                state.attach(Client {
+                    serial: connection,
+                    stream: Arc::clone(&client),
+                    grid: grid.then(GridStream::default),
+                });
+                // A grid client is drawn by its first update instead.
+                (!grid).then(|| state.replay.iter().copied().collect::<Vec<u8>>())
+            };
+            Reply::Spawned {
+                id,
+                pid: hosted.pid,
+            }
+            .write_to(&mut *writing)?;
+            if let Some(replay) = replay {
+                Reply::Output(replay).write_to(&mut *writing)?;
+            }
+            id
+        }
+        _ => return Reply::Error("no session chosen".to_string()).write_to(&mut stream),
+    };
+
+    let streams_grid = registry.session(id).is_some_and(|hosted| {
+        lock(&hosted.state)
+            .client
+            .as_ref()
+            .is_some_and(|client| client.serial == connection && client.grid.is_some())
+    });
+    if streams_grid {
+        let registry = Arc::clone(registry);
+        thread::spawn(move || stream_grid(id, connection, &registry));
+    }
+
+    while let Some(request) = Request::read_from(&mut stream)? {
+        let Some(hosted) = registry.session(id) else {
+            break;
+        };
+        match request {
+            Request::Input(bytes) => {
+                if let Err(err) = lock(&hosted.input).write_all(&bytes) {
+                    eprintln!("daemon write to session {id} failed: {err}");
+                }
+            }
+            Request::Resize { rows, cols } => {
+                let mut state = lock(&hosted.state);
+                state.pty.on_resize(window_size(rows, cols));
+                state
+                    .screen
+                    .resize(TerminalSize::new(cols as usize, rows as usize));
+            }
+            Request::Kill => {
+                // Dropping the PTY hangs the shell up; its reader thread
+                // then sees the end of output.
+                lock(&registry.sessions).remove(&id);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(hosted) = registry.session(id) {
+        lock(&hosted.state).detach(connection);
+    }
+    Ok(())
+}
+
+/// Sends a grid client the rows that changed, every `GRID_INTERVAL`, until
+/// it detaches or the session ends.
+fn stream_grid(id: u64, serial: u64, registry: &Registry) {
+    loop {
+        thread::sleep(GRID_INTERVAL);
+        let Some(hosted) = registry.session(id) else {
+            return;
+        };
+        let update = {
+            let mut state = lock(&hosted.state);
+            let state = &mut *state;
+            let Some(client) = state
+                .client
+                .as_mut()
+                .filter(|client| client.serial == serial)
+            else {
+                return;
+            };
+            client
+                .grid
+                .as_mut()
+                .and_then(|grid| grid.update(&state.screen))
+                .map(|update| (update, Arc::clone(&client.stream)))
+        };
+        if let Some((update, stream)) = update
+            && !send(&hosted, serial, &stream, Reply::Output(update))
+        {
+            return;
+        }
+    }
+}
+
+fn spawn_pty(
+    options: &Options,
+    rows: u16,
+    cols: u16,
+    id: u64,
+) -> io::Result<(tty::Pty, File, File)> {
+    let pty = tty::new(options, window_size(rows, cols), id)?;
+    let reader = pty.file().try_clone()?;
+    let writer = pty.file().try_clone()?;
+    Ok((pty, reader, writer))
+}
+
+fn window_size(rows: u16, cols: u16) -> WindowSize {
+    WindowSize {
+        num_lines: rows,
+        num_cols: cols,
+        cell_width: 1,
+        cell_height: 1,
+    }
+}
+
+/// Reads one session's output into its replay buffer and on to the
+/// attached GUI, until the shell exits.
+fn pump_output(id: u64, mut reader: File, registry: &Registry, socket: &Path) {
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(1));
+                continue;
+            }
+            Err(_) => break,
+        };
+        let Some(hosted) = registry.session(id) else {
+            break;
+        };
+        let raw_client = {
+            let mut state = lock(&hosted.state);
+            push_replay(&mut state.replay, &buf[..n]);
+            state.screen.feed_bytes(&buf[..n]);
+            state
+                .client
+                .as_ref()
+                .filter(|client| client.grid.is_none())
+                .map(|client| (client.serial, Arc::clone(&client.stream)))
+        };
+        if let Some((serial, stream)) = raw_client {
+            send(&hosted, serial, &stream, Reply::Output(buf[..n].to_vec()));
+        }
+    }
+
+    let (hosted, last) = {
+        let mut sessions = lock(&registry.sessions);
+        (sessions.remove(&id), sessions.is_empty())
+    };
+    if let Some(hosted) = hosted {
+        let client = {
+            let mut state = lock(&hosted.state);
+            let state = &mut *state;
+            state.client.as_mut().map(|client| {
+                let update = client
+                    .grid
+                    .as_mut()
+                    .and_then(|grid| grid.update(&state.screen));
+                (update, Arc::clone(&client.stream))
+            })
+        };
+        if let Some((update, stream)) = client {
+            let mut stream = lock(&stream);
+            // A grid client gets the last screen before it is told.
+            if let Some(update) = update {
+                let _ = Reply::Output(update).write_to(&mut *stream);
+            }
+            let _ = Reply::Exited.write_to(&mut *stream);
+        }
+    }
+    if last {
+        shut_down(socket);
+    }
+}
+
+/// Appends `bytes`, dropping the oldest output beyond `REPLAY_BYTES`. The
+/// cut moves on to the next line start, so the replay does not open in the
+/// middle of an escape sequence on that line.
+fn push_replay(replay: &mut VecDeque<u8>, bytes: &[u8]) {
+    replay.extend(bytes);
+    if replay.len() <= REPLAY_BYTES {
+        return;
+    }
+    let mut excess = replay.len() - REPLAY_BYTES;
+    if let Some(newline) = replay.iter().skip(excess).position(|&b| b == b'\n') {
+        excess += newline + 1;
+    }
+    replay.drain(..excess);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_keeps_the_newest_output_from_a_line_start() {
+        let mut replay = VecDeque::new();
+        push_replay(&mut replay, b"first line\r\n");
+        assert_eq!(replay.len(), 12);
+
+        let filler = vec![b'x'; REPLAY_BYTES - 4];
+        push_replay(&mut replay, &filler);
+        push_replay(&mut replay, b"\r\nprompt$ ");
+
+        let kept: Vec<u8> = replay.into_iter().collect();
+        assert_eq!(kept, b"prompt$ ");
+    }
+}
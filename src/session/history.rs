@@ -10,6 +10,9 @@ pub struct SessionHistoryEntry {
     pub profile: Profile,
     pub display_name: String,
     pub timestamp: u64,
+    /// How many times the session was opened, for the most-used list.
+    #[serde(default)]
+    pub count: u32,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -39,7 +42,18 @@ impl SessionHistory {
     }
 
     pub fn record(&mut self, profile: Profile, display_name: String) {
-        self.entries.retain(|e| e.display_name != display_name);
+        self.push(profile, display_name);
+        self.save();
+    }
+
+    /// Moves the session to the front, counting one more use.
+    fn push(&mut self, profile: Profile, display_name: String) {
+        let previous = self
+            .entries
+            .iter()
+            .position(|e| e.display_name == display_name)
+            .map(|index| self.entries.remove(index).count)
+            .unwrap_or(0);
         self.entries.insert(
             0,
             SessionHistoryEntry {
@@ -49,10 +63,19 @@ impl SessionHistory {
                     .duration_since(UNIX_EPOCH)
                     .unwrap_or_default()
                     .as_secs(),
+                count: previous + 1,
             },
         );
         self.entries.truncate(MAX_ENTRIES);
-        self.save();
+    }
+
+    /// Up to `limit` sessions, the most opened first and the latest first
+    /// among equals.
+    pub fn most_used(&self, limit: usize) -> Vec<&SessionHistoryEntry> {
+        let mut entries: Vec<&SessionHistoryEntry> = self.entries.iter().collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.count));
+        entries.truncate(limit);
+        entries
     }
 }
 
@@ -63,3 +86,28 @@ fn history_path() -> Option<PathBuf> {
             .join("session_history.toml"),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_used_sessions_come_first() {
+        let mut history = SessionHistory::default();
+        for name in ["zsh", "fish", "zsh", "ssh", "zsh", "fish"] {
+            history.push(
+                Profile::shell(name.into(), format!("/bin/{name}")),
+                name.into(),
+            );
+        }
+        let names: Vec<&str> = history
+            .most_used(2)
+            .iter()
+            .map(|e| e.display_name.as_str())
+            .collect();
+        assert_eq!(names, ["zsh", "fish"]);
+        // Still most recent first for the lobby.
+        assert_eq!(history.entries[0].display_name, "fish");
+        assert_eq!(history.entries.len(), 3);
+    }
+}
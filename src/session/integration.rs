@@ -0,0 +1,187 @@
+//! Shell integration: snippets that make bash, zsh and fish mark their
+//! prompts and commands (OSC 133) and report their working directory
+//! (OSC 7), and installing them into each shell's startup file.
+
+use std::path::{Path, PathBuf};
+
+const BEGIN: &str = "# >>> RabbiTTY shell integration >>>";
+const END: &str = "# <<< RabbiTTY shell integration <<<";
+
+// A `D` with no command before it is ignored, so the prompt hooks report
+// the last status every time rather than tracking whether anything ran.
+const BASH: &str = r#"if [[ $- == *i* && -z $__rabbitty_integrated ]]; then
+    __rabbitty_integrated=1
+    __rabbitty_prompt() {
+        printf '\e]133;D;%s\a' "$?"
+        printf '\e]7;file://%s%s\a' "$HOSTNAME" "$PWD"
+        printf '\e]133;A\a'
+    }
+    PROMPT_COMMAND="__rabbitty_prompt${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+    PS1="$PS1\[\e]133;B\a\]"
+    PS0="$PS0\e]133;C\a"
+fi"#;
+
+const ZSH: &str = r#"if [[ -o interactive && -z $__rabbitty_integrated ]]; then
+    __rabbitty_integrated=1
+    __rabbitty_precmd() {
+        printf '\e]133;D;%s\a' "$?"
+        printf '\e]7;file://%s%s\a' "$HOST" "$PWD"
+        printf '\e]133;A\a'
+    }
+    __rabbitty_preexec() {
+        printf '\e]133;C\a'
+    }
+    autoload -Uz add-zsh-hook
+    add-zsh-hook precmd __rabbitty_precmd
+    add-zsh-hook preexec __rabbitty_preexec
+    PS1="$PS1%{"$'\e]133;B\a'"%}"
+fi"#;
+
+const FISH: &str = r#"if status is-interactive; and not set -q __rabbitty_integrated
+    set -g __rabbitty_integrated 1
+    function __rabbitty_preexec --on-event fish_preexec
+        printf '\e]133;C\a'
+    end
+    function __rabbitty_postexec --on-event fish_postexec
+        printf '\e]133;D;%s\a' $status
+    end
+    function __rabbitty_prompt --on-event fish_prompt
+        printf '\e]7;file://%s%s\a' $hostname "$PWD"
+        printf '\e]133;A\a'
+    end
+    functions -c fish_prompt __rabbitty_fish_prompt
+    function fish_prompt
+        __rabbitty_fish_prompt
+        printf '\e]133;B\a'
+    end
+end"#;
+
+/// A shell RabbiTTY has an integration snippet for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrationShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl IntegrationShell {
+    pub const ALL: [Self; 3] = [Self::Bash, Self::Zsh, Self::Fish];
+
+    /// The shell a program path runs, by its file name.
+    pub fn from_program(program: &str) -> Option<Self> {
+        let name = Path::new(program).file_name()?.to_str()?;
+        Self::ALL.into_iter().find(|shell| shell.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+        }
+    }
+
+    /// The snippet, between the markers `install` looks for.
+    pub fn snippet(self) -> String {
+        let body = match self {
+            Self::Bash => BASH,
+            Self::Zsh => ZSH,
+            Self::Fish => FISH,
+        };
+        format!("{BEGIN}\n{body}\n{END}\n")
+    }
+
+    /// Where the snippet goes: the interactive startup file, or for fish a
+    /// file of its own in `conf.d`.
+    pub fn startup_file(self) -> Option<PathBuf> {
+        let home = dirs::home_dir()?;
+        Some(match self {
+            Self::Bash => home.join(".bashrc"),
+            Self::Zsh => std::env::var_os("ZDOTDIR")
+                .map(PathBuf::from)
+                .unwrap_or(home)
+                .join(".zshrc"),
+            Self::Fish => std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".config"))
+                .join("fish/conf.d/rabbitty.fish"),
+        })
+    }
+
+    /// Whether the startup file already holds the snippet.
+    pub fn is_installed(self) -> bool {
+        self.startup_file()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .is_some_and(|text| text.contains(BEGIN))
+    }
+
+    /// Writes the snippet into the startup file, replacing an earlier copy.
+    pub fn install(self) -> Result<PathBuf, String> {
+        let path = self
+            .startup_file()
+            .ok_or_else(|| "No home directory to install into".to_string())?;
+        let existing = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("{}: {err}", path.display())),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| format!("{}: {err}", parent.display()))?;
+        }
+        std::fs::write(&path, with_snippet(&existing, &self.snippet()))
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// `text` with `snippet` in place of the marked block, or appended after a
+/// blank line when there is none.
+fn with_snippet(text: &str, snippet: &str) -> String {
+    if let Some(start) = text.find(BEGIN)
+        && let Some(end) = text[start..].find(END).map(|end| start + end + END.len())
+    {
+        let rest = text[end..].strip_prefix('\n').unwrap_or(&text[end..]);
+        return format!("{}{snippet}{rest}", &text[..start]);
+    }
+    let mut out = text.to_string();
+    if !out.is_empty() {
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out.push_str(snippet);
+    out
+}
+
+/// What the settings health check found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrationReport {
+    /// Each shell with whether its snippet is installed.
+    pub installed: Vec<(IntegrationShell, bool)>,
+    /// Open panes.
+    pub panes: usize,
+    /// Panes whose shell has marked a prompt.
+    pub prompts: usize,
+    /// Panes whose shell has reported its working directory.
+    pub directories: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn installing_twice_replaces_the_block() {
+        let rc = "alias ll='ls -l'";
+        let once = with_snippet(rc, &IntegrationShell::Bash.snippet());
+        assert!(once.starts_with("alias ll='ls -l'\n\n# >>> RabbiTTY"));
+        let twice = with_snippet(&format!("{once}export A=1\n"), "NEW\n");
+        assert_eq!(twice, "alias ll='ls -l'\n\nNEW\nexport A=1\n");
+        assert_eq!(
+            IntegrationShell::from_program("/usr/local/bin/fish"),
+            Some(IntegrationShell::Fish)
+        );
+    }
+}
@@ -1,20 +1,45 @@
+pub mod container;
+pub mod daemon;
 pub mod history;
+pub mod integration;
+mod network;
+pub mod resources;
+pub mod telnet;
+pub mod workspace;
 
 use alacritty_terminal::event::{OnResize, WindowSize};
 use alacritty_terminal::tty::{self, Options, Shell};
 #[cfg(windows)]
 use alacritty_terminal::tty::{ChildEvent, EventedPty, EventedReadWrite};
 use iced::futures::channel::mpsc;
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Write};
 #[cfg(unix)]
-use std::io::ErrorKind;
-use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
 use std::path::PathBuf;
 #[cfg(windows)]
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::thread;
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Writes at or below this size go straight to the PTY; larger ones are
+/// streamed from a background thread in pieces of this size.
+const WRITE_CHUNK: usize = 16 * 1024;
+
+/// How long a background write waits for a program that has stopped reading
+/// its input before giving up.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pause between attempts while the program's input buffer is full.
+const WRITE_RETRY: Duration = Duration::from_millis(10);
+
+type Writer = Arc<Mutex<Box<dyn Write + Send>>>;
+
+/// Input waiting behind a paste that is still being written; `None` while
+/// no paste is in flight.
+type Backlog = Arc<Mutex<Option<VecDeque<u8>>>>;
 
 pub struct LaunchSpec {
     pub program: String,
@@ -26,7 +51,8 @@ pub struct LaunchSpec {
 }
 
 pub struct Session {
-    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    writer: Writer,
+    backlog: Backlog,
     #[cfg(unix)]
     pty: Option<tty::Pty>,
     #[cfg(windows)]
@@ -34,11 +60,217 @@ pub struct Session {
     #[cfg(windows)]
     shutdown: Option<Arc<AtomicBool>>,
     reader: Option<JoinHandle<()>>,
-    /// For native SSH sessions: send resize events to the async task.
+    /// For SSH and network sessions: forwards resize events to the
+    /// connection instead of a local PTY.
     resize_tx: Option<tokio::sync::mpsc::UnboundedSender<(u16, u16)>>,
     /// For native SSH sessions: handle that can open additional channels
     /// (e.g., SFTP subsystem) on the active connection.
     ssh: Option<crate::ssh::SshSessionHandle>,
+    /// For shells kept alive by the session daemon: the connection driving
+    /// them in place of a PTY.
+    #[cfg(unix)]
+    daemon: Option<DaemonLink>,
+}
+
+#[cfg(unix)]
+struct DaemonLink {
+    pid: u32,
+    stream: Arc<Mutex<UnixStream>>,
+    /// Set when the GUI is going away, so dropping the session leaves the
+    /// shell running instead of ending it.
+    detached: bool,
+}
+
+/// The PTY master, which is non-blocking: a write that finds the program's
+/// input buffer full waits for room instead of failing. Only for threads
+/// that may wait, such as the daemon's; the GUI writes the master directly
+/// and leaves waiting to the paste thread.
+#[cfg(unix)]
+struct PtyInput(std::fs::File);
+
+#[cfg(unix)]
+impl Write for PtyInput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        loop {
+            match self.0.write(buf) {
+                Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                    wait_writable(&self.0, WRITE_TIMEOUT)?;
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                result => return result,
+            }
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(unix)]
+fn wait_writable(file: &std::fs::File, timeout: Duration) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let mut fd = libc::pollfd {
+        fd: file.as_raw_fd(),
+        events: libc::POLLOUT,
+        revents: 0,
+    };
+    // SAFETY: `fd` is one valid pollfd for the length of the call.
+    let ready = unsafe { libc::poll(&mut fd, 1, timeout.as_millis() as libc::c_int) };
+    match ready {
+        0 => Err(std::io::Error::new(
+            ErrorKind::TimedOut,
+            "the program stopped reading its input",
+        )),
+        -1 => {
+            let err = std::io::Error::last_os_error();
+            if err.kind() == ErrorKind::Interrupted {
+                Ok(())
+            } else {
+                Err(err)
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Writes as much of `bytes` as the reader has room for, without waiting,
+/// and returns how much went.
+fn write_available(writer: &mut dyn Write, bytes: &[u8]) -> std::io::Result<usize> {
+    let mut written = 0;
+    while written < bytes.len() {
+        match writer.write(&bytes[written..]) {
+            Ok(0) => return Err(ErrorKind::WriteZero.into()),
+            Ok(len) => written += len,
+            Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    writer.flush()?;
+    Ok(written)
+}
+
+/// Writes `bytes` now, or queues them behind a paste still in flight.
+/// What a full input buffer can't take right away is queued too and
+/// written from a background thread, so the caller never waits.
+fn write_in_order(writer: &Writer, backlog: &Backlog, bytes: &[u8]) -> Result<(), SessionError> {
+    let mut pending = backlog.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(queue) = pending.as_mut() {
+        queue.extend(bytes);
+        return Ok(());
+    }
+    let written = {
+        let mut guard = writer
+            .lock()
+            .map_err(|err| SessionError::Io(format!("writer lock failed: {err}")))?;
+        write_available(&mut **guard, bytes)
+            .map_err(|err| SessionError::Io(format!("write failed: {err}")))?
+    };
+    if written == bytes.len() {
+        return Ok(());
+    }
+    *pending = Some(VecDeque::from(bytes[written..].to_vec()));
+    drop(pending);
+    spawn_drain(writer, backlog)
+}
+
+/// Streams a large paste from a background thread, so the caller never
+/// waits on a slow reader. Input sent meanwhile queues behind the paste.
+fn stream_in_order(writer: &Writer, backlog: &Backlog, bytes: Vec<u8>) -> Result<(), SessionError> {
+    if bytes.len() <= WRITE_CHUNK {
+        return write_in_order(writer, backlog, &bytes);
+    }
+    let mut pending = backlog.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(queue) = pending.as_mut() {
+        queue.extend(bytes);
+        return Ok(());
+    }
+    *pending = Some(VecDeque::from(bytes));
+    drop(pending);
+    spawn_drain(writer, backlog)
+}
+
+fn spawn_drain(writer: &Writer, backlog: &Backlog) -> Result<(), SessionError> {
+    let (thread_writer, thread_backlog) = (Arc::clone(writer), Arc::clone(backlog));
+    thread::Builder::new()
+        .name("pty-paste".into())
+        .spawn(move || drain_backlog(&thread_writer, &thread_backlog))
+        .map(|_| ())
+        .map_err(|err| {
+            *backlog.lock().unwrap_or_else(PoisonError::into_inner) = None;
+            SessionError::Io(format!("paste thread spawn failed: {err}"))
+        })
+}
+
+/// Writes queued input a chunk at a time until none is left and input can
+/// go straight through again. Neither the queue nor the writer is held
+/// while waiting for room, and a program that takes nothing for
+/// [`WRITE_TIMEOUT`] loses the rest.
+fn drain_backlog(writer: &Writer, backlog: &Backlog) {
+    let mut progress = Instant::now();
+    loop {
+        let chunk: Vec<u8> = {
+            let mut pending = backlog.lock().unwrap_or_else(PoisonError::into_inner);
+            let Some(queue) = pending.as_mut() else {
+                return;
+            };
+            if queue.is_empty() {
+                *pending = None;
+                return;
+            }
+            queue.iter().take(WRITE_CHUNK).copied().collect()
+        };
+        let result = {
+            let mut guard = writer.lock().unwrap_or_else(PoisonError::into_inner);
+            write_available(&mut **guard, &chunk)
+        };
+        let error = match result {
+            Ok(0) if progress.elapsed() < WRITE_TIMEOUT => {
+                thread::sleep(WRITE_RETRY);
+                continue;
+            }
+            Ok(0) => "the program stopped reading its input".to_string(),
+            Ok(written) => {
+                if let Some(queue) = backlog
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .as_mut()
+                {
+                    queue.drain(..written);
+                }
+                progress = Instant::now();
+                continue;
+            }
+            Err(err) => err.to_string(),
+        };
+        eprintln!("Failed to write paste chunk: {error}");
+        *backlog.lock().unwrap_or_else(PoisonError::into_inner) = None;
+        return;
+    }
+}
+
+/// Sends what the terminal writes to the daemon as input frames.
+#[cfg(unix)]
+struct DaemonInput {
+    stream: Arc<Mutex<UnixStream>>,
+}
+
+#[cfg(unix)]
+impl Write for DaemonInput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut stream = self
+            .stream
+            .lock()
+            .map_err(|_| std::io::Error::other("daemon stream poisoned"))?;
+        daemon::Request::Input(buf.to_vec()).write_to(&mut *stream)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -49,8 +281,20 @@ pub enum SessionError {
 
 #[derive(Debug, Clone)]
 pub enum OutputEvent {
-    Data { tab_id: u64, bytes: Vec<u8> },
-    Closed { tab_id: u64 },
+    Data {
+        tab_id: u64,
+        bytes: Vec<u8>,
+    },
+    Closed {
+        tab_id: u64,
+        /// The shell's exit code, or 128 plus the signal that ended it;
+        /// `None` when it isn't known, as for remote sessions.
+        exit_code: Option<i32>,
+    },
+    /// An SSH port forward started, stopped or failed.
+    ForwardsChanged {
+        tab_id: u64,
+    },
 }
 
 #[cfg(windows)]
@@ -121,15 +365,20 @@ impl Session {
             .try_clone()
             .map_err(|err| SessionError::Spawn(format!("writer clone failed: {err}")))?;
 
-        let writer: Arc<Mutex<Box<dyn Write + Send>>> = Arc::new(Mutex::new(Box::new(writer_file)));
+        let writer: Writer = Arc::new(Mutex::new(Box::new(writer_file)));
 
+        let child_pid = pty.child().id();
         let reader_handle = thread::spawn(move || {
             let mut reader = reader_file;
             let mut buf = [0u8; 2048];
             loop {
                 match reader.read(&mut buf) {
                     Ok(0) => {
-                        let _ = send_output_event(&mut output_tx, OutputEvent::Closed { tab_id });
+                        let exit_code = wait_for_exit(child_pid);
+                        let _ = send_output_event(
+                            &mut output_tx,
+                            OutputEvent::Closed { tab_id, exit_code },
+                        );
                         break;
                     }
                     Ok(n) => {
@@ -149,7 +398,11 @@ impl Session {
                         continue;
                     }
                     Err(_) => {
-                        let _ = send_output_event(&mut output_tx, OutputEvent::Closed { tab_id });
+                        let exit_code = wait_for_exit(child_pid);
+                        let _ = send_output_event(
+                            &mut output_tx,
+                            OutputEvent::Closed { tab_id, exit_code },
+                        );
                         break;
                     }
                 }
@@ -158,10 +411,12 @@ impl Session {
 
         Ok(Self {
             writer,
+            backlog: Backlog::default(),
             pty: Some(pty),
             reader: Some(reader_handle),
             resize_tx: None,
             ssh: None,
+            daemon: None,
         })
     }
 
@@ -220,7 +475,13 @@ impl Session {
                 }
 
                 if exited {
-                    let _ = send_output_event(&mut output_tx, OutputEvent::Closed { tab_id });
+                    let _ = send_output_event(
+                        &mut output_tx,
+                        OutputEvent::Closed {
+                            tab_id,
+                            exit_code: None,
+                        },
+                    );
                     break;
                 }
 
@@ -230,6 +491,7 @@ impl Session {
 
         Ok(Self {
             writer,
+            backlog: Backlog::default(),
             pty: Some(pty),
             shutdown: Some(shutdown),
             reader: Some(reader_handle),
@@ -248,6 +510,7 @@ impl Session {
         let handle = crate::ssh::spawn_ssh_session(profile, tab_id, rows, cols, output_tx);
         Self {
             writer: Arc::clone(&handle.writer),
+            backlog: Backlog::default(),
             #[cfg(unix)]
             pty: None,
             #[cfg(windows)]
@@ -257,6 +520,165 @@ impl Session {
             reader: None,
             resize_tx: Some(handle.resize_tx.clone()),
             ssh: Some(handle),
+            #[cfg(unix)]
+            daemon: None,
+        }
+    }
+
+    /// Connects to `host:port` over TCP, speaking telnet or passing raw bytes.
+    pub fn spawn_network(
+        host: String,
+        port: u16,
+        protocol: crate::config::NetworkProtocol,
+        tab_id: u64,
+        rows: u16,
+        cols: u16,
+        output_tx: mpsc::UnboundedSender<OutputEvent>,
+    ) -> Self {
+        let handle =
+            network::spawn_network_session(host, port, protocol, tab_id, rows, cols, output_tx);
+        Self {
+            writer: handle.writer,
+            backlog: Backlog::default(),
+            #[cfg(unix)]
+            pty: None,
+            #[cfg(windows)]
+            pty: None,
+            #[cfg(windows)]
+            shutdown: None,
+            reader: None,
+            resize_tx: Some(handle.resize_tx),
+            ssh: None,
+            #[cfg(unix)]
+            daemon: None,
+        }
+    }
+
+    /// Starts a local shell inside the session daemon, starting the daemon
+    /// if needed. `profile` is stored with it for re-attaching later.
+    #[cfg(unix)]
+    pub fn spawn_persistent(
+        spec: LaunchSpec,
+        profile: String,
+        tab_id: u64,
+        output_tx: mpsc::UnboundedSender<OutputEvent>,
+    ) -> Result<Self, SessionError> {
+        let request = daemon::Request::Spawn {
+            program: spec.program,
+            args: spec.args,
+            env: spec.env,
+            rows: spec.rows,
+            cols: spec.cols,
+            cwd: spec
+                .cwd
+                .or_else(default_working_directory)
+                .map(|cwd| cwd.to_string_lossy().into_owned()),
+            profile,
+            grid: false,
+        };
+        Self::from_daemon(request, tab_id, output_tx)
+    }
+
+    #[cfg(not(unix))]
+    pub fn spawn_persistent(
+        spec: LaunchSpec,
+        _profile: String,
+        tab_id: u64,
+        output_tx: mpsc::UnboundedSender<OutputEvent>,
+    ) -> Result<Self, SessionError> {
+        Self::spawn(spec, tab_id, output_tx)
+    }
+
+    /// Re-attaches to daemon session `id`, replaying its recent output.
+    #[cfg(unix)]
+    pub fn attach(
+        id: u64,
+        tab_id: u64,
+        output_tx: mpsc::UnboundedSender<OutputEvent>,
+    ) -> Result<Self, SessionError> {
+        Self::from_daemon(
+            daemon::Request::Attach { id, grid: false },
+            tab_id,
+            output_tx,
+        )
+    }
+
+    #[cfg(not(unix))]
+    pub fn attach(
+        id: u64,
+        _tab_id: u64,
+        _output_tx: mpsc::UnboundedSender<OutputEvent>,
+    ) -> Result<Self, SessionError> {
+        Err(SessionError::Spawn(format!(
+            "no session daemon to attach {id} from"
+        )))
+    }
+
+    #[cfg(unix)]
+    fn from_daemon(
+        request: daemon::Request,
+        tab_id: u64,
+        mut output_tx: mpsc::UnboundedSender<OutputEvent>,
+    ) -> Result<Self, SessionError> {
+        let io_error = |err: std::io::Error| SessionError::Spawn(format!("session daemon: {err}"));
+        let mut stream = daemon::connect().map_err(io_error)?;
+        request.write_to(&mut stream).map_err(io_error)?;
+        let pid = match daemon::Reply::read_from(&mut stream).map_err(io_error)? {
+            Some(daemon::Reply::Spawned { pid, .. }) => pid,
+            Some(daemon::Reply::Error(message)) => return Err(SessionError::Spawn(message)),
+            _ => return Err(SessionError::Spawn("session daemon hung up".into())),
+        };
+
+        let mut reader = stream.try_clone().map_err(io_error)?;
+        thread::spawn(move || {
+            loop {
+                match daemon::Reply::read_from(&mut reader) {
+                    Ok(Some(daemon::Reply::Output(bytes))) => {
+                        if !send_output_event(&mut output_tx, OutputEvent::Data { tab_id, bytes }) {
+                            break;
+                        }
+                    }
+                    Ok(Some(_)) => continue,
+                    Ok(None) | Err(_) => {
+                        let _ = send_output_event(
+                            &mut output_tx,
+                            OutputEvent::Closed {
+                                tab_id,
+                                exit_code: None,
+                            },
+                        );
+                        break;
+                    }
+                }
+            }
+        });
+
+        let stream = Arc::new(Mutex::new(stream));
+        let writer: Arc<Mutex<Box<dyn Write + Send>>> =
+            Arc::new(Mutex::new(Box::new(DaemonInput {
+                stream: Arc::clone(&stream),
+            })));
+        Ok(Self {
+            writer,
+            backlog: Backlog::default(),
+            pty: None,
+            reader: None,
+            resize_tx: None,
+            ssh: None,
+            daemon: Some(DaemonLink {
+                pid,
+                stream,
+                detached: false,
+            }),
+        })
+    }
+
+    /// Lets a daemon-hosted shell outlive this session: dropping it no
+    /// longer ends the shell. Other sessions are unaffected.
+    pub fn detach(&mut self) {
+        #[cfg(unix)]
+        if let Some(link) = self.daemon.as_mut() {
+            link.detached = true;
         }
     }
 
@@ -266,24 +688,48 @@ impl Session {
         self.ssh.as_ref()
     }
 
+    /// Sends `bytes`, after any paste still being written.
     pub fn send_bytes(&self, bytes: &[u8]) -> Result<(), SessionError> {
-        let mut guard = self
-            .writer
-            .lock()
-            .map_err(|err| SessionError::Io(format!("writer lock failed: {err}")))?;
-        guard
-            .write_all(bytes)
-            .map_err(|err| SessionError::Io(format!("write failed: {err}")))
+        write_in_order(&self.writer, &self.backlog, bytes)
+    }
+
+    /// Sends `bytes` without blocking the caller on a slow reader. Large
+    /// payloads are written from a background thread one chunk at a time, so
+    /// the UI stays responsive while the program on the other end drains its
+    /// input; whatever is sent meanwhile follows the paste rather than
+    /// landing in the middle of it.
+    pub fn send_bytes_chunked(&self, bytes: Vec<u8>) -> Result<(), SessionError> {
+        stream_in_order(&self.writer, &self.backlog, bytes)
     }
 
     pub fn writer(&self) -> Arc<Mutex<Box<dyn Write + Send>>> {
         Arc::clone(&self.writer)
     }
 
+    /// The shell's process id, for shells on this machine.
+    #[cfg(unix)]
+    pub fn process_id(&self) -> Option<u32> {
+        match (&self.pty, &self.daemon) {
+            (Some(pty), _) => Some(pty.child().id()),
+            (None, Some(link)) => Some(link.pid),
+            (None, None) => None,
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn process_id(&self) -> Option<u32> {
+        let pty = self.pty.as_ref()?.lock().ok()?;
+        pty.child_watcher().pid().map(std::num::NonZeroU32::get)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    pub fn process_id(&self) -> Option<u32> {
+        None
+    }
+
     #[cfg(unix)]
     pub fn working_directory(&self) -> Option<PathBuf> {
-        let pid = self.pty.as_ref()?.child().id();
-        process_cwd(pid)
+        process_cwd(self.process_id()?)
     }
 
     #[cfg(not(unix))]
@@ -291,12 +737,84 @@ impl Session {
         None
     }
 
+    /// Name of the process in the foreground of the terminal: the shell, or
+    /// the program it runs. Only known for shells with a local PTY.
+    #[cfg(unix)]
+    pub fn foreground_process(&self) -> Option<String> {
+        process_name(self.foreground_group()?)
+    }
+
+    #[cfg(not(unix))]
+    pub fn foreground_process(&self) -> Option<String> {
+        None
+    }
+
+    /// The program the shell runs in the foreground with its arguments, as
+    /// in `npm run dev`, or just its name where the arguments can't be
+    /// read. `None` while the shell itself is in the foreground.
+    #[cfg(unix)]
+    pub fn foreground_command(&self) -> Option<String> {
+        let group = self.foreground_group()?;
+        if Some(group) == self.process_id() {
+            return None;
+        }
+        process_command(group).or_else(|| process_name(group))
+    }
+
+    #[cfg(not(unix))]
+    pub fn foreground_command(&self) -> Option<String> {
+        None
+    }
+
+    /// The PTY's foreground process group.
+    #[cfg(unix)]
+    fn foreground_group(&self) -> Option<u32> {
+        use std::os::fd::AsRawFd;
+
+        let pty = self.pty.as_ref()?;
+        // SAFETY: the fd is the PTY master, open while `pty` lives.
+        let group = unsafe { libc::tcgetpgrp(pty.file().as_raw_fd()) };
+        (group > 0).then_some(group as u32)
+    }
+
+    /// Whether the program reads input in line mode with echo off, as
+    /// password prompts do. Only known for shells with a local PTY.
+    #[cfg(unix)]
+    pub fn echo_disabled(&self) -> bool {
+        use std::os::fd::AsRawFd;
+
+        let Some(pty) = self.pty.as_ref() else {
+            return false;
+        };
+        // SAFETY: the fd is the PTY master, open while `pty` lives, and
+        // `tcgetattr` only fills in the zeroed struct.
+        let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(pty.file().as_raw_fd(), &mut termios) } != 0 {
+            return false;
+        }
+        termios.c_lflag & libc::ECHO == 0 && termios.c_lflag & libc::ICANON != 0
+    }
+
+    #[cfg(not(unix))]
+    pub fn echo_disabled(&self) -> bool {
+        false
+    }
+
     #[cfg(unix)]
     pub fn resize(&mut self, rows: u16, cols: u16) -> Result<(), SessionError> {
         if let Some(ref tx) = self.resize_tx {
             let _ = tx.send((rows, cols));
             return Ok(());
         }
+        if let Some(ref link) = self.daemon {
+            let mut stream = link
+                .stream
+                .lock()
+                .map_err(|err| SessionError::Io(format!("daemon lock failed: {err}")))?;
+            return daemon::Request::Resize { rows, cols }
+                .write_to(&mut *stream)
+                .map_err(|err| SessionError::Io(format!("daemon resize failed: {err}")));
+        }
         if let Some(ref mut pty) = self.pty {
             let window_size = WindowSize {
                 num_lines: rows,
@@ -342,6 +860,32 @@ fn send_output_event(
     output_tx.unbounded_send(event).is_ok()
 }
 
+/// Reaps the shell once its PTY has closed and returns its exit code, or
+/// 128 plus the signal that ended it. Gives up after a moment when the
+/// shell lingers, or was already reaped by dropping the session.
+#[cfg(unix)]
+fn wait_for_exit(pid: u32) -> Option<i32> {
+    for _ in 0..50 {
+        let mut status = 0;
+        // SAFETY: waitpid only writes the status it is handed.
+        let ret = unsafe { libc::waitpid(pid as libc::pid_t, &mut status, libc::WNOHANG) };
+        if ret < 0 {
+            return None;
+        }
+        if ret == pid as libc::pid_t {
+            return if libc::WIFEXITED(status) {
+                Some(libc::WEXITSTATUS(status))
+            } else if libc::WIFSIGNALED(status) {
+                Some(128 + libc::WTERMSIG(status))
+            } else {
+                None
+            };
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    None
+}
+
 #[cfg(target_os = "linux")]
 fn process_cwd(pid: u32) -> Option<PathBuf> {
     std::fs::read_link(format!("/proc/{pid}/cwd")).ok()
@@ -378,6 +922,70 @@ fn process_cwd(_pid: u32) -> Option<PathBuf> {
     None
 }
 
+#[cfg(target_os = "linux")]
+fn process_name(pid: u32) -> Option<String> {
+    let name = std::fs::read_to_string(format!("/proc/{pid}/comm")).ok()?;
+    Some(name.trim_end().to_string()).filter(|name| !name.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn process_name(pid: u32) -> Option<String> {
+    let mut buffer = [0u8; 256];
+    // SAFETY: `proc_name` writes at most `buffer.len()` bytes into it.
+    let len = unsafe {
+        libc::proc_name(
+            pid as libc::c_int,
+            buffer.as_mut_ptr() as *mut libc::c_void,
+            buffer.len() as u32,
+        )
+    };
+    if len <= 0 {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&buffer[..len as usize]).into_owned())
+}
+
+#[cfg(all(unix, not(target_os = "linux"), not(target_os = "macos")))]
+fn process_name(_pid: u32) -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn process_command(pid: u32) -> Option<String> {
+    command_line_title(&std::fs::read(format!("/proc/{pid}/cmdline")).ok()?)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_command(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Longest command line shown as a title before it is cut short.
+#[cfg(any(target_os = "linux", test))]
+const COMMAND_TITLE_CHARS: usize = 40;
+
+/// A NUL-separated command line as a title: the program's file name, then
+/// its arguments.
+#[cfg(any(target_os = "linux", test))]
+fn command_line_title(cmdline: &[u8]) -> Option<String> {
+    let mut args = cmdline
+        .split(|&byte| byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(String::from_utf8_lossy);
+    let program = args.next()?;
+    let program = program.rsplit('/').next().unwrap_or(&program);
+    let mut title = program.trim_start_matches('-').to_string();
+    for arg in args {
+        title.push(' ');
+        title.push_str(&arg);
+    }
+    if title.chars().count() > COMMAND_TITLE_CHARS {
+        title = title.chars().take(COMMAND_TITLE_CHARS - 1).collect();
+        title.push('…');
+    }
+    Some(title).filter(|title| !title.is_empty())
+}
+
 fn default_working_directory() -> Option<PathBuf> {
     default_working_directory_from_env(
         std::env::var_os("HOME").as_deref(),
@@ -421,6 +1029,17 @@ impl Drop for Session {
         // EIO on its cloned master fd and exit.
         self.pty.take();
 
+        // A daemon-hosted shell is ended by asking the daemon, unless the
+        // GUI detached it; closing the socket then stops the reader.
+        if let Some(link) = self.daemon.take()
+            && let Ok(mut stream) = link.stream.lock()
+        {
+            if !link.detached {
+                let _ = daemon::Request::Kill.write_to(&mut *stream);
+            }
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+        }
+
         if let Some(handle) = self.reader.take() {
             let _ = handle.join();
         }
@@ -489,4 +1108,83 @@ mod tests {
             Some(PathBuf::from(r"C:\Users\rabbitty"))
         );
     }
+
+    /// A pipe whose write end is non-blocking like a PTY master's.
+    #[cfg(unix)]
+    fn nonblocking_pipe() -> (std::fs::File, std::fs::File) {
+        use std::os::fd::FromRawFd;
+
+        let mut fds = [0; 2];
+        // SAFETY: `fds` has room for both ends, which the files then own.
+        unsafe {
+            assert_eq!(libc::pipe(fds.as_mut_ptr()), 0);
+            let flags = libc::fcntl(fds[1], libc::F_GETFL);
+            libc::fcntl(fds[1], libc::F_SETFL, flags | libc::O_NONBLOCK);
+            (
+                std::fs::File::from_raw_fd(fds[0]),
+                std::fs::File::from_raw_fd(fds[1]),
+            )
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pastes_larger_than_the_input_buffer_arrive_whole_and_in_order() {
+        let (mut read_end, write_end) = nonblocking_pipe();
+        let reader = thread::spawn(move || {
+            // Let the paste fill the pipe before anything drains it.
+            thread::sleep(Duration::from_millis(50));
+            let mut received = Vec::new();
+            read_end.read_to_end(&mut received).unwrap();
+            received
+        });
+
+        let writer: Writer = Arc::new(Mutex::new(Box::new(write_end)));
+        let backlog = Backlog::default();
+        let paste = vec![b'a'; 1024 * 1024];
+        stream_in_order(&writer, &backlog, paste.clone()).unwrap();
+        write_in_order(&writer, &backlog, b"typed").unwrap();
+        drop(writer);
+
+        let received = reader.join().unwrap();
+        assert_eq!(received.len(), paste.len() + 5);
+        assert!(received[..paste.len()].iter().all(|&b| b == b'a'));
+        assert!(received.ends_with(b"typed"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn typing_into_a_full_input_buffer_does_not_wait() {
+        let (mut read_end, write_end) = nonblocking_pipe();
+        let writer: Writer = Arc::new(Mutex::new(Box::new(write_end)));
+        let backlog = Backlog::default();
+
+        let started = Instant::now();
+        let typed = vec![b'k'; 1024 * 1024];
+        write_in_order(&writer, &backlog, &typed).unwrap();
+        write_in_order(&writer, &backlog, b"!").unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        drop(writer);
+
+        let mut received = Vec::new();
+        read_end.read_to_end(&mut received).unwrap();
+        assert_eq!(received.len(), typed.len() + 1);
+        assert!(received.ends_with(b"k!"));
+    }
+
+    #[test]
+    fn command_lines_become_titles() {
+        assert_eq!(
+            command_line_title(b"/usr/bin/vim\0notes.txt\0").as_deref(),
+            Some("vim notes.txt")
+        );
+        assert_eq!(
+            command_line_title(b"npm\0run\0dev\0").as_deref(),
+            Some("npm run dev")
+        );
+        let long = command_line_title(&[b"cat\0".as_slice(), &[b'x'; 60]].concat()).unwrap();
+        assert_eq!(long.chars().count(), COMMAND_TITLE_CHARS);
+        assert!(long.ends_with('…'));
+        assert_eq!(command_line_title(b""), None);
+    }
 }
@@ -0,0 +1,299 @@
+//! Sessions over a plain TCP socket, optionally speaking telnet. The socket is
+//! driven from std threads: one connects and reads, another forwards resizes.
+
+use super::telnet::{self, Telnet};
+use super::{OutputEvent, send_output_event};
+use crate::config::NetworkProtocol;
+use crate::ssh::ansi;
+use iced::futures::channel::mpsc;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, mpsc as std_mpsc};
+use std::thread;
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const READ_BUFFER: usize = 16 * 1024;
+
+enum Link {
+    /// Keystrokes typed before the connection is up are sent once it is.
+    Connecting(Vec<u8>),
+    Connected(TcpStream),
+    /// Disconnected; the next keystroke asks for a reconnect.
+    Waiting(std_mpsc::Sender<()>),
+    Closed,
+}
+
+struct Shared {
+    link: Link,
+    telnet: Option<Telnet>,
+    rows: u16,
+    cols: u16,
+}
+
+type SharedLink = Arc<Mutex<Shared>>;
+
+struct NetworkWriter {
+    shared: SharedLink,
+}
+
+impl Write for NetworkWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut guard = self
+            .shared
+            .lock()
+            .map_err(|_| std::io::Error::other("network mutex poisoned"))?;
+        let bytes = match guard.telnet {
+            Some(_) => telnet::encode(buf),
+            None => buf.to_vec(),
+        };
+        match &mut guard.link {
+            Link::Connecting(pending) => pending.extend_from_slice(&bytes),
+            Link::Connected(stream) => stream.write_all(&bytes)?,
+            Link::Waiting(reconnect) => {
+                let _ = reconnect.send(());
+            }
+            Link::Closed => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::BrokenPipe,
+                    "network session closed",
+                ));
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub(super) struct NetworkSessionHandle {
+    pub writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    pub resize_tx: tokio::sync::mpsc::UnboundedSender<(u16, u16)>,
+}
+
+pub(super) fn spawn_network_session(
+    host: String,
+    port: u16,
+    protocol: NetworkProtocol,
+    tab_id: u64,
+    rows: u16,
+    cols: u16,
+    output_tx: mpsc::UnboundedSender<OutputEvent>,
+) -> NetworkSessionHandle {
+    let shared: SharedLink = Arc::new(Mutex::new(Shared {
+        link: Link::Connecting(Vec::new()),
+        telnet: (protocol == NetworkProtocol::Telnet).then(|| Telnet::new(cols, rows)),
+        rows,
+        cols,
+    }));
+    let (resize_tx, mut resize_rx) = tokio::sync::mpsc::unbounded_channel::<(u16, u16)>();
+
+    let resize_shared = Arc::clone(&shared);
+    let _ = thread::Builder::new()
+        .name("network-resize".into())
+        .spawn(move || {
+            while let Some((rows, cols)) = resize_rx.blocking_recv() {
+                let Ok(mut guard) = resize_shared.lock() else {
+                    return;
+                };
+                guard.rows = rows;
+                guard.cols = cols;
+                let report = guard.telnet.as_mut().and_then(|t| t.resize(cols, rows));
+                if let (Some(report), Link::Connected(stream)) = (report, &mut guard.link) {
+                    let _ = stream.write_all(&report);
+                }
+            }
+            // The session was dropped: hang up and release a waiting reader.
+            if let Ok(mut guard) = resize_shared.lock() {
+                if let Link::Connected(stream) = &guard.link {
+                    let _ = stream.shutdown(Shutdown::Both);
+                }
+                guard.link = Link::Closed;
+            }
+        });
+
+    let writer_shared = Arc::clone(&shared);
+    let _ = thread::Builder::new()
+        .name("network-reader".into())
+        .spawn(move || run(host, port, protocol, tab_id, shared, output_tx));
+
+    NetworkSessionHandle {
+        writer: Arc::new(Mutex::new(Box::new(NetworkWriter {
+            shared: writer_shared,
+        }))),
+        resize_tx,
+    }
+}
+
+fn run(
+    host: String,
+    port: u16,
+    protocol: NetworkProtocol,
+    tab_id: u64,
+    shared: SharedLink,
+    mut output_tx: mpsc::UnboundedSender<OutputEvent>,
+) {
+    let badge = ansi::badge(match protocol {
+        NetworkProtocol::Telnet => "TELNET",
+        NetworkProtocol::Raw => "TCP",
+    });
+
+    loop {
+        let connecting = format!(
+            "  {badge}  {}\r\n",
+            ansi::cyan(&format!("Connecting to {host}:{port}..."))
+        );
+        if !send_output_event(
+            &mut output_tx,
+            OutputEvent::Data {
+                tab_id,
+                bytes: connecting.into_bytes(),
+            },
+        ) {
+            return;
+        }
+
+        let result = connect(&host, port)
+            .and_then(|stream| attach(&shared, protocol, stream))
+            .and_then(|stream| match stream {
+                Some(stream) => read_loop(stream, tab_id, &shared, &mut output_tx),
+                None => Ok(()),
+            });
+
+        let (reconnect_tx, reconnect_rx) = std_mpsc::channel();
+        {
+            let Ok(mut guard) = shared.lock() else {
+                return;
+            };
+            if matches!(guard.link, Link::Closed) {
+                return;
+            }
+            guard.link = Link::Waiting(reconnect_tx);
+        }
+
+        let status = match result {
+            Ok(()) => ansi::yellow("Connection closed by remote host."),
+            Err(err) => ansi::red_bold(&err.to_string()),
+        };
+        let msg = format!(
+            "\r\n  {badge}  {status}\r\n  {badge}  {}\r\n",
+            ansi::cyan("Press any key to reconnect...")
+        );
+        let _ = send_output_event(
+            &mut output_tx,
+            OutputEvent::Data {
+                tab_id,
+                bytes: msg.into_bytes(),
+            },
+        );
+
+        if reconnect_rx.recv().is_err() {
+            break;
+        }
+        let Ok(mut guard) = shared.lock() else {
+            return;
+        };
+        if matches!(guard.link, Link::Closed) {
+            break;
+        }
+        guard.link = Link::Connecting(Vec::new());
+    }
+
+    let _ = send_output_event(
+        &mut output_tx,
+        OutputEvent::Closed {
+            tab_id,
+            exit_code: None,
+        },
+    );
+}
+
+fn connect(host: &str, port: u16) -> std::io::Result<TcpStream> {
+    let mut last_err = None;
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("could not resolve {host}"),
+        )
+    }))
+}
+
+/// Publishes the connected stream to the writer, flushing queued input.
+/// Returns `None` when the session was dropped while connecting.
+fn attach(
+    shared: &SharedLink,
+    protocol: NetworkProtocol,
+    stream: TcpStream,
+) -> std::io::Result<Option<TcpStream>> {
+    let _ = stream.set_nodelay(true);
+    let reader = stream.try_clone()?;
+    let mut guard = shared
+        .lock()
+        .map_err(|_| std::io::Error::other("network mutex poisoned"))?;
+    let pending = match std::mem::replace(&mut guard.link, Link::Connected(stream)) {
+        Link::Connecting(pending) => pending,
+        Link::Closed => {
+            if let Link::Connected(stream) = &guard.link {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+            guard.link = Link::Closed;
+            return Ok(None);
+        }
+        _ => Vec::new(),
+    };
+    if protocol == NetworkProtocol::Telnet {
+        // Negotiation starts over on every connection.
+        guard.telnet = Some(Telnet::new(guard.cols, guard.rows));
+    }
+    if let Link::Connected(stream) = &mut guard.link {
+        stream.write_all(&pending)?;
+    }
+    Ok(Some(reader))
+}
+
+fn read_loop(
+    mut stream: TcpStream,
+    tab_id: u64,
+    shared: &SharedLink,
+    output_tx: &mut mpsc::UnboundedSender<OutputEvent>,
+) -> std::io::Result<()> {
+    let mut buf = vec![0u8; READ_BUFFER];
+    loop {
+        let n = match stream.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        let bytes = {
+            let mut guard = shared
+                .lock()
+                .map_err(|_| std::io::Error::other("network mutex poisoned"))?;
+            let guard = &mut *guard;
+            match guard.telnet.as_mut() {
+                Some(telnet) => {
+                    let (mut data, mut reply) = (Vec::new(), Vec::new());
+                    telnet.receive(&buf[..n], &mut data, &mut reply);
+                    if let (false, Link::Connected(stream)) = (reply.is_empty(), &mut guard.link) {
+                        stream.write_all(&reply)?;
+                    }
+                    data
+                }
+                None => buf[..n].to_vec(),
+            }
+        };
+
+        if !bytes.is_empty() && !send_output_event(output_tx, OutputEvent::Data { tab_id, bytes }) {
+            return Ok(());
+        }
+    }
+}
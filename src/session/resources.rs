@@ -0,0 +1,375 @@
+//! CPU and memory use of the process trees under local shells.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// One process as it was when a [`ProcessTable`] was taken.
+#[derive(Debug, Clone)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub parent: u32,
+    pub name: String,
+    /// User and system time used since the process started.
+    pub cpu_time: Duration,
+    pub rss_bytes: u64,
+}
+
+/// Every process on the system at one moment. CPU use is the difference
+/// between two of these.
+#[derive(Debug, Clone)]
+pub struct ProcessTable {
+    pub taken: Instant,
+    pub processes: Vec<ProcessEntry>,
+}
+
+/// What one process used between two samples.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    pub name: String,
+    /// Share of one core; a busy multithreaded process goes over 100.
+    pub cpu_percent: f32,
+    pub rss_bytes: u64,
+}
+
+/// What a shell and everything it started used, busiest process first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreeUsage {
+    pub cpu_percent: f32,
+    pub rss_bytes: u64,
+    pub processes: Vec<ProcessUsage>,
+}
+
+impl ProcessTable {
+    /// Usage of `roots` and their descendants, with CPU measured since
+    /// `previous`; without one CPU reads as zero.
+    pub fn usage(&self, previous: Option<&ProcessTable>, roots: &[u32]) -> TreeUsage {
+        let mut children: HashMap<u32, Vec<&ProcessEntry>> = HashMap::new();
+        for process in &self.processes {
+            children.entry(process.parent).or_default().push(process);
+        }
+        let earlier: HashMap<u32, Duration> = previous
+            .map(|table| {
+                table
+                    .processes
+                    .iter()
+                    .map(|process| (process.pid, process.cpu_time))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let elapsed = previous
+            .map(|table| self.taken.saturating_duration_since(table.taken))
+            .unwrap_or_default();
+
+        let mut usage = TreeUsage::default();
+        let mut pending: Vec<&ProcessEntry> = self
+            .processes
+            .iter()
+            .filter(|process| roots.contains(&process.pid))
+            .collect();
+        while let Some(process) = pending.pop() {
+            // A process new since the last sample counts from zero.
+            let cpu_percent = match earlier.get(&process.pid) {
+                Some(before) if !elapsed.is_zero() => {
+                    process.cpu_time.saturating_sub(*before).as_secs_f32() / elapsed.as_secs_f32()
+                        * 100.0
+                }
+                _ => 0.0,
+            };
+            usage.cpu_percent += cpu_percent;
+            usage.rss_bytes += process.rss_bytes;
+            usage.processes.push(ProcessUsage {
+                pid: process.pid,
+                name: process.name.clone(),
+                cpu_percent,
+                rss_bytes: process.rss_bytes,
+            });
+            if let Some(kids) = children.get(&process.pid) {
+                pending.extend(kids.iter().filter(|kid| kid.pid != process.pid));
+            }
+        }
+        usage.processes.sort_by(|a, b| {
+            b.cpu_percent
+                .total_cmp(&a.cpu_percent)
+                .then(b.rss_bytes.cmp(&a.rss_bytes))
+        });
+        usage
+    }
+}
+
+/// Byte count in at most four characters, for badges: `512K`, `45M`, `1.2G`.
+pub fn compact_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if size < 10.0 && unit > 0 {
+        format!("{size:.1}{}", UNITS[unit])
+    } else {
+        format!("{size:.0}{}", UNITS[unit])
+    }
+}
+
+/// Reads the process table; `None` where that isn't supported.
+#[cfg(target_os = "linux")]
+pub fn snapshot() -> Option<ProcessTable> {
+    // SAFETY: sysconf only reads configuration values.
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+    let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as u64;
+    let processes = std::fs::read_dir("/proc")
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| {
+            let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+            parse_stat(pid, &stat, ticks, page)
+        })
+        .collect();
+    Some(ProcessTable {
+        taken: Instant::now(),
+        processes,
+    })
+}
+
+/// Parses `/proc/<pid>/stat`. The name is in parentheses and may hold
+/// spaces and parentheses itself, so fields are counted from the last `)`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_stat(pid: u32, stat: &str, ticks: u64, page: u64) -> Option<ProcessEntry> {
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let name = stat.get(open + 1..close)?.to_string();
+    // Fields from the state on: state, ppid, ..., utime (11), stime (12),
+    // ..., rss in pages (21).
+    let fields: Vec<&str> = stat.get(close + 1..)?.split_whitespace().collect();
+    let field = |index: usize| fields.get(index)?.parse::<u64>().ok();
+    let cpu_ticks = field(11)? + field(12)?;
+    Some(ProcessEntry {
+        pid,
+        parent: field(1)? as u32,
+        name,
+        cpu_time: Duration::from_nanos(cpu_ticks * 1_000_000_000 / ticks),
+        rss_bytes: field(21)? * page,
+    })
+}
+
+#[cfg(target_os = "macos")]
+pub fn snapshot() -> Option<ProcessTable> {
+    // Task times are in Mach ticks, which are nanoseconds only on Intel.
+    let mut timebase = libc::mach_timebase_info { numer: 0, denom: 0 };
+    // SAFETY: the call fills in the struct it is given.
+    #[allow(deprecated)]
+    unsafe {
+        libc::mach_timebase_info(&mut timebase)
+    };
+    let (numer, denom) = if timebase.denom == 0 {
+        (1, 1)
+    } else {
+        (timebase.numer as u64, timebase.denom as u64)
+    };
+
+    // SAFETY: a null buffer asks for the number of processes.
+    let count = unsafe { libc::proc_listallpids(std::ptr::null_mut(), 0) };
+    if count <= 0 {
+        return None;
+    }
+    // Leave room for processes started in between.
+    let mut pids = vec![0 as libc::c_int; count as usize + 64];
+    let size = (pids.len() * std::mem::size_of::<libc::c_int>()) as libc::c_int;
+    // SAFETY: the buffer holds `size` bytes.
+    let count = unsafe { libc::proc_listallpids(pids.as_mut_ptr() as *mut libc::c_void, size) };
+    if count <= 0 {
+        return None;
+    }
+    pids.truncate(count as usize);
+
+    let processes = pids
+        .into_iter()
+        .filter(|&pid| pid > 0)
+        .filter_map(|pid| {
+            let mut info: libc::proc_taskallinfo = unsafe { std::mem::zeroed() };
+            let size = std::mem::size_of::<libc::proc_taskallinfo>() as libc::c_int;
+            // SAFETY: `info` is `size` bytes of plain data.
+            let ret = unsafe {
+                libc::proc_pidinfo(
+                    pid,
+                    libc::PROC_PIDTASKALLINFO,
+                    0,
+                    &mut info as *mut _ as *mut libc::c_void,
+                    size,
+                )
+            };
+            if ret != size {
+                return None;
+            }
+            let comm = &info.pbsd.pbi_comm;
+            // SAFETY: `pbi_comm` is a fixed array of bytes.
+            let bytes =
+                unsafe { std::slice::from_raw_parts(comm.as_ptr() as *const u8, comm.len()) };
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            let ticks = info.ptinfo.pti_total_user + info.ptinfo.pti_total_system;
+            Some(ProcessEntry {
+                pid: pid as u32,
+                parent: info.pbsd.pbi_ppid,
+                name: String::from_utf8_lossy(&bytes[..end]).into_owned(),
+                cpu_time: Duration::from_nanos(ticks * numer / denom),
+                rss_bytes: info.ptinfo.pti_resident_size,
+            })
+        })
+        .collect();
+    Some(ProcessTable {
+        taken: Instant::now(),
+        processes,
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub fn snapshot() -> Option<ProcessTable> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+        TH32CS_SNAPPROCESS,
+    };
+
+    // SAFETY: taking a snapshot of the process list reads no memory of ours.
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }.ok()?;
+    let mut entry = PROCESSENTRY32W {
+        dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+        ..Default::default()
+    };
+    let mut processes = Vec::new();
+    // SAFETY: `entry` is a PROCESSENTRY32W with its size filled in.
+    let mut found = unsafe { Process32FirstW(snapshot, &mut entry) };
+    while found.is_ok() {
+        let name = &entry.szExeFile;
+        let end = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+        // Processes we may not open, such as system ones, still count as
+        // parents but show no usage.
+        let (cpu_time, rss_bytes) = windows_usage(entry.th32ProcessID).unwrap_or_default();
+        processes.push(ProcessEntry {
+            pid: entry.th32ProcessID,
+            parent: entry.th32ParentProcessID,
+            name: String::from_utf16_lossy(&name[..end]),
+            cpu_time,
+            rss_bytes,
+        });
+        // SAFETY: as above.
+        found = unsafe { Process32NextW(snapshot, &mut entry) };
+    }
+    // SAFETY: the snapshot handle is ours and not used again.
+    let _ = unsafe { CloseHandle(snapshot) };
+    Some(ProcessTable {
+        taken: Instant::now(),
+        processes,
+    })
+}
+
+/// CPU time and working set of a process; `None` if it can't be opened.
+#[cfg(target_os = "windows")]
+fn windows_usage(pid: u32) -> Option<(Duration, u64)> {
+    use windows::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    // SAFETY: opening a process by id reads no memory of ours.
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+    let (mut created, mut exited, mut kernel, mut user) = (
+        FILETIME::default(),
+        FILETIME::default(),
+        FILETIME::default(),
+        FILETIME::default(),
+    );
+    let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+    let mut memory = PROCESS_MEMORY_COUNTERS {
+        cb: size,
+        ..Default::default()
+    };
+    // SAFETY: every pointer is to a local of the type the call fills in.
+    let read = unsafe {
+        GetProcessTimes(process, &mut created, &mut exited, &mut kernel, &mut user)
+            .and_then(|()| GetProcessMemoryInfo(process, &mut memory, size))
+    };
+    // SAFETY: the handle is ours and not used again.
+    let _ = unsafe { CloseHandle(process) };
+    read.ok()?;
+    // File times count 100-nanosecond intervals.
+    let nanos = |time: FILETIME| {
+        ((u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime)) * 100
+    };
+    Some((
+        Duration::from_nanos(nanos(kernel) + nanos(user)),
+        memory.WorkingSetSize as u64,
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn snapshot() -> Option<ProcessTable> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pid: u32, parent: u32, cpu_ms: u64, rss_bytes: u64) -> ProcessEntry {
+        ProcessEntry {
+            pid,
+            parent,
+            name: format!("p{pid}"),
+            cpu_time: Duration::from_millis(cpu_ms),
+            rss_bytes,
+        }
+    }
+
+    #[test]
+    fn usage_covers_the_tree_under_the_roots() {
+        let start = Instant::now();
+        let before = ProcessTable {
+            taken: start,
+            processes: vec![entry(10, 1, 0, 0), entry(11, 10, 0, 0), entry(20, 1, 0, 0)],
+        };
+        let after = ProcessTable {
+            taken: start + Duration::from_secs(2),
+            processes: vec![
+                entry(10, 1, 100, 1024),
+                entry(11, 10, 1_000, 4096),
+                entry(12, 11, 500, 2048),
+                entry(20, 1, 2_000, 1 << 20),
+            ],
+        };
+
+        let usage = after.usage(Some(&before), &[10]);
+        assert_eq!(usage.rss_bytes, 1024 + 4096 + 2048);
+        // 1.1s of CPU over 2s; pid 12 is new, so it counts from the next sample.
+        assert!(
+            (usage.cpu_percent - 55.0).abs() < 0.01,
+            "{}",
+            usage.cpu_percent
+        );
+        assert_eq!(usage.processes[0].pid, 11, "busiest process comes first");
+        assert_eq!(usage.processes.len(), 3);
+
+        assert_eq!(after.usage(None, &[10]).cpu_percent, 0.0);
+    }
+
+    #[test]
+    fn stat_lines_with_odd_names_parse() {
+        let stat = "42 (tmux: (server)) S 7 42 42 0 -1 4194560 0 0 0 0 \
+                    150 50 0 0 20 0 1 0 100 1000000 256 18446744073709551615";
+        let process = parse_stat(42, stat, 100, 4096).unwrap();
+        assert_eq!(process.name, "tmux: (server)");
+        assert_eq!(process.parent, 7);
+        assert_eq!(process.cpu_time, Duration::from_secs(2));
+        assert_eq!(process.rss_bytes, 256 * 4096);
+    }
+
+    #[test]
+    fn compact_bytes_fits_a_badge() {
+        assert_eq!(compact_bytes(512 * 1024), "512K");
+        assert_eq!(compact_bytes(45 << 20), "45M");
+        assert_eq!(compact_bytes(1288 << 20), "1.3G");
+    }
+}
@@ -0,0 +1,262 @@
+//! The client side of telnet (RFC 854) option negotiation. Only what a
+//! terminal needs is accepted: the server may echo and suppress go-ahead, and
+//! we report our terminal type and window size.
+
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+const ECHO: u8 = 1;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const TERMINAL_TYPE: u8 = 24;
+const WINDOW_SIZE: u8 = 31;
+
+const TTYPE_IS: u8 = 0;
+const TTYPE_SEND: u8 = 1;
+const TERMINAL_NAME: &[u8] = b"XTERM-256COLOR";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Data,
+    Iac,
+    Option(u8),
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+#[derive(Debug)]
+pub struct Telnet {
+    state: State,
+    subnegotiation: Vec<u8>,
+    /// Options we agreed to perform.
+    local: [bool; 256],
+    /// Options we asked the server to perform.
+    remote: [bool; 256],
+    columns: u16,
+    rows: u16,
+}
+
+impl Telnet {
+    pub fn new(columns: u16, rows: u16) -> Self {
+        Self {
+            state: State::Data,
+            subnegotiation: Vec::new(),
+            local: [false; 256],
+            remote: [false; 256],
+            columns,
+            rows,
+        }
+    }
+
+    /// Splits `input` into terminal data and negotiation, appending the data
+    /// to `data` and anything that must go back to the server to `reply`.
+    pub fn receive(&mut self, input: &[u8], data: &mut Vec<u8>, reply: &mut Vec<u8>) {
+        for &byte in input {
+            self.state = match (self.state, byte) {
+                (State::Data, IAC) => State::Iac,
+                (State::Data, _) => {
+                    data.push(byte);
+                    State::Data
+                }
+                (State::Iac, IAC) => {
+                    data.push(IAC);
+                    State::Data
+                }
+                (State::Iac, DO | DONT | WILL | WONT) => State::Option(byte),
+                (State::Iac, SB) => {
+                    self.subnegotiation.clear();
+                    State::Subnegotiation
+                }
+                // NOP, go-ahead and the other bare commands carry no data.
+                (State::Iac, _) => State::Data,
+                (State::Option(command), option) => {
+                    self.negotiate(command, option, reply);
+                    State::Data
+                }
+                (State::Subnegotiation, IAC) => State::SubnegotiationIac,
+                (State::Subnegotiation, _) => {
+                    self.subnegotiation.push(byte);
+                    State::Subnegotiation
+                }
+                (State::SubnegotiationIac, SE) => {
+                    self.subnegotiate(reply);
+                    State::Data
+                }
+                (State::SubnegotiationIac, _) => {
+                    self.subnegotiation.push(byte);
+                    State::Subnegotiation
+                }
+            };
+        }
+    }
+
+    /// Records the new size, returning the report to send when the server
+    /// asked for window size updates.
+    pub fn resize(&mut self, columns: u16, rows: u16) -> Option<Vec<u8>> {
+        self.columns = columns;
+        self.rows = rows;
+        if !self.local[WINDOW_SIZE as usize] {
+            return None;
+        }
+        let mut reply = Vec::new();
+        self.window_size(&mut reply);
+        Some(reply)
+    }
+
+    fn negotiate(&mut self, command: u8, option: u8, reply: &mut Vec<u8>) {
+        let index = option as usize;
+        match command {
+            DO => {
+                let supported = matches!(option, TERMINAL_TYPE | WINDOW_SIZE);
+                if supported && !self.local[index] {
+                    self.local[index] = true;
+                    reply.extend([IAC, WILL, option]);
+                } else if !supported {
+                    reply.extend([IAC, WONT, option]);
+                }
+                if option == WINDOW_SIZE {
+                    self.window_size(reply);
+                }
+            }
+            DONT if self.local[index] => {
+                self.local[index] = false;
+                reply.extend([IAC, WONT, option]);
+            }
+            WILL => {
+                let supported = matches!(option, ECHO | SUPPRESS_GO_AHEAD);
+                if supported && !self.remote[index] {
+                    self.remote[index] = true;
+                    reply.extend([IAC, DO, option]);
+                } else if !supported {
+                    reply.extend([IAC, DONT, option]);
+                }
+            }
+            WONT if self.remote[index] => {
+                self.remote[index] = false;
+                reply.extend([IAC, DONT, option]);
+            }
+            _ => {}
+        }
+    }
+
+    fn subnegotiate(&mut self, reply: &mut Vec<u8>) {
+        if self.subnegotiation.as_slice() == [TERMINAL_TYPE, TTYPE_SEND] {
+            reply.extend([IAC, SB, TERMINAL_TYPE, TTYPE_IS]);
+            reply.extend_from_slice(TERMINAL_NAME);
+            reply.extend([IAC, SE]);
+        }
+    }
+
+    fn window_size(&self, reply: &mut Vec<u8>) {
+        reply.extend([IAC, SB, WINDOW_SIZE]);
+        for byte in self
+            .columns
+            .to_be_bytes()
+            .into_iter()
+            .chain(self.rows.to_be_bytes())
+        {
+            reply.push(byte);
+            if byte == IAC {
+                reply.push(IAC);
+            }
+        }
+        reply.extend([IAC, SE]);
+    }
+}
+
+/// Prepares keyboard input for the wire: IAC bytes are doubled, and a bare
+/// carriage return becomes CR NUL as the protocol requires.
+pub fn encode(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut bytes = input.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        out.push(byte);
+        match byte {
+            IAC => out.push(IAC),
+            b'\r' if bytes.peek() != Some(&&b'\n') => out.push(0),
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receive(telnet: &mut Telnet, input: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let (mut data, mut reply) = (Vec::new(), Vec::new());
+        telnet.receive(input, &mut data, &mut reply);
+        (data, reply)
+    }
+
+    #[test]
+    fn negotiation_is_stripped_from_data_even_across_reads() {
+        let mut telnet = Telnet::new(80, 24);
+        let (data, reply) = receive(&mut telnet, &[b'h', b'i', IAC, WILL]);
+        assert_eq!(data, b"hi");
+        assert!(reply.is_empty());
+
+        let (data, reply) = receive(&mut telnet, &[ECHO, b'!', IAC, IAC, IAC, 241]);
+        assert_eq!(data, [b'!', IAC]);
+        assert_eq!(reply, [IAC, DO, ECHO]);
+    }
+
+    #[test]
+    fn accepts_terminal_options_and_refuses_the_rest() {
+        let mut telnet = Telnet::new(80, 24);
+        let (_, reply) = receive(&mut telnet, &[IAC, DO, TERMINAL_TYPE, IAC, DO, 39]);
+        assert_eq!(reply, [IAC, WILL, TERMINAL_TYPE, IAC, WONT, 39]);
+
+        // A repeated request is already agreed and needs no answer.
+        let (_, reply) = receive(&mut telnet, &[IAC, DO, TERMINAL_TYPE]);
+        assert!(reply.is_empty());
+
+        let (_, reply) = receive(&mut telnet, &[IAC, SB, TERMINAL_TYPE, TTYPE_SEND, IAC, SE]);
+        let mut expected = vec![IAC, SB, TERMINAL_TYPE, TTYPE_IS];
+        expected.extend_from_slice(TERMINAL_NAME);
+        expected.extend([IAC, SE]);
+        assert_eq!(reply, expected);
+    }
+
+    #[test]
+    fn window_size_is_sent_once_asked_for_and_on_resize() {
+        let mut telnet = Telnet::new(80, 24);
+        assert_eq!(telnet.resize(100, 30), None);
+
+        let (_, reply) = receive(&mut telnet, &[IAC, DO, WINDOW_SIZE]);
+        assert_eq!(
+            reply,
+            [
+                IAC,
+                WILL,
+                WINDOW_SIZE,
+                IAC,
+                SB,
+                WINDOW_SIZE,
+                0,
+                100,
+                0,
+                30,
+                IAC,
+                SE
+            ]
+        );
+
+        assert_eq!(
+            telnet.resize(255, 40),
+            Some(vec![IAC, SB, WINDOW_SIZE, 0, 255, 255, 0, 40, IAC, SE])
+        );
+    }
+
+    #[test]
+    fn encode_escapes_iac_and_bare_carriage_returns() {
+        assert_eq!(encode(b"ls\r"), b"ls\r\0");
+        assert_eq!(encode(b"a\r\nb"), b"a\r\nb");
+        assert_eq!(encode(&[IAC, b'x']), [IAC, IAC, b'x']);
+    }
+}
@@ -0,0 +1,171 @@
+//! Named sets of tabs and splits, saved to `workspaces.toml` next to the
+//! session history and re-opened from the shell picker.
+
+use crate::gui::pane::Axis;
+use crate::gui::tab::Profile;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The split tree of one tab, with what each pane was running.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkspaceLayout {
+    Pane {
+        profile: Profile,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cwd: Option<PathBuf>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+    },
+    Split {
+        axis: Axis,
+        ratio: f32,
+        first: Box<WorkspaceLayout>,
+        second: Box<WorkspaceLayout>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceTab {
+    /// Hex accent color of the tab.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    pub layout: WorkspaceLayout,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub name: String,
+    /// Index of the tab that was active when the workspace was saved.
+    #[serde(default)]
+    pub active: usize,
+    pub tabs: Vec<WorkspaceTab>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Workspaces {
+    #[serde(default, rename = "workspace")]
+    pub entries: Vec<Workspace>,
+}
+
+impl Workspaces {
+    pub fn load() -> Self {
+        let Some(path) = workspaces_path() else {
+            return Self::default();
+        };
+        std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| toml::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let Some(path) = workspaces_path() else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(s) = toml::to_string_pretty(self) {
+            let _ = std::fs::write(&path, s);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Workspace> {
+        self.entries.iter().find(|w| w.name == name)
+    }
+
+    /// Stores `workspace`, replacing any saved under the same name.
+    pub fn insert(&mut self, workspace: Workspace) {
+        match self.entries.iter_mut().find(|w| w.name == workspace.name) {
+            Some(existing) => *existing = workspace,
+            None => self.entries.push(workspace),
+        }
+    }
+}
+
+fn workspaces_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("rabbitty").join("workspaces.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane(name: &str, cwd: Option<&str>) -> WorkspaceLayout {
+        WorkspaceLayout::Pane {
+            profile: Profile::shell(name.into(), format!("/bin/{name}")),
+            cwd: cwd.map(PathBuf::from),
+            title: Some(format!("{name} title")),
+        }
+    }
+
+    fn workspace(name: &str, tabs: Vec<WorkspaceTab>) -> Workspace {
+        Workspace {
+            name: name.into(),
+            active: 0,
+            tabs,
+        }
+    }
+
+    #[test]
+    fn split_layouts_round_trip_through_toml() {
+        let mut workspaces = Workspaces::default();
+        workspaces.insert(workspace(
+            "backend",
+            vec![WorkspaceTab {
+                color: Some("#e5534b".into()),
+                icon: Some("zsh".into()),
+                layout: WorkspaceLayout::Split {
+                    axis: Axis::Vertical,
+                    ratio: 0.3,
+                    first: Box::new(pane("zsh", Some("/srv/api"))),
+                    second: Box::new(pane("bash", None)),
+                },
+            }],
+        ));
+
+        let toml = toml::to_string_pretty(&workspaces).expect("serialize");
+        let restored: Workspaces = toml::from_str(&toml).expect("deserialize");
+        let tab = &restored.get("backend").expect("saved").tabs[0];
+        assert_eq!(tab.color.as_deref(), Some("#e5534b"));
+        let WorkspaceLayout::Split {
+            axis,
+            ratio,
+            first,
+            second,
+        } = &tab.layout
+        else {
+            panic!("expected a split");
+        };
+        assert_eq!(*axis, Axis::Vertical);
+        assert_eq!(*ratio, 0.3);
+        assert!(matches!(
+            first.as_ref(),
+            WorkspaceLayout::Pane { cwd: Some(cwd), .. } if cwd == &PathBuf::from("/srv/api")
+        ));
+        assert!(matches!(
+            second.as_ref(),
+            WorkspaceLayout::Pane { cwd: None, title: Some(title), .. } if title == "bash title"
+        ));
+    }
+
+    #[test]
+    fn saving_under_an_existing_name_replaces_it() {
+        let tab = || WorkspaceTab {
+            color: None,
+            icon: None,
+            layout: pane("zsh", None),
+        };
+        let mut workspaces = Workspaces::default();
+        workspaces.insert(workspace("backend", vec![tab()]));
+        workspaces.insert(workspace("frontend", vec![tab()]));
+        workspaces.insert(workspace("backend", vec![tab(), tab()]));
+
+        assert_eq!(workspaces.entries.len(), 2);
+        assert_eq!(workspaces.get("backend").unwrap().tabs.len(), 2);
+        assert!(workspaces.get("missing").is_none());
+    }
+}
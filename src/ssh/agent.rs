@@ -0,0 +1,91 @@
+//! The local SSH agent: OpenSSH's socket, or on Windows the OpenSSH agent
+//! service's named pipe with Pageant as the fallback.
+//!
+//! Profiles that sign in with the agent offer each key it holds in turn.
+//! Profiles that forward the agent (`-A`) get the channels the server opens
+//! for it piped to a fresh connection to the local agent.
+
+use russh::Channel;
+use russh::client;
+use russh::keys::agent::client::{AgentClient, AgentStream};
+
+pub(super) type Agent = AgentClient<Box<dyn AgentStream + Send + Unpin>>;
+
+/// Where the OpenSSH for Windows agent service listens.
+#[cfg(windows)]
+const OPENSSH_AGENT_PIPE: &str = r"\\.\pipe\openssh-ssh-agent";
+
+/// Which agent answered, for the connection status lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Source {
+    /// The socket named by `SSH_AUTH_SOCK`.
+    Socket,
+    /// The OpenSSH agent service on Windows.
+    NamedPipe,
+    Pageant,
+}
+
+impl Source {
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            Self::Socket => "ssh-agent",
+            Self::NamedPipe => "the OpenSSH agent service",
+            Self::Pageant => "Pageant",
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(super) async fn connect() -> Result<(Agent, Source), String> {
+    let agent = AgentClient::connect_env()
+        .await
+        .map_err(|err| format!("No SSH agent is reachable through SSH_AUTH_SOCK: {err}"))?;
+    Ok((agent.dynamic(), Source::Socket))
+}
+
+#[cfg(windows)]
+pub(super) async fn connect() -> Result<(Agent, Source), String> {
+    if let Ok(agent) = AgentClient::connect_named_pipe(OPENSSH_AGENT_PIPE).await {
+        return Ok((agent.dynamic(), Source::NamedPipe));
+    }
+    Ok((
+        AgentClient::connect_pageant().await.dynamic(),
+        Source::Pageant,
+    ))
+}
+
+/// Offers the agent's keys one by one until the server accepts one.
+pub(super) async fn authenticate<H: client::Handler>(
+    session: &mut client::Handle<H>,
+    user: &str,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let (mut agent, source) = connect().await?;
+    let keys = agent
+        .request_identities()
+        .await
+        .map_err(|err| format!("Could not list the keys in {}: {err}", source.label()))?;
+    if keys.is_empty() {
+        return Err(format!("{} holds no keys", source.label()).into());
+    }
+    for key in keys {
+        if session
+            .authenticate_publickey_with(user, key, &mut agent)
+            .await?
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Answers one agent channel the server opened by relaying it to the local
+/// agent until either side closes.
+pub(super) async fn serve_forwarded(channel: Channel<client::Msg>) {
+    let Ok((agent, _)) = connect().await else {
+        let _ = channel.close().await;
+        return;
+    };
+    let mut local = agent.into_inner();
+    let mut stream = channel.into_stream();
+    let _ = tokio::io::copy_bidirectional(&mut local, &mut stream).await;
+}
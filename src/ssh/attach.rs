@@ -0,0 +1,131 @@
+//! Remote attach: instead of a shell, the channel runs `rabbitty attach` on
+//! the server and carries the session daemon's protocol. The session lives
+//! in the server's daemon, so it outlives a dropped connection, and the
+//! reconnect resumes it with one redraw of the screen rather than a replay
+//! of everything that scrolled past.
+
+use super::SessionEnd;
+use crate::session::OutputEvent;
+use crate::session::daemon::{Reply, Request};
+use iced::futures::channel::mpsc as futures_mpsc;
+use russh::{Channel, ChannelMsg, client};
+use tokio::sync::mpsc as tokio_mpsc;
+
+/// Run on the server in place of a shell.
+pub(super) const REMOTE_COMMAND: &str = "rabbitty attach";
+
+/// Drives the remote session until it exits or the link drops.
+/// `remote_id` is the daemon session this tab belongs to: `None` starts a
+/// new one, and it is kept across reconnects so they re-attach.
+pub(super) async fn run(
+    channel: &mut Channel<client::Msg>,
+    tab_id: u64,
+    size: &mut (u16, u16),
+    write_rx: &mut tokio_mpsc::UnboundedReceiver<Vec<u8>>,
+    resize_rx: &mut tokio_mpsc::UnboundedReceiver<(u16, u16)>,
+    output_tx: &mut futures_mpsc::UnboundedSender<OutputEvent>,
+    remote_id: &mut Option<u64>,
+) -> Result<SessionEnd, Box<dyn std::error::Error + Send + Sync>> {
+    channel.exec(false, REMOTE_COMMAND).await?;
+
+    let (rows, cols) = *size;
+    let first = match *remote_id {
+        Some(id) => Request::Attach { id, grid: true },
+        None => Request::Spawn {
+            program: String::new(),
+            args: Vec::new(),
+            env: Vec::new(),
+            rows,
+            cols,
+            cwd: None,
+            profile: String::new(),
+            grid: true,
+        },
+    };
+    channel.data(&first.to_bytes()[..]).await?;
+    if remote_id.is_some() {
+        // The window may have changed size while the link was down.
+        channel
+            .data(&Request::Resize { rows, cols }.to_bytes()[..])
+            .await?;
+    }
+
+    let mut attached = false;
+    let mut pending = Vec::new();
+    loop {
+        tokio::select! {
+            msg = channel.wait() => {
+                match msg {
+                    Some(ChannelMsg::Data { data }) => {
+                        pending.extend_from_slice(&data);
+                        while let Some(reply) = Reply::take_from(&mut pending)? {
+                            match reply {
+                                Reply::Spawned { id, .. } => {
+                                    attached = true;
+                                    *remote_id = Some(id);
+                                }
+                                Reply::Output(bytes) => {
+                                    let _ = output_tx
+                                        .unbounded_send(OutputEvent::Data { tab_id, bytes });
+                                }
+                                Reply::Exited => {
+                                    *remote_id = None;
+                                    return Ok(SessionEnd::Exited);
+                                }
+                                Reply::Error(message) => {
+                                    // The session is gone; the next attempt
+                                    // starts a new one.
+                                    *remote_id = None;
+                                    return Err(message.into());
+                                }
+                                Reply::Sessions(_) => {}
+                            }
+                        }
+                    }
+                    Some(ChannelMsg::Eof)
+                    | Some(ChannelMsg::Close)
+                    | Some(ChannelMsg::ExitStatus { .. })
+                        if !attached =>
+                    {
+                        return Err(format!(
+                            "`{REMOTE_COMMAND}` did not start on the server. Is RabbiTTY \
+                             installed there and on the PATH?"
+                        )
+                        .into());
+                    }
+                    Some(ChannelMsg::Eof)
+                    | Some(ChannelMsg::Close)
+                    | Some(ChannelMsg::ExitStatus { .. })
+                    | None => return Ok(SessionEnd::Dropped),
+                    _ => {}
+                }
+            }
+            bytes = write_rx.recv() => {
+                match bytes {
+                    Some(bytes) => {
+                        let frame = Request::Input(bytes).to_bytes();
+                        if channel.data(&frame[..]).await.is_err() {
+                            return Ok(SessionEnd::Dropped);
+                        }
+                    }
+                    None => {
+                        let _ = channel.data(&Request::Kill.to_bytes()[..]).await;
+                        return Ok(SessionEnd::Closed);
+                    }
+                }
+            }
+            resize = resize_rx.recv() => {
+                match resize {
+                    Some((r, c)) => {
+                        *size = (r, c);
+                        let frame = Request::Resize { rows: r, cols: c }.to_bytes();
+                        if channel.data(&frame[..]).await.is_err() {
+                            return Ok(SessionEnd::Dropped);
+                        }
+                    }
+                    None => return Ok(SessionEnd::Closed),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,407 @@
+//! Port forwards carried over the session's own connection.
+//!
+//! Local (`-L`) and dynamic (`-D`) forwards listen here and open a
+//! `direct-tcpip` channel per accepted connection. Remote (`-R`) forwards ask
+//! the server to listen; the channels it opens back arrive through the client
+//! handler and are connected to the target from here.
+
+use super::{SharedSession, SshHandler};
+use crate::config::{ForwardKind, PortForward};
+use crate::session::OutputEvent;
+use iced::futures::channel::mpsc as futures_mpsc;
+use russh::client;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Live state of one forward.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForwardStatus {
+    /// Switched off, or the session is not connected.
+    Stopped,
+    /// Listening, or the server accepted the remote listener.
+    Active,
+    Failed(String),
+}
+
+struct Slot {
+    forward: PortForward,
+    status: ForwardStatus,
+    /// Listener task of a running local or dynamic forward.
+    task: Option<tokio::task::AbortHandle>,
+    /// The server holds a listener for this remote forward.
+    remote_open: bool,
+}
+
+/// The forwards of one tab, shared by the session task, the client handler
+/// and the GUI. Enabled flags outlive reconnects; everything else is reset
+/// when the connection ends.
+#[derive(Clone)]
+pub(super) struct Forwards {
+    slots: Arc<Mutex<Vec<Slot>>>,
+    tab_id: u64,
+    output_tx: futures_mpsc::UnboundedSender<OutputEvent>,
+}
+
+impl Forwards {
+    pub(super) fn new(
+        forwards: Vec<PortForward>,
+        tab_id: u64,
+        output_tx: futures_mpsc::UnboundedSender<OutputEvent>,
+    ) -> Self {
+        let slots = forwards
+            .into_iter()
+            .map(|forward| Slot {
+                forward,
+                status: ForwardStatus::Stopped,
+                task: None,
+                remote_open: false,
+            })
+            .collect();
+        Self {
+            slots: Arc::new(Mutex::new(slots)),
+            tab_id,
+            output_tx,
+        }
+    }
+
+    pub(super) fn list(&self) -> Vec<(PortForward, ForwardStatus)> {
+        self.slots
+            .lock()
+            .map(|slots| {
+                slots
+                    .iter()
+                    .map(|slot| (slot.forward.clone(), slot.status.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Requests the server-side listeners of enabled remote forwards. Runs
+    /// before the session is shared, as russh needs it mutably for this.
+    pub(super) async fn open_remote(&self, session: &mut client::Handle<SshHandler>) {
+        let pending: Vec<(usize, PortForward)> = match self.slots.lock() {
+            Ok(slots) => slots
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| {
+                    slot.forward.kind == ForwardKind::Remote && slot.forward.enabled
+                })
+                .map(|(index, slot)| (index, slot.forward.clone()))
+                .collect(),
+            Err(_) => return,
+        };
+        for (index, forward) in pending {
+            let status = match session
+                .tcpip_forward(forward.bind_host(), u32::from(forward.bind_port))
+                .await
+            {
+                Ok(_) => {
+                    if let Ok(mut slots) = self.slots.lock()
+                        && let Some(slot) = slots.get_mut(index)
+                    {
+                        slot.remote_open = true;
+                    }
+                    ForwardStatus::Active
+                }
+                Err(err) => ForwardStatus::Failed(err.to_string()),
+            };
+            self.set_status(index, status);
+        }
+    }
+
+    /// Starts the enabled local and dynamic forwards on a new session.
+    pub(super) fn start(&self, session: &SharedSession) {
+        let indices: Vec<usize> = match self.slots.lock() {
+            Ok(slots) => slots
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| {
+                    slot.forward.kind != ForwardKind::Remote && slot.forward.enabled
+                })
+                .map(|(index, _)| index)
+                .collect(),
+            Err(_) => return,
+        };
+        for index in indices {
+            self.spawn_listener(index, Arc::clone(session));
+        }
+    }
+
+    /// Tears everything down once the connection has ended.
+    pub(super) fn stop(&self) {
+        if let Ok(mut slots) = self.slots.lock() {
+            if slots.is_empty() {
+                return;
+            }
+            for slot in slots.iter_mut() {
+                if let Some(task) = slot.task.take() {
+                    task.abort();
+                }
+                slot.remote_open = false;
+                slot.status = ForwardStatus::Stopped;
+            }
+        }
+        self.notify();
+    }
+
+    /// Switches forward `index` on or off on the running session.
+    pub(super) fn toggle(&self, index: usize, session: Option<SharedSession>) {
+        let Ok(mut slots) = self.slots.lock() else {
+            return;
+        };
+        let Some(slot) = slots.get_mut(index) else {
+            return;
+        };
+        slot.forward.enabled = !slot.forward.enabled;
+        if !slot.forward.enabled {
+            if let Some(task) = slot.task.take() {
+                task.abort();
+            }
+            slot.status = ForwardStatus::Stopped;
+        } else if slot.forward.kind == ForwardKind::Remote {
+            // A listener the server already holds just starts accepting
+            // again; opening a new one needs the session mutably.
+            slot.status = match (&session, slot.remote_open) {
+                (_, true) => ForwardStatus::Active,
+                (Some(_), false) => {
+                    ForwardStatus::Failed("reconnect to open the remote listener".into())
+                }
+                (None, false) => ForwardStatus::Stopped,
+            };
+        } else if let Some(session) = session {
+            drop(slots);
+            self.spawn_listener(index, session);
+            return;
+        }
+        drop(slots);
+        self.notify();
+    }
+
+    /// Target of the enabled remote forward the server opened `port` for.
+    pub(super) fn remote_target(&self, port: u32) -> Option<(String, u16)> {
+        let slots = self.slots.lock().ok()?;
+        slots
+            .iter()
+            .find(|slot| {
+                slot.forward.kind == ForwardKind::Remote
+                    && slot.forward.enabled
+                    && slot.remote_open
+                    && u32::from(slot.forward.bind_port) == port
+            })
+            .map(|slot| (slot.forward.target_host.clone(), slot.forward.target_port))
+    }
+
+    fn spawn_listener(&self, index: usize, session: SharedSession) {
+        let Some(forward) = self
+            .slots
+            .lock()
+            .ok()
+            .and_then(|slots| slots.get(index).map(|slot| slot.forward.clone()))
+        else {
+            return;
+        };
+        let this = self.clone();
+        let task = tokio::spawn(async move {
+            let listener = match TcpListener::bind((forward.bind_host(), forward.bind_port)).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    this.set_status(index, ForwardStatus::Failed(err.to_string()));
+                    return;
+                }
+            };
+            this.set_status(index, ForwardStatus::Active);
+            loop {
+                match listener.accept().await {
+                    Ok((socket, peer)) => {
+                        tokio::spawn(tunnel(socket, peer, forward.clone(), Arc::clone(&session)));
+                    }
+                    Err(err) => {
+                        this.set_status(index, ForwardStatus::Failed(err.to_string()));
+                        return;
+                    }
+                }
+            }
+        });
+        if let Ok(mut slots) = self.slots.lock()
+            && let Some(slot) = slots.get_mut(index)
+        {
+            slot.task = Some(task.abort_handle());
+        }
+    }
+
+    fn set_status(&self, index: usize, status: ForwardStatus) {
+        if let Ok(mut slots) = self.slots.lock()
+            && let Some(slot) = slots.get_mut(index)
+        {
+            slot.status = status;
+        }
+        self.notify();
+    }
+
+    fn notify(&self) {
+        let _ = self.output_tx.unbounded_send(OutputEvent::ForwardsChanged {
+            tab_id: self.tab_id,
+        });
+    }
+}
+
+/// Carries one accepted local connection over a `direct-tcpip` channel.
+async fn tunnel(
+    mut socket: TcpStream,
+    peer: SocketAddr,
+    forward: PortForward,
+    session: SharedSession,
+) {
+    let (host, port) = match forward.kind {
+        ForwardKind::Dynamic => match socks5_accept(&mut socket).await {
+            Ok(target) => target,
+            Err(_) => return,
+        },
+        _ => (forward.target_host, forward.target_port),
+    };
+    let channel = session
+        .channel_open_direct_tcpip(
+            host,
+            u32::from(port),
+            peer.ip().to_string(),
+            u32::from(peer.port()),
+        )
+        .await;
+    if forward.kind == ForwardKind::Dynamic {
+        let code = if channel.is_ok() {
+            SOCKS5_SUCCEEDED
+        } else {
+            SOCKS5_HOST_UNREACHABLE
+        };
+        if socks5_reply(&mut socket, code).await.is_err() {
+            return;
+        }
+    }
+    let Ok(channel) = channel else {
+        return;
+    };
+    let mut stream = channel.into_stream();
+    let _ = tokio::io::copy_bidirectional(&mut socket, &mut stream).await;
+}
+
+/// Connects a channel the server opened for a remote forward to its target.
+pub(super) async fn connect_remote(channel: russh::Channel<client::Msg>, host: String, port: u16) {
+    let Ok(mut socket) = TcpStream::connect((host.as_str(), port)).await else {
+        let _ = channel.close().await;
+        return;
+    };
+    let mut stream = channel.into_stream();
+    let _ = tokio::io::copy_bidirectional(&mut socket, &mut stream).await;
+}
+
+const SOCKS5_SUCCEEDED: u8 = 0;
+const SOCKS5_HOST_UNREACHABLE: u8 = 4;
+const SOCKS5_COMMAND_NOT_SUPPORTED: u8 = 7;
+const SOCKS5_ADDRESS_NOT_SUPPORTED: u8 = 8;
+
+/// Negotiates a SOCKS5 CONNECT without authentication and returns the
+/// requested destination. The caller sends the final reply once it knows
+/// whether the server could reach it.
+async fn socks5_accept<S>(socket: &mut S) -> std::io::Result<(String, u16)>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let invalid = |message: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+
+    let mut greeting = [0u8; 2];
+    socket.read_exact(&mut greeting).await?;
+    if greeting[0] != 5 {
+        return Err(invalid("not a SOCKS5 client"));
+    }
+    let mut methods = vec![0u8; usize::from(greeting[1])];
+    socket.read_exact(&mut methods).await?;
+    if !methods.contains(&0) {
+        socket.write_all(&[5, 0xff]).await?;
+        return Err(invalid("client requires authentication"));
+    }
+    socket.write_all(&[5, 0]).await?;
+
+    let mut request = [0u8; 4];
+    socket.read_exact(&mut request).await?;
+    let host = match request[3] {
+        1 => {
+            let mut octets = [0u8; 4];
+            socket.read_exact(&mut octets).await?;
+            Ipv4Addr::from(octets).to_string()
+        }
+        3 => {
+            let mut name = vec![0u8; usize::from(socket.read_u8().await?)];
+            socket.read_exact(&mut name).await?;
+            String::from_utf8(name).map_err(|_| invalid("host name is not UTF-8"))?
+        }
+        4 => {
+            let mut octets = [0u8; 16];
+            socket.read_exact(&mut octets).await?;
+            Ipv6Addr::from(octets).to_string()
+        }
+        _ => {
+            socks5_reply(socket, SOCKS5_ADDRESS_NOT_SUPPORTED).await?;
+            return Err(invalid("unknown address type"));
+        }
+    };
+    let port = socket.read_u16().await?;
+    if request[1] != 1 {
+        socks5_reply(socket, SOCKS5_COMMAND_NOT_SUPPORTED).await?;
+        return Err(invalid("only CONNECT is supported"));
+    }
+    Ok((host, port))
+}
+
+async fn socks5_reply<S>(socket: &mut S, code: u8) -> std::io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    socket.write_all(&[5, code, 0, 1, 0, 0, 0, 0, 0, 0]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn socks5_connect_by_name_yields_destination() {
+        block_on(async {
+            let (mut client, mut server) = tokio::io::duplex(64);
+            client.write_all(&[5, 1, 0, 5, 1, 0, 3, 11]).await.unwrap();
+            client.write_all(b"example.com\x01\xbb").await.unwrap();
+
+            let target = socks5_accept(&mut server).await.unwrap();
+            assert_eq!(target, ("example.com".to_string(), 443));
+
+            let mut method = [0u8; 2];
+            client.read_exact(&mut method).await.unwrap();
+            assert_eq!(method, [5, 0]);
+        });
+    }
+
+    #[test]
+    fn socks5_rejects_bind_requests() {
+        block_on(async {
+            let (mut client, mut server) = tokio::io::duplex(64);
+            client
+                .write_all(&[5, 1, 0, 5, 2, 0, 1, 127, 0, 0, 1, 0, 80])
+                .await
+                .unwrap();
+
+            assert!(socks5_accept(&mut server).await.is_err());
+
+            let mut reply = [0u8; 12];
+            client.read_exact(&mut reply).await.unwrap();
+            assert_eq!(reply[3], SOCKS5_COMMAND_NOT_SUPPORTED);
+        });
+    }
+}
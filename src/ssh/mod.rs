@@ -1,8 +1,11 @@
-mod ansi;
+mod agent;
+pub(crate) mod ansi;
+mod attach;
+pub mod forward;
 pub mod sftp;
 pub mod user_config;
 
-use crate::config::{SshAuthMethod, SshProfile};
+use crate::config::{PortForward, SshAuthMethod, SshProfile};
 use crate::session::OutputEvent;
 use async_trait::async_trait;
 use iced::futures::channel::mpsc as futures_mpsc;
@@ -95,6 +98,11 @@ struct SshHandler {
     host: String,
     port: u16,
     host_key_tx: Option<tokio::sync::oneshot::Sender<HostKeyInfo>>,
+    /// Looks up where remote-forwarded connections go. None for the
+    /// connection test.
+    forwards: Option<forward::Forwards>,
+    /// Whether agent channels the server opens are answered.
+    forward_agent: bool,
 }
 
 #[async_trait]
@@ -123,6 +131,43 @@ impl client::Handler for SshHandler {
         }
         Ok(accepts)
     }
+
+    async fn server_channel_open_forwarded_tcpip(
+        &mut self,
+        channel: Channel<client::Msg>,
+        _connected_address: &str,
+        connected_port: u32,
+        _originator_address: &str,
+        _originator_port: u32,
+        _session: &mut client::Session,
+    ) -> Result<(), Self::Error> {
+        match self
+            .forwards
+            .as_ref()
+            .and_then(|forwards| forwards.remote_target(connected_port))
+        {
+            Some((host, port)) => {
+                tokio::spawn(forward::connect_remote(channel, host, port));
+            }
+            None => {
+                let _ = channel.close().await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn server_channel_open_agent_forward(
+        &mut self,
+        channel: Channel<client::Msg>,
+        _session: &mut client::Session,
+    ) -> Result<(), Self::Error> {
+        if self.forward_agent {
+            tokio::spawn(agent::serve_forwarded(channel));
+        } else {
+            let _ = channel.close().await;
+        }
+        Ok(())
+    }
 }
 
 // ── Sync Write → async tokio channel bridge ─────────────────────────
@@ -192,9 +237,25 @@ pub struct SshSessionHandle {
     pub writer: Arc<Mutex<Box<dyn Write + Send>>>,
     pub resize_tx: tokio_mpsc::UnboundedSender<(u16, u16)>,
     session_handle: SessionSlot,
+    forwards: forward::Forwards,
 }
 
 impl SshSessionHandle {
+    /// The profile's port forwards with their live status.
+    pub fn forwards(&self) -> Vec<(PortForward, forward::ForwardStatus)> {
+        self.forwards.list()
+    }
+
+    /// Switches forward `index` on or off without restarting the session.
+    pub fn toggle_forward(&self, index: usize) {
+        let session = self
+            .session_handle
+            .lock()
+            .ok()
+            .and_then(|guard| guard.clone());
+        self.forwards.toggle(index, session);
+    }
+
     /// Open a fresh SFTP subsystem channel on the active session.
     /// Errors out if the SSH session has not yet authenticated.
     pub async fn open_sftp(&self) -> Result<sftp::SftpHandle, String> {
@@ -215,6 +276,25 @@ impl SshSessionHandle {
     }
 }
 
+/// How an established session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionEnd {
+    /// The remote shell exited or closed the channel.
+    Exited,
+    /// The connection was lost, e.g. keep-alives went unanswered.
+    Dropped,
+    /// The tab was closed.
+    Closed,
+}
+
+/// Automatic reconnects give up after this many failed attempts in a row.
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+
+/// Backoff before automatic reconnect `attempt`: 1s, 2s, 4s, … capped at 30s.
+fn reconnect_delay(attempt: u32) -> std::time::Duration {
+    std::time::Duration::from_secs((1u64 << attempt.min(5)).min(30))
+}
+
 pub fn spawn_ssh_session(
     profile: SshProfile,
     tab_id: u64,
@@ -231,6 +311,8 @@ pub fn spawn_ssh_session(
     let writer_handle = Arc::clone(&writer);
     let session_handle: SessionSlot = Arc::new(Mutex::new(None));
     let slot_for_task = Arc::clone(&session_handle);
+    let forwards = forward::Forwards::new(profile.forwards.clone(), tab_id, output_tx.clone());
+    let forwards_for_task = forwards.clone();
 
     tokio::spawn(async move {
         let mut otx = output_tx;
@@ -238,6 +320,12 @@ pub fn spawn_ssh_session(
         let mut resize_rx = resize_rx;
         // Last remote working directory seen via OSC 7; restored on reconnect.
         let mut last_cwd: Option<String> = None;
+        // Daemon session on the server, for profiles that attach remotely.
+        let mut remote_id: Option<u64> = None;
+        // Latest window size, so a reconnect opens the PTY at the current size.
+        let mut size = (rows, cols);
+        // Failed automatic reconnects since the connection dropped.
+        let mut retry: Option<u32> = None;
 
         loop {
             let (attempt_write_tx, attempt_write_rx) = tokio_mpsc::unbounded_channel();
@@ -251,32 +339,46 @@ pub fn spawn_ssh_session(
             let result = ssh_task(
                 profile.clone(),
                 tab_id,
-                rows,
-                cols,
+                &mut size,
                 attempt_write_rx,
                 &mut resize_rx,
                 &mut otx,
                 &slot_for_task,
+                &forwards_for_task,
                 &mut last_cwd,
+                &mut remote_id,
             )
             .await;
 
             if let Ok(mut guard) = slot_for_task.lock() {
                 *guard = None;
             }
+            forwards_for_task.stop();
 
-            let msg = match &result {
-                Ok(()) => format!(
-                    "\r\n  {badge}  {}\r\n  {badge}  {}\r\n",
-                    ansi::yellow("Session disconnected."),
-                    ansi::cyan("Press any key to reconnect...")
-                ),
-                Err(e) => format!(
-                    "\r\n  {badge}  {}\r\n  {badge}  {}\r\n",
-                    ansi::red_bold(&e.to_string()),
-                    ansi::cyan("Press any key to reconnect...")
+            retry = match (&result, retry) {
+                (Ok(SessionEnd::Closed), _) => break,
+                (Ok(SessionEnd::Dropped), _) => Some(0),
+                (Err(_), Some(attempt)) => Some(attempt + 1),
+                _ => None,
+            }
+            .filter(|&attempt| profile.auto_reconnect && attempt < MAX_RECONNECT_ATTEMPTS);
+
+            let status = match &result {
+                Ok(SessionEnd::Dropped) => ansi::red_bold("Connection lost."),
+                Ok(_) => ansi::yellow("Session disconnected."),
+                Err(e) => ansi::red_bold(&e.to_string()),
+            };
+            let next = match retry {
+                Some(attempt) => format!(
+                    "Reconnecting in {}s... Press any key to reconnect now.",
+                    reconnect_delay(attempt).as_secs()
                 ),
+                None => "Press any key to reconnect...".to_string(),
             };
+            let msg = format!(
+                "\r\n  {badge}  {status}\r\n  {badge}  {}\r\n",
+                ansi::cyan(&next)
+            );
             let _ = otx.unbounded_send(OutputEvent::Data {
                 tab_id,
                 bytes: msg.into_bytes(),
@@ -287,18 +389,39 @@ pub fn spawn_ssh_session(
                 *guard = Box::new(SshWriter { tx: wait_tx });
             }
 
-            if wait_rx.recv().await.is_none() {
-                break;
+            let backoff = async {
+                match retry {
+                    Some(attempt) => tokio::time::sleep(reconnect_delay(attempt)).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::pin!(backoff);
+            loop {
+                tokio::select! {
+                    () = &mut backoff => break,
+                    key = wait_rx.recv() => match key {
+                        Some(_) => break,
+                        None => return,
+                    },
+                    resize = resize_rx.recv() => match resize {
+                        Some(next) => size = next,
+                        None => return,
+                    },
+                }
             }
         }
 
-        let _ = otx.unbounded_send(OutputEvent::Closed { tab_id });
+        let _ = otx.unbounded_send(OutputEvent::Closed {
+            tab_id,
+            exit_code: None,
+        });
     });
 
     SshSessionHandle {
         writer,
         resize_tx,
         session_handle,
+        forwards,
     }
 }
 
@@ -327,13 +450,15 @@ async fn test_ssh_connection_inner(
         profile.password = crate::keychain::get_password(&profile.host, &profile.user);
     }
 
-    let config = Arc::new(interactive_ssh_config());
+    let config = Arc::new(interactive_ssh_config(profile.keepalive_interval));
 
     let (fp_tx, fp_rx) = tokio::sync::oneshot::channel();
     let handler = SshHandler {
         host: profile.host.clone(),
         port: profile.port,
         host_key_tx: Some(fp_tx),
+        forwards: None,
+        forward_agent: false,
     };
 
     let connected = if let Some(ref proxy_command) = profile.proxy_command {
@@ -380,14 +505,15 @@ fn send_status(output_tx: &mut futures_mpsc::UnboundedSender<OutputEvent>, tab_i
 async fn ssh_task(
     mut profile: SshProfile,
     tab_id: u64,
-    rows: u16,
-    cols: u16,
+    size: &mut (u16, u16),
     mut write_rx: tokio_mpsc::UnboundedReceiver<Vec<u8>>,
     resize_rx: &mut tokio_mpsc::UnboundedReceiver<(u16, u16)>,
     output_tx: &mut futures_mpsc::UnboundedSender<OutputEvent>,
     session_slot: &SessionSlot,
+    forwards: &forward::Forwards,
     last_cwd: &mut Option<String>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    remote_id: &mut Option<u64>,
+) -> Result<SessionEnd, Box<dyn std::error::Error + Send + Sync>> {
     let badge = ssh_badge();
 
     // --- Status: Connecting ---
@@ -439,16 +565,28 @@ async fn ssh_task(
                 );
             }
         }
+        SshAuthMethod::Agent => {
+            send_status(
+                output_tx,
+                tab_id,
+                &format!(
+                    "         {}\r\n",
+                    ansi::cyan("Using keys from the SSH agent")
+                ),
+            );
+        }
     }
 
     // --- TCP + SSH handshake ---
-    let config = Arc::new(interactive_ssh_config());
+    let config = Arc::new(interactive_ssh_config(profile.keepalive_interval));
 
     let (fp_tx, fp_rx) = tokio::sync::oneshot::channel();
     let handler = SshHandler {
         host: profile.host.clone(),
         port: profile.port,
         host_key_tx: Some(fp_tx),
+        forwards: Some(forwards.clone()),
+        forward_agent: profile.forward_agent,
     };
 
     let connect_timeout = std::time::Duration::from_secs(15);
@@ -535,10 +673,12 @@ async fn ssh_task(
         return Err("Authentication failed".into());
     }
 
+    forwards.open_remote(&mut session).await;
     let session = Arc::new(session);
     if let Ok(mut guard) = session_slot.lock() {
         *guard = Some(Arc::clone(&session));
     }
+    forwards.start(&session);
 
     // --- Connected ---
     send_status(
@@ -550,8 +690,25 @@ async fn ssh_task(
         ),
     );
 
-    // --- Open channel with PTY + shell ---
+    // --- Open channel: remote attach, or PTY + shell ---
     let mut channel = session.channel_open_session().await?;
+    if profile.forward_agent {
+        channel.agent_forward(false).await?;
+    }
+    if profile.remote_attach {
+        return attach::run(
+            &mut channel,
+            tab_id,
+            size,
+            &mut write_rx,
+            resize_rx,
+            output_tx,
+            remote_id,
+        )
+        .await;
+    }
+
+    let (rows, cols) = *size;
     channel
         .request_pty(false, "xterm-256color", cols as u32, rows as u32, 0, 0, &[])
         .await?;
@@ -565,6 +722,7 @@ async fn ssh_task(
     }
 
     // --- I/O bridge ---
+    // From here on a failed write means the connection is gone.
     loop {
         tokio::select! {
             msg = channel.wait() => {
@@ -580,27 +738,34 @@ async fn ssh_task(
                     }
                     Some(ChannelMsg::Eof)
                     | Some(ChannelMsg::Close)
-                    | Some(ChannelMsg::ExitStatus { .. })
-                    | None => break,
+                    | Some(ChannelMsg::ExitStatus { .. }) => return Ok(SessionEnd::Exited),
+                    None => return Ok(SessionEnd::Dropped),
                     _ => {}
                 }
             }
             bytes = write_rx.recv() => {
                 match bytes {
-                    Some(bytes) => channel.data(&bytes[..]).await?,
-                    None => break,
+                    Some(bytes) => {
+                        if channel.data(&bytes[..]).await.is_err() {
+                            return Ok(SessionEnd::Dropped);
+                        }
+                    }
+                    None => return Ok(SessionEnd::Closed),
                 }
             }
             resize = resize_rx.recv() => {
                 match resize {
-                    Some((r, c)) => channel.window_change(c as u32, r as u32, 0, 0).await?,
-                    None => break,
+                    Some((r, c)) => {
+                        *size = (r, c);
+                        if channel.window_change(c as u32, r as u32, 0, 0).await.is_err() {
+                            return Ok(SessionEnd::Dropped);
+                        }
+                    }
+                    None => return Ok(SessionEnd::Closed),
                 }
             }
         }
     }
-
-    Ok(())
 }
 
 fn parse_osc7_cwd(bytes: &[u8]) -> Option<String> {
@@ -616,7 +781,9 @@ fn parse_osc7_cwd(bytes: &[u8]) -> Option<String> {
             break; // incomplete; wait for more data
         };
 
-        if let Some(path) = decode_osc7_payload(&bytes[payload_start..payload_start + term]) {
+        if let Some(path) =
+            crate::terminal::path::decode_osc7(&bytes[payload_start..payload_start + term])
+        {
             latest = Some(path);
         }
         base = payload_start + term + 1;
@@ -639,44 +806,8 @@ fn find_osc_terminator(bytes: &[u8]) -> Option<usize> {
         .or_else(|| bytes.windows(2).position(|w| w == [0x1b, b'\\']))
 }
 
-/// Decodes an OSC 7 payload (`file://host/path`) into the absolute path.
-fn decode_osc7_payload(payload: &[u8]) -> Option<String> {
-    let text = std::str::from_utf8(payload).ok()?;
-    let rest = text.strip_prefix("file://")?;
-    let slash = rest.find('/')?;
-    Some(percent_decode(&rest[slash..]))
-}
-
-fn percent_decode(s: &str) -> String {
-    let bytes = s.as_bytes();
-    let mut out = Vec::with_capacity(bytes.len());
-    let mut i = 0;
-    while i < bytes.len() {
-        if bytes[i] == b'%'
-            && i + 2 < bytes.len()
-            && let (Some(hi), Some(lo)) = (hex_val(bytes[i + 1]), hex_val(bytes[i + 2]))
-        {
-            out.push(hi * 16 + lo);
-            i += 3;
-        } else {
-            out.push(bytes[i]);
-            i += 1;
-        }
-    }
-    String::from_utf8_lossy(&out).into_owned()
-}
-
-fn hex_val(b: u8) -> Option<u8> {
-    match b {
-        b'0'..=b'9' => Some(b - b'0'),
-        b'a'..=b'f' => Some(b - b'a' + 10),
-        b'A'..=b'F' => Some(b - b'A' + 10),
-        _ => None,
-    }
-}
-
 /// Wraps a string in single quotes for safe use as one shell word.
-fn shell_single_quote(s: &str) -> String {
+pub(crate) fn shell_single_quote(s: &str) -> String {
     let mut out = String::with_capacity(s.len() + 2);
     out.push('\'');
     for ch in s.chars() {
@@ -700,10 +831,14 @@ fn ssh_user(configured_user: &str) -> String {
     }
 }
 
-fn interactive_ssh_config() -> client::Config {
+/// Client settings for long-lived sessions: never time out while idle, but
+/// probe the server every `keepalive_interval` seconds (0 disables) and treat
+/// three unanswered probes as a dropped connection.
+fn interactive_ssh_config(keepalive_interval: u64) -> client::Config {
     client::Config {
         inactivity_timeout: None,
-        keepalive_interval: Some(std::time::Duration::from_secs(15)),
+        keepalive_interval: (keepalive_interval > 0)
+            .then(|| std::time::Duration::from_secs(keepalive_interval)),
         keepalive_max: 3,
         ..<_>::default()
     }
@@ -741,6 +876,7 @@ async fn authenticate_session<H: client::Handler>(
             };
             session.authenticate_password(user, password).await?
         }
+        SshAuthMethod::Agent => agent::authenticate(session, user).await?,
     })
 }
 
@@ -943,7 +1079,7 @@ mod tests {
 
     #[test]
     fn interactive_ssh_config_does_not_close_idle_sessions() {
-        let config = interactive_ssh_config();
+        let config = interactive_ssh_config(15);
 
         assert_eq!(config.inactivity_timeout, None);
         assert_eq!(
@@ -951,6 +1087,13 @@ mod tests {
             Some(std::time::Duration::from_secs(15))
         );
         assert_eq!(config.keepalive_max, 3);
+        assert_eq!(interactive_ssh_config(0).keepalive_interval, None);
+    }
+
+    #[test]
+    fn reconnect_backoff_doubles_up_to_thirty_seconds() {
+        let delays: Vec<u64> = (0..8).map(|n| reconnect_delay(n).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 30, 30, 30]);
     }
 
     #[test]
@@ -4,7 +4,7 @@
 //! contribute to matching literal hosts, but never produce a profile of their
 //! own.
 
-use crate::config::{SshAuthMethod, SshProfile};
+use crate::config::{DEFAULT_SSH_KEEPALIVE_INTERVAL, SshAuthMethod, SshProfile};
 use ssh2_config::{ParseRule, SshConfig};
 use std::fs::File;
 use std::io::BufReader;
@@ -57,6 +57,15 @@ pub fn load() -> Vec<SshProfile> {
                 identity_file,
                 password: None,
                 proxy_command: None,
+                keepalive_interval: params
+                    .server_alive_interval
+                    .map_or(DEFAULT_SSH_KEEPALIVE_INTERVAL, |interval| {
+                        interval.as_secs()
+                    }),
+                auto_reconnect: false,
+                remote_attach: false,
+                forward_agent: false,
+                forwards: Vec::new(),
             });
         }
     }
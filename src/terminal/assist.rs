@@ -0,0 +1,169 @@
+//! The Explain and Fix hook: selected text goes to a command or HTTP
+//! endpoint the user configured, and its reply is shown beside the
+//! terminal. Nothing is sent anywhere unless one is set.
+//!
+//! A command, split into words as a shell would with its quotes, gets the
+//! text on standard input, `{action}` in its arguments replaced by
+//! `explain` or `fix`, and answers on standard output. An
+//! `http://` or `https://` address is posted `{"action": …, "text": …}` as
+//! JSON through `curl`, and the response body is shown as it is.
+
+use super::json_string;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssistAction {
+    Explain,
+    Fix,
+}
+
+impl AssistAction {
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Explain => "explain",
+            Self::Fix => "fix",
+        }
+    }
+}
+
+/// Runs the hook and returns its reply, or why it failed.
+pub async fn run(target: String, action: AssistAction, text: String) -> Result<String, String> {
+    let (argv, input) = request(&target, action, text)?;
+    let env = [("RABBITTY_ASSIST_ACTION", action.key().to_string())];
+    crate::platform::pipe_command(&argv, &env, input).await
+}
+
+/// The argv to run and what to write to its standard input.
+fn request(
+    target: &str,
+    action: AssistAction,
+    text: String,
+) -> Result<(Vec<String>, String), String> {
+    let target = target.trim();
+    if target.starts_with("http://") || target.starts_with("https://") {
+        let argv = [
+            "curl",
+            "--silent",
+            "--show-error",
+            "--fail-with-body",
+            "--header",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+            target,
+        ];
+        let body = format!(
+            "{{\"action\":{},\"text\":{}}}",
+            json_string(action.key()),
+            json_string(&text)
+        );
+        return Ok((argv.iter().map(|arg| arg.to_string()).collect(), body));
+    }
+    let argv = command_words(target)
+        .ok_or_else(|| format!("Unbalanced quotes in {target}"))?
+        .into_iter()
+        .map(|arg| arg.replace("{action}", action.key()))
+        .collect();
+    Ok((argv, text))
+}
+
+/// Splits a command line into words the way a POSIX shell would: single
+/// quotes keep everything, double quotes allow `\"` and `\\`, and a
+/// backslash outside quotes escapes the next character. `None` when a
+/// quote is left open.
+fn command_words(line: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ch if ch.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        ch => word.push(ch),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            ch @ ('"' | '\\' | '$' | '`') => word.push(ch),
+                            ch => {
+                                word.push('\\');
+                                word.push(ch);
+                            }
+                        },
+                        ch => word.push(ch),
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    word.get_or_insert_with(String::new).push(next);
+                }
+            }
+            ch => word.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    words.extend(word);
+    Some(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_get_the_action_and_the_text_on_stdin() {
+        let (argv, input) = request(
+            " llm-helper --mode {action} ",
+            AssistAction::Fix,
+            "ls -z".into(),
+        )
+        .unwrap();
+        assert_eq!(argv, ["llm-helper", "--mode", "fix"]);
+        assert_eq!(input, "ls -z");
+    }
+
+    #[test]
+    fn commands_keep_quoted_arguments_whole() {
+        let (argv, _) = request(
+            r#"'/opt/My Tools/helper' --prompt "say \"{action}\"" a\ b ''"#,
+            AssistAction::Explain,
+            String::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            argv,
+            [
+                "/opt/My Tools/helper",
+                "--prompt",
+                "say \"explain\"",
+                "a b",
+                ""
+            ]
+        );
+        assert!(request("helper 'open", AssistAction::Fix, String::new()).is_err());
+    }
+
+    #[test]
+    fn endpoints_are_posted_json_through_curl() {
+        let (argv, body) = request(
+            "https://localhost:8080/explain",
+            AssistAction::Explain,
+            "say \"hi\"\n\u{1b}".into(),
+        )
+        .unwrap();
+        assert_eq!(argv.first().map(String::as_str), Some("curl"));
+        assert_eq!(
+            argv.last().map(String::as_str),
+            Some("https://localhost:8080/explain")
+        );
+        assert_eq!(body, r#"{"action":"explain","text":"say \"hi\"\n\u001b"}"#);
+    }
+}
@@ -0,0 +1,202 @@
+//! Golden tests for the cursor and scrolling behavior full-screen programs
+//! depend on: DECSTBM scroll regions, origin mode (DECOM), autowrap, tab
+//! stops, cursor save and restore (DECSC/DECRC) and the DEC special graphics
+//! character set. Each test feeds a sequence through [`TerminalEngine`] and
+//! compares the rendered screen with what a VT220 (and xterm) shows.
+
+use super::{TerminalEngine, TerminalSize};
+
+fn engine(columns: usize, lines: usize) -> TerminalEngine {
+    TerminalEngine::headless(TerminalSize::new(columns, lines))
+}
+
+fn screen(engine: &TerminalEngine) -> Vec<String> {
+    engine.screen_text()
+}
+
+/// Five numbered lines, then a scroll region over lines 2 to 4.
+fn with_region() -> TerminalEngine {
+    let mut engine = engine(6, 5);
+    engine.feed_bytes(b"1\r\n2\r\n3\r\n4\r\n5\x1b[2;4r");
+    engine
+}
+
+#[test]
+fn setting_a_region_homes_the_cursor() {
+    let engine = with_region();
+    assert_eq!(engine.cursor_position(), (0, 0));
+}
+
+#[test]
+fn linefeed_at_the_bottom_margin_scrolls_only_the_region() {
+    let mut engine = with_region();
+    engine.feed_bytes(b"\x1b[4;1H\nx");
+    assert_eq!(screen(&engine), ["1", "3", "4", "x", "5"]);
+    assert_eq!(engine.cursor_position(), (1, 3));
+    // Lines leaving a region that does not start at the top are gone, not
+    // history.
+    assert_eq!(engine.scroll_position().1, 0);
+}
+
+#[test]
+fn linefeed_below_the_region_stops_at_the_last_line() {
+    let mut engine = with_region();
+    engine.feed_bytes(b"\x1b[5;1H\n\nz");
+    assert_eq!(screen(&engine), ["1", "2", "3", "4", "z"]);
+}
+
+#[test]
+fn reverse_index_at_the_top_margin_scrolls_the_region_down() {
+    let mut engine = with_region();
+    engine.feed_bytes(b"\x1b[2;1H\x1bMy");
+    assert_eq!(screen(&engine), ["1", "y", "2", "3", "5"]);
+}
+
+#[test]
+fn resetting_the_region_scrolls_the_whole_screen_into_history() {
+    let mut engine = with_region();
+    engine.feed_bytes(b"\x1b[r\x1b[5;1H\n6");
+    assert_eq!(screen(&engine), ["2", "3", "4", "5", "6"]);
+    assert_eq!(engine.scroll_position().1, 1);
+}
+
+#[test]
+fn scroll_up_and_down_stay_inside_the_region() {
+    let mut engine = with_region();
+    engine.feed_bytes(b"\x1b[S");
+    assert_eq!(screen(&engine), ["1", "3", "4", "", "5"]);
+    engine.feed_bytes(b"\x1b[2T");
+    assert_eq!(screen(&engine), ["1", "", "", "3", "5"]);
+}
+
+#[test]
+fn inserted_and_deleted_lines_stay_inside_the_region() {
+    let mut engine = with_region();
+    engine.feed_bytes(b"\x1b[2;1H\x1b[L");
+    assert_eq!(screen(&engine), ["1", "", "2", "3", "5"]);
+    engine.feed_bytes(b"\x1b[M");
+    assert_eq!(screen(&engine), ["1", "2", "3", "", "5"]);
+    // Outside the region IL and DL do nothing.
+    engine.feed_bytes(b"\x1b[5;1H\x1b[L\x1b[1;1H\x1b[M");
+    assert_eq!(screen(&engine), ["1", "2", "3", "", "5"]);
+}
+
+#[test]
+fn origin_mode_addresses_rows_from_the_top_margin() {
+    let mut engine = engine(6, 5);
+    engine.feed_bytes(b"\x1b[2;4r\x1b[?6h");
+    assert_eq!(engine.cursor_position(), (0, 1));
+
+    engine.feed_bytes(b"A\x1b[9;3HB");
+    // Row addresses past the bottom margin are clamped to it.
+    assert_eq!(engine.cursor_position(), (3, 3));
+
+    engine.feed_bytes(b"\x1b[?6l\x1b[1;1HC");
+    assert_eq!(screen(&engine), ["C", "A", "", "  B", ""]);
+}
+
+#[test]
+fn origin_mode_keeps_linefeeds_inside_the_region() {
+    let mut engine = engine(6, 5);
+    engine.feed_bytes(b"top\x1b[5;1Hend\x1b[2;4r\x1b[?6h");
+    engine.feed_bytes(b"a\r\nb\r\nc\r\nd");
+    assert_eq!(screen(&engine), ["top", "b", "c", "d", "end"]);
+}
+
+#[test]
+fn the_last_column_wraps_only_when_the_next_character_arrives() {
+    let mut engine = engine(4, 3);
+    engine.feed_bytes(b"abcd");
+    assert_eq!(engine.cursor_position(), (3, 0));
+    engine.feed_bytes(b"\rX");
+    assert_eq!(screen(&engine), ["Xbcd", "", ""]);
+
+    engine.feed_bytes(b"\x1b[1;4Hde");
+    assert_eq!(screen(&engine), ["Xbcd", "e", ""]);
+}
+
+#[test]
+fn autowrap_off_overwrites_the_last_column() {
+    let mut engine = engine(4, 3);
+    engine.feed_bytes(b"\x1b[?7labcdef");
+    assert_eq!(screen(&engine), ["abcf", "", ""]);
+
+    engine.feed_bytes(b"\x1b[?7h\rabcdef");
+    assert_eq!(screen(&engine), ["abcd", "ef", ""]);
+}
+
+#[test]
+fn default_tab_stops_are_every_eight_columns() {
+    let mut engine = engine(20, 2);
+    engine.feed_bytes(b"\tA\tB\t\tC");
+    assert_eq!(
+        screen(&engine),
+        [format!("{:8}A{:7}B  C", "", ""), String::new()]
+    );
+}
+
+#[test]
+fn tab_stops_can_be_cleared_and_set() {
+    let mut engine = engine(20, 2);
+    engine.feed_bytes(b"\x1b[3g\x1b[1;5H\x1bH\r\tX\tY");
+    assert_eq!(
+        screen(&engine),
+        [format!("{:4}X{:14}Y", "", ""), String::new()]
+    );
+
+    // With its only stop cleared, a tab runs to the last column.
+    engine.feed_bytes(b"\x1b[2;5H\x1b[g\r\t+");
+    assert_eq!(screen(&engine)[1], format!("{:19}+", ""));
+}
+
+#[test]
+fn backward_tab_returns_to_the_previous_stop() {
+    let mut engine = engine(20, 2);
+    engine.feed_bytes(b"\x1b[1;13H\x1b[Zb\x1b[2Zc");
+    assert_eq!(screen(&engine)[0], format!("c{:7}b", ""));
+    engine.feed_bytes(b"\x1b[2I!");
+    assert_eq!(screen(&engine)[0], format!("c{0:7}b{0:7}!", ""));
+}
+
+#[test]
+fn dec_special_graphics_draws_lines_until_ascii_is_designated_again() {
+    let mut engine = engine(10, 3);
+    engine.feed_bytes(b"\x1b(0lqk\r\nx~x\r\nmqj\x1b(B lqk");
+    assert_eq!(screen(&engine), ["┌─┐", "│·│", "└─┘ lqk"]);
+}
+
+#[test]
+fn shift_out_switches_to_the_g1_line_drawing_set() {
+    let mut engine = engine(10, 1);
+    engine.feed_bytes(b"\x1b)0a\x0etuvwn\x0fb");
+    assert_eq!(screen(&engine), ["a├┤┴┬┼b"]);
+}
+
+#[test]
+fn restoring_the_cursor_returns_to_the_saved_position() {
+    let mut engine = engine(6, 5);
+    engine.feed_bytes(b"\x1b[2;3H\x1b7\x1b[5;1Hx\x1b8y");
+    assert_eq!(screen(&engine), ["", "  y", "", "", "x"]);
+    assert_eq!(engine.cursor_position(), (3, 1));
+}
+
+#[test]
+fn the_ansi_save_and_restore_sequences_do_the_same() {
+    let mut engine = engine(6, 2);
+    engine.feed_bytes(b"\x1b[2;2H\x1b[s\x1b[Hab\x1b[uc");
+    assert_eq!(screen(&engine), ["ab", " c"]);
+}
+
+#[test]
+fn restoring_the_cursor_brings_back_its_character_set() {
+    let mut engine = engine(6, 1);
+    engine.feed_bytes(b"\x1b(0\x1b7\x1b(B\x1b[1;3Hq\x1b8q");
+    assert_eq!(screen(&engine), ["─ q"]);
+}
+
+#[test]
+fn restoring_without_a_save_homes_the_cursor() {
+    let mut engine = engine(6, 3);
+    engine.feed_bytes(b"\x1b[3;4H\x1b8");
+    assert_eq!(engine.cursor_position(), (0, 0));
+}
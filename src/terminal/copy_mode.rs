@@ -0,0 +1,184 @@
+//! Keyboard-driven selection, vi-style: a cursor that moves over the screen
+//! and scrollback, and a selection that follows it once visual mode is on.
+
+use super::{Selection, SelectionPoint, TerminalSize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+    LineStart,
+    LineEnd,
+    PageUp,
+    PageDown,
+    /// The oldest scrollback line.
+    Top,
+    /// The last screen line.
+    Bottom,
+}
+
+/// How the selection grows from the anchor: `v` selects characters, `V`
+/// whole lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisualMode {
+    Char,
+    Line,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyMode {
+    /// Grid line of the cursor: 0 is the top of the screen, negative lines
+    /// are scrollback.
+    pub line: i64,
+    pub column: usize,
+    visual: Option<(VisualMode, i64, usize)>,
+}
+
+impl CopyMode {
+    pub fn new(line: i64, column: usize) -> Self {
+        Self {
+            line,
+            column,
+            visual: None,
+        }
+    }
+
+    /// Moves the cursor, staying within `history` lines of scrollback and
+    /// the screen of `size`.
+    pub fn apply(&mut self, motion: CopyMotion, history: usize, size: TerminalSize) {
+        let top = -(history as i64);
+        let bottom = size.lines as i64 - 1;
+        let last_column = size.columns.saturating_sub(1);
+        let page = size.lines.max(1) as i64;
+        match motion {
+            CopyMotion::Left => self.column = self.column.saturating_sub(1),
+            CopyMotion::Right => self.column = (self.column + 1).min(last_column),
+            CopyMotion::Up => self.line -= 1,
+            CopyMotion::Down => self.line += 1,
+            CopyMotion::LineStart => self.column = 0,
+            CopyMotion::LineEnd => self.column = last_column,
+            CopyMotion::PageUp => self.line -= page,
+            CopyMotion::PageDown => self.line += page,
+            CopyMotion::Top => self.line = top,
+            CopyMotion::Bottom => self.line = bottom,
+        }
+        self.line = self.line.clamp(top, bottom.max(top));
+        self.column = self.column.min(last_column);
+    }
+
+    /// Starts `mode` at the cursor, switches an active selection to it, or
+    /// ends the selection when `mode` is already on.
+    pub fn toggle_visual(&mut self, mode: VisualMode) {
+        self.visual = match self.visual {
+            Some((current, ..)) if current == mode => None,
+            Some((_, line, column)) => Some((mode, line, column)),
+            None => Some((mode, self.line, self.column)),
+        };
+    }
+
+    /// The span between the anchor and the cursor, in the frame of
+    /// `display_offset`. `None` outside visual mode.
+    pub fn selection(&self, display_offset: usize, columns: usize) -> Option<Selection> {
+        let (mode, line, column) = self.visual?;
+        let offset = display_offset as i64;
+        let (mut start, mut end) = (
+            SelectionPoint {
+                row: line + offset,
+                col: column,
+            },
+            SelectionPoint {
+                row: self.line + offset,
+                col: self.column,
+            },
+        );
+        if mode == VisualMode::Line {
+            if start.row > end.row {
+                std::mem::swap(&mut start, &mut end);
+            }
+            start.col = 0;
+            end.col = columns.saturating_sub(1);
+        }
+        Some(Selection {
+            start,
+            end,
+            anchor_offset: display_offset,
+        })
+    }
+
+    /// How far to scroll (positive is back into history) so the cursor is
+    /// on screen at `display_offset`.
+    pub fn scroll_to_cursor(&self, display_offset: usize, lines: usize) -> i32 {
+        let row = self.line + display_offset as i64;
+        if row < 0 {
+            -row as i32
+        } else if row >= lines as i64 {
+            (lines as i64 - 1 - row) as i32
+        } else {
+            0
+        }
+    }
+
+    /// The cursor's `(column, row)` on screen, if it is in view.
+    pub fn cursor_cell(&self, display_offset: usize, lines: usize) -> Option<(usize, usize)> {
+        let row = self.line + display_offset as i64;
+        (0..lines as i64)
+            .contains(&row)
+            .then_some((self.column, row as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: TerminalSize = TerminalSize::new(10, 4);
+
+    #[test]
+    fn motions_stay_within_the_scrollback_and_screen() {
+        let mut mode = CopyMode::new(3, 2);
+        mode.apply(CopyMotion::Down, 5, SIZE);
+        assert_eq!((mode.line, mode.column), (3, 2));
+        mode.apply(CopyMotion::PageUp, 5, SIZE);
+        mode.apply(CopyMotion::PageUp, 5, SIZE);
+        assert_eq!(mode.line, -5);
+        mode.apply(CopyMotion::LineEnd, 5, SIZE);
+        mode.apply(CopyMotion::Right, 5, SIZE);
+        assert_eq!(mode.column, 9);
+        mode.apply(CopyMotion::Bottom, 5, SIZE);
+        mode.apply(CopyMotion::LineStart, 5, SIZE);
+        assert_eq!((mode.line, mode.column), (3, 0));
+    }
+
+    #[test]
+    fn line_mode_selects_whole_lines_in_either_direction() {
+        let mut mode = CopyMode::new(1, 4);
+        assert_eq!(mode.selection(0, 10), None);
+        mode.toggle_visual(VisualMode::Line);
+        mode.apply(CopyMotion::Up, 5, SIZE);
+        mode.apply(CopyMotion::Up, 5, SIZE);
+
+        let selection = mode.selection(2, 10).unwrap();
+        assert_eq!(selection.start, SelectionPoint { row: 1, col: 0 });
+        assert_eq!(selection.end, SelectionPoint { row: 3, col: 9 });
+
+        mode.toggle_visual(VisualMode::Char);
+        let selection = mode.selection(0, 10).unwrap();
+        assert_eq!(selection.start, SelectionPoint { row: 1, col: 4 });
+        assert_eq!(selection.end, SelectionPoint { row: -1, col: 4 });
+
+        mode.toggle_visual(VisualMode::Char);
+        assert_eq!(mode.selection(0, 10), None);
+    }
+
+    #[test]
+    fn scrolls_just_enough_to_show_the_cursor() {
+        let mode = CopyMode::new(-3, 0);
+        assert_eq!(mode.scroll_to_cursor(0, 4), 3);
+        assert_eq!(mode.scroll_to_cursor(3, 4), 0);
+        assert_eq!(mode.cursor_cell(3, 4), Some((0, 0)));
+        assert_eq!(CopyMode::new(2, 0).scroll_to_cursor(5, 4), -3);
+        assert_eq!(CopyMode::new(2, 0).cursor_cell(5, 4), None);
+    }
+}
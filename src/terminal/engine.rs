@@ -1,17 +1,48 @@
+use super::graphics::{
+    self, GraphicsParser, IMAGE_URI_SCHEME, ImagePlacement, ImageRequest, ImageTile, Segment,
+};
+use super::kitty::{DeleteTarget, KittyAction, KittyCommand, KittyGraphics};
+use super::marks::{Mark, MarkKind, Marks, PromptEvent};
+use super::passthrough::Passthrough;
+use super::queries::{Piece, QueryParser};
+use super::redact::{self, Redactor};
 use super::theme::{enforce_min_contrast, resolve_rgb, rgb_to_rgba};
-use super::{CellVisual, TerminalSize, TerminalTheme};
+use super::trigger::Triggers;
+use super::{
+    CellVisual, Selection, SelectionPoint, TerminalSize, TerminalTheme, repaint, semantic, snapshot,
+};
+use crate::config::{DEFAULT_TERMINAL_SCROLLBACK, Trigger, TriggerAction};
 use alacritty_terminal::event::{Event, EventListener, WindowSize};
 use alacritty_terminal::grid::Dimensions;
 use alacritty_terminal::grid::Scroll;
-use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::{Flags, Hyperlink};
 use alacritty_terminal::term::{
     Config as TermConfig, RenderableContent, Term, TermMode, point_to_viewport,
 };
-use alacritty_terminal::vte::ansi::{CursorShape, Processor, Rgb};
+use alacritty_terminal::vte::ansi::{CursorShape, NamedColor, Processor, Rgb};
+use std::borrow::Cow;
 use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::Write;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Decoded images kept per terminal before ones that scrolled out of the
+/// scrollback are dropped.
+const IMAGE_MEMORY_BUDGET: usize = 256 * 1024 * 1024;
+/// Used for image sizing until the pane reports its real cell size.
+const DEFAULT_CELL_PIXELS: [f32; 2] = [8.0, 16.0];
+/// History the grid may hold beyond the configured scrollback. Output is
+/// trimmed back once per feed, or sooner if it fills the slack, which is how
+/// the engine counts the lines that fall off the top.
+const HISTORY_SLACK: usize = 1024;
+/// Command lines remembered per session for re-running and autocomplete.
+const MAX_COMMANDS: usize = 1000;
+
+/// Formats the reply to an OSC 4/10/11/12 color query.
+type ColorFormatter = Arc<dyn Fn(Rgb) -> String + Sync + Send>;
 
 pub struct TerminalEngine {
     term: Term<PtyEventProxy>,
@@ -19,10 +50,59 @@ pub struct TerminalEngine {
     size: TerminalSize,
     theme: TerminalTheme,
     cells_cache: RefCell<Arc<Vec<CellVisual>>>,
+    images_cache: RefCell<Arc<Vec<Arc<ImagePlacement>>>>,
     cache_dirty: Cell<bool>,
     cache_size: Cell<TerminalSize>,
     title: Arc<Mutex<Option<String>>>,
     bell_pending: Arc<AtomicBool>,
+    /// Color queries seen by the VT parser, answered once it returns.
+    color_requests: Arc<Mutex<Vec<(usize, ColorFormatter)>>>,
+    /// Text area size for `CSI 14 t`, kept in step with resizes.
+    window: Arc<Mutex<WindowSize>>,
+    /// Lines of output that arrived while the viewport was scrolled back.
+    unseen_lines: usize,
+    /// Unwraps tmux passthrough when set.
+    passthrough: Option<Passthrough>,
+    graphics: GraphicsParser,
+    queries: QueryParser,
+    /// Sent in reply to ENQ; empty sends nothing.
+    answerback: String,
+    placements: HashMap<u64, Arc<ImagePlacement>>,
+    cell_pixels: [f32; 2],
+    kitty: KittyGraphics,
+    /// For replies to kitty graphics commands.
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    triggers: Triggers,
+    /// Secret patterns masked in the rendered cells; empty outside privacy
+    /// mode.
+    redactor: RefCell<Redactor>,
+    /// Matched text of `notify` triggers, waiting for the UI.
+    notifications: Vec<String>,
+    scrollback: usize,
+    /// Lines trimmed from the top of the primary screen's history so far.
+    /// Adding it to a line's index in the grid gives a number that stays put
+    /// while the scrollback is trimmed.
+    trimmed: u64,
+    marks: Marks,
+    /// When output first reached each line, by line number, ascending.
+    line_times: VecDeque<(u64, SystemTime)>,
+    /// Whether the shell has marked a prompt through OSC 133 yet.
+    prompts_reported: bool,
+    /// Where the command line being typed began (`OSC 133 ; B`).
+    command_input: Option<(u64, usize)>,
+    /// Command lines the shell ran, as typed, oldest first.
+    commands: Vec<String>,
+    /// Where the running command's output began (`OSC 133 ; C`), as a line
+    /// number and column.
+    output_start: Option<(u64, usize)>,
+    /// When the running command started.
+    command_started: Option<Instant>,
+    /// How long each command that finished since the last check ran.
+    finished_commands: Vec<Duration>,
+    /// Output of the last finished command, first and last cell inclusive.
+    last_output: Option<[(u64, usize); 2]>,
+    /// Last working directory the shell reported through OSC 7.
+    cwd: Option<String>,
 }
 
 impl TerminalEngine {
@@ -33,19 +113,22 @@ impl TerminalEngine {
         theme: TerminalTheme,
     ) -> Self {
         let config = TermConfig {
-            scrolling_history: scrollback,
+            scrolling_history: scrollback + HISTORY_SLACK,
             ..Default::default()
         };
         let title: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
         let bell_pending: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let color_requests = Arc::new(Mutex::new(Vec::new()));
+        let window = Arc::new(Mutex::new(window_size(size, DEFAULT_CELL_PIXELS)));
         let term = Term::new(
             config,
             &size,
             PtyEventProxy {
                 writer: Arc::clone(&writer),
-                size,
+                window: Arc::clone(&window),
                 title: Arc::clone(&title),
                 bell_pending: Arc::clone(&bell_pending),
+                color_requests: Arc::clone(&color_requests),
             },
         );
 
@@ -55,10 +138,37 @@ impl TerminalEngine {
             size,
             theme,
             cells_cache: RefCell::new(Arc::new(Vec::new())),
+            images_cache: RefCell::new(Arc::new(Vec::new())),
             cache_dirty: Cell::new(true),
             cache_size: Cell::new(size),
             title,
             bell_pending,
+            color_requests,
+            window,
+            unseen_lines: 0,
+            passthrough: None,
+            graphics: GraphicsParser::new(),
+            queries: QueryParser::new(),
+            answerback: String::new(),
+            placements: HashMap::new(),
+            cell_pixels: DEFAULT_CELL_PIXELS,
+            kitty: KittyGraphics::new(),
+            writer,
+            triggers: Triggers::default(),
+            redactor: RefCell::new(Redactor::default()),
+            notifications: Vec::new(),
+            scrollback,
+            trimmed: 0,
+            marks: Marks::default(),
+            line_times: VecDeque::new(),
+            prompts_reported: false,
+            command_input: None,
+            commands: Vec::new(),
+            output_start: None,
+            command_started: None,
+            finished_commands: Vec::new(),
+            last_output: None,
+            cwd: None,
         }
     }
 
@@ -75,18 +185,735 @@ impl TerminalEngine {
         self.bell_pending.swap(false, Ordering::Relaxed)
     }
 
+    /// Feeds PTY output. A viewport at the bottom follows the new output; one
+    /// scrolled back into history stays on the lines it was showing, and the
+    /// lines pushed past it are counted in [`Self::unseen_lines`].
     pub fn feed_bytes(&mut self, bytes: &[u8]) {
+        let (offset_before, history_before) = self.scroll_position();
+        let bytes = match &mut self.passthrough {
+            Some(passthrough) => passthrough.unwrap(bytes),
+            None => Cow::Borrowed(bytes),
+        };
+        for segment in self.graphics.split(&bytes) {
+            match segment {
+                Segment::Text(text) => {
+                    for piece in self.queries.split(&text) {
+                        match piece {
+                            Piece::Text(text) => self.advance_text(&text),
+                            Piece::Query(query) => {
+                                if let Some(reply) = query.reply(&self.answerback) {
+                                    self.write_reply(&reply);
+                                }
+                            }
+                        }
+                    }
+                }
+                Segment::Image(request) => self.place_image(request),
+                Segment::Kitty(command) => self.kitty_command(command),
+                Segment::Prompt(event) => self.prompt_event(event),
+                Segment::Cwd(path) => self.cwd = Some(path),
+            }
+        }
+        self.settle_history();
+        self.cache_dirty.set(true);
+
+        if offset_before == 0 {
+            if self.term.grid().display_offset() != 0 {
+                self.term.scroll_display(Scroll::Bottom);
+            }
+            return;
+        }
+        let (offset_after, history_after) = self.scroll_position();
+        if offset_after == 0 {
+            // A reset or alternate-screen switch dropped the scrollback.
+            self.unseen_lines = 0;
+            return;
+        }
+        let added = offset_after
+            .saturating_sub(offset_before)
+            .max(history_after.saturating_sub(history_before));
+        self.unseen_lines = self.unseen_lines.saturating_add(added);
+    }
+
+    pub fn set_triggers(&mut self, triggers: &[Trigger]) {
+        self.triggers = Triggers::new(triggers);
+    }
+
+    /// Masks text matching `patterns` in the rendered cells; an empty list
+    /// turns masking off.
+    pub fn set_redaction(&mut self, patterns: &[String]) {
+        *self.redactor.get_mut() = Redactor::new(patterns);
+        self.cache_dirty.set(true);
+    }
+
+    pub fn set_answerback(&mut self, answerback: &str) {
+        self.answerback = answerback.to_string();
+    }
+
+    /// Whether sequences wrapped in tmux passthrough are unwrapped and
+    /// handled like the rest of the output.
+    pub fn set_tmux_passthrough(&mut self, enabled: bool) {
+        if enabled != self.passthrough.is_some() {
+            self.passthrough = enabled.then(Passthrough::new);
+        }
+    }
+
+    /// Reports the colors programs asked for: what they set through OSC 4,
+    /// 10, 11 or 12, or else the configured theme's.
+    fn answer_color_requests(&mut self) {
+        let requests = match self.color_requests.lock() {
+            Ok(mut requests) if !requests.is_empty() => std::mem::take(&mut *requests),
+            _ => return,
+        };
+        for (index, format) in requests {
+            let rgb = self.term.colors()[index].unwrap_or_else(|| self.theme.slot_color(index));
+            self.write_reply(&format(rgb));
+        }
+    }
+
+    /// Answers the program, e.g. a query about the terminal.
+    fn write_reply(&self, text: &str) {
+        if let Ok(mut guard) = self.writer.lock() {
+            let _ = guard.write_all(text.as_bytes());
+            let _ = guard.flush();
+        }
+    }
+
+    /// Matched text of `notify` triggers since the last call.
+    pub fn take_notifications(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.notifications)
+    }
+
+    /// The longest run time of the commands that finished since the last
+    /// call, as reported through OSC 133.
+    pub fn take_finished_command(&mut self) -> Option<Duration> {
+        self.finished_commands.drain(..).max()
+    }
+
+    /// Feeds text a line at a time, running the triggers over each line as
+    /// its line feed arrives, so every line is matched once no matter how
+    /// often it is redrawn.
+    fn advance_text(&mut self, text: &[u8]) {
+        for chunk in text.split_inclusive(|&byte| byte == b'\n') {
+            if !self.alt_screen() {
+                self.stamp_cursor_line();
+            }
+            self.advance(chunk);
+            self.answer_color_requests();
+            if self.triggers.is_empty() || chunk.last() != Some(&b'\n') {
+                continue;
+            }
+            let line = Line(self.term.grid().cursor.point.line.0 - 1);
+            if line.0 < -(self.term.grid().history_size() as i32) {
+                continue;
+            }
+            for hit in self.triggers.scan(&mut self.term, line) {
+                match hit.action {
+                    TriggerAction::None => {}
+                    TriggerAction::Notify => self.notifications.push(hit.text),
+                    TriggerAction::Bell => self.bell_pending.store(true, Ordering::Relaxed),
+                    TriggerAction::Mark => self.add_mark(hit.line, MarkKind::Trigger),
+                }
+            }
+        }
+    }
+
+    /// Runs `bytes` through the parser. Lines a cleared scrollback
+    /// (`CSI 3 J`, reset) dropped are counted so the lines on screen keep
+    /// their numbers, and the history is trimmed early if it filled the
+    /// grid's slack, before further lines could fall off uncounted.
+    fn advance(&mut self, bytes: &[u8]) {
+        let history_before = (!self.alt_screen()).then(|| self.term.grid().history_size());
         self.processor.advance(&mut self.term, bytes);
+        if self.alt_screen() {
+            return;
+        }
+        let history = self.term.grid().history_size();
+        if let Some(before) = history_before
+            && history < before
+        {
+            self.trimmed += (before - history) as u64;
+        }
+        if history >= self.scrollback + HISTORY_SLACK {
+            self.settle_history();
+        }
+    }
+
+    /// Trims the history back to the configured scrollback, counting the
+    /// dropped lines.
+    fn settle_history(&mut self) {
+        if self.alt_screen() {
+            return;
+        }
+        let grid = self.term.grid_mut();
+        let history = grid.history_size();
+        if history > self.scrollback {
+            grid.update_history(self.scrollback);
+            grid.update_history(self.scrollback + HISTORY_SLACK);
+            self.trimmed += (history - self.scrollback) as u64;
+        }
+        self.marks.forget_before(self.trimmed);
+        while self
+            .line_times
+            .front()
+            .is_some_and(|&(line, _)| line < self.trimmed)
+        {
+            self.line_times.pop_front();
+        }
+    }
+
+    /// Records the time output reaches the cursor's line, unless an
+    /// earlier write already did. Lines after a line feed start a chunk,
+    /// so rows a long line wraps onto get no time of their own.
+    fn stamp_cursor_line(&mut self) {
+        let number = self.line_number(self.term.grid().cursor.point.line);
+        if self
+            .line_times
+            .back()
+            .is_none_or(|&(last, _)| number > last)
+        {
+            self.line_times.push_back((number, SystemTime::now()));
+        }
+    }
+
+    /// When output first reached each row in view; `None` for rows that
+    /// continue a wrapped line or never had any. Empty on the alternate
+    /// screen.
+    pub fn line_times(&self) -> Vec<Option<SystemTime>> {
+        if self.alt_screen() {
+            return Vec::new();
+        }
+        let offset = self.term.grid().display_offset() as i32;
+        (0..self.size.lines as i32)
+            .map(|row| {
+                let number = self.line_number(Line(row - offset));
+                self.line_times
+                    .binary_search_by_key(&number, |&(line, _)| line)
+                    .ok()
+                    .map(|index| self.line_times[index].1)
+            })
+            .collect()
+    }
+
+    /// For each row in view, whether the terminal wrapped it because it ran
+    /// past the last column, so the line goes on in the next row.
+    pub fn wrapped_rows(&self) -> Vec<bool> {
+        let grid = self.term.grid();
+        let offset = grid.display_offset() as i32;
+        let last_column = Column(grid.columns().saturating_sub(1));
+        (0..self.size.lines as i32)
+            .map(|row| {
+                grid[Line(row - offset)][last_column]
+                    .flags
+                    .contains(Flags::WRAPLINE)
+            })
+            .collect()
+    }
+
+    /// Stable number of a line in grid coordinates (negative in scrollback).
+    fn line_number(&self, line: Line) -> u64 {
+        let history = self.term.grid().history_size() as i64;
+        self.trimmed + (history + i64::from(line.0)).max(0) as u64
+    }
+
+    /// Grid line holding line `number`, if it is still in the grid.
+    fn line_of(&self, number: u64) -> Option<Line> {
+        let grid = self.term.grid();
+        let line = number as i64 - self.trimmed as i64 - grid.history_size() as i64;
+        (line >= -(grid.history_size() as i64) && line < grid.screen_lines() as i64)
+            .then_some(Line(line as i32))
+    }
+
+    fn add_mark(&mut self, line: Line, kind: MarkKind) {
+        if self.alt_screen() {
+            return;
+        }
+        let line = self.line_number(line);
+        self.marks.insert(Mark { line, kind });
+    }
+
+    fn prompt_event(&mut self, event: PromptEvent) {
+        if self.alt_screen() {
+            return;
+        }
+        self.prompts_reported = true;
+        let cursor = self.term.grid().cursor.point;
+        match event {
+            PromptEvent::PromptStart => self.add_mark(cursor.line, MarkKind::Prompt),
+            PromptEvent::CommandStart => {
+                self.command_input = Some((self.line_number(cursor.line), cursor.column.0));
+            }
+            PromptEvent::OutputStart => {
+                if let Some(command) = self
+                    .command_input
+                    .take()
+                    .and_then(|start| self.command_line(start, cursor))
+                {
+                    if self.commands.len() >= MAX_COMMANDS {
+                        self.commands.remove(0);
+                    }
+                    self.commands.push(command);
+                }
+                self.output_start = Some((self.line_number(cursor.line), cursor.column.0));
+                self.command_started = Some(Instant::now());
+            }
+            PromptEvent::CommandFinished => {
+                if let Some(started) = self.command_started.take() {
+                    self.finished_commands.push(started.elapsed());
+                }
+                let Some(start) = self.output_start.take() else {
+                    return;
+                };
+                // The output ends on the cell before the cursor.
+                let line = self.line_number(cursor.line);
+                let end = match cursor.column.0 {
+                    0 => line
+                        .checked_sub(1)
+                        .map(|line| (line, self.term.grid().columns() - 1)),
+                    column => Some((line, column - 1)),
+                };
+                self.last_output = end.filter(|&end| end >= start).map(|end| [start, end]);
+            }
+        }
+    }
+
+    /// Text typed after the prompt from `start` up to the cursor, which
+    /// Enter has moved to the start of the next line.
+    fn command_line(&self, start: (u64, usize), cursor: Point) -> Option<String> {
+        let start_line = self.line_of(start.0)?;
+        let (end_line, end_column) = match cursor.column.0 {
+            0 => (Line(cursor.line.0 - 1), self.term.grid().columns() - 1),
+            column => (cursor.line, column - 1),
+        };
+        if (end_line, end_column) < (start_line, start.1) {
+            return None;
+        }
+        let text = self.term.bounds_to_string(
+            Point::new(start_line, Column(start.1)),
+            Point::new(end_line, Column(end_column)),
+        );
+        let text = text.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    /// Commands the shell ran in this session, oldest first.
+    pub fn command_history(&self) -> &[String] {
+        &self.commands
+    }
+
+    /// What has been typed at the prompt so far, up to the cursor. Needs a
+    /// shell that marks where the command line begins (OSC 133 ; B).
+    pub fn typed_command(&self) -> Option<String> {
+        if self.alt_screen() || self.output_start.is_some() {
+            return None;
+        }
+        let (line, column) = self.command_input?;
+        let grid = self.term.grid();
+        let cursor = grid.cursor.point;
+        let mut point = Point::new(self.line_of(line)?, Column(column));
+        let mut text = String::new();
+        while point < cursor {
+            let cell = &grid[point];
+            if !cell
+                .flags
+                .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+            {
+                text.push(cell.c);
+                text.extend(cell.zerowidth().into_iter().flatten());
+            }
+            point = if point.column.0 + 1 < grid.columns() {
+                Point::new(point.line, Column(point.column.0 + 1))
+            } else {
+                Point::new(Line(point.line.0 + 1), Column(0))
+            };
+        }
+        Some(text)
+    }
+
+    /// Whether the command line goes on past the cursor, as when the cursor
+    /// was moved back to edit it: anything but blanks after the cursor on
+    /// its row or the rows that row wraps onto.
+    pub fn input_after_cursor(&self) -> bool {
+        let grid = self.term.grid();
+        let bottom = Line(grid.screen_lines() as i32 - 1);
+        let mut point = grid.cursor.point;
+        loop {
+            let row = &grid[point.line];
+            if (point.column.0..grid.columns()).any(|col| !matches!(row[Column(col)].c, ' ' | '\0'))
+            {
+                return true;
+            }
+            let wraps = row[Column(grid.columns() - 1)]
+                .flags
+                .contains(Flags::WRAPLINE);
+            if !wraps || point.line >= bottom {
+                return false;
+            }
+            point = Point::new(Line(point.line.0 + 1), Column(0));
+        }
+    }
+
+    /// What to type to run the last command again: the command line the
+    /// shell reported through OSC 133, or Up and Enter to recall it from the
+    /// shell's history when it reported none. `None` while a command is
+    /// running or a full-screen program is up.
+    pub fn rerun_input(&self) -> Option<Vec<u8>> {
+        if self.alt_screen() || self.output_start.is_some() {
+            return None;
+        }
+        let input = match self.commands.last() {
+            Some(command) => format!("{command}\r").into_bytes(),
+            None if self.term.mode().contains(TermMode::APP_CURSOR) => b"\x1bOA\r".to_vec(),
+            None => b"\x1b[A\r".to_vec(),
+        };
+        Some(input)
+    }
+
+    /// Whether the shell has marked any prompt or command (OSC 133), which
+    /// shell integration does.
+    pub fn reports_prompts(&self) -> bool {
+        self.prompts_reported
+    }
+
+    /// The shell's working directory as it last reported it (OSC 7). For a
+    /// remote shell this is a path on the remote host.
+    pub fn reported_cwd(&self) -> Option<&str> {
+        self.cwd.as_deref()
+    }
+
+    /// How many DCS, SOS, PM and APC strings the engine has skipped
+    /// because nothing handles them.
+    pub fn unhandled_sequences(&self) -> u64 {
+        self.queries.unhandled_strings()
+    }
+
+    pub fn has_last_output(&self) -> bool {
+        self.last_output.is_some() && !self.alt_screen()
+    }
+
+    /// Selects the output of the last command that reported its boundaries
+    /// through OSC 133, scrolling its start into view when it is off screen.
+    /// Returns the selection and the output's text.
+    pub fn select_last_output(&mut self) -> Option<(Selection, String)> {
+        if self.alt_screen() {
+            return None;
+        }
+        let [start, end] = self.last_output?;
+        let end_line = self.line_of(end.0)?;
+        let top = Line(-(self.term.grid().history_size() as i32));
+        let (start_line, start_column) = match self.line_of(start.0) {
+            Some(line) => (line, start.1),
+            // The start was trimmed away; take what is left.
+            None => (top, 0),
+        };
+
+        let offset = self.term.grid().display_offset() as i32;
+        let visible = -offset..self.size.lines as i32 - offset;
+        if !visible.contains(&start_line.0) {
+            self.scroll_to_mark(self.line_number(start_line));
+        }
+
+        let text = self.term.bounds_to_string(
+            Point::new(start_line, Column(start_column)),
+            Point::new(end_line, Column(end.1)),
+        );
+        let offset = self.term.grid().display_offset() as i64;
+        let selection = Selection {
+            start: SelectionPoint {
+                row: i64::from(start_line.0) + offset,
+                col: start_column,
+            },
+            end: SelectionPoint {
+                row: i64::from(end_line.0) + offset,
+                col: end.1,
+            },
+            anchor_offset: offset as usize,
+        };
+        Some((selection, text))
+    }
+
+    /// A selection of the whole scrollback and screen.
+    pub fn select_all(&self) -> Selection {
+        let grid = self.term.grid();
+        let offset = grid.display_offset() as i64;
+        Selection {
+            start: SelectionPoint {
+                row: offset - grid.history_size() as i64,
+                col: 0,
+            },
+            end: SelectionPoint {
+                row: offset + grid.screen_lines() as i64 - 1,
+                col: grid.columns().saturating_sub(1),
+            },
+            anchor_offset: offset as usize,
+        }
+    }
+
+    /// The span of the first `rules` match covering viewport cell (`row`,
+    /// `col`), searched across the wrapped line it is on.
+    pub fn semantic_selection(
+        &self,
+        row: usize,
+        col: usize,
+        rules: &[String],
+    ) -> Option<Selection> {
+        let offset = self.term.grid().display_offset();
+        let point = Point::new(Line(row as i32 - offset as i32), Column(col));
+        let found = semantic::match_at(&self.term, point, rules)?;
+        let at = |point: Point| SelectionPoint {
+            row: point.line.0 as i64 + offset as i64,
+            col: point.column.0,
+        };
+        Some(Selection {
+            start: at(*found.start()),
+            end: at(*found.end()),
+            anchor_offset: offset,
+        })
+    }
+
+    /// The text `selection` covers, including rows scrolled out of view,
+    /// with trailing blanks dropped.
+    pub fn selection_text(&self, selection: &Selection) -> String {
+        let grid = self.term.grid();
+        let top = -(grid.history_size() as i64);
+        let bottom = grid.screen_lines() as i64 - 1;
+        let last_column = grid.columns().saturating_sub(1);
+        // Selection rows count from the top of the viewport it was made in.
+        let point = |at: SelectionPoint| {
+            let line = (at.row - selection.anchor_offset as i64).clamp(top, bottom);
+            Point::new(Line(line as i32), Column(at.col.min(last_column)))
+        };
+        let (start, end) = selection.ordered();
+        let text = self.term.bounds_to_string(point(start), point(end));
+        text.trim_end().to_string()
+    }
+
+    /// Clears the scrollback and the screen above the cursor, moving the
+    /// cursor's line (usually the prompt) to the top. Does nothing on the
+    /// alternate screen, which the program there owns.
+    pub fn clear_buffer(&mut self) {
+        if self.alt_screen() {
+            return;
+        }
+        self.term.scroll_display(Scroll::Bottom);
+        let cursor = self.term.grid().cursor.point;
+        // Scrolling the cursor's line to the top pushes everything above it
+        // into the history, which `CSI 3 J` then drops.
+        let mut sequence = String::new();
+        if cursor.line.0 > 0 {
+            sequence.push_str(&format!("\x1b[{}S", cursor.line.0));
+        }
+        sequence.push_str(&format!("\x1b[3J\x1b[1;{}H", cursor.column.0 + 1));
+        self.advance_text(sequence.as_bytes());
+        self.unseen_lines = 0;
+        self.cache_dirty.set(true);
+    }
+
+    /// Adds or removes a mark on the cursor's line, or on the top visible
+    /// line while scrolled back.
+    pub fn toggle_mark(&mut self) {
+        if self.alt_screen() {
+            return;
+        }
+        let line = match self.term.grid().display_offset() {
+            0 => self.term.grid().cursor.point.line,
+            offset => Line(-(offset as i32)),
+        };
+        self.marks.toggle(self.line_number(line));
         self.cache_dirty.set(true);
     }
 
+    /// Scrolls the nearest mark above (`older`) or below the top visible
+    /// line to the top of the viewport. Returns false when there is none.
+    pub fn jump_to_mark(&mut self, older: bool) -> bool {
+        if self.alt_screen() {
+            return false;
+        }
+        let offset = self.term.grid().display_offset();
+        let top = self.line_number(Line(-(offset as i32)));
+        let mark = if older {
+            self.marks.before(top)
+        } else {
+            self.marks.after(top)
+        };
+        match mark {
+            Some(mark) => self.scroll_to_mark(mark.line),
+            None if !older && offset > 0 => {
+                self.scroll_to_bottom();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Scrolls so that line `number` is at the top of the viewport, or as
+    /// close as the bottom of the scrollback allows.
+    pub fn scroll_to_mark(&mut self, number: u64) -> bool {
+        let Some(line) = self.line_of(number) else {
+            return false;
+        };
+        let target = (-line.0).max(0);
+        let current = self.term.grid().display_offset() as i32;
+        if target != current {
+            self.term.scroll_display(Scroll::Delta(target - current));
+            self.cache_dirty.set(true);
+        }
+        self.clear_unseen_at_bottom();
+        true
+    }
+
+    /// Marks still in the scrollback, oldest first, with the text of the
+    /// marked line.
+    pub fn marks(&self) -> Vec<(Mark, String)> {
+        if self.alt_screen() {
+            return Vec::new();
+        }
+        let grid = self.term.grid();
+        let last_column = Column(grid.columns().saturating_sub(1));
+        self.marks
+            .iter()
+            .filter_map(|mark| {
+                let line = self.line_of(mark.line)?;
+                let text = self
+                    .term
+                    .bounds_to_string(Point::new(line, Column(0)), Point::new(line, last_column));
+                Some((*mark, text.trim().to_string()))
+            })
+            .collect()
+    }
+
+    /// Lines of output that arrived since the viewport left the bottom.
+    pub fn unseen_lines(&self) -> usize {
+        self.unseen_lines
+    }
+
     pub fn resize(&mut self, new_size: TerminalSize) {
         self.size = new_size;
         self.term.resize(new_size);
+        self.settle_history();
+        self.sync_window_size();
         self.cache_dirty.set(true);
     }
 
+    /// Resizes like [`Self::resize`], carrying `selection` across the reflow.
+    /// Its ends are held as places within logical lines rather than rows and
+    /// columns, so it covers the same text once wrapped lines are rewrapped
+    /// at the new width.
+    pub fn resize_with_selection(
+        &mut self,
+        new_size: TerminalSize,
+        selection: Option<Selection>,
+    ) -> Option<Selection> {
+        let ends = selection.map(|selection| {
+            let (start, end) = selection.ordered();
+            (
+                self.logical_point(start, selection.anchor_offset),
+                self.logical_point(end, selection.anchor_offset),
+            )
+        });
+        self.resize(new_size);
+        let (start, end) = ends?;
+        let anchor_offset = self.term.grid().display_offset();
+        Some(Selection {
+            start: self.point_in_logical_line(start, anchor_offset),
+            end: self.point_in_logical_line(end, anchor_offset),
+            anchor_offset,
+        })
+    }
+
+    /// `point` as (logical line counted from the top of the scrollback,
+    /// offset into that line in cells).
+    fn logical_point(&self, point: SelectionPoint, anchor_offset: usize) -> (usize, usize) {
+        let grid = self.term.grid();
+        let top = -(grid.history_size() as i32);
+        let bottom = grid.screen_lines() as i32 - 1;
+        let columns = grid.columns();
+        let line = (point.row - anchor_offset as i64).clamp(top as i64, bottom as i64) as i32;
+        let wraps = |line: i32| {
+            grid[Line(line)][Column(columns - 1)]
+                .flags
+                .contains(Flags::WRAPLINE)
+        };
+        let (mut logical, mut starts_at) = (0, top);
+        for row in top..line {
+            if !wraps(row) {
+                logical += 1;
+                starts_at = row + 1;
+            }
+        }
+        let column = point.col.min(columns - 1);
+        (logical, (line - starts_at) as usize * columns + column)
+    }
+
+    /// The viewport point for a place from [`Self::logical_point`], in the
+    /// grid as it is now. Places past the end of their line or of the grid
+    /// are pulled back to the last cell.
+    fn point_in_logical_line(
+        &self,
+        (logical, offset): (usize, usize),
+        anchor_offset: usize,
+    ) -> SelectionPoint {
+        let grid = self.term.grid();
+        let top = -(grid.history_size() as i32);
+        let bottom = grid.screen_lines() as i32 - 1;
+        let columns = grid.columns();
+        let wraps = |line: i32| {
+            grid[Line(line)][Column(columns - 1)]
+                .flags
+                .contains(Flags::WRAPLINE)
+        };
+        let mut starts_at = top;
+        for _ in 0..logical {
+            while starts_at < bottom && wraps(starts_at) {
+                starts_at += 1;
+            }
+            starts_at += 1;
+        }
+        let (line, col) = if starts_at > bottom {
+            (bottom, columns - 1)
+        } else {
+            let mut ends_at = starts_at;
+            while ends_at < bottom && wraps(ends_at) {
+                ends_at += 1;
+            }
+            let line = starts_at + (offset / columns) as i32;
+            if line > ends_at {
+                (ends_at, columns - 1)
+            } else {
+                (line, offset % columns)
+            }
+        };
+        SelectionPoint {
+            row: i64::from(line) + anchor_offset as i64,
+            col,
+        }
+    }
+
+    /// Size of one cell in logical pixels, used to lay out inline images and
+    /// to report the text area size.
+    pub fn set_cell_pixel_size(&mut self, size: [f32; 2]) {
+        self.cell_pixels = size;
+        self.sync_window_size();
+    }
+
+    fn sync_window_size(&self) {
+        if let Ok(mut window) = self.window.lock() {
+            *window = window_size(self.size, self.cell_pixels);
+        }
+    }
+
     pub fn render_cells(&self) -> Arc<Vec<CellVisual>> {
+        self.refresh_cache();
+        self.cells_cache.borrow().clone()
+    }
+
+    /// The image placements with at least one tile in the viewport.
+    pub fn render_images(&self) -> Arc<Vec<Arc<ImagePlacement>>> {
+        self.refresh_cache();
+        self.images_cache.borrow().clone()
+    }
+
+    fn refresh_cache(&self) {
         if self.cache_dirty.get() || self.cache_size.get() != self.size {
             let mut cache = self.cells_cache.borrow_mut();
             if let Some(cells) = Arc::get_mut(&mut cache) {
@@ -97,18 +924,200 @@ impl TerminalEngine {
                 self.build_cells_into(&mut cells);
                 *cache = Arc::new(cells);
             }
+            *self.images_cache.borrow_mut() = Arc::new(self.visible_placements(&cache));
             self.cache_dirty.set(false);
             self.cache_size.set(self.size);
         }
-        self.cells_cache.borrow().clone()
+    }
+
+    fn visible_placements(&self, cells: &[CellVisual]) -> Vec<Arc<ImagePlacement>> {
+        let mut seen = HashSet::new();
+        cells
+            .iter()
+            .filter_map(|cell| cell.image)
+            .filter(|tile| seen.insert(tile.placement))
+            .filter_map(|tile| self.placements.get(&tile.placement).cloned())
+            .collect()
+    }
+
+    /// Writes the image's tiles at the cursor and keeps the bitmap for the
+    /// renderer.
+    fn place_image(&mut self, request: ImageRequest) {
+        let placement = request.place(self.cell_pixels, self.size.columns, self.size.lines);
+        let start_column = self.term.grid().cursor.point.column.0;
+        let cells = graphics::placement_cells(&placement, start_column, self.size.columns);
+        self.placements.insert(placement.id, Arc::new(placement));
+        self.advance(&cells);
+        self.prune_images();
+    }
+
+    fn kitty_command(&mut self, command: KittyCommand) {
+        for action in self.kitty.handle(command) {
+            match action {
+                KittyAction::Place {
+                    request,
+                    ids,
+                    move_cursor,
+                } => self.place_kitty_image(request, ids, move_cursor),
+                KittyAction::Delete { target, free } => self.delete_kitty_images(target, free),
+                KittyAction::Reply(text) => self.write_reply(&text),
+            }
+        }
+    }
+
+    /// Unlike inline images, kitty placements only tag the cells they cover,
+    /// leaving any text there to be drawn above or below them. Placing again
+    /// with the same image and placement id moves the placement.
+    fn place_kitty_image(&mut self, request: ImageRequest, ids: (u32, u32), move_cursor: bool) {
+        let mut placement = request.place(self.cell_pixels, self.size.columns, self.size.lines);
+        placement.kitty_ids = Some(ids);
+        if ids.1 != 0 {
+            self.placements.retain(|_, p| p.kitty_ids != Some(ids));
+        }
+
+        let (columns, lines) = (placement.columns, placement.lines);
+        if move_cursor && lines > 1 {
+            // Scroll so the whole image fits below the cursor.
+            let room = format!("{}\x1b[{}A", "\n".repeat(lines - 1), lines - 1);
+            self.advance(room.as_bytes());
+        }
+
+        let cursor = self.term.grid().cursor.point;
+        let (top, left) = (cursor.line.0.max(0) as usize, cursor.column.0);
+        let bottom = (top + lines).min(self.size.lines);
+        let right = (left + columns).min(self.size.columns);
+        let id = placement.id;
+        self.placements.insert(id, Arc::new(placement));
+        let grid = self.term.grid_mut();
+        for line in top..bottom {
+            for column in left..right {
+                let tile = ImageTile {
+                    placement: id,
+                    col: (column - left) as u16,
+                    row: (line - top) as u16,
+                };
+                grid[Line(line as i32)][Column(column)]
+                    .set_hyperlink(Some(Hyperlink::new(None::<String>, tile.uri())));
+            }
+        }
+
+        if move_cursor {
+            let column = right.min(self.size.columns.saturating_sub(1));
+            let goto = format!("\x1b[{};{}H", bottom.max(top + 1), column + 1);
+            self.advance(goto.as_bytes());
+        }
+        self.prune_images();
+    }
+
+    fn delete_kitty_images(&mut self, target: DeleteTarget, free: bool) {
+        let cursor = self.term.grid().cursor.point;
+        let mut doomed = HashSet::new();
+        match target {
+            DeleteTarget::Image { image, placement } => {
+                doomed.extend(self.placements.iter().filter_map(|(&id, p)| {
+                    let (i, p) = p.kitty_ids?;
+                    (i == image && placement.is_none_or(|want| want == p)).then_some(id)
+                }));
+            }
+            DeleteTarget::Range { first, last } => {
+                doomed.extend(self.placements.iter().filter_map(|(&id, p)| {
+                    let (i, _) = p.kitty_ids?;
+                    (first..=last).contains(&i).then_some(id)
+                }));
+            }
+            _ => {
+                // The rest select placements by where they are on screen.
+                let grid = self.term.grid();
+                for line in 0..grid.screen_lines() {
+                    let row = &grid[Line(line as i32)];
+                    for column in 0..grid.columns() {
+                        let Some(tile) = row[Column(column)]
+                            .hyperlink()
+                            .and_then(|link| ImageTile::from_uri(link.uri()))
+                        else {
+                            continue;
+                        };
+                        let Some(placement) = self.placements.get(&tile.placement) else {
+                            continue;
+                        };
+                        let hit = match target {
+                            DeleteTarget::Visible => true,
+                            DeleteTarget::AtCursor => {
+                                line as i32 == cursor.line.0 && column == cursor.column.0
+                            }
+                            DeleteTarget::Cell { column: c, line: l } => line == l && column == c,
+                            DeleteTarget::Column(c) => column == c,
+                            DeleteTarget::Line(l) => line == l,
+                            DeleteTarget::ZIndex(z) => placement.z_index == z,
+                            DeleteTarget::Image { .. } | DeleteTarget::Range { .. } => false,
+                        };
+                        if hit && placement.kitty_ids.is_some() {
+                            doomed.insert(tile.placement);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut released = HashSet::new();
+        for id in doomed {
+            if let Some((image, _)) = self.placements.remove(&id).and_then(|p| p.kitty_ids) {
+                released.insert(image);
+            }
+        }
+        if free {
+            for image in released {
+                let shown = self
+                    .placements
+                    .values()
+                    .any(|p| p.kitty_ids.is_some_and(|(i, _)| i == image));
+                if !shown {
+                    self.kitty.forget(image);
+                }
+            }
+        }
+    }
+
+    /// Drops images once they exceed the memory budget: first those no cell
+    /// refers to any more, then the oldest.
+    fn prune_images(&mut self) {
+        let total = |placements: &HashMap<u64, Arc<ImagePlacement>>| -> usize {
+            placements.values().map(|p| p.image.rgba.len()).sum()
+        };
+        if total(&self.placements) <= IMAGE_MEMORY_BUDGET {
+            return;
+        }
+
+        let grid = self.term.grid();
+        let mut referenced = HashSet::new();
+        for line in -(grid.history_size() as i32)..grid.screen_lines() as i32 {
+            let row = &grid[Line(line)];
+            for col in 0..grid.columns() {
+                if let Some(tile) = row[Column(col)]
+                    .hyperlink()
+                    .and_then(|link| ImageTile::from_uri(link.uri()))
+                {
+                    referenced.insert(tile.placement);
+                }
+            }
+        }
+        self.placements.retain(|id, _| referenced.contains(id));
+
+        while total(&self.placements) > IMAGE_MEMORY_BUDGET
+            && let Some(&oldest) = self.placements.keys().min()
+        {
+            self.placements.remove(&oldest);
+        }
     }
 
     pub fn scroll(&mut self, delta: i32) {
         self.term.scroll_display(Scroll::Delta(delta));
         self.cache_dirty.set(true);
+        self.clear_unseen_at_bottom();
     }
 
     pub fn scroll_to_bottom(&mut self) {
+        self.unseen_lines = 0;
         if self.term.grid().display_offset() == 0 {
             return;
         }
@@ -116,6 +1125,12 @@ impl TerminalEngine {
         self.cache_dirty.set(true);
     }
 
+    fn clear_unseen_at_bottom(&mut self) {
+        if self.term.grid().display_offset() == 0 {
+            self.unseen_lines = 0;
+        }
+    }
+
     /// Returns (display_offset, total_history_lines).
     /// display_offset == 0 means at the bottom (latest output).
     pub fn scroll_position(&self) -> (usize, usize) {
@@ -137,6 +1152,7 @@ impl TerminalEngine {
             self.term.scroll_display(Scroll::Delta(delta));
             self.cache_dirty.set(true);
         }
+        self.clear_unseen_at_bottom();
     }
 
     pub fn set_theme(&mut self, theme: TerminalTheme) {
@@ -165,6 +1181,50 @@ impl TerminalEngine {
         self.term.mode().contains(TermMode::BRACKETED_PASTE)
     }
 
+    /// Each viewport row as escape sequences that redraw it on another
+    /// terminal. The format is described in the `repaint` module.
+    pub fn repaint_rows(&self) -> Vec<Vec<u8>> {
+        repaint::rows(
+            &self.render_cells(),
+            self.size.columns,
+            rgb_to_rgba(self.theme.foreground, 1.0),
+        )
+    }
+
+    /// DEC private mode sets and resets that put another terminal in the
+    /// modes that change what this one's keyboard and mouse input sends.
+    pub fn input_mode_sequences(&self) -> String {
+        const MODES: [(TermMode, u16); 7] = [
+            (TermMode::APP_CURSOR, 1),
+            (TermMode::MOUSE_REPORT_CLICK, 1000),
+            (TermMode::MOUSE_DRAG, 1002),
+            (TermMode::MOUSE_MOTION, 1003),
+            (TermMode::FOCUS_IN_OUT, 1004),
+            (TermMode::SGR_MOUSE, 1006),
+            (TermMode::BRACKETED_PASTE, 2004),
+        ];
+        let mode = self.term.mode();
+        MODES
+            .iter()
+            .map(|&(flag, number)| {
+                let set = if mode.contains(flag) { 'h' } else { 'l' };
+                format!("\x1b[?{number}{set}")
+            })
+            .collect()
+    }
+
+    /// Whether the text before the cursor looks like a prompt for a password
+    /// or other secret, for sessions whose echo state cannot be queried.
+    pub fn prompts_for_secret(&self) -> bool {
+        let grid = self.term.grid();
+        let cursor = grid.cursor.point;
+        let row = &grid[cursor.line];
+        let prompt: String = (0..cursor.column.0.min(self.size.columns))
+            .map(|col| row[Column(col)].c)
+            .collect();
+        is_secret_prompt(&prompt)
+    }
+
     /// Current text cursor as `(col, row)` in viewport coordinates.
     pub fn cursor_position(&self) -> (usize, usize) {
         let point = self.term.grid().cursor.point;
@@ -193,9 +1253,11 @@ impl TerminalEngine {
         }
     }
 
-    /// The theme cursor color as linear RGBA (opaque).
+    /// The cursor color as linear RGBA (opaque): the one set through OSC 12,
+    /// or the theme's.
     pub fn cursor_color(&self) -> [f32; 4] {
-        rgb_to_rgba(self.theme.cursor_rgb(), 1.0)
+        let rgb = self.term.colors()[NamedColor::Cursor].unwrap_or_else(|| self.theme.cursor_rgb());
+        rgb_to_rgba(rgb, 1.0)
     }
 
     fn build_cells_into(&self, cells: &mut Vec<CellVisual>) {
@@ -213,12 +1275,16 @@ impl TerminalEngine {
         let total = self.size.lines * self.size.columns;
         let default_cell = CellVisual {
             ch: ' ',
+            grapheme: None,
             col: 0,
             row: 0,
             fg: default_fg,
             bg: default_bg,
             underline: false,
+            bold: false,
+            italic: false,
             hyperlink: None,
+            image: None,
             wide: false,
         };
 
@@ -283,28 +1349,111 @@ impl TerminalEngine {
                     }
 
                     slot.ch = indexed.cell.c;
+                    slot.grapheme = indexed.cell.zerowidth().map(|marks| {
+                        let mut cluster = String::from(indexed.cell.c);
+                        cluster.extend(marks);
+                        std::sync::Arc::from(cluster)
+                    });
                     slot.col = col;
                     slot.row = row;
                     slot.fg = fg;
                     slot.bg = bg;
                     slot.underline = indexed.cell.flags.intersects(Flags::ALL_UNDERLINES);
+                    slot.bold = indexed.cell.flags.contains(Flags::BOLD);
+                    slot.italic = indexed.cell.flags.contains(Flags::ITALIC);
                     slot.wide = indexed.cell.flags.contains(Flags::WIDE_CHAR);
-                    slot.hyperlink = indexed
-                        .cell
-                        .hyperlink()
-                        .map(|link| std::sync::Arc::from(link.uri()));
+                    match indexed.cell.hyperlink() {
+                        Some(link) if link.uri().starts_with(IMAGE_URI_SCHEME) => {
+                            slot.image = ImageTile::from_uri(link.uri());
+                        }
+                        link => {
+                            slot.hyperlink = link.map(|link| std::sync::Arc::from(link.uri()));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut redactor = self.redactor.borrow_mut();
+        if !redactor.is_empty() {
+            for (row, col) in redactor.covered(&self.term, self.size.lines) {
+                if let Some(slot) = cells.get_mut(idx(row, col, self.size.columns)) {
+                    redact::mask(slot);
                 }
             }
         }
     }
 }
 
+/// Headless use: the engine without a PTY or a renderer, for tests and tools
+/// that feed bytes and read back the screen. The app itself only replays
+/// recordings into one.
+#[allow(dead_code)]
+impl TerminalEngine {
+    /// An engine with the default theme and scrollback whose replies to
+    /// queries go nowhere.
+    pub fn headless(size: TerminalSize) -> Self {
+        Self::new(
+            size,
+            DEFAULT_TERMINAL_SCROLLBACK,
+            Arc::new(Mutex::new(Box::new(std::io::sink()))),
+            TerminalTheme::default(),
+        )
+    }
+
+    /// The rows of the viewport as text, trailing blanks trimmed.
+    pub fn screen_text(&self) -> Vec<String> {
+        snapshot::lines(&self.render_cells(), self.size.columns)
+    }
+
+    /// A plain-text picture of the viewport: text, cursor, marks for
+    /// underlines, links and images, and colors that differ from the theme.
+    /// The format is described in the `snapshot` module.
+    pub fn snapshot(&self) -> String {
+        snapshot::render(
+            &self.render_cells(),
+            self.size.columns,
+            self.cursor_cell(),
+            rgb_to_rgba(self.theme.foreground, 1.0),
+        )
+    }
+}
+
+/// A label ending in a colon that asks for a password, passphrase, PIN or
+/// one-time code, as `sudo`, `ssh` and `gpg` print them.
+fn is_secret_prompt(prompt: &str) -> bool {
+    const PHRASES: [&str; 5] = ["password", "passphrase", "passcode", "암호", "비밀번호"];
+    const WORDS: [&str; 2] = ["pin", "otp"];
+
+    let prompt = prompt.trim_end().to_lowercase();
+    let Some(label) = prompt
+        .strip_suffix(':')
+        .or_else(|| prompt.strip_suffix('：'))
+    else {
+        return false;
+    };
+    PHRASES.iter().any(|phrase| label.contains(phrase))
+        || label
+            .split(|ch: char| !ch.is_alphanumeric())
+            .any(|word| WORDS.contains(&word))
+}
+
+fn window_size(size: TerminalSize, cell_pixels: [f32; 2]) -> WindowSize {
+    WindowSize {
+        num_lines: size.lines as u16,
+        num_cols: size.columns as u16,
+        cell_width: cell_pixels[0].round().max(1.0) as u16,
+        cell_height: cell_pixels[1].round().max(1.0) as u16,
+    }
+}
+
 #[derive(Clone)]
 struct PtyEventProxy {
     writer: Arc<Mutex<Box<dyn Write + Send>>>,
-    size: TerminalSize,
+    window: Arc<Mutex<WindowSize>>,
     title: Arc<Mutex<Option<String>>>,
     bell_pending: Arc<AtomicBool>,
+    color_requests: Arc<Mutex<Vec<(usize, ColorFormatter)>>>,
 }
 
 impl EventListener for PtyEventProxy {
@@ -317,11 +1466,8 @@ impl EventListener for PtyEventProxy {
                 }
             }
             Event::TextAreaSizeRequest(formatter) => {
-                let ws = WindowSize {
-                    num_lines: self.size.lines as u16,
-                    num_cols: self.size.columns as u16,
-                    cell_width: 1,
-                    cell_height: 1,
+                let Some(ws) = self.window.lock().ok().map(|window| *window) else {
+                    return;
                 };
                 if let Ok(mut guard) = self.writer.lock() {
                     let text = formatter(ws);
@@ -337,6 +1483,11 @@ impl EventListener for PtyEventProxy {
             Event::Bell => {
                 self.bell_pending.store(true, Ordering::Relaxed);
             }
+            Event::ColorRequest(index, format) => {
+                if let Ok(mut requests) = self.color_requests.lock() {
+                    requests.push((index, format));
+                }
+            }
             _ => {}
         }
     }
@@ -355,6 +1506,30 @@ mod tests {
         )
     }
 
+    #[test]
+    fn password_prompts_are_recognized_before_the_cursor() {
+        assert!(is_secret_prompt("[sudo] password for sam: "));
+        assert!(is_secret_prompt(
+            "Enter passphrase for key '/home/sam/.ssh/id_ed25519':"
+        ));
+        assert!(is_secret_prompt("Enter PIN:"));
+        assert!(is_secret_prompt("비밀번호:"));
+        assert!(!is_secret_prompt("Password changed."));
+        assert!(!is_secret_prompt("Typing:"));
+        assert!(!is_secret_prompt("~/src $ "));
+
+        let mut engine = TerminalEngine::new(
+            TerminalSize::new(40, 3),
+            100,
+            Arc::new(Mutex::new(Box::new(std::io::sink()))),
+            TerminalTheme::default(),
+        );
+        engine.feed_bytes(b"$ sudo true\r\n[sudo] password for sam: ");
+        assert!(engine.prompts_for_secret());
+        engine.feed_bytes(b"\r\n$ ");
+        assert!(!engine.prompts_for_secret());
+    }
+
     #[test]
     fn scroll_to_bottom_returns_viewport_to_latest_output() {
         let mut engine = test_engine();
@@ -368,4 +1543,498 @@ mod tests {
 
         assert_eq!(engine.scroll_position().0, 0);
     }
+
+    #[test]
+    fn output_follows_the_bottom_when_pinned() {
+        let mut engine = test_engine();
+
+        engine.feed_bytes(b"one\r\ntwo\r\nthree\r\nfour\r\n");
+        assert_eq!(engine.scroll_position().0, 0);
+        assert_eq!(engine.unseen_lines(), 0);
+    }
+
+    #[test]
+    fn output_does_not_move_a_scrolled_back_viewport() {
+        let mut engine = test_engine();
+        engine.feed_bytes(b"one\r\ntwo\r\nthree\r\nfour\r\nfive\r\n");
+        engine.scroll(2);
+        let top_before = engine.render_cells()[0].ch;
+
+        engine.feed_bytes(b"six\r\nseven\r\neight\r\n");
+
+        assert_eq!(engine.render_cells()[0].ch, top_before);
+        assert_eq!(engine.scroll_position().0, 5);
+        assert_eq!(engine.unseen_lines(), 3);
+    }
+
+    #[test]
+    fn returning_to_the_bottom_clears_unseen_lines() {
+        let mut engine = test_engine();
+        engine.feed_bytes(b"one\r\ntwo\r\nthree\r\nfour\r\nfive\r\n");
+        engine.scroll(1);
+        engine.feed_bytes(b"six\r\n");
+        assert_eq!(engine.unseen_lines(), 1);
+
+        engine.scroll(-10);
+        assert_eq!(engine.unseen_lines(), 0);
+
+        engine.scroll(1);
+        engine.feed_bytes(b"seven\r\n");
+        engine.scroll_to_bottom();
+        assert_eq!(engine.unseen_lines(), 0);
+    }
+
+    #[test]
+    fn sixel_images_cover_cells_at_the_cursor() {
+        let mut engine = test_engine();
+        engine.set_cell_pixel_size([8.0, 6.0]);
+        // 16×12 red square: two cells wide, two lines tall.
+        engine.feed_bytes(b"ab\x1bPq#1;2;100;0;0!16~-!16~\x1b\\z");
+
+        let cells = engine.render_cells();
+        let tile = |row: usize, col: usize| cells[row * 8 + col].image;
+        assert_eq!(tile(0, 1), None);
+        assert_eq!(tile(0, 2).map(|t| (t.col, t.row)), Some((0, 0)));
+        assert_eq!(tile(1, 3).map(|t| (t.col, t.row)), Some((1, 1)));
+        assert_eq!(tile(1, 4), None);
+        assert_eq!(cells[12].ch, 'z');
+        assert!(cells.iter().all(|cell| cell.hyperlink.is_none()));
+
+        let images = engine.render_images();
+        assert_eq!(images.len(), 1);
+        assert_eq!((images[0].columns, images[0].lines), (2, 2));
+    }
+
+    #[test]
+    fn images_push_lines_out_of_the_scrollback_like_text() {
+        let mut engine = TerminalEngine::new(
+            TerminalSize::new(8, 3),
+            4,
+            Arc::new(Mutex::new(Box::new(std::io::sink()))),
+            TerminalTheme::default(),
+        );
+        engine.set_cell_pixel_size([8.0, 6.0]);
+        engine.feed_bytes(b"\x1b]133;A\x07two\r\n");
+        assert_eq!(engine.marks().len(), 1);
+
+        let image = b"\x1bPq#1;2;100;0;0!16~-!16~\x1b\\\r\n";
+        engine.feed_bytes(&image.repeat(10));
+        assert_eq!(engine.scroll_position().1, 4);
+        assert!(engine.marks().is_empty());
+    }
+
+    #[test]
+    fn kitty_placements_keep_text_and_delete_by_id() {
+        use base64::Engine as _;
+
+        let mut engine = test_engine();
+        engine.set_cell_pixel_size([8.0, 6.0]);
+        let pixels = base64::engine::general_purpose::STANDARD.encode([0u8; 16 * 12 * 4]);
+        engine.feed_bytes(b"ab");
+        engine.feed_bytes(format!("\x1b_Ga=T,f=32,s=16,v=12,i=7,z=-1;{pixels}\x1b\\").as_bytes());
+        engine.feed_bytes(b"z");
+
+        let cells = engine.render_cells();
+        assert_eq!(cells[2].image.map(|t| (t.col, t.row)), Some((0, 0)));
+        assert_eq!(cells[11].image.map(|t| (t.col, t.row)), Some((1, 1)));
+        // The cursor moved past the image's last line and column.
+        assert_eq!(cells[12].ch, 'z');
+        assert_eq!(cells[0].ch, 'a');
+        let images = engine.render_images();
+        assert_eq!(images[0].kitty_ids, Some((7, 0)));
+        assert_eq!(images[0].z_index, -1);
+
+        engine.feed_bytes(b"\x1b_Ga=d,d=I,i=7\x1b\\");
+        assert!(engine.render_images().is_empty());
+        engine.feed_bytes(b"\x1b_Ga=p,i=7\x1b\\");
+        assert!(engine.render_images().is_empty());
+    }
+
+    #[test]
+    fn combining_marks_stay_with_their_base_character() {
+        let mut engine = test_engine();
+        engine.feed_bytes("e\u{301}x\u{2764}\u{fe0f}".as_bytes());
+
+        let cells = engine.render_cells();
+        assert_eq!(cells[0].ch, 'e');
+        assert_eq!(cells[0].grapheme.as_deref(), Some("e\u{301}"));
+        assert_eq!((cells[1].ch, cells[1].grapheme.as_deref()), ('x', None));
+        assert_eq!(cells[2].grapheme.as_deref(), Some("\u{2764}\u{fe0f}"));
+    }
+
+    #[derive(Clone, Default)]
+    struct Replies(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Replies {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn osc_7_reports_update_the_working_directory() {
+        let mut engine = test_engine();
+        assert_eq!(engine.reported_cwd(), None);
+
+        engine.feed_bytes(b"\x1b]7;file://host/home/me\x07$ ");
+        engine.feed_bytes(b"\x1b]7;file://host/srv/my%20app\x1b\\");
+        assert_eq!(engine.reported_cwd(), Some("/srv/my app"));
+        assert_eq!(engine.render_cells()[0].ch, '$');
+    }
+
+    #[test]
+    fn identification_queries_are_answered_in_order() {
+        let replies = Replies::default();
+        let mut engine = TerminalEngine::new(
+            TerminalSize::new(8, 3),
+            100,
+            Arc::new(Mutex::new(Box::new(replies.clone()))),
+            TerminalTheme::default(),
+        );
+        engine.feed_bytes(b"\x05\x1b[>q\x1b[c");
+        engine.set_answerback("hello");
+        engine.feed_bytes(b"ok\x05");
+
+        let written = String::from_utf8(replies.0.lock().unwrap().clone()).unwrap();
+        assert!(written.starts_with("\x1bP>|RabbiTTY "), "{written:?}");
+        assert!(
+            written.ends_with("\x1b\\\x1b[?62;4;22chello"),
+            "{written:?}"
+        );
+        assert_eq!(engine.render_cells()[0].ch, 'o');
+    }
+
+    #[test]
+    fn strings_nothing_handles_are_skipped_and_counted() {
+        let mut engine = TerminalEngine::headless(TerminalSize::new(20, 1));
+        // ReGIS, SOS, PM, an APC that is not kitty graphics, and the
+        // Tektronix mode switch.
+        engine.feed_bytes(b"a\x1bPp S(E)\x1b\\b\x1bXsos\x1b\\c\x1b^pm\x1b\\d");
+        engine.feed_bytes(b"\x1b_apc\x1b\\e\x1b[?38hf");
+        assert_eq!(engine.screen_text(), ["abcdef"]);
+        assert_eq!(engine.unhandled_sequences(), 4);
+    }
+
+    #[test]
+    fn tmux_passthrough_is_unwrapped_only_when_enabled() {
+        // XTVERSION, which only the engine's own query handling answers.
+        let wrapped = b"\x1bPtmux;\x1b\x1b[>q\x1b\\";
+        let replies = Replies::default();
+        let mut engine = TerminalEngine::new(
+            TerminalSize::new(8, 3),
+            100,
+            Arc::new(Mutex::new(Box::new(replies.clone()))),
+            TerminalTheme::default(),
+        );
+        engine.feed_bytes(wrapped);
+        assert!(replies.0.lock().unwrap().is_empty());
+        assert_eq!(engine.unhandled_sequences(), 1);
+
+        engine.set_tmux_passthrough(true);
+        engine.feed_bytes(wrapped);
+        let written = String::from_utf8(replies.0.lock().unwrap().clone()).unwrap();
+        assert!(written.starts_with("\x1bP>|RabbiTTY "), "{written:?}");
+        assert_eq!(engine.unhandled_sequences(), 1);
+        assert_eq!(engine.screen_text(), ["", "", ""]);
+    }
+
+    #[test]
+    fn wrapped_rows_are_reported_and_copied_as_one_line() {
+        let mut engine = TerminalEngine::headless(TerminalSize::new(4, 3));
+        engine.feed_bytes(b"abcdef\r\nxy");
+        assert_eq!(engine.wrapped_rows(), [true, false, false]);
+        let all = engine.select_all();
+        assert_eq!(engine.selection_text(&all), "abcdef\nxy");
+    }
+
+    #[test]
+    fn selection_keeps_its_text_when_lines_rewrap() {
+        let mut engine = TerminalEngine::headless(TerminalSize::new(8, 3));
+        engine.feed_bytes(b"abcdefghij\r\nxyz");
+        // From `e` on the first row to `y` on the third.
+        let selection = Selection {
+            start: SelectionPoint { row: 0, col: 4 },
+            end: SelectionPoint { row: 2, col: 1 },
+            anchor_offset: 0,
+        };
+        assert_eq!(engine.selection_text(&selection), "efghij\nxy");
+
+        let narrow = engine.resize_with_selection(TerminalSize::new(4, 3), Some(selection));
+        assert_eq!(engine.selection_text(&narrow.unwrap()), "efghij\nxy");
+
+        let wide = engine.resize_with_selection(TerminalSize::new(12, 3), narrow);
+        assert_eq!(engine.selection_text(&wide.unwrap()), "efghij\nxy");
+        assert_eq!(
+            engine.resize_with_selection(TerminalSize::new(6, 3), None),
+            None
+        );
+    }
+
+    #[test]
+    fn text_area_size_reports_follow_resizes() {
+        let replies = Replies::default();
+        let mut engine = TerminalEngine::new(
+            TerminalSize::new(8, 3),
+            100,
+            Arc::new(Mutex::new(Box::new(replies.clone()))),
+            TerminalTheme::default(),
+        );
+        engine.set_cell_pixel_size([9.0, 18.0]);
+        engine.resize(TerminalSize::new(20, 6));
+        engine.feed_bytes(b"\x1b[14t");
+
+        let written = String::from_utf8(replies.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(written, "\x1b[4;108;180t");
+    }
+
+    #[test]
+    fn color_queries_report_set_colors_and_fall_back_to_the_theme() {
+        let replies = Replies::default();
+        let mut engine = TerminalEngine::new(
+            TerminalSize::new(8, 3),
+            100,
+            Arc::new(Mutex::new(Box::new(replies.clone()))),
+            TerminalTheme::default(),
+        );
+        let take = || String::from_utf8(std::mem::take(&mut *replies.0.lock().unwrap())).unwrap();
+
+        engine.feed_bytes(b"\x1b]11;?\x07");
+        let background = take();
+        assert!(background.starts_with("\x1b]11;rgb:"), "{background:?}");
+
+        engine.feed_bytes(b"\x1b]4;1;#102030\x07\x1b]4;1;?\x07\x1b]12;#ff0000\x07");
+        assert!(take().contains("rgb:1010/2020/3030"));
+        assert_eq!(
+            engine.cursor_color(),
+            rgb_to_rgba(Rgb { r: 255, g: 0, b: 0 }, 1.0)
+        );
+
+        engine.feed_bytes(b"\x1b]104;1\x07\x1b]112\x07\x1b]4;1;?\x07");
+        assert!(!take().contains("rgb:1010/2020/3030"));
+        assert_eq!(
+            engine.cursor_color(),
+            TerminalEngine::new(
+                TerminalSize::new(8, 3),
+                100,
+                Arc::new(Mutex::new(Box::new(std::io::sink()))),
+                TerminalTheme::default(),
+            )
+            .cursor_color()
+        );
+
+        engine.feed_bytes(b"\x1b]11;#000000\x07\x1b]11;?\x07");
+        assert!(take().contains("rgb:0000/0000/0000"));
+        engine.feed_bytes(b"\x1b]111\x07\x1b]11;?\x07");
+        assert_eq!(take(), background);
+    }
+
+    #[test]
+    fn bold_and_italic_cells_are_marked_for_the_renderer() {
+        let mut engine = test_engine();
+        engine.feed_bytes(b"\x1b[1mB\x1b[0;3mI\x1b[0mR");
+        let cells = engine.render_cells();
+        assert!(cells[0].bold && !cells[0].italic);
+        assert!(cells[1].italic && !cells[1].bold);
+        assert!(!cells[2].bold && !cells[2].italic);
+    }
+
+    #[test]
+    fn triggers_style_matches_on_completed_lines() {
+        let mut engine = test_engine();
+        engine.set_triggers(&[Trigger {
+            pattern: "ERR".into(),
+            foreground: Some([255, 0, 0]),
+            background: None,
+            underline: true,
+            action: TriggerAction::Notify,
+        }]);
+
+        engine.feed_bytes(b"ok ERR");
+        assert!(!engine.render_cells()[3].underline);
+        assert!(engine.take_notifications().is_empty());
+
+        engine.feed_bytes(b"\r\n");
+        let cells = engine.render_cells();
+        assert!(cells[3..6].iter().all(|cell| cell.underline));
+        assert_ne!(cells[3].fg, cells[1].fg);
+        assert!(!cells[2].underline);
+        assert_eq!(engine.take_notifications(), vec!["ERR".to_string()]);
+    }
+
+    #[test]
+    fn marks_keep_their_line_while_scrollback_is_trimmed() {
+        let mut engine = TerminalEngine::new(
+            TerminalSize::new(8, 3),
+            4,
+            Arc::new(Mutex::new(Box::new(std::io::sink()))),
+            TerminalTheme::default(),
+        );
+        assert!(!engine.reports_prompts());
+        engine.feed_bytes(b"one\r\n\x1b]133;A\x07two\r\n");
+        assert!(engine.reports_prompts());
+        for _ in 0..5 {
+            engine.feed_bytes(b"x\r\n");
+        }
+        assert_eq!(engine.scroll_position().1, 4);
+
+        let marks = engine.marks();
+        assert_eq!(marks.len(), 1);
+        assert_eq!(marks[0].0.kind, MarkKind::Prompt);
+        assert_eq!(marks[0].1, "two");
+
+        assert!(engine.jump_to_mark(true));
+        let top = engine.render_cells();
+        assert_eq!((top[0].ch, top[1].ch, top[2].ch), ('t', 'w', 'o'));
+
+        engine.feed_bytes(b"x\r\n");
+        assert!(engine.marks().is_empty());
+    }
+
+    #[test]
+    fn lines_dropped_within_one_feed_are_all_counted() {
+        let mut engine = TerminalEngine::new(
+            TerminalSize::new(8, 3),
+            4,
+            Arc::new(Mutex::new(Box::new(std::io::sink()))),
+            TerminalTheme::default(),
+        );
+        let mut output = b"x\r\n".repeat(3 * HISTORY_SLACK);
+        output.extend_from_slice(b"\x1b]133;A\x07two\r\ny\r\n");
+        engine.feed_bytes(&output);
+        assert_eq!(engine.scroll_position().1, 4);
+
+        let marks = engine.marks();
+        assert_eq!(marks.len(), 1);
+        assert_eq!(marks[0].1, "two");
+    }
+
+    #[test]
+    fn selects_the_output_of_the_last_command() {
+        let mut engine = test_engine();
+        engine.feed_bytes(b"$ ls\r\n\x1b]133;C\x07a\r\nbc\r\n\x1b]133;D;0\x07$ ");
+
+        let (selection, text) = engine.select_last_output().expect("output was recorded");
+        assert_eq!(text, "a\nbc");
+        assert_eq!(selection.start, SelectionPoint { row: 0, col: 0 });
+        assert_eq!(selection.end, SelectionPoint { row: 1, col: 7 });
+    }
+
+    #[test]
+    fn reruns_the_command_line_the_shell_reported() {
+        let mut engine = test_engine();
+        assert_eq!(engine.rerun_input().as_deref(), Some(&b"\x1b[A\r"[..]));
+
+        engine.feed_bytes(b"\x1b]133;A\x07$ \x1b]133;B\x07make\r\n\x1b]133;C\x07");
+        assert!(engine.rerun_input().is_none(), "a command is running");
+
+        engine.feed_bytes(b"ok\r\n\x1b]133;D;0\x07\x1b]133;A\x07$ \x1b]133;B\x07");
+        assert_eq!(engine.rerun_input().as_deref(), Some(&b"make\r"[..]));
+    }
+
+    #[test]
+    fn typed_command_reads_the_prompt_up_to_the_cursor() {
+        let mut engine = test_engine();
+        assert!(engine.typed_command().is_none());
+
+        engine.feed_bytes(b"\x1b]133;A\x07$ \x1b]133;B\x07git ");
+        assert_eq!(engine.typed_command().as_deref(), Some("git "));
+
+        engine.feed_bytes(b"st\r\n\x1b]133;C\x07");
+        assert!(engine.typed_command().is_none());
+        assert_eq!(engine.command_history(), ["git st"]);
+    }
+
+    #[test]
+    fn text_after_the_cursor_means_the_input_is_mid_line() {
+        let mut engine = test_engine();
+        engine.feed_bytes(b"\x1b]133;A\x07$ \x1b]133;B\x07git status");
+        assert!(!engine.input_after_cursor());
+
+        // Back over "tus" to edit: a suggestion would land mid-command.
+        engine.feed_bytes(b"\x1b[3D");
+        assert_eq!(engine.typed_command().as_deref(), Some("git sta"));
+        assert!(engine.input_after_cursor());
+
+        // Erased to the end of the line, the cursor is at the end again.
+        engine.feed_bytes(b"\x1b[K");
+        assert!(!engine.input_after_cursor());
+    }
+
+    #[test]
+    fn lines_keep_the_time_their_output_arrived() {
+        let mut engine = test_engine();
+        engine.feed_bytes(b"one\r\n");
+        let before = SystemTime::now();
+        engine.feed_bytes(b"0123456789\r\n$ ");
+
+        // `one` has scrolled off; the long line wraps onto a second row.
+        let times = engine.line_times();
+        assert_eq!(times.len(), 3);
+        assert!(times[0].is_some_and(|at| at >= before));
+        assert_eq!(times[1], None, "continues the wrapped line");
+        assert!(times[2].is_some());
+
+        engine.scroll(1);
+        let scrolled = engine.line_times();
+        assert!(scrolled[0].is_some_and(|at| at <= before));
+        assert_eq!(scrolled[1], times[0], "times scroll with their lines");
+    }
+
+    #[test]
+    fn finished_commands_report_their_run_time_once() {
+        let mut engine = test_engine();
+        engine.feed_bytes(b"$ make\r\n\x1b]133;C\x07");
+        assert_eq!(engine.take_finished_command(), None, "still running");
+
+        std::thread::sleep(Duration::from_millis(20));
+        engine.feed_bytes(b"done\r\n\x1b]133;D;0\x07$ ");
+        let elapsed = engine.take_finished_command().expect("finished");
+        assert!(elapsed >= Duration::from_millis(20));
+        assert_eq!(engine.take_finished_command(), None);
+
+        // A prompt without a command has nothing to report.
+        engine.feed_bytes(b"\x1b]133;D;0\x07$ ");
+        assert_eq!(engine.take_finished_command(), None);
+    }
+
+    #[test]
+    fn select_all_covers_the_scrollback() {
+        let mut engine = test_engine();
+        engine.feed_bytes(b"one\r\ntwo\r\nthree\r\nfour\r\nfive");
+        engine.scroll(1);
+
+        let selection = engine.select_all();
+        assert_eq!(
+            engine.selection_text(&selection),
+            "one\ntwo\nthree\nfour\nfive"
+        );
+    }
+
+    #[test]
+    fn clear_buffer_keeps_only_the_cursor_line() {
+        let mut engine = test_engine();
+        engine.feed_bytes(b"one\r\ntwo\r\nthree\r\nfour\r\n$ ls");
+
+        engine.clear_buffer();
+
+        assert_eq!(engine.scroll_position(), (0, 0));
+        assert_eq!(engine.screen_text(), ["$ ls", "", ""]);
+        assert_eq!(engine.cursor_position(), (4, 0));
+    }
+
+    #[test]
+    fn semantic_selection_follows_a_path_across_wrapped_rows() {
+        let mut engine = test_engine();
+        engine.feed_bytes(b"cd /usr/local/bin");
+        let rules = crate::config::DEFAULT_SEMANTIC_RULES.map(String::from);
+
+        let selection = engine.semantic_selection(1, 2, &rules).unwrap();
+        assert_eq!(engine.selection_text(&selection), "/usr/local/bin");
+        assert_eq!(engine.semantic_selection(0, 1, &rules), None);
+    }
 }
@@ -287,9 +287,6 @@ fn discover_well_known(db: &Database) -> Vec<String> {
 
 /// Load a system font suitable for CJK/wide character fallback.
 pub fn load_cjk_fallback_font() -> Option<FontArc> {
-    let mut db = Database::new();
-    db.load_system_fonts();
-
     const CJK_FAMILIES: &[&str] = &[
         "Apple SD Gothic Neo",
         "Hiragino Sans",
@@ -305,7 +302,36 @@ pub fn load_cjk_fallback_font() -> Option<FontArc> {
         "Noto Sans Mono CJK SC",
     ];
 
-    for family_name in CJK_FAMILIES {
+    load_first_family(CJK_FAMILIES).map(|(font, _)| FontArc::new(font))
+}
+
+/// A font with color glyphs. The face index is kept so the color tables can
+/// be read from the same data as the outlines.
+#[derive(Debug)]
+pub struct ColorFont {
+    pub font: FontVec,
+    pub index: u32,
+}
+
+/// Load a system emoji font for color glyph fallback: bitmap (CBDT, sbix)
+/// or layered (COLR).
+pub fn load_emoji_fallback_font() -> Option<ColorFont> {
+    const EMOJI_FAMILIES: &[&str] = &[
+        "Apple Color Emoji",
+        "Segoe UI Emoji",
+        "Noto Color Emoji",
+        "Twemoji Mozilla",
+        "JoyPixels",
+    ];
+
+    load_first_family(EMOJI_FAMILIES).map(|(font, index)| ColorFont { font, index })
+}
+
+fn load_first_family(family_names: &[&str]) -> Option<(FontVec, u32)> {
+    let mut db = Database::new();
+    db.load_system_fonts();
+
+    for family_name in family_names {
         let families = [Family::Name(family_name)];
         let query = Query {
             families: &families,
@@ -315,7 +341,7 @@ pub fn load_cjk_fallback_font() -> Option<FontArc> {
             let result = db.with_face_data(id, |data, index| {
                 FontVec::try_from_vec_and_index(data.to_vec(), index)
                     .ok()
-                    .map(FontArc::new)
+                    .map(|font| (font, index))
             });
             if let Some(Some(font)) = result {
                 return Some(font);
@@ -0,0 +1,1114 @@
+use super::kitty::KittyCommand;
+use super::marks::PromptEvent;
+use base64::Engine as _;
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Encoded image payloads larger than this are discarded unread.
+const MAX_PAYLOAD: usize = 48 * 1024 * 1024;
+/// Largest edge a decoded image may have; bigger images are scaled down so
+/// they still fit in a GPU texture.
+pub(crate) const MAX_EDGE: u32 = 8192;
+/// Sixel canvases are drawn pixel by pixel, so they get a tighter bound.
+const MAX_SIXEL_EDGE: usize = 4096;
+/// Most pixels a Sixel canvas may hold, 32 MiB of RGBA.
+const MAX_SIXEL_PIXELS: usize = 4096 * 2048;
+const MAX_DCS_PARAMS: usize = 32;
+const ITERM_INTRO: &[u8] = b"1337;File=";
+/// Shares its first three bytes with [`ITERM_INTRO`].
+const PROMPT_INTRO: &[u8] = b"133;";
+const CWD_INTRO: &[u8] = b"7;";
+
+/// Prefix of the OSC 8 hyperlinks the engine writes into cells covered by an
+/// image. The grid then scrolls, clips and erases images exactly like text.
+pub(crate) const IMAGE_URI_SCHEME: &str = "rabbitty-image:";
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A decoded bitmap, RGBA8 with straight alpha.
+#[derive(Debug)]
+pub struct ImageData {
+    /// Process-wide unique, so renderers can key GPU textures on it.
+    pub id: u64,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl ImageData {
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Self {
+        Self {
+            id: next_id(),
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    pub(crate) fn from_encoded(bytes: &[u8]) -> Option<Self> {
+        let mut image = image::load_from_memory(bytes).ok()?;
+        if image.width() > MAX_EDGE || image.height() > MAX_EDGE {
+            image = image.resize(MAX_EDGE, MAX_EDGE, image::imageops::FilterType::Triangle);
+        }
+        let rgba = image.to_rgba8();
+        let (width, height) = rgba.dimensions();
+        (width > 0 && height > 0).then(|| Self::new(width, height, rgba.into_raw()))
+    }
+}
+
+/// An image laid out over a block of cells.
+///
+/// The `source` rectangle of the bitmap (in texture coordinates) is stretched
+/// over the first `fill` fraction of the `columns × lines` box; the rest of
+/// the last column/line stays empty, so an image keeps its pixel size instead
+/// of being rounded up to whole cells.
+#[derive(Debug)]
+pub struct ImagePlacement {
+    pub id: u64,
+    pub image: Arc<ImageData>,
+    pub source: [f32; 4],
+    pub columns: usize,
+    pub lines: usize,
+    pub fill: [f32; 2],
+    /// Negative values draw below text, the rest above it.
+    pub z_index: i32,
+    /// Image and placement ids for placements made through the kitty
+    /// protocol, which can later be deleted by id.
+    pub kitty_ids: Option<(u32, u32)>,
+}
+
+/// The part of a placement shown in one cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageTile {
+    pub placement: u64,
+    pub col: u16,
+    pub row: u16,
+}
+
+impl ImageTile {
+    pub(crate) fn uri(&self) -> String {
+        format!(
+            "{IMAGE_URI_SCHEME}{}/{}/{}",
+            self.placement, self.col, self.row
+        )
+    }
+
+    pub(crate) fn from_uri(uri: &str) -> Option<Self> {
+        let mut parts = uri.strip_prefix(IMAGE_URI_SCHEME)?.split('/');
+        let tile = Self {
+            placement: parts.next()?.parse().ok()?,
+            col: parts.next()?.parse().ok()?,
+            row: parts.next()?.parse().ok()?,
+        };
+        parts.next().is_none().then_some(tile)
+    }
+}
+
+/// A requested edge length, as accepted by iTerm2's `width=`/`height=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Auto,
+    Cells(u32),
+    Pixels(u32),
+    Percent(u32),
+}
+
+impl Dimension {
+    fn parse(value: &str) -> Self {
+        let value = value.trim();
+        let parsed = if let Some(px) = value.strip_suffix("px") {
+            px.parse().ok().map(Self::Pixels)
+        } else if let Some(pct) = value.strip_suffix('%') {
+            pct.parse().ok().map(Self::Percent)
+        } else {
+            value.parse().ok().map(Self::Cells)
+        };
+        parsed
+            .filter(|d| *d != Self::Cells(0))
+            .unwrap_or(Self::Auto)
+    }
+
+    fn to_pixels(self, cell: f32, available_cells: usize) -> Option<f32> {
+        match self {
+            Self::Auto => None,
+            Self::Cells(n) => Some(n as f32 * cell),
+            Self::Pixels(n) => Some(n as f32),
+            Self::Percent(p) => Some(available_cells as f32 * cell * p.min(100) as f32 / 100.0),
+        }
+    }
+}
+
+/// A decoded image waiting to be placed at the cursor.
+#[derive(Debug)]
+pub struct ImageRequest {
+    pub image: Arc<ImageData>,
+    /// Pixel rectangle of the image to show as `[x, y, width, height]`;
+    /// `None` shows all of it.
+    pub source: Option<[u32; 4]>,
+    pub width: Dimension,
+    pub height: Dimension,
+    pub preserve_aspect: bool,
+    pub z_index: i32,
+}
+
+impl ImageRequest {
+    pub fn natural(image: Arc<ImageData>) -> Self {
+        Self {
+            image,
+            source: None,
+            width: Dimension::Auto,
+            height: Dimension::Auto,
+            preserve_aspect: true,
+            z_index: 0,
+        }
+    }
+
+    /// The source rectangle clamped to the bitmap, never empty.
+    fn source_rect(&self) -> [u32; 4] {
+        let (w, h) = (self.image.width, self.image.height);
+        let [x, y, sw, sh] = self.source.unwrap_or([0, 0, w, h]);
+        let x = x.min(w - 1);
+        let y = y.min(h - 1);
+        let sw = if sw == 0 { w - x } else { sw.min(w - x) };
+        let sh = if sh == 0 { h - y } else { sh.min(h - y) };
+        [x, y, sw, sh]
+    }
+
+    /// Lays the image out over cells of `cell` pixels in a terminal of
+    /// `columns × lines`. Auto-sized images keep their pixel size but are
+    /// scaled down to fit the terminal's width; requested sizes are bounded
+    /// by the whole terminal.
+    pub fn place(self, cell: [f32; 2], columns: usize, lines: usize) -> ImagePlacement {
+        let [cell_w, cell_h] = [cell[0].max(1.0), cell[1].max(1.0)];
+        let [src_x, src_y, src_w, src_h] = self.source_rect();
+        let natural_w = src_w as f32;
+        let natural_h = src_h as f32;
+        let requested_w = self.width.to_pixels(cell_w, columns);
+        let requested_h = self.height.to_pixels(cell_h, lines);
+
+        let (width, height) = match (requested_w, requested_h) {
+            (None, None) => {
+                let max_w = columns.max(1) as f32 * cell_w;
+                let scale = (max_w / natural_w).min(1.0);
+                (natural_w * scale, natural_h * scale)
+            }
+            (Some(w), None) if self.preserve_aspect => (w, natural_h * w / natural_w),
+            (None, Some(h)) if self.preserve_aspect => (natural_w * h / natural_h, h),
+            (Some(w), Some(h)) if self.preserve_aspect => {
+                let scale = (w / natural_w).min(h / natural_h);
+                (natural_w * scale, natural_h * scale)
+            }
+            (w, h) => (w.unwrap_or(natural_w), h.unwrap_or(natural_h)),
+        };
+        let (width, height) = if requested_w.is_some() || requested_h.is_some() {
+            let max_w = columns.max(1) as f32 * cell_w;
+            let max_h = lines.max(1) as f32 * cell_h;
+            if self.preserve_aspect {
+                let scale = (max_w / width).min(max_h / height).min(1.0);
+                (width * scale, height * scale)
+            } else {
+                (width.min(max_w), height.min(max_h))
+            }
+        } else {
+            (width, height)
+        };
+
+        let columns = (width / cell_w).ceil().max(1.0);
+        let lines = (height / cell_h).ceil().max(1.0);
+        let [image_w, image_h] = [self.image.width as f32, self.image.height as f32];
+        ImagePlacement {
+            id: next_id(),
+            source: [
+                src_x as f32 / image_w,
+                src_y as f32 / image_h,
+                (src_x + src_w) as f32 / image_w,
+                (src_y + src_h) as f32 / image_h,
+            ],
+            image: self.image,
+            columns: columns as usize,
+            lines: lines as usize,
+            fill: [
+                (width / (columns * cell_w)).clamp(0.0, 1.0),
+                (height / (lines * cell_h)).clamp(0.0, 1.0),
+            ],
+            z_index: self.z_index,
+            kitty_ids: None,
+        }
+    }
+}
+
+/// A run of PTY output, split around the image, prompt and directory
+/// sequences it contained.
+#[derive(Debug)]
+pub enum Segment<'a> {
+    Text(Cow<'a, [u8]>),
+    Image(ImageRequest),
+    Kitty(KittyCommand),
+    Prompt(PromptEvent),
+    /// The shell's working directory, reported through OSC 7.
+    Cwd(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protocol {
+    Iterm,
+    Sixel,
+    Kitty,
+    Prompt,
+    Cwd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Intro {
+    Osc,
+    /// `OSC 7`, which no other introducer starts with.
+    Cwd,
+    Dcs,
+    Apc,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Ground,
+    /// Inside an escape sequence that may still turn out to be an image.
+    Intro {
+        kind: Intro,
+        len: usize,
+    },
+    Payload {
+        protocol: Protocol,
+        escape: bool,
+    },
+}
+
+/// Pulls iTerm2 inline images (`OSC 1337 ; File=… ST`), Sixel graphics
+/// (`DCS … q … ST`), kitty graphics commands (`APC G … ST`), shell prompt
+/// marks (`OSC 133 ; … ST`) and working directory reports
+/// (`OSC 7 ; file://… ST`) out of the PTY stream before it reaches the VT
+/// parser, which would otherwise drop them.
+/// Sequences may straddle reads.
+#[derive(Debug)]
+pub struct GraphicsParser {
+    state: State,
+    /// Introducer bytes carried over from the previous read.
+    held: Vec<u8>,
+    payload: Vec<u8>,
+    overflowed: bool,
+}
+
+impl Default for GraphicsParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GraphicsParser {
+    pub fn new() -> Self {
+        Self {
+            state: State::Ground,
+            held: Vec::new(),
+            payload: Vec::new(),
+            overflowed: false,
+        }
+    }
+
+    pub fn split<'a>(&mut self, input: &'a [u8]) -> Vec<Segment<'a>> {
+        let mut segments = Vec::new();
+        let mut text_start = 0;
+        let mut intro_start = 0;
+        let mut i = 0;
+
+        while i < input.len() {
+            let byte = input[i];
+            match self.state {
+                State::Ground => {
+                    let Some(offset) = input[i..].iter().position(|&b| b == 0x1b) else {
+                        break;
+                    };
+                    i += offset;
+                    intro_start = i;
+                    self.state = State::Intro {
+                        kind: Intro::Osc,
+                        len: 1,
+                    };
+                    i += 1;
+                }
+                State::Intro { kind, len } => match intro_step(kind, len, byte) {
+                    Step::More(kind) => {
+                        self.state = State::Intro { kind, len: len + 1 };
+                        i += 1;
+                    }
+                    Step::Matched(protocol) => {
+                        if !self.held.is_empty() {
+                            self.held.clear();
+                        } else if intro_start > text_start {
+                            segments.push(Segment::Text(Cow::Borrowed(
+                                &input[text_start..intro_start],
+                            )));
+                        }
+                        self.state = State::Payload {
+                            protocol,
+                            escape: false,
+                        };
+                        self.payload.clear();
+                        self.overflowed = false;
+                        i += 1;
+                        text_start = i;
+                    }
+                    Step::Rejected => {
+                        // Not an image: hand the bytes to the VT parser as-is.
+                        // The rejected byte is rescanned, it may start a new
+                        // sequence.
+                        if !self.held.is_empty() {
+                            segments
+                                .push(Segment::Text(Cow::Owned(std::mem::take(&mut self.held))));
+                        }
+                        self.state = State::Ground;
+                    }
+                },
+                State::Payload { protocol, escape } => {
+                    if escape {
+                        self.finish(protocol, &mut segments);
+                        if byte == b'\\' {
+                            i += 1;
+                            text_start = i;
+                        } else {
+                            // Any ESC other than ST also ends the sequence and
+                            // begins the next one.
+                            self.state = State::Intro {
+                                kind: Intro::Osc,
+                                len: 1,
+                            };
+                            if i == 0 {
+                                self.held.push(0x1b);
+                                text_start = 0;
+                            } else {
+                                intro_start = i - 1;
+                                text_start = i - 1;
+                            }
+                        }
+                        continue;
+                    }
+                    let end = input[i..]
+                        .iter()
+                        .position(|&b| matches!(b, 0x1b | 0x07 | 0x18 | 0x1a));
+                    let chunk_end = end.map_or(input.len(), |offset| i + offset);
+                    self.collect(&input[i..chunk_end]);
+                    i = chunk_end;
+                    let Some(terminator) = input.get(i).copied() else {
+                        break;
+                    };
+                    i += 1;
+                    match terminator {
+                        0x1b => {
+                            self.state = State::Payload {
+                                protocol,
+                                escape: true,
+                            };
+                        }
+                        0x07 if matches!(
+                            protocol,
+                            Protocol::Iterm | Protocol::Prompt | Protocol::Cwd
+                        ) =>
+                        {
+                            self.finish(protocol, &mut segments);
+                            text_start = i;
+                        }
+                        // BEL does not end a DCS or APC.
+                        0x07 => {}
+                        // CAN and SUB cancel the sequence.
+                        _ => {
+                            self.abort();
+                            text_start = i;
+                        }
+                    }
+                }
+            }
+        }
+
+        match self.state {
+            State::Ground => {
+                if text_start < input.len() {
+                    segments.push(Segment::Text(Cow::Borrowed(&input[text_start..])));
+                }
+            }
+            State::Intro { .. } => {
+                if self.held.is_empty() && intro_start > text_start {
+                    segments.push(Segment::Text(Cow::Borrowed(
+                        &input[text_start..intro_start],
+                    )));
+                }
+                let from = if self.held.is_empty() {
+                    intro_start
+                } else {
+                    text_start
+                };
+                self.held.extend_from_slice(&input[from..]);
+            }
+            State::Payload { .. } => {}
+        }
+        segments
+    }
+
+    fn collect(&mut self, bytes: &[u8]) {
+        if self.overflowed {
+            return;
+        }
+        if self.payload.len() + bytes.len() > MAX_PAYLOAD {
+            self.overflowed = true;
+            self.payload = Vec::new();
+        } else {
+            self.payload.extend_from_slice(bytes);
+        }
+    }
+
+    fn abort(&mut self) {
+        self.state = State::Ground;
+        self.payload.clear();
+        self.overflowed = false;
+    }
+
+    fn finish(&mut self, protocol: Protocol, segments: &mut Vec<Segment<'_>>) {
+        let payload = std::mem::take(&mut self.payload);
+        let overflowed = self.overflowed;
+        self.abort();
+        if overflowed {
+            return;
+        }
+        let request = match protocol {
+            Protocol::Iterm => parse_iterm(&payload),
+            Protocol::Sixel => {
+                decode_sixel(&payload).map(|image| ImageRequest::natural(Arc::new(image)))
+            }
+            Protocol::Kitty => {
+                if let Some(command) = KittyCommand::parse(&payload) {
+                    segments.push(Segment::Kitty(command));
+                }
+                return;
+            }
+            Protocol::Prompt => {
+                if let Some(event) = PromptEvent::parse(&payload) {
+                    segments.push(Segment::Prompt(event));
+                }
+                return;
+            }
+            Protocol::Cwd => {
+                if let Some(path) = super::path::decode_osc7(&payload) {
+                    segments.push(Segment::Cwd(path));
+                }
+                return;
+            }
+        };
+        if let Some(request) = request {
+            segments.push(Segment::Image(request));
+        }
+    }
+}
+
+enum Step {
+    More(Intro),
+    Matched(Protocol),
+    Rejected,
+}
+
+/// Advances the introducer match by one byte. `len` counts the bytes seen so
+/// far, starting with ESC.
+fn intro_step(kind: Intro, len: usize, byte: u8) -> Step {
+    if len == 1 {
+        return match byte {
+            b']' => Step::More(Intro::Osc),
+            b'P' => Step::More(Intro::Dcs),
+            b'_' => Step::More(Intro::Apc),
+            _ => Step::Rejected,
+        };
+    }
+    match kind {
+        Intro::Osc => {
+            let at = len - 2;
+            if at == 0 && byte == CWD_INTRO[0] {
+                Step::More(Intro::Cwd)
+            } else if at + 1 == PROMPT_INTRO.len() && byte == PROMPT_INTRO[at] {
+                Step::Matched(Protocol::Prompt)
+            } else if ITERM_INTRO.get(at) != Some(&byte) {
+                Step::Rejected
+            } else if at + 1 == ITERM_INTRO.len() {
+                Step::Matched(Protocol::Iterm)
+            } else {
+                Step::More(Intro::Osc)
+            }
+        }
+        Intro::Cwd => match byte {
+            b';' => Step::Matched(Protocol::Cwd),
+            _ => Step::Rejected,
+        },
+        Intro::Dcs => match byte {
+            b'q' => Step::Matched(Protocol::Sixel),
+            b'0'..=b'9' | b';' if len < MAX_DCS_PARAMS => Step::More(Intro::Dcs),
+            _ => Step::Rejected,
+        },
+        Intro::Apc => match byte {
+            b'G' => Step::Matched(Protocol::Kitty),
+            _ => Step::Rejected,
+        },
+    }
+}
+
+/// Parses the part of `OSC 1337 ; File=` after the `=`:
+/// `key=value;…:base64-data`. Only `inline=1` files are displayed; anything
+/// else is a download request, which is not supported.
+fn parse_iterm(payload: &[u8]) -> Option<ImageRequest> {
+    let split = payload.iter().position(|&b| b == b':')?;
+    let params = std::str::from_utf8(&payload[..split]).ok()?;
+    let data: Vec<u8> = payload[split + 1..]
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+
+    let mut inline = false;
+    let mut width = Dimension::Auto;
+    let mut height = Dimension::Auto;
+    let mut preserve_aspect = true;
+    for param in params.split(';') {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+        match key {
+            "inline" => inline = value == "1",
+            "width" => width = Dimension::parse(value),
+            "height" => height = Dimension::parse(value),
+            "preserveAspectRatio" => preserve_aspect = value != "0",
+            _ => {}
+        }
+    }
+    if !inline {
+        return None;
+    }
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&data)
+        .ok()?;
+    Some(ImageRequest {
+        width,
+        height,
+        preserve_aspect,
+        ..ImageRequest::natural(Arc::new(ImageData::from_encoded(&bytes)?))
+    })
+}
+
+/// The VT340's power-on palette, which Sixel images may draw with before
+/// (or without) defining colors of their own.
+const SIXEL_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [51, 51, 204],
+    [204, 36, 36],
+    [51, 204, 51],
+    [204, 51, 204],
+    [51, 204, 204],
+    [204, 204, 51],
+    [120, 120, 120],
+    [69, 69, 69],
+    [87, 87, 153],
+    [153, 69, 69],
+    [87, 153, 87],
+    [153, 87, 153],
+    [87, 153, 153],
+    [153, 153, 87],
+    [204, 204, 204],
+];
+
+/// Decodes a Sixel data stream (everything between `q` and ST). Pixels no
+/// sixel touches stay transparent.
+pub fn decode_sixel(data: &[u8]) -> Option<ImageData> {
+    let mut palette: Vec<[u8; 3]> = SIXEL_PALETTE.to_vec();
+    palette.resize(256, [0, 0, 0]);
+    let mut color = palette[0];
+    let mut canvas = SixelCanvas::default();
+    let (mut x, mut y) = (0usize, 0usize);
+    let mut i = 0;
+
+    while i < data.len() {
+        let byte = data[i];
+        i += 1;
+        match byte {
+            b'?'..=b'~' => {
+                canvas.plot(x, y, byte - b'?', 1, color);
+                x += 1;
+            }
+            b'!' => {
+                let (params, next) = sixel_params(data, i);
+                i = next;
+                let count = params.first().copied().unwrap_or(1).max(1) as usize;
+                if let Some(&sixel @ b'?'..=b'~') = data.get(i) {
+                    i += 1;
+                    canvas.plot(x, y, sixel - b'?', count, color);
+                    x = x.saturating_add(count);
+                }
+            }
+            b'#' => {
+                let (params, next) = sixel_params(data, i);
+                i = next;
+                let index = params.first().copied().unwrap_or(0) as usize % palette.len();
+                if let [_, space, a, b, c] = params[..] {
+                    palette[index] = match space {
+                        1 => hls_to_rgb(a, b, c),
+                        _ => [a, b, c].map(|v| (v.min(100) * 255 / 100) as u8),
+                    };
+                }
+                color = palette[index];
+            }
+            b'"' => {
+                // Raster attributes: aspect ratio and an optional size hint.
+                // The image grows to whatever the data draws, so the size
+                // only reserves room up front.
+                let (params, next) = sixel_params(data, i);
+                i = next;
+                if let [_, _, width, height, ..] = params[..] {
+                    canvas.reserve(
+                        (width as usize).min(MAX_SIXEL_EDGE),
+                        (height as usize).min(MAX_SIXEL_EDGE),
+                    );
+                }
+            }
+            b'$' => x = 0,
+            b'-' => {
+                x = 0;
+                y += 6;
+            }
+            _ => {}
+        }
+    }
+
+    if canvas.width == 0 || canvas.height == 0 {
+        return None;
+    }
+    let (width, height) = (canvas.width as u32, canvas.height as u32);
+    Some(ImageData::new(width, height, canvas.into_rgba()))
+}
+
+/// A Sixel image under construction; it grows to cover whatever is drawn.
+/// The buffer behind it grows by doubling, so drawing a large image copies
+/// it only a few times.
+#[derive(Default)]
+struct SixelCanvas {
+    pixels: Vec<[u8; 4]>,
+    /// Size of `pixels`, at least `width × height`.
+    stride: usize,
+    rows: usize,
+    width: usize,
+    height: usize,
+}
+
+impl SixelCanvas {
+    /// Paints the set bits of one sixel, `count` times from column `x`, in
+    /// the six-pixel band starting at row `y`.
+    fn plot(&mut self, x: usize, y: usize, bits: u8, count: usize, rgb: [u8; 3]) {
+        if bits == 0 || x >= MAX_SIXEL_EDGE || y >= MAX_SIXEL_EDGE {
+            return;
+        }
+        let end = x.saturating_add(count).min(MAX_SIXEL_EDGE);
+        let bottom = (y + 6).min(MAX_SIXEL_EDGE);
+        let (width, height) = (end.max(self.width), bottom.max(self.height));
+        if !self.reserve(width, height) {
+            return;
+        }
+        self.width = width;
+        self.height = height;
+        for bit in 0..6 {
+            if bits & (1 << bit) != 0 && y + bit < bottom {
+                let row = (y + bit) * self.stride;
+                self.pixels[row + x..row + end].fill([rgb[0], rgb[1], rgb[2], 255]);
+            }
+        }
+    }
+
+    /// Makes room for `width × height` pixels. `false` when that is more
+    /// than [`MAX_SIXEL_PIXELS`].
+    fn reserve(&mut self, width: usize, height: usize) -> bool {
+        if width <= self.stride && height <= self.rows {
+            return true;
+        }
+        let doubled = (
+            width.max(self.stride * 2).min(MAX_SIXEL_EDGE),
+            height.max(self.rows * 2).min(MAX_SIXEL_EDGE),
+        );
+        let exact = (width.max(self.stride), height.max(self.rows));
+        let Some((stride, rows)) = [doubled, exact]
+            .into_iter()
+            .find(|(stride, rows)| stride * rows <= MAX_SIXEL_PIXELS)
+        else {
+            return false;
+        };
+        let mut grown = vec![[0u8; 4]; stride * rows];
+        for row in 0..self.height {
+            grown[row * stride..row * stride + self.width]
+                .copy_from_slice(&self.pixels[row * self.stride..row * self.stride + self.width]);
+        }
+        self.pixels = grown;
+        self.stride = stride;
+        self.rows = rows;
+        true
+    }
+
+    /// The drawn `width × height` pixels, row by row.
+    fn into_rgba(self) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(self.width * self.height * 4);
+        for row in 0..self.height {
+            let start = row * self.stride;
+            rgba.extend(self.pixels[start..start + self.width].iter().flatten());
+        }
+        rgba
+    }
+}
+
+fn sixel_params(data: &[u8], mut i: usize) -> (Vec<u32>, usize) {
+    let mut params = Vec::new();
+    let mut current: Option<u32> = None;
+    while let Some(&byte) = data.get(i) {
+        match byte {
+            b'0'..=b'9' => {
+                let digit = u32::from(byte - b'0');
+                current = Some(
+                    current
+                        .unwrap_or(0)
+                        .saturating_mul(10)
+                        .saturating_add(digit),
+                );
+            }
+            b';' => params.push(current.take().unwrap_or(0)),
+            _ => break,
+        }
+        i += 1;
+    }
+    if let Some(value) = current {
+        params.push(value);
+    }
+    (params, i)
+}
+
+/// Sixel HLS puts blue at 0°, red at 120° and green at 240°.
+fn hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 3] {
+    let h = ((hue + 240) % 360) as f32 / 360.0;
+    let l = lightness.min(100) as f32 / 100.0;
+    let s = saturation.min(100) as f32 / 100.0;
+    if s == 0.0 {
+        let v = (l * 255.0).round() as u8;
+        return [v, v, v];
+    }
+    let q = if l < 0.5 {
+        l * (1.0 + s)
+    } else {
+        l + s - l * s
+    };
+    let p = 2.0 * l - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.0);
+        let v = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+        (v * 255.0).round() as u8
+    };
+    [channel(h + 1.0 / 3.0), channel(h), channel(h - 1.0 / 3.0)]
+}
+
+/// Bytes that write `placement` over the cells right of and below the cursor
+/// at `start_column`, each tagged with its tile, and leave the cursor after the
+/// image's last line. Columns past the right margin are clipped.
+pub(crate) fn placement_cells(
+    placement: &ImagePlacement,
+    start_column: usize,
+    terminal_columns: usize,
+) -> Vec<u8> {
+    let columns = placement
+        .columns
+        .min(terminal_columns.saturating_sub(start_column))
+        .max(1);
+    let capacity = placement
+        .lines
+        .checked_mul(columns)
+        .and_then(|cells| cells.checked_mul(40))
+        .unwrap_or(0);
+    let mut out = Vec::with_capacity(capacity);
+    for row in 0..placement.lines {
+        for col in 0..columns {
+            let tile = ImageTile {
+                placement: placement.id,
+                col: col.min(u16::MAX as usize) as u16,
+                row: row.min(u16::MAX as usize) as u16,
+            };
+            out.extend_from_slice(format!("\x1b]8;;{}\x1b\\ ", tile.uri()).as_bytes());
+        }
+        out.extend_from_slice(b"\x1b]8;;\x1b\\");
+        if row + 1 < placement.lines {
+            // LF keeps the column and scrolls at the bottom margin; CHA then
+            // returns to the image's left edge.
+            out.extend_from_slice(format!("\n\x1b[{}G", start_column + 1).as_bytes());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = image::RgbaImage::from_pixel(width, height, image::Rgba([255, 0, 0, 255]));
+        let mut out = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut out, image::ImageFormat::Png)
+            .expect("encode png");
+        out.into_inner()
+    }
+
+    fn iterm_sequence(params: &str, data: &[u8]) -> Vec<u8> {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+        format!("\x1b]1337;File={params}:{encoded}\x07").into_bytes()
+    }
+
+    fn texts(segments: &[Segment<'_>]) -> Vec<u8> {
+        segments
+            .iter()
+            .filter_map(|s| match s {
+                Segment::Text(t) => Some(t.to_vec()),
+                Segment::Image(_) | Segment::Kitty(_) | Segment::Prompt(_) | Segment::Cwd(_) => {
+                    None
+                }
+            })
+            .flatten()
+            .collect()
+    }
+
+    #[test]
+    fn passes_ordinary_output_through() {
+        let mut parser = GraphicsParser::new();
+        let input = b"\x1b[31mred\x1b]0;title\x07\x1bP+q544e\x1b\\done";
+        let segments = parser.split(input);
+        assert_eq!(texts(&segments), input.to_vec());
+        assert!(matches!(segments[..], [Segment::Text(Cow::Borrowed(_))]));
+    }
+
+    #[test]
+    fn extracts_iterm_images_between_text() {
+        let mut parser = GraphicsParser::new();
+        let mut input = b"before ".to_vec();
+        input.extend(iterm_sequence("inline=1;width=4", &png(8, 8)));
+        input.extend(b" after");
+        let segments = parser.split(&input);
+        assert_eq!(segments.len(), 3);
+        let Segment::Image(request) = &segments[1] else {
+            panic!("expected an image");
+        };
+        assert_eq!((request.image.width, request.image.height), (8, 8));
+        assert_eq!(request.width, Dimension::Cells(4));
+        assert_eq!(texts(&segments), b"before  after".to_vec());
+    }
+
+    #[test]
+    fn reassembles_sequences_split_across_reads() {
+        let mut input = b"ab".to_vec();
+        input.extend(iterm_sequence("inline=1", &png(2, 2)));
+        input.extend(b"cd");
+        for cut in [3, 5, 12, input.len() - 3] {
+            let mut parser = GraphicsParser::new();
+            let mut segments = parser.split(&input[..cut]);
+            segments.extend(parser.split(&input[cut..]));
+            assert_eq!(texts(&segments), b"abcd".to_vec(), "cut at {cut}");
+            assert_eq!(
+                segments
+                    .iter()
+                    .filter(|s| matches!(s, Segment::Image(_)))
+                    .count(),
+                1
+            );
+        }
+    }
+
+    #[test]
+    fn returns_a_rejected_prefix_held_from_the_previous_read() {
+        let mut parser = GraphicsParser::new();
+        let mut segments = parser.split(b"x\x1b]13");
+        segments.extend(parser.split(b"4;y\x07z"));
+        assert_eq!(texts(&segments), b"x\x1b]134;y\x07z".to_vec());
+    }
+
+    #[test]
+    fn ignores_downloads_and_bad_data() {
+        let mut parser = GraphicsParser::new();
+        let mut input = iterm_sequence("name=eA==", &png(2, 2));
+        input.extend(iterm_sequence("inline=1", b"not an image"));
+        assert!(parser.split(&input).is_empty());
+    }
+
+    #[test]
+    fn decodes_sixel_with_repeats_and_palette() {
+        // Two bands: a red 3×6 block, then a 2×1 green line below it.
+        let image = decode_sixel(b"#1;2;100;0;0#1!3~-#2;2;0;100;0@@").unwrap();
+        assert_eq!((image.width, image.height), (3, 12));
+        let px = |x: usize, y: usize| {
+            let i = (y * 3 + x) * 4;
+            image.rgba[i..i + 4].to_vec()
+        };
+        assert_eq!(px(2, 5), vec![255, 0, 0, 255]);
+        assert_eq!(px(1, 6), vec![0, 255, 0, 255]);
+        assert_eq!(px(2, 6)[3], 0);
+        assert_eq!(px(0, 7)[3], 0);
+    }
+
+    #[test]
+    fn sixel_canvases_stay_within_their_caps() {
+        let image = decode_sixel(b"\"1;1;99999;99999!99999999~-~").unwrap();
+        assert_eq!((image.width, image.height), (MAX_SIXEL_EDGE as u32, 12));
+        assert_eq!(image.rgba.len(), MAX_SIXEL_EDGE * 12 * 4);
+
+        // A canvas past the pixel cap stops growing instead of allocating.
+        let mut tall = b"!4096~".to_vec();
+        tall.extend(b"-~".repeat(MAX_SIXEL_EDGE / 6));
+        let image = decode_sixel(&tall).unwrap();
+        assert!(image.width as usize * image.height as usize <= MAX_SIXEL_PIXELS);
+    }
+
+    #[test]
+    fn extracts_sixel_from_a_dcs_sequence() {
+        let mut parser = GraphicsParser::new();
+        let segments = parser.split(b"\x1bP0;1;0q\"1;1;2;6#0~~\x1b\\ok");
+        assert!(matches!(&segments[0], Segment::Image(r) if r.image.width == 2));
+        assert_eq!(texts(&segments), b"ok".to_vec());
+    }
+
+    #[test]
+    fn extracts_osc_133_prompt_marks() {
+        let mut parser = GraphicsParser::new();
+        let segments = parser.split(b"$\x1b]133;A\x07> \x1b]133;D;1\x1b\\ok");
+        assert!(matches!(
+            &segments[1],
+            Segment::Prompt(PromptEvent::PromptStart)
+        ));
+        assert!(matches!(
+            &segments[3],
+            Segment::Prompt(PromptEvent::CommandFinished)
+        ));
+        assert_eq!(texts(&segments), b"$> ok".to_vec());
+    }
+
+    #[test]
+    fn extracts_osc_7_working_directories() {
+        let mut parser = GraphicsParser::new();
+        let segments = parser.split(b"a\x1b]7;file://host/tmp/x%20y\x07b\x1b]7");
+        assert!(matches!(&segments[1], Segment::Cwd(path) if path == "/tmp/x y"));
+        assert_eq!(texts(&segments), b"ab".to_vec());
+
+        // Split across reads, ST-terminated, and OSC 77 left alone.
+        let segments = parser.split(b";file:///home\x1b\\\x1b]77;x\x07");
+        assert!(matches!(&segments[0], Segment::Cwd(path) if path == "/home"));
+        assert_eq!(texts(&segments), b"\x1b]77;x\x07".to_vec());
+    }
+
+    #[test]
+    fn extracts_kitty_commands_from_apc_strings() {
+        let mut parser = GraphicsParser::new();
+        let mut segments = parser.split(b"a\x1b_Ga=p,i=3\x1b");
+        segments.extend(parser.split(b"\\b\x1b_other\x1b\\"));
+        assert!(matches!(&segments[1], Segment::Kitty(c) if c.image_id == 3));
+        assert_eq!(texts(&segments), b"ab\x1b_other\x1b\\".to_vec());
+    }
+
+    #[test]
+    fn sixel_hls_uses_blue_at_zero_degrees() {
+        assert_eq!(hls_to_rgb(0, 50, 100), [0, 0, 255]);
+        assert_eq!(hls_to_rgb(120, 50, 100), [255, 0, 0]);
+        assert_eq!(hls_to_rgb(240, 50, 100), [0, 255, 0]);
+    }
+
+    #[test]
+    fn placement_keeps_pixel_size_and_fits_the_width() {
+        let placement = ImageRequest::natural(Arc::new(ImageData::new(
+            25,
+            40,
+            vec![0; 25 * 40 * 4],
+        )))
+        .place([10.0, 20.0], 80, 24);
+        assert_eq!((placement.columns, placement.lines), (3, 2));
+        assert_eq!(placement.fill, [25.0 / 30.0, 1.0]);
+
+        let wide =
+            ImageRequest::natural(Arc::new(ImageData::new(2000, 100, vec![0; 2000 * 100 * 4])))
+                .place([10.0, 20.0], 80, 24);
+        assert_eq!((wide.columns, wide.lines), (80, 2));
+    }
+
+    #[test]
+    fn placement_honours_requested_cells_and_aspect() {
+        let request = |width, height, preserve_aspect| ImageRequest {
+            width,
+            height,
+            preserve_aspect,
+            ..ImageRequest::natural(Arc::new(ImageData::new(100, 50, vec![0; 100 * 50 * 4])))
+        };
+        let p = request(Dimension::Cells(10), Dimension::Auto, true).place([10.0, 10.0], 80, 24);
+        assert_eq!((p.columns, p.lines), (10, 5));
+        let p =
+            request(Dimension::Cells(10), Dimension::Cells(10), false).place([10.0, 10.0], 80, 24);
+        assert_eq!((p.columns, p.lines), (10, 10));
+        let p = request(Dimension::Percent(50), Dimension::Auto, true).place([10.0, 10.0], 80, 24);
+        assert_eq!(p.columns, 40);
+    }
+
+    #[test]
+    fn requested_sizes_are_bounded_by_the_terminal() {
+        let request = |width, height, preserve_aspect| ImageRequest {
+            width,
+            height,
+            preserve_aspect,
+            ..ImageRequest::natural(Arc::new(ImageData::new(100, 50, vec![0; 100 * 50 * 4])))
+        };
+        let p = request(Dimension::Cells(99_999_999), Dimension::Auto, true).place(
+            [10.0, 10.0],
+            80,
+            24,
+        );
+        assert_eq!((p.columns, p.lines), (48, 24));
+        let p = request(
+            Dimension::Pixels(u32::MAX),
+            Dimension::Cells(u32::MAX),
+            false,
+        )
+        .place([10.0, 10.0], 80, 24);
+        assert_eq!((p.columns, p.lines), (80, 24));
+        assert!(placement_cells(&p, 0, 80).len() < 80 * 24 * 64);
+    }
+
+    #[test]
+    fn placement_maps_the_source_rectangle_to_texture_coordinates() {
+        let placement = ImageRequest {
+            source: Some([10, 0, 50, 0]),
+            ..ImageRequest::natural(Arc::new(ImageData::new(100, 50, vec![0; 100 * 50 * 4])))
+        }
+        .place([10.0, 10.0], 80, 24);
+        assert_eq!(placement.source, [0.1, 0.0, 0.6, 1.0]);
+        assert_eq!((placement.columns, placement.lines), (5, 5));
+    }
+
+    #[test]
+    fn tile_uris_round_trip() {
+        let tile = ImageTile {
+            placement: 42,
+            col: 3,
+            row: 7,
+        };
+        assert_eq!(ImageTile::from_uri(&tile.uri()), Some(tile));
+        assert_eq!(ImageTile::from_uri("https://example.com"), None);
+        assert_eq!(ImageTile::from_uri("rabbitty-image:1/2"), None);
+    }
+}
@@ -0,0 +1,152 @@
+//! Command history for autocomplete: the shell's own history file, read
+//! once, and the commands the shell ran in the session. Nothing leaves the
+//! machine.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Most recent entries kept from the history file.
+const MAX_ENTRIES: usize = 10_000;
+
+/// The user's shell history, oldest first, each command once at its most
+/// recent position. Empty when no history file is found.
+pub fn load() -> Vec<String> {
+    let Some(path) = history_path() else {
+        return Vec::new();
+    };
+    let Ok(bytes) = std::fs::read(&path) else {
+        return Vec::new();
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    let fish = path.file_name().is_some_and(|name| name == "fish_history");
+    let entries = if fish {
+        parse_fish(&text)
+    } else {
+        parse_lines(&text)
+    };
+    dedup(entries)
+}
+
+/// The most recent command that continues `typed`, looking at the
+/// session's commands before the history file's.
+pub fn suggest<'a>(typed: &str, session: &'a [String], file: &'a [String]) -> Option<&'a str> {
+    if typed.trim().is_empty() {
+        return None;
+    }
+    session
+        .iter()
+        .rev()
+        .chain(file.iter().rev())
+        .map(String::as_str)
+        .find(|command| command.len() > typed.len() && command.starts_with(typed))
+}
+
+/// `$HISTFILE` when the GUI inherited it, otherwise the default file of
+/// the login shell, otherwise the first history file that exists.
+fn history_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("HISTFILE").map(PathBuf::from)
+        && path.is_file()
+    {
+        return Some(path);
+    }
+    let home = dirs::home_dir()?;
+    let zsh = home.join(".zsh_history");
+    let bash = home.join(".bash_history");
+    let fish = dirs::data_dir()
+        .unwrap_or_else(|| home.join(".local/share"))
+        .join("fish")
+        .join("fish_history");
+    let shell = crate::platform::shell::login_shell().unwrap_or_default();
+    let mut candidates = if shell.ends_with("fish") {
+        vec![fish, zsh, bash]
+    } else if shell.ends_with("bash") {
+        vec![bash, zsh, fish]
+    } else {
+        vec![zsh, bash, fish]
+    };
+    #[cfg(target_os = "windows")]
+    if let Some(roaming) = dirs::config_dir() {
+        candidates.insert(
+            0,
+            roaming.join(r"Microsoft\Windows\PowerShell\PSReadLine\ConsoleHost_history.txt"),
+        );
+    }
+    candidates.retain(|path| path.is_file());
+    candidates.into_iter().next()
+}
+
+/// One command per line, as bash and PowerShell write them. Also reads
+/// zsh's extended format (`: <time>:<duration>;command`) and skips bash's
+/// `#<time>` lines.
+fn parse_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .filter(|line| !is_bash_timestamp(line))
+        .map(|line| match line.strip_prefix(": ") {
+            Some(rest) => rest.split_once(';').map_or(line, |(_, command)| command),
+            None => line,
+        })
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_bash_timestamp(line: &str) -> bool {
+    line.strip_prefix('#')
+        .is_some_and(|rest| !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// fish keeps a YAML-like list of `- cmd: …` entries.
+fn parse_fish(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(|line| line.strip_prefix("- cmd: "))
+        .map(|command| command.replace("\\n", "\n").replace("\\\\", "\\"))
+        .filter(|command| !command.trim().is_empty())
+        .collect()
+}
+
+fn dedup(entries: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut out: Vec<String> = entries
+        .into_iter()
+        .rev()
+        .filter(|entry| seen.insert(entry.clone()))
+        .take(MAX_ENTRIES)
+        .collect();
+    out.reverse();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_bash_zsh_and_fish_history() {
+        let bash = "#1700000000\ngit status\ncargo build\n";
+        assert_eq!(parse_lines(bash), ["git status", "cargo build"]);
+
+        let zsh = ": 1700000000:0;git status\n: 1700000001:3;make test\n";
+        assert_eq!(parse_lines(zsh), ["git status", "make test"]);
+
+        let fish = "- cmd: ls -la\n  when: 1700000000\n- cmd: echo a\\nb\n";
+        assert_eq!(parse_fish(fish), ["ls -la", "echo a\nb"]);
+    }
+
+    #[test]
+    fn suggests_the_most_recent_match_preferring_the_session() {
+        let file = dedup(vec![
+            "git status".to_string(),
+            "git push".to_string(),
+            "git status".to_string(),
+        ]);
+        assert_eq!(file, ["git push", "git status"]);
+        assert_eq!(suggest("git s", &[], &file), Some("git status"));
+        assert_eq!(suggest("git ", &[], &file), Some("git status"));
+
+        let session = vec!["git stash".to_string()];
+        assert_eq!(suggest("git s", &session, &file), Some("git stash"));
+        assert_eq!(suggest("git status", &session, &file), None);
+        assert_eq!(suggest("  ", &session, &file), None);
+    }
+}
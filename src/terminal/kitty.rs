@@ -0,0 +1,548 @@
+use super::TerminalSize;
+use super::graphics::{Dimension, ImageData, ImageRequest, MAX_EDGE};
+use base64::Engine as _;
+use std::collections::{HashMap, VecDeque};
+use std::io::Read;
+use std::sync::Arc;
+
+/// Transmitted images kept per terminal; the oldest are evicted past this.
+const STORE_BUDGET: usize = 320 * 1024 * 1024;
+/// Largest chunked transmission accepted, in base64 bytes.
+const MAX_TRANSMISSION: usize = 64 * 1024 * 1024;
+/// Ids the terminal picks for images sent without one count down from here,
+/// away from the small ids programs choose.
+const FIRST_AUTO_ID: u32 = u32::MAX;
+
+/// One `APC G … ST` command of the kitty graphics protocol.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KittyCommand {
+    pub action: u8,
+    pub quiet: u8,
+    pub format: u32,
+    pub medium: u8,
+    pub compression: u8,
+    pub width: u32,
+    pub height: u32,
+    pub image_id: u32,
+    pub image_number: u32,
+    pub placement_id: u32,
+    pub more: bool,
+    /// Source rectangle `x, y, w, h` in pixels; zero width/height means "to
+    /// the edge".
+    pub source: [u32; 4],
+    pub columns: u32,
+    pub rows: u32,
+    pub z_index: i32,
+    pub keep_cursor: bool,
+    pub virtual_placement: bool,
+    pub delete: u8,
+    /// Still base64-encoded.
+    pub payload: Vec<u8>,
+}
+
+impl Default for KittyCommand {
+    fn default() -> Self {
+        Self {
+            action: b't',
+            quiet: 0,
+            format: 32,
+            medium: b'd',
+            compression: 0,
+            width: 0,
+            height: 0,
+            image_id: 0,
+            image_number: 0,
+            placement_id: 0,
+            more: false,
+            source: [0; 4],
+            columns: 0,
+            rows: 0,
+            z_index: 0,
+            keep_cursor: false,
+            virtual_placement: false,
+            delete: b'a',
+            payload: Vec::new(),
+        }
+    }
+}
+
+impl KittyCommand {
+    /// Parses the body of the APC string after the leading `G`:
+    /// `key=value,…;payload`. Unknown keys are ignored.
+    pub fn parse(body: &[u8]) -> Option<Self> {
+        let (control, payload) = match body.iter().position(|&b| b == b';') {
+            Some(split) => (&body[..split], &body[split + 1..]),
+            None => (body, &[][..]),
+        };
+        let control = std::str::from_utf8(control).ok()?;
+        let mut cmd = Self {
+            payload: payload.to_vec(),
+            ..Self::default()
+        };
+        for pair in control.split(',').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=')?;
+            let letter = || value.bytes().next().filter(|_| value.len() == 1);
+            let number = || value.parse::<u32>().ok();
+            match key {
+                "a" => cmd.action = letter()?,
+                "q" => cmd.quiet = number()?.min(2) as u8,
+                "f" => cmd.format = number()?,
+                "t" => cmd.medium = letter()?,
+                "o" => cmd.compression = letter()?,
+                "s" => cmd.width = number()?,
+                "v" => cmd.height = number()?,
+                "i" => cmd.image_id = number()?,
+                "I" => cmd.image_number = number()?,
+                "p" => cmd.placement_id = number()?,
+                "m" => cmd.more = number()? == 1,
+                "x" => cmd.source[0] = number()?,
+                "y" => cmd.source[1] = number()?,
+                "w" => cmd.source[2] = number()?,
+                "h" => cmd.source[3] = number()?,
+                "c" => cmd.columns = number()?,
+                "r" => cmd.rows = number()?,
+                "z" => cmd.z_index = value.parse().ok()?,
+                "C" => cmd.keep_cursor = number()? == 1,
+                "U" => cmd.virtual_placement = number()? == 1,
+                "d" => cmd.delete = letter()?,
+                _ => {}
+            }
+        }
+        Some(cmd)
+    }
+}
+
+/// Which placements a delete command (`a=d`) removes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteTarget {
+    /// Every placement on screen.
+    Visible,
+    /// Placements of an image, optionally only the one with this placement id.
+    Image {
+        image: u32,
+        placement: Option<u32>,
+    },
+    /// Placements of any image whose id is in the range.
+    Range {
+        first: u32,
+        last: u32,
+    },
+    /// Placements covering a cell; 0-based.
+    Cell {
+        column: usize,
+        line: usize,
+    },
+    AtCursor,
+    Column(usize),
+    Line(usize),
+    ZIndex(i32),
+}
+
+#[derive(Debug)]
+pub enum KittyAction {
+    /// Show an image at the cursor.
+    Place {
+        request: ImageRequest,
+        ids: (u32, u32),
+        move_cursor: bool,
+    },
+    /// Remove placements; `free` also drops the images' data once no
+    /// placement shows them.
+    Delete { target: DeleteTarget, free: bool },
+    /// Bytes to send back to the program.
+    Reply(String),
+}
+
+/// The terminal side of the kitty graphics protocol: the id-based image store
+/// and chunked-transmission state. Placements themselves live in the grid.
+#[derive(Debug, Default)]
+pub struct KittyGraphics {
+    images: HashMap<u32, Arc<ImageData>>,
+    /// Image ids, oldest transmission first.
+    order: VecDeque<u32>,
+    /// Image number (`I=`) → the id of the latest image sent with it.
+    numbers: HashMap<u32, u32>,
+    /// The first chunk of a transmission whose `m=1` chunks are still arriving.
+    pending: Option<KittyCommand>,
+    auto_ids: u32,
+}
+
+impl KittyGraphics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle(&mut self, cmd: KittyCommand) -> Vec<KittyAction> {
+        let cmd = if let Some(mut first) = self.pending.take() {
+            // Follow-up chunks carry only `m` (and maybe `q`) plus data.
+            if first.payload.len() + cmd.payload.len() > MAX_TRANSMISSION {
+                return self.reply(&first, Err("EFBIG:transmission too large".into()));
+            }
+            first.payload.extend_from_slice(&cmd.payload);
+            if cmd.more {
+                self.pending = Some(first);
+                return Vec::new();
+            }
+            first
+        } else if cmd.more && matches!(cmd.action, b't' | b'T' | b'q') {
+            self.pending = Some(cmd);
+            return Vec::new();
+        } else {
+            cmd
+        };
+
+        match cmd.action {
+            b't' | b'T' | b'q' => self.transmit(cmd),
+            b'p' => self.put(cmd),
+            b'd' => delete_action(&cmd, self.resolve(&cmd))
+                .into_iter()
+                .collect(),
+            _ => self.reply(&cmd, Err("EINVAL:unsupported action".into())),
+        }
+    }
+
+    /// Drops an image's data, e.g. after its last placement was deleted with
+    /// an uppercase delete command.
+    pub fn forget(&mut self, id: u32) {
+        self.images.remove(&id);
+        self.order.retain(|&other| other != id);
+        self.numbers.retain(|_, &mut other| other != id);
+    }
+
+    fn transmit(&mut self, mut cmd: KittyCommand) -> Vec<KittyAction> {
+        let image = match decode(&cmd) {
+            Ok(image) => Arc::new(image),
+            Err(err) => return self.reply(&cmd, Err(err)),
+        };
+        if cmd.action == b'q' {
+            return self.reply(&cmd, Ok(()));
+        }
+
+        // Anonymous transmissions get an id but, as before, no reply.
+        let anonymous = cmd.image_id == 0 && cmd.image_number == 0;
+        if cmd.image_id == 0 {
+            cmd.image_id = FIRST_AUTO_ID - self.auto_ids;
+            self.auto_ids = (self.auto_ids + 1) % (FIRST_AUTO_ID / 2);
+        }
+        if cmd.image_number != 0 {
+            self.numbers.insert(cmd.image_number, cmd.image_id);
+        }
+        self.store(cmd.image_id, Arc::clone(&image));
+
+        let mut actions = Vec::new();
+        if cmd.action == b'T' {
+            actions.extend(place_action(&cmd, cmd.image_id, image));
+        }
+        if !anonymous {
+            actions.extend(self.reply(&cmd, Ok(())));
+        }
+        actions
+    }
+
+    fn put(&mut self, cmd: KittyCommand) -> Vec<KittyAction> {
+        let Some(id) = self.resolve(&cmd) else {
+            return self.reply(&cmd, Err("ENOENT:no such image".into()));
+        };
+        let Some(image) = self.images.get(&id).cloned() else {
+            return self.reply(&cmd, Err("ENOENT:no such image".into()));
+        };
+        let mut actions: Vec<_> = place_action(&cmd, id, image).into_iter().collect();
+        actions.extend(self.reply(&cmd, Ok(())));
+        actions
+    }
+
+    fn resolve(&self, cmd: &KittyCommand) -> Option<u32> {
+        if cmd.image_id != 0 {
+            Some(cmd.image_id)
+        } else if cmd.image_number != 0 {
+            self.numbers.get(&cmd.image_number).copied()
+        } else {
+            None
+        }
+    }
+
+    fn store(&mut self, id: u32, image: Arc<ImageData>) {
+        self.order.retain(|&other| other != id);
+        self.order.push_back(id);
+        self.images.insert(id, image);
+
+        let mut total: usize = self.images.values().map(|i| i.rgba.len()).sum();
+        while total > STORE_BUDGET && self.order.len() > 1 {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = self.images.remove(&oldest) {
+                total -= evicted.rgba.len();
+            }
+        }
+    }
+
+    /// A response is only sent when the program identified the image, and
+    /// `q=1` silences successes, `q=2` everything.
+    fn reply(&self, cmd: &KittyCommand, result: Result<(), String>) -> Vec<KittyAction> {
+        let silenced = match result {
+            Ok(()) => cmd.quiet >= 1,
+            Err(_) => cmd.quiet >= 2,
+        };
+        if silenced || (cmd.image_id == 0 && cmd.image_number == 0) {
+            return Vec::new();
+        }
+        let mut keys = vec![format!("i={}", cmd.image_id)];
+        if cmd.image_number != 0 {
+            keys.push(format!("I={}", cmd.image_number));
+        }
+        if cmd.placement_id != 0 {
+            keys.push(format!("p={}", cmd.placement_id));
+        }
+        let message = result.err().unwrap_or_else(|| "OK".to_string());
+        vec![KittyAction::Reply(format!(
+            "\x1b_G{};{message}\x1b\\",
+            keys.join(",")
+        ))]
+    }
+}
+
+fn place_action(cmd: &KittyCommand, id: u32, image: Arc<ImageData>) -> Option<KittyAction> {
+    if cmd.virtual_placement {
+        // Unicode placeholders are drawn by the program as text; not supported.
+        return None;
+    }
+    // No grid is larger than the biggest a pane gets; placing bounds the
+    // size again by the pane's own grid.
+    let cells = |n: u32, max: usize| match n {
+        0 => Dimension::Auto,
+        n => Dimension::Cells(n.min(max as u32)),
+    };
+    let cropped = cmd.source != [0; 4];
+    Some(KittyAction::Place {
+        request: ImageRequest {
+            source: cropped.then_some(cmd.source),
+            width: cells(cmd.columns, TerminalSize::MAX_COLUMNS),
+            height: cells(cmd.rows, TerminalSize::MAX_LINES),
+            preserve_aspect: cmd.columns == 0 || cmd.rows == 0,
+            z_index: cmd.z_index,
+            ..ImageRequest::natural(image)
+        },
+        ids: (id, cmd.placement_id),
+        move_cursor: !cmd.keep_cursor,
+    })
+}
+
+fn delete_action(cmd: &KittyCommand, image: Option<u32>) -> Option<KittyAction> {
+    let [x, y, ..] = cmd.source;
+    let cell = |n: u32| n.saturating_sub(1) as usize;
+    let target = match cmd.delete.to_ascii_lowercase() {
+        b'a' => DeleteTarget::Visible,
+        b'i' | b'n' => DeleteTarget::Image {
+            image: image?,
+            placement: (cmd.placement_id != 0).then_some(cmd.placement_id),
+        },
+        b'r' => DeleteTarget::Range { first: x, last: y },
+        b'c' => DeleteTarget::AtCursor,
+        b'p' | b'q' => DeleteTarget::Cell {
+            column: cell(x),
+            line: cell(y),
+        },
+        b'x' => DeleteTarget::Column(cell(x)),
+        b'y' => DeleteTarget::Line(cell(y)),
+        b'z' => DeleteTarget::ZIndex(cmd.z_index),
+        _ => return None,
+    };
+    Some(KittyAction::Delete {
+        target,
+        free: cmd.delete.is_ascii_uppercase(),
+    })
+}
+
+fn decode(cmd: &KittyCommand) -> Result<ImageData, String> {
+    if cmd.medium != b'd' {
+        return Err("EINVAL:only direct transmission is supported".into());
+    }
+    let encoded: Vec<u8> = cmd
+        .payload
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    let mut data = base64::engine::general_purpose::STANDARD
+        .decode(&encoded)
+        .map_err(|_| "EINVAL:bad base64 data".to_string())?;
+    match cmd.compression {
+        0 => {}
+        b'z' => {
+            let mut inflated = Vec::new();
+            flate2::read::ZlibDecoder::new(data.as_slice())
+                .take(MAX_TRANSMISSION as u64 * 4)
+                .read_to_end(&mut inflated)
+                .map_err(|_| "EINVAL:bad zlib data".to_string())?;
+            data = inflated;
+        }
+        _ => return Err("EINVAL:unsupported compression".into()),
+    }
+
+    match cmd.format {
+        100 => ImageData::from_encoded(&data).ok_or_else(|| "EBADPNG:cannot decode".into()),
+        24 | 32 => {
+            let (width, height) = (cmd.width, cmd.height);
+            if width == 0 || height == 0 || width > MAX_EDGE || height > MAX_EDGE {
+                return Err("EINVAL:bad image size".into());
+            }
+            let channels = (cmd.format / 8) as usize;
+            let needed = width as usize * height as usize * channels;
+            if data.len() < needed {
+                return Err("ENODATA:insufficient image data".into());
+            }
+            let rgba = if channels == 4 {
+                data.truncate(needed);
+                data
+            } else {
+                data[..needed]
+                    .chunks_exact(3)
+                    .flat_map(|px| [px[0], px[1], px[2], 255])
+                    .collect()
+            };
+            Ok(ImageData::new(width, height, rgba))
+        }
+        _ => Err("EINVAL:unsupported format".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(control: &str, data: &[u8]) -> KittyCommand {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+        KittyCommand::parse(format!("{control};{encoded}").as_bytes()).unwrap()
+    }
+
+    fn replies(actions: &[KittyAction]) -> Vec<&str> {
+        actions
+            .iter()
+            .filter_map(|a| match a {
+                KittyAction::Reply(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_control_keys() {
+        let cmd = KittyCommand::parse(b"a=T,f=100,i=7,p=2,c=10,r=4,z=-1,C=1,q=1;AAAA").unwrap();
+        assert_eq!(cmd.action, b'T');
+        assert_eq!(cmd.format, 100);
+        assert_eq!((cmd.image_id, cmd.placement_id), (7, 2));
+        assert_eq!((cmd.columns, cmd.rows, cmd.z_index), (10, 4, -1));
+        assert!(cmd.keep_cursor);
+        assert_eq!(cmd.quiet, 1);
+        assert_eq!(cmd.payload, b"AAAA");
+        assert_eq!(KittyCommand::parse(b"a=T,i"), None);
+    }
+
+    #[test]
+    fn transmits_raw_rgb_and_replies_ok() {
+        let mut kitty = KittyGraphics::new();
+        let actions = kitty.handle(command("a=t,f=24,s=2,v=1,i=5", &[1, 2, 3, 4, 5, 6]));
+        assert_eq!(replies(&actions), vec!["\x1b_Gi=5;OK\x1b\\"]);
+        let image = &kitty.images[&5];
+        assert_eq!(image.rgba, vec![1, 2, 3, 255, 4, 5, 6, 255]);
+    }
+
+    #[test]
+    fn assembles_chunked_transmissions() {
+        let mut kitty = KittyGraphics::new();
+        let pixels = [9u8; 16];
+        let encoded = base64::engine::general_purpose::STANDARD.encode(pixels);
+        let (head, tail) = encoded.split_at(8);
+        let first = KittyCommand::parse(format!("a=T,f=32,s=2,v=2,i=3,m=1;{head}").as_bytes());
+        assert!(kitty.handle(first.unwrap()).is_empty());
+        let last = KittyCommand::parse(format!("m=0;{tail}").as_bytes()).unwrap();
+        let actions = kitty.handle(last);
+        assert!(matches!(
+            actions[0],
+            KittyAction::Place {
+                ids: (3, 0),
+                move_cursor: true,
+                ..
+            }
+        ));
+        assert_eq!(replies(&actions), vec!["\x1b_Gi=3;OK\x1b\\"]);
+    }
+
+    #[test]
+    fn puts_stored_images_by_id_and_number() {
+        let mut kitty = KittyGraphics::new();
+        kitty.handle(command("a=t,f=32,s=1,v=1,I=4,q=2", &[0, 0, 0, 255]));
+        let actions = kitty.handle(command("a=p,I=4,c=3,z=-2", &[]));
+        let KittyAction::Place { request, .. } = &actions[0] else {
+            panic!("expected a placement");
+        };
+        assert_eq!(request.width, Dimension::Cells(3));
+        assert_eq!(request.z_index, -2);
+
+        let huge = kitty.handle(command("a=p,I=4,c=4294967295,r=4294967295", &[]));
+        let Some(KittyAction::Place { request, .. }) = huge.into_iter().next() else {
+            panic!("expected a placement");
+        };
+        assert_eq!(
+            request.width,
+            Dimension::Cells(TerminalSize::MAX_COLUMNS as u32)
+        );
+        assert_eq!(
+            request.height,
+            Dimension::Cells(TerminalSize::MAX_LINES as u32)
+        );
+        let placement = request.place([10.0, 20.0], 80, 24);
+        assert_eq!((placement.columns, placement.lines), (80, 24));
+
+        let missing = kitty.handle(command("a=p,i=99", &[]));
+        assert_eq!(
+            replies(&missing),
+            vec!["\x1b_Gi=99;ENOENT:no such image\x1b\\"]
+        );
+    }
+
+    #[test]
+    fn queries_do_not_store_and_errors_respect_quiet() {
+        let mut kitty = KittyGraphics::new();
+        let ok = kitty.handle(command("a=q,f=24,s=1,v=1,i=31", &[1, 2, 3]));
+        assert_eq!(replies(&ok), vec!["\x1b_Gi=31;OK\x1b\\"]);
+        assert!(kitty.images.is_empty());
+
+        let file = kitty.handle(command("a=q,t=f,i=31", b"/tmp/x.png"));
+        assert!(replies(&file)[0].contains("EINVAL"));
+        assert!(kitty.handle(command("a=q,t=f,i=31,q=2", b"/x")).is_empty());
+        assert!(kitty.handle(command("a=q,t=f", b"/x")).is_empty());
+    }
+
+    #[test]
+    fn decodes_delete_targets() {
+        let mut kitty = KittyGraphics::new();
+        let delete = |kitty: &mut KittyGraphics, control: &str| match kitty
+            .handle(command(control, &[]))
+            .pop()
+        {
+            Some(KittyAction::Delete { target, free }) => Some((target, free)),
+            _ => None,
+        };
+        assert_eq!(
+            delete(&mut kitty, "a=d"),
+            Some((DeleteTarget::Visible, false))
+        );
+        assert_eq!(
+            delete(&mut kitty, "a=d,d=I,i=4,p=2"),
+            Some((
+                DeleteTarget::Image {
+                    image: 4,
+                    placement: Some(2)
+                },
+                true
+            ))
+        );
+        assert_eq!(
+            delete(&mut kitty, "a=d,d=p,x=3,y=1"),
+            Some((DeleteTarget::Cell { column: 2, line: 0 }, false))
+        );
+        assert_eq!(delete(&mut kitty, "a=d,d=n,I=9"), None);
+    }
+}
@@ -0,0 +1,151 @@
+//! Scrollback bookmarks. Marks are kept by line number, counted from the
+//! first line the primary screen ever scrolled into history, so they keep
+//! pointing at the same text while old lines are trimmed away.
+
+/// Why a line was marked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkKind {
+    /// Dropped by the user.
+    User,
+    /// A shell prompt reported through OSC 133.
+    Prompt,
+    /// A trigger with the `mark` action matched the line.
+    Trigger,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mark {
+    pub line: u64,
+    pub kind: MarkKind,
+}
+
+/// The OSC 133 (FinalTerm) semantic prompt sequences shells emit around
+/// prompts and command output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptEvent {
+    /// `A`: a prompt is about to be drawn.
+    PromptStart,
+    /// `B`: the prompt ended; the user types the command from here.
+    CommandStart,
+    /// `C`: the command was submitted and its output follows.
+    OutputStart,
+    /// `D`: the command finished.
+    CommandFinished,
+}
+
+impl PromptEvent {
+    /// Parses the part of `OSC 133 ; …` after the `;`. Trailing options such
+    /// as `D;exit-code` or `A;aid=…` are ignored.
+    pub fn parse(payload: &[u8]) -> Option<Self> {
+        let kind = payload.split(|&b| b == b';').next()?;
+        match kind {
+            b"A" => Some(Self::PromptStart),
+            b"B" => Some(Self::CommandStart),
+            b"C" => Some(Self::OutputStart),
+            b"D" => Some(Self::CommandFinished),
+            _ => None,
+        }
+    }
+}
+
+/// Marks ordered by line, at most one per line.
+#[derive(Debug, Default)]
+pub struct Marks {
+    marks: Vec<Mark>,
+}
+
+impl Marks {
+    /// Adds `mark` unless its line is already marked.
+    pub fn insert(&mut self, mark: Mark) {
+        if let Err(index) = self.search(mark.line) {
+            self.marks.insert(index, mark);
+        }
+    }
+
+    /// Removes the mark on `line`, or adds a user mark when there is none.
+    pub fn toggle(&mut self, line: u64) {
+        match self.search(line) {
+            Ok(index) => {
+                self.marks.remove(index);
+            }
+            Err(index) => self.marks.insert(
+                index,
+                Mark {
+                    line,
+                    kind: MarkKind::User,
+                },
+            ),
+        }
+    }
+
+    /// Drops marks on lines that were trimmed from the scrollback.
+    pub fn forget_before(&mut self, line: u64) {
+        let keep = self.marks.partition_point(|mark| mark.line < line);
+        self.marks.drain(..keep);
+    }
+
+    /// The closest mark above `line`.
+    pub fn before(&self, line: u64) -> Option<Mark> {
+        let index = self.marks.partition_point(|mark| mark.line < line);
+        index.checked_sub(1).map(|index| self.marks[index])
+    }
+
+    /// The closest mark below `line`.
+    pub fn after(&self, line: u64) -> Option<Mark> {
+        let index = self.marks.partition_point(|mark| mark.line <= line);
+        self.marks.get(index).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Mark> {
+        self.marks.iter()
+    }
+
+    fn search(&self, line: u64) -> Result<usize, usize> {
+        self.marks.binary_search_by_key(&line, |mark| mark.line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mark(line: u64, kind: MarkKind) -> Mark {
+        Mark { line, kind }
+    }
+
+    #[test]
+    fn prompt_events_ignore_trailing_options() {
+        assert_eq!(PromptEvent::parse(b"A"), Some(PromptEvent::PromptStart));
+        assert_eq!(
+            PromptEvent::parse(b"D;127"),
+            Some(PromptEvent::CommandFinished)
+        );
+        assert_eq!(
+            PromptEvent::parse(b"A;aid=42"),
+            Some(PromptEvent::PromptStart)
+        );
+        assert_eq!(PromptEvent::parse(b"Z"), None);
+        assert_eq!(PromptEvent::parse(b""), None);
+    }
+
+    #[test]
+    fn marks_stay_sorted_and_navigate_by_line() {
+        let mut marks = Marks::default();
+        marks.insert(mark(30, MarkKind::Prompt));
+        marks.insert(mark(10, MarkKind::Trigger));
+        marks.toggle(20);
+        marks.insert(mark(20, MarkKind::Prompt));
+
+        let lines: Vec<u64> = marks.iter().map(|mark| mark.line).collect();
+        assert_eq!(lines, vec![10, 20, 30]);
+        assert_eq!(marks.before(20), Some(mark(10, MarkKind::Trigger)));
+        assert_eq!(marks.after(20), Some(mark(30, MarkKind::Prompt)));
+        assert_eq!(marks.before(10), None);
+        assert_eq!(marks.after(30), None);
+
+        marks.toggle(20);
+        marks.forget_before(15);
+        let lines: Vec<u64> = marks.iter().map(|mark| mark.line).collect();
+        assert_eq!(lines, vec![30]);
+    }
+}
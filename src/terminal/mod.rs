@@ -1,6 +1,26 @@
+pub mod assist;
+#[cfg(test)]
+mod conformance;
+pub mod copy_mode;
 mod engine;
 pub mod font;
+pub mod graphics;
+pub mod history;
+pub mod kitty;
+pub mod marks;
+pub mod passthrough;
+pub mod paste;
+pub mod path;
+pub mod prediction;
+pub mod queries;
+pub mod recording;
+pub mod redact;
+mod repaint;
+mod semantic;
+mod snapshot;
 pub mod theme;
+pub mod timestamps;
+pub mod trigger;
 pub mod url;
 
 pub use engine::TerminalEngine;
@@ -15,9 +35,22 @@ pub struct TerminalSize {
 }
 
 impl TerminalSize {
+    /// Widest grid a pane gets, however large the window or small the font.
+    pub const MAX_COLUMNS: usize = 1000;
+    /// Tallest grid a pane gets.
+    pub const MAX_LINES: usize = 500;
+
     pub const fn new(columns: usize, lines: usize) -> Self {
         Self { columns, lines }
     }
+
+    /// Keeps both dimensions between one cell and the maxima.
+    pub fn clamped(self) -> Self {
+        Self {
+            columns: self.columns.clamp(1, Self::MAX_COLUMNS),
+            lines: self.lines.clamp(1, Self::MAX_LINES),
+        }
+    }
 }
 
 impl Dimensions for TerminalSize {
@@ -152,11 +185,36 @@ mod selection_tests {
 #[derive(Debug, Clone)]
 pub struct CellVisual {
     pub ch: char,
+    /// The whole grapheme cluster when `ch` carries combining marks, joiners or
+    /// variation selectors; `None` for the common single-character cell.
+    pub grapheme: Option<std::sync::Arc<str>>,
     pub col: usize,
     pub row: usize,
     pub fg: [f32; 4],
     pub bg: [f32; 4],
     pub underline: bool,
+    pub bold: bool,
+    pub italic: bool,
     pub wide: bool,
     pub hyperlink: Option<std::sync::Arc<str>>,
+    pub image: Option<graphics::ImageTile>,
+}
+
+/// `text` as a quoted JSON string, with control characters escaped.
+pub(crate) fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch.is_control() => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
 }
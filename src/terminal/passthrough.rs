@@ -0,0 +1,140 @@
+//! Unwraps tmux passthrough: `DCS tmux ; <sequence> ST`, with every ESC of
+//! the wrapped sequence doubled. Programs inside tmux use it to reach the
+//! outer terminal with sequences tmux would otherwise swallow, OSC 52
+//! clipboard writes and notifications among them. The wrapped sequence
+//! replaces the wrapper in the output, so it goes through the same handlers
+//! as one printed outside tmux.
+
+use std::borrow::Cow;
+
+const INTRO: &[u8] = b"\x1bPtmux;";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    /// Matched this many bytes of [`INTRO`].
+    Intro(usize),
+    Payload,
+    /// After an ESC in the payload: a second ESC is a wrapped one, `\`
+    /// ends the passthrough.
+    PayloadEscape,
+}
+
+/// Per-pane unwrapper. A wrapper split across reads is carried over.
+#[derive(Debug)]
+pub struct Passthrough {
+    state: State,
+}
+
+impl Default for Passthrough {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Passthrough {
+    pub fn new() -> Self {
+        Self {
+            state: State::Ground,
+        }
+    }
+
+    pub fn unwrap<'a>(&mut self, input: &'a [u8]) -> Cow<'a, [u8]> {
+        let untouched = self.state == State::Ground
+            && input.last() != Some(&0x1b)
+            && !input.windows(2).any(|pair| pair == &INTRO[..2]);
+        if untouched {
+            return Cow::Borrowed(input);
+        }
+
+        let mut out = Vec::with_capacity(input.len());
+        let mut i = 0;
+        while i < input.len() {
+            let byte = input[i];
+            match self.state {
+                State::Ground => {
+                    if byte == INTRO[0] {
+                        self.state = State::Intro(1);
+                    } else {
+                        out.push(byte);
+                    }
+                }
+                State::Intro(len) => {
+                    if byte == INTRO[len] {
+                        self.state = if len + 1 == INTRO.len() {
+                            State::Payload
+                        } else {
+                            State::Intro(len + 1)
+                        };
+                    } else {
+                        // Not a passthrough: the matched bytes go out as they
+                        // were and this one is looked at again.
+                        out.extend_from_slice(&INTRO[..len]);
+                        self.state = State::Ground;
+                        continue;
+                    }
+                }
+                State::Payload => {
+                    if byte == 0x1b {
+                        self.state = State::PayloadEscape;
+                    } else {
+                        out.push(byte);
+                    }
+                }
+                State::PayloadEscape => {
+                    if byte == 0x1b {
+                        out.push(0x1b);
+                        self.state = State::Payload;
+                    } else {
+                        // `ESC \` ends it; any other escape cancels the
+                        // string and starts a sequence of its own.
+                        self.state = State::Ground;
+                        if byte != b'\\' {
+                            out.push(0x1b);
+                            continue;
+                        }
+                    }
+                }
+            }
+            i += 1;
+        }
+        Cow::Owned(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapped_sequences_replace_their_wrapper() {
+        let mut passthrough = Passthrough::new();
+        assert_eq!(
+            &*passthrough.unwrap(b"a\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\b"),
+            b"a\x1b]52;c;aGk=\x07b"
+        );
+        assert_eq!(
+            &*passthrough.unwrap(b"\x1bPtmux;\x1b\x1b]9;done\x1b\x1b\\\x1b\\"),
+            b"\x1b]9;done\x1b\\"
+        );
+    }
+
+    #[test]
+    fn a_wrapper_split_across_reads_is_still_unwrapped() {
+        let mut passthrough = Passthrough::new();
+        assert_eq!(&*passthrough.unwrap(b"x\x1bPtm"), b"x");
+        assert_eq!(&*passthrough.unwrap(b"ux;\x1b"), b"");
+        assert_eq!(&*passthrough.unwrap(b"\x1b]2;t\x07\x1b"), b"\x1b]2;t\x07");
+        assert_eq!(&*passthrough.unwrap(b"\\y"), b"y");
+    }
+
+    #[test]
+    fn other_sequences_pass_through() {
+        let mut passthrough = Passthrough::new();
+        let input = b"\x1bPq#0~\x1b\\\x1b[1m\x1bP+q544e\x1b\\";
+        assert_eq!(&*passthrough.unwrap(input), input);
+        assert!(matches!(passthrough.unwrap(b"plain"), Cow::Borrowed(_)));
+        // An intro cut short by another escape still reaches the parser.
+        assert_eq!(&*passthrough.unwrap(b"\x1bPt\x1b[0m"), b"\x1bPt\x1b[0m");
+    }
+}
@@ -0,0 +1,290 @@
+use std::path::Path;
+
+const PREVIEW_LINES: usize = 6;
+const PREVIEW_LINE_CHARS: usize = 80;
+
+/// Size of a clipboard payload, used to decide whether a paste needs
+/// confirmation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasteSize {
+    /// Lines shown to the user; a trailing line ending adds none.
+    pub lines: usize,
+    pub bytes: usize,
+    /// Whether the text holds any line ending, trailing ones included.
+    pub line_break: bool,
+}
+
+impl PasteSize {
+    pub fn of(text: &str) -> Self {
+        let breaks = text
+            .char_indices()
+            .filter(|&(i, ch)| ch == '\n' || (ch == '\r' && !text[i + 1..].starts_with('\n')))
+            .count();
+        let trailing = text.ends_with('\n') || text.ends_with('\r');
+        Self {
+            lines: (breaks + usize::from(!trailing)).max(1),
+            bytes: text.len(),
+            line_break: text.contains(['\n', '\r']),
+        }
+    }
+
+    /// Whether pasting would submit a line: any line ending counts, so a
+    /// copied command ending in one is confirmed like several lines.
+    pub fn is_multiline(&self) -> bool {
+        self.line_break
+    }
+
+    /// A threshold of 0 disables that limit.
+    pub fn exceeds(&self, max_lines: usize, max_bytes: usize) -> bool {
+        (max_lines > 0 && self.lines > max_lines) || (max_bytes > 0 && self.bytes > max_bytes)
+    }
+}
+
+/// Strips bytes that would let clipboard contents act as terminal input
+/// rather than text: C0 controls other than tab and newline, DEL, and C1
+/// controls. Line endings are normalized to `\r`, which is what the Enter key
+/// sends. Removing ESC also means the contents cannot forge the
+/// bracketed-paste end marker.
+pub fn sanitize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\r' => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                out.push('\r');
+            }
+            '\n' => out.push('\r'),
+            '\t' => out.push('\t'),
+            ch if ch.is_control() => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Sanitizes `text` and wraps it in bracketed-paste markers when the running
+/// program asked for them.
+pub fn encode(text: &str, bracketed: bool) -> Vec<u8> {
+    let sanitized = sanitize(text);
+    if bracketed {
+        format!("\x1b[200~{sanitized}\x1b[201~").into_bytes()
+    } else {
+        sanitized.into_bytes()
+    }
+}
+
+/// The first few lines of `text`, each truncated, for the confirmation dialog.
+pub fn preview(text: &str) -> String {
+    let sanitized = sanitize(text);
+    let mut lines: Vec<String> = sanitized
+        .split('\r')
+        .take(PREVIEW_LINES)
+        .map(|line| {
+            let line = line.replace('\t', "    ");
+            if line.chars().count() > PREVIEW_LINE_CHARS {
+                let cut: String = line.chars().take(PREVIEW_LINE_CHARS - 1).collect();
+                format!("{cut}…")
+            } else {
+                line
+            }
+        })
+        .collect();
+    if PasteSize::of(text).lines > PREVIEW_LINES {
+        lines.push("…".to_string());
+    }
+    lines.join("\n")
+}
+
+/// Quotes a dropped file's path so the shell reads it as a single word.
+///
+/// POSIX shells get single quotes (or `$'...'` when the name contains control
+/// characters, which single quotes cannot carry safely). Windows shells get
+/// double quotes when the path contains a space or a shell metacharacter.
+pub fn quote_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if cfg!(windows) {
+        quote_windows(&path)
+    } else {
+        quote_posix(&path)
+    }
+}
+
+fn quote_posix(path: &str) -> String {
+    const SAFE: &[char] = &['/', '.', '_', '-', '+', ',', ':', '@', '%', '='];
+    if !path.is_empty()
+        && path
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || SAFE.contains(&ch))
+    {
+        return path.to_string();
+    }
+    if path.chars().any(char::is_control) {
+        let mut out = String::from("$'");
+        for ch in path.chars() {
+            match ch {
+                '\\' => out.push_str("\\\\"),
+                '\'' => out.push_str("\\'"),
+                '\n' => out.push_str("\\n"),
+                '\t' => out.push_str("\\t"),
+                '\r' => out.push_str("\\r"),
+                // C0 and C1 controls alike, as the bytes of their UTF-8
+                // encoding, so the shell reads back the same name.
+                ch if ch.is_control() => {
+                    for byte in ch.encode_utf8(&mut [0; 4]).bytes() {
+                        out.push_str(&format!("\\x{byte:02x}"));
+                    }
+                }
+                ch => out.push(ch),
+            }
+        }
+        out.push('\'');
+        return out;
+    }
+    format!("'{}'", path.replace('\'', "'\\''"))
+}
+
+fn quote_windows(path: &str) -> String {
+    const SPECIAL: &[char] = &[
+        ' ', '&', '(', ')', '[', ']', '{', '}', '^', '=', ';', '!', '\'', '+', ',', '`', '~', '$',
+        '@', '#',
+    ];
+    if path.is_empty() || path.contains(SPECIAL) {
+        format!("\"{path}\"")
+    } else {
+        path.to_string()
+    }
+}
+
+/// Human-readable byte count for dialog copy.
+pub fn format_size(bytes: usize) -> String {
+    const KIB: f64 = 1024.0;
+    let bytes_f = bytes as f64;
+    if bytes_f < KIB {
+        format!("{bytes} B")
+    } else if bytes_f < KIB * KIB {
+        format!("{:.1} KiB", bytes_f / KIB)
+    } else {
+        format!("{:.1} MiB", bytes_f / (KIB * KIB))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_keeps_text_tabs_and_newlines() {
+        assert_eq!(sanitize("ls -la\tfoo\n"), "ls -la\tfoo\r");
+        assert_eq!(sanitize("héllo 世界"), "héllo 世界");
+    }
+
+    #[test]
+    fn sanitize_normalizes_line_endings() {
+        assert_eq!(sanitize("a\r\nb\nc\rd"), "a\rb\rc\rd");
+    }
+
+    #[test]
+    fn sanitize_strips_control_bytes() {
+        assert_eq!(sanitize("a\x03b\x04c\x1ad"), "abcd");
+        assert_eq!(sanitize("rm\x7f\x08"), "rm");
+        assert_eq!(sanitize("x\u{9b}31my"), "x31my");
+    }
+
+    #[test]
+    fn sanitize_prevents_breaking_out_of_bracketed_paste() {
+        let payload = encode("safe\x1b[201~rm -rf ~\n", true);
+        assert_eq!(payload, b"\x1b[200~safe[201~rm -rf ~\r\x1b[201~".to_vec());
+    }
+
+    #[test]
+    fn encode_without_brackets_is_just_sanitized_text() {
+        assert_eq!(encode("echo hi\n", false), b"echo hi\r".to_vec());
+    }
+
+    #[test]
+    fn size_counts_lines_and_bytes() {
+        assert_eq!(
+            PasteSize::of("one"),
+            PasteSize {
+                lines: 1,
+                bytes: 3,
+                line_break: false
+            }
+        );
+        assert_eq!(PasteSize::of("one\n").lines, 1);
+        assert!(!PasteSize::of("one").is_multiline());
+        assert!(PasteSize::of("one\n").is_multiline());
+        assert!(PasteSize::of("one\r").is_multiline());
+        assert_eq!(PasteSize::of("one\ntwo").lines, 2);
+        assert_eq!(PasteSize::of("a\r\nb\r\nc\r\n").lines, 3);
+        assert_eq!(PasteSize::of("a\rb").lines, 2);
+    }
+
+    #[test]
+    fn exceeds_ignores_disabled_limits() {
+        let size = PasteSize {
+            lines: 5_000,
+            bytes: 2_000_000,
+            line_break: true,
+        };
+        assert!(size.exceeds(1_000, 0));
+        assert!(size.exceeds(0, 1_000_000));
+        assert!(!size.exceeds(0, 0));
+        assert!(!size.exceeds(10_000, 4_000_000));
+    }
+
+    #[test]
+    fn preview_truncates_lines_and_line_count() {
+        let text: String = (0..10).map(|i| format!("line {i}\n")).collect();
+        let preview = preview(&text);
+        assert_eq!(preview.lines().count(), PREVIEW_LINES + 1);
+        assert!(preview.starts_with("line 0\nline 1"));
+        assert!(preview.ends_with('…'));
+
+        let long = "x".repeat(200);
+        assert_eq!(super::preview(&long).chars().count(), PREVIEW_LINE_CHARS);
+    }
+
+    #[test]
+    fn posix_quoting_leaves_plain_paths_alone() {
+        assert_eq!(quote_posix("/home/me/notes.txt"), "/home/me/notes.txt");
+        assert_eq!(quote_posix("/tmp/a-b_c+1@2"), "/tmp/a-b_c+1@2");
+    }
+
+    #[test]
+    fn posix_quoting_wraps_spaces_and_quotes() {
+        assert_eq!(quote_posix("/tmp/My File.txt"), "'/tmp/My File.txt'");
+        assert_eq!(quote_posix("/tmp/it's"), "'/tmp/it'\\''s'");
+        assert_eq!(quote_posix("/tmp/$HOME;rm"), "'/tmp/$HOME;rm'");
+    }
+
+    #[test]
+    fn posix_quoting_escapes_control_characters() {
+        assert_eq!(quote_posix("/tmp/a\nb"), "$'/tmp/a\\nb'");
+        assert_eq!(quote_posix("/tmp/x\x1by'z"), "$'/tmp/x\\x1by\\'z'");
+        assert_eq!(quote_posix("/tmp/a\u{9b}b"), "$'/tmp/a\\xc2\\x9bb'");
+        assert_eq!(quote_posix("/tmp/\u{85}"), "$'/tmp/\\xc2\\x85'");
+    }
+
+    #[test]
+    fn windows_quoting_wraps_paths_with_spaces() {
+        assert_eq!(
+            quote_windows(r"C:\Users\me\file.txt"),
+            r"C:\Users\me\file.txt"
+        );
+        assert_eq!(
+            quote_windows(r"C:\Program Files\app.exe"),
+            r#""C:\Program Files\app.exe""#
+        );
+    }
+
+    #[test]
+    fn format_size_picks_a_unit() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
@@ -0,0 +1,297 @@
+use std::path::{Path, PathBuf};
+
+const LEADING: [char; 7] = ['(', '[', '{', '<', '"', '\'', '`'];
+const TRAILING: [char; 12] = ['.', ',', ';', ':', '!', '?', '"', '\'', ')', ']', '}', '>'];
+
+/// A file path found in terminal output, with the optional `:line:column`
+/// suffix compilers and linters print after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathSpan {
+    pub start: usize,
+    pub end: usize,
+    pub path: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+/// Finds the path under `col`, if any.
+///
+/// Accepts `path`, `path:line`, `path:line:col` (rustc, gcc, eslint...) and
+/// `path(line,col)` (MSVC). A bare word only counts as a path when it contains
+/// a separator or carries a line number, so ordinary prose is left alone.
+pub fn path_at(row: &[char], col: usize) -> Option<PathSpan> {
+    if col >= row.len() || row[col].is_whitespace() {
+        return None;
+    }
+
+    let mut start = col;
+    while start > 0 && !row[start - 1].is_whitespace() {
+        start -= 1;
+    }
+    let mut end = col;
+    while end + 1 < row.len() && !row[end + 1].is_whitespace() {
+        end += 1;
+    }
+
+    while start < end && LEADING.contains(&row[start]) {
+        start += 1;
+    }
+    while end > start && TRAILING.contains(&row[end]) {
+        if row[end] == ')' && is_msvc_location(&row[start..=end]) {
+            break;
+        }
+        end -= 1;
+    }
+    if col < start || col > end {
+        return None;
+    }
+
+    let word: String = row[start..=end].iter().collect();
+    if word.contains("://") {
+        return None;
+    }
+
+    let (path, line, column) = split_location(&word);
+    if !looks_like_path(path, line.is_some()) {
+        return None;
+    }
+
+    Some(PathSpan {
+        start,
+        end,
+        path: path.to_string(),
+        line,
+        column,
+    })
+}
+
+/// Resolves a detected path against the pane's working directory and returns
+/// it only if it exists.
+pub fn resolve(path: &str, cwd: Option<&Path>) -> Option<PathBuf> {
+    let expanded = if let Some(rest) = path.strip_prefix("~/") {
+        dirs::home_dir()?.join(rest)
+    } else {
+        PathBuf::from(path)
+    };
+    let resolved = if expanded.is_absolute() {
+        expanded
+    } else {
+        cwd?.join(expanded)
+    };
+    resolved.exists().then_some(resolved)
+}
+
+/// Builds the argv for the configured editor command.
+///
+/// `{path}`, `{line}` and `{column}` are substituted per argument (a missing
+/// line or column becomes `1`), so paths with spaces stay one argument and
+/// nothing passes through a shell. Returns `None` for an empty template.
+pub fn editor_argv(
+    template: &str,
+    path: &Path,
+    line: Option<u32>,
+    column: Option<u32>,
+) -> Option<Vec<String>> {
+    let path = path.to_string_lossy();
+    let line = line.unwrap_or(1).to_string();
+    let column = column.unwrap_or(1).to_string();
+    let argv: Vec<String> = template
+        .split_whitespace()
+        .map(|arg| {
+            arg.replace("{path}", &path)
+                .replace("{line}", &line)
+                .replace("{column}", &column)
+        })
+        .collect();
+    (!argv.is_empty()).then_some(argv)
+}
+
+/// Decodes an OSC 7 payload (`file://host/path`) into the absolute path it
+/// names. Windows shells report `file://host/C:/dir`; the slash before the
+/// drive letter is dropped.
+pub fn decode_osc7(payload: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(payload).ok()?;
+    let rest = text.strip_prefix("file://")?;
+    let slash = rest.find('/')?;
+    let path = percent_decode(&rest[slash..]);
+    let bytes = path.as_bytes();
+    if bytes.len() >= 3 && bytes[1].is_ascii_alphabetic() && bytes[2] == b':' {
+        return Some(path[1..].to_string());
+    }
+    Some(path)
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && let (Some(hi), Some(lo)) = (hex_val(bytes[i + 1]), hex_val(bytes[i + 2]))
+        {
+            out.push(hi * 16 + lo);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex_val(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn is_msvc_location(chars: &[char]) -> bool {
+    let word: String = chars.iter().collect();
+    split_location(&word).1.is_some() && word.ends_with(')')
+}
+
+fn split_location(word: &str) -> (&str, Option<u32>, Option<u32>) {
+    // MSVC: `file.cpp(12,5)` / `file.cpp(12)`
+    if let Some(inner) = word.strip_suffix(')')
+        && let Some(open) = inner.rfind('(')
+    {
+        let mut numbers = inner[open + 1..].split(',');
+        let line = numbers.next().and_then(|n| n.trim().parse().ok());
+        let column = numbers.next().and_then(|n| n.trim().parse().ok());
+        if line.is_some() && numbers.next().is_none() {
+            return (&word[..open], line, column);
+        }
+    }
+
+    let mut path = word;
+    let mut numbers = Vec::with_capacity(2);
+    while numbers.len() < 2
+        && let Some((head, tail)) = path.rsplit_once(':')
+        && let Ok(n) = tail.parse::<u32>()
+    {
+        numbers.push(n);
+        path = head;
+    }
+    match numbers.as_slice() {
+        [line] => (path, Some(*line), None),
+        [column, line] => (path, Some(*line), Some(*column)),
+        _ => (word, None, None),
+    }
+}
+
+fn looks_like_path(path: &str, has_line: bool) -> bool {
+    if path.is_empty() || !path.chars().any(char::is_alphabetic) {
+        return false;
+    }
+    let has_separator = path.contains('/') || (cfg!(windows) && path.contains('\\'));
+    if has_separator {
+        // Lone `/` or `//` are punctuation, not paths.
+        return path.trim_matches('/').chars().any(char::is_alphanumeric);
+    }
+    // `main.rs:10` — a file name is only trusted with a location attached.
+    has_line
+        && path
+            .rsplit_once('.')
+            .is_some_and(|(stem, ext)| !stem.is_empty() && ext.chars().all(char::is_alphanumeric))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    fn at(text: &str, col: usize) -> Option<PathSpan> {
+        path_at(&row(text), col)
+    }
+
+    #[test]
+    fn finds_absolute_and_relative_paths() {
+        assert_eq!(at("cat /etc/hosts now", 6).unwrap().path, "/etc/hosts");
+        assert_eq!(at("edit ./src/main.rs", 8).unwrap().path, "./src/main.rs");
+        assert_eq!(at("~/notes.md", 0).unwrap().path, "~/notes.md");
+    }
+
+    #[test]
+    fn parses_rustc_and_gcc_locations() {
+        let span = at("  --> src/gui/tab.rs:264:5", 10).unwrap();
+        assert_eq!(span.path, "src/gui/tab.rs");
+        assert_eq!(span.line, Some(264));
+        assert_eq!(span.column, Some(5));
+        assert_eq!((span.start, span.end), (6, 25));
+
+        let span = at("main.c:12: error: expected ';'", 2).unwrap();
+        assert_eq!(span.path, "main.c");
+        assert_eq!(span.line, Some(12));
+        assert_eq!(span.column, None);
+    }
+
+    #[test]
+    fn parses_msvc_locations() {
+        let span = at(r"src\app.cpp(42,7): error C2065", 3).unwrap();
+        assert_eq!(span.line, Some(42));
+        assert_eq!(span.column, Some(7));
+        assert!(span.path.ends_with("app.cpp"));
+    }
+
+    #[test]
+    fn trims_surrounding_punctuation() {
+        let span = at("(see src/lib.rs).", 6).unwrap();
+        assert_eq!(span.path, "src/lib.rs");
+        assert_eq!(at("'/tmp/x'", 3).unwrap().path, "/tmp/x");
+    }
+
+    #[test]
+    fn ignores_prose_urls_and_numbers() {
+        assert_eq!(at("hello world", 2), None);
+        assert_eq!(at("version.txt is here", 2), None);
+        assert_eq!(at("https://example.com/a/b", 10), None);
+        assert_eq!(at("ratio 1/2", 7), None);
+        assert_eq!(at("a / b", 2), None);
+    }
+
+    #[test]
+    fn editor_argv_substitutes_placeholders() {
+        let argv = editor_argv(
+            "code -g {path}:{line}:{column}",
+            Path::new("/tmp/My File.rs"),
+            Some(3),
+            None,
+        )
+        .unwrap();
+        assert_eq!(argv, vec!["code", "-g", "/tmp/My File.rs:3:1"]);
+        assert_eq!(editor_argv("  ", Path::new("/x"), None, None), None);
+    }
+
+    #[test]
+    fn resolve_joins_relative_paths_to_the_working_directory() {
+        let dir = std::env::temp_dir();
+        let name = format!("rabbitty-path-{}", std::process::id());
+        std::fs::write(dir.join(&name), b"").unwrap();
+        assert_eq!(resolve(&name, Some(&dir)), Some(dir.join(&name)));
+        assert_eq!(resolve(&name, None), None);
+        assert_eq!(resolve("definitely/not/here", Some(&dir)), None);
+        let _ = std::fs::remove_file(dir.join(&name));
+    }
+
+    #[test]
+    fn osc7_payloads_decode_to_local_paths() {
+        assert_eq!(
+            decode_osc7(b"file://host/home/me/a%20b").as_deref(),
+            Some("/home/me/a b")
+        );
+        assert_eq!(decode_osc7(b"file:///tmp").as_deref(), Some("/tmp"));
+        assert_eq!(
+            decode_osc7(b"file://pc/C:/Users/me").as_deref(),
+            Some("C:/Users/me")
+        );
+        assert_eq!(decode_osc7(b"http://host/tmp"), None);
+        assert_eq!(decode_osc7(b"file://host"), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Speculative local echo for high-latency sessions, after mosh: printable
+//! keystrokes are drawn at the cursor right away and dropped again once the
+//! server's echo lands on screen, or as soon as the screen contradicts them.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Predictions the server has not confirmed in this long are abandoned, e.g.
+/// when the remote program stops echoing.
+const PREDICTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Prediction {
+    pub ch: char,
+    pub col: usize,
+    pub row: usize,
+    made: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct Predictor {
+    pending: VecDeque<Prediction>,
+    /// Set once the server has echoed a prediction since the last reset.
+    /// Nothing is shown before that, so input the server never echoes (a
+    /// password prompt) never appears on screen.
+    confirmed: bool,
+}
+
+impl Predictor {
+    /// Records keystrokes just sent to the server. Printable ASCII is
+    /// predicted after the cursor, backspace takes back the last prediction,
+    /// and anything else (Enter, arrows, control keys) has an unknown effect,
+    /// so prediction starts over.
+    pub fn input(
+        &mut self,
+        bytes: &[u8],
+        cursor: Option<(usize, usize)>,
+        columns: usize,
+        now: Instant,
+    ) {
+        if bytes == b"\x7f" {
+            self.pending.pop_back();
+            return;
+        }
+        let Some(cursor) = cursor.filter(|_| bytes.iter().all(|b| (0x20..0x7f).contains(b))) else {
+            self.reset();
+            return;
+        };
+        for &byte in bytes {
+            let (col, row) = match self.pending.back() {
+                Some(last) => (last.col + 1, last.row),
+                None => cursor,
+            };
+            // Where the line wraps is up to the server; don't guess.
+            if col >= columns {
+                return;
+            }
+            self.pending.push_back(Prediction {
+                ch: char::from(byte),
+                col,
+                row,
+                made: now,
+            });
+        }
+    }
+
+    /// Forgets every prediction and waits for a fresh echo before showing more.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.confirmed = false;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Checks pending predictions against the screen after new output.
+    /// `cell` reads the character at a viewport `(col, row)`.
+    pub fn reconcile(
+        &mut self,
+        cursor: Option<(usize, usize)>,
+        cell: impl Fn(usize, usize) -> Option<char>,
+        now: Instant,
+    ) {
+        while let Some(front) = self.pending.front().copied() {
+            if now.duration_since(front.made) > PREDICTION_TIMEOUT
+                || cursor.is_none_or(|(_, row)| row != front.row)
+            {
+                self.reset();
+                return;
+            }
+            match cell(front.col, front.row) {
+                Some(ch) if ch == front.ch => {
+                    self.pending.pop_front();
+                    // A space matches a blank cell before any echo arrives.
+                    self.confirmed |= ch != ' ';
+                }
+                // Not echoed yet.
+                Some(' ') => return,
+                _ => {
+                    self.reset();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// The predictions to draw, oldest first.
+    pub fn visible(&self, now: Instant) -> impl Iterator<Item = &Prediction> {
+        let shown = self.confirmed
+            && self
+                .pending
+                .front()
+                .is_some_and(|p| now.duration_since(p.made) <= PREDICTION_TIMEOUT);
+        self.pending.iter().filter(move |_| shown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(line: &str) -> impl Fn(usize, usize) -> Option<char> + '_ {
+        move |col, row| (row == 0).then(|| line.chars().nth(col).unwrap_or(' '))
+    }
+
+    fn visible(predictor: &Predictor, now: Instant) -> String {
+        predictor.visible(now).map(|p| p.ch).collect()
+    }
+
+    #[test]
+    fn predictions_show_only_after_the_server_echoes_once() {
+        let now = Instant::now();
+        let mut predictor = Predictor::default();
+        predictor.input(b"l", Some((2, 0)), 80, now);
+        assert_eq!(visible(&predictor, now), "");
+
+        predictor.reconcile(Some((3, 0)), screen("$ l"), now);
+        assert!(predictor.is_empty());
+
+        predictor.input(b"s -", Some((3, 0)), 80, now);
+        assert_eq!(visible(&predictor, now), "s -");
+        let cols: Vec<usize> = predictor.visible(now).map(|p| p.col).collect();
+        assert_eq!(cols, [3, 4, 5]);
+
+        // The space matches the blank cell it was typed into.
+        predictor.reconcile(Some((4, 0)), screen("$ ls"), now);
+        assert_eq!(visible(&predictor, now), "-");
+    }
+
+    #[test]
+    fn contradicting_output_and_control_keys_discard_predictions() {
+        let now = Instant::now();
+        let mut predictor = Predictor::default();
+        predictor.input(b"ab", Some((0, 0)), 80, now);
+        predictor.reconcile(Some((1, 0)), screen("a"), now);
+        predictor.reconcile(Some((2, 0)), screen("aX"), now);
+        assert!(predictor.is_empty());
+
+        predictor.input(b"c", Some((2, 0)), 80, now);
+        predictor.input(b"\r", Some((2, 0)), 80, now);
+        assert!(predictor.is_empty());
+
+        // Enter starts over: the next line is not shown until echoed again.
+        predictor.input(b"secret", Some((0, 0)), 80, now);
+        assert_eq!(visible(&predictor, now), "");
+    }
+
+    #[test]
+    fn backspace_takes_back_and_edges_stop_prediction() {
+        let now = Instant::now();
+        let mut predictor = Predictor::default();
+        predictor.input(b"xyz", Some((77, 0)), 80, now);
+        predictor.input(b"\x7f", Some((77, 0)), 80, now);
+        let cols: Vec<usize> = predictor.pending.iter().map(|p| p.col).collect();
+        assert_eq!(cols, [77, 78]);
+
+        predictor.input(b"yz", Some((77, 0)), 80, now);
+        let cols: Vec<usize> = predictor.pending.iter().map(|p| p.col).collect();
+        assert_eq!(cols, [77, 78, 79]);
+    }
+
+    #[test]
+    fn unconfirmed_predictions_expire() {
+        let now = Instant::now();
+        let mut predictor = Predictor::default();
+        predictor.input(b"a", Some((0, 0)), 80, now);
+        predictor.reconcile(Some((1, 0)), screen("a"), now);
+        predictor.input(b"b", Some((1, 0)), 80, now);
+        assert_eq!(visible(&predictor, now), "b");
+
+        let later = now + PREDICTION_TIMEOUT * 2;
+        assert_eq!(visible(&predictor, later), "");
+        predictor.reconcile(Some((1, 0)), screen("a"), later);
+        assert!(predictor.is_empty());
+    }
+}
@@ -0,0 +1,303 @@
+//! Identification queries the terminal answers itself: ENQ, primary and
+//! secondary device attributes (DA1/DA2) and XTVERSION. They are taken out
+//! of the stream before it reaches the VT parser, which would answer DA as a
+//! generic VT102 and ignore the others, leaving programs that probe for them
+//! waiting.
+//!
+//! The parser also counts the DCS, SOS, PM and APC strings that reach it.
+//! Those the engine understands (images, kitty graphics) were taken out
+//! before, so these are ones nothing handles, ReGIS or Sixel meant for another
+//! terminal say; the VT parser skips them whole.
+
+use std::borrow::Cow;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// `62` a VT220-class terminal, `4` with Sixel graphics, `22` ANSI color.
+const PRIMARY_DA: &str = "\x1b[?62;4;22c";
+/// Parameter bytes a query may have between `CSI` and its final byte.
+const MAX_QUERY_PARAMS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Query {
+    /// `ENQ`, answered with the configured answerback string.
+    Enquiry,
+    /// `CSI c` or `CSI 0 c`.
+    PrimaryAttributes,
+    /// `CSI > c` or `CSI > 0 c`.
+    SecondaryAttributes,
+    /// `CSI > q` or `CSI > 0 q`.
+    Version,
+}
+
+impl Query {
+    /// What to write back to the program; `None` when there is nothing to
+    /// say, as for ENQ without an answerback string.
+    pub fn reply(self, answerback: &str) -> Option<Cow<'static, str>> {
+        match self {
+            Self::Enquiry => (!answerback.is_empty()).then(|| answerback.to_string().into()),
+            Self::PrimaryAttributes => Some(PRIMARY_DA.into()),
+            Self::SecondaryAttributes => {
+                Some(format!("\x1b[>1;{};0c", version_number(VERSION)).into())
+            }
+            Self::Version => Some(format!("\x1bP>|RabbiTTY {VERSION}\x1b\\").into()),
+        }
+    }
+}
+
+/// `1.2.3` → `10203`, the way xterm reports its patch level in DA2.
+fn version_number(version: &str) -> u32 {
+    let mut parts = version
+        .split(['.', '-', '+'])
+        .map(|part| part.parse::<u32>().ok());
+    let mut next = || parts.next().flatten().unwrap_or(0).min(99);
+    let (major, minor, patch) = (next(), next(), next());
+    major * 10_000 + minor * 100 + patch
+}
+
+/// A run of PTY output, split around the queries it contained.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Piece<'a> {
+    Text(Cow<'a, [u8]>),
+    Query(Query),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    /// After `ESC`; the escape is held back until it is known not to be a
+    /// query.
+    Escape,
+    /// After `ESC [`, with the parameter bytes seen so far.
+    Csi {
+        secondary: bool,
+        params: usize,
+    },
+    /// Inside an OSC, DCS, APC, PM or SOS string, where bytes are payload.
+    Str,
+    StrEscape,
+}
+
+/// Finds queries in PTY output. Sequences may straddle reads.
+#[derive(Debug)]
+pub struct QueryParser {
+    state: State,
+    /// Bytes of a possible query carried over from the previous read.
+    held: Vec<u8>,
+    unhandled: u64,
+}
+
+impl Default for QueryParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueryParser {
+    pub fn new() -> Self {
+        Self {
+            state: State::Ground,
+            held: Vec::new(),
+            unhandled: 0,
+        }
+    }
+
+    /// How many DCS, SOS, PM and APC strings have gone by.
+    pub fn unhandled_strings(&self) -> u64 {
+        self.unhandled
+    }
+
+    pub fn split<'a>(&mut self, input: &'a [u8]) -> Vec<Piece<'a>> {
+        let mut pieces = Vec::new();
+        let mut text_start = 0;
+        // Where the escape that may be a query began in `input`; `held`
+        // has its start when that was in an earlier read.
+        let mut candidate = 0;
+
+        for (i, &byte) in input.iter().enumerate() {
+            match self.state {
+                State::Ground => match byte {
+                    0x1b => {
+                        self.state = State::Escape;
+                        candidate = i;
+                    }
+                    0x05 => {
+                        push_text(&mut pieces, &input[text_start..i]);
+                        pieces.push(Piece::Query(Query::Enquiry));
+                        text_start = i + 1;
+                    }
+                    _ => {}
+                },
+                State::Escape => match byte {
+                    b'[' => {
+                        self.state = State::Csi {
+                            secondary: false,
+                            params: 0,
+                        }
+                    }
+                    b']' => self.abandon(&mut pieces, State::Str),
+                    b'P' | b'_' | b'^' | b'X' => {
+                        self.unhandled += 1;
+                        self.abandon(&mut pieces, State::Str);
+                    }
+                    _ => self.abandon(&mut pieces, State::Ground),
+                },
+                State::Csi { secondary, params } => {
+                    let query = match byte {
+                        b'c' if secondary => Some(Query::SecondaryAttributes),
+                        b'c' => Some(Query::PrimaryAttributes),
+                        b'q' if secondary => Some(Query::Version),
+                        _ => None,
+                    };
+                    if let Some(query) = query {
+                        if self.held.is_empty() {
+                            push_text(&mut pieces, &input[text_start..candidate]);
+                        }
+                        self.held.clear();
+                        pieces.push(Piece::Query(query));
+                        text_start = i + 1;
+                        self.state = State::Ground;
+                    } else if byte == b'>' && params == 0 {
+                        self.state = State::Csi {
+                            secondary: true,
+                            params: 1,
+                        };
+                    } else if byte == b'0' && params < MAX_QUERY_PARAMS {
+                        self.state = State::Csi {
+                            secondary,
+                            params: params + 1,
+                        };
+                    } else {
+                        self.abandon(&mut pieces, State::Ground);
+                    }
+                }
+                State::Str => match byte {
+                    0x07 => self.state = State::Ground,
+                    0x1b => self.state = State::StrEscape,
+                    _ => {}
+                },
+                State::StrEscape => self.state = State::Ground,
+            }
+        }
+
+        if matches!(self.state, State::Escape | State::Csi { .. }) {
+            let start = if self.held.is_empty() { candidate } else { 0 };
+            push_text(&mut pieces, &input[text_start..start]);
+            self.held.extend_from_slice(&input[start..]);
+        } else {
+            push_text(&mut pieces, &input[text_start..]);
+        }
+        pieces
+    }
+
+    /// The escape being held is not a query after all: it goes out as
+    /// text ahead of the rest of this read.
+    fn abandon<'a>(&mut self, pieces: &mut Vec<Piece<'a>>, next: State) {
+        if !self.held.is_empty() {
+            pieces.push(Piece::Text(Cow::Owned(std::mem::take(&mut self.held))));
+        }
+        self.state = next;
+    }
+}
+
+fn push_text<'a>(pieces: &mut Vec<Piece<'a>>, text: &'a [u8]) {
+    if !text.is_empty() {
+        pieces.push(Piece::Text(Cow::Borrowed(text)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(bytes: &[u8]) -> Piece<'_> {
+        Piece::Text(Cow::Borrowed(bytes))
+    }
+
+    #[test]
+    fn device_attribute_queries_are_taken_out_of_the_stream() {
+        let mut parser = QueryParser::new();
+        assert_eq!(
+            parser.split(b"a\x1b[cb\x1b[>0cc\x1b[>qd\x1b[0c"),
+            vec![
+                text(b"a"),
+                Piece::Query(Query::PrimaryAttributes),
+                text(b"b"),
+                Piece::Query(Query::SecondaryAttributes),
+                text(b"c"),
+                Piece::Query(Query::Version),
+                text(b"d"),
+                Piece::Query(Query::PrimaryAttributes),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_sequences_pass_through_untouched() {
+        let mut parser = QueryParser::new();
+        let input = b"\x1b[31mred\x1b[2 q\x1b[>4;1m\x1b7";
+        let out: Vec<u8> = parser
+            .split(input)
+            .into_iter()
+            .flat_map(|piece| match piece {
+                Piece::Text(text) => text.into_owned(),
+                Piece::Query(query) => panic!("unexpected {query:?}"),
+            })
+            .collect();
+        assert_eq!(out, input);
+    }
+
+    #[test]
+    fn a_query_split_across_reads_is_still_found() {
+        let mut parser = QueryParser::new();
+        assert_eq!(parser.split(b"x\x1b"), vec![text(b"x")]);
+        assert_eq!(parser.split(b"[>"), vec![]);
+        assert_eq!(
+            parser.split(b"cy"),
+            vec![Piece::Query(Query::SecondaryAttributes), text(b"y")]
+        );
+
+        assert_eq!(parser.split(b"\x1b["), vec![]);
+        assert_eq!(
+            parser.split(b"1mz"),
+            vec![Piece::Text(Cow::Owned(b"\x1b[".to_vec())), text(b"1mz")]
+        );
+    }
+
+    #[test]
+    fn enq_inside_a_string_is_payload() {
+        let mut parser = QueryParser::new();
+        assert_eq!(
+            parser.split(b"\x1b]0;a\x05b\x07\x05"),
+            vec![text(b"\x1b]0;a\x05b\x07"), Piece::Query(Query::Enquiry)]
+        );
+    }
+
+    #[test]
+    fn strings_nothing_handles_are_counted() {
+        let mut parser = QueryParser::new();
+        parser.split(b"\x1b]0;title\x07\x1bPp S(E)\x1b\\\x1bXsos\x1b\\\x1b^pm");
+        assert_eq!(parser.unhandled_strings(), 3);
+        parser.split(b"\x1b\\\x1b_");
+        assert_eq!(parser.unhandled_strings(), 4);
+    }
+
+    #[test]
+    fn replies_identify_rabbitty() {
+        assert_eq!(version_number("0.4.12"), 412);
+        assert_eq!(version_number("1.2.3-beta.1"), 10203);
+        assert_eq!(Query::Enquiry.reply(""), None);
+        assert_eq!(Query::Enquiry.reply("ok").as_deref(), Some("ok"));
+        assert!(
+            Query::Version
+                .reply("")
+                .unwrap()
+                .starts_with("\x1bP>|RabbiTTY ")
+        );
+        assert!(
+            Query::SecondaryAttributes
+                .reply("")
+                .unwrap()
+                .starts_with("\x1b[>1;")
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! Session recording in the asciicast v2 format: a header line with the
+//! terminal size, then one `[seconds, "o", text]` event per chunk of output
+//! and an `"r"` event for each resize. Players such as asciinema replay it.
+//!
+//! The clip runs from where recording was started to where it was stopped;
+//! a long session keeps only its most recent [`MAX_RECORDED_BYTES`] of
+//! output. [`Recording::replay`] plays it back into a headless engine frame
+//! by frame, which the renderer draws into an animated GIF.
+
+use super::{TerminalEngine, TerminalSize, TerminalTheme, json_string};
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+/// Output kept per recording; older events are dropped to stay under it.
+pub const MAX_RECORDED_BYTES: usize = 32 * 1024 * 1024;
+
+/// Output closer together than this is replayed as one frame.
+const FRAME_STEP: Duration = Duration::from_millis(50);
+
+/// The longest a replayed frame is shown: idle stretches are cut short, and
+/// the last frame is held this long.
+const MAX_FRAME: Duration = Duration::from_secs(2);
+
+enum Event {
+    Output(String),
+    Resize(TerminalSize),
+}
+
+pub struct Recording {
+    started: Instant,
+    /// The size at the first kept event.
+    size: TerminalSize,
+    events: VecDeque<(f64, Event)>,
+    /// Output held in `events`.
+    bytes: usize,
+    /// When the newest dropped event happened; clips start no earlier.
+    from: f64,
+    /// The start of a UTF-8 sequence split across reads.
+    partial: Vec<u8>,
+}
+
+impl Recording {
+    pub fn start(size: TerminalSize) -> Self {
+        Self::start_at(size, Instant::now())
+    }
+
+    fn start_at(size: TerminalSize, started: Instant) -> Self {
+        Self {
+            started,
+            size,
+            events: VecDeque::new(),
+            bytes: 0,
+            from: 0.0,
+            partial: Vec::new(),
+        }
+    }
+
+    pub fn output(&mut self, bytes: &[u8]) {
+        self.output_at(Instant::now(), bytes);
+    }
+
+    fn output_at(&mut self, at: Instant, bytes: &[u8]) {
+        self.partial.extend_from_slice(bytes);
+        let complete = match std::str::from_utf8(&self.partial) {
+            Ok(_) => self.partial.len(),
+            // Keep an incomplete sequence at the end for the next read;
+            // bytes that can never be valid are replaced.
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => self.partial.len(),
+        };
+        if complete == 0 {
+            return;
+        }
+        let text = String::from_utf8_lossy(&self.partial[..complete]).into_owned();
+        self.partial.drain(..complete);
+        self.bytes += text.len();
+        self.events
+            .push_back((self.seconds(at), Event::Output(text)));
+        self.trim();
+    }
+
+    pub fn resize(&mut self, size: TerminalSize) {
+        self.resize_at(Instant::now(), size);
+    }
+
+    fn resize_at(&mut self, at: Instant, size: TerminalSize) {
+        self.events
+            .push_back((self.seconds(at), Event::Resize(size)));
+    }
+
+    /// Drops the oldest events until the output fits the cap, keeping at
+    /// least the newest one.
+    fn trim(&mut self) {
+        while self.bytes > MAX_RECORDED_BYTES && self.events.len() > 1 {
+            let Some((at, event)) = self.events.pop_front() else {
+                break;
+            };
+            match event {
+                Event::Output(text) => self.bytes -= text.len(),
+                Event::Resize(to) => self.size = to,
+            }
+            self.from = at;
+        }
+    }
+
+    fn seconds(&self, at: Instant) -> f64 {
+        at.saturating_duration_since(self.started).as_secs_f64()
+    }
+
+    /// The largest the terminal was during the recording.
+    pub fn largest_size(&self) -> TerminalSize {
+        self.events
+            .iter()
+            .filter_map(|(_, event)| match event {
+                Event::Resize(to) => Some(*to),
+                Event::Output(_) => None,
+            })
+            .fold(self.size, |largest, size| {
+                TerminalSize::new(
+                    largest.columns.max(size.columns),
+                    largest.lines.max(size.lines),
+                )
+            })
+    }
+
+    /// Plays the events within `range` back into a headless engine drawn with
+    /// `theme`, calling `frame` with the screen each time it is about to
+    /// change and how long it was shown. As with [`Self::to_asciicast`], the
+    /// clip opens on the screen as it was at the start of the range.
+    pub fn replay<E>(
+        &self,
+        range: Range<f64>,
+        theme: TerminalTheme,
+        mut frame: impl FnMut(&TerminalEngine, Duration) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let range = range.start.max(self.from)..range.end;
+        let mut engine = TerminalEngine::headless(self.size);
+        engine.set_theme(theme);
+        let mut shown = range.start;
+        for (at, event) in &self.events {
+            if *at >= range.end {
+                break;
+            }
+            if *at >= range.start && at - shown >= FRAME_STEP.as_secs_f64() {
+                frame(&engine, Duration::from_secs_f64(at - shown).min(MAX_FRAME))?;
+                shown = *at;
+            }
+            match event {
+                Event::Output(text) => engine.feed_bytes(text.as_bytes()),
+                Event::Resize(to) => engine.resize(*to),
+            }
+        }
+        frame(&engine, MAX_FRAME)
+    }
+
+    /// The events within `range` (in seconds from the start) as an asciicast
+    /// v2 document, timed from the start of the range. Output and resizes
+    /// before it are folded into its first moment, so the clip opens on the
+    /// screen as it was.
+    pub fn to_asciicast(&self, range: Range<f64>) -> String {
+        let range = range.start.max(self.from)..range.end;
+        let mut size = self.size;
+        let mut lead = String::new();
+        for (at, event) in &self.events {
+            if *at >= range.start {
+                break;
+            }
+            match event {
+                Event::Output(text) => lead.push_str(text),
+                Event::Resize(to) => size = *to,
+            }
+        }
+
+        let mut cast = format!(
+            "{{\"version\": 2, \"width\": {}, \"height\": {}}}\n",
+            size.columns, size.lines
+        );
+        if !lead.is_empty() {
+            cast.push_str(&format!("[0.000000, \"o\", {}]\n", json_string(&lead)));
+        }
+        for (at, event) in &self.events {
+            if !range.contains(at) {
+                continue;
+            }
+            let at = at - range.start;
+            let (code, data) = match event {
+                Event::Output(text) => ("o", text.clone()),
+                Event::Resize(to) => ("r", format!("{}x{}", to.columns, to.lines)),
+            };
+            cast.push_str(&format!("[{at:.6}, \"{code}\", {}]\n", json_string(&data)));
+        }
+        cast
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_is_written_as_timed_events() {
+        let start = Instant::now();
+        let mut recording = Recording::start_at(TerminalSize::new(80, 24), start);
+        recording.output_at(start + Duration::from_millis(250), b"$ ls\r\n");
+        recording.output_at(start + Duration::from_secs(1), b"\x1b[1m\"a\"\x1b[0m");
+        assert_eq!(
+            recording.to_asciicast(0.0..f64::INFINITY),
+            concat!(
+                "{\"version\": 2, \"width\": 80, \"height\": 24}\n",
+                "[0.250000, \"o\", \"$ ls\\r\\n\"]\n",
+                "[1.000000, \"o\", \"\\u001b[1m\\\"a\\\"\\u001b[0m\"]\n",
+            )
+        );
+    }
+
+    #[test]
+    fn a_range_starts_on_the_screen_as_it_was() {
+        let start = Instant::now();
+        let mut recording = Recording::start_at(TerminalSize::new(80, 24), start);
+        recording.output_at(start + Duration::from_secs(1), b"one ");
+        recording.output_at(start + Duration::from_secs(2), b"two ");
+        recording.output_at(start + Duration::from_secs(3), b"three");
+        let cast = recording.to_asciicast(1.5..2.5);
+        let events: Vec<&str> = cast.lines().skip(1).collect();
+        assert_eq!(
+            events,
+            ["[0.000000, \"o\", \"one \"]", "[0.500000, \"o\", \"two \"]"]
+        );
+    }
+
+    #[test]
+    fn replay_draws_a_frame_per_burst_of_output() {
+        let start = Instant::now();
+        let mut recording = Recording::start_at(TerminalSize::new(20, 2), start);
+        recording.output_at(start + Duration::from_millis(250), b"a");
+        recording.output_at(start + Duration::from_millis(260), b"b");
+        recording.output_at(start + Duration::from_secs(10), b"c");
+
+        let mut frames = Vec::new();
+        recording
+            .replay(
+                0.0..f64::INFINITY,
+                TerminalTheme::default(),
+                |engine, shown| {
+                    frames.push((engine.screen_text()[0].clone(), shown));
+                    Ok::<_, ()>(())
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            frames,
+            [
+                (String::new(), Duration::from_millis(250)),
+                ("ab".to_string(), MAX_FRAME),
+                ("abc".to_string(), MAX_FRAME),
+            ]
+        );
+    }
+
+    #[test]
+    fn characters_split_across_reads_are_kept_whole() {
+        let start = Instant::now();
+        let mut recording = Recording::start_at(TerminalSize::new(80, 24), start);
+        let text = "한".as_bytes();
+        recording.output_at(start, &text[..1]);
+        recording.output_at(start, &text[1..]);
+        assert!(
+            recording
+                .to_asciicast(0.0..1.0)
+                .ends_with("[0.000000, \"o\", \"한\"]\n")
+        );
+    }
+
+    #[test]
+    fn long_recordings_keep_their_latest_output() {
+        let start = Instant::now();
+        let mut recording = Recording::start_at(TerminalSize::new(80, 24), start);
+        let chunk = vec![b'x'; MAX_RECORDED_BYTES / 2];
+        recording.resize_at(start, TerminalSize::new(100, 30));
+        recording.output_at(start + Duration::from_secs(1), &chunk);
+        recording.output_at(start + Duration::from_secs(2), &chunk);
+        recording.output_at(start + Duration::from_secs(3), b"tail");
+        assert!(recording.bytes <= MAX_RECORDED_BYTES);
+
+        let cast = recording.to_asciicast(0.0..f64::INFINITY);
+        assert!(cast.starts_with("{\"version\": 2, \"width\": 100, \"height\": 30}\n"));
+        assert!(cast.ends_with("[2.000000, \"o\", \"tail\"]\n"));
+    }
+}
@@ -0,0 +1,110 @@
+//! The screen re-encoded as escape sequences, one row at a time, so another
+//! terminal can be brought to the same picture by writing the changed rows.
+//!
+//! Each row moves the cursor to its start, writes its cells with truecolor
+//! SGR for colors that differ from the theme's defaults and erases whatever
+//! is left after its last non-blank cell.
+
+use super::CellVisual;
+use super::snapshot::visible;
+use super::theme::linear_to_srgb_u8;
+use std::io::Write;
+
+pub(super) fn rows(cells: &[CellVisual], columns: usize, default_fg: [f32; 4]) -> Vec<Vec<u8>> {
+    cells
+        .chunks(columns.max(1))
+        .enumerate()
+        .map(|(index, row)| encode_row(index, row, default_fg))
+        .collect()
+}
+
+fn encode_row(index: usize, row: &[CellVisual], default_fg: [f32; 4]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(row.len() + 16);
+    let _ = write!(out, "\x1b[{};1H", index + 1);
+
+    let cells: Vec<&CellVisual> = visible(row).collect();
+    let end = cells
+        .iter()
+        .rposition(|cell| !is_blank(cell))
+        .map_or(0, |last| last + 1);
+
+    let mut current = String::new();
+    for cell in &cells[..end] {
+        let style = sgr(cell, default_fg);
+        if style != current {
+            let _ = write!(out, "\x1b[{style}m");
+            current = style;
+        }
+        match &cell.grapheme {
+            Some(cluster) => out.extend_from_slice(cluster.as_bytes()),
+            None if cell.ch.is_control() => out.push(b' '),
+            None => {
+                let mut buf = [0u8; 4];
+                out.extend_from_slice(cell.ch.encode_utf8(&mut buf).as_bytes());
+            }
+        }
+    }
+    out.extend_from_slice(b"\x1b[0m\x1b[K");
+    out
+}
+
+/// A cell erasing to the end of the line reproduces: blank, with default
+/// colors and no underline.
+fn is_blank(cell: &CellVisual) -> bool {
+    cell.grapheme.is_none()
+        && (cell.ch == ' ' || cell.ch.is_control())
+        && cell.bg[3] <= 0.0
+        && !cell.underline
+}
+
+fn sgr(cell: &CellVisual, default_fg: [f32; 4]) -> String {
+    let mut style = String::from("0");
+    if cell.fg != default_fg {
+        let [r, g, b] = srgb(cell.fg);
+        style.push_str(&format!(";38;2;{r};{g};{b}"));
+    }
+    // Default backgrounds are transparent.
+    if cell.bg[3] > 0.0 {
+        let [r, g, b] = srgb(cell.bg);
+        style.push_str(&format!(";48;2;{r};{g};{b}"));
+    }
+    if cell.underline {
+        style.push_str(";4");
+    }
+    style
+}
+
+fn srgb(color: [f32; 4]) -> [u8; 3] {
+    [
+        linear_to_srgb_u8(color[0]),
+        linear_to_srgb_u8(color[1]),
+        linear_to_srgb_u8(color[2]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::terminal::{TerminalEngine, TerminalSize};
+
+    #[test]
+    fn rows_written_to_another_terminal_reproduce_the_screen() {
+        let size = TerminalSize::new(20, 3);
+        let mut source = TerminalEngine::headless(size);
+        source.feed_bytes(
+            b"plain\r\n\x1b[31mred\x1b[0m and \x1b[4mlined\x1b[0m\r\n\xe4\xbd\xa0\xe5\xa5\xbd",
+        );
+
+        let mut copy = TerminalEngine::headless(size);
+        copy.feed_bytes(b"stale text everywhere\r\nmore");
+        for row in source.repaint_rows() {
+            copy.feed_bytes(&row);
+        }
+
+        assert_eq!(copy.screen_text(), source.screen_text());
+        let snapshot = |engine: &TerminalEngine| {
+            let picture = engine.snapshot();
+            picture.split_once('\n').map(|(_, rest)| rest.to_string())
+        };
+        assert_eq!(snapshot(&copy), snapshot(&source), "colors and marks");
+    }
+}
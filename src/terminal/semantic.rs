@@ -0,0 +1,38 @@
+//! Semantic selection: the span a shift-double-click selects, found by a
+//! list of regexes for units such as paths, URLs and hashes.
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Direction, Line, Point};
+use alacritty_terminal::term::Term;
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::search::{Match, RegexIter, RegexSearch};
+
+/// The first match of the first rule that covers `point`, searched over the
+/// logical line `point` is on. Rules that are not valid regexes are skipped.
+pub(super) fn match_at<T>(term: &Term<T>, point: Point, rules: &[String]) -> Option<Match> {
+    let grid = term.grid();
+    let last_column = Column(grid.columns().saturating_sub(1));
+    let top = -(grid.history_size() as i32);
+    let bottom = grid.screen_lines() as i32 - 1;
+    let wraps = |line: i32| {
+        grid[Line(line)][last_column]
+            .flags
+            .contains(Flags::WRAPLINE)
+    };
+    let mut first = point.line.0;
+    while first > top && wraps(first - 1) {
+        first -= 1;
+    }
+    let mut last = point.line.0;
+    while last < bottom && wraps(last) {
+        last += 1;
+    }
+    let start = Point::new(Line(first), Column(0));
+    let end = Point::new(Line(last), last_column);
+
+    rules.iter().find_map(|rule| {
+        let mut regex = RegexSearch::new(rule).ok()?;
+        RegexIter::new(start, end, Direction::Right, term, &mut regex)
+            .find(|found| found.contains(&point))
+    })
+}
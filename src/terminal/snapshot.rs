@@ -0,0 +1,130 @@
+//! Plain-text pictures of a terminal screen, for tests and tools that run
+//! the engine without a renderer.
+//!
+//! ```text
+//! cursor 4,1
+//! |ab link |
+//!  __ LLLL
+//! |wxyz    |
+//! colors
+//!  1:0-3 fg=#ffff00 bg=#000000
+//! ```
+//!
+//! Each row sits between bars so trailing blanks show; a wide character
+//! takes its own column and the one after it. A row of marks follows any row
+//! with underlined (`_`), linked (`L`) or image (`I`) cells, and `colors`
+//! lists runs of cells whose colors differ from the theme's defaults, by row
+//! and column range.
+
+use super::CellVisual;
+use super::theme::linear_to_srgb_u8;
+use std::fmt::Write;
+
+/// The visible rows as text, trailing blanks trimmed.
+pub(super) fn lines(cells: &[CellVisual], columns: usize) -> Vec<String> {
+    cells
+        .chunks(columns.max(1))
+        .map(|row| row_text(row).trim_end().to_string())
+        .collect()
+}
+
+pub(super) fn render(
+    cells: &[CellVisual],
+    columns: usize,
+    cursor: Option<(usize, usize)>,
+    default_fg: [f32; 4],
+) -> String {
+    let mut out = String::new();
+    match cursor {
+        Some((col, row)) => {
+            let _ = writeln!(out, "cursor {col},{row}");
+        }
+        None => out.push_str("cursor hidden\n"),
+    }
+
+    let mut runs = Vec::new();
+    for (index, row) in cells.chunks(columns.max(1)).enumerate() {
+        let _ = writeln!(out, "|{}|", row_text(row));
+        let marks: String = visible(row).map(mark).collect();
+        if !marks.trim_end().is_empty() {
+            let _ = writeln!(out, " {}", marks.trim_end());
+        }
+        color_runs(index, row, default_fg, &mut runs);
+    }
+    if !runs.is_empty() {
+        out.push_str("colors\n");
+        for run in runs {
+            let _ = writeln!(out, " {run}");
+        }
+    }
+    out
+}
+
+/// Cells that take up a column of their own: the spacer after a wide
+/// character is skipped.
+pub(super) fn visible(row: &[CellVisual]) -> impl Iterator<Item = &CellVisual> {
+    row.iter()
+        .enumerate()
+        .filter(|&(col, _)| col == 0 || !row[col - 1].wide)
+        .map(|(_, cell)| cell)
+}
+
+fn row_text(row: &[CellVisual]) -> String {
+    let mut text = String::with_capacity(row.len());
+    for cell in visible(row) {
+        match &cell.grapheme {
+            Some(cluster) => text.push_str(cluster),
+            // A tab leaves `\t` in the cell it started from.
+            None if cell.ch.is_control() => text.push(' '),
+            None => text.push(cell.ch),
+        }
+    }
+    text
+}
+
+fn mark(cell: &CellVisual) -> char {
+    if cell.image.is_some() {
+        'I'
+    } else if cell.hyperlink.is_some() {
+        'L'
+    } else if cell.underline {
+        '_'
+    } else {
+        ' '
+    }
+}
+
+fn color_runs(row_index: usize, row: &[CellVisual], default_fg: [f32; 4], runs: &mut Vec<String>) {
+    let mut run: Option<(usize, String)> = None;
+    for col in 0..=row.len() {
+        let style = row.get(col).and_then(|cell| style(cell, default_fg));
+        if run.as_ref().map(|(_, style)| style) == style.as_ref() {
+            continue;
+        }
+        if let Some((start, style)) = run.take() {
+            runs.push(format!("{row_index}:{start}-{} {style}", col - 1));
+        }
+        run = style.map(|style| (col, style));
+    }
+}
+
+fn style(cell: &CellVisual, default_fg: [f32; 4]) -> Option<String> {
+    let mut parts = Vec::new();
+    if cell.fg != default_fg {
+        parts.push(format!("fg={}", hex(cell.fg)));
+    }
+    // Default backgrounds are transparent.
+    if cell.bg[3] > 0.0 {
+        parts.push(format!("bg={}", hex(cell.bg)));
+    }
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+fn hex(color: [f32; 4]) -> String {
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        linear_to_srgb_u8(color[0]),
+        linear_to_srgb_u8(color[1]),
+        linear_to_srgb_u8(color[2])
+    )
+}
@@ -377,10 +377,11 @@ impl Default for TerminalTheme {
 
 impl TerminalTheme {
     pub fn from_config(config: &AppConfig) -> Self {
-        let base_ansi = if let Some(preset) = find_preset(&config.theme.color_scheme) {
-            preset.ansi.map(rgb_from_triplet)
-        } else if let Some(ref ansi) = config.theme.ansi_colors {
+        // Explicit colors override the preset's, as they do when loading.
+        let base_ansi = if let Some(ref ansi) = config.theme.ansi_colors {
             ansi.map(rgb_from_triplet)
+        } else if let Some(preset) = find_preset(&config.theme.color_scheme) {
+            preset.ansi.map(rgb_from_triplet)
         } else {
             all_presets()[0].ansi.map(rgb_from_triplet)
         };
@@ -394,6 +395,12 @@ impl TerminalTheme {
         }
     }
 
+    /// The background as linear RGBA, opaque. Cells of this color are left
+    /// transparent, so whatever draws the grid fills it in under them.
+    pub fn background_color(&self) -> [f32; 4] {
+        rgb_to_rgba(self.background, 1.0)
+    }
+
     /// The configured cursor color.
     pub(super) fn cursor_rgb(&self) -> Rgb {
         self.cursor
@@ -432,6 +439,17 @@ impl TerminalTheme {
         }
     }
 
+    /// The configured value of color slot `index`, numbered as in [`Colors`]
+    /// and OSC 4/10/11/12: the 256 indexed colors, then the named ones.
+    pub(super) fn slot_color(&self, index: usize) -> Rgb {
+        match u8::try_from(index) {
+            Ok(index) => self.indexed_color(index),
+            Err(_) if index == NamedColor::Background as usize => self.background,
+            Err(_) if index == NamedColor::Cursor as usize => self.cursor,
+            Err(_) => self.foreground,
+        }
+    }
+
     pub(super) fn indexed_color(&self, index: u8) -> Rgb {
         match index {
             0..=15 => self.ansi[index as usize],
@@ -586,6 +604,16 @@ pub(super) fn srgb_u8_to_linear(value: u8) -> f32 {
     }
 }
 
+pub(super) fn linear_to_srgb_u8(value: f32) -> u8 {
+    let v = value.clamp(0.0, 1.0);
+    let s = if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0).round() as u8
+}
+
 pub(super) fn rgb_to_rgba(rgb: Rgb, alpha: f32) -> [f32; 4] {
     [
         srgb_u8_to_linear(rgb.r),
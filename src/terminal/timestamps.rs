@@ -0,0 +1,90 @@
+//! The timestamp gutter: when each line of output arrived, drawn in a few
+//! columns left of the pane as a time of day or as how long ago.
+
+use super::CellVisual;
+use crate::config::TimestampMode;
+use std::time::{Duration, SystemTime};
+
+/// Width of the gutter in cells, the last one a blank separator.
+pub const GUTTER_COLUMNS: usize = 9;
+
+/// The gutter text for each row in view; blank where no line starts.
+pub fn labels(times: &[Option<SystemTime>], mode: TimestampMode, now: SystemTime) -> Vec<String> {
+    times
+        .iter()
+        .map(|time| time.map_or_else(String::new, |at| label(at, mode, now)))
+        .collect()
+}
+
+/// `14:03:27` for the clock, or the time since as [`elapsed`] writes it.
+fn label(at: SystemTime, mode: TimestampMode, now: SystemTime) -> String {
+    match mode {
+        TimestampMode::Off => String::new(),
+        TimestampMode::Absolute => chrono::DateTime::<chrono::Local>::from(at)
+            .format("%H:%M:%S")
+            .to_string(),
+        TimestampMode::Relative => elapsed(now.duration_since(at).unwrap_or_default()),
+    }
+}
+
+/// `42s`, `3m05s`, `2h10m` or `4d02h`: short enough for the gutter.
+pub fn elapsed(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3_600 => format!("{}m{:02}s", secs / 60, secs % 60),
+        3_600..86_400 => format!("{}h{:02}m", secs / 3_600, secs % 3_600 / 60),
+        _ => format!("{}d{:02}h", secs / 86_400, secs % 86_400 / 3_600),
+    }
+}
+
+/// Cells drawing `labels` right-aligned in the gutter, one row each.
+pub fn cells(labels: &[String], color: [f32; 4]) -> Vec<CellVisual> {
+    let width = GUTTER_COLUMNS - 1;
+    labels
+        .iter()
+        .enumerate()
+        .flat_map(|(row, label)| {
+            let start = width.saturating_sub(label.chars().count());
+            label
+                .chars()
+                .take(width)
+                .enumerate()
+                .map(move |(index, ch)| CellVisual {
+                    ch,
+                    grapheme: None,
+                    col: start + index,
+                    row,
+                    fg: color,
+                    bg: [0.0; 4],
+                    underline: false,
+                    bold: false,
+                    italic: false,
+                    wide: false,
+                    hyperlink: None,
+                    image: None,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_labels_fit_the_gutter() {
+        let now = SystemTime::now();
+        let ago = |secs| Some(now - Duration::from_secs(secs));
+        let times = [ago(5), None, ago(185), ago(7_800), ago(3 * 86_400 + 7_200)];
+
+        let labels = labels(&times, TimestampMode::Relative, now);
+        assert_eq!(labels, ["5s", "", "3m05s", "2h10m", "3d02h"]);
+        assert!(labels.iter().all(|label| label.len() < GUTTER_COLUMNS));
+
+        let cells = cells(&labels, [1.0; 4]);
+        let first: String = cells.iter().filter(|c| c.row == 0).map(|c| c.ch).collect();
+        assert_eq!(first, "5s");
+        assert_eq!(cells[0].col, GUTTER_COLUMNS - 3, "right-aligned");
+    }
+}
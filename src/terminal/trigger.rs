@@ -0,0 +1,138 @@
+//! User-configured regex triggers, run over each line of output as it
+//! completes rather than over the visible grid every frame.
+
+use crate::config::{Trigger, TriggerAction};
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Direction, Line, Point};
+use alacritty_terminal::term::Term;
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::search::{Match, RegexIter, RegexSearch};
+use alacritty_terminal::vte::ansi::{Color, Rgb};
+
+/// A trigger with an action matched; the UI decides how to surface it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerHit {
+    pub action: TriggerAction,
+    /// Text of the first match on the line.
+    pub text: String,
+    /// Screen line (negative in scrollback) the logical line ends on.
+    pub line: Line,
+}
+
+struct Rule {
+    regex: RegexSearch,
+    foreground: Option<Rgb>,
+    background: Option<Rgb>,
+    underline: bool,
+    action: TriggerAction,
+}
+
+#[derive(Default)]
+pub struct Triggers {
+    rules: Vec<Rule>,
+}
+
+impl Triggers {
+    /// Compiles `triggers`, skipping patterns that are not valid regexes.
+    pub fn new(triggers: &[Trigger]) -> Self {
+        let rgb = |c: [u8; 3]| Rgb {
+            r: c[0],
+            g: c[1],
+            b: c[2],
+        };
+        let rules = triggers
+            .iter()
+            .filter_map(|trigger| match RegexSearch::new(&trigger.pattern) {
+                Ok(regex) => Some(Rule {
+                    regex,
+                    foreground: trigger.foreground.map(rgb),
+                    background: trigger.background.map(rgb),
+                    underline: trigger.underline,
+                    action: trigger.action,
+                }),
+                Err(err) => {
+                    eprintln!("[trigger] ignoring {:?}: {err}", trigger.pattern);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Matches every rule against the logical line ending on `line`, restyles
+    /// the matched cells and returns one hit per rule that has an action.
+    pub fn scan<T>(&mut self, term: &mut Term<T>, line: Line) -> Vec<TriggerHit> {
+        let grid = term.grid();
+        let last_column = Column(grid.columns().saturating_sub(1));
+        let top = -(grid.history_size() as i32);
+        let mut first = line.0;
+        while first > top
+            && grid[Line(first - 1)][last_column]
+                .flags
+                .contains(Flags::WRAPLINE)
+        {
+            first -= 1;
+        }
+        let start = Point::new(Line(first), Column(0));
+        let end = Point::new(line, last_column);
+
+        let mut hits = Vec::new();
+        for rule in &mut self.rules {
+            let matches: Vec<Match> =
+                RegexIter::new(start, end, Direction::Right, term, &mut rule.regex).collect();
+            let Some(found) = matches.first() else {
+                continue;
+            };
+            if rule.action != TriggerAction::None {
+                hits.push(TriggerHit {
+                    action: rule.action,
+                    text: term.bounds_to_string(*found.start(), *found.end()),
+                    line,
+                });
+            }
+            for found in matches {
+                rule.paint(term, found);
+            }
+        }
+        hits
+    }
+}
+
+impl Rule {
+    fn paint<T>(&self, term: &mut Term<T>, found: Match) {
+        if self.foreground.is_none() && self.background.is_none() && !self.underline {
+            return;
+        }
+        let grid = term.grid_mut();
+        let last_column = grid.columns().saturating_sub(1);
+        let (start, end) = found.into_inner();
+        for line in start.line.0..=end.line.0 {
+            let from = if line == start.line.0 {
+                start.column.0
+            } else {
+                0
+            };
+            let to = if line == end.line.0 {
+                end.column.0
+            } else {
+                last_column
+            };
+            for column in from..=to {
+                let cell = &mut grid[Line(line)][Column(column)];
+                if let Some(fg) = self.foreground {
+                    cell.fg = Color::Spec(fg);
+                }
+                if let Some(bg) = self.background {
+                    cell.bg = Color::Spec(bg);
+                }
+                if self.underline {
+                    cell.flags.insert(Flags::UNDERLINE);
+                }
+            }
+        }
+    }
+}
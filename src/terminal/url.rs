@@ -1,4 +1,5 @@
 const SCHEMES: [&str; 2] = ["https://", "http://"];
+const WEB_SEARCH: &str = "https://www.google.com/search?q=";
 const TRAILING: [char; 12] = ['.', ',', ';', ':', '!', '?', '"', '\'', ')', ']', '}', '>'];
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -8,10 +9,87 @@ pub struct UrlSpan {
     pub url: String,
 }
 
-pub fn is_openable(uri: &str) -> bool {
-    SCHEMES
-        .iter()
-        .any(|scheme| uri.len() > scheme.len() && uri[..scheme.len()].eq_ignore_ascii_case(scheme))
+/// The scheme of `uri`, e.g. `https` or `mailto`, as written.
+pub fn scheme(uri: &str) -> Option<&str> {
+    let (scheme, rest) = uri.split_once(':')?;
+    (is_valid_scheme(scheme) && !rest.is_empty() && rest != "//").then_some(scheme)
+}
+
+/// RFC 3986: a letter followed by letters, digits, `+`, `-` or `.`.
+pub fn is_valid_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Whether an OSC 8 target is worth offering as a link at all. Whether it
+/// may actually be opened is [`is_allowed`]'s call.
+pub fn is_link(uri: &str) -> bool {
+    scheme(uri).is_some() && !uri.starts_with(super::graphics::IMAGE_URI_SCHEME)
+}
+
+/// Whether `uri`'s scheme is one of `schemes`, which are lowercase.
+pub fn is_allowed(uri: &str, schemes: &[String]) -> bool {
+    scheme(uri).is_some_and(|scheme| {
+        schemes
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(scheme))
+    })
+}
+
+/// `uri` with everything that could disguise it escaped as `\u{..}`:
+/// control, invisible and bidi characters anywhere, and any non-ASCII
+/// character in the host, where look-alike letters pass for real domains.
+pub fn reveal(uri: &str) -> String {
+    let host = host_range(uri);
+    let mut out = String::with_capacity(uri.len());
+    for (i, ch) in uri.char_indices() {
+        let hidden = ch.is_control() || ch.is_whitespace() || is_invisible(ch);
+        if hidden || (!ch.is_ascii() && host.contains(&i)) {
+            out.push_str(&format!("\\u{{{:04X}}}", ch as u32));
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Byte range of the part of `uri` that names the server: the authority
+/// of `scheme://` URLs, everything after the scheme otherwise (`mailto:`).
+fn host_range(uri: &str) -> std::ops::Range<usize> {
+    let Some(scheme) = scheme(uri) else {
+        return 0..uri.len();
+    };
+    let start = scheme.len() + 1;
+    if !uri[start..].starts_with("//") {
+        return start..uri.len();
+    }
+    let start = start + 2;
+    let end = uri[start..]
+        .find(['/', '?', '#'])
+        .map_or(uri.len(), |offset| start + offset);
+    start..end
+}
+
+fn is_invisible(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{00AD}'
+            | '\u{034F}'
+            | '\u{061C}'
+            | '\u{115F}'
+            | '\u{1160}'
+            | '\u{17B4}'
+            | '\u{17B5}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{206F}'
+            | '\u{3164}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+    )
 }
 
 pub fn url_at(row: &[char], col: usize) -> Option<UrlSpan> {
@@ -62,10 +140,38 @@ pub fn url_at(row: &[char], col: usize) -> Option<UrlSpan> {
     Some(UrlSpan { start, end, url })
 }
 
+/// A web search for `query`, whitespace collapsed and percent-encoded.
+pub fn web_search(query: &str) -> String {
+    let query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut url = String::from(WEB_SEARCH);
+    for byte in query.bytes() {
+        match byte {
+            b' ' => url.push('+'),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    url
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn web_search_encodes_the_query() {
+        assert_eq!(
+            web_search("  error:\n  E0308 \"mismatched\"\n"),
+            "https://www.google.com/search?q=error%3A+E0308+%22mismatched%22"
+        );
+        assert_eq!(
+            web_search("터미널"),
+            "https://www.google.com/search?q=%ED%84%B0%EB%AF%B8%EB%84%90"
+        );
+    }
+
     fn row(text: &str) -> Vec<char> {
         text.chars().collect()
     }
@@ -139,10 +245,53 @@ mod tests {
 
     #[test]
     fn osc8_uris_are_scheme_checked_too() {
-        assert!(is_openable("https://example.com"));
-        assert!(is_openable("HTTP://EXAMPLE.COM"));
-        assert!(!is_openable("file:///etc/passwd"));
-        assert!(!is_openable("https://"));
+        let allowed = ["http", "https", "mailto"].map(String::from);
+        assert!(is_allowed("https://example.com", &allowed));
+        assert!(is_allowed("HTTP://EXAMPLE.COM", &allowed));
+        assert!(is_allowed("mailto:me@example.com", &allowed));
+        assert!(!is_allowed("file:///etc/passwd", &allowed));
+        assert!(!is_allowed("vscode://open?file=x", &allowed));
+        assert!(!is_allowed("https://", &allowed));
+        assert!(!is_allowed("example.com", &allowed));
+    }
+
+    #[test]
+    fn custom_schemes_are_links_but_image_cells_are_not() {
+        assert!(is_link("file:///etc/passwd"));
+        assert!(is_link("x-help:topic"));
+        assert!(!is_link("1http://x"));
+        assert!(!is_link("no scheme"));
+        assert!(!is_link(&format!(
+            "{}7",
+            crate::terminal::graphics::IMAGE_URI_SCHEME
+        )));
+    }
+
+    #[test]
+    fn reveal_escapes_spoofing_characters() {
+        assert_eq!(reveal("https://example.com/a"), "https://example.com/a");
+        // Cyrillic "а" in the host looks like Latin "a".
+        assert_eq!(
+            reveal("https://ex\u{0430}mple.com/"),
+            "https://ex\\u{0430}mple.com/"
+        );
+        // Non-ASCII paths are left readable.
+        assert_eq!(
+            reveal("https://ko.wikipedia.org/wiki/토끼"),
+            "https://ko.wikipedia.org/wiki/토끼"
+        );
+        assert_eq!(
+            reveal("https://a.dev/\u{202E}fdp.exe"),
+            "https://a.dev/\\u{202E}fdp.exe"
+        );
+        assert_eq!(
+            reveal("mailto:me@g\u{043E}\u{043E}gle.com"),
+            "mailto:me@g\\u{043E}\\u{043E}gle.com"
+        );
+        assert_eq!(
+            reveal("https://a.dev/x\u{1b}[2J"),
+            "https://a.dev/x\\u{001B}[2J"
+        );
     }
 
     #[test]
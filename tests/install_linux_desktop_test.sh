@@ -19,7 +19,7 @@ assert_contains 'Name=Rabbitty'
 assert_contains 'Exec='
 assert_contains 'Icon=io.github.rabbitty'
 assert_contains 'Categories=System;TerminalEmulator;'
-assert_contains 'StartupWMClass=rabbitty'
+assert_contains 'StartupWMClass=io.github.rabbitty'
 assert_contains 'update-desktop-database'
 assert_contains 'gtk-update-icon-cache'
 assert_contains 'kbuildsycoca'
@@ -0,0 +1,135 @@
+//! Grid snapshots for byte sequences, run through the headless engine.
+//!
+//! Each `tests/snapshots/*.snap` file is one case:
+//!
+//! ```text
+//! # What the case covers.
+//! size 10x3
+//! input hello\r\n
+//! input \e[4mworld
+//! ---
+//! <the expected snapshot>
+//! ```
+//!
+//! `input` lines are fed as separate reads, so sequences can be split across
+//! them. They take `\e`, `\r`, `\n`, `\t`, `\\` and `\xHH` escapes; anything
+//! else is literal UTF-8. Set `RABBITTY_BLESS=1` to write the current output
+//! back as the expected snapshot.
+
+use rabbitty::terminal::{TerminalEngine, TerminalSize};
+use std::path::Path;
+
+#[test]
+fn snapshots_match() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots");
+    let bless = std::env::var_os("RABBITTY_BLESS").is_some();
+    let mut paths: Vec<_> = std::fs::read_dir(&dir)
+        .expect("read tests/snapshots")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "snap"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no snapshots in {}", dir.display());
+
+    let mut failures = Vec::new();
+    for path in paths {
+        let source = std::fs::read_to_string(&path).expect("read snapshot");
+        let case = Case::parse(&source).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+        let actual = case.run();
+        if actual == case.expected {
+            continue;
+        }
+        if bless {
+            std::fs::write(&path, format!("{}---\n{actual}", case.header)).expect("bless");
+        } else {
+            failures.push(format!(
+                "{}\n--- expected\n{}--- actual\n{actual}",
+                path.display(),
+                case.expected
+            ));
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} snapshot(s) differ; rerun with RABBITTY_BLESS=1 to accept them\n\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+struct Case {
+    header: String,
+    size: TerminalSize,
+    inputs: Vec<Vec<u8>>,
+    expected: String,
+}
+
+impl Case {
+    fn parse(source: &str) -> Result<Self, String> {
+        let (header, expected) = source
+            .split_once("---\n")
+            .ok_or("missing the `---` line before the snapshot")?;
+        let mut size = None;
+        let mut inputs = Vec::new();
+        for line in header.lines() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "size" => {
+                    let parsed = value
+                        .split_once('x')
+                        .and_then(|(columns, lines)| {
+                            Some((columns.parse().ok()?, lines.parse().ok()?))
+                        })
+                        .ok_or_else(|| format!("size `{value}` is not COLUMNSxLINES"))?;
+                    size = Some(TerminalSize::new(parsed.0, parsed.1));
+                }
+                "input" => inputs.push(unescape(value)?),
+                other => return Err(format!("unknown key `{other}`")),
+            }
+        }
+        Ok(Self {
+            header: header.to_string(),
+            size: size.ok_or("missing `size`")?,
+            inputs,
+            expected: expected.to_string(),
+        })
+    }
+
+    fn run(&self) -> String {
+        let mut engine = TerminalEngine::headless(self.size);
+        for input in &self.inputs {
+            engine.feed_bytes(input);
+        }
+        engine.snapshot()
+    }
+}
+
+fn unescape(text: &str) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('e') => out.push(0x1b),
+            Some('r') => out.push(b'\r'),
+            Some('n') => out.push(b'\n'),
+            Some('t') => out.push(b'\t'),
+            Some('\\') => out.push(b'\\'),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let byte = u8::from_str_radix(&hex, 16)
+                    .map_err(|_| format!("`\\x{hex}` is not a hex byte"))?;
+                out.push(byte);
+            }
+            Some(other) => return Err(format!("unknown escape `\\{other}`")),
+            None => return Err("trailing `\\`".to_string()),
+        }
+    }
+    Ok(out)
+}